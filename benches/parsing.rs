@@ -0,0 +1,144 @@
+//! Parsing throughput and allocation benchmarks
+//!
+//! Measures the bundled WGS record (`Bioseq-set`), the TP73 Entrezgene export, and a
+//! synthetic `Bioseq-set` built by repeating the entries of the WGS record. Each input
+//! is parsed completely, with a projection ([`ParseOptions::select()`]), in parallel
+//! ([`BioSeqSet::from_str_parallel()`], serial unless built with the `rayon` feature)
+//! and by borrowing ([`scan_nodes_borrowed()`]), so that these strategies can be
+//! compared against each other and across commits.
+//!
+//! Throughput is reported in bytes per second. The number and size of allocations of a
+//! single parse are printed before each group is measured.
+//!
+//! The synthetic set defaults to 64 MiB; set `NCBI_BENCH_SYNTHETIC_MB` to change it
+//! (eg: `1024` for a 1 GiB set):
+//!
+//! ```sh
+//! cargo bench --bench parsing --features rayon
+//! NCBI_BENCH_SYNTHETIC_MB=1024 cargo bench --bench parsing -- synthetic
+//! ```
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkGroup, Criterion, Throughput};
+use criterion::measurement::WallTime;
+use ncbi::parsing::{scan_nodes_borrowed, ParseOptions};
+use ncbi::seqfeat::GbQualRef;
+use ncbi::seqset::BioSeqSet;
+use ncbi::{get_local_xml, parse_xml, parse_xml_with};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+/// Allocator which counts allocations and allocated bytes
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+const WGS: &str = "tests/data/2519734237.xml";
+const ENTREZGENE: &str = "tests/data/tp73.genbank.xml";
+
+/// Default size of the synthetic set, in MiB
+const SYNTHETIC_MB: usize = 64;
+
+/// Elements kept by the projection benchmarks
+const SELECTION: &[&str] = &["Bioseq_id", "Seqdesc_source", "Entrezgene_gene"];
+
+/// `Bioseq-set` of at least `size` bytes, made of copies of the entries of the WGS record
+fn synthetic_set(size: usize) -> String {
+    const OPEN: &str = "<Bioseq-set_seq-set>";
+    const CLOSE: &str = "</Bioseq-set_seq-set>";
+
+    let wgs = get_local_xml(WGS);
+    let start = wgs.find(OPEN).expect("WGS record has no entries") + OPEN.len();
+    let end = wgs.rfind(CLOSE).expect("WGS record has no entries");
+    let entries = &wgs[start..end];
+
+    let mut xml = String::with_capacity(size + wgs.len());
+    xml.push_str(&wgs[..start]);
+    while xml.len() < size {
+        xml.push_str(entries);
+    }
+    xml.push_str(&wgs[end..]);
+    xml
+}
+
+/// Print the allocations made by a single call of `f`
+fn report_allocations<T>(name: &str, f: impl FnOnce() -> T) {
+    let (count, bytes) = (ALLOCATIONS.load(Ordering::Relaxed), ALLOCATED.load(Ordering::Relaxed));
+    drop(black_box(f()));
+    let count = ALLOCATIONS.load(Ordering::Relaxed) - count;
+    let bytes = ALLOCATED.load(Ordering::Relaxed) - bytes;
+    println!("{}: {} allocations, {:.1} MiB allocated", name, count, bytes as f64 / (1024.0 * 1024.0));
+}
+
+/// Benchmark each parsing strategy on `xml`
+fn bench_strategies(group: &mut BenchmarkGroup<WallTime>, name: &str, xml: &str, is_set: bool) {
+    let projection = ParseOptions::select(SELECTION);
+    group.throughput(Throughput::Bytes(xml.len() as u64));
+
+    report_allocations(&format!("{}/full", name), || parse_xml(xml));
+    group.bench_function("full", |b| b.iter(|| parse_xml(black_box(xml)).unwrap()));
+
+    report_allocations(&format!("{}/projection", name), || parse_xml_with(xml, &projection));
+    group.bench_function("projection", |b| {
+        b.iter(|| parse_xml_with(black_box(xml), &projection).unwrap())
+    });
+
+    report_allocations(&format!("{}/borrowed", name), || scan_nodes_borrowed::<GbQualRef>(xml));
+    group.bench_function("borrowed", |b| {
+        b.iter(|| scan_nodes_borrowed::<GbQualRef>(black_box(xml)))
+    });
+
+    if is_set {
+        report_allocations(&format!("{}/parallel", name), || BioSeqSet::from_str_parallel(xml, 0));
+        group.bench_function("parallel", |b| {
+            b.iter(|| BioSeqSet::from_str_parallel(black_box(xml), 0).unwrap())
+        });
+    }
+}
+
+fn wgs(c: &mut Criterion) {
+    let xml = get_local_xml(WGS);
+    let mut group = c.benchmark_group("wgs");
+    bench_strategies(&mut group, "wgs", &xml, true);
+    group.finish();
+}
+
+fn entrezgene(c: &mut Criterion) {
+    let xml = get_local_xml(ENTREZGENE);
+    let mut group = c.benchmark_group("entrezgene");
+    bench_strategies(&mut group, "entrezgene", &xml, false);
+    group.finish();
+}
+
+fn synthetic(c: &mut Criterion) {
+    let mb = std::env::var("NCBI_BENCH_SYNTHETIC_MB")
+        .ok()
+        .and_then(|mb| mb.parse().ok())
+        .unwrap_or(SYNTHETIC_MB);
+    let xml = synthetic_set(mb * 1024 * 1024);
+
+    let mut group = c.benchmark_group("synthetic");
+    // a single parse of a large set takes seconds
+    group.sample_size(10).measurement_time(Duration::from_secs(30));
+    bench_strategies(&mut group, "synthetic", &xml, true);
+    group.finish();
+}
+
+criterion_group!(benches, wgs, entrezgene, synthetic);
+criterion_main!(benches);
@@ -0,0 +1,56 @@
+//! Measure heap usage of parsed records
+//!
+//! Parses the WGS fixture several times and reports the heap memory held by the
+//! resulting records. Compare the default build with the `intern` feature:
+//!
+//! ```sh
+//! cargo run --release --example parse_memory
+//! cargo run --release --example parse_memory --features intern
+//! ```
+
+use ncbi::intern::interned_count;
+use ncbi::{get_local_xml, parse_xml};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+/// Allocator which keeps track of the number of bytes currently allocated
+struct Counting;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+const PATH: &str = "tests/data/2519734237.xml";
+const COPIES: usize = 20;
+
+fn main() {
+    let data = get_local_xml(PATH);
+
+    let before = ALLOCATED.load(Ordering::Relaxed);
+    let start = Instant::now();
+    let records: Vec<_> = (0..COPIES)
+        .map(|_| parse_xml(data.as_str()).expect("Fixture could not be parsed"))
+        .collect();
+    let elapsed = start.elapsed();
+    let held = ALLOCATED.load(Ordering::Relaxed) - before;
+
+    println!("interning: {}", cfg!(feature = "intern"));
+    println!("records parsed: {}", records.len());
+    println!("parse time: {:.2?} ({:.2?} per record)", elapsed, elapsed / COPIES as u32);
+    println!("heap held by records: {:.1} MiB", held as f64 / (1024.0 * 1024.0));
+    println!("interned strings: {}", interned_count());
+}
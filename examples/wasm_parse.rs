@@ -0,0 +1,51 @@
+//! Summarize NCBI XML in the browser
+//!
+//! Exposes [`summarize()`] for records uploaded by the user, and [`fetch_summary()`]
+//! for records fetched from E-utilities with the `fetch` API. Build with:
+//!
+//! ```sh
+//! cargo build --release --example wasm_parse --target wasm32-unknown-unknown \
+//!     --no-default-features --features wasm
+//! wasm-bindgen --target web --out-dir pkg \
+//!     target/wasm32-unknown-unknown/release/examples/wasm_parse.wasm
+//! ```
+//!
+//! then, from a page with `<input type="file" id="upload">`:
+//!
+//! ```js
+//! import init, { summarize } from "./pkg/wasm_parse.js";
+//! await init();
+//! document.getElementById("upload").onchange = async (event) => {
+//!     const xml = await event.target.files[0].text();
+//!     console.log(summarize(xml));
+//! };
+//! ```
+
+#[cfg(target_arch = "wasm32")]
+use ncbi::summary::Summary;
+#[cfg(target_arch = "wasm32")]
+use ncbi::transport::http_get_async;
+#[cfg(target_arch = "wasm32")]
+use ncbi::{build_fetch_url, parse_xml, DataType, EntrezDb};
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::prelude::*;
+
+/// Overview of the records in `xml`, one line per object
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen]
+pub fn summarize(xml: &str) -> Result<String, JsValue> {
+    match parse_xml(xml).map_err(|e| JsValue::from_str(&e))? {
+        DataType::BioSeqSet(set) => Ok(set.tree()),
+        DataType::EntrezgeneSet(genes) => Ok(genes.iter().map(|gene| gene.tree()).collect()),
+        _ => Err(JsValue::from_str("Unsupported record type")),
+    }
+}
+
+/// Fetch the nucleotide record `id` and summarize it
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen]
+pub async fn fetch_summary(id: String) -> Result<String, JsValue> {
+    let url = build_fetch_url(EntrezDb::Nucleotide, &id, "native", "xml");
+    let xml = http_get_async(&url).await.map_err(|e| JsValue::from_str(&e))?;
+    summarize(&xml)
+}
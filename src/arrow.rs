@@ -0,0 +1,195 @@
+//! Export of records to Arrow tables, for analytics with DataFusion or polars
+//!
+//! [`Record`]s are flattened into three [`RecordBatch`]es, which share the `seq_id`
+//! column so that they can be joined:
+//!
+//! - [`sequences_batch()`]: one row per sequence, with its length and organism
+//! - [`features_batch()`]: one row per feature, with its location and main qualifiers
+//! - [`descriptors_batch()`]: one row per descriptor given on the sequence itself
+//!
+//! With the `parquet` feature, batches are written to Parquet files by
+//! [`write_parquet()`].
+//!
+//! # Example
+//! ```ignore
+//! let records = Record::from_set(&set);
+//! let features = features_batch(&records)?;
+//! write_parquet(&[features], File::create("features.parquet").unwrap())?;
+//! ```
+
+use crate::record::{Record, Taxonomy};
+use crate::seqloc::NaStrand;
+use crate::summary::desc_kind;
+use crate::r#pub::{CitationStyle, Pub};
+use crate::seq::{SeqDesc, SeqInst};
+use arrow_array::{ArrayRef, RecordBatch, StringArray, UInt64Array};
+use arrow_schema::{DataType, Field, Schema};
+use std::sync::Arc;
+
+/// Columns of [`sequences_batch()`]
+pub fn sequences_schema() -> Schema {
+    Schema::new(vec![
+        Field::new("seq_id", DataType::Utf8, false),
+        Field::new("description", DataType::Utf8, true),
+        Field::new("mol", DataType::Utf8, true),
+        Field::new("length", DataType::UInt64, true),
+        Field::new("organism", DataType::Utf8, true),
+        Field::new("taxid", DataType::UInt64, true),
+    ])
+}
+
+/// Columns of [`features_batch()`]
+///
+/// `start` and `stop` are the first and last positions covered by a feature, starting
+/// at 0.
+pub fn features_schema() -> Schema {
+    Schema::new(vec![
+        Field::new("seq_id", DataType::Utf8, false),
+        Field::new("feat_type", DataType::Utf8, false),
+        Field::new("start", DataType::UInt64, true),
+        Field::new("stop", DataType::UInt64, true),
+        Field::new("strand", DataType::Utf8, true),
+        Field::new("gene", DataType::Utf8, true),
+        Field::new("locus_tag", DataType::Utf8, true),
+        Field::new("product", DataType::Utf8, true),
+    ])
+}
+
+/// Columns of [`descriptors_batch()`]
+pub fn descriptors_schema() -> Schema {
+    Schema::new(vec![
+        Field::new("seq_id", DataType::Utf8, false),
+        Field::new("descriptor", DataType::Utf8, false),
+        Field::new("value", DataType::Utf8, true),
+    ])
+}
+
+fn batch(schema: Schema, columns: Vec<ArrayRef>) -> Result<RecordBatch, String> {
+    RecordBatch::try_new(Arc::new(schema), columns).map_err(|e| e.to_string())
+}
+
+fn strings<'a, I: IntoIterator<Item = Option<&'a str>>>(values: I) -> ArrayRef {
+    Arc::new(values.into_iter().collect::<StringArray>())
+}
+
+fn integers<I: IntoIterator<Item = Option<u64>>>(values: I) -> ArrayRef {
+    Arc::new(values.into_iter().collect::<UInt64Array>())
+}
+
+/// Value of `strand` as given in XML (eg: "plus", "both-rev")
+fn strand_name(strand: &NaStrand) -> &'static str {
+    match strand {
+        NaStrand::Unknown => "unknown",
+        NaStrand::Plus => "plus",
+        NaStrand::Minus => "minus",
+        NaStrand::Both => "both",
+        NaStrand::BothRev => "both-rev",
+        NaStrand::Other => "other",
+    }
+}
+
+/// Sequence metadata of `records`
+pub fn sequences_batch(records: &[Record]) -> Result<RecordBatch, String> {
+    fn inst(record: &Record) -> Option<&SeqInst> {
+        record.bioseq()?.inst.as_ref()
+    }
+    fn taxonomy(record: &Record) -> Option<&Taxonomy> {
+        record.taxonomy.as_ref()
+    }
+
+    let mols: Vec<Option<String>> = records
+        .iter()
+        .map(|record| inst(record).map(|inst| format!("{:?}", inst.mol).to_lowercase()))
+        .collect();
+    batch(
+        sequences_schema(),
+        vec![
+            strings(records.iter().map(|record| Some(record.id.as_str()))),
+            strings(records.iter().map(|record| record.description.as_deref())),
+            strings(mols.iter().map(Option::as_deref)),
+            integers(records.iter().map(|record| {
+                inst(record)
+                    .and_then(|inst| inst.length)
+                    .or_else(|| record.length().map(|length| length as u64))
+            })),
+            strings(records.iter().map(|record| {
+                taxonomy(record).and_then(|taxonomy| taxonomy.organism.as_deref())
+            })),
+            integers(records.iter().map(|record| taxonomy(record).and_then(|taxonomy| taxonomy.taxid))),
+        ],
+    )
+}
+
+/// Features of `records`
+pub fn features_batch(records: &[Record]) -> Result<RecordBatch, String> {
+    let rows: Vec<_> = records
+        .iter()
+        .flat_map(|record| record.features.iter().map(move |feat| (record.id.as_str(), feat)))
+        .collect();
+
+    batch(
+        features_schema(),
+        vec![
+            strings(rows.iter().map(|(id, _)| Some(*id))),
+            strings(rows.iter().map(|(_, feat)| Some(feat.key.as_str()))),
+            integers(rows.iter().map(|(_, feat)| feat.start)),
+            integers(rows.iter().map(|(_, feat)| feat.end)),
+            strings(rows.iter().map(|(_, feat)| feat.strand.as_ref().map(strand_name))),
+            strings(rows.iter().map(|(_, feat)| feat.qualifier("gene"))),
+            strings(rows.iter().map(|(_, feat)| feat.qualifier("locus_tag"))),
+            strings(rows.iter().map(|(_, feat)| feat.qualifier("product"))),
+        ],
+    )
+}
+
+/// Text of descriptors which have a natural single value
+///
+/// Sources are represented by the name of their organism, publications by their
+/// citation, and user objects by their type.
+fn desc_value(desc: &SeqDesc) -> Option<String> {
+    match desc {
+        SeqDesc::Name(text)
+        | SeqDesc::Title(text)
+        | SeqDesc::Comment(text)
+        | SeqDesc::Region(text)
+        | SeqDesc::Het(text) => Some(text.clone()),
+        SeqDesc::Source(source) => source.org.taxname.clone(),
+        SeqDesc::Pub(desc) => Some(Pub::Equiv(desc.r#pub.clone()).to_citation_string(CitationStyle::Full)),
+        SeqDesc::User(object) => object.r#type.as_str().map(str::to_string),
+        SeqDesc::MolInfo(molinfo) => molinfo.gb_mol_type.clone(),
+        _ => None,
+    }
+}
+
+/// Descriptors given on the sequences of `records`
+///
+/// Only records built from a [`BioSeq`](crate::seq::BioSeq) have descriptors, and
+/// those inherited from an enclosing set are not included.
+pub fn descriptors_batch(records: &[Record]) -> Result<RecordBatch, String> {
+    let rows: Vec<(&str, &str, Option<String>)> = records
+        .iter()
+        .filter_map(|record| Some((record.id.as_str(), record.bioseq()?.descr.as_ref()?)))
+        .flat_map(|(id, descr)| descr.iter().map(move |desc| (id, desc_kind(desc), desc_value(desc))))
+        .collect();
+
+    batch(
+        descriptors_schema(),
+        vec![
+            strings(rows.iter().map(|(id, _, _)| Some(*id))),
+            strings(rows.iter().map(|(_, kind, _)| Some(*kind))),
+            strings(rows.iter().map(|(_, _, value)| value.as_deref())),
+        ],
+    )
+}
+
+#[cfg(feature = "parquet")]
+/// Write `batches`, which must share a schema, as a single Parquet file
+pub fn write_parquet<W: std::io::Write + Send>(batches: &[RecordBatch], writer: W) -> Result<(), String> {
+    let schema = batches.first().ok_or("No batches to write".to_string())?.schema();
+    let mut writer = parquet::arrow::ArrowWriter::try_new(writer, schema, None).map_err(|e| e.to_string())?;
+    for batch in batches {
+        writer.write(batch).map_err(|e| e.to_string())?;
+    }
+    writer.close().map_err(|e| e.to_string())?;
+    Ok(())
+}
@@ -2,8 +2,10 @@
 //! Adapted from ["biblio.asn"](https://www.ncbi.nlm.nih.gov/IEB/ToolBox/CPP_DOC/lxr/source/src/objects/biblio/biblio.asn)
 
 use crate::general::{Date, DbTag, PersonId};
-use crate::parsing::{read_vec_node, read_node, read_string, UnexpectedTags};
-use crate::parsing::{XmlNode, XmlVecNode};
+use crate::parsing::{attribute_value, bytes_to_string, next_event, parse_num, qualified_name, read_attributes, read_int, read_vec_node, read_node, read_string, read_vec_str_unchecked, UnexpectedTags};
+use crate::parsing::{TagMatch, XmlNode, XmlValue, XmlVecNode};
+use enum_primitive::FromPrimitive;
+use quick_xml::events::attributes::Attributes;
 use quick_xml::events::{BytesStart, Event};
 use quick_xml::Reader;
 use serde::{Deserialize, Serialize};
@@ -46,6 +48,7 @@ pub type PmPid = String;
 
 pub type ArticleIdSet = Vec<ArticleId>;
 
+enum_from_primitive! {
 #[derive(Clone, Serialize_repr, Deserialize_repr, PartialEq, Debug)]
 #[repr(u8)]
 /// points of publication
@@ -93,6 +96,48 @@ pub enum PubStatus {
 
     Other = 255,
 }
+}
+
+asn_names!(PubStatus {
+    Received => "received",
+    Accepted => "accepted",
+    EPublish => "epublish",
+    PPublish => "ppublish",
+    Revised => "revised",
+    PMC => "pmc",
+    PMCR => "pmcr",
+    PubMed => "pubmed",
+    PubMedR => "pubmedr",
+    AheadOfPrint => "aheadofprint",
+    PreMedline => "premedline",
+    Medline => "medline",
+    Other => "other",
+});
+
+impl XmlNode for PubStatus {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("PubStatus")
+    }
+
+    /// Value is given as text (eg: `<PubStatus value="ppublish">4</PubStatus>`)
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) if e.name() == Self::start_bytes().name() => {
+                    return Self::from_u8(read_int(reader)?);
+                }
+                Event::Text(text) => {
+                    return Self::from_u8(parse_num(&bytes_to_string(&text)).ok()??);
+                }
+                Event::End(e) if Self::is_end(&e) => return None,
+                _ => (),
+            }
+        }
+    }
+}
 
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 /// done as a struct so fields can be added
@@ -102,6 +147,46 @@ pub struct PubStatusDate {
     pub date: Date,
 }
 
+impl XmlNode for PubStatusDate {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("PubStatusDate")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let pubstatus_element = BytesStart::new("PubStatusDate_pubstatus");
+        let date_element = BytesStart::new("PubStatusDate_date");
+
+        let mut pubstatus = None;
+        let mut date = None;
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => {
+                    let name = e.name();
+
+                    if name.is_tag(pubstatus_element.name()) {
+                        pubstatus = read_node(reader);
+                    } else if name.is_tag(date_element.name()) {
+                        date = read_node(reader);
+                    }
+                }
+                Event::End(e) if Self::is_end(&e) => {
+                    return Self {
+                        pubstatus: pubstatus?,
+                        date: date?,
+                    }
+                    .into();
+                }
+                _ => (),
+            }
+        }
+    }
+}
+impl XmlVecNode for PubStatusDate {}
+
 pub type PubStatusDateSet = Vec<PubStatusDate>;
 
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
@@ -136,6 +221,50 @@ pub struct CitJour {
     pub imp: Imprint,
 }
 
+impl XmlNode for CitJour {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("Cit-jour")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let title_element = BytesStart::new("Cit-jour_title");
+        let imp_element = BytesStart::new("Cit-jour_imp");
+
+        let mut title = None;
+        let mut imp = None;
+
+        let forbidden = UnexpectedTags(&[]);
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => {
+                    let name = e.name();
+
+                    if name.is_tag(title_element.name()) {
+                        title = read_node(reader);
+                    } else if name.is_tag(imp_element.name()) {
+                        imp = read_node(reader);
+                    } else if name != Self::start_bytes().name() {
+                        forbidden.check(&name);
+                    }
+                }
+                Event::End(e) if Self::is_end(&e) => {
+                    return Self {
+                        title: title?,
+                        imp: imp?,
+                    }
+                    .into();
+                }
+                _ => (),
+            }
+        }
+    }
+}
+impl XmlVecNode for CitJour {}
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 /// book citation
 pub struct CitBook {
@@ -151,6 +280,60 @@ pub struct CitBook {
     pub imp: Imprint,
 }
 
+impl XmlNode for CitBook {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("Cit-book")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let title_element = BytesStart::new("Cit-book_title");
+        let coll_element = BytesStart::new("Cit-book_coll");
+        let authors_element = BytesStart::new("Cit-book_authors");
+        let imp_element = BytesStart::new("Cit-book_imp");
+
+        let mut title = None;
+        let mut coll = None;
+        let mut authors = None;
+        let mut imp = None;
+
+        let forbidden = UnexpectedTags(&[]);
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => {
+                    let name = e.name();
+
+                    if name.is_tag(title_element.name()) {
+                        title = read_node(reader);
+                    } else if name.is_tag(coll_element.name()) {
+                        coll = read_node(reader);
+                    } else if name.is_tag(authors_element.name()) {
+                        authors = read_node(reader);
+                    } else if name.is_tag(imp_element.name()) {
+                        imp = read_node(reader);
+                    } else if name != Self::start_bytes().name() {
+                        forbidden.check(&name);
+                    }
+                }
+                Event::End(e) if Self::is_end(&e) => {
+                    return Self {
+                        title: title?,
+                        coll,
+                        authors: authors?,
+                        imp: imp?,
+                    }
+                    .into();
+                }
+                _ => (),
+            }
+        }
+    }
+}
+impl XmlVecNode for CitBook {}
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 /// meeting proceedings
 pub struct CitProc {
@@ -160,6 +343,50 @@ pub struct CitProc {
     pub meet: Meeting,
 }
 
+impl XmlNode for CitProc {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("Cit-proc")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let book_element = BytesStart::new("Cit-proc_book");
+        let meet_element = BytesStart::new("Cit-proc_meet");
+
+        let mut book = None;
+        let mut meet = None;
+
+        let forbidden = UnexpectedTags(&[]);
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => {
+                    let name = e.name();
+
+                    if name.is_tag(book_element.name()) {
+                        book = read_node(reader);
+                    } else if name.is_tag(meet_element.name()) {
+                        meet = read_node(reader);
+                    } else if name != Self::start_bytes().name() {
+                        forbidden.check(&name);
+                    }
+                }
+                Event::End(e) if Self::is_end(&e) => {
+                    return Self {
+                        book: book?,
+                        meet: meet?,
+                    }
+                    .into();
+                }
+                _ => (),
+            }
+        }
+    }
+}
+impl XmlVecNode for CitProc {}
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 #[serde(rename_all = "kebab-case")]
 /// Patent citation
@@ -204,6 +431,103 @@ pub struct CitPat {
     pub r#abstract: Option<String>,
 }
 
+impl XmlNode for CitPat {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("Cit-pat")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let title_element = BytesStart::new("Cit-pat_title");
+        let authors_element = BytesStart::new("Cit-pat_authors");
+        let country_element = BytesStart::new("Cit-pat_country");
+        let doc_type_element = BytesStart::new("Cit-pat_doc-type");
+        let number_element = BytesStart::new("Cit-pat_number");
+        let date_issue_element = BytesStart::new("Cit-pat_date-issue");
+        let class_element = BytesStart::new("Cit-pat_class");
+        let app_number_element = BytesStart::new("Cit-pat_app-number");
+        let app_date_element = BytesStart::new("Cit-pat_app-date");
+        let applicants_element = BytesStart::new("Cit-pat_applicants");
+        let assignees_element = BytesStart::new("Cit-pat_assignees");
+        let priority_element = BytesStart::new("Cit-pat_priority");
+        let abstract_element = BytesStart::new("Cit-pat_abstract");
+
+        let mut title = None;
+        let mut authors = None;
+        let mut country = None;
+        let mut doc_type = None;
+        let mut cit = CitPat {
+            title: String::new(),
+            authors: AuthList::default(),
+            country: String::new(),
+            doc_type: String::new(),
+            number: None,
+            date_issue: None,
+            class: None,
+            app_number: None,
+            app_date: None,
+            applicants: None,
+            assignees: None,
+            priority: None,
+            r#abstract: None,
+        };
+
+        let forbidden = UnexpectedTags(&[]);
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => {
+                    let name = e.name();
+
+                    if name.is_tag(title_element.name()) {
+                        title = read_string(reader);
+                    } else if name.is_tag(authors_element.name()) {
+                        authors = read_node(reader);
+                    } else if name.is_tag(country_element.name()) {
+                        country = read_string(reader);
+                    } else if name.is_tag(doc_type_element.name()) {
+                        doc_type = read_string(reader);
+                    } else if name.is_tag(number_element.name()) {
+                        cit.number = read_string(reader);
+                    } else if name.is_tag(date_issue_element.name()) {
+                        cit.date_issue = read_node(reader);
+                    } else if name.is_tag(class_element.name()) {
+                        cit.class = read_vec_str_unchecked(reader, &e.to_end()).into();
+                    } else if name.is_tag(app_number_element.name()) {
+                        cit.app_number = read_string(reader);
+                    } else if name.is_tag(app_date_element.name()) {
+                        cit.app_date = read_node(reader);
+                    } else if name.is_tag(applicants_element.name()) {
+                        cit.applicants = read_node(reader);
+                    } else if name.is_tag(assignees_element.name()) {
+                        cit.assignees = read_node(reader);
+                    } else if name.is_tag(priority_element.name()) {
+                        cit.priority = read_vec_node(reader, e.to_end()).into();
+                    } else if name.is_tag(abstract_element.name()) {
+                        cit.r#abstract = read_string(reader);
+                    } else if name != Self::start_bytes().name() {
+                        forbidden.check(&name);
+                    }
+                }
+                Event::End(e) if Self::is_end(&e) => {
+                    return Self {
+                        title: title?,
+                        authors: authors?,
+                        country: country?,
+                        doc_type: doc_type?,
+                        ..cit
+                    }
+                    .into();
+                }
+                _ => (),
+            }
+        }
+    }
+}
+impl XmlVecNode for CitPat {}
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 pub struct PatentPriority {
     /// patent country code
@@ -216,7 +540,53 @@ pub struct PatentPriority {
     pub date: Date,
 }
 
-#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+impl XmlNode for PatentPriority {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("Patent-priority")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let country_element = BytesStart::new("Patent-priority_country");
+        let number_element = BytesStart::new("Patent-priority_number");
+        let date_element = BytesStart::new("Patent-priority_date");
+
+        let mut country = None;
+        let mut number = None;
+        let mut date = None;
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => {
+                    let name = e.name();
+
+                    if name.is_tag(country_element.name()) {
+                        country = read_string(reader);
+                    } else if name.is_tag(number_element.name()) {
+                        number = read_string(reader);
+                    } else if name.is_tag(date_element.name()) {
+                        date = read_node(reader);
+                    }
+                }
+                Event::End(e) if Self::is_end(&e) => {
+                    return Self {
+                        country: country?,
+                        number: number?,
+                        date: date?,
+                    }
+                    .into();
+                }
+                _ => (),
+            }
+        }
+    }
+}
+impl XmlVecNode for PatentPriority {}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "lowercase")]
 pub enum IdPatChoice {
     /// patent document number
@@ -226,7 +596,8 @@ pub enum IdPatChoice {
     AppNumber(String),
 }
 
-#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "kebab-case")]
 /// identifies a patent
 pub struct IdPat {
@@ -239,6 +610,53 @@ pub struct IdPat {
     pub doc_type: Option<String>,
 }
 
+impl XmlNode for IdPat {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("Id-pat")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let country_element = BytesStart::new("Id-pat_country");
+        let number_element = BytesStart::new("Id-pat_id_number");
+        let app_number_element = BytesStart::new("Id-pat_id_app-number");
+        let doc_type_element = BytesStart::new("Id-pat_doc-type");
+
+        let mut country = None;
+        let mut id = None;
+        let mut doc_type = None;
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => {
+                    let name = e.name();
+
+                    if name.is_tag(country_element.name()) {
+                        country = read_string(reader);
+                    } else if name.is_tag(number_element.name()) {
+                        id = read_string(reader).map(IdPatChoice::Number);
+                    } else if name.is_tag(app_number_element.name()) {
+                        id = read_string(reader).map(IdPatChoice::AppNumber);
+                    } else if name.is_tag(doc_type_element.name()) {
+                        doc_type = read_string(reader);
+                    }
+                }
+                Event::End(e) if Self::is_end(&e) => {
+                    return Self {
+                        country: country?,
+                        id: id?,
+                        doc_type,
+                    }
+                    .into();
+                }
+                _ => (),
+            }
+        }
+    }
+}
+
 #[derive(Clone, Serialize_repr, Deserialize_repr, PartialEq, Debug)]
 #[repr(u8)]
 /// # Note
@@ -251,6 +669,27 @@ pub enum CitLetType {
     Thesis,
 }
 
+asn_names!(CitLetType {
+    Manuscript => "manuscript",
+    Letter => "letter",
+    Thesis => "thesis",
+});
+
+impl XmlValue for CitLetType {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("Cit-let_type")
+    }
+
+    fn from_attributes(attributes: Attributes) -> Option<Self> {
+        match attribute_value(attributes)?.as_str() {
+            "manuscript" => Self::Manuscript.into(),
+            "letter" => Self::Letter.into(),
+            "thesis" => Self::Thesis.into(),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 #[serde(rename_all = "kebab-case")]
 /// cite a letter, thesis, or manuscript
@@ -265,6 +704,54 @@ pub struct CitLet {
     pub r#type: CitLetType,
 }
 
+impl XmlNode for CitLet {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("Cit-let")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let cit_element = BytesStart::new("Cit-let_cit");
+        let man_id_element = BytesStart::new("Cit-let_man-id");
+
+        let mut cit = None;
+        let mut man_id = None;
+        let mut r#type = None;
+
+        let forbidden = UnexpectedTags(&[]);
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => {
+                    let name = e.name();
+
+                    if name.is_tag(cit_element.name()) {
+                        cit = read_node(reader);
+                    } else if name.is_tag(man_id_element.name()) {
+                        man_id = read_string(reader);
+                    } else if name != Self::start_bytes().name() {
+                        forbidden.check(&name);
+                    }
+                }
+                Event::Empty(e) if e.name().is_tag(CitLetType::start_bytes().name()) => {
+                    r#type = read_attributes(&e);
+                }
+                Event::End(e) if Self::is_end(&e) => {
+                    return Self {
+                        cit: cit?,
+                        man_id,
+                        r#type: r#type?,
+                    }
+                    .into();
+                }
+                _ => (),
+            }
+        }
+    }
+}
+
 #[derive(Clone, Serialize_repr, Deserialize_repr, PartialEq, Debug, Default)]
 #[repr(u8)]
 /// Internal representation for medium of submission for `medium` in [`CitSub`]
@@ -282,6 +769,14 @@ pub enum CitSubMedium {
     Other = 255,
 }
 
+asn_names!(CitSubMedium {
+    Paper => "paper",
+    Tape => "tape",
+    Floppy => "floppy",
+    Email => "email",
+    Other => "other",
+});
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 /// Cite a direct data submission
 ///
@@ -338,13 +833,13 @@ impl XmlNode for CitSub {
         let forbidden = UnexpectedTags(&[]);
 
         loop {
-            match reader.read_event().unwrap() {
+            match next_event(reader)? {
                 Event::Start(e) => {
                     let name = e.name();
 
-                    if name == authors_element.name() {
-                        cit.authors = read_node(reader).unwrap();
-                    } else if name == date_element.name() {
+                    if name.is_tag(authors_element.name()) {
+                        cit.authors = read_node(reader)?;
+                    } else if name.is_tag(date_element.name()) {
                         cit.date = read_node(reader);
                     } else if name != Self::start_bytes().name() {
                         forbidden.check(&name);
@@ -410,15 +905,15 @@ impl XmlNode for CitGen {
         let forbidden = UnexpectedTags(&[]);
 
         loop {
-            match reader.read_event().unwrap() {
+            match next_event(reader)? {
                 Event::Start(e) => {
                     let name = e.name();
 
-                    if name == cit_element.name() {
+                    if name.is_tag(cit_element.name()) {
                         gen.cit = read_string(reader);
-                    } else if name == title_element.name() {
+                    } else if name.is_tag(title_element.name()) {
                         gen.title = read_string(reader);
-                    } else if name == authors_element.name() {
+                    } else if name.is_tag(authors_element.name()) {
                         gen.authors = read_node(reader);
                     } else if name != Self::start_bytes().name() {
                         forbidden.check(&name)
@@ -469,16 +964,22 @@ impl XmlNode for AuthListNames {
     {
         // variants
         let std_element = BytesStart::new("Auth-list_names_std");
+        let ml_element = BytesStart::new("Auth-list_names_ml");
+        let str_element = BytesStart::new("Auth-list_names_str");
 
         let forbidden = UnexpectedTags(&[]);
 
         loop {
-            match reader.read_event().unwrap() {
+            match next_event(reader)? {
                 Event::Start(e) => {
                     let name = e.name();
 
-                    if name == std_element.name() {
-                        return Self::Std(read_vec_node(reader, std_element.to_end())).into();
+                    if name.is_tag(std_element.name()) {
+                        return Self::Std(read_vec_node(reader, e.to_end())).into();
+                    } else if name.is_tag(ml_element.name()) {
+                        return Self::Ml(read_vec_str_unchecked(reader, &e.to_end())).into();
+                    } else if name.is_tag(str_element.name()) {
+                        return Self::Str(read_vec_str_unchecked(reader, &e.to_end())).into();
                     } else if name == Self::start_bytes().name() {
                         forbidden.check(&name);
                     }
@@ -520,13 +1021,13 @@ impl XmlNode for AuthList {
         let forbidden = UnexpectedTags(&[]);
 
         loop {
-            match reader.read_event().unwrap() {
+            match next_event(reader)? {
                 Event::Start(e) => {
                     let name = e.name();
 
-                    if name == names_element.name() {
-                        list.names = read_node(reader).unwrap();
-                    } else if name == affil_element.name() {
+                    if name.is_tag(names_element.name()) {
+                        list.names = read_node(reader)?;
+                    } else if name.is_tag(affil_element.name()) {
                         list.affil = read_node(reader);
                     } else if name != Self::start_bytes().name() {
                         forbidden.check(&name);
@@ -554,6 +1055,11 @@ pub enum AuthorLevel {
     Secondary,
 }
 
+asn_names!(AuthorLevel {
+    Primary => "primary",
+    Secondary => "secondary",
+});
+
 #[derive(Clone, Serialize_repr, Deserialize_repr, PartialEq, Debug)]
 #[repr(u8)]
 /// # Note
@@ -567,6 +1073,13 @@ pub enum AuthorRole {
     Translator,
 }
 
+asn_names!(AuthorRole {
+    Compiler => "compiler",
+    Editor => "editor",
+    PatentAssignee => "patent-assignee",
+    Translator => "translator",
+});
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 #[serde(rename_all = "kebab-case")]
 pub struct Author {
@@ -611,13 +1124,13 @@ impl XmlNode for Author {
         let forbidden = UnexpectedTags(&[]);
 
         loop {
-            match reader.read_event().unwrap() {
+            match next_event(reader)? {
                 Event::Start(e) => {
                     let name = e.name();
 
-                    if name == name_element.name() {
-                        author.name = read_node(reader).unwrap();
-                    } else {
+                    if name.is_tag(name_element.name()) {
+                        author.name = read_node(reader)?;
+                    } else if name != Self::start_bytes().name() {
                         forbidden.check(&name);
                     }
                 }
@@ -684,23 +1197,23 @@ impl XmlNode for AffilStd {
         let forbidden = UnexpectedTags(&[]);
 
         loop {
-            match reader.read_event().unwrap() {
+            match next_event(reader)? {
                 Event::Start(e) => {
                     let name = e.name();
 
-                    if name == affil_element.name() {
+                    if name.is_tag(affil_element.name()) {
                         affil.affil = read_string(reader);
-                    } else if name == div_element.name() {
+                    } else if name.is_tag(div_element.name()) {
                         affil.div = read_string(reader);
-                    } else if name == city_element.name() {
+                    } else if name.is_tag(city_element.name()) {
                         affil.city = read_string(reader);
-                    } else if name == sub_element.name() {
+                    } else if name.is_tag(sub_element.name()) {
                         affil.sub = read_string(reader);
-                    } else if name == country_element.name() {
+                    } else if name.is_tag(country_element.name()) {
                         affil.country = read_string(reader);
-                    } else if name == street_element.name() {
+                    } else if name.is_tag(street_element.name()) {
                         affil.street = read_string(reader);
-                    } else if name == postal_code_element.name() {
+                    } else if name.is_tag(postal_code_element.name()) {
                         affil.postal_code = read_string(reader);
                     } else if name != Self::start_bytes().name() {
                         forbidden.check(&name);
@@ -741,15 +1254,15 @@ impl XmlNode for Affil {
         let std_element = BytesStart::new("Affil_std");
 
         loop {
-            match reader.read_event().unwrap() {
+            match next_event(reader)? {
                 Event::Start(e) => {
                     let name = e.name();
 
-                    if name == std_element.name() {
-                        return Self::Std(read_node(reader).unwrap()).into();
+                    if name.is_tag(std_element.name()) {
+                        return Self::Std(read_node(reader)?).into();
                     }
-                    if name == str_element.name() {
-                        return Self::Str(read_string(reader).unwrap()).into();
+                    if name.is_tag(str_element.name()) {
+                        return Self::Str(read_string(reader)?).into();
                     }
                 }
                 Event::End(e) => {
@@ -816,8 +1329,58 @@ pub enum TitleItem {
     ISBN(String),
 }
 
+impl XmlNode for TitleItem {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("Title_E")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let mut item = None;
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => {
+                    let variant: fn(String) -> Self = match qualified_name(e.name(), Self::start_bytes().name()).as_ref() {
+                        b"Title_E_name" => Self::Name,
+                        b"Title_E_tsub" => Self::TSub,
+                        b"Title_E_trans" => Self::Trans,
+                        b"Title_E_jta" => Self::Jta,
+                        b"Title_E_iso-jta" => Self::IsoJta,
+                        b"Title_E_ml-jta" => Self::MlJta,
+                        b"Title_E_coden" => Self::Coden,
+                        b"Title_E_issn" => Self::ISSN,
+                        b"Title_E_abr" => Self::Abr,
+                        b"Title_E_isbn" => Self::ISBN,
+                        _ => continue,
+                    };
+                    item = read_string(reader).map(variant);
+                }
+                Event::End(e) if Self::is_end(&e) => return item,
+                _ => (),
+            }
+        }
+    }
+}
+impl XmlVecNode for TitleItem {}
+
 pub type Title = Vec<TitleItem>;
 
+impl XmlNode for Title {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("Title")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        TitleItem::vec_from_reader(reader, Self::start_bytes().to_end()).into()
+    }
+}
+
 #[derive(Clone, Serialize_repr, Deserialize_repr, PartialEq, Debug)]
 #[repr(u8)]
 /// For pre-publication citations
@@ -836,6 +1399,27 @@ pub enum ImprintPrePub {
     Other = 255,
 }
 
+asn_names!(ImprintPrePub {
+    Submitted => "submitted",
+    InPress => "in-press",
+    Other => "other",
+});
+
+impl XmlValue for ImprintPrePub {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("Imprint_prepub")
+    }
+
+    fn from_attributes(attributes: Attributes) -> Option<Self> {
+        match attribute_value(attributes)?.as_str() {
+            "submitted" => Self::Submitted.into(),
+            "in-press" => Self::InPress.into(),
+            "other" => Self::Other.into(),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 #[serde(rename_all = "kebab-case")]
 pub struct Imprint {
@@ -877,6 +1461,96 @@ pub struct Imprint {
     pub history: Option<PubStatusDateSet>,
 }
 
+impl XmlNode for Imprint {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("Imprint")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let date_element = BytesStart::new("Imprint_date");
+        let volume_element = BytesStart::new("Imprint_volume");
+        let issue_element = BytesStart::new("Imprint_issue");
+        let pages_element = BytesStart::new("Imprint_pages");
+        let section_element = BytesStart::new("Imprint_section");
+        let pub_element = BytesStart::new("Imprint_pub");
+        let cprt_element = BytesStart::new("Imprint_cprt");
+        let part_sup_element = BytesStart::new("Imprint_part-sup");
+        let language_element = BytesStart::new("Imprint_language");
+        let part_supi_element = BytesStart::new("Imprint_part-supi");
+        let retract_element = BytesStart::new("Imprint_retract");
+        let pubstatus_element = BytesStart::new("Imprint_pubstatus");
+        let history_element = BytesStart::new("Imprint_history");
+
+        let mut date = None;
+        let mut imp = Imprint {
+            date: Date::default(),
+            volume: None,
+            issue: None,
+            pages: None,
+            section: None,
+            r#pub: None,
+            cprt: None,
+            part_sup: None,
+            language: None,
+            prepub: None,
+            part_supi: None,
+            retract: None,
+            pubstatus: None,
+            history: None,
+        };
+
+        let forbidden = UnexpectedTags(&[]);
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => {
+                    let name = e.name();
+
+                    if name.is_tag(date_element.name()) {
+                        date = read_node(reader);
+                    } else if name.is_tag(volume_element.name()) {
+                        imp.volume = read_string(reader);
+                    } else if name.is_tag(issue_element.name()) {
+                        imp.issue = read_string(reader);
+                    } else if name.is_tag(pages_element.name()) {
+                        imp.pages = read_string(reader);
+                    } else if name.is_tag(section_element.name()) {
+                        imp.section = read_string(reader);
+                    } else if name.is_tag(pub_element.name()) {
+                        imp.r#pub = read_node(reader);
+                    } else if name.is_tag(cprt_element.name()) {
+                        imp.cprt = read_node(reader);
+                    } else if name.is_tag(part_sup_element.name()) {
+                        imp.part_sup = read_string(reader);
+                    } else if name.is_tag(language_element.name()) {
+                        imp.language = read_string(reader);
+                    } else if name.is_tag(part_supi_element.name()) {
+                        imp.part_supi = read_string(reader);
+                    } else if name.is_tag(retract_element.name()) {
+                        imp.retract = read_node(reader);
+                    } else if name.is_tag(pubstatus_element.name()) {
+                        imp.pubstatus = read_node(reader);
+                    } else if name.is_tag(history_element.name()) {
+                        imp.history = read_vec_node(reader, e.to_end()).into();
+                    } else if name != Self::start_bytes().name() {
+                        forbidden.check(&name);
+                    }
+                }
+                Event::Empty(e) if e.name().is_tag(ImprintPrePub::start_bytes().name()) => {
+                    imp.prepub = read_attributes(&e);
+                }
+                Event::End(e) if Self::is_end(&e) => {
+                    return Self { date: date?, ..imp }.into();
+                }
+                _ => (),
+            }
+        }
+    }
+}
+
 #[derive(Clone, Serialize_repr, Deserialize_repr, PartialEq, Debug)]
 #[repr(u8)]
 /// represents type of entry retraction
@@ -899,6 +1573,29 @@ pub enum CitRetractType {
     Erratum,
 }
 
+asn_names!(CitRetractType {
+    Retracted => "retracted",
+    Notice => "notice",
+    InError => "in-error",
+    Erratum => "erratum",
+});
+
+impl XmlValue for CitRetractType {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("Cit-retract_type")
+    }
+
+    fn from_attributes(attributes: Attributes) -> Option<Self> {
+        match attribute_value(attributes)?.as_str() {
+            "retracted" => Self::Retracted.into(),
+            "notice" => Self::Notice.into(),
+            "in-error" => Self::InError.into(),
+            "erratum" => Self::Erratum.into(),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 pub struct CitRetract {
     #[serde(rename = "type")]
@@ -909,9 +1606,186 @@ pub struct CitRetract {
     pub exp: Option<String>,
 }
 
+impl XmlNode for CitRetract {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("Cit-retract")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let exp_element = BytesStart::new("Cit-retract_exp");
+
+        let mut r#type = None;
+        let mut exp = None;
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) if e.name().is_tag(exp_element.name()) => {
+                    exp = read_string(reader);
+                }
+                Event::Empty(e) if e.name().is_tag(CitRetractType::start_bytes().name()) => {
+                    r#type = read_attributes(&e);
+                }
+                Event::End(e) if Self::is_end(&e) => {
+                    return Self { r#type: r#type?, exp }.into();
+                }
+                _ => (),
+            }
+        }
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 pub struct Meeting {
     pub number: String,
     pub date: Date,
     pub place: Option<Affil>,
 }
+
+impl XmlNode for Meeting {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("Meeting")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let number_element = BytesStart::new("Meeting_number");
+        let date_element = BytesStart::new("Meeting_date");
+        let place_element = BytesStart::new("Meeting_place");
+
+        let mut number = None;
+        let mut date = None;
+        let mut place = None;
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => {
+                    let name = e.name();
+
+                    if name.is_tag(number_element.name()) {
+                        number = read_string(reader);
+                    } else if name.is_tag(date_element.name()) {
+                        date = read_node(reader);
+                    } else if name.is_tag(place_element.name()) {
+                        place = read_node(reader);
+                    }
+                }
+                Event::End(e) if Self::is_end(&e) => {
+                    return Self {
+                        number: number?,
+                        date: date?,
+                        place,
+                    }
+                    .into();
+                }
+                _ => (),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::biblio::{AuthListNames, CitJour, CitProc, CitRetractType, ImprintPrePub, PubStatus, Title, TitleItem};
+    use crate::general::{Date, DateStd};
+    use crate::parsing::parse_node;
+
+    #[test]
+    fn test_bare_title() {
+        let xml = "<Title><Title_E><name>Genomics</name></Title_E><Title_E><iso-jta>Genomics</iso-jta></Title_E></Title>";
+        assert_eq!(
+            parse_node::<Title>(xml),
+            Some(vec![TitleItem::Name("Genomics".into()), TitleItem::IsoJta("Genomics".into())])
+        );
+    }
+
+    #[test]
+    fn test_cit_jour() {
+        let xml = r#"<Cit-jour>
+            <Cit-jour_title>
+                <Title>
+                    <Title_E><Title_E_iso-jta>Nucleic Acids Res.</Title_E_iso-jta></Title_E>
+                    <Title_E><Title_E_issn>0305-1048</Title_E_issn></Title_E>
+                </Title>
+            </Cit-jour_title>
+            <Cit-jour_imp>
+                <Imprint>
+                    <Imprint_date><Date><Date_std><Date-std><Date-std_year>2024</Date-std_year></Date-std></Date_std></Date></Imprint_date>
+                    <Imprint_pages>1-10</Imprint_pages>
+                    <Imprint_prepub value="in-press"/>
+                    <Imprint_pubstatus><PubStatus value="aheadofprint">10</PubStatus></Imprint_pubstatus>
+                    <Imprint_history>
+                        <PubStatusDateSet>
+                            <PubStatusDate>
+                                <PubStatusDate_pubstatus><PubStatus value="received">1</PubStatus></PubStatusDate_pubstatus>
+                                <PubStatusDate_date><Date><Date_std><Date-std><Date-std_year>2023</Date-std_year></Date-std></Date_std></Date></PubStatusDate_date>
+                            </PubStatusDate>
+                        </PubStatusDateSet>
+                    </Imprint_history>
+                    <Imprint_retract>
+                        <Cit-retract><Cit-retract_type value="erratum"/><Cit-retract_exp>Corrected figure 2</Cit-retract_exp></Cit-retract>
+                    </Imprint_retract>
+                </Imprint>
+            </Cit-jour_imp>
+        </Cit-jour>"#;
+        let journal = parse_node::<CitJour>(xml).unwrap();
+        assert_eq!(
+            journal.title,
+            vec![TitleItem::IsoJta("Nucleic Acids Res.".into()), TitleItem::ISSN("0305-1048".into())]
+        );
+        let imp = journal.imp;
+        assert_eq!(imp.pages.as_deref(), Some("1-10"));
+        assert_eq!(imp.prepub, Some(ImprintPrePub::InPress));
+        assert_eq!(imp.pubstatus, Some(PubStatus::AheadOfPrint));
+        let history = imp.history.unwrap();
+        assert_eq!(history[0].pubstatus, PubStatus::Received);
+        let retract = imp.retract.unwrap();
+        assert_eq!(retract.r#type, CitRetractType::Erratum);
+        assert_eq!(retract.exp.as_deref(), Some("Corrected figure 2"));
+    }
+
+    #[test]
+    fn test_cit_proc() {
+        let xml = r#"<Cit-proc>
+            <Cit-proc_book>
+                <Cit-book>
+                    <Cit-book_title><Title><Title_E><Title_E_name>Proceedings of ISMB</Title_E_name></Title_E></Title></Cit-book_title>
+                    <Cit-book_authors>
+                        <Auth-list><Auth-list_names><Auth-list_names_ml><Auth-list_names_ml_E>Doe J</Auth-list_names_ml_E></Auth-list_names_ml></Auth-list_names></Auth-list>
+                    </Cit-book_authors>
+                    <Cit-book_imp>
+                        <Imprint><Imprint_date><Date><Date_str>1999</Date_str></Date></Imprint_date></Imprint>
+                    </Cit-book_imp>
+                </Cit-book>
+            </Cit-proc_book>
+            <Cit-proc_meet>
+                <Meeting>
+                    <Meeting_number>7</Meeting_number>
+                    <Meeting_date><Date><Date_std><Date-std><Date-std_year>1999</Date-std_year><Date-std_month>8</Date-std_month></Date-std></Date_std></Date></Meeting_date>
+                    <Meeting_place><Affil><Affil_str>Heidelberg, Germany</Affil_str></Affil></Meeting_place>
+                </Meeting>
+            </Cit-proc_meet>
+        </Cit-proc>"#;
+        let proc = parse_node::<CitProc>(xml).unwrap();
+        assert_eq!(proc.book.title, vec![TitleItem::Name("Proceedings of ISMB".into())]);
+        assert_eq!(proc.book.imp.date, Date::Str("1999".into()));
+        assert_eq!(proc.meet.number, "7");
+        assert_eq!(
+            proc.meet.date,
+            Date::Date(DateStd {
+                year: 1999,
+                month: Some(8),
+                ..Default::default()
+            })
+        );
+        assert!(proc.meet.place.is_some());
+        assert_eq!(proc.book.authors.names, AuthListNames::Ml(vec!["Doe J".into()]));
+
+        // required fields are not optional
+        assert!(parse_node::<CitProc>(&xml.replace("<Meeting_number>7</Meeting_number>", "")).is_none());
+    }
+}
@@ -0,0 +1,635 @@
+//! Conserved Domain Database
+//!
+//! Adapted from ["cdd.asn"](https://www.ncbi.nlm.nih.gov/IEB/ToolBox/CPP_DOC/lxr/source/src/objects/cdd/cdd.asn)
+//! from the NCBI C++ Toolkit
+//!
+//! Only the subset of a [`Cdd`] record that describes the domain model is parsed:
+//! identifiers, descriptions, the domain hierarchy, and annotated sites. Alignment
+//! data used by Cn3D (`seqannot`, `features`, `style-dictionary`, etc.) is skipped.
+//! The position-specific scoring matrix of a domain is given by [`Cdd::scoreparams`].
+
+use crate::eutils::{fetch_links, EntrezDb};
+use crate::general::Date;
+use crate::parsing::{next_event, qualified_name, read_int, read_node, read_string, read_vec_node, read_vec_str_unchecked, UnexpectedTags};
+use crate::parsing::{XmlNode, XmlVecNode};
+use crate::r#pub::Pub;
+use crate::scoremat::PssmWithParameters;
+use crate::seqfeat::OrgRef;
+use crate::seqloc::{SeqId, SeqInterval, SeqLoc};
+use enum_primitive::FromPrimitive;
+use quick_xml::events::{BytesEnd, BytesStart, Event};
+use quick_xml::Reader;
+use serde::{Deserialize, Serialize};
+use serde_repr::{Deserialize_repr, Serialize_repr};
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+#[serde(rename_all = "kebab-case")]
+/// Accession of a domain model
+pub struct GlobalId {
+    /// eg: "cd00001", "pfam00069"
+    pub accession: String,
+    pub release: Option<String>,
+    pub version: Option<i64>,
+    pub database: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum CddId {
+    /// UID within [`EntrezDb::ConservedDomains`]
+    Uid(u64),
+    /// accession and version
+    Gid(GlobalId),
+}
+
+pub type CddIdSet = Vec<CddId>;
+
+enum_from_primitive! {
+    #[derive(Clone, Serialize_repr, Deserialize_repr, PartialEq, Debug)]
+    #[repr(u8)]
+    /// Curation status given by [`CddDescr::Status`]
+    pub enum CddStatus {
+        Unassigned,
+        /// finished curated domain
+        FinishedOk,
+        /// pending release, not curated
+        PendingRelease,
+        /// other curated data
+        OtherAsn,
+        /// imported from other collection without curation
+        MatrixOnly,
+        /// not yet reviewed
+        UpdateRunning,
+        /// automatically generated alignment
+        AutoUpdated,
+        /// replaced by another domain
+        Claimed,
+        /// curated and complete
+        CuratedComplete,
+        Other = 255,
+    }
+}
+
+asn_names!(CddStatus {
+    Unassigned => "unassigned",
+    FinishedOk => "finished-ok",
+    PendingRelease => "pending-release",
+    OtherAsn => "other-asn1",
+    MatrixOnly => "matrix-only",
+    UpdateRunning => "update-running",
+    AutoUpdated => "auto-updated",
+    Claimed => "claimed",
+    CuratedComplete => "curated-complete",
+    Other => "other",
+});
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[serde(rename_all = "kebab-case")]
+/// Descriptive annotation of a [`Cdd`]
+pub enum CddDescr {
+    /// alternative name for this domain
+    Othername(String),
+
+    /// functional category
+    Category(String),
+
+    /// free-text description
+    Comment(String),
+
+    /// reference for this domain
+    Reference(Box<Pub>),
+
+    CreateDate(Date),
+
+    /// source organism for taxonomic scope
+    TaxSource(Box<OrgRef>),
+
+    /// source database, eg: "Pfam"
+    Source(String),
+
+    Status(CddStatus),
+
+    UpdateDate(Date),
+
+    /// curator notes
+    Scrapbook(Vec<String>),
+
+    /// identifier of the domain in the source database
+    SourceId(CddIdSet),
+
+    /// domains that this one was derived from
+    OldRoot(CddIdSet),
+}
+
+enum_from_primitive! {
+    #[derive(Clone, Serialize_repr, Deserialize_repr, PartialEq, Debug)]
+    #[repr(u8)]
+    /// Relationship of a domain to its parent, given by [`DomainParent::parent_type`]
+    pub enum DomainParentType {
+        Classical,
+        Fusion,
+        Deletion,
+        Permutation,
+        Other = 255,
+    }
+}
+
+asn_names!(DomainParentType {
+    Classical => "classical",
+    Fusion => "fusion",
+    Deletion => "deletion",
+    Permutation => "permutation",
+    Other => "other",
+});
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[serde(rename_all = "kebab-case")]
+/// Parent of a domain within the domain hierarchy
+pub struct DomainParent {
+    pub parent_type: DomainParentType,
+    pub parentid: CddId,
+    pub from: Option<SeqInterval>,
+    pub to: Option<SeqInterval>,
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[serde(rename_all = "kebab-case")]
+/// Site annotated upon the domain alignment, eg: an active or binding site
+pub struct AlignAnnot {
+    pub location: SeqLoc,
+    pub description: Option<String>,
+    /// same meaning as [`SiteAnnot::type`](crate::scoremat::SiteAnnot)
+    pub r#type: Option<i64>,
+    pub aliases: Option<Vec<String>>,
+    /// motif to validate mapping of sites
+    pub motif: Option<String>,
+    /// 0 for validation,
+    /// 1 for motif in seqloc,
+    /// 2 for multiple motifs in seqloc
+    pub motifuse: Option<i64>,
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+#[serde(rename_all = "kebab-case")]
+/// Conserved domain model
+pub struct Cdd {
+    /// short name, eg: "PKc"
+    pub name: String,
+    pub id: CddIdSet,
+    pub description: Option<Vec<CddDescr>>,
+    /// region of the master sequence covered by the profile
+    pub profile_range: Option<SeqInterval>,
+    pub parent: Option<DomainParent>,
+    pub children: Option<CddIdSet>,
+    pub siblings: Option<CddIdSet>,
+    pub neighbors: Option<CddIdSet>,
+    /// master sequences that have a 3D structure
+    pub master3d: Option<Vec<SeqId>>,
+    pub alignannot: Option<Vec<AlignAnnot>>,
+    pub ancestors: Option<Vec<DomainParent>>,
+    /// scoring matrix of the domain model
+    pub scoreparams: Option<PssmWithParameters>,
+}
+
+pub type CddSet = Vec<Cdd>;
+
+impl Cdd {
+    /// UID within [`EntrezDb::ConservedDomains`]
+    pub fn uid(&self) -> Option<u64> {
+        self.id.iter().find_map(|id| match id {
+            CddId::Uid(uid) => Some(*uid),
+            _ => None,
+        })
+    }
+
+    /// Accession of this domain, which is also used to identify its [`Pssm`](crate::scoremat::Pssm)
+    pub fn accession(&self) -> Option<&str> {
+        self.id.iter().find_map(|id| match id {
+            CddId::Gid(gid) => Some(gid.accession.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Free-text descriptions of the domain
+    pub fn comments(&self) -> Vec<&str> {
+        self.description
+            .iter()
+            .flatten()
+            .filter_map(|descr| match descr {
+                CddDescr::Comment(comment) => Some(comment.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// Conserved domains linked to each protein in `ids`, in the same order as `ids`
+///
+/// Only the UIDs of domains are returned, since domain records themselves are not
+/// available from EFetch.
+pub fn fetch_protein_domains<S: AsRef<str>>(ids: &[S]) -> Result<Vec<Vec<CddId>>, String> {
+    let sets = fetch_links(EntrezDb::Protein, EntrezDb::ConservedDomains, ids)?;
+    if sets.len() != ids.len() {
+        return Err(format!("Expected {} link sets, got {}", ids.len(), sets.len()));
+    }
+    Ok(sets
+        .iter()
+        .map(|set| {
+            set.linked_ids(Some("protein_cdd"))
+                .into_iter()
+                .filter_map(|id| id.parse().ok().map(CddId::Uid))
+                .collect()
+        })
+        .collect())
+}
+
+impl XmlNode for GlobalId {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("Global-id")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> {
+        let mut id = Self::default();
+
+        let forbidden = UnexpectedTags(&[]);
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => match qualified_name(e.name(), Self::start_bytes().name()).as_ref() {
+                    b"Global-id_accession" => id.accession = read_string(reader)?,
+                    b"Global-id_release" => id.release = read_string(reader),
+                    b"Global-id_version" => id.version = read_int(reader),
+                    b"Global-id_database" => id.database = read_string(reader),
+                    b"Global-id" => (),
+                    _ => forbidden.check(&e.name()),
+                },
+                Event::End(e) if Self::is_end(&e) => {
+                    return id.into();
+                }
+                _ => (),
+            }
+        }
+    }
+}
+
+impl XmlNode for CddId {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("Cdd-id")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> {
+        let mut id = None;
+
+        let forbidden = UnexpectedTags(&[]);
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => match qualified_name(e.name(), Self::start_bytes().name()).as_ref() {
+                    b"Cdd-id_uid" => id = read_int(reader).map(Self::Uid),
+                    b"Cdd-id_gid" => id = read_node(reader).map(Self::Gid),
+                    b"Cdd-id" => (),
+                    _ => forbidden.check(&e.name()),
+                },
+                Event::End(e) if Self::is_end(&e) => {
+                    return id;
+                }
+                _ => (),
+            }
+        }
+    }
+}
+
+impl XmlVecNode for CddId {}
+
+impl XmlNode for CddDescr {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("Cdd-descr")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> {
+        let mut descr = None;
+
+        let forbidden = UnexpectedTags(&[
+            BytesStart::new("Cdd-descr_repeats"),
+            BytesStart::new("Cdd-descr_curation-status"),
+            BytesStart::new("Cdd-descr_read-status"),
+            BytesStart::new("Cdd-descr_book-ref"),
+            BytesStart::new("Cdd-descr_attribution"),
+            BytesStart::new("Cdd-descr_title"),
+        ]);
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => match qualified_name(e.name(), Self::start_bytes().name()).as_ref() {
+                    b"Cdd-descr_othername" => descr = read_string(reader).map(Self::Othername),
+                    b"Cdd-descr_category" => descr = read_string(reader).map(Self::Category),
+                    b"Cdd-descr_comment" => descr = read_string(reader).map(Self::Comment),
+                    b"Cdd-descr_reference" => descr = read_node(reader).map(Box::new).map(Self::Reference),
+                    b"Cdd-descr_create-date" => descr = read_node(reader).map(Self::CreateDate),
+                    b"Cdd-descr_tax-source" => descr = read_node(reader).map(Box::new).map(Self::TaxSource),
+                    b"Cdd-descr_source" => descr = read_string(reader).map(Self::Source),
+                    b"Cdd-descr_status" => {
+                        descr = read_int::<u8>(reader)
+                            .and_then(CddStatus::from_u8)
+                            .map(Self::Status)
+                    }
+                    b"Cdd-descr_update-date" => descr = read_node(reader).map(Self::UpdateDate),
+                    b"Cdd-descr_scrapbook" => {
+                        descr = Self::Scrapbook(read_vec_str_unchecked(reader, &e.to_end())).into()
+                    }
+                    b"Cdd-descr_source-id" => {
+                        descr = Self::SourceId(read_vec_node(reader, e.to_end())).into()
+                    }
+                    b"Cdd-descr_old-root" => {
+                        descr = Self::OldRoot(read_vec_node(reader, e.to_end())).into()
+                    }
+                    b"Cdd-descr" => (),
+                    _ => {
+                        forbidden.check(&e.name());
+                        reader.read_to_end(e.name()).ok()?;
+                    }
+                },
+                Event::End(e) if Self::is_end(&e) => {
+                    return descr;
+                }
+                _ => (),
+            }
+        }
+    }
+}
+
+impl XmlVecNode for CddDescr {}
+
+impl XmlNode for DomainParent {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("Domain-parent")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> {
+        let mut parent_type = None;
+        let mut parentid = None;
+        let mut from = None;
+        let mut to = None;
+
+        let forbidden = UnexpectedTags(&[]);
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => match qualified_name(e.name(), Self::start_bytes().name()).as_ref() {
+                    b"Domain-parent_parent-type" => {
+                        parent_type = read_int::<u8>(reader).and_then(DomainParentType::from_u8)
+                    }
+                    b"Domain-parent_parentid" => parentid = read_node(reader),
+                    b"Domain-parent_from" => from = read_node(reader),
+                    b"Domain-parent_to" => to = read_node(reader),
+                    b"Domain-parent" => (),
+                    _ => forbidden.check(&e.name()),
+                },
+                Event::End(e) if Self::is_end(&e) => {
+                    return Self {
+                        parent_type: parent_type?,
+                        parentid: parentid?,
+                        from,
+                        to,
+                    }
+                    .into();
+                }
+                _ => (),
+            }
+        }
+    }
+}
+
+impl XmlVecNode for DomainParent {}
+
+impl XmlNode for AlignAnnot {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("Align-annot")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> {
+        let mut location = None;
+        let mut description = None;
+        let mut r#type = None;
+        let mut aliases = None;
+        let mut motif = None;
+        let mut motifuse = None;
+
+        let forbidden = UnexpectedTags(&[BytesStart::new("Align-annot_evidence")]);
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => match qualified_name(e.name(), Self::start_bytes().name()).as_ref() {
+                    b"Align-annot_location" => location = read_node(reader),
+                    b"Align-annot_description" => description = read_string(reader),
+                    b"Align-annot_type" => r#type = read_int(reader),
+                    b"Align-annot_aliases" => {
+                        aliases = read_vec_str_unchecked(reader, &e.to_end()).into()
+                    }
+                    b"Align-annot_motif" => motif = read_string(reader),
+                    b"Align-annot_motifuse" => motifuse = read_int(reader),
+                    b"Align-annot" => (),
+                    _ => {
+                        forbidden.check(&e.name());
+                        reader.read_to_end(e.name()).ok()?;
+                    }
+                },
+                Event::End(e) if Self::is_end(&e) => {
+                    return Self {
+                        location: location?,
+                        description,
+                        r#type,
+                        aliases,
+                        motif,
+                        motifuse,
+                    }
+                    .into();
+                }
+                _ => (),
+            }
+        }
+    }
+}
+
+impl XmlVecNode for AlignAnnot {}
+
+impl XmlNode for Cdd {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("Cdd")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> {
+        let mut cdd = Self::default();
+
+        // used by Cn3D, and not needed to describe the domain model
+        let forbidden = UnexpectedTags(&[
+            BytesStart::new("Cdd_seqannot"),
+            BytesStart::new("Cdd_features"),
+            BytesStart::new("Cdd_sequences"),
+            BytesStart::new("Cdd_trunc-master"),
+            BytesStart::new("Cdd_posfreq"),
+            BytesStart::new("Cdd_scoremat"),
+            BytesStart::new("Cdd_distance"),
+            BytesStart::new("Cdd_pending"),
+            BytesStart::new("Cdd_rejects"),
+            BytesStart::new("Cdd_style-dictionary"),
+            BytesStart::new("Cdd_user-annotations"),
+            BytesStart::new("Cdd_seqtree"),
+        ]);
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => match qualified_name(e.name(), Self::start_bytes().name()).as_ref() {
+                    b"Cdd_name" => cdd.name = read_string(reader)?,
+                    b"Cdd_id" => cdd.id = read_vec_node(reader, e.to_end()),
+                    b"Cdd_description" => cdd.description = read_vec_node(reader, e.to_end()).into(),
+                    b"Cdd_profile-range" => cdd.profile_range = read_node(reader),
+                    b"Cdd_parent" => cdd.parent = read_node(reader),
+                    b"Cdd_children" => cdd.children = read_vec_node(reader, e.to_end()).into(),
+                    b"Cdd_siblings" => cdd.siblings = read_vec_node(reader, e.to_end()).into(),
+                    b"Cdd_neighbors" => cdd.neighbors = read_vec_node(reader, e.to_end()).into(),
+                    b"Cdd_master3d" => cdd.master3d = read_vec_node(reader, e.to_end()).into(),
+                    b"Cdd_alignannot" => cdd.alignannot = read_vec_node(reader, e.to_end()).into(),
+                    b"Cdd_ancestors" => cdd.ancestors = read_vec_node(reader, e.to_end()).into(),
+                    b"Cdd_scoreparams" => cdd.scoreparams = read_node(reader),
+                    b"Cdd" => (),
+                    _ => {
+                        forbidden.check(&e.name());
+                        reader.read_to_end(e.name()).ok()?;
+                    }
+                },
+                Event::End(e) if Self::is_end(&e) => {
+                    return cdd.into();
+                }
+                _ => (),
+            }
+        }
+    }
+}
+
+impl XmlVecNode for Cdd {}
+
+/// Parse every [`Cdd`] within a `Cdd-set`
+pub fn read_cdd_set(reader: &mut Reader<&[u8]>) -> CddSet {
+    read_vec_node(reader, BytesEnd::new("Cdd-set"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cdd::{Cdd, CddDescr, CddId, CddStatus, DomainParentType};
+    use crate::parsing::parse_node;
+
+    const DOMAIN: &str = r#"
+<Cdd>
+  <Cdd_name>PKc_like</Cdd_name>
+  <Cdd_id>
+    <Cdd-id-set>
+      <Cdd-id>
+        <Cdd-id_uid>270622</Cdd-id_uid>
+      </Cdd-id>
+      <Cdd-id>
+        <Cdd-id_gid>
+          <Global-id>
+            <Global-id_accession>cd00180</Global-id_accession>
+            <Global-id_version>12</Global-id_version>
+          </Global-id>
+        </Cdd-id_gid>
+      </Cdd-id>
+    </Cdd-id-set>
+  </Cdd_id>
+  <Cdd_description>
+    <Cdd-descr-set>
+      <Cdd-descr>
+        <Cdd-descr_comment>Protein Kinases, catalytic domain</Cdd-descr_comment>
+      </Cdd-descr>
+      <Cdd-descr>
+        <Cdd-descr_status>1</Cdd-descr_status>
+      </Cdd-descr>
+      <Cdd-descr>
+        <Cdd-descr_repeats>
+          <Cdd-repeat>
+            <Cdd-repeat_count>1</Cdd-repeat_count>
+          </Cdd-repeat>
+        </Cdd-descr_repeats>
+      </Cdd-descr>
+    </Cdd-descr-set>
+  </Cdd_description>
+  <Cdd_seqannot>
+    <Seq-annot>
+      <Seq-annot_data>
+        <Seq-annot_data_align/>
+      </Seq-annot_data>
+    </Seq-annot>
+  </Cdd_seqannot>
+  <Cdd_parent>
+    <Domain-parent>
+      <Domain-parent_parent-type>0</Domain-parent_parent-type>
+      <Domain-parent_parentid>
+        <Cdd-id>
+          <Cdd-id_uid>270000</Cdd-id_uid>
+        </Cdd-id>
+      </Domain-parent_parentid>
+    </Domain-parent>
+  </Cdd_parent>
+  <Cdd_children>
+    <Cdd-id-set>
+      <Cdd-id>
+        <Cdd-id_uid>270623</Cdd-id_uid>
+      </Cdd-id>
+    </Cdd-id-set>
+  </Cdd_children>
+  <Cdd_alignannot>
+    <Align-annot-set>
+      <Align-annot>
+        <Align-annot_location>
+          <Seq-loc>
+            <Seq-loc_int>
+              <Seq-interval>
+                <Seq-interval_from>10</Seq-interval_from>
+                <Seq-interval_to>17</Seq-interval_to>
+                <Seq-interval_id>
+                  <Seq-id>
+                    <Seq-id_gi>15718680</Seq-id_gi>
+                  </Seq-id>
+                </Seq-interval_id>
+              </Seq-interval>
+            </Seq-loc_int>
+          </Seq-loc>
+        </Align-annot_location>
+        <Align-annot_description>ATP binding site</Align-annot_description>
+        <Align-annot_type>1</Align-annot_type>
+      </Align-annot>
+    </Align-annot-set>
+  </Cdd_alignannot>
+</Cdd>"#;
+
+    #[test]
+    fn test_parse_cdd() {
+        let cdd = parse_node::<Cdd>(DOMAIN).unwrap();
+
+        assert_eq!(cdd.name, "PKc_like");
+        assert_eq!(cdd.uid(), Some(270622));
+        assert_eq!(cdd.accession(), Some("cd00180"));
+        assert_eq!(cdd.comments(), vec!["Protein Kinases, catalytic domain"]);
+
+        // unimplemented description is dropped
+        let description = cdd.description.as_ref().unwrap();
+        assert_eq!(description.len(), 2);
+        assert_eq!(description[1], CddDescr::Status(CddStatus::FinishedOk));
+
+        let parent = cdd.parent.as_ref().unwrap();
+        assert_eq!(parent.parent_type, DomainParentType::Classical);
+        assert_eq!(parent.parentid, CddId::Uid(270000));
+        assert_eq!(cdd.children, Some(vec![CddId::Uid(270623)]));
+
+        let annot = &cdd.alignannot.as_ref().unwrap()[0];
+        assert_eq!(annot.description.as_deref(), Some("ATP binding site"));
+        assert_eq!(annot.r#type, Some(1));
+    }
+
+    #[test]
+    fn test_cdd_requires_name() {
+        let unnamed = DOMAIN.replace("<Cdd_name>PKc_like</Cdd_name>", "<Cdd_name></Cdd_name>");
+        assert!(parse_node::<Cdd>(&unnamed).is_none());
+    }
+}
@@ -31,12 +31,13 @@ IMPORTS Gene-ref FROM NCBI-Gene
     Pub FROM NCBI-Pub;
 */
 
-use crate::seqloc::SeqLoc ;
+use crate::seqloc::{SeqInterval, SeqLoc} ;
 use crate::r#pub::Pub ;
-use crate::seqfeat::{BioSource, BioSourceGenome, BioSourceOrigin, GeneRef, ProtRef, RnaRef, RnaRefType} ;
+use crate::seqfeat::{BioSource, BioSourceGenome, BioSourceOrigin, GeneRef, ProtRef, RnaRef} ;
 
-use crate::general::{Date, DbTag, PersonId};
-use crate::parsing::{read_vec_node, read_int, read_node, read_string, read_vec_str_unchecked, UnexpectedTags, read_bool_attribute};
+use crate::biblio::PubMedId;
+use crate::general::{Date, DbTag, ObjectId, PersonId};
+use crate::parsing::{next_event, qualified_name, read_vec_node, read_int, read_node, read_string, read_vec_str_unchecked, UnexpectedTags, read_bool_attribute};
 use crate::parsing::{XmlNode, XmlVecNode};
 
 use serde::{Deserialize, Serialize};
@@ -44,6 +45,7 @@ use serde_repr::{Deserialize_repr, Serialize_repr};
 
 use quick_xml::events::{BytesStart, Event};
 use quick_xml::Reader;
+use std::str::FromStr;
 
 
 /*
@@ -75,6 +77,46 @@ pub enum EntrezgeneType {
     Other = 255,
 }
 
+asn_names!(EntrezgeneType {
+    Unknown => "unknown",
+    TRna => "tRNA",
+    RRna => "rRNA",
+    SnRna => "snRNA",
+    ScRna => "scRNA",
+    SnoRna => "snoRNA",
+    ProteinCoding => "protein-coding",
+    Pseudo => "pseudo",
+    Transposon => "transposon",
+    MiscRna => "miscRNA",
+    NcRna => "ncRNA",
+    BiologicalRegion => "biological-region",
+    Other => "other",
+});
+
+impl EntrezgeneType {
+    /// Type given by its name (eg: "protein-coding") or numeric value
+    ///
+    /// Unrecognized values are [`Self::Unknown`].
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "0"|"unknown" => Self::Unknown,
+            "1"|"tRNA" => Self::TRna,
+            "2"|"rRNA" => Self::RRna,
+            "3"|"snRNA" => Self::SnRna,
+            "4"|"scRNA" => Self::ScRna,
+            "5"|"snoRNA" => Self::SnoRna,
+            "6"|"protein-coding" => Self::ProteinCoding,
+            "7"|"pseudo" => Self::Pseudo,
+            "8"|"transposon" => Self::Transposon,
+            "9"|"miscRNA" => Self::MiscRna,
+            "10"|"ncRNA" => Self::NcRna,
+            "11"|"biological-region" => Self::BiologicalRegion,
+            "255"|"other" => Self::Other,
+            _ => Self::Unknown,
+        }
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 #[serde(rename_all = "lowercase")]
 pub struct Entrezgene {
@@ -109,6 +151,452 @@ pub struct Entrezgene {
     pub non_unique_keys: Option<Vec<DbTag>> ,
 }
 
+impl Entrezgene {
+    /// Genomic commentary for the primary annotated locus
+    ///
+    /// This is the first [`GeneCommentaryType::Genomic`] commentary of [`Self::locus`]
+    /// (typically the reference assembly) that gives an interval.
+    pub fn genomic_locus(&self) -> Option<&GeneCommentary> {
+        self.locus.iter().flatten().find(|commentary| {
+            commentary.r#type == GeneCommentaryType::Genomic && commentary.locus_interval().is_some()
+        })
+    }
+
+    /// Versioned accessions of transcripts annotated on [`Self::genomic_locus()`]
+    pub fn transcript_accessions(&self) -> Vec<String> {
+        let products = self.genomic_locus().and_then(|locus| locus.products.as_ref());
+        products
+            .into_iter()
+            .flatten()
+            .filter(|product| {
+                matches!(
+                    product.r#type,
+                    GeneCommentaryType::MRna
+                        | GeneCommentaryType::RRna
+                        | GeneCommentaryType::TRna
+                        | GeneCommentaryType::NcRna
+                        | GeneCommentaryType::PreRna
+                )
+            })
+            .filter_map(|product| product.versioned_accession())
+            .collect()
+    }
+
+    /// Gene Ontology annotations
+    ///
+    /// Terms are found in the "GeneOntology" commentary of [`Self::properties`], which
+    /// groups each annotation by aspect (eg: "Function"). Every annotation is given as
+    /// a commentary whose [`OtherSource`] holds the GO [`DbTag`], term name and
+    /// evidence code, and whose references hold supporting PubMed ids.
+    pub fn go_terms(&self) -> Vec<GoTerm> {
+        let ontology = self
+            .properties
+            .iter()
+            .flatten()
+            .filter(|commentary| commentary.heading.as_deref() == Some("GeneOntology"));
+
+        let mut terms = Vec::new();
+        for aspect in ontology.flat_map(|commentary| commentary.comment.iter().flatten()) {
+            let aspect_type = match aspect.label.as_deref().and_then(|label| label.parse().ok()) {
+                Some(aspect_type) => aspect_type,
+                None => continue,
+            };
+            for annotation in aspect.comment.iter().flatten() {
+                if let Some(term) = GoTerm::from_commentary(aspect_type, annotation) {
+                    terms.push(term);
+                }
+            }
+        }
+        terms
+    }
+
+    /// Orthologous genes in other organisms
+    ///
+    /// Orthologs are given by [`Self::homology`], or by an "Orthologs" commentary of
+    /// [`Self::comments`] (eg: "Orthologs from Annotation Pipeline"). Each ortholog is
+    /// a commentary whose [`OtherSource`]s hold the "GeneID" and "taxon" [`DbTag`]s,
+    /// along with the gene symbol and organism name.
+    pub fn orthologs(&self) -> Vec<Ortholog> {
+        let groups = self.comments.iter().flatten().filter(|commentary| {
+            commentary.heading.as_deref().is_some_and(|heading| heading.starts_with("Orthologs"))
+        });
+
+        let mut orthologs = Vec::new();
+        for commentary in self.homology.iter().flatten().chain(groups) {
+            commentary.visit(&mut |commentary| {
+                if let Some(ortholog) = Ortholog::from_commentary(commentary) {
+                    orthologs.push(ortholog);
+                }
+            });
+        }
+        orthologs
+    }
+
+    /// Phenotypes associated with the gene
+    ///
+    /// Phenotypes are the [`GeneCommentaryType::Phenotype`] commentaries of the
+    /// "Phenotypes" commentary of [`Self::comments`]. The heading of each names the
+    /// phenotype, and its nested commentaries link it to other databases (eg: OMIM,
+    /// MedGen) and to supporting publications.
+    pub fn phenotypes(&self) -> Vec<Phenotype> {
+        self.comments
+            .iter()
+            .flatten()
+            .filter(|commentary| commentary.heading.as_deref() == Some("Phenotypes"))
+            .flat_map(|commentary| commentary.comment.iter().flatten())
+            .filter(|commentary| commentary.r#type == GeneCommentaryType::Phenotype)
+            .filter_map(Phenotype::from_commentary)
+            .collect()
+    }
+
+    /// GeneRIFs: short statements about the function of the gene, with their references
+    ///
+    /// Only [`GeneCommentaryType::Generif`] commentaries of [`Self::comments`] which give
+    /// a text are returned, so that interactions, also given as GeneRIFs, are excluded.
+    pub fn generifs(&self) -> Vec<GeneRif> {
+        self.comments
+            .iter()
+            .flatten()
+            .filter(|commentary| commentary.r#type == GeneCommentaryType::Generif)
+            .filter_map(|commentary| {
+                Some(GeneRif {
+                    text: commentary.text.clone()?,
+                    pmids: commentary.pmids(),
+                })
+            })
+            .collect()
+    }
+
+    /// Annotation of the gene on each genome assembly (eg: GRCh38 and T2T-CHM13)
+    ///
+    /// Every genomic commentary of [`Self::locus`] whose heading names an assembly is
+    /// returned, in order, so that RefSeqGene and other loci which are not placed on an
+    /// assembly are left out.
+    pub fn assembly_loci(&self) -> Vec<AssemblyLocus<'_>> {
+        self.locus
+            .iter()
+            .flatten()
+            .filter(|commentary| commentary.r#type == GeneCommentaryType::Genomic)
+            .filter_map(AssemblyLocus::from_commentary)
+            .collect()
+    }
+
+    /// Annotation of the gene on `assembly`
+    ///
+    /// `assembly` is either the full name of an assembly (eg: "GRCh38.p14"), or its
+    /// name without the patch level (eg: "GRCh38").
+    pub fn assembly_locus(&self, assembly: &str) -> Option<AssemblyLocus<'_>> {
+        self.assembly_loci().into_iter().find(|locus| {
+            locus.assembly == assembly
+                || locus
+                    .assembly
+                    .strip_prefix(assembly)
+                    .is_some_and(|patch| patch.starts_with('.'))
+        })
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+/// Annotation of a gene on one genome assembly, given by [`Entrezgene::assembly_loci()`]
+pub struct AssemblyLocus<'a> {
+    /// "Reference" for the reference assembly of the organism, otherwise "Alternate"
+    pub category: &'a str,
+    /// name of the assembly (eg: "GRCh38.p14")
+    pub assembly: &'a str,
+    /// assembly unit or strain (eg: "Primary Assembly", "C57BL/6J")
+    pub unit: Option<String>,
+    /// name of the chromosome (eg: "1", "X")
+    pub chromosome: Option<&'a str>,
+    pub commentary: &'a GeneCommentary,
+}
+
+impl<'a> AssemblyLocus<'a> {
+    /// Parse the heading (eg: "Reference GRCh38.p14 Primary Assembly") and label (eg:
+    /// "Chromosome 1 Reference GRCh38.p14 Primary Assembly") of a genomic commentary
+    fn from_commentary(commentary: &'a GeneCommentary) -> Option<Self> {
+        let mut words = commentary.heading.as_deref()?.split_whitespace();
+        let category = words.next()?;
+        let assembly = words.next()?;
+        let unit = words.collect::<Vec<_>>().join(" ");
+        let chromosome = commentary
+            .label
+            .as_deref()
+            .and_then(|label| label.strip_prefix("Chromosome "))
+            .and_then(|label| label.split_whitespace().next());
+
+        Some(Self {
+            category,
+            assembly,
+            unit: (!unit.is_empty()).then_some(unit),
+            chromosome,
+            commentary,
+        })
+    }
+
+    /// Versioned accession of the chromosome or scaffold (eg: "NC_000001.11")
+    pub fn accession(&self) -> Option<String> {
+        self.commentary.versioned_accession()
+    }
+
+    /// Interval covered by the gene on [`Self::accession()`]
+    pub fn interval(&self) -> Option<&'a SeqInterval> {
+        self.commentary.locus_interval()
+    }
+
+    /// First and last positions covered by the gene, starting at 0
+    pub fn range(&self) -> Option<(i64, i64)> {
+        self.interval().map(|interval| (interval.from, interval.to))
+    }
+
+    /// Transcripts annotated on this assembly, followed by the proteins they encode
+    pub fn products(&self) -> Vec<LocusProduct<'a>> {
+        let mut products = Vec::new();
+        for transcript in self.commentary.products.iter().flatten() {
+            products.push(LocusProduct {
+                commentary: transcript,
+                parent: None,
+            });
+            for protein in transcript.products.iter().flatten() {
+                products.push(LocusProduct {
+                    commentary: protein,
+                    parent: Some(transcript),
+                });
+            }
+        }
+        products
+    }
+
+    /// Product with `accession`, given with or without its version (eg: "NM_005427.4")
+    pub fn product(&self, accession: &str) -> Option<LocusProduct<'a>> {
+        self.products().into_iter().find(|product| {
+            product.commentary.accession.as_deref() == Some(accession)
+                || product.accession().as_deref() == Some(accession)
+        })
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+/// Transcript or protein of an [`AssemblyLocus`]
+pub struct LocusProduct<'a> {
+    pub commentary: &'a GeneCommentary,
+    /// transcript encoding a protein
+    pub parent: Option<&'a GeneCommentary>,
+}
+
+impl<'a> LocusProduct<'a> {
+    pub fn r#type(&self) -> &'a GeneCommentaryType {
+        &self.commentary.r#type
+    }
+
+    /// Versioned accession of the product (eg: "NM_005427.4")
+    pub fn accession(&self) -> Option<String> {
+        self.commentary.versioned_accession()
+    }
+
+    /// eg: "transcript variant 1", "isoform a"
+    pub fn label(&self) -> Option<&'a str> {
+        self.commentary.label.as_deref()
+    }
+
+    /// Exons, or coding regions of proteins, on the sequence of the assembly
+    pub fn location(&self) -> Option<&'a SeqLoc> {
+        self.commentary.genomic_coords.as_ref()?.first()
+    }
+
+    /// First and last positions of [`Self::location()`], starting at 0
+    pub fn range(&self) -> Option<(i64, i64)> {
+        self.location()?.total_range()
+    }
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+#[serde(rename_all = "lowercase")]
+/// Branch of the Gene Ontology that a [`GoTerm`] belongs to
+pub enum GoAspect {
+    /// molecular function
+    Function,
+    /// biological process
+    Process,
+    /// cellular component
+    Component,
+}
+
+impl FromStr for GoAspect {
+    type Err = String;
+
+    /// Parse the label of an aspect commentary
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Function" => Ok(Self::Function),
+            "Process" => Ok(Self::Process),
+            "Component" => Ok(Self::Component),
+            _ => Err(format!("Unknown GO aspect: {}", s)),
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+/// Gene Ontology annotation extracted by [`Entrezgene::go_terms()`]
+pub struct GoTerm {
+    pub aspect: GoAspect,
+    /// GO identifier (eg: "GO:0001228")
+    pub id: String,
+    /// term name
+    pub name: Option<String>,
+    /// relation to the gene (eg: "enables", "involved_in")
+    pub qualifier: Option<String>,
+    /// evidence code (eg: "IDA")
+    pub evidence: Option<String>,
+    /// supporting PubMed ids
+    pub pmids: Vec<PubMedId>,
+}
+
+impl GoTerm {
+    fn from_commentary(aspect: GoAspect, commentary: &GeneCommentary) -> Option<Self> {
+        let source = commentary
+            .source
+            .iter()
+            .flatten()
+            .find(|source| source.src.as_ref().is_some_and(|tag| tag.db == "GO"))?;
+        let id = match &source.src.as_ref()?.tag {
+            ObjectId::Id(id) => format!("GO:{:07}", id),
+            ObjectId::Str(id) if id.starts_with("GO:") => id.to_string(),
+            ObjectId::Str(id) => format!("GO:{}", id),
+        };
+        let evidence = source.post_text.as_ref().map(|text| {
+            text.trim_start_matches("evidence:").trim().to_string()
+        });
+        let pmids = commentary.pmids();
+
+        Some(Self {
+            aspect,
+            id,
+            name: source.anchor.clone(),
+            qualifier: source.pre_text.clone(),
+            evidence,
+            pmids,
+        })
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+/// Orthologous gene extracted by [`Entrezgene::orthologs()`]
+pub struct Ortholog {
+    /// GeneID of the ortholog
+    pub gene_id: u64,
+    /// taxonomy id of its organism
+    pub taxid: Option<u64>,
+    /// gene symbol (eg: "TP73")
+    pub symbol: Option<String>,
+    /// common name of the organism (eg: "mouse")
+    pub organism: Option<String>,
+    /// kind of ortholog (eg: "Primary", "Additional")
+    pub label: Option<String>,
+}
+
+impl Ortholog {
+    fn from_commentary(commentary: &GeneCommentary) -> Option<Self> {
+        let sources = commentary.source.as_ref()?;
+        let id_of = |db: &str| {
+            sources.iter().find_map(|source| {
+                let tag = source.src.as_ref().filter(|tag| tag.db == db)?;
+                match &tag.tag {
+                    ObjectId::Id(id) => Some((source, *id)),
+                    ObjectId::Str(id) => Some((source, id.parse().ok()?)),
+                }
+            })
+        };
+        let (gene, gene_id) = id_of("GeneID")?;
+
+        Some(Self {
+            gene_id,
+            taxid: id_of("taxon").map(|(_, taxid)| taxid),
+            symbol: gene.pre_text.clone(),
+            organism: gene.anchor.clone(),
+            label: commentary.label.clone(),
+        })
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+/// Phenotype extracted by [`Entrezgene::phenotypes()`]
+pub struct Phenotype {
+    /// name of the phenotype (eg: "Ciliary dyskinesia, primary, 47, and lissencephaly")
+    pub name: String,
+    /// description, or evidence of the association (eg: for copy number responses)
+    pub text: Option<String>,
+    /// identifiers of the phenotype in other databases (eg: "MIM", "MedGen")
+    pub xrefs: Vec<DbTag>,
+    /// supporting PubMed ids
+    pub pmids: Vec<PubMedId>,
+}
+
+impl Phenotype {
+    fn from_commentary(commentary: &GeneCommentary) -> Option<Self> {
+        let mut xrefs = Vec::new();
+        let mut pmids = Vec::new();
+        commentary.visit(&mut |commentary| {
+            xrefs.extend(commentary.source.iter().flatten().filter_map(|source| source.src.clone()));
+            pmids.extend(commentary.pmids());
+        });
+        pmids.dedup();
+
+        Some(Self {
+            name: commentary.heading.clone()?,
+            text: commentary.text.clone(),
+            xrefs,
+            pmids,
+        })
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+/// Reference Into Function extracted by [`Entrezgene::generifs()`]
+pub struct GeneRif {
+    pub text: String,
+    /// PubMed ids of the publications it is drawn from
+    pub pmids: Vec<PubMedId>,
+}
+
+impl GeneCommentary {
+    /// PubMed ids given by [`Self::refs`]
+    pub fn pmids(&self) -> Vec<PubMedId> {
+        self.refs
+            .iter()
+            .flatten()
+            .filter_map(|r#pub| match r#pub {
+                Pub::PmId(pmid) => Some(*pmid),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Call `f` on this commentary, then on each nested [`Self::comment`], depth first
+    fn visit<F: FnMut(&GeneCommentary)>(&self, f: &mut F) {
+        f(self);
+        for commentary in self.comment.iter().flatten() {
+            commentary.visit(f);
+        }
+    }
+
+    /// Accession including version (eg: "NM_005427.4")
+    pub fn versioned_accession(&self) -> Option<String> {
+        let accession = self.accession.as_ref()?;
+        match self.version {
+            Some(version) => Some(format!("{}.{}", accession, version)),
+            None => Some(accession.clone()),
+        }
+    }
+
+    /// Interval covered by a genomic commentary, given in [`Self::seqs`]
+    pub fn locus_interval(&self) -> Option<&SeqInterval> {
+        self.seqs.iter().flatten().find_map(|loc| match loc {
+            SeqLoc::Int(interval) => Some(interval),
+            _ => None,
+        })
+    }
+}
+
 pub type EntrezgeneSet = Vec<Entrezgene>;
 
 #[derive(Clone, Serialize_repr, Deserialize_repr, PartialEq, Debug)]
@@ -119,6 +607,12 @@ pub enum GeneTrackStatus {
     Discontinued = 2,
 }
 
+asn_names!(GeneTrackStatus {
+    Live => "live",
+    Secondary => "secondary",
+    Discontinued => "discontinued",
+});
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 #[serde(rename_all = "lowercase")]
 pub struct GeneTrack {
@@ -223,6 +717,40 @@ pub enum GeneCommentaryType {
     Other = 255 ,
 }
 
+asn_names!(GeneCommentaryType {
+    Genomic => "genomic",
+    PreRna => "pre-RNA",
+    MRna => "mRNA",
+    RRna => "rRNA",
+    TRna => "tRNA",
+    SnRNA => "snRNA",
+    ScRNA => "scRNA",
+    Peptide => "peptide",
+    OtherGenetic => "other-genetic",
+    GenomicMrna => "genomic-mRNA",
+    CRna => "cRNA",
+    MaturePeptide => "mature-peptide",
+    PreProtein => "pre-protein",
+    MiscRNA => "miscRNA",
+    SnoRNA => "snoRNA",
+    Property => "property",
+    Reference => "reference",
+    Generif => "generif",
+    Phenotype => "phenotype",
+    Complex => "complex",
+    Compound => "compound",
+    NcRna => "ncRNA",
+    GeneGroup => "gene-group",
+    Assembly => "assembly",
+    AssemblyUnit => "assembly-unit",
+    CRegion => "c-region",
+    DSegment => "d-segment",
+    JSegment => "j-segment",
+    VSegment => "v-segment",
+    Comment => "comment",
+    Other => "other",
+});
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 #[serde(rename_all = "lowercase")]
 pub struct GeneCommentary {
@@ -273,6 +801,14 @@ pub enum MapType {
     Min = 4,
 }
 
+asn_names!(MapType {
+    Cyto => "cyto",
+    Bp => "bp",
+    CM => "cM",
+    CR => "cR",
+    Min => "min",
+});
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 #[serde(rename_all = "lowercase")]
 pub enum MapsMethodChoice {
@@ -339,22 +875,7 @@ fn read_gene_track_status(reader: &mut Reader<&[u8]>) -> Option<GeneTrackStatus>
 
 fn read_entrezgene_type(reader: &mut Reader<&[u8]>) -> Option<EntrezgeneType> {
     let text = read_string(reader).unwrap_or_default();
-    match text.as_str() {
-        "0"|"unknown" => Some(EntrezgeneType::Unknown),
-        "1"|"tRNA" => Some(EntrezgeneType::TRna),
-        "2"|"rRNA" => Some(EntrezgeneType::RRna),
-        "3"|"snRNA" => Some(EntrezgeneType::SnRna),
-        "4"|"scRNA" => Some(EntrezgeneType::ScRna),
-        "5"|"snoRNA" => Some(EntrezgeneType::SnoRna),
-        "6"|"protein-coding" => Some(EntrezgeneType::ProteinCoding),
-        "7"|"pseudo" => Some(EntrezgeneType::Pseudo),
-        "8"|"transposon" => Some(EntrezgeneType::Transposon),
-        "9"|"miscRNA" => Some(EntrezgeneType::MiscRna),
-        "10"|"ncRNA" => Some(EntrezgeneType::NcRna),
-        "11"|"biological-region" => Some(EntrezgeneType::BiologicalRegion),
-        "255"|"other" => Some(EntrezgeneType::Other),
-        _ => Some(EntrezgeneType::Unknown),
-    }
+    Some(EntrezgeneType::from_name(text.as_str()))
 }
 
 impl XmlNode for Entrezgene {
@@ -363,7 +884,7 @@ impl XmlNode for Entrezgene {
     }
 
     fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> {
-        println!("Starting parsing of <Entrezgene>");
+        log::trace!("parsing <Entrezgene> at byte {}", reader.buffer_position());
 
         let mut gene = Entrezgene {
             track_info: None,
@@ -387,38 +908,37 @@ impl XmlNode for Entrezgene {
             non_unique_keys: None,
         };
 
-        let forbidden_tags = [
-            BytesStart::new("extra-field"),
-            BytesStart::new("other-field"),
-        ];
-        let forbidden = UnexpectedTags(&forbidden_tags);
+        let forbidden = UnexpectedTags(&[]);
 
         loop {
-            match reader.read_event().unwrap() {
+            match next_event(reader)? {
                 Event::Start(e) => {
                     //println!("D Entrezgene: Encountered tag {:?}", e.name());
-                    match e.name().as_ref() {
-                        b"track-info" | b"Entrezgene_track-info" => gene.track_info = read_node(reader),
-                        b"type" | b"Entrezgene_type" => {
+                    match qualified_name(e.name(), Self::start_bytes().name()).as_ref() {
+                        b"Entrezgene_track-info" => gene.track_info = read_node(reader),
+                        b"Entrezgene_type" => {
                             if let Some(t) = read_entrezgene_type(reader) {
                                 gene.r#type = t;
                             }
                         }
-                        b"source" | b"Entrezgene_source" => gene.source = read_node(reader).unwrap_or_default(),
-                        b"gene" | b"Entrezgene_gene" => gene.gene = read_node(reader).unwrap(),
-                        b"prot" | b"Entrezgene_prot" => gene.prot = read_node(reader),
-                        b"rna" => gene.rna = read_node(reader),
-                        b"summary" | b"Entrezgene_summary" => gene.summary = read_string(reader),
-                        b"location" | b"Entrezgene_location" => gene.location = Some(read_vec_node(reader, e.to_end())),
-                        b"gene-source" | b"Entrezgene_gene-source" => gene.gene_source = read_node(reader),
-                        b"locus" | b"Entrezgene_locus" => gene.locus = Some(read_vec_node(reader, e.to_end())),
-                        b"properties" | b"Entrezgene_properties" => gene.properties = Some(read_vec_node(reader, e.to_end())),
-                        b"comments" | b"Entrezgene_comments" => gene.comments = Some(read_vec_node(reader, e.to_end())),
-                        b"unique-keys" | b"Entrezgene_unique-keys" => gene.unique_keys = Some(read_vec_node(reader, e.to_end())),
-                        b"xtra-index-terms" | b"Entrezgene_xtra-index-terms" => gene.xtra_index_terms = Some(read_vec_node(reader, e.to_end())),
-                        b"xtra-properties" | b"Entrezgene_xtra-properties" => gene.xtra_properties = Some(read_vec_node(reader, e.to_end())),
-                        b"xtra-iq" | b"Entrezgene_xtra-iq" => gene.xtra_iq = Some(read_vec_node(reader, e.to_end())),
-                        b"non-unique-keys" | b"Entrezgene_non-unique-keys" => gene.non_unique_keys = Some(read_vec_node(reader, e.to_end())),
+                        b"Entrezgene_source" => gene.source = read_node(reader).unwrap_or_default(),
+                        b"Entrezgene_gene" => gene.gene = read_node(reader)?,
+                        b"Entrezgene_prot" => gene.prot = read_node(reader),
+                        b"Entrezgene_rna" => gene.rna = read_node(reader),
+                        b"Entrezgene_summary" => gene.summary = read_string(reader),
+                        b"Entrezgene_location" => gene.location = Some(read_vec_node(reader, e.to_end())),
+                        b"Entrezgene_gene-source" => gene.gene_source = read_node(reader),
+                        b"Entrezgene_locus" => gene.locus = Some(read_vec_node(reader, e.to_end())),
+                        b"Entrezgene_properties" => gene.properties = Some(read_vec_node(reader, e.to_end())),
+                        b"Entrezgene_refgene" => gene.refgene = Some(read_vec_node(reader, e.to_end())),
+                        b"Entrezgene_homology" => gene.homology = Some(read_vec_node(reader, e.to_end())),
+                        b"Entrezgene_comments" => gene.comments = Some(read_vec_node(reader, e.to_end())),
+                        b"Entrezgene_unique-keys" => gene.unique_keys = Some(read_vec_node(reader, e.to_end())),
+                        b"Entrezgene_xtra-index-terms" => gene.xtra_index_terms = Some(read_vec_node(reader, e.to_end())),
+                        b"Entrezgene_xtra-properties" => gene.xtra_properties = Some(read_vec_node(reader, e.to_end())),
+                        b"Entrezgene_xtra-iq" => gene.xtra_iq = Some(read_vec_node(reader, e.to_end())),
+                        b"Entrezgene_non-unique-keys" => gene.non_unique_keys = Some(read_vec_node(reader, e.to_end())),
+                        b"Entrezgene" => (),
                         _ => forbidden.check(&e.name()),
                     }
                 },
@@ -459,12 +979,12 @@ impl XmlNode for GeneSource {
         let mut source = GeneSource::default();
 
         loop {
-            match reader.read_event().unwrap() {
-                Event::Start(e) => match e.name().as_ref() {
-                    b"Src" => source.src = read_string(reader).unwrap_or_default(),
-                    b"SrcInt" => source.src_int = read_int(reader),
-                    b"SrcStr1" => source.src_str1 = read_string(reader),
-                    b"SrcStr2" => source.src_str2 = read_string(reader),
+            match next_event(reader)? {
+                Event::Start(e) => match qualified_name(e.name(), Self::start_bytes().name()).as_ref() {
+                    b"Gene-source_src" => source.src = read_string(reader).unwrap_or_default(),
+                    b"Gene-source_src-int" => source.src_int = read_int(reader),
+                    b"Gene-source_src-str1" => source.src_str1 = read_string(reader),
+                    b"Gene-source_src-str2" => source.src_str2 = read_string(reader),
                     _ => (),
                 },
                 Event::End(e) => {
@@ -480,17 +1000,6 @@ impl XmlNode for GeneSource {
     }
 }
 
-// should go elsewhere
-impl XmlNode for RnaRef {
-    fn start_bytes() -> BytesStart<'static> {
-        BytesStart::new("Rna-ref")
-    }
-
-    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> {
-        Some(RnaRef::default()) // Placeholder: add real parsing logic here
-    }
-}
-
 impl XmlNode for Maps {
     fn start_bytes() -> BytesStart<'static> {
         BytesStart::new("Maps")
@@ -541,31 +1050,32 @@ impl XmlNode for GeneCommentary {
             rna: None,
         };
 
-        let forbidden_tags = [BytesStart::new("unknown-tag")];
-        let forbidden = UnexpectedTags(&forbidden_tags);
+        let forbidden = UnexpectedTags(&[]);
 
         loop {
-            match reader.read_event().unwrap() {
-                Event::Start(e) => match e.name().as_ref() {
-                    b"type" | b"Gene-commentary_type" => {
+            match next_event(reader)? {
+                Event::Start(e) => match qualified_name(e.name(), Self::start_bytes().name()).as_ref() {
+                    b"Gene-commentary_type" => {
                         if let Some(t) = read_gene_commentary_type(reader) {
                             commentary.r#type = t;
                         }
                     }
-                    b"heading" | b"Gene-commentary_heading" => commentary.heading = read_string(reader),
-                    b"label" | b"Gene-commentary_label" => commentary.label = read_string(reader),
-                    b"text" | b"Gene-commentary_text" => commentary.text = read_string(reader),
-                    b"accession" | b"Gene-commentary_accession" => commentary.accession = read_string(reader),
-                    b"version" | b"Gene-commentary_version" => commentary.version = Some(read_string(reader).unwrap().parse().unwrap()),
-                    b"xtra-properties" | b"Gene-commentary_xtra-properties" => commentary.xtra_properties = Some(read_vec_node(reader, e.to_end())) ,
-                    b"refs" | b"Gene-commentary_refs" => commentary.comment = Some(read_vec_node(reader, e.to_end())) ,
-                    b"seqs" | b"Gene-commentary_seqs" => commentary.seqs = Some(read_vec_node(reader, e.to_end())) ,
-                    b"source" | b"Gene-commentary_source" => commentary.source = Some(read_vec_node(reader, e.to_end())) ,
-                    b"genomic-coords" | b"Gene-commentary_genomic-coords" => commentary.genomic_coords = Some(read_vec_node(reader, e.to_end())) ,
+                    b"Gene-commentary_heading" => commentary.heading = read_string(reader),
+                    b"Gene-commentary_label" => commentary.label = read_string(reader),
+                    b"Gene-commentary_text" => commentary.text = read_string(reader),
+                    b"Gene-commentary_accession" => commentary.accession = read_string(reader),
+                    b"Gene-commentary_version" => commentary.version = Some(read_string(reader)?.parse().ok()?),
+                    b"Gene-commentary_xtra-properties" => commentary.xtra_properties = Some(read_vec_node(reader, e.to_end())) ,
+                    b"Gene-commentary_refs" => commentary.refs = Some(read_vec_node(reader, e.to_end())) ,
+                    b"Gene-commentary_seqs" => commentary.seqs = Some(read_vec_node(reader, e.to_end())) ,
+                    b"Gene-commentary_source" => commentary.source = Some(read_vec_node(reader, e.to_end())) ,
+                    b"Gene-commentary_genomic-coords" => commentary.genomic_coords = Some(read_vec_node(reader, e.to_end())) ,
                     b"Gene-commentary_products" => commentary.products = Some(read_vec_node(reader, e.to_end())) ,
+                    b"Gene-commentary_properties" => commentary.properties = Some(read_vec_node(reader, e.to_end())) ,
                     b"Gene-commentary_comment" => commentary.comment = Some(read_vec_node(reader, e.to_end())) ,
                     b"Gene-commentary_create-date" => commentary.create_date = read_node(reader) ,
                     b"Gene-commentary_update-date" => commentary.update_date = read_node(reader) ,
+                    b"Gene-commentary_rna" => commentary.rna = read_node(reader) ,
                     _ => forbidden.check(&e.name()),
                 },
                 Event::End(e) => {
@@ -683,16 +1193,6 @@ impl Default for GeneSource {
     }
 }
 
-impl Default for RnaRef {
-    fn default() -> Self {
-        RnaRef {
-            ext: None,                   // Optional, initialize as `None`
-            pseudo: Some(false),         // Default `false`
-            r#type: RnaRefType::Unknown, // Default enum variant
-        }
-    }
-}
-
 impl Default for Maps {
     fn default() -> Self {
         Maps {
@@ -712,7 +1212,7 @@ impl XmlNode for Vec<Entrezgene> {
         let forbidden = UnexpectedTags(&[]);
 
         loop {
-            match reader.read_event().unwrap() {
+            match next_event(reader)? {
                 Event::Start(e) => {
                     let name = e.name();
 
@@ -720,14 +1220,14 @@ impl XmlNode for Vec<Entrezgene> {
                         if let Some(entrezgene) = read_node::<Entrezgene>(reader) {
                             genes.push(entrezgene);
                         } else {
-                            println!("Skipping a failed <Entrezgene>");
+                            log::warn!("skipping <Entrezgene> ending at byte {}, which could not be parsed", reader.buffer_position());
                         }
                     } else {
                         forbidden.check(&name); // Check unexpected tags here
                     }
                 }
                 Event::End(e) if e.name() == Self::start_bytes().to_end().name() => {
-                    println!("Successfully finished parsing <Entrezgene-Set>");
+                    log::debug!("parsed {} genes of <Entrezgene-Set>", genes.len());
                     return Some(genes);
                 }
                 Event::Text(e) => {
@@ -738,12 +1238,12 @@ impl XmlNode for Vec<Entrezgene> {
                     let text = unescaped.trim();
 
                     if !text.is_empty() {
-                        println!("Unexpected text between nodes: '{}'", text);
+                        log::warn!("unexpected text in <Entrezgene-Set> at byte {}: '{}'", reader.buffer_position(), text);
                     }
                 }
 
                 Event::Eof => {
-                    println!("Unexpected EOF while parsing <Entrezgene-Set>");
+                    log::warn!("unexpected EOF while parsing <Entrezgene-Set>");
                     break;
                 }
                 _ => (), // Catch all other events
@@ -777,14 +1277,14 @@ impl XmlNode for GeneTrack {
         let mut track = GeneTrack::default();
 
         loop {
-            match reader.read_event().unwrap() {
-                Event::Start(e) => match e.name().as_ref() {
-                    b"geneid"|b"Gene-track_geneid" => track.geneid = read_string(reader).unwrap().parse().unwrap(),
-                    b"status"|b"Gene-track_status" => track.status = read_node(reader).unwrap(),
-                    b"current-id"|b"Gene-track_current-id" => track.current_id = Some(read_vec_node(reader, e.to_end())),
-                    b"create-date"|b"Gene-track_create-date" => track.create_date = read_node(reader).unwrap(),
-                    b"update-date"|b"Gene-track_update-date" => track.update_date = read_node(reader).unwrap(),
-                    b"discontinue-date"|b"Gene-track_discontinue-date" => track.discontinue_date = read_node(reader),
+            match next_event(reader)? {
+                Event::Start(e) => match qualified_name(e.name(), Self::start_bytes().name()).as_ref() {
+                    b"Gene-track_geneid" => track.geneid = read_string(reader)?.parse().ok()?,
+                    b"Gene-track_status" => track.status = read_node(reader)?,
+                    b"Gene-track_current-id" => track.current_id = Some(read_vec_node(reader, e.to_end())),
+                    b"Gene-track_create-date" => track.create_date = read_node(reader)?,
+                    b"Gene-track_update-date" => track.update_date = read_node(reader)?,
+                    b"Gene-track_discontinue-date" => track.discontinue_date = read_node(reader),
                     _ => (), // Ignore unknown tags
                 },
                 Event::End(e) => {
@@ -803,7 +1303,7 @@ impl XmlNode for GeneTrack {
 
 impl XmlNode for OtherSource {
     fn start_bytes() -> BytesStart<'static> {
-        BytesStart::new("OtherSource")
+        BytesStart::new("Other-source")
     }
 
     fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> {
@@ -811,13 +1311,13 @@ impl XmlNode for OtherSource {
         let forbidden = UnexpectedTags(&[]);
 
         loop {
-            match reader.read_event().unwrap() {
-                Event::Start(e) => match e.name().as_ref() {
-                    b"src" => source.src = read_node(reader),
-                    b"pre-text" => source.pre_text = read_string(reader),
-                    b"anchor" => source.anchor = read_string(reader),
-                    b"url" => source.url = read_string(reader),
-                    b"post-text" => source.post_text = read_string(reader),
+            match next_event(reader)? {
+                Event::Start(e) => match qualified_name(e.name(), Self::start_bytes().name()).as_ref() {
+                    b"Other-source_src" => source.src = read_node(reader),
+                    b"Other-source_pre-text" => source.pre_text = read_string(reader),
+                    b"Other-source_anchor" => source.anchor = read_string(reader),
+                    b"Other-source_url" => source.url = read_string(reader),
+                    b"Other-source_post-text" => source.post_text = read_string(reader),
                     _ => forbidden.check(&e.name()),
                 },
                 Event::End(e) => {
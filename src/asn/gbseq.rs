@@ -0,0 +1,1037 @@
+//! GenBank records as given by EFetch with `rettype=gb&retmode=xml`
+//!
+//! Adapted from ["gbseq.asn"](https://www.ncbi.nlm.nih.gov/IEB/ToolBox/CPP_DOC/lxr/source/src/objects/gbseq/gbseq.asn)
+//! from the NCBI C++ Toolkit
+//!
+//! A [`GBSeq`] mirrors the GenBank flat file: locations are given as text along with
+//! their intervals, and qualifiers are kept as name/value pairs. Only the fields
+//! needed to describe a sequence and its features are parsed; contigs, cross
+//! references and structured comments are skipped.
+//!
+//! Records are converted to and from [`BioSeq`], so that sequences fetched in either
+//! format can be handled alike:
+//!
+//! ```ignore
+//! let bioseq = BioSeq::from(&gbseq);
+//! let gbseq = GBSeq::from(&bioseq);
+//! ```
+//!
+//! Both conversions are best-effort. GenBank records do not distinguish the
+//! qualifiers of the `source` feature, so only the organism and its taxon are kept
+//! when converting to a [`BioSeq`]. In the other direction, descriptors without a
+//! GenBank counterpart (eg: user objects) are dropped.
+
+use crate::biblio::{AuthList, AuthListNames, CitGen};
+use crate::blast::parse_fasta_id;
+use crate::coords::{Pos1, Range0};
+use crate::general::{Date, DateStd, DbTag, FuzzLimit, IntFuzz, ObjectId};
+use crate::gff::{db_tag_label, seq_id_label};
+use crate::parsing::{next_event, qualified_name, read_bool_attribute, read_int, read_string, read_vec_node, read_vec_str_unchecked};
+use crate::parsing::{XmlNode, XmlVecNode};
+use crate::r#pub::{author_names, full_date, CitationStyle, Pub, MONTHS};
+use crate::seq::{BioSeq, Descriptors, Mol, PubDesc, Repr, SeqAnnot, SeqAnnotData, SeqData, SeqDesc, SeqInst, Strand, Topology};
+use crate::seqblock::GBBlock;
+use crate::seqfeat::{BioSource, GbQual, OrgName, OrgRef, SeqFeat};
+use crate::seqloc::{NaStrand, SeqId, SeqInterval, SeqLoc, SeqLocMix, SeqPoint, TextseqId};
+use crate::tbl::{apply_qualifier, feature_data, feature_key, qualifiers};
+use quick_xml::events::{BytesEnd, BytesStart, Event};
+use quick_xml::Reader;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+#[serde(rename_all = "kebab-case")]
+/// A GenBank record
+pub struct GBSeq {
+    /// eg: "NC_000913"
+    pub locus: Option<String>,
+    pub length: u64,
+    /// "single", "double" or "mixed"
+    pub strandedness: Option<String>,
+    /// eg: "DNA", "mRNA", "AA"
+    pub moltype: String,
+    /// "linear" or "circular"
+    pub topology: Option<String>,
+    /// eg: "BCT", "PRI"
+    pub division: String,
+    /// eg: "15-JUN-2023"
+    pub update_date: String,
+    pub create_date: Option<String>,
+    pub definition: String,
+    pub primary_accession: Option<String>,
+    /// eg: "NC_000913.3"
+    pub accession_version: Option<String>,
+    /// identifiers in FASTA style (eg: "ref|NC_000913.3|", "gi|556503834")
+    pub other_seqids: Option<Vec<String>>,
+    pub secondary_accessions: Option<Vec<String>>,
+    pub keywords: Option<Vec<String>>,
+    /// source line (eg: "Escherichia coli str. K-12 substr. MG1655")
+    pub source: Option<String>,
+    pub organism: Option<String>,
+    /// lineage, separated by "; "
+    pub taxonomy: Option<String>,
+    pub references: Option<Vec<GBReference>>,
+    pub comment: Option<String>,
+    pub feature_table: Option<Vec<GBFeature>>,
+    /// residues in lowercase
+    pub sequence: Option<String>,
+}
+
+pub type GBSet = Vec<GBSeq>;
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct GBReference {
+    /// number of the reference within the record
+    pub reference: String,
+    /// bases covered by the reference (eg: "1..4641652")
+    pub position: Option<String>,
+    /// eg: "Blattner,F.R."
+    pub authors: Option<Vec<String>>,
+    pub consortium: Option<String>,
+    pub title: Option<String>,
+    pub journal: String,
+    pub pubmed: Option<u64>,
+    pub remark: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct GBFeature {
+    /// eg: "gene", "CDS"
+    pub key: String,
+    /// location as in the flat file (eg: "complement(join(1..20,31..>60))")
+    pub location: String,
+    pub intervals: Option<Vec<GBInterval>>,
+    pub partial5: Option<bool>,
+    pub partial3: Option<bool>,
+    pub quals: Option<Vec<GBQualifier>>,
+}
+
+impl GBFeature {
+    /// Value of the first qualifier named `name`
+    ///
+    /// Qualifiers without a value (eg: "pseudo") give an empty string.
+    pub fn qualifier(&self, name: &str) -> Option<&str> {
+        self.quals
+            .iter()
+            .flatten()
+            .find(|qual| qual.name == name)
+            .map(|qual| qual.value.as_deref().unwrap_or_default())
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+#[serde(rename_all = "kebab-case")]
+/// Interval of a feature location
+///
+/// Positions start at 1, and `from` is greater than `to` on the complementary strand.
+pub struct GBInterval {
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+    pub point: Option<i64>,
+    /// on the complementary strand
+    pub iscomp: Option<bool>,
+    /// between two bases, given by `from` and `to`
+    pub interbp: Option<bool>,
+    pub accession: String,
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+pub struct GBQualifier {
+    pub name: String,
+    pub value: Option<String>,
+}
+
+impl XmlNode for GBQualifier {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("GBQualifier")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> {
+        let mut qual = Self::default();
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => match qualified_name(e.name(), Self::start_bytes().name()).as_ref() {
+                    b"GBQualifier_name" => qual.name = read_string(reader)?,
+                    b"GBQualifier_value" => qual.value = read_string(reader),
+                    _ => (),
+                },
+                Event::End(e) if Self::is_end(&e) => return qual.into(),
+                _ => (),
+            }
+        }
+    }
+}
+
+impl XmlVecNode for GBQualifier {}
+
+impl XmlNode for GBInterval {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("GBInterval")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> {
+        let mut interval = Self::default();
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => match qualified_name(e.name(), Self::start_bytes().name()).as_ref() {
+                    b"GBInterval_from" => interval.from = read_int(reader),
+                    b"GBInterval_to" => interval.to = read_int(reader),
+                    b"GBInterval_point" => interval.point = read_int(reader),
+                    b"GBInterval_accession" => interval.accession = read_string(reader)?,
+                    _ => (),
+                },
+                Event::Empty(e) => match qualified_name(e.name(), Self::start_bytes().name()).as_ref() {
+                    b"GBInterval_iscomp" => interval.iscomp = read_bool_attribute(&e),
+                    b"GBInterval_interbp" => interval.interbp = read_bool_attribute(&e),
+                    _ => (),
+                },
+                Event::End(e) if Self::is_end(&e) => return interval.into(),
+                _ => (),
+            }
+        }
+    }
+}
+
+impl XmlVecNode for GBInterval {}
+
+impl XmlNode for GBFeature {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("GBFeature")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> {
+        let mut feature = Self::default();
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => match qualified_name(e.name(), Self::start_bytes().name()).as_ref() {
+                    b"GBFeature_key" => feature.key = read_string(reader)?,
+                    b"GBFeature_location" => feature.location = read_string(reader)?,
+                    b"GBFeature_intervals" => feature.intervals = read_vec_node(reader, e.to_end()).into(),
+                    b"GBFeature_quals" => feature.quals = read_vec_node(reader, e.to_end()).into(),
+                    b"GBFeature" => (),
+                    _ => {
+                        reader.read_to_end(e.name()).ok()?;
+                    }
+                },
+                Event::Empty(e) => match qualified_name(e.name(), Self::start_bytes().name()).as_ref() {
+                    b"GBFeature_partial5" => feature.partial5 = read_bool_attribute(&e),
+                    b"GBFeature_partial3" => feature.partial3 = read_bool_attribute(&e),
+                    _ => (),
+                },
+                Event::End(e) if Self::is_end(&e) => return feature.into(),
+                _ => (),
+            }
+        }
+    }
+}
+
+impl XmlVecNode for GBFeature {}
+
+impl XmlNode for GBReference {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("GBReference")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> {
+        let mut reference = Self::default();
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => match qualified_name(e.name(), Self::start_bytes().name()).as_ref() {
+                    b"GBReference_reference" => reference.reference = read_string(reader)?,
+                    b"GBReference_position" => reference.position = read_string(reader),
+                    b"GBReference_authors" => {
+                        reference.authors = read_vec_str_unchecked(reader, &e.to_end()).into()
+                    }
+                    b"GBReference_consortium" => reference.consortium = read_string(reader),
+                    b"GBReference_title" => reference.title = read_string(reader),
+                    b"GBReference_journal" => reference.journal = read_string(reader)?,
+                    b"GBReference_pubmed" => reference.pubmed = read_int(reader),
+                    b"GBReference_remark" => reference.remark = read_string(reader),
+                    b"GBReference" => (),
+                    _ => {
+                        reader.read_to_end(e.name()).ok()?;
+                    }
+                },
+                Event::End(e) if Self::is_end(&e) => return reference.into(),
+                _ => (),
+            }
+        }
+    }
+}
+
+impl XmlVecNode for GBReference {}
+
+impl XmlNode for GBSeq {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("GBSeq")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> {
+        let mut seq = Self::default();
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => match qualified_name(e.name(), Self::start_bytes().name()).as_ref() {
+                    b"GBSeq_locus" => seq.locus = read_string(reader),
+                    b"GBSeq_length" => seq.length = read_int(reader)?,
+                    b"GBSeq_strandedness" => seq.strandedness = read_string(reader),
+                    b"GBSeq_moltype" => seq.moltype = read_string(reader)?,
+                    b"GBSeq_topology" => seq.topology = read_string(reader),
+                    b"GBSeq_division" => seq.division = read_string(reader)?,
+                    b"GBSeq_update-date" => seq.update_date = read_string(reader)?,
+                    b"GBSeq_create-date" => seq.create_date = read_string(reader),
+                    b"GBSeq_definition" => seq.definition = read_string(reader)?,
+                    b"GBSeq_primary-accession" => seq.primary_accession = read_string(reader),
+                    b"GBSeq_accession-version" => seq.accession_version = read_string(reader),
+                    b"GBSeq_other-seqids" => {
+                        seq.other_seqids = read_vec_str_unchecked(reader, &e.to_end()).into()
+                    }
+                    b"GBSeq_secondary-accessions" => {
+                        seq.secondary_accessions = read_vec_str_unchecked(reader, &e.to_end()).into()
+                    }
+                    b"GBSeq_keywords" => {
+                        seq.keywords = read_vec_str_unchecked(reader, &e.to_end()).into()
+                    }
+                    b"GBSeq_source" => seq.source = read_string(reader),
+                    b"GBSeq_organism" => seq.organism = read_string(reader),
+                    b"GBSeq_taxonomy" => seq.taxonomy = read_string(reader),
+                    b"GBSeq_references" => seq.references = read_vec_node(reader, e.to_end()).into(),
+                    b"GBSeq_comment" => seq.comment = read_string(reader),
+                    b"GBSeq_feature-table" => seq.feature_table = read_vec_node(reader, e.to_end()).into(),
+                    b"GBSeq_sequence" => seq.sequence = read_string(reader),
+                    b"GBSeq" => (),
+                    _ => {
+                        reader.read_to_end(e.name()).ok()?;
+                    }
+                },
+                Event::End(e) if Self::is_end(&e) => return seq.into(),
+                _ => (),
+            }
+        }
+    }
+}
+
+impl XmlVecNode for GBSeq {}
+
+/// Parse every [`GBSeq`] within a `GBSet`
+pub fn read_gb_set(reader: &mut Reader<&[u8]>) -> GBSet {
+    read_vec_node(reader, BytesEnd::new("GBSet"))
+}
+
+/// Parse a date as given by GenBank (eg: "15-JUN-2023"), keeping other text as is
+fn parse_date(text: &str) -> Date {
+    let parsed = || {
+        let mut fields = text.split('-');
+        let day = fields.next()?.parse().ok()?;
+        let month = fields.next()?;
+        let month = MONTHS.iter().position(|name| name.eq_ignore_ascii_case(month))? as u8 + 1;
+        let year = fields.next()?.parse().ok()?;
+        Some(DateStd {
+            year,
+            month: Some(month),
+            day: Some(day),
+            ..DateStd::default()
+        })
+    };
+    match parsed() {
+        Some(date) => Date::Std(date),
+        None => Date::Str(text.to_string()),
+    }
+}
+
+/// Accession, with an optional version, as a [`SeqId`] of the same kind as `like`
+///
+/// GenBank records give the accessions of related sequences (eg: "protein_id"
+/// qualifiers) without a database, which is assumed to be that of the record itself.
+fn accession_id(accession: &str, like: Option<&SeqId>) -> SeqId {
+    let (accession, version) = match accession.rsplit_once('.') {
+        Some((accession, version)) if version.parse::<u64>().is_ok() => (accession, version.parse().ok()),
+        _ => (accession, None),
+    };
+    let text = TextseqId {
+        accession: Some(accession.to_string()),
+        version,
+        ..TextseqId::default()
+    };
+    match like {
+        Some(SeqId::Other(_)) => SeqId::Other(text),
+        Some(SeqId::Embl(_)) => SeqId::Embl(text),
+        Some(SeqId::Ddbj(_)) => SeqId::Ddbj(text),
+        Some(SeqId::Swissprot(_)) => SeqId::Swissprot(text),
+        _ => SeqId::Genbank(text),
+    }
+}
+
+/// Identifier in FASTA style, as given by `GBSeq_other-seqids`
+fn fasta_id(id: &SeqId) -> Option<String> {
+    let tag = match id {
+        SeqId::Gi(gi) => return Some(format!("gi|{}", gi)),
+        SeqId::Local(ObjectId::Str(id)) => return Some(format!("lcl|{}", id)),
+        SeqId::Local(ObjectId::Id(id)) => return Some(format!("lcl|{}", id)),
+        SeqId::Other(_) => "ref",
+        SeqId::Genbank(_) => "gb",
+        SeqId::Embl(_) => "emb",
+        SeqId::Ddbj(_) => "dbj",
+        SeqId::Swissprot(_) => "sp",
+        _ => return None,
+    };
+    Some(format!("{}|{}|", tag, seq_id_label(id)))
+}
+
+impl GBSeq {
+    /// Identifiers of the record, the first one being its accession
+    fn ids(&self) -> Vec<SeqId> {
+        let mut ids: Vec<SeqId> = self
+            .other_seqids
+            .iter()
+            .flatten()
+            .map(|id| parse_fasta_id(id))
+            .collect();
+        // identifiers that could not be interpreted are kept as local ids
+        if !ids.iter().any(|id| matches!(id, SeqId::Genbank(_) | SeqId::Other(_) | SeqId::Embl(_) | SeqId::Ddbj(_) | SeqId::Swissprot(_))) {
+            let accession = self.accession_version.as_ref().or(self.primary_accession.as_ref());
+            if let Some(accession) = accession {
+                ids.insert(0, accession_id(accession, None));
+            }
+        }
+        ids.sort_by_key(|id| matches!(id, SeqId::Gi(_) | SeqId::Local(_)));
+        ids
+    }
+
+    fn mol(&self) -> Mol {
+        match self.moltype.as_str() {
+            "AA" => Mol::AA,
+            moltype if moltype.contains("RNA") => Mol::RNA,
+            moltype if moltype.contains("DNA") => Mol::DNA,
+            _ => Mol::NA,
+        }
+    }
+
+    fn inst(&self) -> SeqInst {
+        let mol = self.mol();
+        let seq_data = self.sequence.as_ref().map(|sequence| match mol {
+            Mol::AA => SeqData::Iaa(sequence.to_uppercase()),
+            _ => SeqData::Ina(sequence.to_uppercase()),
+        });
+        SeqInst {
+            repr: if seq_data.is_some() { Repr::Raw } else { Repr::Virtual },
+            mol,
+            length: Some(self.length),
+            topology: match self.topology.as_deref() {
+                Some("circular") => Topology::Circular,
+                _ => Topology::Linear,
+            },
+            strand: match self.strandedness.as_deref() {
+                Some("single") => Strand::SS,
+                Some("double") => Strand::DS,
+                Some("mixed") => Strand::Mixed,
+                _ => Strand::NotSet,
+            },
+            seq_data,
+            ..SeqInst::default()
+        }
+    }
+
+    /// Organism, with the taxon given by the `source` feature
+    fn bio_source(&self) -> Option<BioSource> {
+        let organism = self.organism.as_ref()?;
+        let db: Vec<DbTag> = self
+            .feature_table
+            .iter()
+            .flatten()
+            .filter(|feature| feature.key == "source")
+            .flat_map(|feature| feature.quals.iter().flatten())
+            .filter(|qual| qual.name == "db_xref")
+            .filter_map(|qual| {
+                let (db, tag) = qual.value.as_ref()?.split_once(':')?;
+                let tag = match tag.parse() {
+                    Ok(id) => ObjectId::Id(id),
+                    Err(_) => ObjectId::Str(tag.into()),
+                };
+                Some(DbTag { db: db.into(), tag })
+            })
+            .collect();
+        Some(BioSource {
+            org: OrgRef {
+                taxname: Some(organism.clone()),
+                db: (!db.is_empty()).then_some(db),
+                orgname: self.taxonomy.as_ref().map(|lineage| OrgName {
+                    lineage: Some(lineage.clone()),
+                    ..OrgName::default()
+                }),
+                ..OrgRef::default()
+            },
+            ..BioSource::default()
+        })
+    }
+
+    fn descriptors(&self) -> Vec<SeqDesc> {
+        let mut descr = vec![SeqDesc::Title(self.definition.clone())];
+        if let Some(source) = self.bio_source() {
+            descr.push(SeqDesc::Source(source));
+        }
+        descr.push(SeqDesc::Genbank(GBBlock {
+            extra_accessions: self.secondary_accessions.clone(),
+            source: self.source.clone(),
+            keywords: self.keywords.clone(),
+            origin: None,
+            date: None,
+            entry_date: None,
+            div: Some(self.division.clone()),
+            taxonomy: None,
+        }));
+        if let Some(comment) = &self.comment {
+            descr.push(SeqDesc::Comment(comment.clone()));
+        }
+        for reference in self.references.iter().flatten() {
+            descr.push(SeqDesc::Pub(reference.into()));
+        }
+        if let Some(date) = &self.create_date {
+            descr.push(SeqDesc::CreateDate(parse_date(date)));
+        }
+        descr.push(SeqDesc::UpdateDate(parse_date(&self.update_date)));
+        descr
+    }
+
+    /// Location of `feature`, on the sequence identified by `id` unless an interval
+    /// gives another accession
+    fn location(&self, feature: &GBFeature, id: &SeqId) -> SeqLoc {
+        let own_accession = |accession: &str| {
+            accession.is_empty()
+                || Some(accession) == self.accession_version.as_deref()
+                || Some(accession) == self.primary_accession.as_deref()
+        };
+        let mut locs: Vec<SeqLoc> = feature
+            .intervals
+            .iter()
+            .flatten()
+            .filter_map(|interval| {
+                let id = match own_accession(&interval.accession) {
+                    true => id.clone(),
+                    false => accession_id(&interval.accession, Some(id)),
+                };
+                let strand = match interval.iscomp {
+                    Some(true) => NaStrand::Minus,
+                    _ => NaStrand::Plus,
+                };
+                match (interval.from, interval.to, interval.point) {
+                    (Some(from), Some(to), _) => {
+                        let range = Range0::from_one_based(pos1(from.min(to))?, pos1(from.max(to))?)?;
+                        Some(SeqLoc::Int(SeqInterval {
+                            from: range.start().into(),
+                            to: range.end().into(),
+                            strand: Some(if from > to { NaStrand::Minus } else { strand }),
+                            id,
+                            fuzz_from: None,
+                            fuzz_to: None,
+                        }))
+                    }
+                    (_, _, Some(point)) => Some(SeqLoc::Pnt(SeqPoint {
+                        point: pos1(point)?.to_zero_based().into(),
+                        strand: Some(strand),
+                        id,
+                        fuzz: None,
+                    })),
+                    _ => None,
+                }
+            })
+            .collect();
+
+        // partial ends are marked on the first and last intervals, in biological order
+        if feature.partial5 == Some(true) {
+            if let Some(SeqLoc::Int(interval)) = locs.first_mut() {
+                match interval.strand {
+                    Some(NaStrand::Minus) => interval.fuzz_to = Some(IntFuzz::Lim(FuzzLimit::GT)),
+                    _ => interval.fuzz_from = Some(IntFuzz::Lim(FuzzLimit::LT)),
+                }
+            }
+        }
+        if feature.partial3 == Some(true) {
+            if let Some(SeqLoc::Int(interval)) = locs.last_mut() {
+                match interval.strand {
+                    Some(NaStrand::Minus) => interval.fuzz_from = Some(IntFuzz::Lim(FuzzLimit::LT)),
+                    _ => interval.fuzz_to = Some(IntFuzz::Lim(FuzzLimit::GT)),
+                }
+            }
+        }
+
+        match locs.len() {
+            0 => SeqLoc::Null,
+            1 => locs.remove(0),
+            _ => SeqLoc::Mix(SeqLocMix(locs)),
+        }
+    }
+
+    fn feature(&self, feature: &GBFeature, id: &SeqId) -> SeqFeat {
+        let mut feat = SeqFeat::new(feature_data(&feature.key));
+        feat.location = self.location(feature, id);
+        if feature.partial5 == Some(true) || feature.partial3 == Some(true) {
+            feat.partial = Some(true);
+        }
+        for qual in feature.quals.iter().flatten() {
+            let value = qual.value.as_deref().unwrap_or_default();
+            if qual.name == "protein_id" {
+                feat.product = Some(SeqLoc::Whole(accession_id(value, Some(id))));
+            } else if apply_qualifier(&mut feat, &qual.name, value).is_err() {
+                // keep values that could not be interpreted
+                feat.qual.get_or_insert_with(Vec::new).push(GbQual {
+                    qual: qual.name.as_str().into(),
+                    val: value.to_string(),
+                });
+            }
+        }
+        feat
+    }
+}
+
+impl From<&GBReference> for PubDesc {
+    fn from(reference: &GBReference) -> Self {
+        let mut names = reference.authors.clone().unwrap_or_default();
+        names.extend(reference.consortium.clone());
+        let mut equiv = vec![Pub::Gen(CitGen {
+            cit: Some(reference.journal.clone()),
+            authors: (!names.is_empty()).then_some(AuthList {
+                names: AuthListNames::Str(names),
+                affil: None,
+            }),
+            title: reference.title.clone(),
+            ..CitGen::default()
+        })];
+        if let Some(pmid) = reference.pubmed {
+            equiv.push(Pub::PmId(pmid));
+        }
+        PubDesc {
+            r#pub: equiv,
+            comment: reference.remark.clone(),
+            ..PubDesc::default()
+        }
+    }
+}
+
+impl From<&GBSeq> for BioSeq {
+    /// Features are located on the first identifier of the record, and the `source`
+    /// feature is only used to give the taxon of the organism.
+    fn from(gbseq: &GBSeq) -> Self {
+        let id = gbseq.ids();
+        let features: Vec<SeqFeat> = match id.first() {
+            Some(first) => gbseq
+                .feature_table
+                .iter()
+                .flatten()
+                .filter(|feature| feature.key != "source")
+                .map(|feature| gbseq.feature(feature, first))
+                .collect(),
+            None => Vec::new(),
+        };
+        BioSeq {
+            id,
+            descr: Some(gbseq.descriptors()),
+            inst: Some(gbseq.inst()),
+            annot: (!features.is_empty()).then(|| vec![SeqAnnot::new(SeqAnnotData::FTable(features))]),
+        }
+    }
+}
+
+impl From<GBSeq> for BioSeq {
+    fn from(gbseq: GBSeq) -> Self {
+        Self::from(&gbseq)
+    }
+}
+
+/// Position of a [`GBInterval`], which counts from 1
+fn pos1(pos: i64) -> Option<Pos1> {
+    Pos1::new(u64::try_from(pos).ok()?)
+}
+
+/// Components of a location with coordinates, in biological order
+fn components(loc: &SeqLoc, length: Option<u64>) -> Vec<SeqLoc> {
+    match loc {
+        SeqLoc::Whole(id) => match length.and_then(Range0::whole) {
+            Some(range) => vec![SeqLoc::Int(SeqInterval {
+                from: range.start().into(),
+                to: range.end().into(),
+                strand: None,
+                id: id.clone(),
+                fuzz_from: None,
+                fuzz_to: None,
+            })],
+            None => Vec::new(),
+        },
+        SeqLoc::Int(_) | SeqLoc::Pnt(_) => vec![loc.clone()],
+        SeqLoc::PackedInt(intervals) => intervals.iter().cloned().map(SeqLoc::Int).collect(),
+        SeqLoc::Mix(mix) => mix.0.iter().flat_map(|loc| components(loc, length)).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn is_minus(loc: &SeqLoc) -> bool {
+    loc.strand() == Some(NaStrand::Minus)
+}
+
+/// Span of a single component, regardless of strand (eg: "<1..20")
+fn span(loc: &SeqLoc) -> String {
+    match loc {
+        SeqLoc::Int(interval) => {
+            let mark = |fuzz: &Option<IntFuzz>, mark: &'static str| match fuzz {
+                Some(fuzz) if fuzz.is_partial_limit() => mark,
+                _ => "",
+            };
+            match interval.range0() {
+                Some(range) => format!(
+                    "{}{}..{}{}",
+                    mark(&interval.fuzz_from, "<"),
+                    range.start1(),
+                    mark(&interval.fuzz_to, ">"),
+                    range.end1()
+                ),
+                None => String::new(),
+            }
+        }
+        SeqLoc::Pnt(point) => point.pos0().map(|pos| pos.to_one_based().to_string()).unwrap_or_default(),
+        _ => String::new(),
+    }
+}
+
+fn join(spans: Vec<String>) -> String {
+    match spans.len() {
+        1 => spans.into_iter().next().unwrap_or_default(),
+        _ => format!("join({})", spans.join(",")),
+    }
+}
+
+/// Location as written in a GenBank flat file
+fn location_string(components: &[SeqLoc]) -> String {
+    if components.iter().all(is_minus) {
+        let spans = components.iter().rev().map(span).collect();
+        format!("complement({})", join(spans))
+    } else {
+        let spans = components
+            .iter()
+            .map(|loc| match is_minus(loc) {
+                true => format!("complement({})", span(loc)),
+                false => span(loc),
+            })
+            .collect();
+        join(spans)
+    }
+}
+
+fn gb_interval(loc: &SeqLoc) -> Option<GBInterval> {
+    match loc {
+        SeqLoc::Int(interval) => {
+            let minus = interval.strand == Some(NaStrand::Minus);
+            let range = interval.range0()?;
+            let (from, to) = match minus {
+                true => (range.end1(), range.start1()),
+                false => (range.start1(), range.end1()),
+            };
+            Some(GBInterval {
+                from: Some(from.into()),
+                to: Some(to.into()),
+                iscomp: minus.then_some(true),
+                accession: seq_id_label(&interval.id),
+                ..GBInterval::default()
+            })
+        }
+        SeqLoc::Pnt(point) => Some(GBInterval {
+            point: Some(point.pos0()?.to_one_based().into()),
+            iscomp: (point.strand == Some(NaStrand::Minus)).then_some(true),
+            accession: seq_id_label(&point.id),
+            ..GBInterval::default()
+        }),
+        _ => None,
+    }
+}
+
+fn gb_feature(feat: &SeqFeat, length: Option<u64>) -> Option<GBFeature> {
+    let components = components(&feat.location, length);
+    if components.is_empty() {
+        return None;
+    }
+    let quals = qualifiers(feat)
+        .into_iter()
+        .map(|(name, value)| GBQualifier {
+            name: name.to_string(),
+            value: (!value.is_empty()).then_some(value),
+        })
+        .collect::<Vec<_>>();
+    Some(GBFeature {
+        key: feature_key(feat),
+        location: location_string(&components),
+        intervals: components.iter().map(gb_interval).collect(),
+        partial5: feat.location.is_partial_5prime().then_some(true),
+        partial3: feat.location.is_partial_3prime().then_some(true),
+        quals: (!quals.is_empty()).then_some(quals),
+    })
+}
+
+/// `source` feature covering `whole`, the range of the whole sequence
+fn source_feature(source: &BioSource, accession: &str, whole: Range0) -> GBFeature {
+    let mut quals = Vec::new();
+    if let Some(taxname) = &source.org.taxname {
+        quals.push(GBQualifier {
+            name: "organism".to_string(),
+            value: Some(taxname.clone()),
+        });
+    }
+    for tag in source.org.db.iter().flatten() {
+        quals.push(GBQualifier {
+            name: "db_xref".to_string(),
+            value: Some(db_tag_label(tag)),
+        });
+    }
+    GBFeature {
+        key: "source".to_string(),
+        location: format!("{}..{}", whole.start1(), whole.end1()),
+        intervals: Some(vec![GBInterval {
+            from: Some(whole.start1().into()),
+            to: Some(whole.end1().into()),
+            accession: accession.to_string(),
+            ..GBInterval::default()
+        }]),
+        partial5: None,
+        partial3: None,
+        quals: Some(quals),
+    }
+}
+
+fn gb_reference(number: usize, desc: &PubDesc) -> GBReference {
+    let mut reference = GBReference {
+        reference: number.to_string(),
+        remark: desc.comment.clone(),
+        ..GBReference::default()
+    };
+    for r#pub in desc.r#pub.iter() {
+        match r#pub {
+            Pub::Gen(cit) => {
+                reference.authors = cit.authors.as_ref().map(author_names);
+                reference.title = cit.title.clone();
+                reference.journal = cit.cit.clone().unwrap_or_default();
+                reference.pubmed = reference.pubmed.or(cit.pmid);
+            }
+            Pub::PmId(pmid) => reference.pubmed = Some(*pmid),
+            _ => (),
+        }
+    }
+    if reference.journal.is_empty() {
+        reference.journal = Pub::Equiv(desc.r#pub.clone()).to_citation_string(CitationStyle::Full);
+    }
+    reference
+}
+
+impl From<&BioSeq> for GBSeq {
+    /// Only descriptors given on `bioseq` itself are used, so that those inherited from
+    /// an enclosing set should be copied beforehand.
+    fn from(bioseq: &BioSeq) -> Self {
+        let inst = bioseq.inst.as_ref();
+        let residues = inst.and_then(|inst| inst.residues().ok());
+        let length = inst
+            .and_then(|inst| inst.length)
+            .or(residues.as_ref().map(|residues| residues.len() as u64));
+        let accession = bioseq.id.iter().find(|id| fasta_id(id).is_some() && !matches!(id, SeqId::Gi(_) | SeqId::Local(_)));
+        let accession_version = accession.map(seq_id_label);
+        let genbank = bioseq.descr.iter().flatten().find_map(|desc| match desc {
+            SeqDesc::Genbank(block) => Some(block),
+            _ => None,
+        });
+
+        let mut feature_table = Vec::new();
+        if let (Some(source), Some(whole)) = (bioseq.source(), length.and_then(Range0::whole)) {
+            let accession = accession_version.clone().unwrap_or_default();
+            feature_table.push(source_feature(source, &accession, whole));
+        }
+        feature_table.extend(
+            bioseq
+                .annot
+                .iter()
+                .flatten()
+                .flat_map(|annot| annot.features())
+                .filter_map(|feat| gb_feature(feat, length)),
+        );
+        let references: Vec<GBReference> = bioseq
+            .descr
+            .iter()
+            .flatten()
+            .filter_map(|desc| match desc {
+                SeqDesc::Pub(desc) => Some(desc),
+                _ => None,
+            })
+            .enumerate()
+            .map(|(i, desc)| gb_reference(i + 1, desc))
+            .collect();
+        let organism = bioseq.organism();
+
+        GBSeq {
+            locus: accession.and_then(|id| match id {
+                SeqId::Other(text) | SeqId::Genbank(text) | SeqId::Embl(text) | SeqId::Ddbj(text) | SeqId::Swissprot(text) => {
+                    text.name.clone().or(text.accession.clone())
+                }
+                _ => None,
+            }),
+            length: length.unwrap_or_default(),
+            strandedness: inst.and_then(|inst| match inst.strand {
+                Strand::SS => Some("single".to_string()),
+                Strand::DS => Some("double".to_string()),
+                Strand::Mixed => Some("mixed".to_string()),
+                _ => None,
+            }),
+            moltype: match inst.map(|inst| &inst.mol) {
+                Some(Mol::AA) => "AA",
+                Some(Mol::RNA) => "RNA",
+                Some(Mol::DNA) => "DNA",
+                _ => "NA",
+            }
+            .to_string(),
+            topology: inst.map(|inst| match inst.topology {
+                Topology::Circular => "circular".to_string(),
+                _ => "linear".to_string(),
+            }),
+            division: genbank.and_then(|block| block.div.clone()).unwrap_or_default(),
+            update_date: bioseq.update_date().map(full_date).unwrap_or_default(),
+            create_date: bioseq.create_date().map(full_date),
+            definition: bioseq.title().unwrap_or_default().to_string(),
+            primary_accession: accession.and_then(|id| match id {
+                SeqId::Other(text) | SeqId::Genbank(text) | SeqId::Embl(text) | SeqId::Ddbj(text) | SeqId::Swissprot(text) => {
+                    text.accession.clone()
+                }
+                _ => None,
+            }),
+            accession_version,
+            other_seqids: Some(bioseq.id.iter().filter_map(fasta_id).collect()),
+            secondary_accessions: genbank.and_then(|block| block.extra_accessions.clone()),
+            keywords: genbank.and_then(|block| block.keywords.clone()),
+            source: genbank.and_then(|block| block.source.clone()),
+            organism: organism.and_then(|org| org.taxname.clone()),
+            taxonomy: organism.and_then(|org| org.orgname.as_ref()?.lineage.clone()),
+            references: (!references.is_empty()).then_some(references),
+            comment: bioseq.descr.iter().flatten().find_map(|desc| match desc {
+                SeqDesc::Comment(comment) => Some(comment.clone()),
+                _ => None,
+            }),
+            feature_table: (!feature_table.is_empty()).then_some(feature_table),
+            sequence: residues.map(|residues| residues.to_lowercase()),
+        }
+    }
+}
+
+impl From<BioSeq> for GBSeq {
+    fn from(bioseq: BioSeq) -> Self {
+        Self::from(&bioseq)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::gbseq::{gb_interval, location_string, parse_date, GBFeature, GBInterval, GBSeq};
+    use crate::general::{Date, FuzzLimit, IntFuzz};
+    use crate::parsing::parse_node;
+    use crate::seqloc::{NaStrand, SeqId, SeqInterval, SeqLoc};
+
+    #[test]
+    fn test_parse_date() {
+        match parse_date("15-JUN-2023") {
+            Date::Std(date) => {
+                assert_eq!(date.year, 2023);
+                assert_eq!(date.month, Some(6));
+                assert_eq!(date.day, Some(15));
+            }
+            date => panic!("unexpected date {:?}", date),
+        }
+        assert_eq!(parse_date("2023"), Date::Str("2023".to_string()));
+    }
+
+    #[test]
+    fn test_location_string() {
+        let interval = |from, to, strand| {
+            SeqLoc::Int(SeqInterval {
+                from,
+                to,
+                strand: Some(strand),
+                id: SeqId::Gi(1),
+                fuzz_from: None,
+                fuzz_to: None,
+            })
+        };
+        let minus = [interval(30, 59, NaStrand::Minus), interval(0, 19, NaStrand::Minus)];
+        assert_eq!(location_string(&minus), "complement(join(1..20,31..60))");
+
+        let mut partial = interval(0, 19, NaStrand::Plus);
+        if let SeqLoc::Int(interval) = &mut partial {
+            interval.fuzz_from = Some(IntFuzz::Lim(FuzzLimit::LT));
+        }
+        let mixed = [partial, interval(30, 59, NaStrand::Minus)];
+        assert_eq!(location_string(&mixed), "join(<1..20,complement(31..60))");
+    }
+
+    #[test]
+    fn test_interval_positions() {
+        let feature = |intervals: Vec<GBInterval>| GBFeature {
+            intervals: Some(intervals),
+            ..GBFeature::default()
+        };
+        let gbseq = GBSeq::default();
+        let id = SeqId::Gi(1);
+
+        // positions count from 1, and `from` is after `to` on the minus strand
+        let minus = GBInterval { from: Some(45), to: Some(10), ..GBInterval::default() };
+        let loc = gbseq.location(&feature(vec![minus]), &id);
+        match &loc {
+            SeqLoc::Int(interval) => {
+                assert_eq!((interval.from, interval.to), (9, 44));
+                assert_eq!(interval.strand, Some(NaStrand::Minus));
+            }
+            loc => panic!("unexpected location {:?}", loc),
+        }
+        let written = gb_interval(&loc).unwrap();
+        assert_eq!((written.from, written.to, written.iscomp), (Some(45), Some(10), Some(true)));
+        let point = GBInterval { point: Some(1), ..GBInterval::default() };
+        assert!(matches!(gbseq.location(&feature(vec![point]), &id), SeqLoc::Pnt(point) if point.point == 0));
+
+        // positions which are not 1-based are left out
+        let zero = GBInterval { from: Some(0), to: Some(10), ..GBInterval::default() };
+        let negative = GBInterval { point: Some(-3), ..GBInterval::default() };
+        assert_eq!(gbseq.location(&feature(vec![zero, negative]), &id), SeqLoc::Null);
+    }
+
+    #[test]
+    fn test_feature() {
+        let xml = r#"
+<GBFeature>
+  <GBFeature_key>CDS</GBFeature_key>
+  <GBFeature_location>complement(&lt;10..45)</GBFeature_location>
+  <GBFeature_intervals>
+    <GBInterval>
+      <GBInterval_from>45</GBInterval_from>
+      <GBInterval_to>10</GBInterval_to>
+      <GBInterval_iscomp value="true"/>
+      <GBInterval_accession>AB000001.2</GBInterval_accession>
+    </GBInterval>
+  </GBFeature_intervals>
+  <GBFeature_partial3 value="true"/>
+  <GBFeature_quals>
+    <GBQualifier>
+      <GBQualifier_name>pseudo</GBQualifier_name>
+    </GBQualifier>
+    <GBQualifier>
+      <GBQualifier_name>note</GBQualifier_name>
+      <GBQualifier_value>truncated</GBQualifier_value>
+    </GBQualifier>
+  </GBFeature_quals>
+</GBFeature>"#;
+        let feature = parse_node::<GBFeature>(xml).unwrap();
+        assert_eq!(feature.key, "CDS");
+        assert_eq!(feature.location, "complement(<10..45)");
+        assert_eq!(feature.partial3, Some(true));
+        assert_eq!(feature.partial5, None);
+
+        let interval = &feature.intervals.as_ref().unwrap()[0];
+        assert_eq!((interval.from, interval.to, interval.iscomp), (Some(45), Some(10), Some(true)));
+        assert_eq!(feature.qualifier("pseudo"), Some(""));
+        assert_eq!(feature.qualifier("note"), Some("truncated"));
+    }
+}
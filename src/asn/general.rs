@@ -2,13 +2,18 @@
 //!
 //! As per [general.asn](https://www.ncbi.nlm.nih.gov/IEB/ToolBox/CPP_DOC/asn_spec/general.asn.html)
 
-use crate::parsing::{read_vec_node, read_int, read_node, read_real, read_string, read_vec_int_unchecked, read_vec_str_unchecked, UnexpectedTags};
-use crate::parsing::{XmlNode, XmlVecNode};
+use crate::intern::SharedStr;
+use crate::user::{decode_user_object, decodes_user_objects, DecodedUserObject};
+use crate::parsing::{attribute_value, next_event, read_attributes, read_bool, read_bool_attribute, read_vec_node, read_int, read_node, read_node_borrowed, read_real, read_str, read_string, read_vec_int_unchecked, read_vec_str_unchecked, UnexpectedTags};
+use crate::parsing::{TagMatch, XmlNode, XmlNodeRef, XmlValue, XmlVecNode};
+use quick_xml::events::attributes::Attributes;
 use quick_xml::events::{BytesStart, Event};
 use quick_xml::Reader;
 use serde::{Deserialize, Serialize};
+use std::any::Any;
 
-#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "lowercase")]
 /// Model precise timestamp or an un-parsed string
 ///
@@ -22,6 +27,7 @@ pub enum Date {
 }
 
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "lowercase")]
 pub struct DateUStd {
     #[serde(rename = "Date-std")]
@@ -49,18 +55,22 @@ impl XmlNode for Date {
         // variants
         let std_element = BytesStart::new("Date-std");
         let std_u_element = BytesStart::new("Date_std");
+        let str_element = BytesStart::new("Date_str");
 
         loop {
-            match reader.read_event().unwrap() {
+            match next_event(reader)? {
                 Event::Start(e) => {
                     //println!("{:?}", e.name());
                     let name = e.name();
 
-                    if name == std_element.name() {
-                        return Date::Date(read_node(reader).unwrap()).into();
+                    if name.is_tag(std_element.name()) {
+                        return Date::Date(read_node(reader)?).into();
+                    }
+                    if name.is_tag(std_u_element.name()) {
+                        return Date::Date(read_node(reader)?).into();
                     }
-                    if name == std_u_element.name() {
-                        return Date::Date(read_node(reader).unwrap()).into();
+                    if name.is_tag(str_element.name()) {
+                        return Date::Str(read_string(reader)?).into();
                     }
                 }
                 Event::End(e) => {
@@ -74,7 +84,8 @@ impl XmlNode for Date {
     }
 }
 
-#[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 /// NOTE: this is NOT a unix tm struct
 pub struct DateStd {
     /// full year
@@ -116,23 +127,23 @@ impl XmlNode for DateStd {
         let forbidden = UnexpectedTags(&[]);
 
         loop {
-            match reader.read_event().unwrap() {
+            match next_event(reader)? {
                 Event::Start(e) => {
                     let name = e.name();
 
-                    if name == year_element.name() {
-                        date.year = read_int(reader).unwrap();
-                    } else if name == month_element.name() {
+                    if name.is_tag(year_element.name()) {
+                        date.year = read_int(reader)?;
+                    } else if name.is_tag(month_element.name()) {
                         date.month = read_int(reader);
-                    } else if name == day_element.name() {
+                    } else if name.is_tag(day_element.name()) {
                         date.day = read_int(reader);
-                    } else if name == season_element.name() {
+                    } else if name.is_tag(season_element.name()) {
                         date.season = read_string(reader);
-                    } else if name == hour_element.name() {
+                    } else if name.is_tag(hour_element.name()) {
                         date.hour = read_int(reader);
-                    } else if name == minute_element.name() {
+                    } else if name.is_tag(minute_element.name()) {
                         date.minute = read_int(reader);
-                    } else if name == second_element.name() {
+                    } else if name.is_tag(second_element.name()) {
                         date.second = read_int(reader);
                     } else if name != Self::start_bytes().name() {
                         forbidden.check(&name);
@@ -149,17 +160,28 @@ impl XmlNode for DateStd {
     }
 }
 
-#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 /// Can tag or name anything
 pub enum ObjectId {
     Id(u64),
-    Str(String),
+    Str(SharedStr),
 }
 
 /// explicitly implemented because a default is not in original spec
 impl Default for ObjectId {
     fn default() -> Self {
-        Self::Str(String::default())
+        Self::Str(SharedStr::default())
+    }
+}
+
+impl ObjectId {
+    /// Return inner string if variant is [`ObjectId::Str`]
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::Str(s) => Some(s.as_str()),
+            Self::Id(_) => None,
+        }
     }
 }
 
@@ -174,23 +196,60 @@ impl XmlNode for ObjectId {
         let str_element = BytesStart::new("Object-id_str");
 
         loop {
-            if let Event::Start(e) = reader.read_event().unwrap() {
-                if e.name() == id_element.name() {
-                    return ObjectId::Id(read_int(reader).unwrap()).into();
+            if let Event::Start(e) = next_event(reader)? {
+                if e.name().is_tag(id_element.name()) {
+                    return ObjectId::Id(read_int(reader)?).into();
                 }
-                if e.name() == str_element.name() {
-                    return ObjectId::Str(read_string(reader).unwrap()).into();
+                if e.name().is_tag(str_element.name()) {
+                    return ObjectId::Str(read_string(reader)?.into()).into();
                 }
             }
         }
     }
 }
+impl XmlVecNode for ObjectId {}
 
-#[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+/// Borrowed view of an [`ObjectId`]
+pub enum ObjectIdRef<'a> {
+    Id(u64),
+    Str(&'a str),
+}
+
+impl<'a> XmlNodeRef<'a> for ObjectIdRef<'a> {
+    type Owned = ObjectId;
+
+    fn from_reader_borrowed(reader: &mut Reader<&'a [u8]>) -> Option<Self> {
+        let id_element = BytesStart::new("Object-id_id");
+        let str_element = BytesStart::new("Object-id_str");
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) if e.name().is_tag(id_element.name()) => {
+                    return ObjectIdRef::Id(read_int(reader)?).into()
+                }
+                Event::Start(e) if e.name().is_tag(str_element.name()) => {
+                    return ObjectIdRef::Str(read_str(reader)?).into()
+                }
+                _ => (),
+            }
+        }
+    }
+
+    fn to_owned_node(&self) -> ObjectId {
+        match self {
+            Self::Id(id) => ObjectId::Id(*id),
+            Self::Str(text) => ObjectId::Str((*text).into()),
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 /// Generalized for tagging
 pub struct DbTag {
     /// name of database or system
-    pub db: String,
+    pub db: SharedStr,
     /// appropriate tag
     pub tag: ObjectId,
 }
@@ -206,14 +265,14 @@ impl XmlNode for DbTag {
         let tag_element = BytesStart::new("Dbtag_tag");
 
         loop {
-            match reader.read_event().unwrap() {
+            match next_event(reader)? {
                 Event::Start(e) => {
                     let name = e.name();
 
-                    if name == db_element.name() {
-                        tag.db = read_string(reader).unwrap();
-                    } else if name == tag_element.name() {
-                        tag.tag = read_node(reader).unwrap();
+                    if name.is_tag(db_element.name()) {
+                        tag.db = read_string(reader)?.into();
+                    } else if name.is_tag(tag_element.name()) {
+                        tag.tag = read_node(reader)?;
                     }
                 }
                 Event::End(e) => {
@@ -228,7 +287,103 @@ impl XmlNode for DbTag {
 }
 impl XmlVecNode for DbTag {}
 
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+/// Borrowed view of a [`DbTag`]
+pub struct DbTagRef<'a> {
+    pub db: &'a str,
+    pub tag: ObjectIdRef<'a>,
+}
+
+impl<'a> XmlNodeRef<'a> for DbTagRef<'a> {
+    type Owned = DbTag;
+
+    fn from_reader_borrowed(reader: &mut Reader<&'a [u8]>) -> Option<Self> {
+        let db_element = BytesStart::new("Dbtag_db");
+        let tag_element = BytesStart::new("Dbtag_tag");
+
+        let mut db = None;
+        let mut tag = None;
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => {
+                    let name = e.name();
+
+                    if name.is_tag(db_element.name()) {
+                        db = read_str(reader);
+                    } else if name.is_tag(tag_element.name()) {
+                        tag = read_node_borrowed(reader);
+                    }
+                }
+                Event::End(e) if DbTag::is_end(&e) => return Some(Self { db: db?, tag: tag? }),
+                _ => (),
+            }
+        }
+    }
+
+    fn to_owned_node(&self) -> DbTag {
+        DbTag {
+            db: self.db.into(),
+            tag: self.tag.to_owned_node(),
+        }
+    }
+}
+
+impl DbTag {
+    /// Interpret tag as a typed identifier for well-known databases
+    pub fn typed(&self) -> DbXref {
+        DbXref::from(self)
+    }
+
+    /// Numeric value of [`Self::tag`]
+    ///
+    /// String tags are accepted if they are numeric, optionally prefixed by the
+    /// database name (eg: "HGNC:5")
+    fn numeric_tag(&self) -> Option<u64> {
+        match &self.tag {
+            ObjectId::Id(id) => Some(*id),
+            ObjectId::Str(s) => {
+                let s = s.trim();
+                let s = s
+                    .strip_prefix(self.db.as_str())
+                    .map(|s| s.trim_start_matches(':'))
+                    .unwrap_or(s);
+                s.parse().ok()
+            }
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+/// Typed identifiers for commonly referenced databases in [`DbTag`]
+pub enum DbXref {
+    /// NCBI Gene
+    GeneId(u64),
+    /// HUGO Gene Nomenclature Committee
+    Hgnc(u64),
+    /// Online Mendelian Inheritance in Man
+    Mim(u64),
+    /// NCBI Taxonomy
+    Taxon(u64),
+    /// any other database, or a tag that could not be interpreted
+    Other(DbTag),
+}
+
+impl From<&DbTag> for DbXref {
+    fn from(tag: &DbTag) -> Self {
+        let id = tag.numeric_tag();
+        match (tag.db.as_str(), id) {
+            ("GeneID", Some(id)) => Self::GeneId(id),
+            ("HGNC", Some(id)) => Self::Hgnc(id),
+            ("MIM", Some(id)) => Self::Mim(id),
+            ("taxon", Some(id)) => Self::Taxon(id),
+            _ => Self::Other(tag.clone()),
+        }
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "lowercase")]
 /// define a std element for people
 pub enum PersonId {
@@ -260,15 +415,27 @@ impl XmlNode for PersonId {
         Self: Sized,
     {
         // variants
+        let dbtag_element = BytesStart::new("Person-id_dbtag");
         let name_element = BytesStart::new("Person-id_name");
+        let ml_element = BytesStart::new("Person-id_ml");
+        let str_element = BytesStart::new("Person-id_str");
+        let consortium_element = BytesStart::new("Person-id_consortium");
 
         loop {
-            match reader.read_event().unwrap() {
+            match next_event(reader)? {
                 Event::Start(e) => {
                     let name = e.name();
 
-                    if name == name_element.name() {
-                        return PersonId::Name(read_node(reader).unwrap()).into();
+                    if name.is_tag(dbtag_element.name()) {
+                        return PersonId::DbTag(read_node(reader)?).into();
+                    } else if name.is_tag(name_element.name()) {
+                        return PersonId::Name(read_node(reader)?).into();
+                    } else if name.is_tag(ml_element.name()) {
+                        return PersonId::ML(read_string(reader)?).into();
+                    } else if name.is_tag(str_element.name()) {
+                        return PersonId::Str(read_string(reader)?).into();
+                    } else if name.is_tag(consortium_element.name()) {
+                        return PersonId::Consortium(read_string(reader)?).into();
                     }
                 }
                 Event::End(e) => {
@@ -283,6 +450,7 @@ impl XmlNode for PersonId {
 }
 
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 /// structured names
 pub struct NameStd {
     pub last: String,
@@ -321,15 +489,15 @@ impl XmlNode for NameStd {
         let forbidden = UnexpectedTags(&[]);
 
         loop {
-            match reader.read_event().unwrap() {
+            match next_event(reader)? {
                 Event::Start(e) => {
                     let name = e.name();
 
-                    if name == last_element.name() {
-                        name_std.last = read_string(reader).unwrap();
-                    } else if name == first_element.name() {
+                    if name.is_tag(last_element.name()) {
+                        name_std.last = read_string(reader)?;
+                    } else if name.is_tag(first_element.name()) {
                         name_std.first = read_string(reader);
-                    } else if name == initials_element.name() {
+                    } else if name.is_tag(initials_element.name()) {
                         name_std.initials = read_string(reader);
                     } else if name != Self::start_bytes().name() {
                         forbidden.check(&name);
@@ -347,12 +515,14 @@ impl XmlNode for NameStd {
 }
 
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Range {
     pub max: i64,
     pub min: i64,
 }
 
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "lowercase")]
 pub enum FuzzLimit {
     /// unknown
@@ -378,6 +548,7 @@ pub enum FuzzLimit {
 }
 
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "lowercase")]
 /// Communicate uncertainties in integer values
 pub enum IntFuzz {
@@ -391,7 +562,115 @@ pub enum IntFuzz {
     Alt(Vec<i64>),
 }
 
+impl XmlNode for Range {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("Int-fuzz_range")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let mut max = None;
+        let mut min = None;
+
+        // elements
+        let max_element = BytesStart::new("Int-fuzz_range_max");
+        let min_element = BytesStart::new("Int-fuzz_range_min");
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => {
+                    let name = e.name();
+
+                    if name.is_tag(max_element.name()) {
+                        max = read_int(reader);
+                    } else if name.is_tag(min_element.name()) {
+                        min = read_int(reader);
+                    }
+                }
+                Event::End(e) if Self::is_end(&e) => {
+                    return Self {
+                        max: max?,
+                        min: min?,
+                    }
+                    .into();
+                }
+                _ => (),
+            }
+        }
+    }
+}
+
+impl XmlValue for FuzzLimit {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("Int-fuzz_lim")
+    }
+
+    fn from_attributes(attributes: Attributes) -> Option<Self> {
+        match attribute_value(attributes)?.as_str() {
+            "unk" => Self::Unk.into(),
+            "gt" => Self::GT.into(),
+            "lt" => Self::LT.into(),
+            "tr" => Self::TR.into(),
+            "tl" => Self::TL.into(),
+            "circle" => Self::Circle.into(),
+            "other" => Self::Other.into(),
+            _ => None,
+        }
+    }
+}
+
+impl IntFuzz {
+    /// Whether the value is a limit extending beyond the given position, as for the
+    /// ends of partial features
+    pub fn is_partial_limit(&self) -> bool {
+        matches!(self, Self::Lim(FuzzLimit::LT | FuzzLimit::GT))
+    }
+}
+
+impl XmlNode for IntFuzz {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("Int-fuzz")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        // variants
+        let pm_element = BytesStart::new("Int-fuzz_p-m");
+        let range_element = Range::start_bytes();
+        let pct_element = BytesStart::new("Int-fuzz_pct");
+        let alt_element = BytesStart::new("Int-fuzz_alt");
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => {
+                    let name = e.name();
+
+                    if name.is_tag(pm_element.name()) {
+                        return Self::PM(read_int(reader)?).into();
+                    } else if name.is_tag(range_element.name()) {
+                        return Self::Range(Range::from_reader(reader)?).into();
+                    } else if name.is_tag(pct_element.name()) {
+                        return Self::Pct(read_int(reader)?).into();
+                    } else if name.is_tag(alt_element.name()) {
+                        return Self::Alt(read_vec_int_unchecked(reader, &e.to_end())).into();
+                    }
+                }
+                Event::Empty(e) if e.name().is_tag(FuzzLimit::start_bytes().name()) => {
+                    return Self::Lim(read_attributes(&e)?).into();
+                }
+                Event::End(e) if Self::is_end(&e) => return None,
+                _ => (),
+            }
+        }
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 /// a general object for a user defined structured data item
 ///
 /// used by [`SeqFeat`] and [`SeqDescr`]
@@ -405,6 +684,60 @@ pub struct UserObject {
 
     /// the object itself
     pub data: Vec<UserField>,
+
+    #[serde(skip)]
+    #[cfg_attr(feature = "arbitrary", arbitrary(default))]
+    /// typed value of the object, when its type is registered with
+    /// [`register_user_object()`](crate::user::register_user_object)
+    pub decoded: Option<DecodedUserObject>,
+}
+
+impl UserObject {
+    /// Whether [`Self::r#type`] is the string `name`
+    pub fn is_type(&self, name: &str) -> bool {
+        self.r#type.as_str() == Some(name)
+    }
+
+    /// Value decoded as `T` by the parser registered for this type, if any
+    pub fn decoded<T: Any>(&self) -> Option<&T> {
+        self.decoded.as_ref()?.downcast_ref()
+    }
+
+    /// Empty object of type `name`
+    pub fn new(name: &str) -> Self {
+        Self {
+            class: None,
+            r#type: ObjectId::Str(name.into()),
+            data: Vec::new(),
+            decoded: None,
+        }
+    }
+
+    /// Find the first field with the given string label
+    pub fn field(&self, label: &str) -> Option<&UserField> {
+        self.data.iter().find(|field| field.label.as_str() == Some(label))
+    }
+
+    /// Mutable reference to the first field with the given string label
+    pub fn field_mut(&mut self, label: &str) -> Option<&mut UserField> {
+        self.data.iter_mut().find(|field| field.label.as_str() == Some(label))
+    }
+
+    /// Replace the data of the first field labeled `label`, or append a new field
+    pub fn set_field(&mut self, label: &str, data: UserData) {
+        match self.field_mut(label) {
+            Some(field) => *field = UserField::new(label, data),
+            None => self.data.push(UserField::new(label, data)),
+        }
+    }
+
+    /// Remove every field labeled `label`, returning the first one
+    pub fn remove_field(&mut self, label: &str) -> Option<UserField> {
+        let position = self.data.iter().position(|field| field.label.as_str() == Some(label))?;
+        let field = self.data.remove(position);
+        self.data.retain(|field| field.label.as_str() != Some(label));
+        Some(field)
+    }
 }
 
 impl XmlNode for UserObject {
@@ -417,6 +750,7 @@ impl XmlNode for UserObject {
         Self: Sized,
     {
         let mut object = Self::default();
+        let mut decoder = decodes_user_objects().then(|| reader.clone());
 
         // elements
         let class_element = BytesStart::new("User-object_class");
@@ -424,20 +758,23 @@ impl XmlNode for UserObject {
         let type_element = BytesStart::new("User-object_type");
 
         loop {
-            match reader.read_event().unwrap() {
+            match next_event(reader)? {
                 Event::Start(e) => {
                     let name = e.name();
 
-                    if name == class_element.name() {
+                    if name.is_tag(class_element.name()) {
                         object.class = read_string(reader);
-                    } else if name == type_element.name() {
-                        object.r#type = read_node(reader).unwrap();
-                    } else if name == data_element.name() {
-                        object.data = read_vec_node(reader, data_element.to_end());
+                    } else if name.is_tag(type_element.name()) {
+                        object.r#type = read_node(reader)?;
+                    } else if name.is_tag(data_element.name()) {
+                        object.data = read_vec_node(reader, e.to_end());
                     }
                 }
                 Event::End(e) => {
                     if Self::is_end(&e) {
+                        if let Some(decoder) = decoder.as_mut() {
+                            object.decoded = decode_user_object(&object, decoder);
+                        }
                         return object.into();
                     }
                 }
@@ -449,6 +786,7 @@ impl XmlNode for UserObject {
 impl XmlVecNode for UserObject {}
 
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "lowercase")]
 pub enum UserData {
     Str(String),
@@ -472,10 +810,29 @@ impl Default for UserData {
     }
 }
 
+impl UserData {
+    /// Return inner string if variant is [`UserData::Str`]
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::Str(s) => Some(s.as_str()),
+            _ => None,
+        }
+    }
+
+    /// Return inner strings for [`UserData::Strs`], or a single [`UserData::Str`]
+    pub fn as_strs(&self) -> Vec<&str> {
+        match self {
+            Self::Str(s) => vec![s.as_str()],
+            Self::Strs(strs) => strs.iter().map(|s| s.as_str()).collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
 impl XmlNode for UserData {
-    /// This enumerated value is not enclosed by a tag
+    /// This enumerated value is enclosed by the data element of [`UserField`]
     fn start_bytes() -> BytesStart<'static> {
-        unimplemented!()
+        BytesStart::new("User-field_data")
     }
 
     fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self>
@@ -497,36 +854,40 @@ impl XmlNode for UserData {
         let forbidden = UnexpectedTags(&[]);
 
         loop {
-            match reader.read_event().unwrap() {
+            match next_event(reader)? {
                 Event::Start(e) => {
                     let name = e.name();
 
-                    if name == str_element.name() {
-                        return Self::Str(read_string(reader).unwrap()).into();
-                    } else if name == int_element.name() {
-                        return Self::Int(read_int::<i64>(reader).unwrap()).into();
-                    } else if name == real_element.name() {
-                        return Self::Real(read_real(reader).unwrap()).into()
-                    } else if name == bool_element.name() {
-                        unimplemented!()
-                    } else if name == object_element.name() {
-                        return Self::Object(read_node(reader).unwrap()).into();
-                    } else if name == strs_element.name() {
-                        return Self::Strs(read_vec_str_unchecked(reader, &strs_element.to_end())).into();
-                    } else if name == ints_element.name() {
-                        return Self::Ints(read_vec_int_unchecked(reader, &ints_element.to_end())).into();
-                    } else if name == reals_element.name() {
-                        return Self::Reals(read_vec_str_unchecked(reader, &reals_element.to_end())).into();
-                    } else if name == fields_element.name() {
-                        return Self::Fields(read_vec_node(reader, fields_element.to_end())).into()
+                    if name.is_tag(str_element.name()) {
+                        return Self::Str(read_string(reader)?).into();
+                    } else if name.is_tag(int_element.name()) {
+                        return Self::Int(read_int::<i64>(reader)?).into();
+                    } else if name.is_tag(real_element.name()) {
+                        return Self::Real(read_real(reader)?).into()
+                    } else if name.is_tag(bool_element.name()) {
+                        return Self::Bool(read_bool(reader)?).into();
+                    } else if name.is_tag(object_element.name()) {
+                        return Self::Object(read_node(reader)?).into();
+                    } else if name.is_tag(strs_element.name()) {
+                        return Self::Strs(read_vec_str_unchecked(reader, &e.to_end())).into();
+                    } else if name.is_tag(ints_element.name()) {
+                        return Self::Ints(read_vec_int_unchecked(reader, &e.to_end())).into();
+                    } else if name.is_tag(reals_element.name()) {
+                        return Self::Reals(read_vec_str_unchecked(reader, &e.to_end())).into();
+                    } else if name.is_tag(fields_element.name()) {
+                        return Self::Fields(read_vec_node(reader, e.to_end())).into()
                     } else if name == BytesStart::new("User-field").name() {
                         return Self::Fields(read_vec_node(reader, BytesStart::new("User-field").to_end())).into()
-                    } else if name == objects_element.name() {
-                        return Self::Objects(read_vec_node(reader, objects_element.to_end())).into()
-                    } else if name != BytesStart::new("User-field_label").name() {
+                    } else if name.is_tag(objects_element.name()) {
+                        return Self::Objects(read_vec_node(reader, e.to_end())).into()
+                    } else if !name.is_tag(BytesStart::new("User-field_label").name()) {
                         forbidden.check(&name);
                     }
                 }
+                Event::Empty(e) if e.name().is_tag(bool_element.name()) => {
+                    return Self::Bool(read_bool_attribute(&e)?).into();
+                }
+                Event::End(e) if Self::is_end(&e) => return None,
                 _ => (),
             }
         }
@@ -534,6 +895,7 @@ impl XmlNode for UserData {
 }
 
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct UserField {
     /// field label
     pub label: ObjectId,
@@ -543,6 +905,25 @@ pub struct UserField {
     pub data: UserData,
 }
 
+impl UserField {
+    /// Field labeled `label`
+    ///
+    /// [`Self::num`] is set to the number of values of multi-valued data, as required
+    /// for [`UserData::Strs`], [`UserData::Ints`] and [`UserData::Reals`].
+    pub fn new(label: &str, data: UserData) -> Self {
+        let num = match &data {
+            UserData::Strs(values) | UserData::Reals(values) => Some(values.len() as i64),
+            UserData::Ints(values) => Some(values.len() as i64),
+            _ => None,
+        };
+        Self {
+            label: ObjectId::Str(label.into()),
+            num,
+            data,
+        }
+    }
+}
+
 impl XmlNode for UserField {
     fn start_bytes() -> BytesStart<'static> {
         BytesStart::new("User-field")
@@ -562,15 +943,15 @@ impl XmlNode for UserField {
         let forbidden = UnexpectedTags(&[]);
 
         loop {
-            match reader.read_event().unwrap() {
+            match next_event(reader)? {
                 Event::Start(e) => {
                     let name = e.name();
 
-                    if name == label_element.name() {
-                        field.label = read_node(reader).unwrap();
-                    } else if name == data_element.name() {
-                        field.data = read_node(reader).unwrap();
-                    } else if name == num_element.name() {
+                    if name.is_tag(label_element.name()) {
+                        field.label = read_node(reader)?;
+                    } else if name.is_tag(data_element.name()) {
+                        field.data = read_node(reader)?;
+                    } else if name.is_tag(num_element.name()) {
                         field.num = read_int(reader);
                     } else if name != Self::start_bytes().name() {
                         forbidden.check(&name)
@@ -592,7 +973,7 @@ impl XmlVecNode for UserField {}
 #[cfg(test)]
 mod tests {
     use quick_xml::Reader;
-    use crate::general::UserField;
+    use crate::general::{FuzzLimit, IntFuzz, Range, UserData, UserField};
     use crate::parsing::read_node;
 
     #[test]
@@ -781,4 +1162,56 @@ mod tests {
             println!("{:?}", node)
         }
     }
+
+    #[test]
+    fn test_bool_data() {
+        let field = |data: &str| {
+            format!("<User-field>\
+                <User-field_label><Object-id><Object-id_str>flag</Object-id_str></Object-id></User-field_label>\
+                <User-field_data>{data}</User-field_data>\
+                </User-field>")
+        };
+        let cases = [
+            ("<User-field_data_bool value=\"true\"/>", Some(UserData::Bool(true))),
+            ("<User-field_data_bool value=\"false\"/>", Some(UserData::Bool(false))),
+            ("<User-field_data_bool>true</User-field_data_bool>", Some(UserData::Bool(true))),
+            ("<User-field_data_bool>maybe</User-field_data_bool>", None),
+        ];
+        for (data, expected) in cases {
+            let xml = field(data);
+            let mut reader = Reader::from_str(xml.as_str());
+            let field: Option<UserField> = read_node(&mut reader);
+            assert_eq!(field.map(|field| field.data), expected);
+        }
+    }
+
+    #[test]
+    fn test_int_fuzz() {
+        let cases = [
+            ("<Int-fuzz><Int-fuzz_lim value=\"gt\"/></Int-fuzz>", IntFuzz::Lim(FuzzLimit::GT)),
+            ("<Int-fuzz><lim value=\"tl\"/></Int-fuzz>", IntFuzz::Lim(FuzzLimit::TL)),
+            ("<Int-fuzz><Int-fuzz_p-m>5</Int-fuzz_p-m></Int-fuzz>", IntFuzz::PM(5)),
+            (
+                "<Int-fuzz><Int-fuzz_range>\
+                <Int-fuzz_range_max>20</Int-fuzz_range_max>\
+                <Int-fuzz_range_min>10</Int-fuzz_range_min>\
+                </Int-fuzz_range></Int-fuzz>",
+                IntFuzz::Range(Range { max: 20, min: 10 }),
+            ),
+            (
+                "<Int-fuzz><Int-fuzz_alt>\
+                <Int-fuzz_alt_E>3</Int-fuzz_alt_E>\
+                <Int-fuzz_alt_E>7</Int-fuzz_alt_E>\
+                </Int-fuzz_alt></Int-fuzz>",
+                IntFuzz::Alt(vec![3, 7]),
+            ),
+        ];
+        for (xml, expected) in cases {
+            let mut reader = Reader::from_str(xml);
+            let fuzz: IntFuzz = read_node(&mut reader).unwrap();
+            assert_eq!(fuzz, expected);
+        }
+        assert!(IntFuzz::Lim(FuzzLimit::LT).is_partial_limit());
+        assert!(!IntFuzz::Lim(FuzzLimit::Circle).is_partial_limit());
+    }
 }
\ No newline at end of file
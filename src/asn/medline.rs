@@ -27,6 +27,12 @@ pub enum MedlineEntryStatus {
     Medline,
 }
 
+asn_names!(MedlineEntryStatus {
+    Publisher => "publisher",
+    PreMedline => "premedline",
+    Medline => "medline",
+});
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 #[serde(rename_all = "kebab-case")]
 /// a MEDLINE or PubMed entry
@@ -123,6 +129,23 @@ pub enum MedlineSiType {
     GDB,
 }
 
+asn_names!(MedlineSiType {
+    DDBJ => "ddbj",
+    CarbBank => "carbbank",
+    EMBL => "embl",
+    HDB => "hdb",
+    GenBank => "genbank",
+    HGML => "hgml",
+    MIM => "mim",
+    MSD => "msd",
+    PDB => "pdb",
+    PIR => "pir",
+    PrfSeqDb => "prfseqdb",
+    PSD => "psd",
+    SwissProt => "swissprot",
+    GDB => "gdb",
+});
+
 #[derive(Clone, Serialize_repr, Deserialize_repr, PartialEq, Debug)]
 #[repr(u8)]
 /// Internal representation of type of medline substance record for [`MedlineRn`]
@@ -141,6 +164,12 @@ pub enum MedlineRnType {
     EC,
 }
 
+asn_names!(MedlineRnType {
+    NameOnly => "nameonly",
+    CAS => "cas",
+    EC => "ec",
+});
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 /// Medline substance records
 pub struct MedlineRn {
@@ -183,6 +212,12 @@ pub enum MedlineFieldType {
     Erratum,
 }
 
+asn_names!(MedlineFieldType {
+    Other => "other",
+    Comment => "comment",
+    Erratum => "erratum",
+});
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 pub struct MedlineField {
     #[serde(rename = "type")]
@@ -208,6 +243,12 @@ pub enum DocRefType {
     NCBIGi,
 }
 
+asn_names!(DocRefType {
+    Medline => "medline",
+    PubMed => "pubmed",
+    NCBIGi => "ncbigi",
+});
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 /// reference to a document
 pub struct DocRef {
@@ -1,5 +1,44 @@
+/// Implement [`FromStr`](std::str::FromStr) and [`Display`](std::fmt::Display) for an
+/// enumeration from the identifiers of its values in the ASN.1 specification
+///
+/// These are the names given by the `value` attribute of XML elements (eg:
+/// `<MolInfo_biomol value="genomic">1</MolInfo_biomol>`).
+macro_rules! asn_names {
+    ($type:ident { $($variant:ident => $name:literal),+ $(,)? }) => {
+        #[allow(deprecated)]
+        impl $type {
+            /// Identifier of the value in the ASN.1 specification
+            pub fn asn_name(&self) -> &'static str {
+                match self {
+                    $(Self::$variant => $name,)+
+                }
+            }
+        }
+
+        #[allow(deprecated)]
+        impl std::str::FromStr for $type {
+            type Err = String;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                match s {
+                    $($name => Ok(Self::$variant),)+
+                    _ => Err(format!("Unknown {}: {}", stringify!($type), s)),
+                }
+            }
+        }
+
+        impl std::fmt::Display for $type {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(self.asn_name())
+            }
+        }
+    };
+}
+
 pub mod biblio;
+pub mod cdd;
 pub mod entrezgene;
+pub mod gbseq;
 pub mod general;
 pub mod medline;
 pub mod r#pub;
@@ -13,3 +52,4 @@ pub mod seqloc;
 pub mod seqres;
 pub mod seqset;
 pub mod seqtable;
+pub mod submit;
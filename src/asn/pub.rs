@@ -5,11 +5,13 @@
 //! Adapted from ["pub.asn"](https://www.ncbi.nlm.nih.gov/IEB/ToolBox/CPP_DOC/lxr/source/src/objects/pub/pub.asn)
 
 use crate::biblio::{
-    CitArt, CitBook, CitGen, CitJour, CitLet, CitPat, CitProc, CitSub, IdPat, PubMedId,
+    ArticleId, AuthList, AuthListNames, CitArt, CitArtFrom, CitBook, CitGen, CitJour, CitLet, CitPat,
+    CitProc, CitSub, IdPat, PubMedId, Title, TitleItem,
 };
+use crate::general::{Date, PersonId};
 use crate::medline::MedlineEntry;
-use crate::parsing::read_node;
-use crate::parsing::{XmlNode, XmlVecNode};
+use crate::parsing::{next_event, read_int, read_node, read_vec_node};
+use crate::parsing::{TagMatch, XmlNode, XmlVecNode};
 use quick_xml::events::{BytesStart, Event};
 use quick_xml::Reader;
 use serde::{Deserialize, Serialize};
@@ -61,16 +63,37 @@ impl XmlNode for Pub {
         // variants
         let sub_element = BytesStart::new("Pub_sub");
         let gen_element = BytesStart::new("Pub_gen");
+        let journal_element = BytesStart::new("Pub_journal");
+        let book_element = BytesStart::new("Pub_book");
+        let proc_element = BytesStart::new("Pub_proc");
+        let patent_element = BytesStart::new("Pub_patent");
+        let pat_id_element = BytesStart::new("Pub_pat-id");
+        let man_element = BytesStart::new("Pub_man");
+        let pmid_element = BytesStart::new("PubMedId");
 
         loop {
-            match reader.read_event().unwrap() {
+            match next_event(reader)? {
                 Event::Start(e) => {
                     let name = e.name();
 
-                    if name == sub_element.name() {
-                        return Pub::Sub(read_node(reader).unwrap()).into();
-                    } else if name == gen_element.name() {
-                        return Pub::Gen(read_node(reader).unwrap()).into();
+                    if name.is_tag(sub_element.name()) {
+                        return Pub::Sub(read_node(reader)?).into();
+                    } else if name.is_tag(gen_element.name()) {
+                        return Pub::Gen(read_node(reader)?).into();
+                    } else if name.is_tag(journal_element.name()) {
+                        return Pub::Journal(read_node(reader)?).into();
+                    } else if name.is_tag(book_element.name()) {
+                        return Pub::Book(read_node(reader)?).into();
+                    } else if name.is_tag(proc_element.name()) {
+                        return Pub::Proc(read_node(reader)?).into();
+                    } else if name.is_tag(patent_element.name()) {
+                        return Pub::Patent(read_node(reader)?).into();
+                    } else if name.is_tag(pat_id_element.name()) {
+                        return Pub::PatId(read_node(reader)?).into();
+                    } else if name.is_tag(man_element.name()) {
+                        return Pub::Man(read_node(reader)?).into();
+                    } else if name.is_tag(pmid_element.name()) {
+                        return Pub::PmId(read_int(reader)?).into();
                     }
                 }
                 Event::End(e) => {
@@ -100,6 +123,404 @@ impl XmlNode for PubEquiv {
     }
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+/// Format of the strings given by [`Pub::to_citation_string()`]
+pub enum CitationStyle {
+    /// first author and year (eg: "Smith J et al. (2020)")
+    Short,
+
+    #[default]
+    /// authors, title, journal, year and identifiers, in the style of Vancouver
+    ///
+    /// eg: "Smith J, Doe A. Title. Nature. 2020;580(7):12-15. doi:10.1/x. PMID: 1."
+    Full,
+}
+
+/// Maximum number of authors listed by [`CitationStyle::Full`] before "et al."
+const MAX_AUTHORS: usize = 6;
+
+pub(crate) const MONTHS: [&str; 12] = [
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+
+/// Name of an author as "Last Initials" (eg: "Smith JA")
+fn author_name(name: &PersonId) -> Option<String> {
+    match name {
+        PersonId::Name(name) => {
+            let initials = match (&name.initials, &name.first) {
+                (Some(initials), _) => initials.replace(['.', ' '], ""),
+                (None, Some(first)) => first.chars().take(1).collect(),
+                (None, None) => String::new(),
+            };
+            match initials.is_empty() {
+                true => Some(name.last.clone()),
+                false => Some(format!("{} {}", name.last, initials)),
+            }
+        }
+        PersonId::ML(name) | PersonId::Str(name) | PersonId::Consortium(name) => Some(name.clone()),
+        PersonId::DbTag(_) => None,
+    }
+}
+
+pub(crate) fn author_names(authors: &AuthList) -> Vec<String> {
+    match &authors.names {
+        AuthListNames::Std(authors) => authors.iter().filter_map(|author| author_name(&author.name)).collect(),
+        AuthListNames::Ml(names) | AuthListNames::Str(names) => names.clone(),
+    }
+}
+
+fn format_authors(names: &[String], style: CitationStyle) -> Option<String> {
+    match (style, names) {
+        (_, []) => None,
+        (_, [name]) => Some(name.clone()),
+        (CitationStyle::Short, [first, second]) => Some(format!("{} and {}", first, second)),
+        (CitationStyle::Short, [first, ..]) => Some(format!("{} et al.", first)),
+        (CitationStyle::Full, names) if names.len() > MAX_AUTHORS => {
+            Some(format!("{}, et al.", names[..MAX_AUTHORS].join(", ")))
+        }
+        (CitationStyle::Full, names) => Some(names.join(", ")),
+    }
+}
+
+/// Title of an article or book
+fn title_name(title: &Title) -> Option<&str> {
+    title.iter().find_map(|item| match item {
+        TitleItem::Name(name) => Some(name.as_str()),
+        _ => None,
+    })
+}
+
+/// Abbreviated title of a journal, falling back to its full title
+fn journal_name(title: &Title) -> Option<&str> {
+    let abbreviated = title.iter().find_map(|item| match item {
+        TitleItem::IsoJta(name) | TitleItem::MlJta(name) | TitleItem::Jta(name) => Some(name.as_str()),
+        _ => None,
+    });
+    abbreviated.or_else(|| title_name(title))
+}
+
+fn year(date: &Date) -> String {
+    match date {
+        Date::Str(date) => date.clone(),
+        Date::Date(date) | Date::Std(date) => date.year.to_string(),
+    }
+}
+
+/// Date as given by GenBank (eg: "15-JUN-2020"), or the year when incomplete
+pub(crate) fn full_date(date: &Date) -> String {
+    match date {
+        Date::Date(std) | Date::Std(std) => match (std.day, std.month) {
+            (Some(day), Some(month @ 1..=12)) => {
+                format!("{:02}-{}-{}", day, MONTHS[month as usize - 1], std.year)
+            }
+            _ => year(date),
+        },
+        Date::Str(_) => year(date),
+    }
+}
+
+/// "2020;580(7):12-15"
+fn volume_string(
+    year: Option<String>,
+    volume: Option<&String>,
+    issue: Option<&String>,
+    pages: Option<&String>,
+) -> Option<String> {
+    let mut string = year.unwrap_or_default();
+    if let Some(volume) = volume {
+        if !string.is_empty() {
+            string.push(';');
+        }
+        string.push_str(volume);
+    }
+    if let Some(issue) = issue {
+        string.push_str(&format!("({})", issue));
+    }
+    if let Some(pages) = pages {
+        string.push_str(&format!(":{}", pages));
+    }
+    match string.is_empty() {
+        true => None,
+        false => Some(string),
+    }
+}
+
+/// Parts of a citation, which are joined by ". "
+#[derive(Default)]
+struct Citation {
+    authors: Vec<String>,
+    year: Option<String>,
+    parts: Vec<String>,
+    doi: Option<String>,
+    pmid: Option<PubMedId>,
+}
+
+impl Citation {
+    fn part(&mut self, part: Option<impl AsRef<str>>) {
+        if let Some(part) = part {
+            let part = part.as_ref().trim().trim_end_matches('.');
+            if !part.is_empty() {
+                self.parts.push(part.to_string());
+            }
+        }
+    }
+
+    fn render(mut self, style: CitationStyle) -> String {
+        let authors = format_authors(&self.authors, style);
+        if style == CitationStyle::Short {
+            let year = self.year.map(|year| format!("({})", year));
+            let first = authors.or_else(|| self.parts.first().cloned());
+            return first.into_iter().chain(year).collect::<Vec<_>>().join(" ");
+        }
+
+        let mut parts: Vec<String> = authors.into_iter().map(|a| a.trim_end_matches('.').to_string()).collect();
+        parts.append(&mut self.parts);
+        parts.extend(self.doi.map(|doi| format!("doi:{}", doi)));
+        parts.extend(self.pmid.map(|pmid| format!("PMID: {}", pmid)));
+        match parts.is_empty() {
+            true => String::new(),
+            false => format!("{}.", parts.join(". ")),
+        }
+    }
+}
+
+impl Pub {
+    /// Human-readable citation of the publication
+    ///
+    /// Articles, submissions and generic citations are rendered with their authors,
+    /// title, journal, year and identifiers. For [`Pub::Equiv`], the most complete
+    /// citation is rendered, along with PubMed ids given by the other members.
+    pub fn to_citation_string(&self, style: CitationStyle) -> String {
+        self.citation().render(style)
+    }
+
+    fn citation(&self) -> Citation {
+        match self {
+            Pub::Article(article) => article_citation(article),
+            Pub::Medline(entry) => article_citation(&entry.cit),
+            Pub::Sub(sub) => sub_citation(sub),
+            Pub::Gen(gen) => gen_citation(gen),
+            Pub::Journal(journal) => {
+                let mut citation = Citation {
+                    year: Some(year(&journal.imp.date)),
+                    ..Default::default()
+                };
+                citation.part(journal_name(&journal.title));
+                citation
+            }
+            Pub::Book(book) => book_citation(book),
+            Pub::Proc(proc) => book_citation(&proc.book),
+            Pub::Man(letter) => book_citation(&letter.cit),
+            Pub::Patent(patent) => {
+                let mut citation = Citation {
+                    authors: author_names(&patent.authors),
+                    year: patent.date_issue.as_ref().map(year),
+                    ..Default::default()
+                };
+                citation.part(Some(&patent.title));
+                citation.part(Some(format!("Patent {} {}", patent.country, patent.number.as_deref().unwrap_or_default())));
+                citation
+            }
+            Pub::PatId(id) => {
+                let mut citation = Citation::default();
+                citation.part(Some(format!("Patent {}", id.country)));
+                citation
+            }
+            Pub::Muid(muid) => {
+                let mut citation = Citation::default();
+                citation.part(Some(format!("MUID: {}", muid)));
+                citation
+            }
+            Pub::PmId(pmid) => Citation {
+                pmid: Some(*pmid),
+                ..Default::default()
+            },
+            Pub::Equiv(equiv) => equiv_citation(equiv),
+        }
+    }
+
+    /// Identifiers of the cited article, from any variant
+    ///
+    /// Members of [`Pub::Equiv`] are searched recursively. Identifiers given more than
+    /// once are only returned once.
+    pub fn article_ids(&self) -> Vec<ArticleId> {
+        let mut ids = Vec::new();
+        self.collect_article_ids(&mut ids);
+        ids
+    }
+
+    fn collect_article_ids(&self, ids: &mut Vec<ArticleId>) {
+        let mut push = |id: ArticleId| {
+            if !ids.contains(&id) {
+                ids.push(id);
+            }
+        };
+        match self {
+            Pub::PmId(pmid) => push(ArticleId::PubMed(*pmid)),
+            Pub::Muid(muid) => push(ArticleId::Medline(*muid)),
+            Pub::Gen(gen) => {
+                gen.pmid.into_iter().for_each(|pmid| push(ArticleId::PubMed(pmid)));
+                gen.muid.into_iter().for_each(|muid| push(ArticleId::Medline(muid)));
+            }
+            Pub::Article(article) => article.ids.iter().flatten().cloned().for_each(push),
+            Pub::Medline(entry) => {
+                entry.pmid.into_iter().for_each(|pmid| push(ArticleId::PubMed(pmid)));
+                entry.uid.into_iter().for_each(|uid| push(ArticleId::Medline(uid)));
+                entry.cit.ids.iter().flatten().cloned().for_each(push);
+            }
+            Pub::Equiv(equiv) => equiv.iter().for_each(|member| member.collect_article_ids(ids)),
+            _ => (),
+        }
+    }
+
+    /// PubMed ids of the cited article (see [`Self::article_ids()`])
+    pub fn pmids(&self) -> Vec<PubMedId> {
+        self.article_ids()
+            .into_iter()
+            .filter_map(|id| match id {
+                ArticleId::PubMed(pmid) => Some(pmid),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// DOIs of the cited article (see [`Self::article_ids()`])
+    pub fn dois(&self) -> Vec<String> {
+        self.article_ids()
+            .into_iter()
+            .filter_map(|id| match id {
+                ArticleId::DOI(doi) => Some(doi),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Rank of the variant when choosing the member of a [`PubEquiv`] to render
+    fn completeness(&self) -> usize {
+        match self {
+            Pub::Article(_) | Pub::Medline(_) => 5,
+            Pub::Gen(gen) if gen.title.is_some() || gen.journal.is_some() => 4,
+            Pub::Sub(_) | Pub::Book(_) | Pub::Proc(_) | Pub::Patent(_) | Pub::Man(_) => 4,
+            Pub::Gen(_) | Pub::Journal(_) | Pub::Equiv(_) => 3,
+            Pub::PatId(_) => 2,
+            Pub::PmId(_) => 1,
+            Pub::Muid(_) => 0,
+        }
+    }
+}
+
+fn article_citation(article: &CitArt) -> Citation {
+    let mut citation = Citation {
+        authors: article.authors.as_ref().map(author_names).unwrap_or_default(),
+        ..Default::default()
+    };
+    citation.part(article.title.as_ref().and_then(title_name));
+
+    match &article.from {
+        CitArtFrom::Journal(journal) => {
+            let imp = &journal.imp;
+            citation.year = Some(year(&imp.date));
+            citation.part(journal_name(&journal.title));
+            citation.part(volume_string(
+                citation.year.clone(),
+                imp.volume.as_ref(),
+                imp.issue.as_ref(),
+                imp.pages.as_ref(),
+            ));
+        }
+        CitArtFrom::Book(book) | CitArtFrom::Proc(CitProc { book, .. }) => {
+            let imp = &book.imp;
+            citation.year = Some(year(&imp.date));
+            citation.part(title_name(&book.title).map(|title| format!("In: {}", title)));
+            citation.part(volume_string(citation.year.clone(), None, None, imp.pages.as_ref()));
+        }
+    }
+
+    for id in article.ids.iter().flatten() {
+        match id {
+            ArticleId::DOI(doi) => citation.doi = Some(doi.clone()),
+            ArticleId::PubMed(pmid) => citation.pmid = Some(*pmid),
+            _ => (),
+        }
+    }
+    citation
+}
+
+fn book_citation(book: &CitBook) -> Citation {
+    let mut citation = Citation {
+        authors: author_names(&book.authors),
+        year: Some(year(&book.imp.date)),
+        ..Default::default()
+    };
+    citation.part(title_name(&book.title));
+    citation.part(citation.year.clone());
+    citation
+}
+
+fn sub_citation(sub: &CitSub) -> Citation {
+    let date = sub.date.as_ref().or(sub.imp.as_ref().map(|imp| &imp.date));
+    let mut citation = Citation {
+        authors: author_names(&sub.authors),
+        year: date.map(year),
+        ..Default::default()
+    };
+    citation.part(Some("Direct Submission"));
+    citation.part(date.map(|date| format!("Submitted ({})", full_date(date))));
+    citation.part(sub.descr.as_ref());
+    citation
+}
+
+fn gen_citation(gen: &CitGen) -> Citation {
+    let mut citation = Citation {
+        authors: gen.authors.as_ref().map(author_names).unwrap_or_default(),
+        year: gen.date.as_ref().map(year),
+        pmid: gen.pmid,
+        ..Default::default()
+    };
+    citation.part(gen.title.as_ref());
+    citation.part(gen.journal.as_ref().and_then(journal_name));
+    citation.part(gen.cit.as_ref());
+    citation.part(volume_string(
+        citation.year.clone(),
+        gen.volume.as_ref(),
+        gen.issue.as_ref(),
+        gen.pages.as_ref(),
+    ));
+    citation
+}
+
+/// Most complete member of `equiv`, searching nested [`Pub::Equiv`] members
+///
+/// The earliest member wins ties. Returns `None` when `equiv` is empty.
+pub fn best_pub(equiv: &PubEquiv) -> Option<&Pub> {
+    equiv
+        .iter()
+        .filter_map(|member| match member {
+            Pub::Equiv(inner) => best_pub(inner),
+            _ => Some(member),
+        })
+        .enumerate()
+        .max_by_key(|(i, member)| (member.completeness(), std::cmp::Reverse(*i)))
+        .map(|(_, member)| member)
+}
+
+fn equiv_citation(equiv: &PubEquiv) -> Citation {
+    let mut citation = best_pub(equiv).map(Pub::citation).unwrap_or_default();
+
+    for member in equiv {
+        match member {
+            Pub::PmId(pmid) if citation.pmid.is_none() => citation.pmid = Some(*pmid),
+            Pub::Article(article) | Pub::Medline(MedlineEntry { cit: article, .. }) => {
+                let ids = article_citation(article);
+                citation.pmid = citation.pmid.or(ids.pmid);
+                citation.doi = citation.doi.or(ids.doi);
+            }
+            _ => (),
+        }
+    }
+    citation
+}
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 #[serde(rename_all = "lowercase")]
 pub enum PubSet {
@@ -111,3 +532,151 @@ pub enum PubSet {
     Proc(Vec<CitProc>),
     Patent(Vec<CitPat>),
 }
+
+impl XmlNode for PubSet {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("Pub-set")
+    }
+
+    /// Sets of MEDLINE entries and articles are not parsed, as these have no parser
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        // variants
+        let pub_element = BytesStart::new("Pub-set_pub");
+        let journal_element = BytesStart::new("Pub-set_journal");
+        let book_element = BytesStart::new("Pub-set_book");
+        let proc_element = BytesStart::new("Pub-set_proc");
+        let patent_element = BytesStart::new("Pub-set_patent");
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => {
+                    let name = e.name();
+
+                    if name.is_tag(pub_element.name()) {
+                        return PubSet::Pub(read_vec_node(reader, e.to_end())).into();
+                    } else if name.is_tag(journal_element.name()) {
+                        return PubSet::Journal(read_vec_node(reader, e.to_end())).into();
+                    } else if name.is_tag(book_element.name()) {
+                        return PubSet::Book(read_vec_node(reader, e.to_end())).into();
+                    } else if name.is_tag(proc_element.name()) {
+                        return PubSet::Proc(read_vec_node(reader, e.to_end())).into();
+                    } else if name.is_tag(patent_element.name()) {
+                        return PubSet::Patent(read_vec_node(reader, e.to_end())).into();
+                    }
+                }
+                Event::End(e) if Self::is_end(&e) => {
+                    return None;
+                }
+                _ => (),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::biblio::{ArticleId, AuthList, AuthListNames, CitArt, CitArtFrom, CitGen, CitJour, Imprint, TitleItem};
+    use crate::general::{Date, DateStd};
+    use crate::r#pub::{best_pub, CitationStyle, Pub};
+
+    fn ml_authors(names: &[&str]) -> AuthList {
+        AuthList {
+            names: AuthListNames::Ml(names.iter().map(|name| name.to_string()).collect()),
+            affil: None,
+        }
+    }
+
+    fn article() -> CitArt {
+        let imp = Imprint {
+            date: Date::Std(DateStd {
+                year: 2020,
+                ..Default::default()
+            }),
+            volume: Some("580".into()),
+            issue: Some("7".into()),
+            pages: Some("12-15".into()),
+            section: None,
+            r#pub: None,
+            cprt: None,
+            part_sup: None,
+            language: None,
+            prepub: None,
+            part_supi: None,
+            retract: None,
+            pubstatus: None,
+            history: None,
+        };
+        CitArt {
+            title: Some(vec![TitleItem::Name("A title.".into())]),
+            authors: Some(ml_authors(&["Smith J", "Doe A"])),
+            from: CitArtFrom::Journal(CitJour {
+                title: vec![TitleItem::Name("Nature".into()), TitleItem::IsoJta("Nat".into())],
+                imp,
+            }),
+            ids: Some(vec![ArticleId::DOI("10.1/x".into())]),
+        }
+    }
+
+    #[test]
+    fn article_citation() {
+        let article = Pub::Article(article());
+        assert_eq!(
+            article.to_citation_string(CitationStyle::Full),
+            "Smith J, Doe A. A title. Nat. 2020;580(7):12-15. doi:10.1/x."
+        );
+        assert_eq!(article.to_citation_string(CitationStyle::Short), "Smith J and Doe A (2020)");
+
+        // PubMed id is taken from other members of the equivalence
+        let equiv = Pub::Equiv(vec![Pub::PmId(1), article]);
+        assert!(equiv.to_citation_string(CitationStyle::Full).ends_with("doi:10.1/x. PMID: 1."));
+    }
+
+    #[test]
+    fn gen_citation() {
+        let names: Vec<String> = (1..=8).map(|i| format!("Author{} A", i)).collect();
+        let names: Vec<&str> = names.iter().map(String::as_str).collect();
+        let gen = Pub::Gen(CitGen {
+            cit: Some("Unpublished".into()),
+            authors: Some(ml_authors(&names)),
+            title: Some("Title".into()),
+            pmid: Some(2),
+            ..Default::default()
+        });
+        assert_eq!(
+            gen.to_citation_string(CitationStyle::Full),
+            "Author1 A, Author2 A, Author3 A, Author4 A, Author5 A, Author6 A, et al. Title. Unpublished. PMID: 2."
+        );
+        assert_eq!(gen.to_citation_string(CitationStyle::Short), "Author1 A et al.");
+        assert_eq!(Pub::Equiv(vec![]).to_citation_string(CitationStyle::Full), "");
+    }
+
+    #[test]
+    fn article_ids() {
+        let mut cited = article();
+        cited.ids.as_mut().unwrap().push(ArticleId::PubMed(1));
+        let equiv = Pub::Equiv(vec![
+            Pub::PmId(1),
+            Pub::Muid(3),
+            Pub::Equiv(vec![Pub::Article(cited.clone()), Pub::PmId(2)]),
+        ]);
+        assert_eq!(equiv.pmids(), vec![1, 2]);
+        assert_eq!(equiv.dois(), vec!["10.1/x".to_string()]);
+        assert_eq!(
+            equiv.article_ids(),
+            vec![ArticleId::PubMed(1), ArticleId::Medline(3), ArticleId::DOI("10.1/x".into()), ArticleId::PubMed(2)]
+        );
+        assert!(Pub::Gen(CitGen::default()).pmids().is_empty());
+
+        // the article nested in an equivalence is preferred over PubMed ids
+        let members = match equiv {
+            Pub::Equiv(members) => members,
+            _ => unreachable!(),
+        };
+        assert_eq!(best_pub(&members), Some(&Pub::Article(cited)));
+        assert_eq!(best_pub(&members[..2].to_vec()), Some(&Pub::PmId(1)));
+        assert_eq!(best_pub(&vec![]), None);
+    }
+}
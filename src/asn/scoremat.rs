@@ -47,26 +47,56 @@ IMPORTS    Object-id   FROM NCBI-General
            Seq-entry   FROM NCBI-Seqset;
 */
 
+use crate::general::ObjectId;
+use crate::parsing::{next_event, qualified_name, tag_matches, read_bool_attribute, read_int, read_node, read_real, read_string, read_vec_int_unchecked, read_vec_node, UnexpectedTags};
+use crate::parsing::{XmlNode, XmlVecNode};
+use crate::seq::{NCBI4NA_ALPHABET, NCBISTDAA_ALPHABET};
+use enum_primitive::FromPrimitive;
+use quick_xml::escape::escape;
+use quick_xml::events::{BytesEnd, BytesStart, Event};
+use quick_xml::Reader;
 use serde::{Serialize, Deserialize};
 use serde_repr::{Serialize_repr, Deserialize_repr};
+use std::fmt::Display;
 
 
-/// Enumeration for BlockProperty types
-#[derive(Serialize_repr, Deserialize_repr, Debug, Clone, PartialEq, Default)]
-#[repr(u8)]
-pub enum BlockPropertyType {
-    #[default]
-    Unassigned = 0,
-    Threshold = 1, /// score threshold for heuristics
-    MinScore = 2, /// observed minimum score in CD
-    MaxScore = 3, /// observed maximum score in CD
-    MeanScore = 4, /// observed meanscore in CD
-    Variance = 5, /// observed score variance
-    Name = 10, /// just name the block
-    IsOptional = 20, /// block may not have to be used
-    Other = 255,
+enum_from_primitive! {
+    /// Enumeration for BlockProperty types
+    #[derive(Serialize_repr, Deserialize_repr, Debug, Clone, PartialEq, Default)]
+    #[repr(u8)]
+    pub enum BlockPropertyType {
+        #[default]
+        Unassigned = 0,
+        /// score threshold for heuristics
+        Threshold = 1,
+        /// observed minimum score in CD
+        MinScore = 2,
+        /// observed maximum score in CD
+        MaxScore = 3,
+        /// observed meanscore in CD
+        MeanScore = 4,
+        /// observed score variance
+        Variance = 5,
+        /// just name the block
+        Name = 10,
+        /// block may not have to be used
+        IsOptional = 20,
+        Other = 255,
+    }
 }
 
+asn_names!(BlockPropertyType {
+    Unassigned => "unassigned",
+    Threshold => "threshold",
+    MinScore => "minscore",
+    MaxScore => "maxscore",
+    MeanScore => "meanscore",
+    Variance => "variance",
+    Name => "name",
+    IsOptional => "is-optional",
+    Other => "other",
+});
+
 /// BlockProperty structure
 /// a rudimentary block/core-model, to be used with block-based alignment
 /// routines and threading
@@ -100,7 +130,7 @@ pub struct LoopConstraint {
 fn default_max_length() -> i64 { 100000 }
 
 /// CoreDef structure
-#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
 pub struct CoreDef {
     /// number of core elements/blocks
     pub nblocks: i64,
@@ -115,7 +145,7 @@ pub struct CoreDef {
 }
 
 /// SiteAnnot structure
-#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
 pub struct SiteAnnot {
     /// location of the annotation
     pub start_position: i64,
@@ -187,7 +217,7 @@ pub type SiteAnnotSet = Vec<SiteAnnot>;
 /// Contains the PSSM's scores and its associated statistical parameters.
 /// Dimensions and order in which scores are stored must be the same as that
 /// specified in Pssm::numRows, Pssm::numColumns, and Pssm::byrow
-#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
 pub struct PssmFinalData {
     /// PSSM's scores
     pub scores: Vec<i64>,
@@ -227,7 +257,7 @@ pub struct PssmFinalData {
 /// stored must be the same as that specified in Pssm::numRows,
 /// Pssm::numColumns, and Pssm::byrow
 
-#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
 pub struct PssmIntermediateData {
     /// observed residue frequencies (or counts) per position of the PSSM 
     /// (prior to application of pseudocounts)
@@ -281,7 +311,7 @@ pub struct PssmIntermediateData {
 /// values for the second column, and so on. In order to provide
 /// flexibility for external applications, the boolean field "byrow" is
 /// provided to specify the storage order.
-#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
 pub struct Pssm {
     /// Is the this a protein or nucleotide scoring matrix?
     #[serde(default = "default_true")]
@@ -322,7 +352,7 @@ fn default_true() -> bool { true }
 /// (*.aux) and it contains parameters set at creation time of the PSSM.
 /// Also, the matrixName field is used by formatrpsdb to build a PSSM from
 /// a Pssm structure which only contains PssmIntermediateData.
-#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
 pub struct FormatRpsDbParameters {
     /// name of the underlying score matrix whose frequency ratios were
     /// used in PSSM construction (e.g.: BLOSUM62)
@@ -336,7 +366,7 @@ pub struct FormatRpsDbParameters {
 /// PssmParameters structure
 /// Populated by PSSM engine of PSI-BLAST, original source for these values
 /// are the PSI-BLAST options specified using the BLAST options API
-#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
 pub struct PssmParameters {
     /// pseudocount constant used for PSSM. This field corresponds to beta in 
     /// equation 2 of Nucleic Acids Res. 2001 Jul 15;29(14):2994-3005.
@@ -358,7 +388,7 @@ pub struct PssmParameters {
 /// PssmWithParameters structure
 /// Envelope containing PSSM and the parameters used to create it.
 /// Provided for use in PSI-BLAST, formatrpsdb, and for the structure group.
-#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
 pub struct PssmWithParameters {
     /// This field is applicable to PSI-BLAST and formatrpsdb.
     /// When both the intermediate and final PSSM data are provided in this
@@ -379,3 +409,804 @@ pub struct PssmWithParameters {
     /// the PSSM or else the results produced by PSI-BLAST will be unreliable.
     pub params: Option<PssmParameters>,
 }
+
+impl Pssm {
+    /// Residues corresponding to each row when [`Self::row_labels`] is not given
+    fn alphabet(&self) -> &'static [char] {
+        if self.is_protein {
+            &NCBISTDAA_ALPHABET
+        } else {
+            &NCBI4NA_ALPHABET
+        }
+    }
+
+    /// Row of the matrix that corresponds to `residue`
+    ///
+    /// Rows are looked up in [`Self::row_labels`] if given, otherwise in the order of
+    /// ncbistdaa for proteins and ncbi4na for nucleotides.
+    pub fn row_index(&self, residue: char) -> Option<usize> {
+        let residue = residue.to_ascii_uppercase();
+        let row = match &self.row_labels {
+            Some(labels) => labels
+                .iter()
+                .position(|label| label.trim().eq_ignore_ascii_case(residue.encode_utf8(&mut [0; 4]))),
+            None => self.alphabet().iter().position(|c| *c == residue),
+        }?;
+        (row < usize::try_from(self.num_rows).ok()?).then_some(row)
+    }
+
+    /// Index of a cell within the linear matrix, taking [`Self::by_row`] into account
+    pub fn offset(&self, row: usize, column: usize) -> Option<usize> {
+        let rows = usize::try_from(self.num_rows).ok()?;
+        let columns = usize::try_from(self.num_columns).ok()?;
+        if row >= rows || column >= columns {
+            return None;
+        }
+        if self.by_row {
+            Some(row * columns + column)
+        } else {
+            Some(column * rows + row)
+        }
+    }
+
+    /// Score of `row_aa` at position `column` of the query
+    ///
+    /// Returns `None` if the residue or column is out of bounds, or if there is no
+    /// [`PssmFinalData`].
+    pub fn score(&self, row_aa: char, column: usize) -> Option<i64> {
+        let offset = self.offset(self.row_index(row_aa)?, column)?;
+        self.final_data.as_ref()?.scores.get(offset).copied()
+    }
+
+    /// Frequency ratio of `row_aa` at position `column` of the query
+    pub fn freq_ratio(&self, row_aa: char, column: usize) -> Option<f64> {
+        let offset = self.offset(self.row_index(row_aa)?, column)?;
+        self.intermediate_data.as_ref()?.freq_ratios.get(offset).copied()
+    }
+
+    /// Check that all matrices agree with [`Self::num_rows`] and [`Self::num_columns`]
+    pub fn validate(&self) -> Result<(), String> {
+        if self.num_rows <= 0 || self.num_columns <= 0 {
+            return Err(format!("Invalid PSSM dimensions: {}x{}", self.num_rows, self.num_columns));
+        }
+        if self.intermediate_data.is_none() && self.final_data.is_none() {
+            return Err("PSSM contains neither intermediate nor final data".to_string());
+        }
+        if let Some(labels) = &self.row_labels {
+            if labels.len() as i64 != self.num_rows {
+                return Err(format!("Expected {} row labels, found {}", self.num_rows, labels.len()));
+            }
+        }
+
+        let cells = (self.num_rows * self.num_columns) as usize;
+        let check = |name: &str, len: usize| {
+            if len == cells {
+                Ok(())
+            } else {
+                Err(format!(
+                    "Expected {} {} ({} rows x {} columns), found {}",
+                    cells, name, self.num_rows, self.num_columns, len
+                ))
+            }
+        };
+        if let Some(data) = &self.final_data {
+            check("scores", data.scores.len())?;
+        }
+        if let Some(data) = &self.intermediate_data {
+            check("freq_ratios", data.freq_ratios.len())?;
+            if let Some(freqs) = &data.res_freqs_per_pos {
+                check("res_freqs_per_pos", freqs.len())?;
+            }
+            if let Some(freqs) = &data.weighted_res_freqs_per_pos {
+                check("weighted_res_freqs_per_pos", freqs.len())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Serialize as NCBI XML
+    ///
+    /// [`Self::query`] is not written, since it is not parsed.
+    pub fn to_xml(&self) -> String {
+        let mut out = String::new();
+        self.write_xml(&mut out);
+        out
+    }
+
+    fn write_xml(&self, out: &mut String) {
+        out.push_str("<Pssm>");
+        push_bool(out, "Pssm_isProtein", self.is_protein);
+        if let Some(identifier) = &self.identifier {
+            push_node(out, "Pssm_identifier", |out| {
+                push_node(out, "Object-id", |out| push_value(out, "Object-id_str", identifier))
+            });
+        }
+        push_value(out, "Pssm_numRows", self.num_rows);
+        push_value(out, "Pssm_numColumns", self.num_columns);
+        if let Some(labels) = &self.row_labels {
+            push_list(out, "Pssm_rowLabels", labels);
+        }
+        push_bool(out, "Pssm_byRow", self.by_row);
+        if let Some(data) = &self.intermediate_data {
+            push_node(out, "Pssm_intermediateData", |out| data.write_xml(out));
+        }
+        if let Some(data) = &self.final_data {
+            push_node(out, "Pssm_finalData", |out| data.write_xml(out));
+        }
+        out.push_str("</Pssm>");
+    }
+}
+
+impl PssmFinalData {
+    fn write_xml(&self, out: &mut String) {
+        out.push_str("<PssmFinalData>");
+        push_list(out, "PssmFinalData_scores", &self.scores);
+        push_value(out, "PssmFinalData_lambda", self.lambda);
+        push_value(out, "PssmFinalData_kappa", self.kappa);
+        push_value(out, "PssmFinalData_h", self.h);
+        push_value(out, "PssmFinalData_scalingFactor", self.scaling_factor);
+        if let Some(value) = self.lambda_ungapped {
+            push_value(out, "PssmFinalData_lambdaUngapped", value);
+        }
+        if let Some(value) = self.kappa_ungapped {
+            push_value(out, "PssmFinalData_kappaUngapped", value);
+        }
+        if let Some(value) = self.h_ungapped {
+            push_value(out, "PssmFinalData_hUngapped", value);
+        }
+        if let Some(value) = self.word_score_threshold {
+            push_value(out, "PssmFinalData_wordScoreThreshold", value);
+        }
+        out.push_str("</PssmFinalData>");
+    }
+}
+
+impl PssmIntermediateData {
+    fn write_xml(&self, out: &mut String) {
+        out.push_str("<PssmIntermediateData>");
+        if let Some(values) = &self.res_freqs_per_pos {
+            push_list(out, "PssmIntermediateData_resFreqsPerPos", values);
+        }
+        if let Some(values) = &self.weighted_res_freqs_per_pos {
+            push_list(out, "PssmIntermediateData_weightedResFreqsPerPos", values);
+        }
+        push_list(out, "PssmIntermediateData_freqRatios", &self.freq_ratios);
+        if let Some(values) = &self.information_content {
+            push_list(out, "PssmIntermediateData_informationContent", values);
+        }
+        if let Some(values) = &self.gapless_column_weights {
+            push_list(out, "PssmIntermediateData_gaplessColumnWeights", values);
+        }
+        if let Some(values) = &self.sigma {
+            push_list(out, "PssmIntermediateData_sigma", values);
+        }
+        if let Some(values) = &self.interval_sizes {
+            push_list(out, "PssmIntermediateData_intervalSizes", values);
+        }
+        if let Some(values) = &self.num_matching_seqs {
+            push_list(out, "PssmIntermediateData_numMatchingSeqs", values);
+        }
+        if let Some(values) = &self.num_indept_obsr {
+            push_list(out, "PssmIntermediateData_numIndeptObsr", values);
+        }
+        out.push_str("</PssmIntermediateData>");
+    }
+}
+
+impl PssmWithParameters {
+    /// Check the enclosed [`Pssm`]
+    pub fn validate(&self) -> Result<(), String> {
+        self.pssm.validate()
+    }
+
+    /// Serialize as NCBI XML
+    pub fn to_xml(&self) -> String {
+        let mut out = String::new();
+        out.push_str("<PssmWithParameters>");
+        push_node(&mut out, "PssmWithParameters_pssm", |out| self.pssm.write_xml(out));
+        if let Some(params) = &self.params {
+            push_node(&mut out, "PssmWithParameters_params", |out| params.write_xml(out));
+        }
+        out.push_str("</PssmWithParameters>");
+        out
+    }
+}
+
+impl PssmParameters {
+    fn write_xml(&self, out: &mut String) {
+        out.push_str("<PssmParameters>");
+        if let Some(value) = self.pseudocount {
+            push_value(out, "PssmParameters_pseudocount", value);
+        }
+        if let Some(params) = &self.rpsdbparams {
+            push_node(out, "PssmParameters_rpsdbparams", |out| {
+                out.push_str("<FormatRpsDbParameters>");
+                push_value(out, "FormatRpsDbParameters_matrixName", &params.matrix_name);
+                if let Some(value) = params.gap_open {
+                    push_value(out, "FormatRpsDbParameters_gapOpen", value);
+                }
+                if let Some(value) = params.gap_extend {
+                    push_value(out, "FormatRpsDbParameters_gapExtend", value);
+                }
+                out.push_str("</FormatRpsDbParameters>");
+            });
+        }
+        if let Some(constraints) = &self.constraints {
+            push_node(out, "PssmParameters_constraints", |out| constraints.write_xml(out));
+        }
+        if let Some(value) = self.bit_score_thresh {
+            push_value(out, "PssmParameters_bitScoreThresh", value);
+        }
+        if let Some(value) = self.bit_score_reporting_thresh {
+            push_value(out, "PssmParameters_bitScoreReportingThresh", value);
+        }
+        if let Some(sites) = &self.annotated_sites {
+            push_node(out, "PssmParameters_annotatedSites", |out| {
+                out.push_str("<Site-annot-set>");
+                sites.iter().for_each(|site| site.write_xml(out));
+                out.push_str("</Site-annot-set>");
+            });
+        }
+        out.push_str("</PssmParameters>");
+    }
+}
+
+impl CoreDef {
+    fn write_xml(&self, out: &mut String) {
+        out.push_str("<CoreDef>");
+        push_value(out, "CoreDef_nblocks", self.nblocks);
+        push_node(out, "CoreDef_blocks", |out| {
+            for block in &self.blocks {
+                out.push_str("<CoreBlock>");
+                push_value(out, "CoreBlock_start", block.start);
+                push_value(out, "CoreBlock_stop", block.stop);
+                if let Some(value) = block.minstart {
+                    push_value(out, "CoreBlock_minstart", value);
+                }
+                if let Some(value) = block.maxstop {
+                    push_value(out, "CoreBlock_maxstop", value);
+                }
+                if let Some(properties) = &block.property {
+                    push_node(out, "CoreBlock_property", |out| {
+                        for property in properties {
+                            out.push_str("<BlockProperty>");
+                            push_value(out, "BlockProperty_type", property.r#type.clone() as u8);
+                            if let Some(value) = property.intvalue {
+                                push_value(out, "BlockProperty_intvalue", value);
+                            }
+                            if let Some(value) = &property.textvalue {
+                                push_value(out, "BlockProperty_textvalue", value);
+                            }
+                            out.push_str("</BlockProperty>");
+                        }
+                    });
+                }
+                out.push_str("</CoreBlock>");
+            }
+        });
+        push_node(out, "CoreDef_loops", |out| {
+            for constraint in &self.loops {
+                out.push_str("<LoopConstraint>");
+                push_value(out, "LoopConstraint_minlength", constraint.minlength);
+                push_value(out, "LoopConstraint_maxlength", constraint.maxlength);
+                out.push_str("</LoopConstraint>");
+            }
+        });
+        if let Some(value) = self.is_discontinuous {
+            push_bool(out, "CoreDef_isDiscontinuous", value);
+        }
+        if let Some(values) = &self.insertions {
+            push_list(out, "CoreDef_insertions", values);
+        }
+        out.push_str("</CoreDef>");
+    }
+}
+
+impl SiteAnnot {
+    fn write_xml(&self, out: &mut String) {
+        out.push_str("<Site-annot>");
+        push_value(out, "Site-annot_startPosition", self.start_position);
+        push_value(out, "Site-annot_stopPosition", self.stop_position);
+        if let Some(value) = &self.description {
+            push_value(out, "Site-annot_description", value);
+        }
+        if let Some(value) = self.r#type {
+            push_value(out, "Site-annot_type", value);
+        }
+        if let Some(values) = &self.aliases {
+            push_list(out, "Site-annot_aliases", values);
+        }
+        if let Some(value) = &self.motif {
+            push_value(out, "Site-annot_motif", value);
+        }
+        if let Some(value) = self.motif_use {
+            push_value(out, "Site-annot_motifuse", value);
+        }
+        out.push_str("</Site-annot>");
+    }
+}
+
+fn push_value<T: Display>(out: &mut String, tag: &str, value: T) {
+    out.push_str(&format!("<{tag}>{}</{tag}>", escape(&value.to_string())));
+}
+
+fn push_bool(out: &mut String, tag: &str, value: bool) {
+    out.push_str(&format!("<{tag} value=\"{value}\"/>"));
+}
+
+fn push_list<T: Display>(out: &mut String, tag: &str, values: &[T]) {
+    push_node(out, tag, |out| {
+        values.iter().for_each(|value| push_value(out, &format!("{tag}_E"), value))
+    });
+}
+
+fn push_node<F: FnOnce(&mut String)>(out: &mut String, tag: &str, content: F) {
+    out.push_str(&format!("<{tag}>"));
+    content(out);
+    out.push_str(&format!("</{tag}>"));
+}
+
+/// Parse the text of each element enclosed by `end`, unescaping entities
+///
+/// Unlike [`crate::parsing::read_vec_str_unchecked()`], labels and aliases may contain markup
+/// characters and non-ASCII text.
+fn read_labels(reader: &mut Reader<&[u8]>, end: &BytesEnd) -> Option<Vec<String>> {
+    let mut labels = Vec::new();
+    loop {
+        match next_event(reader)? {
+            Event::Text(text) => {
+                let text = text.unescape().ok()?;
+                let text = text.trim();
+                if !text.is_empty() {
+                    labels.push(text.to_string());
+                }
+            }
+            Event::End(e) if e.name() == end.name() => return labels.into(),
+            _ => (),
+        }
+    }
+}
+
+/// Parse the next text as `f64`
+fn read_f64(reader: &mut Reader<&[u8]>) -> Option<f64> {
+    read_real(reader)?.parse().ok()
+}
+
+impl XmlNode for Pssm {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("Pssm")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> {
+        let mut pssm = Self {
+            is_protein: true,
+            ..Self::default()
+        };
+        let mut num_rows = None;
+        let mut num_columns = None;
+
+        let forbidden = UnexpectedTags(&[BytesStart::new("Pssm_query")]);
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => match qualified_name(e.name(), Self::start_bytes().name()).as_ref() {
+                    b"Pssm_identifier" => {
+                        pssm.identifier = read_node(reader).map(|id| match id {
+                            ObjectId::Id(id) => id.to_string(),
+                            ObjectId::Str(id) => id.into(),
+                        })
+                    }
+                    b"Pssm_numRows" => num_rows = read_int(reader),
+                    b"Pssm_numColumns" => num_columns = read_int(reader),
+                    b"Pssm_rowLabels" => {
+                        pssm.row_labels = read_labels(reader, &e.to_end())
+                    }
+                    b"Pssm_intermediateData" => pssm.intermediate_data = read_node(reader),
+                    b"Pssm_finalData" => pssm.final_data = read_node(reader),
+                    b"Pssm" => (),
+                    _ => {
+                        forbidden.check(&e.name());
+                        reader.read_to_end(e.name()).ok()?;
+                    }
+                },
+                Event::Empty(e) => match qualified_name(e.name(), Self::start_bytes().name()).as_ref() {
+                    b"Pssm_isProtein" => pssm.is_protein = read_bool_attribute(&e).unwrap_or(true),
+                    b"Pssm_byRow" => pssm.by_row = read_bool_attribute(&e).unwrap_or_default(),
+                    _ => (),
+                },
+                Event::End(e) if Self::is_end(&e) => {
+                    pssm.num_rows = num_rows?;
+                    pssm.num_columns = num_columns?;
+                    return pssm.into();
+                }
+                _ => (),
+            }
+        }
+    }
+}
+
+impl XmlNode for PssmFinalData {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("PssmFinalData")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> {
+        let mut data = Self {
+            scaling_factor: 1,
+            ..Self::default()
+        };
+        let mut lambda = None;
+        let mut kappa = None;
+        let mut h = None;
+
+        let forbidden = UnexpectedTags(&[]);
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => match qualified_name(e.name(), Self::start_bytes().name()).as_ref() {
+                    b"PssmFinalData_scores" => data.scores = read_vec_int_unchecked(reader, &e.to_end()),
+                    b"PssmFinalData_lambda" => lambda = read_f64(reader),
+                    b"PssmFinalData_kappa" => kappa = read_f64(reader),
+                    b"PssmFinalData_h" => h = read_f64(reader),
+                    b"PssmFinalData_scalingFactor" => data.scaling_factor = read_int(reader)?,
+                    b"PssmFinalData_lambdaUngapped" => data.lambda_ungapped = read_f64(reader),
+                    b"PssmFinalData_kappaUngapped" => data.kappa_ungapped = read_f64(reader),
+                    b"PssmFinalData_hUngapped" => data.h_ungapped = read_f64(reader),
+                    b"PssmFinalData_wordScoreThreshold" => data.word_score_threshold = read_f64(reader),
+                    b"PssmFinalData" => (),
+                    _ => forbidden.check(&e.name()),
+                },
+                Event::End(e) if Self::is_end(&e) => {
+                    data.lambda = lambda?;
+                    data.kappa = kappa?;
+                    data.h = h?;
+                    return data.into();
+                }
+                _ => (),
+            }
+        }
+    }
+}
+
+impl XmlNode for PssmIntermediateData {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("PssmIntermediateData")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> {
+        let mut data = Self::default();
+
+        let forbidden = UnexpectedTags(&[]);
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => {
+                    let end = e.to_end();
+                    match qualified_name(e.name(), Self::start_bytes().name()).as_ref() {
+                        b"PssmIntermediateData_resFreqsPerPos" => {
+                            data.res_freqs_per_pos = read_vec_int_unchecked(reader, &end).into()
+                        }
+                        b"PssmIntermediateData_weightedResFreqsPerPos" => {
+                            data.weighted_res_freqs_per_pos = read_vec_int_unchecked(reader, &end).into()
+                        }
+                        b"PssmIntermediateData_freqRatios" => {
+                            data.freq_ratios = read_vec_int_unchecked(reader, &end)
+                        }
+                        b"PssmIntermediateData_informationContent" => {
+                            data.information_content = read_vec_int_unchecked(reader, &end).into()
+                        }
+                        b"PssmIntermediateData_gaplessColumnWeights" => {
+                            data.gapless_column_weights = read_vec_int_unchecked(reader, &end).into()
+                        }
+                        b"PssmIntermediateData_sigma" => {
+                            data.sigma = read_vec_int_unchecked(reader, &end).into()
+                        }
+                        b"PssmIntermediateData_intervalSizes" => {
+                            data.interval_sizes = read_vec_int_unchecked(reader, &end).into()
+                        }
+                        b"PssmIntermediateData_numMatchingSeqs" => {
+                            data.num_matching_seqs = read_vec_int_unchecked(reader, &end).into()
+                        }
+                        b"PssmIntermediateData_numIndeptObsr" => {
+                            data.num_indept_obsr = read_vec_int_unchecked(reader, &end).into()
+                        }
+                        b"PssmIntermediateData" => (),
+                        _ => forbidden.check(&e.name()),
+                    }
+                }
+                Event::End(e) if Self::is_end(&e) => {
+                    return data.into();
+                }
+                _ => (),
+            }
+        }
+    }
+}
+
+impl XmlNode for PssmWithParameters {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("PssmWithParameters")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> {
+        let mut pssm = None;
+        let mut params = None;
+
+        let forbidden = UnexpectedTags(&[]);
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => match qualified_name(e.name(), Self::start_bytes().name()).as_ref() {
+                    b"PssmWithParameters_pssm" => pssm = read_node(reader),
+                    b"PssmWithParameters_params" => params = read_node(reader),
+                    b"PssmWithParameters" => (),
+                    _ => forbidden.check(&e.name()),
+                },
+                Event::End(e) if Self::is_end(&e) => {
+                    return Self { pssm: pssm?, params }.into();
+                }
+                _ => (),
+            }
+        }
+    }
+}
+
+impl XmlNode for PssmParameters {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("PssmParameters")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> {
+        let mut params = Self::default();
+
+        let forbidden = UnexpectedTags(&[]);
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => match qualified_name(e.name(), Self::start_bytes().name()).as_ref() {
+                    b"PssmParameters_pseudocount" => params.pseudocount = read_int(reader),
+                    b"PssmParameters_rpsdbparams" => params.rpsdbparams = read_node(reader),
+                    b"PssmParameters_constraints" => params.constraints = read_node(reader),
+                    b"PssmParameters_bitScoreThresh" => params.bit_score_thresh = read_f64(reader),
+                    b"PssmParameters_bitScoreReportingThresh" => {
+                        params.bit_score_reporting_thresh = read_f64(reader)
+                    }
+                    b"PssmParameters_annotatedSites" => {
+                        params.annotated_sites = read_vec_node(reader, e.to_end()).into()
+                    }
+                    b"PssmParameters" => (),
+                    _ => forbidden.check(&e.name()),
+                },
+                Event::End(e) if Self::is_end(&e) => {
+                    return params.into();
+                }
+                _ => (),
+            }
+        }
+    }
+}
+
+impl XmlNode for FormatRpsDbParameters {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("FormatRpsDbParameters")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> {
+        let mut matrix_name = None;
+        let mut gap_open = None;
+        let mut gap_extend = None;
+
+        let forbidden = UnexpectedTags(&[]);
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => match qualified_name(e.name(), Self::start_bytes().name()).as_ref() {
+                    b"FormatRpsDbParameters_matrixName" => matrix_name = read_string(reader),
+                    b"FormatRpsDbParameters_gapOpen" => gap_open = read_int(reader),
+                    b"FormatRpsDbParameters_gapExtend" => gap_extend = read_int(reader),
+                    b"FormatRpsDbParameters" => (),
+                    _ => forbidden.check(&e.name()),
+                },
+                Event::End(e) if Self::is_end(&e) => {
+                    return Self {
+                        matrix_name: matrix_name?,
+                        gap_open,
+                        gap_extend,
+                    }
+                    .into();
+                }
+                _ => (),
+            }
+        }
+    }
+}
+
+impl XmlNode for CoreDef {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("CoreDef")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> {
+        let mut def = Self::default();
+        let mut nblocks = None;
+
+        let forbidden = UnexpectedTags(&[]);
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => match qualified_name(e.name(), Self::start_bytes().name()).as_ref() {
+                    b"CoreDef_nblocks" => nblocks = read_int(reader),
+                    b"CoreDef_blocks" => def.blocks = read_vec_node(reader, e.to_end()),
+                    b"CoreDef_loops" => def.loops = read_vec_node(reader, e.to_end()),
+                    b"CoreDef_insertions" => {
+                        def.insertions = read_vec_int_unchecked(reader, &e.to_end()).into()
+                    }
+                    b"CoreDef" => (),
+                    _ => forbidden.check(&e.name()),
+                },
+                Event::Empty(e) if tag_matches(e.name().as_ref(), b"CoreDef_isDiscontinuous") => {
+                    def.is_discontinuous = read_bool_attribute(&e);
+                }
+                Event::End(e) if Self::is_end(&e) => {
+                    def.nblocks = nblocks?;
+                    return def.into();
+                }
+                _ => (),
+            }
+        }
+    }
+}
+
+impl XmlNode for CoreBlock {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("CoreBlock")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> {
+        let mut start = None;
+        let mut stop = None;
+        let mut minstart = None;
+        let mut maxstop = None;
+        let mut property = None;
+
+        let forbidden = UnexpectedTags(&[]);
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => match qualified_name(e.name(), Self::start_bytes().name()).as_ref() {
+                    b"CoreBlock_start" => start = read_int(reader),
+                    b"CoreBlock_stop" => stop = read_int(reader),
+                    b"CoreBlock_minstart" => minstart = read_int(reader),
+                    b"CoreBlock_maxstop" => maxstop = read_int(reader),
+                    b"CoreBlock_property" => property = read_vec_node(reader, e.to_end()).into(),
+                    b"CoreBlock" => (),
+                    _ => forbidden.check(&e.name()),
+                },
+                Event::End(e) if Self::is_end(&e) => {
+                    return Self {
+                        start: start?,
+                        stop: stop?,
+                        minstart,
+                        maxstop,
+                        property,
+                    }
+                    .into();
+                }
+                _ => (),
+            }
+        }
+    }
+}
+
+impl XmlVecNode for CoreBlock {}
+
+impl XmlNode for BlockProperty {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("BlockProperty")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> {
+        let mut r#type = None;
+        let mut intvalue = None;
+        let mut textvalue = None;
+
+        let forbidden = UnexpectedTags(&[]);
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => match qualified_name(e.name(), Self::start_bytes().name()).as_ref() {
+                    b"BlockProperty_type" => {
+                        r#type = read_int::<u8>(reader).and_then(BlockPropertyType::from_u8)
+                    }
+                    b"BlockProperty_intvalue" => intvalue = read_int(reader),
+                    b"BlockProperty_textvalue" => textvalue = read_string(reader),
+                    b"BlockProperty" => (),
+                    _ => forbidden.check(&e.name()),
+                },
+                Event::End(e) if Self::is_end(&e) => {
+                    return Self {
+                        r#type: r#type?,
+                        intvalue,
+                        textvalue,
+                    }
+                    .into();
+                }
+                _ => (),
+            }
+        }
+    }
+}
+
+impl XmlVecNode for BlockProperty {}
+
+impl XmlNode for LoopConstraint {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("LoopConstraint")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> {
+        let mut constraint = Self {
+            minlength: 0,
+            maxlength: default_max_length(),
+        };
+
+        let forbidden = UnexpectedTags(&[]);
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => match qualified_name(e.name(), Self::start_bytes().name()).as_ref() {
+                    b"LoopConstraint_minlength" => constraint.minlength = read_int(reader)?,
+                    b"LoopConstraint_maxlength" => constraint.maxlength = read_int(reader)?,
+                    b"LoopConstraint" => (),
+                    _ => forbidden.check(&e.name()),
+                },
+                Event::End(e) if Self::is_end(&e) => {
+                    return constraint.into();
+                }
+                _ => (),
+            }
+        }
+    }
+}
+
+impl XmlVecNode for LoopConstraint {}
+
+impl XmlNode for SiteAnnot {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("Site-annot")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> {
+        let mut site = Self::default();
+        let mut start_position = None;
+        let mut stop_position = None;
+
+        let forbidden = UnexpectedTags(&[]);
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => match qualified_name(e.name(), Self::start_bytes().name()).as_ref() {
+                    b"Site-annot_startPosition" => start_position = read_int(reader),
+                    b"Site-annot_stopPosition" => stop_position = read_int(reader),
+                    b"Site-annot_description" => site.description = read_string(reader),
+                    b"Site-annot_type" => site.r#type = read_int(reader),
+                    b"Site-annot_aliases" => {
+                        site.aliases = read_labels(reader, &e.to_end())
+                    }
+                    b"Site-annot_motif" => site.motif = read_string(reader),
+                    b"Site-annot_motifuse" => site.motif_use = read_int(reader),
+                    b"Site-annot" => (),
+                    _ => forbidden.check(&e.name()),
+                },
+                Event::End(e) if Self::is_end(&e) => {
+                    site.start_position = start_position?;
+                    site.stop_position = stop_position?;
+                    return site.into();
+                }
+                _ => (),
+            }
+        }
+    }
+}
+
+impl XmlVecNode for SiteAnnot {}
@@ -4,22 +4,26 @@
 //!
 //! Adapted from ["seq.asn"](https://www.ncbi.nlm.nih.gov/IEB/ToolBox/CPP_DOC/lxr/source/src/objects/seq/seq.asn)
 
+use crate::eutils::{open_local, parse_xml, DataType};
 use crate::general::{Date, DbTag, IntFuzz, ObjectId, UserObject};
-use crate::parsing::{read_vec_node, read_attributes, read_int, read_node, read_string, UnexpectedTags, attribute_value};
-use crate::r#pub::PubEquiv;
+use crate::parsing::{next_event, read_vec_node, read_attributes, read_bool_attribute, read_int, read_node, read_string, UnexpectedTags, attribute_value};
+use crate::r#pub::{best_pub, CitationStyle, Pub, PubEquiv};
 use crate::seqalign::SeqAlign;
 use crate::seqblock::{EMBLBlock, GBBlock, PDBBlock, PIRBlock, PRFBlock, SPBlock};
-use crate::seqfeat::{BioSource, ModelEvidenceSupport, OrgRef, SeqFeat};
-use crate::seqloc::{SeqId, SeqLoc};
+use crate::seqfeat::{BioSource, FeatId, ModelEvidenceSupport, OrgRef, SeqFeat, SeqFeatData};
+use crate::seqloc::{NaStrand, SeqId, SeqInterval, SeqLoc};
 use crate::seqres::SeqGraph;
+use crate::seqtools::reverse_complement;
 use crate::seqtable::SeqTable;
-use crate::parsing::{XmlNode, XmlVecNode, XmlValue};
+use crate::parsing::{read_raw, TagMatch, XmlNode, XmlVecNode, XmlValue};
 use enum_primitive::FromPrimitive;
 use quick_xml::events::{BytesStart, Event};
 use quick_xml::events::attributes::Attributes;
 use quick_xml::Reader;
 use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
+use std::io::Read;
+use std::path::Path;
 
 /// Single continuous biological sequence.
 ///
@@ -53,6 +57,166 @@ pub struct BioSeq {
     pub annot: Option<Vec<SeqAnnot>>,
 }
 
+impl BioSeq {
+    /// Source organism given by [`SeqDesc::Source`]
+    pub fn source(&self) -> Option<&BioSource> {
+        self.descr.iter().flatten().find_map(|desc| match desc {
+            SeqDesc::Source(source) => Some(source),
+            _ => None,
+        })
+    }
+
+    /// Whether the sequence is a protein, as given by [`SeqInst::mol`]
+    pub fn is_protein(&self) -> bool {
+        self.inst.as_ref().is_some_and(|inst| inst.mol == Mol::AA)
+    }
+
+    /// Sequence history given by [`SeqInst::hist`]
+    pub fn history(&self) -> Option<&SeqHist> {
+        self.inst.as_ref().and_then(|inst| inst.hist.as_ref())
+    }
+
+    /// Whether the record has been withdrawn, as marked by [`SeqHist::deleted`]
+    pub fn is_suppressed(&self) -> bool {
+        self.history().is_some_and(SeqHist::is_deleted)
+    }
+
+    /// Ids of the sequences which replace this one
+    pub fn replaced_by_ids(&self) -> &[SeqId] {
+        self.history()
+            .and_then(|hist| hist.replaced_by.as_ref())
+            .map(|rec| rec.ids.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Ids of the sequences which this one replaces
+    pub fn replaces_ids(&self) -> &[SeqId] {
+        self.history()
+            .and_then(|hist| hist.replaces.as_ref())
+            .map(|rec| rec.ids.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Gaps of a delta sequence, as `(start, length, type)`
+    ///
+    /// Literals without sequence data are gaps of [`SeqGapType::Unknown`] type. The
+    /// start of a gap is only known if the lengths of all components before it are
+    /// known, so gaps following a [`SeqLoc::Whole`] component are omitted.
+    pub fn gaps(&self) -> Vec<(u64, u64, SeqGapType)> {
+        let mut gaps = Vec::new();
+        let mut start = Some(0);
+        for part in self.delta_parts() {
+            let length = match part {
+                DeltaSeq::Loc(loc) => delta_loc_length(loc),
+                DeltaSeq::Literal(literal) => {
+                    if let (Some(start), Some(r#type)) = (start, literal.gap_type()) {
+                        gaps.push((start, literal.length, r#type));
+                    }
+                    Some(literal.length)
+                }
+            };
+            start = start.zip(length).map(|(start, length)| start + length);
+        }
+        gaps
+    }
+
+    /// Number of gaps of a delta sequence
+    pub fn gap_count(&self) -> usize {
+        self.delta_parts()
+            .iter()
+            .filter(|part| matches!(part, DeltaSeq::Literal(literal) if literal.is_gap()))
+            .count()
+    }
+
+    /// Length of the sequence, excluding the gaps of a delta sequence
+    pub fn ungapped_length(&self) -> Option<u64> {
+        let length = self.inst.as_ref()?.length?;
+        let gaps: u64 = self
+            .delta_parts()
+            .iter()
+            .filter_map(|part| match part {
+                DeltaSeq::Literal(literal) if literal.is_gap() => Some(literal.length),
+                _ => None,
+            })
+            .sum();
+        Some(length.saturating_sub(gaps))
+    }
+
+    fn delta_parts(&self) -> &[DeltaSeq] {
+        match self.inst.as_ref().and_then(|inst| inst.ext.as_ref()) {
+            Some(SeqExt::Delta(parts)) => parts.as_slice(),
+            _ => &[],
+        }
+    }
+
+    /// Extract the residues described by `loc` as an IUPAC string
+    ///
+    /// Intervals on the minus strand are reverse complemented, and the parts of a
+    /// [`SeqLoc::Mix`] or [`SeqLoc::PackedInt`] are concatenated in the order given.
+    /// Every [`SeqId`] referenced by `loc` must be one of `self.id`; locations that
+    /// point into other sequences cannot be resolved here and return an error.
+    pub fn extract(&self, loc: &SeqLoc) -> Result<String, String> {
+        let residues = match &self.inst {
+            Some(inst) => inst.residues()?,
+            None => return Err("Bioseq has no instance data".to_string()),
+        };
+        self.extract_loc(residues.as_str(), loc)
+    }
+
+    fn extract_loc(&self, residues: &str, loc: &SeqLoc) -> Result<String, String> {
+        match loc {
+            SeqLoc::Null | SeqLoc::Empty(_) => Ok(String::new()),
+            SeqLoc::Whole(id) => {
+                self.check_id(id)?;
+                Ok(residues.to_string())
+            }
+            SeqLoc::Int(interval) => self.extract_interval(residues, interval),
+            SeqLoc::PackedInt(intervals) => intervals
+                .iter()
+                .map(|interval| self.extract_interval(residues, interval))
+                .collect(),
+            SeqLoc::Pnt(point) => {
+                self.check_id(&point.id)?;
+                extract_range(residues, point.point, point.point, point.strand.as_ref())
+            }
+            SeqLoc::Mix(mix) => mix
+                .0
+                .iter()
+                .map(|loc| self.extract_loc(residues, loc))
+                .collect(),
+            _ => Err("Unsupported Seq-loc variant for extraction".to_string()),
+        }
+    }
+
+    fn extract_interval(&self, residues: &str, interval: &SeqInterval) -> Result<String, String> {
+        self.check_id(&interval.id)?;
+        extract_range(residues, interval.from, interval.to, interval.strand.as_ref())
+    }
+
+    fn check_id(&self, id: &SeqId) -> Result<(), String> {
+        if self.id.contains(id) {
+            Ok(())
+        } else {
+            Err(format!("Seq-loc references a different sequence: {:?}", id))
+        }
+    }
+}
+
+/// Slice `residues` using inclusive, 0-based coordinates
+pub(crate) fn extract_range(residues: &str, from: i64, to: i64, strand: Option<&NaStrand>) -> Result<String, String> {
+    if from < 0 || to < from || to as usize >= residues.len() {
+        return Err(format!(
+            "Interval {}..{} is out of bounds for sequence of length {}",
+            from, to, residues.len()
+        ));
+    }
+    let slice = &residues[from as usize..=to as usize];
+    match strand {
+        Some(NaStrand::Minus) | Some(NaStrand::BothRev) => Ok(reverse_complement(slice)),
+        _ => Ok(slice.to_string()),
+    }
+}
+
 impl XmlNode for BioSeq {
     fn start_bytes() -> BytesStart<'static> {
         BytesStart::new("Bioseq")
@@ -67,17 +231,17 @@ impl XmlNode for BioSeq {
         let annot_elem = BytesStart::new("Bioseq_annot");
 
         loop {
-            match reader.read_event().unwrap() {
+            match next_event(reader)? {
                 Event::Start(e) => {
                     let name = e.name();
 
-                    if name == id_elem.name() {
+                    if name.is_tag(id_elem.name()) {
                         bioseq.id = read_vec_node(reader, id_elem.to_end());
-                    } else if name == descr_elem.name() {
+                    } else if name.is_tag(descr_elem.name()) {
                         bioseq.descr = read_node(reader);
-                    } else if name == inst_elem.name() {
+                    } else if name.is_tag(inst_elem.name()) {
                         bioseq.inst = read_node(reader);
-                    } else if name == annot_elem.name() {
+                    } else if name.is_tag(annot_elem.name()) {
                         bioseq.annot = Some(read_vec_node(reader, annot_elem.to_end()));
                     }
                 }
@@ -94,6 +258,74 @@ impl XmlNode for BioSeq {
 
 pub type SeqDescr = Vec<SeqDesc>;
 
+/// Accessors for commonly used [`SeqDesc`] variants
+///
+/// Implemented for [`SeqDescr`] and any object which contains one, so that
+/// descriptors can be queried without matching each [`SeqDesc`] by hand.
+pub trait Descriptors {
+    /// All descriptors of this object
+    fn descriptors(&self) -> &[SeqDesc];
+
+    /// First [`SeqDesc::Title`]
+    fn title(&self) -> Option<&str> {
+        self.descriptors().iter().find_map(|desc| match desc {
+            SeqDesc::Title(title) => Some(title.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Source organism, given by [`SeqDesc::Source`] or the deprecated [`SeqDesc::Org`]
+    #[allow(deprecated)]
+    fn organism(&self) -> Option<&OrgRef> {
+        self.descriptors().iter().find_map(|desc| match desc {
+            SeqDesc::Source(source) => Some(&source.org),
+            SeqDesc::Org(org) => Some(org),
+            _ => None,
+        })
+    }
+
+    /// NCBI Taxonomy id of [`Self::organism()`]
+    fn taxid(&self) -> Option<u64> {
+        self.organism()?.taxid()
+    }
+
+    /// First [`SeqDesc::MolInfo`]
+    fn molinfo(&self) -> Option<&MolInfo> {
+        self.descriptors().iter().find_map(|desc| match desc {
+            SeqDesc::MolInfo(molinfo) => Some(molinfo),
+            _ => None,
+        })
+    }
+
+    /// Date entry was first created or released
+    fn create_date(&self) -> Option<&Date> {
+        self.descriptors().iter().find_map(|desc| match desc {
+            SeqDesc::CreateDate(date) => Some(date),
+            _ => None,
+        })
+    }
+
+    /// Date of last update
+    fn update_date(&self) -> Option<&Date> {
+        self.descriptors().iter().find_map(|desc| match desc {
+            SeqDesc::UpdateDate(date) => Some(date),
+            _ => None,
+        })
+    }
+}
+
+impl Descriptors for SeqDescr {
+    fn descriptors(&self) -> &[SeqDesc] {
+        self.as_slice()
+    }
+}
+
+impl Descriptors for BioSeq {
+    fn descriptors(&self) -> &[SeqDesc] {
+        self.descr.as_deref().unwrap_or(&[])
+    }
+}
+
 impl XmlNode for SeqDescr {
     fn start_bytes() -> BytesStart<'static> {
         BytesStart::new("Seq-descr")
@@ -170,6 +402,12 @@ pub enum SeqDesc {
     MolInfo(MolInfo),
     /// model evidence for XM records
     ModelEv(ModelEvidenceSupport),
+    /// descriptor which is not parsed, kept as XML (eg: `<Seqdesc_genbank>...`)
+    ///
+    /// Holds the choice element as given in the input, so that it may be written back
+    /// within a `<Seqdesc>` element without loss. Only produced with the `raw-xml`
+    /// feature, otherwise such descriptors are skipped.
+    Unknown(String),
 }
 
 impl XmlNode for SeqDesc {
@@ -186,25 +424,40 @@ impl XmlNode for SeqDesc {
         let user_element = BytesStart::new("Seqdesc_user");
         let create_element = BytesStart::new("Seqdesc_create-date");
         let update_element = BytesStart::new("Seqdesc_update-date");
+        let title_element = BytesStart::new("Seqdesc_title");
+        let name_element = BytesStart::new("Seqdesc_name");
+        let region_element = BytesStart::new("Seqdesc_region");
+        let modelev_element = BytesStart::new("Seqdesc_modelev");
 
         loop {
-            match reader.read_event().unwrap() {
+            match next_event(reader)? {
                 Event::Start(e) => {
                     let name = e.name();
-                    if name == source_element.name() {
-                        return Self::Source(read_node(reader).unwrap()).into();
-                    } else if name == molinfo_element.name() {
-                        return Self::MolInfo(read_node(reader).unwrap()).into();
-                    } else if name == pub_element.name() {
-                        return Self::Pub(read_node(reader).unwrap()).into();
-                    } else if name == comment_element.name() {
-                        return Self::Comment(read_string(reader).unwrap()).into();
-                    } else if name == user_element.name() {
-                        return Self::User(read_node(reader).unwrap()).into();
-                    } else if name == create_element.name() {
-                        return Self::CreateDate(read_node(reader).unwrap()).into()
-                    } else if name == update_element.name() {
-                        return Self::UpdateDate(read_node(reader).unwrap()).into()
+                    if name.is_tag(source_element.name()) {
+                        return Self::Source(read_node(reader)?).into();
+                    } else if name.is_tag(molinfo_element.name()) {
+                        return Self::MolInfo(read_node(reader)?).into();
+                    } else if name.is_tag(pub_element.name()) {
+                        return Self::Pub(read_node(reader)?).into();
+                    } else if name.is_tag(comment_element.name()) {
+                        return Self::Comment(read_string(reader)?).into();
+                    } else if name.is_tag(user_element.name()) {
+                        return Self::User(read_node(reader)?).into();
+                    } else if name.is_tag(create_element.name()) {
+                        return Self::CreateDate(read_node(reader)?).into()
+                    } else if name.is_tag(update_element.name()) {
+                        return Self::UpdateDate(read_node(reader)?).into()
+                    } else if name.is_tag(title_element.name()) {
+                        return Self::Title(read_string(reader)?).into()
+                    } else if name.is_tag(name_element.name()) {
+                        return Self::Name(read_string(reader)?).into()
+                    } else if name.is_tag(region_element.name()) {
+                        return Self::Region(read_string(reader)?).into()
+                    } else if name.is_tag(modelev_element.name()) {
+                        return Self::ModelEv(read_node(reader)?).into()
+                    }
+                    if cfg!(feature = "raw-xml") {
+                        return Self::Unknown(read_raw(reader, &e)?).into();
                     }
                 }
                 Event::End(e) => {
@@ -261,6 +514,26 @@ enum_from_primitive! {
     }
 }
 
+asn_names!(BioMol {
+    Unknown => "unknown",
+    Genomic => "genomic",
+    PreRNA => "pre-RNA",
+    mRNA => "mRNA",
+    rRNA => "rRNA",
+    tRNA => "tRNA",
+    snRNA => "snRNA",
+    scRNA => "scRNA",
+    Peptide => "peptide",
+    OtherGenetic => "other-genetic",
+    Genomic_mRNA => "genomic-mRNA",
+    cRNA => "cRNA",
+    snoRNA => "snoRNA",
+    TranscribedRNA => "transcribed-RNA",
+    ncRNA => "ncRNA",
+    tmRNA => "tmRNA",
+    Other => "other",
+});
+
 impl XmlNode for BioMol {
     fn start_bytes() -> BytesStart<'static> {
         BytesStart::new("MolInfo_biomol")
@@ -270,7 +543,7 @@ impl XmlNode for BioMol {
     where
         Self: Sized,
     {
-        BioMol::from_u8(read_int::<u8>(reader).unwrap())
+        BioMol::from_u8(read_int::<u8>(reader)?)
     }
 }
 
@@ -340,6 +613,35 @@ enum_from_primitive! {
     }
 }
 
+asn_names!(MolTech {
+    Unknown => "unknown",
+    Standard => "standard",
+    EST => "est",
+    STS => "sts",
+    Survey => "survey",
+    GeneMap => "genemap",
+    PhysMap => "physmap",
+    Derived => "derived",
+    ConceptTrans => "concept-trans",
+    SeqPept => "seq-pept",
+    Both => "both",
+    SeqPeptOverlap => "seq-pept-overlap",
+    SeqPeptHomol => "seq-pept-homol",
+    ConceptTransA => "concept-trans-a",
+    HTGS1 => "htgs-1",
+    HTGS2 => "htgs-2",
+    HTGS3 => "htgs-3",
+    FLI_cDNA => "fli-cDNA",
+    HTGS0 => "htgs-0",
+    HTC => "htc",
+    WGS => "wgs",
+    Barcode => "barcode",
+    CompositeWgsHtgs => "composite-wgs-htgs",
+    TSA => "tsa",
+    Targeted => "targeted",
+    Other => "other",
+});
+
 impl XmlNode for MolTech {
     fn start_bytes() -> BytesStart<'static> {
         BytesStart::new("MolInfo_tech")
@@ -349,34 +651,61 @@ impl XmlNode for MolTech {
     where
         Self: Sized,
     {
-        MolTech::from_u8(read_int::<u8>(reader).unwrap())
+        MolTech::from_u8(read_int::<u8>(reader)?)
     }
 }
 
-#[derive(Clone, Serialize_repr, Deserialize_repr, PartialEq, Debug, Default)]
-#[repr(u8)]
-/// Capture sequence completeness.
-///
-/// Completeness is not indicated in most records. For genomes, assume
-/// the sequences are incomplete unless specifically marked as complete.
-/// For mRNAs, assume the ends are not known exactly unless marked as having
-/// the left or right end.
-///
-/// # Note
-///
-/// Original implementation lists this as `INTEGER`, therefore it is assumed that
-/// serialized representation is an integer
-pub enum MolCompleteness {
-    #[default]
-    Unknown,
-    Complete,
-    Partial,
-    NoLeft,
-    NoRight,
-    NoEnds,
-    HasLeft,
-    HasRight,
-    Other = 255,
+enum_from_primitive! {
+    #[derive(Clone, Serialize_repr, Deserialize_repr, PartialEq, Debug, Default)]
+    #[repr(u8)]
+    /// Capture sequence completeness.
+    ///
+    /// Completeness is not indicated in most records. For genomes, assume
+    /// the sequences are incomplete unless specifically marked as complete.
+    /// For mRNAs, assume the ends are not known exactly unless marked as having
+    /// the left or right end.
+    ///
+    /// # Note
+    ///
+    /// Original implementation lists this as `INTEGER`, therefore it is assumed that
+    /// serialized representation is an integer
+    pub enum MolCompleteness {
+        #[default]
+        Unknown,
+        Complete,
+        Partial,
+        NoLeft,
+        NoRight,
+        NoEnds,
+        HasLeft,
+        HasRight,
+        Other = 255,
+    }
+}
+
+asn_names!(MolCompleteness {
+    Unknown => "unknown",
+    Complete => "complete",
+    Partial => "partial",
+    NoLeft => "no-left",
+    NoRight => "no-right",
+    NoEnds => "no-ends",
+    HasLeft => "has-left",
+    HasRight => "has-right",
+    Other => "other",
+});
+
+impl XmlNode for MolCompleteness {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("MolInfo_completeness")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        MolCompleteness::from_u8(read_int::<u8>(reader)?)
+    }
 }
 
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
@@ -390,6 +719,27 @@ pub struct MolInfo {
     pub gb_mol_type: Option<String>,
 }
 
+impl MolInfo {
+    /// Whether the molecule is marked as complete
+    ///
+    /// Most records do not indicate completeness, so `false` does not imply that
+    /// the molecule is partial. See [`Self::is_partial()`].
+    pub fn is_complete(&self) -> bool {
+        self.completeness == MolCompleteness::Complete
+    }
+
+    /// Whether the molecule is marked as missing one or both ends
+    pub fn is_partial(&self) -> bool {
+        matches!(
+            self.completeness,
+            MolCompleteness::Partial
+                | MolCompleteness::NoLeft
+                | MolCompleteness::NoRight
+                | MolCompleteness::NoEnds
+        )
+    }
+}
+
 impl XmlNode for MolInfo {
     fn start_bytes() -> BytesStart<'static> {
         BytesStart::new("MolInfo")
@@ -403,18 +753,27 @@ impl XmlNode for MolInfo {
 
         let bio_mol_element = BytesStart::new("MolInfo_biomol");
         let tech_element = BytesStart::new("MolInfo_tech");
+        let tech_exp_element = BytesStart::new("MolInfo_techexp");
+        let completeness_element = BytesStart::new("MolInfo_completeness");
+        let gb_mol_type_element = BytesStart::new("MolInfo_gbmoltype");
 
         let forbidden = UnexpectedTags(&[]);
 
         loop {
-            match reader.read_event().unwrap() {
+            match next_event(reader)? {
                 Event::Start(e) => {
                     let name = e.name();
 
-                    if name == bio_mol_element.name() {
-                        mol_info.bio_mol = read_node(reader).unwrap();
-                    } else if name == tech_element.name() {
-                        mol_info.tech = read_node(reader).unwrap();
+                    if name.is_tag(bio_mol_element.name()) {
+                        mol_info.bio_mol = read_node(reader)?;
+                    } else if name.is_tag(tech_element.name()) {
+                        mol_info.tech = read_node(reader)?;
+                    } else if name.is_tag(tech_exp_element.name()) {
+                        mol_info.tech_exp = read_string(reader);
+                    } else if name.is_tag(completeness_element.name()) {
+                        mol_info.completeness = read_node(reader)?;
+                    } else if name.is_tag(gb_mol_type_element.name()) {
+                        mol_info.gb_mol_type = read_string(reader);
                     } else if name != Self::start_bytes().name() {
                         forbidden.check(&name);
                     }
@@ -459,6 +818,21 @@ pub enum GIBBMol {
     Other = 255,
 }
 
+asn_names!(GIBBMol {
+    Unknown => "unknown",
+    Genomic => "genomic",
+    PreRNA => "pre-mRNA",
+    mRNA => "mRNA",
+    rRNA => "rRNA",
+    tRNA => "tRNA",
+    snRNA => "snRNA",
+    scRNA => "scRNA",
+    Peptide => "peptide",
+    OtherGenetic => "other-genetic",
+    Genomic_mRNA => "genomic-mRNA",
+    Other => "other",
+});
+
 #[derive(Clone, Serialize_repr, Deserialize_repr, PartialEq, Debug)]
 #[repr(u8)]
 /// GenInfo Backbone Modifiers
@@ -508,6 +882,37 @@ pub enum GIBBMod {
     Other = 255,
 }
 
+asn_names!(GIBBMod {
+    DNA => "dna",
+    RNA => "rna",
+    ExtraChrom => "extrachrom",
+    Plasmid => "plasmid",
+    Mitochondrial => "mitochondrial",
+    Chloroplast => "chloroplast",
+    Kinetoplast => "kinetoplast",
+    Cyanelle => "cyanelle",
+    Synthetic => "synthetic",
+    Recombinant => "recombinant",
+    Partial => "partial",
+    Complete => "complete",
+    Mutagen => "mutagen",
+    NatMut => "natmut",
+    Transposon => "transposon",
+    InsertionSeq => "insertion-seq",
+    NoLeft => "noleft",
+    NoRight => "noright",
+    MacroNuclear => "macronuclear",
+    ProViral => "proviral",
+    EST => "est",
+    STS => "sts",
+    Survey => "survey",
+    Chromoplast => "chromoplast",
+    GeneMap => "genemap",
+    RestMap => "restmap",
+    PhysMap => "physmap",
+    Other => "other",
+});
+
 #[derive(Clone, Serialize_repr, Deserialize_repr, PartialEq, Debug)]
 #[repr(u8)]
 /// Sequencing method
@@ -532,6 +937,16 @@ pub enum GIBBMethod {
     Other = 255,
 }
 
+asn_names!(GIBBMethod {
+    ConceptTrans => "concept-trans",
+    SeqPept => "seq-pept",
+    Both => "both",
+    SeqPeptOverlap => "seq-pept-overlap",
+    SeqPeptHomol => "seq-pept-homol",
+    ConceptTransA => "concept-trans-a",
+    Other => "other",
+});
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 #[serde(rename_all = "kebab-case")]
 /// Any display numbering system
@@ -589,6 +1004,12 @@ pub enum NumRefType {
     Aligns,
 }
 
+asn_names!(NumRefType {
+    NotSet => "not-set",
+    Sources => "sources",
+    Aligns => "aligns",
+});
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 #[serde(rename_all = "kebab-case")]
 /// Number by reference to other sequences
@@ -649,6 +1070,22 @@ pub struct PubDesc {
     pub ref_type: PubDescRefType,
 }
 
+impl PubDesc {
+    /// Human-readable citation of the publication
+    ///
+    /// See [`Pub::to_citation_string()`]
+    pub fn to_citation_string(&self, style: CitationStyle) -> String {
+        Pub::Equiv(self.r#pub.clone()).to_citation_string(style)
+    }
+
+    /// Most complete of the equivalent citations (eg: an article rather than its PubMed id)
+    ///
+    /// See [`best_pub()`]
+    pub fn primary_citation(&self) -> Option<&Pub> {
+        best_pub(&self.r#pub)
+    }
+}
+
 impl XmlNode for PubDesc {
     fn start_bytes() -> BytesStart<'static> {
         BytesStart::new("Pubdesc")
@@ -666,12 +1103,12 @@ impl XmlNode for PubDesc {
         let forbidden = UnexpectedTags(&[]);
 
         loop {
-            match reader.read_event().unwrap() {
+            match next_event(reader)? {
                 Event::Start(e) => {
                     let name = e.name();
 
-                    if name == pub_element.name() {
-                        desc.r#pub = read_node(reader).unwrap();
+                    if name.is_tag(pub_element.name()) {
+                        desc.r#pub = read_node(reader)?;
                     } else if name != Self::start_bytes().name() {
                         forbidden.check(&name);
                     }
@@ -756,6 +1193,19 @@ pub enum Repr {
     Other = 255,
 }
 
+asn_names!(Repr {
+    NotSet => "not-set",
+    Virtual => "virtual",
+    Raw => "raw",
+    Seg => "seg",
+    Const => "const",
+    Ref => "ref",
+    Consen => "consen",
+    Map => "map",
+    Delta => "delta",
+    Other => "other",
+});
+
 impl XmlValue for Repr {
     fn start_bytes() -> BytesStart<'static> {
         BytesStart::new("Seq-inst_repr")
@@ -801,6 +1251,15 @@ pub enum Mol {
     Other = 255,
 }
 
+asn_names!(Mol {
+    NotSet => "not-set",
+    DNA => "dna",
+    RNA => "rna",
+    AA => "aa",
+    NA => "na",
+    Other => "other",
+});
+
 impl XmlValue for Mol {
     fn start_bytes() -> BytesStart<'static> {
         BytesStart::new("Seq-inst_mol")
@@ -811,9 +1270,9 @@ impl XmlValue for Mol {
             match attributes.as_str() {
                 "not-set" => Self::NotSet.into(),
                 "dna" => Self::DNA.into(),
-                "rna" => Self::NotSet.into(),
-                "aa" => Self::DNA.into(),
-                "na" => Self::DNA.into(),
+                "rna" => Self::RNA.into(),
+                "aa" => Self::AA.into(),
+                "na" => Self::NA.into(),
                 "other" => Self::Other.into(),
                 _ => None
             }
@@ -840,6 +1299,14 @@ pub enum Topology {
     Other = 255,
 }
 
+asn_names!(Topology {
+    NotSet => "not-set",
+    Linear => "linear",
+    Circular => "circular",
+    Tandem => "tandem",
+    Other => "other",
+});
+
 #[derive(Clone, Serialize_repr, Deserialize_repr, PartialEq, Debug)]
 #[repr(u8)]
 /// Internal representation of strandedness in living organism for [`SeqInst`]
@@ -858,6 +1325,14 @@ pub enum Strand {
     Other = 255,
 }
 
+asn_names!(Strand {
+    NotSet => "not-set",
+    SS => "ss",
+    DS => "ds",
+    Mixed => "mixed",
+    Other => "other",
+});
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 #[serde(rename_all = "kebab-case")]
 /// Instances of sequences
@@ -923,19 +1398,25 @@ impl XmlNode for SeqInst {
         let repr_element = BytesStart::new("Seq-inst_repr");
         let mol_element = BytesStart::new("Seq-inst_mol");
         let length_element = BytesStart::new("Seq-inst_length");
+        let seq_data_element = BytesStart::new("Seq-inst_seq-data");
         let ext_element = BytesStart::new("Seq-inst_ext");
+        let hist_element = BytesStart::new("Seq-inst_hist");
 
         let forbidden = UnexpectedTags(&[]);
 
         loop {
-            match reader.read_event().unwrap() {
+            match next_event(reader)? {
                 Event::Start(e) => {
                     let name = e.name();
 
-                    if name == length_element.name() {
+                    if name.is_tag(length_element.name()) {
                         inst.length = read_int(reader);
-                    } else if name == ext_element.name() {
+                    } else if name.is_tag(seq_data_element.name()) {
+                        inst.seq_data = read_node(reader);
+                    } else if name.is_tag(ext_element.name()) {
                         inst.ext = read_node(reader);
+                    } else if name.is_tag(hist_element.name()) {
+                        inst.hist = read_node(reader);
                     } else if name != Self::start_bytes().name() {
                         forbidden.check(&name);
                     }
@@ -943,10 +1424,10 @@ impl XmlNode for SeqInst {
                 Event::Empty(e) => {
                     let name = e.name();
 
-                    if name == repr_element.name() {
-                        inst.repr = read_attributes(&e).unwrap();
-                    } else if name == mol_element.name() {
-                        inst.mol = read_attributes(&e).unwrap();
+                    if name.is_tag(repr_element.name()) {
+                        inst.repr = read_attributes(&e)?;
+                    } else if name.is_tag(mol_element.name()) {
+                        inst.mol = read_attributes(&e)?;
                     } else if name != Self::start_bytes().name() {
                         forbidden.check(&name);
                     }
@@ -962,6 +1443,19 @@ impl XmlNode for SeqInst {
     }
 }
 
+impl SeqInst {
+    /// Decode [`Self::seq_data`] into a one-letter IUPAC string
+    ///
+    /// Packed nucleic acid encodings rely on [`Self::length`] to know where the
+    /// final byte ends.
+    pub fn residues(&self) -> Result<String, String> {
+        match &self.seq_data {
+            Some(data) => data.to_iupac(self.length),
+            None => Err("Seq-inst has no sequence data".to_string()),
+        }
+    }
+}
+
 // Sequence extensions for representing more complex types
 
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
@@ -986,18 +1480,20 @@ impl XmlNode for SeqExt {
 
     fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> where Self: Sized {
         // variants
-        let _seg_element = BytesStart::new("Seq-ext_seg");
+        let seg_element = BytesStart::new("Seq-ext_seg");
         let _ref_element = BytesStart::new("Seq-ext_ref");
         let _map_element = BytesStart::new("Seq-ext_map");
         let delta_element = BytesStart::new("Seq-ext_delta");
 
         loop {
-            match reader.read_event().unwrap() {
+            match next_event(reader)? {
                 Event::Start(e) => {
                     let name = e.name();
 
-                    if name == delta_element.name() {
-                        return Self::Delta(read_vec_node(reader, delta_element.to_end())).into();
+                    if name.is_tag(delta_element.name()) {
+                        return Self::Delta(read_vec_node(reader, e.to_end())).into();
+                    } else if name.is_tag(seg_element.name()) {
+                        return Self::Seg(read_vec_node(reader, e.to_end())).into();
                     }
                 }
                 Event::End(e) => {
@@ -1016,6 +1512,19 @@ pub type RefExt = SeqLoc;
 pub type MapExt = Vec<SeqFeat>;
 pub type DeltaExt = Vec<DeltaSeq>;
 
+/// Number of residues covered by a component of a delta sequence, if given by `loc`
+fn delta_loc_length(loc: &SeqLoc) -> Option<u64> {
+    let interval = |from: i64, to: i64| u64::try_from(to - from + 1).ok();
+    match loc {
+        SeqLoc::Null | SeqLoc::Empty(_) => Some(0),
+        SeqLoc::Int(i) => interval(i.from, i.to),
+        SeqLoc::PackedInt(intervals) => intervals.iter().map(|i| interval(i.from, i.to)).sum(),
+        SeqLoc::Pnt(_) => Some(1),
+        SeqLoc::Mix(mix) => mix.0.iter().map(delta_loc_length).sum(),
+        _ => None,
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 #[serde(rename_all = "kebab-case")]
 pub enum DeltaSeq {
@@ -1034,15 +1543,17 @@ impl XmlNode for DeltaSeq {
     fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> where Self: Sized {
         // variant tags
         let loc_variant = BytesStart::new("Delta-seq_loc");
-        let _literal_variant = BytesStart::new("Delta-seq_literal");
+        let literal_variant = BytesStart::new("Delta-seq_literal");
 
         loop {
-            match reader.read_event().unwrap() {
+            match next_event(reader)? {
                 Event::Start(e) => {
                     let name = e.name();
 
-                    if name == loc_variant.name() {
-                        return Self::Loc(read_node(reader).unwrap()).into()
+                    if name.is_tag(loc_variant.name()) {
+                        return Self::Loc(read_node(reader)?).into()
+                    } else if name.is_tag(literal_variant.name()) {
+                        return Self::Literal(read_node(reader)?).into()
                     }
                 }
                 Event::End(e) => {
@@ -1070,6 +1581,58 @@ pub struct SeqLiteral {
     pub seq_data: Option<SeqData>,
 }
 
+impl SeqLiteral {
+    /// Whether the literal is a gap, rather than a piece of sequence
+    ///
+    /// Literals without sequence data are gaps of unknown type.
+    pub fn is_gap(&self) -> bool {
+        matches!(self.seq_data, None | Some(SeqData::Gap(_)))
+    }
+
+    /// Type of gap, if the literal is a gap
+    pub fn gap_type(&self) -> Option<SeqGapType> {
+        match &self.seq_data {
+            None => Some(SeqGapType::Unknown),
+            Some(SeqData::Gap(gap)) => Some(gap.r#type.clone()),
+            Some(_) => None,
+        }
+    }
+}
+
+impl XmlNode for SeqLiteral {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("Seq-literal")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> where Self: Sized {
+        let mut literal = Self {
+            length: 0,
+            full: None,
+            seq_data: None,
+        };
+
+        // elements
+        let length_element = BytesStart::new("Seq-literal_length");
+        let seq_data_element = BytesStart::new("Seq-literal_seq-data");
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => {
+                    let name = e.name();
+
+                    if name.is_tag(length_element.name()) {
+                        literal.length = read_int(reader)?;
+                    } else if name.is_tag(seq_data_element.name()) {
+                        literal.seq_data = read_node(reader);
+                    }
+                }
+                Event::End(e) if Self::is_end(&e) => return literal.into(),
+                _ => ()
+            }
+        }
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 #[serde(rename_all = "kebab-case")]
 /// internal structure for storing sequence history deletion status
@@ -1078,7 +1641,7 @@ pub enum SeqHistDeleted {
     Date(Date),
 }
 
-#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
 #[serde(rename_all = "kebab-case")]
 /// Sequence history record
 /// assembly: records how seq was assembled from others
@@ -1089,13 +1652,97 @@ pub struct SeqHist {
     pub deleted: Option<SeqHistDeleted>,
 }
 
-#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+impl SeqHist {
+    /// Whether the sequence has been withdrawn
+    ///
+    /// A deletion date implies that the sequence is deleted.
+    pub fn is_deleted(&self) -> bool {
+        matches!(self.deleted, Some(SeqHistDeleted::Bool(true)) | Some(SeqHistDeleted::Date(_)))
+    }
+}
+
+impl XmlNode for SeqHist {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("Seq-hist")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> where Self: Sized {
+        let mut hist = Self::default();
+
+        // elements
+        let assembly_element = BytesStart::new("Seq-hist_assembly");
+        let replaces_element = BytesStart::new("Seq-hist_replaces");
+        let replaced_by_element = BytesStart::new("Seq-hist_replaced-by");
+        let deleted_element = BytesStart::new("Seq-hist_deleted");
+        let deleted_bool_element = BytesStart::new("Seq-hist_deleted_bool");
+        let deleted_date_element = BytesStart::new("Seq-hist_deleted_date");
+
+        let forbidden = UnexpectedTags(&[]);
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => {
+                    let name = e.name();
+
+                    if name.is_tag(assembly_element.name()) {
+                        hist.assembly = Some(read_vec_node(reader, e.to_end()));
+                    } else if name.is_tag(replaces_element.name()) {
+                        hist.replaces = read_node(reader);
+                    } else if name.is_tag(replaced_by_element.name()) {
+                        hist.replaced_by = read_node(reader);
+                    } else if name.is_tag(deleted_date_element.name()) {
+                        hist.deleted = read_node(reader).map(SeqHistDeleted::Date);
+                    } else if name != Self::start_bytes().name() && !name.is_tag(deleted_element.name()) {
+                        forbidden.check(&name);
+                    }
+                }
+                Event::Empty(e) if e.name().is_tag(deleted_bool_element.name()) => {
+                    hist.deleted = read_bool_attribute(&e).map(SeqHistDeleted::Bool);
+                }
+                Event::End(e) if Self::is_end(&e) => return hist.into(),
+                _ => ()
+            }
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
 #[serde(rename_all = "kebab-case")]
 pub struct SeqHistRec {
     pub date: Option<Date>,
     pub ids: Vec<SeqId>,
 }
 
+impl XmlNode for SeqHistRec {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("Seq-hist-rec")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> where Self: Sized {
+        let mut rec = Self::default();
+
+        // elements
+        let date_element = BytesStart::new("Seq-hist-rec_date");
+        let ids_element = BytesStart::new("Seq-hist-rec_ids");
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => {
+                    let name = e.name();
+
+                    if name.is_tag(date_element.name()) {
+                        rec.date = read_node(reader);
+                    } else if name.is_tag(ids_element.name()) {
+                        rec.ids = read_vec_node(reader, e.to_end());
+                    }
+                }
+                Event::End(e) if Self::is_end(&e) => return rec.into(),
+                _ => ()
+            }
+        }
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 #[serde(rename_all = "kebab-case")]
 /// Sequence representations
@@ -1134,6 +1781,117 @@ pub enum SeqData {
     Gap(SeqGap),
 }
 
+impl SeqData {
+    /// Decode residues into one-letter IUPAC codes
+    ///
+    /// `length` is required for [`SeqData::N2na`] and [`SeqData::N4na`] since the last
+    /// byte may be padded.
+    pub fn to_iupac(&self, length: Option<u64>) -> Result<String, String> {
+        match self {
+            Self::Ina(seq) | Self::Iaa(seq) | Self::NEaa(seq) => Ok(seq.clone()),
+            Self::N2na(bytes) => {
+                let length = length.ok_or("NCBI2na data requires a length")? as usize;
+                if length > bytes.len() * 4 {
+                    return Err("NCBI2na data is shorter than length".to_string());
+                }
+                Ok((0..length)
+                    .map(|i| {
+                        let shift = 6 - 2 * (i % 4);
+                        NCBI2NA_ALPHABET[((bytes[i / 4] >> shift) & 0b11) as usize]
+                    })
+                    .collect())
+            }
+            Self::N4na(bytes) => {
+                let length = length.ok_or("NCBI4na data requires a length")? as usize;
+                if length > bytes.len() * 2 {
+                    return Err("NCBI4na data is shorter than length".to_string());
+                }
+                Ok((0..length)
+                    .map(|i| {
+                        let shift = 4 - 4 * (i % 2);
+                        NCBI4NA_ALPHABET[((bytes[i / 2] >> shift) & 0x0F) as usize]
+                    })
+                    .collect())
+            }
+            Self::NStdAAs(bytes) => bytes
+                .iter()
+                .map(|b| {
+                    NCBISTDAA_ALPHABET
+                        .get(*b as usize)
+                        .copied()
+                        .ok_or(format!("Invalid NCBIstdaa code {}", b))
+                })
+                .collect(),
+            _ => Err("Sequence encoding cannot be converted to IUPAC".to_string()),
+        }
+    }
+}
+
+const NCBI2NA_ALPHABET: [char; 4] = ['A', 'C', 'G', 'T'];
+pub(crate) const NCBI4NA_ALPHABET: [char; 16] = [
+    '-', 'A', 'C', 'M', 'G', 'R', 'S', 'V', 'T', 'W', 'Y', 'H', 'K', 'D', 'B', 'N',
+];
+pub(crate) const NCBISTDAA_ALPHABET: [char; 28] = [
+    '-', 'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'K', 'L', 'M', 'N', 'P', 'Q', 'R', 'S',
+    'T', 'V', 'W', 'X', 'Y', 'Z', 'U', '*', 'O', 'J',
+];
+
+/// Parse hex-encoded binary sequence data as given by Entrez XML
+fn hex_to_bytes(text: &str) -> Option<Vec<u8>> {
+    let text = text.trim();
+    if !text.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(text.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+impl XmlNode for SeqData {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("Seq-data")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> where Self: Sized {
+        // variant values
+        let iupacna_element = BytesStart::new("IUPACna");
+        let iupacaa_element = BytesStart::new("IUPACaa");
+        let ncbi2na_element = BytesStart::new("NCBI2na");
+        let ncbi4na_element = BytesStart::new("NCBI4na");
+        let ncbieaa_element = BytesStart::new("NCBIeaa");
+        let ncbistdaa_element = BytesStart::new("NCBIstdaa");
+        let gap_element = BytesStart::new("Seq-data_gap");
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => {
+                    let name = e.name();
+
+                    if name.is_tag(iupacna_element.name()) {
+                        return Self::Ina(read_string(reader)?.trim().to_string()).into();
+                    } else if name.is_tag(iupacaa_element.name()) {
+                        return Self::Iaa(read_string(reader)?.trim().to_string()).into();
+                    } else if name.is_tag(ncbieaa_element.name()) {
+                        return Self::NEaa(read_string(reader)?.trim().to_string()).into();
+                    } else if name.is_tag(ncbi2na_element.name()) {
+                        return Self::N2na(hex_to_bytes(&read_string(reader)?)?).into();
+                    } else if name.is_tag(ncbi4na_element.name()) {
+                        return Self::N4na(hex_to_bytes(&read_string(reader)?)?).into();
+                    } else if name.is_tag(ncbistdaa_element.name()) {
+                        return Self::NStdAAs(hex_to_bytes(&read_string(reader)?)?).into();
+                    } else if name.is_tag(gap_element.name()) {
+                        return Self::Gap(read_node(reader)?).into();
+                    }
+                }
+                Event::End(e) if Self::is_end(&e) => return None,
+                Event::Eof => return None,
+                _ => ()
+            }
+        }
+    }
+}
+
 #[derive(Clone, Serialize_repr, Deserialize_repr, PartialEq, Debug)]
 #[repr(u8)]
 /// internal structure for `type` field in [`SeqGap`]
@@ -1161,18 +1919,76 @@ pub enum SeqGapType {
     Other = 255,
 }
 
-#[derive(Clone, Serialize_repr, Deserialize_repr, PartialEq, Debug)]
-#[repr(u8)]
-/// Internal representation for linkage status for [`SeqGap`]
-///
-/// # Note
-///
-/// Original implementation lists this as `INTEGER`, therefore it is assumed that
-/// serialized representation is an integer
-pub enum SeqGapLinkage {
-    Unlinked,
-    Linked,
-    Other = 255,
+asn_names!(SeqGapType {
+    Unknown => "unknown",
+    Fragment => "fragment",
+    Clone => "clone",
+    ShortArm => "short-arm",
+    Heterochromatin => "heterochromatin",
+    Centromere => "centromere",
+    Telomere => "telomere",
+    Repeat => "repeat",
+    Contig => "contig",
+    Scaffold => "scaffold",
+    Contamination => "contamination",
+    Other => "other",
+});
+
+impl XmlNode for SeqGapType {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("Seq-gap_type")
+    }
+
+    #[allow(deprecated)]
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> {
+        match read_int::<u8>(reader)? {
+            0 => Self::Unknown.into(),
+            1 => Self::Fragment.into(),
+            2 => Self::Clone.into(),
+            3 => Self::ShortArm.into(),
+            4 => Self::Heterochromatin.into(),
+            5 => Self::Centromere.into(),
+            6 => Self::Telomere.into(),
+            7 => Self::Repeat.into(),
+            8 => Self::Contig.into(),
+            9 => Self::Scaffold.into(),
+            10 => Self::Contamination.into(),
+            255 => Self::Other.into(),
+            _ => None,
+        }
+    }
+}
+
+enum_from_primitive! {
+    #[derive(Clone, Serialize_repr, Deserialize_repr, PartialEq, Debug)]
+    #[repr(u8)]
+    /// Internal representation for linkage status for [`SeqGap`]
+    ///
+    /// # Note
+    ///
+    /// Original implementation lists this as `INTEGER`, therefore it is assumed that
+    /// serialized representation is an integer
+    pub enum SeqGapLinkage {
+        Unlinked,
+        Linked,
+        Other = 255,
+    }
+}
+
+asn_names!(SeqGapLinkage {
+    Unlinked => "unlinked",
+    Linked => "linked",
+    Other => "other",
+});
+
+impl XmlNode for SeqGapLinkage {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("Seq-gap_linkage")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> {
+        Self::from_u8(read_int::<u8>(reader)?)
+    }
 }
 
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
@@ -1183,22 +1999,90 @@ pub struct SeqGap {
     pub linkage_evidence: Option<Vec<LinkageEvidence>>,
 }
 
-#[derive(Clone, Serialize_repr, Deserialize_repr, PartialEq, Debug)]
-#[repr(u8)]
-/// internal representation for `type` in [`LinkageEvidence`]
-pub enum LinkageEvidenceType {
-    PairedEnds,
-    AlignGenus,
-    AlignXGenus,
-    AlignTrans,
-    WithinClone,
-    CloneContig,
-    Map,
-    Strobe,
-    Unspecified,
-    PCR,
-    ProximityLigation,
-    Other = 255,
+impl XmlNode for SeqGap {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("Seq-gap")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> where Self: Sized {
+        let mut r#type = None;
+        let mut linkage = None;
+        let mut linkage_evidence = None;
+
+        // elements
+        let type_element = BytesStart::new("Seq-gap_type");
+        let linkage_element = BytesStart::new("Seq-gap_linkage");
+        let evidence_element = BytesStart::new("Seq-gap_linkage-evidence");
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => {
+                    let name = e.name();
+
+                    if name.is_tag(type_element.name()) {
+                        r#type = read_node(reader);
+                    } else if name.is_tag(linkage_element.name()) {
+                        linkage = read_node(reader);
+                    } else if name.is_tag(evidence_element.name()) {
+                        linkage_evidence = Some(read_vec_node(reader, e.to_end()));
+                    }
+                }
+                Event::End(e) if Self::is_end(&e) => {
+                    return Self {
+                        r#type: r#type?,
+                        linkage,
+                        linkage_evidence,
+                    }.into()
+                }
+                _ => ()
+            }
+        }
+    }
+}
+
+enum_from_primitive! {
+    #[derive(Clone, Serialize_repr, Deserialize_repr, PartialEq, Debug)]
+    #[repr(u8)]
+    /// internal representation for `type` in [`LinkageEvidence`]
+    pub enum LinkageEvidenceType {
+        PairedEnds,
+        AlignGenus,
+        AlignXGenus,
+        AlignTrans,
+        WithinClone,
+        CloneContig,
+        Map,
+        Strobe,
+        Unspecified,
+        PCR,
+        ProximityLigation,
+        Other = 255,
+    }
+}
+
+asn_names!(LinkageEvidenceType {
+    PairedEnds => "paired-ends",
+    AlignGenus => "align-genus",
+    AlignXGenus => "align-xgenus",
+    AlignTrans => "align-trans",
+    WithinClone => "within-clone",
+    CloneContig => "clone-contig",
+    Map => "map",
+    Strobe => "strobe",
+    Unspecified => "unspecified",
+    PCR => "pcr",
+    ProximityLigation => "proximity-ligation",
+    Other => "other",
+});
+
+impl XmlNode for LinkageEvidenceType {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("Linkage-evidence_type")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> {
+        Self::from_u8(read_int::<u8>(reader)?)
+    }
 }
 
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
@@ -1207,6 +2091,29 @@ pub struct LinkageEvidence {
     pub r#type: LinkageEvidenceType,
 }
 
+impl XmlNode for LinkageEvidence {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("Linkage-evidence")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> where Self: Sized {
+        let mut r#type = None;
+
+        let type_element = BytesStart::new("Linkage-evidence_type");
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) if e.name().is_tag(type_element.name()) => {
+                    r#type = read_node(reader);
+                }
+                Event::End(e) if Self::is_end(&e) => return Self { r#type: r#type? }.into(),
+                _ => ()
+            }
+        }
+    }
+}
+impl XmlVecNode for LinkageEvidence {}
+
 /// IUPAC 1 letter codes, no spaces
 pub type IUPACna = String;
 
@@ -1301,6 +2208,56 @@ pub enum AnnotDesc {
     Region(SeqLoc),
 }
 
+impl XmlNode for AnnotDesc {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("Annotdesc")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> {
+        // variants
+        let name_element = BytesStart::new("Annotdesc_name");
+        let title_element = BytesStart::new("Annotdesc_title");
+        let comment_element = BytesStart::new("Annotdesc_comment");
+        let pub_element = BytesStart::new("Annotdesc_pub");
+        let user_element = BytesStart::new("Annotdesc_user");
+        let create_element = BytesStart::new("Annotdesc_create-date");
+        let update_element = BytesStart::new("Annotdesc_update-date");
+        let src_element = BytesStart::new("Annotdesc_src");
+        let region_element = BytesStart::new("Annotdesc_region");
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => {
+                    let name = e.name();
+                    if name.is_tag(name_element.name()) {
+                        return Self::Name(read_string(reader)?).into();
+                    } else if name.is_tag(title_element.name()) {
+                        return Self::Title(read_string(reader)?).into();
+                    } else if name.is_tag(comment_element.name()) {
+                        return Self::Comment(read_string(reader)?).into();
+                    } else if name.is_tag(pub_element.name()) {
+                        return Self::Pub(read_node(reader)?).into();
+                    } else if name.is_tag(user_element.name()) {
+                        return Self::User(read_node(reader)?).into();
+                    } else if name.is_tag(create_element.name()) {
+                        return Self::CreateDate(read_node(reader)?).into();
+                    } else if name.is_tag(update_element.name()) {
+                        return Self::UpdateDate(read_node(reader)?).into();
+                    } else if name.is_tag(src_element.name()) {
+                        return Self::Src(read_node(reader)?).into();
+                    } else if name.is_tag(region_element.name()) {
+                        return Self::Region(read_node(reader)?).into();
+                    }
+                }
+                // `align` descriptors do not yet have a parsing implementation
+                Event::End(e) if Self::is_end(&e) => return None,
+                _ => (),
+            }
+        }
+    }
+}
+impl XmlVecNode for AnnotDesc {}
+
 #[derive(Clone, Serialize_repr, Deserialize_repr, PartialEq, Debug)]
 #[repr(u8)]
 /// Internal representation of align type for [`SeqAnnot`]
@@ -1319,6 +2276,13 @@ pub enum AlignType {
     Other = 255,
 }
 
+asn_names!(AlignType {
+    Ref => "ref",
+    Alt => "alt",
+    Blocks => "blocks",
+    Other => "other",
+});
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 #[serde(rename_all = "kebab-case")]
 pub struct AlignDef {
@@ -1346,6 +2310,17 @@ pub enum SeqAnnotDB {
     Other = 255,
 }
 
+asn_names!(SeqAnnotDB {
+    GenBank => "genbank",
+    EMBL => "embl",
+    DDBJ => "ddbj",
+    PIR => "pir",
+    SP => "sp",
+    BBone => "bbone",
+    PDB => "pdb",
+    Other => "other",
+});
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 #[serde(rename_all = "lowercase")]
 /// Internal representation for `data` choice in [`SeqAnnot`]
@@ -1363,6 +2338,11 @@ pub enum SeqAnnotData {
     #[serde(rename = "seq-table")]
     /// features in table form
     SeqTable(SeqTable),
+
+    /// data which is not parsed, kept as XML (eg: `<Seq-annot_data_graph>...`)
+    ///
+    /// Only produced with the `raw-xml` feature, see [`SeqDesc::Unknown`].
+    Unknown(String),
 }
 
 impl XmlNode for SeqAnnotData {
@@ -1373,14 +2353,19 @@ impl XmlNode for SeqAnnotData {
     fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> where Self: Sized {
         // variant tags
         let ftable_tag = BytesStart::new("Seq-annot_data_ftable");
+        let align_tag = BytesStart::new("Seq-annot_data_align");
 
         loop {
-            match reader.read_event().unwrap() {
+            match next_event(reader)? {
                 Event::Start(e) => {
                     let name = e.name();
 
-                    if name == ftable_tag.name() {
-                        return Self::FTable(read_vec_node(reader, ftable_tag.to_end())).into()
+                    if name.is_tag(ftable_tag.name()) {
+                        return Self::FTable(read_vec_node(reader, e.to_end())).into()
+                    } else if name.is_tag(align_tag.name()) {
+                        return Self::Align(read_vec_node(reader, e.to_end())).into()
+                    } else if cfg!(feature = "raw-xml") && !name.is_tag(Self::start_bytes().name()) {
+                        return Self::Unknown(read_raw(reader, &e)?).into()
                     }
                 }
                 Event::End(e) => {
@@ -1424,6 +2409,83 @@ impl SeqAnnot {
             data,
         }
     }
+
+    /// Read a standalone annotation from an XML file, which may be gzip compressed
+    ///
+    /// NCBI distributes some annotation tracks (eg: SNPs) as files whose root element
+    /// is `Seq-annot`, without the sequences they annotate.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let mut xml = String::new();
+        open_local(path)?
+            .read_to_string(&mut xml)
+            .map_err(|e| format!("{}: {}", path.display(), e))?;
+        match parse_xml(&xml)? {
+            DataType::SeqAnnot(annot) => Ok(annot),
+            _ => Err(format!("{}: root element is not a Seq-annot", path.display())),
+        }
+    }
+
+    /// Features contained by a feature table
+    ///
+    /// Empty if [`Self::data`] is not [`SeqAnnotData::FTable`]
+    pub fn features(&self) -> &[SeqFeat] {
+        match &self.data {
+            SeqAnnotData::FTable(features) => features.as_slice(),
+            _ => &[],
+        }
+    }
+
+    /// Find feature by [`FeatId`]
+    pub fn feature_by_id(&self, id: &FeatId) -> Option<&SeqFeat> {
+        self.features().iter().find(|feat| feat.has_id(id))
+    }
+
+    /// Features referenced by the [`SeqFeatXref`] id's of `feat`
+    ///
+    /// [`SeqFeatXref`]: crate::seqfeat::SeqFeatXref
+    pub fn xref_features(&self, feat: &SeqFeat) -> Vec<&SeqFeat> {
+        feat.xref_ids()
+            .filter_map(|id| self.feature_by_id(id))
+            .collect()
+    }
+
+    /// Gene feature that `feat` belongs to
+    ///
+    /// Resolution is attempted in order:
+    /// - a gene feature referenced by [`FeatId`] in `feat.xref`
+    /// - a gene feature with the same `locus_tag` or `locus` as [`SeqFeat::gene_xref()`]
+    /// - the smallest gene feature whose extent contains the extent of `feat`, when
+    ///   neither reference matches a gene of this annotation
+    pub fn parent_gene(&self, feat: &SeqFeat) -> Option<&SeqFeat> {
+        let is_gene = |f: &&SeqFeat| matches!(f.data, SeqFeatData::Gene(_));
+
+        if let Some(gene) = self.xref_features(feat).into_iter().find(is_gene) {
+            return Some(gene);
+        }
+
+        let genes = self.features().iter().filter(is_gene);
+        let by_xref = feat.gene_xref().and_then(|xref| {
+            genes.clone().find(|gene| match &gene.data {
+                SeqFeatData::Gene(gene) => {
+                    (xref.locus_tag.is_some() && gene.locus_tag == xref.locus_tag)
+                        || (xref.locus_tag.is_none() && xref.locus.is_some() && gene.locus == xref.locus)
+                }
+                _ => false,
+            })
+        });
+        if by_xref.is_some() {
+            return by_xref;
+        }
+
+        let (from, to) = feat.location.total_range()?;
+        genes
+            .filter(|gene| !std::ptr::eq(*gene, feat))
+            .filter_map(|gene| gene.location.total_range().map(|range| (gene, range)))
+            .filter(|(_, (gene_from, gene_to))| *gene_from <= from && to <= *gene_to)
+            .min_by_key(|(_, (gene_from, gene_to))| gene_to - gene_from)
+            .map(|(gene, _)| gene)
+    }
 }
 
 impl XmlNode for SeqAnnot {
@@ -1435,17 +2497,23 @@ impl XmlNode for SeqAnnot {
         let mut annot = SeqAnnot::default();
 
         // attribute tags
+        let name_tag = BytesStart::new("Seq-annot_name");
+        let desc_tag = BytesStart::new("Seq-annot_desc");
         let data_tag = BytesStart::new("Seq-annot_data");
 
         let forbidden = UnexpectedTags(&[]);
 
         loop {
-            match reader.read_event().unwrap() {
+            match next_event(reader)? {
                 Event::Start(e) => {
                     let name = e.name();
 
-                    if name == data_tag.name() {
-                        annot.data = read_node(reader).unwrap();
+                    if name.is_tag(name_tag.name()) {
+                        annot.name = read_string(reader);
+                    } else if name.is_tag(desc_tag.name()) {
+                        annot.desc = Some(read_vec_node(reader, e.to_end()));
+                    } else if name.is_tag(data_tag.name()) {
+                        annot.data = read_node(reader)?;
                     } else if name != Self::start_bytes().name() {
                         forbidden.check(&name);
                     }
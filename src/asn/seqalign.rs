@@ -3,7 +3,12 @@
 //! Adapted from ["seqalign.asn"](https://www.ncbi.nlm.nih.gov/IEB/ToolBox/CPP_DOC/lxr/source/src/objects/seqalign/seqalign.asn)
 
 use crate::general::{ObjectId, UserObject};
+use crate::parsing::{next_event, attribute_value, read_attributes, read_int, read_node, read_real, read_vec_int_unchecked, read_vec_node, UnexpectedTags};
+use crate::parsing::{TagMatch, XmlNode, XmlValue, XmlVecNode};
 use crate::seqloc::{NaStrand, SeqId, SeqLoc};
+use quick_xml::events::attributes::Attributes;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
 use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
 
@@ -31,6 +36,37 @@ pub enum SeqAlignType {
     Other = 255,
 }
 
+asn_names!(SeqAlignType {
+    NotSet => "not-set",
+    Global => "global",
+    Diags => "diags",
+    Partial => "partial",
+    Disc => "disc",
+    Other => "other",
+});
+
+impl XmlValue for SeqAlignType {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("Seq-align_type")
+    }
+
+    fn from_attributes(attributes: Attributes) -> Option<Self> {
+        if let Some(attributes) = attribute_value(attributes) {
+            match attributes.as_str() {
+                "not-set" => Self::NotSet.into(),
+                "global" => Self::Global.into(),
+                "diags" => Self::Diags.into(),
+                "partial" => Self::Partial.into(),
+                "disc" => Self::Disc.into(),
+                "other" => Self::Other.into(),
+                _ => None
+            }
+        } else {
+            None
+        }
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 #[serde(rename_all = "lowercase")]
 pub enum SeqAlignSegs {
@@ -62,6 +98,109 @@ pub struct SeqAlign {
     pub ext: Option<Vec<UserObject>>,
 }
 
+impl XmlNode for SeqAlignSegs {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("Seq-align_segs")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> where Self: Sized {
+        // variant tags
+        let dendiag_tag = BytesStart::new("Seq-align_segs_dendiag");
+        let denseg_tag = BytesStart::new("Seq-align_segs_denseg");
+        let std_tag = BytesStart::new("Seq-align_segs_std");
+        let packed_tag = BytesStart::new("Seq-align_segs_packed");
+        let disc_tag = BytesStart::new("Seq-align_segs_disc");
+        let spliced_tag = BytesStart::new("Seq-align_segs_spliced");
+        let sparse_tag = BytesStart::new("Seq-align_segs_sparse");
+
+        let forbidden = [
+            dendiag_tag,
+            std_tag,
+            packed_tag,
+            spliced_tag,
+            sparse_tag,
+        ];
+        let forbidden = UnexpectedTags(&forbidden);
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => {
+                    let name = e.name();
+
+                    if name.is_tag(denseg_tag.name()) {
+                        return Self::DenSeg(read_node(reader)?).into();
+                    } else if name.is_tag(disc_tag.name()) {
+                        return Self::Disc(read_vec_node(reader, e.to_end())).into();
+                    } else if name != Self::start_bytes().name() {
+                        forbidden.check(&name);
+                    }
+                }
+                Event::End(e) if Self::is_end(&e) => return None,
+                _ => ()
+            }
+        }
+    }
+}
+
+impl XmlNode for SeqAlign {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("Seq-align")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> where Self: Sized {
+        let mut align = Self {
+            r#type: SeqAlignType::NotSet,
+            dim: None,
+            score: None,
+            segs: SeqAlignSegs::Disc(Vec::new()),
+            bounds: None,
+            id: None,
+            ext: None,
+        };
+
+        // field tags
+        let type_tag = BytesStart::new("Seq-align_type");
+        let dim_tag = BytesStart::new("Seq-align_dim");
+        let score_tag = BytesStart::new("Seq-align_score");
+        let segs_tag = BytesStart::new("Seq-align_segs");
+        let bounds_tag = BytesStart::new("Seq-align_bounds");
+        let id_tag = BytesStart::new("Seq-align_id");
+        let ext_tag = BytesStart::new("Seq-align_ext");
+
+        let forbidden = UnexpectedTags(&[]);
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => {
+                    let name = e.name();
+
+                    if name.is_tag(dim_tag.name()) {
+                        align.dim = read_int(reader);
+                    } else if name.is_tag(score_tag.name()) {
+                        align.score = Some(read_vec_node(reader, e.to_end()));
+                    } else if name.is_tag(segs_tag.name()) {
+                        align.segs = read_node(reader)?;
+                    } else if name.is_tag(bounds_tag.name()) {
+                        align.bounds = Some(read_vec_node(reader, e.to_end()));
+                    } else if name.is_tag(id_tag.name()) {
+                        align.id = Some(read_vec_node(reader, e.to_end()));
+                    } else if name.is_tag(ext_tag.name()) {
+                        align.ext = Some(read_vec_node(reader, e.to_end()));
+                    } else if name != Self::start_bytes().name() {
+                        forbidden.check(&name);
+                    }
+                }
+                Event::Empty(e) if e.name().is_tag(type_tag.name()) => {
+                    align.r#type = read_attributes(&e)?;
+                }
+                Event::End(e) if Self::is_end(&e) => return align.into(),
+                _ => ()
+            }
+        }
+    }
+}
+impl XmlVecNode for SeqAlign {}
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 /// for (multiway) diagonals
 pub struct DenseDiag {
@@ -102,7 +241,7 @@ pub struct DenseSeg {
     /// sequences in order
     pub ids: Vec<SeqId>,
     /// start OFFSETS in ids order within segs
-    pub starts: Vec<u64>,
+    pub starts: Vec<i64>,
     /// lengths in ids order within segs
     pub lens: Vec<u64>,
     pub strands: Option<Vec<NaStrand>>,
@@ -110,6 +249,90 @@ pub struct DenseSeg {
     pub scores: Option<Vec<Score>>,
 }
 
+impl DenseSeg {
+    /// Start of row `row` in segment `seg`, or `None` for a gap
+    pub fn start(&self, row: usize, seg: usize) -> Option<u64> {
+        let start = *self.starts.get(seg * self.dim as usize + row)?;
+        if start < 0 {
+            None
+        } else {
+            Some(start as u64)
+        }
+    }
+
+    /// Strand of row `row`, if given
+    pub fn strand(&self, row: usize) -> Option<&NaStrand> {
+        self.strands.as_ref()?.get(row)
+    }
+
+    /// Number of aligned columns
+    pub fn aligned_length(&self) -> u64 {
+        self.lens.iter().sum()
+    }
+}
+
+impl XmlNode for DenseSeg {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("Dense-seg")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> where Self: Sized {
+        let mut seg = Self {
+            dim: 2,
+            numseg: 0,
+            ids: Vec::new(),
+            starts: Vec::new(),
+            lens: Vec::new(),
+            strands: None,
+            scores: None,
+        };
+
+        // field tags
+        let dim_tag = BytesStart::new("Dense-seg_dim");
+        let numseg_tag = BytesStart::new("Dense-seg_numseg");
+        let ids_tag = BytesStart::new("Dense-seg_ids");
+        let starts_tag = BytesStart::new("Dense-seg_starts");
+        let lens_tag = BytesStart::new("Dense-seg_lens");
+        let strands_tag = BytesStart::new("Dense-seg_strands");
+        let scores_tag = BytesStart::new("Dense-seg_scores");
+
+        let forbidden = UnexpectedTags(&[]);
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => {
+                    let name = e.name();
+
+                    if name.is_tag(dim_tag.name()) {
+                        seg.dim = read_int(reader)?;
+                    } else if name.is_tag(numseg_tag.name()) {
+                        seg.numseg = read_int(reader)?;
+                    } else if name.is_tag(ids_tag.name()) {
+                        seg.ids = read_vec_node(reader, e.to_end());
+                    } else if name.is_tag(starts_tag.name()) {
+                        seg.starts = read_vec_int_unchecked(reader, &e.to_end());
+                    } else if name.is_tag(lens_tag.name()) {
+                        seg.lens = read_vec_int_unchecked(reader, &e.to_end());
+                    } else if name.is_tag(strands_tag.name()) {
+                        seg.strands = Some(Vec::new());
+                    } else if name.is_tag(scores_tag.name()) {
+                        seg.scores = Some(read_vec_node(reader, e.to_end()));
+                    } else if name != Self::start_bytes().name() {
+                        forbidden.check(&name);
+                    }
+                }
+                Event::Empty(e) if e.name() == NaStrand::start_bytes().name() => {
+                    if let (Some(strands), Some(strand)) = (seg.strands.as_mut(), read_attributes(&e)) {
+                        strands.push(strand);
+                    }
+                }
+                Event::End(e) if Self::is_end(&e) => return seg.into(),
+                _ => ()
+            }
+        }
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 /// for (multiway) global or partial alignments
 pub struct PackedSeg {
@@ -367,4 +590,41 @@ pub struct Score {
     pub value: ScoreValue,
 }
 
+impl XmlNode for Score {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("Score")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> where Self: Sized {
+        let mut id = None;
+        let mut value = None;
+
+        // field tags
+        let id_tag = BytesStart::new("Score_id");
+        let int_tag = BytesStart::new("Score_value_int");
+        let real_tag = BytesStart::new("Score_value_real");
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => {
+                    let name = e.name();
+
+                    if name.is_tag(id_tag.name()) {
+                        id = read_node(reader);
+                    } else if name.is_tag(int_tag.name()) {
+                        value = read_int(reader).map(ScoreValue::Int);
+                    } else if name.is_tag(real_tag.name()) {
+                        value = read_real(reader)
+                            .and_then(|real| real.trim().parse().ok())
+                            .map(ScoreValue::Real);
+                    }
+                }
+                Event::End(e) if Self::is_end(&e) => return Some(Self { id, value: value? }),
+                _ => ()
+            }
+        }
+    }
+}
+impl XmlVecNode for Score {}
+
 pub type ScoreSet = Vec<Score>;
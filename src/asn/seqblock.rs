@@ -37,6 +37,27 @@ pub enum EMBLDbNameCode {
     Other = 255,
 }
 
+asn_names!(EMBLDbNameCode {
+    EMBL => "embl",
+    GenBank => "genbank",
+    DDBJ => "ddbj",
+    GenInfo => "geninfo",
+    MedLine => "medline",
+    SWISSPROT => "swissprot",
+    PIR => "pir",
+    PDB => "pdb",
+    EPD => "epd",
+    ECD => "ecd",
+    TFD => "tfd",
+    FlyBase => "flybase",
+    ProSite => "prosite",
+    Enzyme => "enzyme",
+    MIM => "mim",
+    EcoSeq => "ecoseq",
+    HIV => "hiv",
+    Other => "other",
+});
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 #[serde(rename_all = "lowercase")]
 pub enum EMBLDbName {
@@ -66,6 +87,13 @@ pub enum EMBLBlockClass {
     Other = 255,
 }
 
+asn_names!(EMBLBlockClass {
+    NotSet => "not-set",
+    Standard => "standard",
+    Unannotated => "unannotated",
+    Other => "other",
+});
+
 #[derive(Clone, Serialize_repr, Deserialize_repr, PartialEq, Debug)]
 #[repr(u8)]
 /// Internal representation of block division for [`EMBLBlockClass`]
@@ -93,6 +121,25 @@ pub enum EMBLBlockDiv {
     Other = 255,
 }
 
+asn_names!(EMBLBlockDiv {
+    Fun => "fun",
+    Inv => "inv",
+    Mam => "mam",
+    Org => "org",
+    Pln => "pln",
+    Pri => "pri",
+    Pro => "pro",
+    Rod => "rod",
+    Syn => "syn",
+    Una => "una",
+    Vrl => "vrl",
+    Vrt => "vrt",
+    Pat => "pat",
+    Est => "est",
+    STS => "sts",
+    Other => "other",
+});
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 pub struct EMBLBlock {
     pub class: EMBLBlockClass,
@@ -121,6 +168,13 @@ pub enum SPBlockClass {
     Other = 255,
 }
 
+asn_names!(SPBlockClass {
+    NotSet => "not-set",
+    Standard => "standard",
+    Prelim => "prelim",
+    Other => "other",
+});
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 #[serde(rename_all = "kebab-case")]
 /// SWISSPROT specific descriptions
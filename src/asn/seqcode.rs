@@ -91,3 +91,344 @@ pub struct SeqCodeSet {
     pub codes: Option<Vec<SeqCodeTable>>,
     pub maps: Option<Vec<SeqMapTable>>,
 }
+
+/// Translation table for a genetic code, as given by NCBI's "gc.prt"
+///
+/// Codons are ordered by their first, second and third base, each in the order
+/// T, C, A, G (ie: TTT, TTC, TTA, TTG, TCT, ... GGG).
+#[derive(Clone, PartialEq, Debug)]
+pub struct CodeTable {
+    /// id referenced by [`crate::seqfeat::GeneticCodeOpt::Id`] and [`crate::seqfeat::OrgName::gcode`]
+    pub id: u64,
+    pub name: &'static str,
+    /// amino acid encoded by each codon, in [`SeqCodeType::NCBIEaa`]
+    pub ncbieaa: &'static str,
+    /// "M" for codons which may initiate translation
+    pub sncbieaa: &'static str,
+}
+
+/// Bases represented by an IUPAC nucleotide, in the order used to index codons
+fn bases(base: u8) -> &'static [usize] {
+    match base.to_ascii_uppercase() {
+        b'T' | b'U' => &[0],
+        b'C' => &[1],
+        b'A' => &[2],
+        b'G' => &[3],
+        b'Y' => &[0, 1],
+        b'W' => &[0, 2],
+        b'K' => &[0, 3],
+        b'M' => &[1, 2],
+        b'S' => &[1, 3],
+        b'R' => &[2, 3],
+        b'H' => &[0, 1, 2],
+        b'B' => &[0, 1, 3],
+        b'D' => &[0, 2, 3],
+        b'V' => &[1, 2, 3],
+        b'N' => &[0, 1, 2, 3],
+        _ => &[],
+    }
+}
+
+/// Indices of every codon represented by `codon`, which may contain ambiguity codes
+fn codon_indices(codon: &[u8]) -> Vec<usize> {
+    match codon {
+        [first, second, third] => {
+            let mut indices = Vec::new();
+            for a in bases(*first) {
+                for b in bases(*second) {
+                    for c in bases(*third) {
+                        indices.push(a * 16 + b * 4 + c);
+                    }
+                }
+            }
+            indices
+        }
+        _ => Vec::new(),
+    }
+}
+
+impl CodeTable {
+    /// Amino acid encoded by `codon`, as an [`SeqCodeType::NCBIEaa`] letter
+    ///
+    /// `codon` is given in IUPAC, where "U" is read as "T". Ambiguous codons are
+    /// translated if every codon they represent encodes the same amino acid (eg: "CTN"
+    /// is "L"), and are "X" otherwise. Returns `None` if `codon` is not three bases.
+    pub fn translate_codon(&self, codon: &str) -> Option<char> {
+        let indices = codon_indices(codon.as_bytes());
+        let table = self.ncbieaa.as_bytes();
+        let first = *table.get(*indices.first()?)?;
+        match indices.iter().all(|i| table[*i] == first) {
+            true => Some(first as char),
+            false => Some('X'),
+        }
+    }
+
+    /// Whether `codon` may initiate translation
+    pub fn is_start(&self, codon: &str) -> bool {
+        let indices = codon_indices(codon.as_bytes());
+        !indices.is_empty() && indices.iter().all(|i| self.sncbieaa.as_bytes()[*i] == b'M')
+    }
+
+    /// Whether `codon` terminates translation
+    pub fn is_stop(&self, codon: &str) -> bool {
+        self.translate_codon(codon) == Some('*')
+    }
+
+    /// Codons which may initiate translation, in table order
+    pub fn start_codons(&self) -> Vec<String> {
+        const BASES: [char; 4] = ['T', 'C', 'A', 'G'];
+        self.sncbieaa
+            .bytes()
+            .enumerate()
+            .filter(|(_, aa)| *aa == b'M')
+            .map(|(i, _)| [BASES[i / 16], BASES[i / 4 % 4], BASES[i % 4]].iter().collect())
+            .collect()
+    }
+
+    /// Translate the complete codons of `seq`
+    ///
+    /// When `initiator` is set, a start codon at the beginning of `seq` is translated
+    /// as "M", as is done for the first codon of a coding region. Trailing bases which
+    /// do not form a full codon are ignored.
+    pub fn translate(&self, seq: &str, initiator: bool) -> String {
+        seq.as_bytes()
+            .chunks_exact(3)
+            .enumerate()
+            .map(|(i, codon)| {
+                let codon = std::str::from_utf8(codon).unwrap_or_default();
+                match i == 0 && initiator && self.is_start(codon) {
+                    true => 'M',
+                    false => self.translate_codon(codon).unwrap_or('X'),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Genetic codes defined by NCBI
+///
+/// See <https://www.ncbi.nlm.nih.gov/Taxonomy/Utils/wprintgc.cgi>
+pub const GENETIC_CODES: [CodeTable; 26] = [
+    CodeTable {
+        id: 1,
+        name: "Standard",
+        ncbieaa: "FFLLSSSSYY**CC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        sncbieaa: "---M------**--*----M---------------M----------------------------",
+    },
+    CodeTable {
+        id: 2,
+        name: "Vertebrate Mitochondrial",
+        ncbieaa: "FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIMMTTTTNNKKSS**VVVVAAAADDEEGGGG",
+        sncbieaa: "----------**--------------------MMMM----------**---M------------",
+    },
+    CodeTable {
+        id: 3,
+        name: "Yeast Mitochondrial",
+        ncbieaa: "FFLLSSSSYY**CCWWTTTTPPPPHHQQRRRRIIMMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        sncbieaa: "----------**----------------------MM---------------M------------",
+    },
+    CodeTable {
+        id: 4,
+        name: "Mold Mitochondrial; Protozoan Mitochondrial; Coelenterate Mitochondrial; Mycoplasma; Spiroplasma",
+        ncbieaa: "FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        sncbieaa: "--MM------**-------M------------MMMM---------------M------------",
+    },
+    CodeTable {
+        id: 5,
+        name: "Invertebrate Mitochondrial",
+        ncbieaa: "FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIMMTTTTNNKKSSSSVVVVAAAADDEEGGGG",
+        sncbieaa: "---M------**--------------------MMMM---------------M------------",
+    },
+    CodeTable {
+        id: 6,
+        name: "Ciliate Nuclear; Dasycladacean Nuclear; Hexamita Nuclear",
+        ncbieaa: "FFLLSSSSYYQQCC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        sncbieaa: "--------------*--------------------M----------------------------",
+    },
+    CodeTable {
+        id: 9,
+        name: "Echinoderm Mitochondrial; Flatworm Mitochondrial",
+        ncbieaa: "FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIIMTTTTNNNKSSSSVVVVAAAADDEEGGGG",
+        sncbieaa: "-----------------------------------M---------------M------------",
+    },
+    CodeTable {
+        id: 10,
+        name: "Euplotid Nuclear",
+        ncbieaa: "FFLLSSSSYY**CCCWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        sncbieaa: "-----------------------------------M----------------------------",
+    },
+    CodeTable {
+        id: 11,
+        name: "Bacterial, Archaeal and Plant Plastid",
+        ncbieaa: "FFLLSSSSYY**CC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        sncbieaa: "---M------**--*----M------------MMMM---------------M------------",
+    },
+    CodeTable {
+        id: 12,
+        name: "Alternative Yeast Nuclear",
+        ncbieaa: "FFLLSSSSYY**CC*WLLLSPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        sncbieaa: "----------**--*----M---------------M----------------------------",
+    },
+    CodeTable {
+        id: 13,
+        name: "Ascidian Mitochondrial",
+        ncbieaa: "FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIMMTTTTNNKKSSGGVVVVAAAADDEEGGGG",
+        sncbieaa: "---M------**----------------------MM---------------M------------",
+    },
+    CodeTable {
+        id: 14,
+        name: "Alternative Flatworm Mitochondrial",
+        ncbieaa: "FFLLSSSSYYY*CCWWLLLLPPPPHHQQRRRRIIIMTTTTNNNKSSSSVVVVAAAADDEEGGGG",
+        sncbieaa: "-----------*-----------------------M----------------------------",
+    },
+    CodeTable {
+        id: 16,
+        name: "Chlorophycean Mitochondrial",
+        ncbieaa: "FFLLSSSSYY*LCC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        sncbieaa: "-----------------------------------M----------------------------",
+    },
+    CodeTable {
+        id: 21,
+        name: "Trematode Mitochondrial",
+        ncbieaa: "FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIMMTTTTNNNKSSSSVVVVAAAADDEEGGGG",
+        sncbieaa: "-----------------------------------M---------------M------------",
+    },
+    CodeTable {
+        id: 22,
+        name: "Scenedesmus obliquus Mitochondrial",
+        ncbieaa: "FFLLSS*SYY*LCC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        sncbieaa: "-----------------------------------M----------------------------",
+    },
+    CodeTable {
+        id: 23,
+        name: "Thraustochytrium Mitochondrial",
+        ncbieaa: "FF*LSSSSYY**CC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        sncbieaa: "--------------------------------M--M---------------M------------",
+    },
+    CodeTable {
+        id: 24,
+        name: "Rhabdopleuridae Mitochondrial",
+        ncbieaa: "FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSSKVVVVAAAADDEEGGGG",
+        sncbieaa: "---M------**-------M---------------M---------------M------------",
+    },
+    CodeTable {
+        id: 25,
+        name: "Candidate Division SR1 and Gracilibacteria",
+        ncbieaa: "FFLLSSSSYY**CCGWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        sncbieaa: "---M-------------------------------M---------------M------------",
+    },
+    CodeTable {
+        id: 26,
+        name: "Pachysolen tannophilus Nuclear",
+        ncbieaa: "FFLLSSSSYY**CC*WLLLAPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        sncbieaa: "----------**--*----M---------------M----------------------------",
+    },
+    CodeTable {
+        id: 27,
+        name: "Karyorelict Nuclear",
+        ncbieaa: "FFLLSSSSYYQQCCWWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        sncbieaa: "--------------*--------------------M----------------------------",
+    },
+    CodeTable {
+        id: 28,
+        name: "Condylostoma Nuclear",
+        ncbieaa: "FFLLSSSSYYQQCCWWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        sncbieaa: "----------**--*--------------------M----------------------------",
+    },
+    CodeTable {
+        id: 29,
+        name: "Mesodinium Nuclear",
+        ncbieaa: "FFLLSSSSYYYYCC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        sncbieaa: "--------------*--------------------M----------------------------",
+    },
+    CodeTable {
+        id: 30,
+        name: "Peritrich Nuclear",
+        ncbieaa: "FFLLSSSSYYEECC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        sncbieaa: "--------------*--------------------M----------------------------",
+    },
+    CodeTable {
+        id: 31,
+        name: "Blastocrithidia Nuclear",
+        ncbieaa: "FFLLSSSSYYEECCWWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        sncbieaa: "----------**-----------------------M----------------------------",
+    },
+    CodeTable {
+        id: 32,
+        name: "Balanophoraceae Plastid",
+        ncbieaa: "FFLLSSSSYY*WCC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        sncbieaa: "---M------*---*----M------------MMMM---------------M------------",
+    },
+    CodeTable {
+        id: 33,
+        name: "Cephalodiscidae Mitochondrial",
+        ncbieaa: "FFLLSSSSYYY*CCWWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSSKVVVVAAAADDEEGGGG",
+        sncbieaa: "---M-------*-------M---------------M---------------M------------",
+    },
+];
+
+/// Genetic code with the given id
+pub fn genetic_code(id: u64) -> Option<&'static CodeTable> {
+    GENETIC_CODES.iter().find(|code| code.id == id)
+}
+
+/// Genetic code with the given name, ignoring case
+///
+/// Names of codes which apply to several groups (eg: "Mold Mitochondrial; Protozoan
+/// Mitochondrial; ...") match any of the groups.
+pub fn genetic_code_by_name(name: &str) -> Option<&'static CodeTable> {
+    let name = name.trim();
+    GENETIC_CODES.iter().find(|code| {
+        code.name.eq_ignore_ascii_case(name)
+            || code.name.split("; ").any(|group| group.eq_ignore_ascii_case(name))
+    })
+}
+
+/// The standard genetic code, which applies unless another code is given
+pub fn standard_code() -> &'static CodeTable {
+    &GENETIC_CODES[0]
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::seqcode::{genetic_code, genetic_code_by_name, standard_code, GENETIC_CODES};
+
+    #[test]
+    fn genetic_codes() {
+        for code in GENETIC_CODES.iter() {
+            assert_eq!(code.ncbieaa.len(), 64, "table {}", code.id);
+            assert_eq!(code.sncbieaa.len(), 64, "table {}", code.id);
+        }
+        let standard = genetic_code(1).unwrap();
+        // ATG: A = 2, T = 0, G = 3
+        let atg = 2 * 16 + 3;
+        assert_eq!(&standard.ncbieaa[atg..=atg], "M");
+        assert_eq!(&standard.sncbieaa[atg..=atg], "M");
+        assert!(genetic_code(7).is_none());
+    }
+
+    #[test]
+    fn translate_codons() {
+        let standard = standard_code();
+        assert_eq!(standard.translate_codon("ATG"), Some('M'));
+        assert_eq!(standard.translate_codon("uga"), Some('*'));
+        // synonymous at the third position
+        assert_eq!(standard.translate_codon("CTN"), Some('L'));
+        assert_eq!(standard.translate_codon("NTG"), Some('X'));
+        assert_eq!(standard.translate_codon("AT"), None);
+        assert!(standard.is_stop("TAA") && !standard.is_stop("TGG"));
+        assert_eq!(standard.start_codons(), vec!["TTG", "CTG", "ATG"]);
+
+        // TGA encodes tryptophan in vertebrate mitochondria
+        let mito = genetic_code_by_name("vertebrate mitochondrial").unwrap();
+        assert_eq!(mito.id, 2);
+        assert_eq!(mito.translate_codon("TGA"), Some('W'));
+        assert!(mito.is_stop("AGA"));
+        assert_eq!(genetic_code_by_name("Mycoplasma").unwrap().id, 4);
+
+        // alternative start codons are translated as methionine
+        let bacterial = genetic_code(11).unwrap();
+        assert_eq!(bacterial.translate("GTGAAATAGC", true), "MK*");
+        assert_eq!(bacterial.translate("GTGAAATAGC", false), "VK*");
+    }
+}
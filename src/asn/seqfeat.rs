@@ -53,21 +53,25 @@
 //! that the data is correct but may not behave as expected.
 
 use crate::biblio::{PubMedId, DOI};
-use crate::general::{DbTag, IntFuzz, ObjectId, UserObject};
-use crate::parsing::{read_vec_node, read_int, read_node, read_string, read_vec_str_unchecked, UnexpectedTags, read_bool_attribute};
-use crate::r#pub::PubSet;
-use crate::seq::{Heterogen, Numbering, PubDesc, SeqLiteral};
-use crate::seqloc::{GiimportId, SeqId, SeqLoc};
-use crate::parsing::{XmlNode, XmlVecNode};
+use crate::general::{Date, DateStd, DbTag, DbXref, IntFuzz, ObjectId, UserObject};
+use crate::intern::SharedStr;
+use crate::parsing::{next_event, read_vec_node, read_int, read_node, read_raw, read_str, read_string, read_vec_int_unchecked, read_vec_str_unchecked, UnexpectedTags, read_bool_attribute};
+use crate::r#pub::{PubSet, MONTHS};
+use crate::seqcode::{genetic_code, genetic_code_by_name, standard_code, CodeTable, GENETIC_CODES};
+use crate::seq::{Heterogen, Numbering, PubDesc, SeqLiteral, NCBISTDAA_ALPHABET};
+use crate::seqloc::{GiimportId, NaStrand, SeqId, SeqLoc, TextseqId};
+use crate::parsing::{attribute_value, TagMatch, XmlNode, XmlNodeRef, XmlValue, XmlVecNode};
 use bitflags::bitflags;
 use enum_primitive::FromPrimitive;
+use quick_xml::events::attributes::Attributes;
 use quick_xml::events::{BytesStart, Event};
 use quick_xml::Reader;
 use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
 use std::str::FromStr;
 
-#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "lowercase")]
 /// Feature identifiers
 pub enum FeatId {
@@ -103,14 +107,14 @@ impl XmlNode for FeatId {
         let forbidden = UnexpectedTags(&forbidden);
 
         loop {
-            match reader.read_event().unwrap() {
+            match next_event(reader)? {
                 Event::Start(e) => {
                     let name = e.name();
 
-                    if name == local_tag.name() {
-                        return Self::Local(read_node(reader).unwrap()).into();
-                    } else if name == general_tag.name() {
-                        return Self::General(read_node(reader).unwrap()).into();
+                    if name.is_tag(local_tag.name()) {
+                        return Self::Local(read_node(reader)?).into();
+                    } else if name.is_tag(general_tag.name()) {
+                        return Self::General(read_node(reader)?).into();
                     } else if name != Self::start_bytes().name() {
                         forbidden.check(&name);
                     }
@@ -125,8 +129,10 @@ impl XmlNode for FeatId {
         }
     }
 }
+impl XmlVecNode for FeatId {}
 
 #[derive(Clone, Serialize_repr, Deserialize_repr, PartialEq, Debug)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[repr(u8)]
 /// Experimental evidence for existence of feature
 ///
@@ -142,6 +148,25 @@ pub enum SeqFeatExpEvidence {
     NotExperimental,
 }
 
+asn_names!(SeqFeatExpEvidence {
+    Experimental => "experimental",
+    NotExperimental => "not-experimental",
+});
+
+impl XmlValue for SeqFeatExpEvidence {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("Seq-feat_exp-ev")
+    }
+
+    fn from_attributes(attributes: Attributes) -> Option<Self> {
+        match attribute_value(attributes)?.as_str() {
+            "experimental" => Self::Experimental.into(),
+            "not-experimental" => Self::NotExperimental.into(),
+            _ => None
+        }
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 #[serde(rename_all = "kebab-case")]
 /// Sequence feature generalization
@@ -201,6 +226,41 @@ pub struct SeqFeat {
     pub support: Option<SeqFeatSupport>,
 }
 
+#[cfg(feature = "arbitrary")]
+/// Generates features whose data is a gene, region, bond, site, secondary structure,
+/// non-standard residue or heterogen
+///
+/// Fields which refer to publications, user objects or other features are left empty.
+impl<'a> arbitrary::Arbitrary<'a> for SeqFeat {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let data = match u.int_in_range(0..=6)? {
+            0 => SeqFeatData::Gene(u.arbitrary()?),
+            1 => SeqFeatData::Region(u.arbitrary()?),
+            2 => SeqFeatData::Bond(u.arbitrary()?),
+            3 => SeqFeatData::Site(u.arbitrary()?),
+            4 => SeqFeatData::PSecStr(u.arbitrary()?),
+            5 => SeqFeatData::NonStdResidue(u.arbitrary()?),
+            _ => SeqFeatData::Het(u.arbitrary()?),
+        };
+        Ok(Self {
+            id: u.arbitrary()?,
+            partial: u.arbitrary()?,
+            except: u.arbitrary()?,
+            comment: u.arbitrary()?,
+            product: u.arbitrary()?,
+            location: u.arbitrary()?,
+            qual: u.arbitrary()?,
+            title: u.arbitrary()?,
+            exp_ev: u.arbitrary()?,
+            dbxref: u.arbitrary()?,
+            pseudo: u.arbitrary()?,
+            except_text: u.arbitrary()?,
+            ids: u.arbitrary()?,
+            ..Self::new(data)
+        })
+    }
+}
+
 impl SeqFeat {
     /// not originally in spec
     pub fn default() -> Self {
@@ -230,6 +290,281 @@ impl SeqFeat {
             support: None,
         }
     }
+
+    /// Value of the first qualifier named `name` (eg: "product")
+    ///
+    /// Flag qualifiers (eg: "pseudo") have an empty value.
+    pub fn qualifier(&self, name: &str) -> Option<&str> {
+        self.qualifiers()
+            .find(|(qual, _)| *qual == name)
+            .map(|(_, val)| val)
+    }
+
+    /// Name and value of each qualifier, in order
+    pub fn qualifiers(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.qual
+            .iter()
+            .flatten()
+            .map(|qual| (qual.qual.as_str(), qual.val.as_str()))
+    }
+
+    /// Gene referenced by a [`SeqFeatXref`]
+    ///
+    /// Suppresses the need to search for an overlapping gene feature when the gene
+    /// is given directly (eg: as `locus_tag` on a CDS).
+    pub fn gene_xref(&self) -> Option<&GeneRef> {
+        self.xref.as_ref()?.iter().find_map(|xref| match &xref.data {
+            Some(SeqFeatData::Gene(gene)) => Some(gene),
+            _ => None,
+        })
+    }
+
+    /// Feature ids referenced by [`Self::xref`]
+    pub fn xref_ids(&self) -> impl Iterator<Item = &FeatId> {
+        self.xref.iter().flatten().filter_map(|xref| xref.id.as_ref())
+    }
+
+    /// All database cross-references for `db_name`
+    ///
+    /// For gene features, [`GeneRef::db`] is searched in addition to [`Self::dbxref`].
+    pub fn dbxrefs<'a>(&'a self, db_name: &'a str) -> impl Iterator<Item = &'a DbTag> {
+        self.all_dbxrefs().filter(move |tag| tag.db == db_name)
+    }
+
+    /// First database cross-reference for `db_name`
+    pub fn dbxref(&self, db_name: &str) -> Option<&DbTag> {
+        self.all_dbxrefs().find(|tag| tag.db == db_name)
+    }
+
+    /// [`Self::dbxref`], followed by [`GeneRef::db`] for gene features
+    fn all_dbxrefs(&self) -> impl Iterator<Item = &DbTag> {
+        let gene_db = match &self.data {
+            SeqFeatData::Gene(gene) => gene.db.as_ref(),
+            _ => None,
+        };
+        self.dbxref.iter().flatten().chain(gene_db.into_iter().flatten())
+    }
+
+    /// NCBI Gene ID from `GeneID` cross-reference
+    pub fn gene_id(&self) -> Option<u64> {
+        match self.dbxref("GeneID")?.typed() {
+            DbXref::GeneId(id) => Some(id),
+            _ => None,
+        }
+    }
+
+    /// Whether any of this feature's identifiers is equal to `id`
+    pub fn has_id(&self, id: &FeatId) -> bool {
+        self.id.as_ref() == Some(id) || self.ids.iter().flatten().any(|i| i == id)
+    }
+
+    /// Whether the feature is annotated on a pseudogene
+    ///
+    /// Besides [`Self::pseudo`], pseudogenes are flagged by a `pseudo` or `pseudogene`
+    /// qualifier, or by [`GeneRef::pseudo`] on gene features and gene xrefs.
+    pub fn is_pseudo(&self) -> bool {
+        let gene_pseudo = match &self.data {
+            SeqFeatData::Gene(gene) => gene.pseudo,
+            _ => false,
+        };
+        self.pseudo == Some(true)
+            || gene_pseudo
+            || self.gene_xref().is_some_and(|gene| gene.pseudo)
+            || self.qualifiers().any(|(name, _)| name == "pseudo" || name == "pseudogene")
+    }
+
+    /// Kind of pseudogene given by the `pseudogene` qualifier
+    pub fn pseudogene_type(&self) -> Option<PseudogeneType> {
+        self.qualifier("pseudogene")?.parse().ok()
+    }
+
+    /// Reasons for the feature to be biologically exceptional
+    ///
+    /// Reasons are read from [`Self::except_text`] and from `exception` qualifiers,
+    /// either of which may list several reasons separated by commas.
+    pub fn exceptions(&self) -> Vec<FeatException> {
+        self.except_text
+            .as_deref()
+            .into_iter()
+            .chain(self.qualifiers().filter(|(name, _)| *name == "exception").map(|(_, value)| value))
+            .flat_map(|text| text.split(','))
+            .map(str::trim)
+            .filter(|text| !text.is_empty())
+            .map(FeatException::from)
+            .collect()
+    }
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+#[serde(rename_all = "lowercase")]
+/// Kind of pseudogene, as given by the INSDC `pseudogene` qualifier
+pub enum PseudogeneType {
+    /// lacks introns and promoters, through retrotransposition of an mRNA
+    Processed,
+    /// arose by duplication of a gene, then lost its function
+    Unprocessed,
+    /// lost its function without being duplicated
+    Unitary,
+    /// lost its function in some individuals only
+    Allelic,
+    Unknown,
+}
+
+impl FromStr for PseudogeneType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "processed" => Ok(Self::Processed),
+            "unprocessed" => Ok(Self::Unprocessed),
+            "unitary" => Ok(Self::Unitary),
+            "allelic" => Ok(Self::Allelic),
+            "unknown" => Ok(Self::Unknown),
+            _ => Err(format!("Unknown pseudogene type: {}", s)),
+        }
+    }
+}
+
+impl std::fmt::Display for PseudogeneType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Processed => "processed",
+            Self::Unprocessed => "unprocessed",
+            Self::Unitary => "unitary",
+            Self::Allelic => "allelic",
+            Self::Unknown => "unknown",
+        })
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+/// Reason for a feature to be exceptional, given by [`SeqFeat::exceptions()`]
+///
+/// Covers the vocabulary of the INSDC `exception` qualifier, and the additional
+/// explanations accepted in RefSeq records.
+pub enum FeatException {
+    RnaEditing,
+    ReasonsGivenInCitation,
+    RibosomalSlippage,
+    TransSplicing,
+    AlternativeProcessing,
+    ArtificialFrameshift,
+    NonconsensusSpliceSite,
+    RearrangementRequiredForProduct,
+    ModifiedCodonRecognition,
+    AlternativeStartCodon,
+    DicistronicGene,
+    TranscribedProductReplaced,
+    TranslatedProductReplaced,
+    TranscribedPseudogene,
+    AnnotatedByTranscriptOrProteomicData,
+    HeterogeneousPopulationSequenced,
+    LowQualitySequenceRegion,
+    UnextendablePartialCodingRegion,
+    MismatchesInTranscription,
+    MismatchesInTranslation,
+    UnclassifiedTranscriptionDiscrepancy,
+    UnclassifiedTranslationDiscrepancy,
+    AdjustedForLowQualityGenome,
+    CircularRna,
+    /// text outside of the controlled vocabulary
+    Other(String),
+}
+
+/// Text of each [`FeatException`], except [`FeatException::Other`]
+const EXCEPTION_TEXTS: [(&str, FeatException); 24] = [
+    ("RNA editing", FeatException::RnaEditing),
+    ("reasons given in citation", FeatException::ReasonsGivenInCitation),
+    ("ribosomal slippage", FeatException::RibosomalSlippage),
+    ("trans-splicing", FeatException::TransSplicing),
+    ("alternative processing", FeatException::AlternativeProcessing),
+    ("artificial frameshift", FeatException::ArtificialFrameshift),
+    ("nonconsensus splice site", FeatException::NonconsensusSpliceSite),
+    ("rearrangement required for product", FeatException::RearrangementRequiredForProduct),
+    ("modified codon recognition", FeatException::ModifiedCodonRecognition),
+    ("alternative start codon", FeatException::AlternativeStartCodon),
+    ("dicistronic gene", FeatException::DicistronicGene),
+    ("transcribed product replaced", FeatException::TranscribedProductReplaced),
+    ("translated product replaced", FeatException::TranslatedProductReplaced),
+    ("transcribed pseudogene", FeatException::TranscribedPseudogene),
+    ("annotated by transcript or proteomic data", FeatException::AnnotatedByTranscriptOrProteomicData),
+    ("heterogeneous population sequenced", FeatException::HeterogeneousPopulationSequenced),
+    ("low-quality sequence region", FeatException::LowQualitySequenceRegion),
+    ("unextendable partial coding region", FeatException::UnextendablePartialCodingRegion),
+    ("mismatches in transcription", FeatException::MismatchesInTranscription),
+    ("mismatches in translation", FeatException::MismatchesInTranslation),
+    ("unclassified transcription discrepancy", FeatException::UnclassifiedTranscriptionDiscrepancy),
+    ("unclassified translation discrepancy", FeatException::UnclassifiedTranslationDiscrepancy),
+    ("adjusted for low-quality genome", FeatException::AdjustedForLowQualityGenome),
+    ("circular RNA", FeatException::CircularRna),
+];
+
+impl FeatException {
+    /// Text of the exception, as given in records
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Other(text) => text,
+            exception => EXCEPTION_TEXTS
+                .iter()
+                .find(|(_, known)| known == exception)
+                .map_or("", |(text, _)| text),
+        }
+    }
+
+    /// Whether the exception is outside of the controlled vocabulary
+    pub fn is_other(&self) -> bool {
+        matches!(self, Self::Other(_))
+    }
+
+    /// Whether translation shifts reading frame at a programmed site, so that the
+    /// coding region has a length which is not a multiple of 3
+    pub fn is_programmed_frameshift(&self) -> bool {
+        matches!(self, Self::RibosomalSlippage)
+    }
+
+    /// Whether the product of a coding region differs from the conceptual translation
+    /// of its location, so that translations should not be compared
+    pub fn alters_translation(&self) -> bool {
+        matches!(
+            self,
+            Self::RnaEditing
+                | Self::RibosomalSlippage
+                | Self::TransSplicing
+                | Self::ArtificialFrameshift
+                | Self::RearrangementRequiredForProduct
+                | Self::ModifiedCodonRecognition
+                | Self::AlternativeStartCodon
+                | Self::TranslatedProductReplaced
+                | Self::AnnotatedByTranscriptOrProteomicData
+                | Self::LowQualitySequenceRegion
+                | Self::MismatchesInTranslation
+                | Self::UnclassifiedTranslationDiscrepancy
+                | Self::AdjustedForLowQualityGenome
+        )
+    }
+
+    /// Whether the location of the feature is not a simple collinear interval set of
+    /// the genome, such as products spliced from distant loci
+    pub fn has_unusual_location(&self) -> bool {
+        matches!(self, Self::TransSplicing | Self::CircularRna | Self::RearrangementRequiredForProduct)
+    }
+}
+
+impl From<&str> for FeatException {
+    /// Case and surrounding whitespace are ignored
+    fn from(text: &str) -> Self {
+        let text = text.trim();
+        EXCEPTION_TEXTS
+            .iter()
+            .find(|(known, _)| known.eq_ignore_ascii_case(text))
+            .map_or_else(|| Self::Other(text.to_string()), |(_, exception)| exception.clone())
+    }
+}
+
+impl std::fmt::Display for FeatException {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
 }
 
 impl XmlNode for SeqFeat {
@@ -252,57 +587,66 @@ impl XmlNode for SeqFeat {
         let title_tag = BytesStart::new("Seq-feat_title");
         let ext_tag = BytesStart::new("Seq-feat_ext");
         let cit_tag = BytesStart::new("Seq-feat_cit");
-        let exp_ev_tag = BytesStart::new("Seq-feat_exp_ev");
+        let exp_ev_tag = BytesStart::new("Seq-feat_exp-ev");
         let xref_tag = BytesStart::new("Seq-feat_xref");
-        let dbxref_tag = BytesStart::new("Seq-feat_db_xref");
+        let dbxref_tag = BytesStart::new("Seq-feat_dbxref");
         let pseudo_tag = BytesStart::new("Seq-feat_pseudo");
-        let except_text_tag = BytesStart::new("Seq-feat_except_text");
+        let except_text_tag = BytesStart::new("Seq-feat_except-text");
         let ids_tag = BytesStart::new("Seq-feat_ids");
         let exts_tag = BytesStart::new("Seq-feat_exts");
         let support_tag = BytesStart::new("Seq-feat_support");
 
-        // list of XML elements that have not been implemented yet
-        let forbidden = [
-            partial_tag,
-            except_tag,
-            title_tag,
-            cit_tag,
-            exp_ev_tag,
-            dbxref_tag,
-            except_text_tag,
-            ids_tag,
-            exts_tag,
-            support_tag
-        ];
-        let forbidden = UnexpectedTags(&forbidden);
+        let forbidden = UnexpectedTags(&[]);
 
         loop {
-            match reader.read_event().unwrap() {
+            match next_event(reader)? {
                 Event::Start(e) => {
                     let name = e.name();
-                    if name == id_tag.name() {
+                    if name.is_tag(id_tag.name()) {
                         feat.id = read_node(reader);
-                    } else if name == ext_tag.name() {
+                    } else if name.is_tag(ext_tag.name()) {
                         feat.ext = read_node(reader);
-                    } else if name == product_tag.name() {
+                    } else if name.is_tag(product_tag.name()) {
                         feat.product = read_node(reader);
-                    } else if name == qual_tag.name() {
-                        feat.qual = Some(read_vec_node(reader, qual_tag.to_end()));
-                    } else if name == data_tag.name() {
-                        feat.data = read_node(reader).unwrap();
-                    } else if name == location_tag.name() {
-                        feat.location = read_node(reader).unwrap();
-                    } else if name == comment_tag.name() {
+                    } else if name.is_tag(qual_tag.name()) {
+                        feat.qual = Some(read_vec_node(reader, e.to_end()));
+                    } else if name.is_tag(data_tag.name()) {
+                        feat.data = read_node(reader)?;
+                    } else if name.is_tag(location_tag.name()) {
+                        feat.location = read_node(reader)?;
+                    } else if name.is_tag(comment_tag.name()) {
                         feat.comment = read_string(reader);
-                    } else if name == xref_tag.name() {
-                        feat.xref = Some(read_vec_node(reader, xref_tag.to_end()));
+                    } else if name.is_tag(xref_tag.name()) {
+                        feat.xref = Some(read_vec_node(reader, e.to_end()));
+                    } else if name.is_tag(dbxref_tag.name()) {
+                        feat.dbxref = Some(read_vec_node(reader, e.to_end()));
+                    } else if name.is_tag(title_tag.name()) {
+                        feat.title = read_string(reader);
+                    } else if name.is_tag(except_text_tag.name()) {
+                        feat.except_text = read_string(reader);
+                    } else if name.is_tag(cit_tag.name()) {
+                        feat.cit = read_node(reader);
+                    } else if name.is_tag(ids_tag.name()) {
+                        feat.ids = Some(read_vec_node(reader, e.to_end()));
+                    } else if name.is_tag(exts_tag.name()) {
+                        feat.exts = Some(read_vec_node(reader, e.to_end()));
+                    } else if name.is_tag(support_tag.name()) {
+                        feat.support = read_node(reader);
                     } else if name != Self::start_bytes().name() {
                         forbidden.check(&name);
                     }
                 }
                 Event::Empty(e) => {
-                    if e.name() == pseudo_tag.name() {
+                    let name = e.name();
+
+                    if name.is_tag(pseudo_tag.name()) {
                         feat.pseudo = read_bool_attribute(&e);
+                    } else if name.is_tag(partial_tag.name()) {
+                        feat.partial = read_bool_attribute(&e);
+                    } else if name.is_tag(except_tag.name()) {
+                        feat.except = read_bool_attribute(&e);
+                    } else if name.is_tag(exp_ev_tag.name()) {
+                        feat.exp_ev = SeqFeatExpEvidence::from_attributes(e.html_attributes());
                     }
                 }
                 Event::End(e) => {
@@ -318,6 +662,7 @@ impl XmlNode for SeqFeat {
 impl XmlVecNode for SeqFeat {}
 
 #[derive(Clone, Serialize_repr, Deserialize_repr, PartialEq, Debug)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[repr(u8)]
 /// Internal representation of chemical bond for [`SeqFeatData`]
 ///
@@ -333,7 +678,16 @@ pub enum SeqFeatBond {
     Other = 255,
 }
 
+asn_names!(SeqFeatBond {
+    Disulfide => "disulfide",
+    Thiolester => "thiolester",
+    XLink => "xlink",
+    Thioether => "thioether",
+    Other => "other",
+});
+
 #[derive(Clone, Serialize_repr, Deserialize_repr, PartialEq, Debug)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[repr(u8)]
 /// Internal representation of site biochemical modification for [`SeqFeatData`]
 ///
@@ -371,7 +725,38 @@ pub enum SeqFeatSite {
     Other = 255,
 }
 
+asn_names!(SeqFeatSite {
+    Active => "active",
+    Binding => "binding",
+    Cleavage => "cleavage",
+    Inhibit => "inhibit",
+    Modified => "modified",
+    Clycosylation => "glycosylation",
+    Myristoylation => "myristoylation",
+    Mutagenized => "mutagenized",
+    MetalBinding => "metal-binding",
+    Phosphorylation => "phosphorylation",
+    Acetylation => "acetylation",
+    Amidation => "amidation",
+    Methylation => "methylation",
+    Hydroxylation => "hydroxylation",
+    Sulfatation => "sulfatation",
+    OxidativeDeamination => "oxidative-deamination",
+    PyrrolidoneCarboxylicAcid => "pyrrolidone-carboxylic-acid",
+    GammaCarboxylglutamicAcid => "gamma-carboxyglutamic-acid",
+    Blocked => "blocked",
+    LipidBinding => "lipid-binding",
+    NpBinding => "np-binding",
+    DnaBinding => "dna-binding",
+    SignalPeptide => "signal-peptide",
+    TransitPeptide => "transit-peptide",
+    TransmembraneRegion => "transmembrane-region",
+    Nitrosylation => "nitrosylation",
+    Other => "other",
+});
+
 #[derive(Clone, Serialize_repr, Deserialize_repr, PartialEq, Debug)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[repr(u8)]
 /// Internal representation of protein secondary structure for [`SeqFeatData`]
 ///
@@ -390,6 +775,12 @@ pub enum PSecStr {
     Turn,
 }
 
+asn_names!(PSecStr {
+    Helix => "helix",
+    Sheet => "sheet",
+    Turn => "turn",
+});
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 #[serde(rename_all = "lowercase")]
 pub enum SeqFeatData {
@@ -439,6 +830,11 @@ pub enum SeqFeatData {
     BioSrc(BioSource),
     Clone(CloneRef),
     Variation(VariationRef),
+
+    /// feature data which is not parsed, kept as XML (eg: `<SeqFeatData_imp>...`)
+    ///
+    /// Only produced with the `raw-xml` feature, see [`crate::seq::SeqDesc::Unknown`].
+    Unknown(String),
 }
 
 impl XmlNode for SeqFeatData {
@@ -465,18 +861,20 @@ impl XmlNode for SeqFeatData {
         let num_tag = BytesStart::new("SeqFeatData_num");
         let psec_str_tag = BytesStart::new("SeqFeatData_psec-str");
         let non_std_residue_tag = BytesStart::new("SeqFeatData_non-std-residue");
-        let het_tag = BytesStart::new("SeqFeatData_het");
         let biosrc_tag = BytesStart::new("SeqFeatData_biosrc");
         let clone_tag = BytesStart::new("SeqFeatData_clone");
         let variation_tag = BytesStart::new("SeqFeatData_variation");
 
+        let het_tag = BytesStart::new("SeqFeatData_het");
+
+        // value of "SeqFeatData_het"
+        let heterogen_tag = BytesStart::new("Heterogen");
+
         let forbidden = [
             org_tag,
-            rna_tag,
             pub_tag,
             seq_tag,
             imp_tag,
-            region_tag,
             bond_tag,
             site_tag,
             rsite_tag,
@@ -484,8 +882,6 @@ impl XmlNode for SeqFeatData {
             txinit_tag,
             num_tag,
             psec_str_tag,
-            non_std_residue_tag,
-            het_tag,
             biosrc_tag,
             clone_tag,
             variation_tag
@@ -493,20 +889,35 @@ impl XmlNode for SeqFeatData {
         let forbidden = UnexpectedTags(&forbidden);
 
         loop {
-            match reader.read_event().unwrap() {
+            match next_event(reader)? {
                 Event::Start(e) => {
                     let name = e.name();
 
-                    if name == gene_tag.name() {
-                        return Self::Gene(read_node(reader).unwrap()).into()
+                    if name.is_tag(gene_tag.name()) {
+                        return Self::Gene(read_node(reader)?).into()
+                    }
+                    else if name.is_tag(cdregion_tag.name()) {
+                        return Self::CdRegion(read_node(reader)?).into()
+                    }
+                    else if name.is_tag(prot_tag.name()) {
+                        return Self::Prot(read_node(reader)?).into();
                     }
-                    else if name == cdregion_tag.name() {
-                        return Self::CdRegion(read_node(reader).unwrap()).into()
+                    else if name.is_tag(rna_tag.name()) {
+                        return Self::RNA(read_node(reader)?).into();
                     }
-                    else if name == prot_tag.name() {
-                        return Self::Prot(read_node(reader).unwrap()).into();
+                    else if name.is_tag(region_tag.name()) {
+                        return Self::Region(read_string(reader)?).into();
                     }
-                    else if name != Self::start_bytes().name() {
+                    else if name.is_tag(non_std_residue_tag.name()) {
+                        return Self::NonStdResidue(read_string(reader)?).into();
+                    }
+                    else if name.is_tag(heterogen_tag.name()) {
+                        return Self::Het(read_string(reader)?).into();
+                    }
+                    else if name != Self::start_bytes().name() && !name.is_tag(het_tag.name()) {
+                        if cfg!(feature = "raw-xml") {
+                            return Self::Unknown(read_raw(reader, &e)?).into();
+                        }
                         forbidden.check(&name);
                     }
                 }
@@ -540,13 +951,13 @@ impl XmlNode for SeqFeatXref {
         let id_tag = BytesStart::new("SeqFeatXref_id");
 
         loop {
-            match reader.read_event().unwrap() {
+            match next_event(reader)? {
                 Event::Start(e) => {
                     let name = e.name();
 
-                    if name == data_tag.name() {
+                    if name.is_tag(data_tag.name()) {
                         xref.data = read_node(reader);
-                    } else if name == id_tag.name() {
+                    } else if name.is_tag(id_tag.name()) {
                         xref.id = read_node(reader);
                     }
                 }
@@ -585,6 +996,13 @@ pub enum EvidenceCategory {
     Existence,
 }
 
+asn_names!(EvidenceCategory {
+    NotSet => "not-set",
+    Coordinates => "coordinates",
+    Description => "description",
+    Existence => "existence",
+});
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 pub struct ExperimentSupport {
     pub category: Option<EvidenceCategory>,
@@ -631,46 +1049,761 @@ pub enum InferenceSupportType {
     Other = 255,
 }
 
-#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
-#[serde(rename_all = "kebab-case")]
-pub struct InferenceSupport {
-    pub category: Option<EvidenceCategory>,
-    #[serde(rename = "type")]
-    pub r#type: InferenceSupportType,
-    pub other_type: Option<String>,
-    // TODO: default to false
-    pub same_species: bool,
-    pub basis: EvidenceBasis,
-    pub pmids: Option<Vec<PubMedId>>,
-    pub dois: Option<Vec<DOI>>,
+asn_names!(InferenceSupportType {
+    NotSet => "not-set",
+    SimilarToSequence => "similar-to-sequence",
+    SimilarToAA => "similar-to-aa",
+    SimilarToDNA => "similar-to-dna",
+    SimilarToRNA => "similar-to-rna",
+    SimilarTomRNA => "similar-to-mrna",
+    SimilarToEst => "similar-to-est",
+    SimilarToOtherRNA => "similar-to-other-rna",
+    Profile => "profile",
+    NucleotideMotif => "nucleotide-motif",
+    ProteinMotif => "protein-motif",
+    AbInitioPrediction => "ab-initio-prediction",
+    Alignment => "alignment",
+    Other => "other",
+});
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub struct InferenceSupport {
+    pub category: Option<EvidenceCategory>,
+    #[serde(rename = "type")]
+    pub r#type: InferenceSupportType,
+    pub other_type: Option<String>,
+    // TODO: default to false
+    pub same_species: bool,
+    pub basis: EvidenceBasis,
+    pub pmids: Option<Vec<PubMedId>>,
+    pub dois: Option<Vec<DOI>>,
+}
+
+impl EvidenceCategory {
+    /// Label used as the prefix of `/experiment` and `/inference` qualifiers
+    fn label(&self) -> Option<&'static str> {
+        match self {
+            Self::NotSet => None,
+            Self::Coordinates => Some("COORDINATES"),
+            Self::Description => Some("DESCRIPTION"),
+            Self::Existence => Some("EXISTENCE"),
+        }
+    }
+
+    /// Split an optional category prefix from a qualifier value
+    fn split_prefix(text: &str) -> (Option<Self>, &str) {
+        for category in [Self::Coordinates, Self::Description, Self::Existence] {
+            let label = category.label().unwrap();
+            if let Some(rest) = text.strip_prefix(label).and_then(|rest| rest.strip_prefix(':')) {
+                return (Some(category), rest.trim_start());
+            }
+        }
+        (None, text)
+    }
+}
+
+impl FromStr for ExperimentSupport {
+    type Err = String;
+
+    /// Parse the value of an INSDC `/experiment` qualifier
+    ///
+    /// The value takes the form `[CATEGORY:]text`, where text may end with
+    /// bracketed PubMed ids or DOIs (eg: "EXISTENCE:Northern blot [PMID:12345]").
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (category, text) = EvidenceCategory::split_prefix(s.trim());
+        if text.is_empty() {
+            return Err(format!("Experiment has no description: \"{}\"", s));
+        }
+
+        let mut experiment = ExperimentSupport {
+            category,
+            explanation: text.to_string(),
+            pmids: None,
+            dois: None,
+        };
+        if let Some((explanation, refs)) = split_references(text) {
+            experiment.explanation = explanation.to_string();
+            experiment.pmids = refs.0;
+            experiment.dois = refs.1;
+        }
+        Ok(experiment)
+    }
+}
+
+impl std::fmt::Display for ExperimentSupport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(label) = self.category.as_ref().and_then(EvidenceCategory::label) {
+            write!(f, "{}:", label)?;
+        }
+        write!(f, "{}", self.explanation)?;
+        write_references(f, &self.pmids, &self.dois)
+    }
+}
+
+type References = (Option<Vec<PubMedId>>, Option<Vec<DOI>>);
+
+/// Split trailing "[PMID:1,DOI:10.1000/1]" from `text`
+fn split_references(text: &str) -> Option<(&str, References)> {
+    let (explanation, refs) = text.strip_suffix(']')?.rsplit_once('[')?;
+
+    let mut pmids = Vec::new();
+    let mut dois = Vec::new();
+    for item in refs.split(',') {
+        match item.trim().split_once(':')? {
+            ("PMID", pmid) => pmids.push(pmid.trim().parse().ok()?),
+            ("DOI", doi) => dois.push(doi.trim().to_string()),
+            _ => return None,
+        }
+    }
+    let pmids = (!pmids.is_empty()).then_some(pmids);
+    let dois = (!dois.is_empty()).then_some(dois);
+    Some((explanation.trim_end(), (pmids, dois)))
+}
+
+fn write_references(
+    f: &mut std::fmt::Formatter<'_>,
+    pmids: &Option<Vec<PubMedId>>,
+    dois: &Option<Vec<DOI>>,
+) -> std::fmt::Result {
+    let refs: Vec<String> = pmids
+        .iter()
+        .flatten()
+        .map(|pmid| format!("PMID:{}", pmid))
+        .chain(dois.iter().flatten().map(|doi| format!("DOI:{}", doi)))
+        .collect();
+    if refs.is_empty() {
+        return Ok(());
+    }
+    write!(f, " [{}]", refs.join(","))
+}
+
+impl InferenceSupportType {
+    /// INSDC vocabulary for each type
+    ///
+    /// Longer phrases are listed first so that they are matched before their prefixes.
+    const VOCABULARY: [(InferenceSupportType, &'static str); 13] = [
+        (Self::NotSet, "non-experimental evidence, no additional details recorded"),
+        (Self::SimilarTomRNA, "similar to RNA sequence, mRNA"),
+        (Self::SimilarToEst, "similar to RNA sequence, EST"),
+        (Self::SimilarToOtherRNA, "similar to RNA sequence, other RNA"),
+        (Self::SimilarToRNA, "similar to RNA sequence"),
+        (Self::SimilarToAA, "similar to AA sequence"),
+        (Self::SimilarToDNA, "similar to DNA sequence"),
+        (Self::SimilarToSequence, "similar to sequence"),
+        (Self::Profile, "profile"),
+        (Self::NucleotideMotif, "nucleotide motif"),
+        (Self::ProteinMotif, "protein motif"),
+        (Self::AbInitioPrediction, "ab initio prediction"),
+        (Self::Alignment, "alignment"),
+    ];
+
+    fn label(&self) -> Option<&'static str> {
+        Self::VOCABULARY
+            .iter()
+            .find(|(r#type, _)| r#type == self)
+            .map(|(_, label)| *label)
+    }
+
+    /// Whether the evidence basis lists sequence accessions rather than programs
+    fn is_similarity(&self) -> bool {
+        matches!(
+            self,
+            Self::SimilarToSequence
+                | Self::SimilarToAA
+                | Self::SimilarToDNA
+                | Self::SimilarToRNA
+                | Self::SimilarTomRNA
+                | Self::SimilarToEst
+                | Self::SimilarToOtherRNA
+        )
+    }
+}
+
+/// Parse "RefSeq:WP_000000000.1" as a [`SeqId`]
+///
+/// Databases without a dedicated [`SeqId`] variant are kept as [`SeqId::General`].
+fn parse_basis_accession(text: &str) -> Result<SeqId, String> {
+    let (db, accession) = text
+        .split_once(':')
+        .ok_or(format!("Evidence basis is not of the form DATABASE:ACCESSION: \"{}\"", text))?;
+    let textseq = || {
+        let (accession, version) = match accession.rsplit_once('.') {
+            Some((accession, version)) if version.parse::<u64>().is_ok() => {
+                (accession, version.parse().ok())
+            }
+            _ => (accession, None),
+        };
+        TextseqId {
+            accession: Some(accession.to_string()),
+            version,
+            ..TextseqId::default()
+        }
+    };
+    Ok(match db {
+        "INSD" => SeqId::Genbank(textseq()),
+        "RefSeq" => SeqId::Other(textseq()),
+        "UniProtKB" => SeqId::Swissprot(textseq()),
+        _ => SeqId::General(DbTag {
+            db: db.into(),
+            tag: ObjectId::Str(accession.into()),
+        }),
+    })
+}
+
+fn format_basis_accession(id: &SeqId) -> Option<String> {
+    let (db, text) = match id {
+        SeqId::Genbank(text) | SeqId::Embl(text) | SeqId::Ddbj(text) => ("INSD", text),
+        SeqId::Other(text) => ("RefSeq", text),
+        SeqId::Swissprot(text) => ("UniProtKB", text),
+        SeqId::General(tag) => {
+            return Some(match &tag.tag {
+                ObjectId::Id(id) => format!("{}:{}", tag.db, id),
+                ObjectId::Str(id) => format!("{}:{}", tag.db, id),
+            })
+        }
+        _ => return None,
+    };
+    let accession = text.accession.as_ref()?;
+    Some(match text.version {
+        Some(version) => format!("{}:{}.{}", db, accession, version),
+        None => format!("{}:{}", db, accession),
+    })
+}
+
+impl FromStr for InferenceSupport {
+    type Err = String;
+
+    /// Parse the value of an INSDC `/inference` qualifier
+    ///
+    /// The value takes the form `[CATEGORY:]TYPE[ (same species)][:EVIDENCE_BASIS]`
+    /// (eg: "COORDINATES: similar to AA sequence:RefSeq:WP_000000000.1"). The evidence
+    /// basis of similarity types is a comma separated list of `DATABASE:ACCESSION`,
+    /// and is otherwise a list of `PROGRAM:VERSION`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (category, text) = EvidenceCategory::split_prefix(s.trim());
+
+        let (r#type, rest) = InferenceSupportType::VOCABULARY
+            .iter()
+            .find_map(|(r#type, label)| text.strip_prefix(label).map(|rest| (r#type.clone(), rest)))
+            .ok_or(format!("Unknown inference type: \"{}\"", s))?;
+
+        let (same_species, rest) = match rest.strip_prefix(" (same species)") {
+            Some(rest) => (true, rest),
+            None => (false, rest),
+        };
+
+        let mut basis = EvidenceBasis {
+            programs: None,
+            accessions: None,
+        };
+        match rest.strip_prefix(':') {
+            None if rest.is_empty() => (),
+            None => return Err(format!("Unexpected text after inference type: \"{}\"", s)),
+            Some(items) if r#type.is_similarity() => {
+                basis.accessions = Some(
+                    items
+                        .split(',')
+                        .map(|item| parse_basis_accession(item.trim()))
+                        .collect::<Result<_, _>>()?,
+                );
+            }
+            Some(items) => {
+                basis.programs = Some(
+                    items
+                        .split(',')
+                        .map(|item| match item.trim().split_once(':') {
+                            Some((name, version)) => ProgramId {
+                                name: name.to_string(),
+                                version: Some(version.to_string()),
+                            },
+                            None => ProgramId {
+                                name: item.trim().to_string(),
+                                version: None,
+                            },
+                        })
+                        .collect(),
+                );
+            }
+        }
+
+        Ok(InferenceSupport {
+            category,
+            r#type,
+            other_type: None,
+            same_species,
+            basis,
+            pmids: None,
+            dois: None,
+        })
+    }
+}
+
+impl std::fmt::Display for InferenceSupport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(label) = self.category.as_ref().and_then(EvidenceCategory::label) {
+            write!(f, "{}: ", label)?;
+        }
+        let label = match self.r#type {
+            InferenceSupportType::Other => self.other_type.as_deref().unwrap_or_default(),
+            ref r#type => r#type.label().unwrap_or_default(),
+        };
+        write!(f, "{}", label)?;
+        if self.same_species {
+            write!(f, " (same species)")?;
+        }
+
+        let accessions = self.basis.accessions.iter().flatten().filter_map(format_basis_accession);
+        let programs = self.basis.programs.iter().flatten().map(|program| match &program.version {
+            Some(version) => format!("{}:{}", program.name, version),
+            None => program.name.clone(),
+        });
+        let basis: Vec<String> = accessions.chain(programs).collect();
+        if !basis.is_empty() {
+            write!(f, ":{}", basis.join(","))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub struct ModelEvidenceItem {
+    pub id: SeqId,
+    pub exon_count: Option<u64>,
+    pub exon_length: Option<u64>,
+    // TODO: default to false
+    pub full_length: bool,
+    // TODO: default to false
+    pub supports_all_exon_combo: bool,
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub struct ModelEvidenceSupport {
+    pub method: Option<String>,
+    pub mrna: Option<Vec<ModelEvidenceItem>>,
+    pub est: Option<Vec<ModelEvidenceItem>>,
+    pub protein: Option<Vec<ModelEvidenceItem>>,
+    pub identification: Option<SeqId>,
+    pub dbxref: Option<Vec<DbTag>>,
+    pub exon_count: Option<u64>,
+    pub exon_length: Option<u64>,
+    pub full_length: bool,             // TODO: default false
+    pub supports_all_exon_combo: bool, // TODO: default false
+}
+
+impl XmlNode for SeqFeatSupport {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("SeqFeatSupport")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> where Self: Sized {
+        // field tags
+        let experiment_tag = BytesStart::new("SeqFeatSupport_experiment");
+        let inference_tag = BytesStart::new("SeqFeatSupport_inference");
+        let model_evidence_tag = BytesStart::new("SeqFeatSupport_model-evidence");
+
+        let mut support = Self {
+            experiment: None,
+            inference: None,
+            model_evidence: None,
+        };
+
+        let forbidden = UnexpectedTags(&[]);
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => {
+                    let name = e.name();
+
+                    if name.is_tag(experiment_tag.name()) {
+                        support.experiment = Some(read_vec_node(reader, e.to_end()));
+                    } else if name.is_tag(inference_tag.name()) {
+                        support.inference = Some(read_vec_node(reader, e.to_end()));
+                    } else if name.is_tag(model_evidence_tag.name()) {
+                        support.model_evidence = Some(read_vec_node(reader, e.to_end()));
+                    } else if name != Self::start_bytes().name() {
+                        forbidden.check(&name)
+                    }
+                }
+                Event::End(e) if Self::is_end(&e) => return support.into(),
+                _ => ()
+            }
+        }
+    }
+}
+
+impl XmlValue for EvidenceCategory {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("EvidenceCategory")
+    }
+
+    fn from_attributes(attributes: Attributes) -> Option<Self> {
+        match attribute_value(attributes)?.as_str() {
+            "not-set" => Self::NotSet.into(),
+            "coordinates" => Self::Coordinates.into(),
+            "description" => Self::Description.into(),
+            "existence" => Self::Existence.into(),
+            _ => None
+        }
+    }
+}
+
+impl XmlNode for ExperimentSupport {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("ExperimentSupport")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> where Self: Sized {
+        // field tags
+        let category_field_tag = BytesStart::new("ExperimentSupport_category");
+        let category_tag = EvidenceCategory::start_bytes();
+        let explanation_tag = BytesStart::new("ExperimentSupport_explanation");
+        let pmids_tag = BytesStart::new("ExperimentSupport_pmids");
+        let dois_tag = BytesStart::new("ExperimentSupport_dois");
+
+        let mut category = None;
+        let mut explanation = None;
+        let mut pmids = None;
+        let mut dois = None;
+
+        let forbidden = UnexpectedTags(&[]);
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => {
+                    let name = e.name();
+
+                    if name.is_tag(category_tag.name()) {
+                        category = EvidenceCategory::from_attributes(e.html_attributes());
+                    } else if name.is_tag(explanation_tag.name()) {
+                        explanation = read_string(reader);
+                    } else if name.is_tag(pmids_tag.name()) {
+                        pmids = Some(read_vec_int_unchecked(reader, &e.to_end()));
+                    } else if name.is_tag(dois_tag.name()) {
+                        dois = Some(read_vec_str_unchecked(reader, &e.to_end()));
+                    } else if name != Self::start_bytes().name() && !name.is_tag(category_field_tag.name()) {
+                        forbidden.check(&name)
+                    }
+                }
+                Event::End(e) if Self::is_end(&e) => {
+                    return Some(Self {
+                        category,
+                        explanation: explanation?,
+                        pmids,
+                        dois,
+                    })
+                }
+                _ => ()
+            }
+        }
+    }
+}
+impl XmlVecNode for ExperimentSupport {}
+
+impl XmlNode for ProgramId {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("Program-id")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> where Self: Sized {
+        // field tags
+        let name_tag = BytesStart::new("Program-id_name");
+        let version_tag = BytesStart::new("Program-id_version");
+
+        let mut program_name = None;
+        let mut version = None;
+
+        let forbidden = UnexpectedTags(&[]);
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => {
+                    let name = e.name();
+
+                    if name.is_tag(name_tag.name()) {
+                        program_name = read_string(reader);
+                    } else if name.is_tag(version_tag.name()) {
+                        version = read_string(reader);
+                    } else if name != Self::start_bytes().name() {
+                        forbidden.check(&name)
+                    }
+                }
+                Event::End(e) if Self::is_end(&e) => {
+                    return Some(Self {
+                        name: program_name?,
+                        version,
+                    })
+                }
+                _ => ()
+            }
+        }
+    }
+}
+impl XmlVecNode for ProgramId {}
+
+impl XmlNode for EvidenceBasis {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("EvidenceBasis")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> where Self: Sized {
+        // field tags
+        let programs_tag = BytesStart::new("EvidenceBasis_programs");
+        let accessions_tag = BytesStart::new("EvidenceBasis_accessions");
+
+        let mut basis = Self {
+            programs: None,
+            accessions: None,
+        };
+
+        let forbidden = UnexpectedTags(&[]);
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => {
+                    let name = e.name();
+
+                    if name.is_tag(programs_tag.name()) {
+                        basis.programs = Some(read_vec_node(reader, e.to_end()));
+                    } else if name.is_tag(accessions_tag.name()) {
+                        basis.accessions = Some(read_vec_node(reader, e.to_end()));
+                    } else if name != Self::start_bytes().name() {
+                        forbidden.check(&name)
+                    }
+                }
+                Event::End(e) if Self::is_end(&e) => return basis.into(),
+                _ => ()
+            }
+        }
+    }
+}
+
+impl XmlValue for InferenceSupportType {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("InferenceSupport_type")
+    }
+
+    fn from_attributes(attributes: Attributes) -> Option<Self> {
+        match attribute_value(attributes)?.as_str() {
+            "not-set" => Self::NotSet.into(),
+            "similar-to-sequence" => Self::SimilarToSequence.into(),
+            "similar-to-aa" => Self::SimilarToAA.into(),
+            "similar-to-dna" => Self::SimilarToDNA.into(),
+            "similar-to-rna" => Self::SimilarToRNA.into(),
+            "similar-to-mrna" => Self::SimilarTomRNA.into(),
+            "similar-to-est" => Self::SimilarToEst.into(),
+            "similar-to-other-rna" => Self::SimilarToOtherRNA.into(),
+            "profile" => Self::Profile.into(),
+            "nucleotide-motif" => Self::NucleotideMotif.into(),
+            "protein-motif" => Self::ProteinMotif.into(),
+            "ab-initio-prediction" => Self::AbInitioPrediction.into(),
+            "alignment" => Self::Alignment.into(),
+            "other" => Self::Other.into(),
+            _ => None
+        }
+    }
+}
+
+impl XmlNode for InferenceSupport {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("InferenceSupport")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> where Self: Sized {
+        // field tags
+        let category_field_tag = BytesStart::new("InferenceSupport_category");
+        let category_tag = EvidenceCategory::start_bytes();
+        let type_tag = InferenceSupportType::start_bytes();
+        let other_type_tag = BytesStart::new("InferenceSupport_other-type");
+        let same_species_tag = BytesStart::new("InferenceSupport_same-species");
+        let basis_tag = BytesStart::new("InferenceSupport_basis");
+        let pmids_tag = BytesStart::new("InferenceSupport_pmids");
+        let dois_tag = BytesStart::new("InferenceSupport_dois");
+
+        let mut inference = Self {
+            category: None,
+            r#type: InferenceSupportType::default(),
+            other_type: None,
+            same_species: false,
+            basis: EvidenceBasis {
+                programs: None,
+                accessions: None,
+            },
+            pmids: None,
+            dois: None,
+        };
+
+        let forbidden = UnexpectedTags(&[]);
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => {
+                    let name = e.name();
+
+                    if name.is_tag(category_tag.name()) {
+                        inference.category = EvidenceCategory::from_attributes(e.html_attributes());
+                    } else if name.is_tag(type_tag.name()) {
+                        inference.r#type = InferenceSupportType::from_attributes(e.html_attributes())?;
+                    } else if name.is_tag(other_type_tag.name()) {
+                        inference.other_type = read_string(reader);
+                    } else if name.is_tag(basis_tag.name()) {
+                        inference.basis = read_node(reader)?;
+                    } else if name.is_tag(pmids_tag.name()) {
+                        inference.pmids = Some(read_vec_int_unchecked(reader, &e.to_end()));
+                    } else if name.is_tag(dois_tag.name()) {
+                        inference.dois = Some(read_vec_str_unchecked(reader, &e.to_end()));
+                    } else if name != Self::start_bytes().name() && !name.is_tag(category_field_tag.name()) {
+                        forbidden.check(&name)
+                    }
+                }
+                Event::Empty(e) if e.name().is_tag(same_species_tag.name()) => {
+                    inference.same_species = read_bool_attribute(&e).unwrap_or_default();
+                }
+                Event::End(e) if Self::is_end(&e) => return inference.into(),
+                _ => ()
+            }
+        }
+    }
+}
+impl XmlVecNode for InferenceSupport {}
+
+impl XmlNode for ModelEvidenceItem {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("ModelEvidenceItem")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> where Self: Sized {
+        // field tags
+        let id_tag = BytesStart::new("ModelEvidenceItem_id");
+        let exon_count_tag = BytesStart::new("ModelEvidenceItem_exon-count");
+        let exon_length_tag = BytesStart::new("ModelEvidenceItem_exon-length");
+        let full_length_tag = BytesStart::new("ModelEvidenceItem_full-length");
+        let combo_tag = BytesStart::new("ModelEvidenceItem_supports-all-exon-combo");
+
+        let mut id = None;
+        let mut exon_count = None;
+        let mut exon_length = None;
+        let mut full_length = false;
+        let mut supports_all_exon_combo = false;
+
+        let forbidden = UnexpectedTags(&[]);
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => {
+                    let name = e.name();
+
+                    if name.is_tag(id_tag.name()) {
+                        id = read_node(reader);
+                    } else if name.is_tag(exon_count_tag.name()) {
+                        exon_count = read_int(reader);
+                    } else if name.is_tag(exon_length_tag.name()) {
+                        exon_length = read_int(reader);
+                    } else if name != Self::start_bytes().name() {
+                        forbidden.check(&name)
+                    }
+                }
+                Event::Empty(e) => {
+                    let name = e.name();
+
+                    if name.is_tag(full_length_tag.name()) {
+                        full_length = read_bool_attribute(&e).unwrap_or_default();
+                    } else if name.is_tag(combo_tag.name()) {
+                        supports_all_exon_combo = read_bool_attribute(&e).unwrap_or_default();
+                    }
+                }
+                Event::End(e) if Self::is_end(&e) => {
+                    return Some(Self {
+                        id: id?,
+                        exon_count,
+                        exon_length,
+                        full_length,
+                        supports_all_exon_combo,
+                    })
+                }
+                _ => ()
+            }
+        }
+    }
 }
+impl XmlVecNode for ModelEvidenceItem {}
 
-#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
-#[serde(rename_all = "kebab-case")]
-pub struct ModelEvidenceItem {
-    pub id: SeqId,
-    pub exon_count: Option<u64>,
-    pub exon_length: Option<u64>,
-    // TODO: default to false
-    pub full_length: bool,
-    // TODO: default to false
-    pub supports_all_exon_combo: bool,
-}
+impl XmlNode for ModelEvidenceSupport {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("ModelEvidenceSupport")
+    }
 
-#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
-#[serde(rename_all = "kebab-case")]
-pub struct ModelEvidenceSupport {
-    pub method: Option<String>,
-    pub mrna: Option<Vec<ModelEvidenceItem>>,
-    pub est: Option<Vec<ModelEvidenceItem>>,
-    pub protein: Option<Vec<ModelEvidenceItem>>,
-    pub identification: Option<SeqId>,
-    pub dbxref: Option<Vec<DbTag>>,
-    pub exon_count: Option<u64>,
-    pub exon_length: Option<u64>,
-    pub full_length: bool,             // TODO: default false
-    pub supports_all_exon_combo: bool, // TODO: default false
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> where Self: Sized {
+        // field tags
+        let method_tag = BytesStart::new("ModelEvidenceSupport_method");
+        let mrna_tag = BytesStart::new("ModelEvidenceSupport_mrna");
+        let est_tag = BytesStart::new("ModelEvidenceSupport_est");
+        let protein_tag = BytesStart::new("ModelEvidenceSupport_protein");
+        let identification_tag = BytesStart::new("ModelEvidenceSupport_identification");
+        let dbxref_tag = BytesStart::new("ModelEvidenceSupport_dbxref");
+        let exon_count_tag = BytesStart::new("ModelEvidenceSupport_exon-count");
+        let exon_length_tag = BytesStart::new("ModelEvidenceSupport_exon-length");
+        let full_length_tag = BytesStart::new("ModelEvidenceSupport_full-length");
+        let combo_tag = BytesStart::new("ModelEvidenceSupport_supports-all-exon-combo");
+
+        let mut support = Self {
+            method: None,
+            mrna: None,
+            est: None,
+            protein: None,
+            identification: None,
+            dbxref: None,
+            exon_count: None,
+            exon_length: None,
+            full_length: false,
+            supports_all_exon_combo: false,
+        };
+
+        let forbidden = UnexpectedTags(&[]);
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => {
+                    let name = e.name();
+
+                    if name.is_tag(method_tag.name()) {
+                        support.method = read_string(reader);
+                    } else if name.is_tag(mrna_tag.name()) {
+                        support.mrna = Some(read_vec_node(reader, e.to_end()));
+                    } else if name.is_tag(est_tag.name()) {
+                        support.est = Some(read_vec_node(reader, e.to_end()));
+                    } else if name.is_tag(protein_tag.name()) {
+                        support.protein = Some(read_vec_node(reader, e.to_end()));
+                    } else if name.is_tag(identification_tag.name()) {
+                        support.identification = read_node(reader);
+                    } else if name.is_tag(dbxref_tag.name()) {
+                        support.dbxref = Some(read_vec_node(reader, e.to_end()));
+                    } else if name.is_tag(exon_count_tag.name()) {
+                        support.exon_count = read_int(reader);
+                    } else if name.is_tag(exon_length_tag.name()) {
+                        support.exon_length = read_int(reader);
+                    } else if name != Self::start_bytes().name() {
+                        forbidden.check(&name)
+                    }
+                }
+                Event::Empty(e) => {
+                    let name = e.name();
+
+                    if name.is_tag(full_length_tag.name()) {
+                        support.full_length = read_bool_attribute(&e).unwrap_or_default();
+                    } else if name.is_tag(combo_tag.name()) {
+                        support.supports_all_exon_combo = read_bool_attribute(&e).unwrap_or_default();
+                    }
+                }
+                Event::End(e) if Self::is_end(&e) => return support.into(),
+                _ => ()
+            }
+        }
+    }
 }
+impl XmlVecNode for ModelEvidenceSupport {}
 
 #[derive(Clone, Serialize_repr, Deserialize_repr, PartialEq, Debug, Default)]
 #[repr(u8)]
@@ -690,6 +1823,13 @@ pub enum CdRegionFrame {
     Three,
 }
 
+asn_names!(CdRegionFrame {
+    NotSet => "not-set",
+    One => "one",
+    Two => "two",
+    Three => "three",
+});
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
 #[serde(rename_all = "kebab-case")]
 /// Instructions to translate from a nucleic acid to a peptide
@@ -729,6 +1869,86 @@ pub struct CdRegion {
     pub stops: Option<u64>,
 }
 
+impl CdRegion {
+    /// Translation table of the coding region
+    ///
+    /// Coding regions which do not give a code use the standard code.
+    pub fn genetic_code(&self) -> Option<&'static CodeTable> {
+        match &self.code {
+            Some(code) => code.resolve(),
+            None => Some(standard_code()),
+        }
+    }
+
+    /// Nucleotide offset of the first complete codon
+    pub(crate) fn frame_offset(&self) -> usize {
+        match self.frame {
+            CdRegionFrame::NotSet | CdRegionFrame::One => 0,
+            CdRegionFrame::Two => 1,
+            CdRegionFrame::Three => 2,
+        }
+    }
+
+    /// Translate `cds`, the residues of the coding region at `location`
+    ///
+    /// `cds` is the spliced sequence given by `location` (eg: from
+    /// [`crate::seq::BioSeq::extract()`]), starting at the first base of the region.
+    /// Translation starts at [`Self::frame`], and an initial start codon is read as
+    /// "M" when the region starts in frame one. [`Self::code_break`] exceptions (eg:
+    /// selenocysteine) replace the amino acid of the codon they are located on.
+    pub fn translate(&self, location: &SeqLoc, cds: &str) -> Result<String, String> {
+        let code = self.genetic_code().ok_or("Unknown genetic code")?;
+        let offset = self.frame_offset();
+        let cds = cds.get(offset..).unwrap_or_default();
+        let mut protein: Vec<char> = code.translate(cds, offset == 0).chars().collect();
+
+        for code_break in self.code_break.as_deref().unwrap_or_default() {
+            let aa = code_break.aa.to_char().ok_or_else(|| format!("Invalid code-break {:?}", code_break.aa))?;
+            let position = code_break
+                .first_base()
+                .and_then(|base| product_offset(location, base))
+                .filter(|position| *position >= offset)
+                .ok_or_else(|| format!("Code-break outside of coding region: {:?}", code_break.loc))?;
+            let codon = (position - offset) / 3;
+            match codon.cmp(&protein.len()) {
+                std::cmp::Ordering::Less => protein[codon] = aa,
+                // incomplete final codon (eg: a stop completed by the poly-A tail)
+                std::cmp::Ordering::Equal => protein.push(aa),
+                std::cmp::Ordering::Greater => return Err(format!("Code-break beyond the end of the coding region: {:?}", code_break.loc)),
+            }
+        }
+        Ok(protein.into_iter().collect())
+    }
+}
+
+/// Intervals of `location` as `(from, to, minus)`, in the order that they are spliced
+fn spans(location: &SeqLoc) -> Vec<(i64, i64, bool)> {
+    let minus = |strand: &Option<NaStrand>| matches!(strand, Some(NaStrand::Minus) | Some(NaStrand::BothRev));
+    match location {
+        SeqLoc::Int(interval) => vec![(interval.from, interval.to, minus(&interval.strand))],
+        SeqLoc::PackedInt(intervals) => intervals
+            .iter()
+            .map(|interval| (interval.from, interval.to, minus(&interval.strand)))
+            .collect(),
+        SeqLoc::Pnt(point) => vec![(point.point, point.point, minus(&point.strand))],
+        SeqLoc::Mix(mix) => mix.0.iter().flat_map(spans).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Offset of the sequence position `base` within the spliced residues of `location`
+fn product_offset(location: &SeqLoc, base: i64) -> Option<usize> {
+    let mut offset = 0;
+    for (from, to, minus) in spans(location) {
+        if (from..=to).contains(&base) {
+            let within = if minus { to - base } else { base - from };
+            return Some(offset + within as usize);
+        }
+        offset += (to - from + 1) as usize;
+    }
+    None
+}
+
 impl XmlNode for CdRegion {
     fn start_bytes() -> BytesStart<'static> {
         BytesStart::new("Cdregion")
@@ -744,23 +1964,25 @@ impl XmlNode for CdRegion {
         let gaps_tag = BytesStart::new("Cdregion_gaps");
         let mismatch_tag = BytesStart::new("Cdregion_mismatch");
         let code_tag = BytesStart::new("Cdregion_code");
-        let _code_break_tag = BytesStart::new("Cdregion_code-break");
+        let code_break_tag = BytesStart::new("Cdregion_code-break");
         let stops_tag = BytesStart::new("Cdregion_stops");
 
         let forbidden = UnexpectedTags(&[]);
 
         loop {
-            match reader.read_event().unwrap() {
+            match next_event(reader)? {
                 Event::Start(e) => {
                     let name = e.name();
 
-                    if name == code_tag.name() {
-                        cdregion.code = Some(read_vec_node(reader, code_tag.to_end()))
-                    } else if name == gaps_tag.name() {
+                    if name.is_tag(code_tag.name()) {
+                        cdregion.code = Some(read_vec_node(reader, e.to_end()))
+                    } else if name.is_tag(code_break_tag.name()) {
+                        cdregion.code_break = Some(read_vec_node(reader, e.to_end()))
+                    } else if name.is_tag(gaps_tag.name()) {
                         cdregion.gaps = read_int(reader);
-                    } else if name == mismatch_tag.name() {
+                    } else if name.is_tag(mismatch_tag.name()) {
                         cdregion.mismatch = read_int(reader);
-                    } else if name == stops_tag.name() {
+                    } else if name.is_tag(stops_tag.name()) {
                         cdregion.stops = read_int(reader);
                     } else if name != Self::start_bytes().name() {
                         forbidden.check(&name)
@@ -826,12 +2048,18 @@ impl XmlNode for GeneticCodeOpt {
         let sncbistdaa_tag = BytesStart::new("Genetic-code_E_sncbistdaa");
 
         loop {
-            match reader.read_event().unwrap() {
+            match next_event(reader)? {
                 Event::Start(e) => {
                     let name = e.name();
 
-                    if name == id_tag.name() {
-                        return Self::Id(read_int(reader).unwrap()).into()
+                    if name.is_tag(id_tag.name()) {
+                        return Self::Id(read_int(reader)?).into()
+                    } else if name.is_tag(name_tag.name()) {
+                        return Self::Name(read_string(reader)?).into()
+                    } else if name.is_tag(ncbieaa_tag.name()) {
+                        return Self::NcbiEaa(read_string(reader)?).into()
+                    } else if name.is_tag(sncbieaa_tag.name()) {
+                        return Self::SNcbiEaa(read_string(reader)?).into()
                     }
                 }
                 Event::End(e) => {
@@ -848,6 +2076,39 @@ impl XmlVecNode for GeneticCodeOpt {}
 
 pub type GeneticCode = Vec<GeneticCodeOpt>;
 
+/// Lookup of the translation table specified by a [`GeneticCode`]
+pub trait ResolveGeneticCode {
+    /// Translation table given by id, name, or amino acid string, in that order of
+    /// preference
+    ///
+    /// A code which gives none of these is the standard code. Returns `None` if the
+    /// code is not one of [`GENETIC_CODES`].
+    fn resolve(&self) -> Option<&'static CodeTable>;
+}
+
+impl ResolveGeneticCode for GeneticCode {
+    fn resolve(&self) -> Option<&'static CodeTable> {
+        let id = self.iter().find_map(|opt| match opt {
+            GeneticCodeOpt::Id(id) => Some(*id),
+            _ => None,
+        });
+        let name = self.iter().find_map(|opt| match opt {
+            GeneticCodeOpt::Name(name) => Some(name.as_str()),
+            _ => None,
+        });
+        let ncbieaa = self.iter().find_map(|opt| match opt {
+            GeneticCodeOpt::NcbiEaa(ncbieaa) => Some(ncbieaa.as_str()),
+            _ => None,
+        });
+        match (id, name, ncbieaa) {
+            (Some(id), _, _) => genetic_code(id),
+            (None, Some(name), _) => genetic_code_by_name(name),
+            (None, None, Some(ncbieaa)) => GENETIC_CODES.iter().find(|code| code.ncbieaa == ncbieaa),
+            (None, None, None) => Some(standard_code()),
+        }
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 #[serde(rename_all = "lowercase")]
 /// the amino acid that is the exception
@@ -872,6 +2133,102 @@ pub struct CodeBreak {
     pub aa: CodeBreakAA,
 }
 
+impl CodeBreakAA {
+    /// One-letter code of the amino acid
+    pub fn to_char(&self) -> Option<char> {
+        match self {
+            Self::NcbiAa(code) => u8::try_from(*code).ok().filter(u8::is_ascii_alphabetic).map(char::from),
+            Self::Ncbi8aa(code) | Self::NcbiStdAa(code) => NCBISTDAA_ALPHABET.get(*code as usize).copied(),
+        }
+    }
+}
+
+impl XmlNode for CodeBreakAA {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("Code-break_aa")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> where Self: Sized {
+        // variant tags
+        let ncbieaa_tag = BytesStart::new("Code-break_aa_ncbieaa");
+        let ncbi8aa_tag = BytesStart::new("Code-break_aa_ncbi8aa");
+        let ncbistdaa_tag = BytesStart::new("Code-break_aa_ncbistdaa");
+
+        let forbidden = UnexpectedTags(&[]);
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => {
+                    let name = e.name();
+
+                    if name.is_tag(ncbieaa_tag.name()) {
+                        return Self::NcbiAa(read_int(reader)?).into()
+                    } else if name.is_tag(ncbi8aa_tag.name()) {
+                        return Self::Ncbi8aa(read_int(reader)?).into()
+                    } else if name.is_tag(ncbistdaa_tag.name()) {
+                        return Self::NcbiStdAa(read_int(reader)?).into()
+                    } else if name != Self::start_bytes().name() {
+                        forbidden.check(&name)
+                    }
+                }
+                Event::End(e) if Self::is_end(&e) => {
+                    return None
+                }
+                _ => ()
+            }
+        }
+    }
+}
+
+impl CodeBreak {
+    /// Sequence position of the first base of the codon
+    fn first_base(&self) -> Option<i64> {
+        let (from, to) = self.loc.total_range()?;
+        match spans(&self.loc).first() {
+            Some((_, _, true)) => Some(to),
+            _ => Some(from),
+        }
+    }
+}
+
+impl XmlNode for CodeBreak {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("Code-break")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> where Self: Sized {
+        // field tags
+        let loc_tag = BytesStart::new("Code-break_loc");
+        let aa_tag = BytesStart::new("Code-break_aa");
+
+        let mut loc = None;
+        let mut aa = None;
+
+        let forbidden = UnexpectedTags(&[]);
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => {
+                    let name = e.name();
+
+                    if name.is_tag(loc_tag.name()) {
+                        loc = read_node(reader);
+                    } else if name.is_tag(aa_tag.name()) {
+                        aa = CodeBreakAA::from_reader(reader);
+                    } else if name != Self::start_bytes().name() {
+                        forbidden.check(&name)
+                    }
+                }
+                Event::End(e) if Self::is_end(&e) => {
+                    return Some(Self { loc: loc?, aa: aa? })
+                }
+                _ => ()
+            }
+        }
+    }
+}
+impl XmlVecNode for CodeBreak {}
+
 /// table of genetic codes
 pub type GeneticCodeTable = Vec<GeneticCode>;
 
@@ -888,8 +2245,9 @@ pub struct ImpFeat {
 }
 
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct GbQual {
-    pub qual: String,
+    pub qual: SharedStr,
     pub val: String,
 }
 
@@ -908,14 +2266,15 @@ impl XmlNode for GbQual {
         let forbidden = UnexpectedTags(&[]);
 
         loop {
-            match reader.read_event().unwrap() {
+            match next_event(reader)? {
                 Event::Start(e) => {
                     let name = e.name();
 
-                    if name == qual_tag.name() {
-                        qual.qual = read_string(reader).unwrap();
-                    } else if name == val_tag.name() {
-                        qual.val = read_string(reader).unwrap();
+                    if name.is_tag(qual_tag.name()) {
+                        qual.qual = read_string(reader)?.into();
+                    } else if name.is_tag(val_tag.name()) {
+                        // flag qualifiers (eg: "pseudo") have an empty value
+                        qual.val = read_string(reader).unwrap_or_default();
                     } else {
                         forbidden.check(&name);
                     }
@@ -932,6 +2291,49 @@ impl XmlNode for GbQual {
 }
 impl XmlVecNode for GbQual {}
 
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+/// Borrowed view of a [`GbQual`]
+pub struct GbQualRef<'a> {
+    pub qual: &'a str,
+    pub val: &'a str,
+}
+
+impl<'a> XmlNodeRef<'a> for GbQualRef<'a> {
+    type Owned = GbQual;
+
+    fn from_reader_borrowed(reader: &mut Reader<&'a [u8]>) -> Option<Self> {
+        let qual_tag = BytesStart::new("Gb-qual_qual");
+        let val_tag = BytesStart::new("Gb-qual_val");
+
+        let mut qual = None;
+        let mut val = None;
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => {
+                    let name = e.name();
+
+                    if name.is_tag(qual_tag.name()) {
+                        qual = read_str(reader);
+                    } else if name.is_tag(val_tag.name()) {
+                        val = read_str(reader);
+                    }
+                }
+                Event::End(e) if GbQual::is_end(&e) => {
+                    return Some(Self { qual: qual?, val: val? })
+                }
+                _ => (),
+            }
+        }
+    }
+
+    fn to_owned_node(&self) -> GbQual {
+        GbQual {
+            qual: self.qual.into(),
+            val: self.val.to_string(),
+        }
+    }
+}
+
 #[derive(Clone, Serialize_repr, Deserialize_repr, PartialEq, Debug)]
 #[repr(u8)]
 /// Internal representation of placement method for [`CloneRef`]
@@ -965,6 +2367,18 @@ pub enum CloneRefPlacementMethod {
     Other = 255,
 }
 
+asn_names!(CloneRefPlacementMethod {
+    EndSeq => "end-seq",
+    InsertAlignment => "insert-alignment",
+    STS => "sts",
+    Fish => "fish",
+    Fingerprint => "fingerprint",
+    EndSeqInsertAlignment => "end-seq-insert-alignment",
+    External => "external",
+    Curated => "curated",
+    Other => "other",
+});
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 #[serde(rename_all = "kebab-case")]
 /// Specification of clone features
@@ -997,6 +2411,12 @@ pub enum CloneSeqType {
     Other = 255,
 }
 
+asn_names!(CloneSeqType {
+    Insert => "insert",
+    End => "end",
+    Other => "other",
+});
+
 #[derive(Clone, Serialize_repr, Deserialize_repr, PartialEq, Debug)]
 #[repr(u8)]
 /// Internal representation of clone confidence for [`CloneSeq`]
@@ -1038,6 +2458,20 @@ pub enum CloneSeqConfidence {
     Other = 255,
 }
 
+asn_names!(CloneSeqConfidence {
+    Multiple => "multiple",
+    Na => "na",
+    NoHitRep => "nohit-rep",
+    NoHitNoRep => "nohit-norep",
+    OtherChrm => "other-chrm",
+    Unique => "unique",
+    Virtual => "virtual",
+    MultipleRep => "multiple-rep",
+    MultipleNoRep => "multiple-norep",
+    NoHit => "no-hit",
+    Other => "other",
+});
+
 #[derive(Clone, Serialize_repr, Deserialize_repr, PartialEq, Debug)]
 #[repr(u8)]
 ///
@@ -1059,6 +2493,13 @@ pub enum CloneSeqSupport {
     NonSupporting,
 }
 
+asn_names!(CloneSeqSupport {
+    Prototype => "prototype",
+    Supporting => "supporting",
+    SupportsOther => "supports-other",
+    NonSupporting => "non-supporting",
+});
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 #[serde(rename_all = "kebab-case")]
 pub struct CloneSeq {
@@ -1208,6 +2649,13 @@ pub enum VariantMapWeight {
     ManyPlacements = 10,
 }
 
+asn_names!(VariantMapWeight {
+    IsUniquelyPlaced => "is-uniquely-placed",
+    PlacedTwiceOnSameChrom => "placed-twice-on-same-chrom",
+    PlacedTypeOnDiffChrom => "placed-twice-on-diff-chrom",
+    ManyPlacements => "many-placements",
+});
+
 bitflags! {
     #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
     pub struct FrequencyBasedValidation: u8 {
@@ -1244,6 +2692,11 @@ pub enum VariantGenotype {
     HasGenotypes,
 }
 
+asn_names!(VariantGenotype {
+    InHaplotypeSet => "in-haplotype-set",
+    HasGenotypes => "has-genotypes",
+});
+
 bitflags! {
     #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
     pub struct VariantQualityCheck: u8 {
@@ -1274,6 +2727,12 @@ pub enum VariantConfidence {
     Other = 255,
 }
 
+asn_names!(VariantConfidence {
+    Unknown => "unknown",
+    LikelyArtifact => "likely-artifact",
+    Other => "other",
+});
+
 bitflags! {
     #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
     /// origin of this allele, if known
@@ -1314,6 +2773,15 @@ pub enum VariantAlleleState {
     Other = 255,
 }
 
+asn_names!(VariantAlleleState {
+    Unknown => "unknown",
+    Homosygous => "homozygous",
+    Heterozygous => "heterozygous",
+    Hemizygous => "hemizygous",
+    Nullizygous => "nullizygous",
+    Other => "other",
+});
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 #[serde(rename_all = "kebab-case")]
 /// Specification of variation features
@@ -1389,6 +2857,18 @@ pub enum PhenotypeClinicalSignificance {
     Other = 255,
 }
 
+asn_names!(PhenotypeClinicalSignificance {
+    Unknown => "unknown",
+    Untested => "untested",
+    NonPathogenic => "non-pathogenic",
+    ProbableNonPathogenic => "probable-non-pathogenic",
+    ProbablePathogenic => "probable-pathogenic",
+    Pathogenic => "pathogenic",
+    DrugResponse => "drug-response",
+    Histocompatibility => "histocompatibility",
+    Other => "other",
+});
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 #[serde(rename_all = "kebab-case")]
 pub struct Phenotype {
@@ -1460,6 +2940,36 @@ pub enum VariantRefMethod {
     Other = 255,
 }
 
+asn_names!(VariantRefMethod {
+    Unknown => "unknown",
+    BacAcgh => "bac-acgh",
+    Computational => "computational",
+    Curated => "curated",
+    DigitalArray => "digital-array",
+    ExpressionArray => "expression-array",
+    Fish => "fish",
+    FlankingSequence => "flanking-sequence",
+    Maph => "maph",
+    McdAnalysis => "mcd-analysis",
+    Mlpa => "mlpa",
+    OeaAssembly => "oea-assembly",
+    OligoAcgh => "oligo-acgh",
+    PairedEnd => "paired-end",
+    Pcr => "pcr",
+    Qpcr => "qpcr",
+    ReadDepth => "read-depth",
+    Roma => "roma",
+    RtPcr => "rt-pcr",
+    Sage => "sage",
+    SequenceAlignment => "sequence-alignment",
+    Sequencing => "sequencing",
+    SnpArray => "snp-array",
+    Southern => "southern",
+    Western => "western",
+    OpticalMapping => "optical-mapping",
+    Other => "other",
+});
+
 #[derive(Clone, Serialize_repr, Deserialize_repr, PartialEq, Debug)]
 #[repr(u8)]
 pub enum VariationRefDataSetType {
@@ -1476,6 +2986,20 @@ pub enum VariationRefDataSetType {
     Other = 255,
 }
 
+asn_names!(VariationRefDataSetType {
+    Unknown => "unknown",
+    Compound => "compound",
+    Products => "products",
+    Haplotype => "haplotype",
+    Genotype => "genotype",
+    Mosaic => "mosaic",
+    Individual => "individual",
+    Population => "population",
+    Alleles => "alleles",
+    Package => "package",
+    Other => "other",
+});
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 pub struct VariationRefDataSet {
     #[serde(rename = "type")]
@@ -1643,6 +3167,13 @@ pub enum DeltaAction {
     InsBefore,
 }
 
+asn_names!(DeltaAction {
+    Morph => "morph",
+    Offset => "offset",
+    DelAt => "del-at",
+    InsBefore => "ins-before",
+});
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 #[serde(rename_all = "kebab-case")]
 pub struct DeltaItem {
@@ -1740,6 +3271,31 @@ pub enum VariationInstType {
     Other = 255,
 }
 
+asn_names!(VariationInstType {
+    Unknown => "unknown",
+    Identity => "identity",
+    Inv => "inv",
+    Snv => "snv",
+    Mnp => "mnp",
+    DelIns => "delins",
+    Del => "del",
+    Ins => "ins",
+    Microsatellite => "microsatellite",
+    Transposon => "transposon",
+    Cnv => "cnv",
+    DirectCopy => "direct-copy",
+    RevDirectCopy => "rev-direct-copy",
+    InvertedCopy => "inverted-copy",
+    EvertedCopy => "everted-copy",
+    Translocation => "translocation",
+    ProtMissense => "prot-missense",
+    ProtNonsense => "prot-nonsense",
+    ProtNeutral => "prot-neutral",
+    ProtSilent => "prot-silent",
+    ProtOther => "prot-other",
+    Other => "other",
+});
+
 #[derive(Clone, Serialize_repr, Deserialize_repr, PartialEq, Debug)]
 #[repr(u8)]
 /// Used to label items in a [`VariationRef`] package
@@ -1754,6 +3310,12 @@ pub enum VariationInstObservation {
     Variant = 4,
 }
 
+asn_names!(VariationInstObservation {
+    Asserted => "asserted",
+    Reference => "reference",
+    Variant => "variant",
+});
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 pub struct VariationInst {
     #[serde(rename = "type")]
@@ -1808,6 +3370,45 @@ pub enum RnaRefType {
     Other = 255,
 }
 
+asn_names!(RnaRefType {
+    Unknown => "unknown",
+    PreMsg => "premsg",
+    mRNA => "mRNA",
+    tRNA => "tRNA",
+    rRNA => "rRNA",
+    snRNA => "snRNA",
+    scRNA => "scRNA",
+    snoRNA => "snoRNA",
+    ncRNA => "ncRNA",
+    tmRNA => "tmRNA",
+    MiscRNA => "miscRNA",
+    Other => "other",
+});
+
+impl XmlValue for RnaRefType {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("RNA-ref_type")
+    }
+
+    fn from_attributes(attributes: Attributes) -> Option<Self> {
+        match attribute_value(attributes)?.as_str() {
+            "unknown" => Self::Unknown.into(),
+            "premsg" => Self::PreMsg.into(),
+            "mRNA" => Self::mRNA.into(),
+            "tRNA" => Self::tRNA.into(),
+            "rRNA" => Self::rRNA.into(),
+            "snRNA" => Self::snRNA.into(),
+            "scRNA" => Self::scRNA.into(),
+            "snoRNA" => Self::snoRNA.into(),
+            "ncRNA" => Self::ncRNA.into(),
+            "tmRNA" => Self::tmRNA.into(),
+            "miscRNA" => Self::MiscRNA.into(),
+            "other" => Self::Other.into(),
+            _ => None,
+        }
+    }
+}
+
 #[allow(non_camel_case_types)]
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 #[serde(rename_all = "lowercase")]
@@ -1822,6 +3423,37 @@ pub enum RnaRefExt {
     Gen(RnaGen),
 }
 
+impl XmlNode for RnaRefExt {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("RNA-ref_ext")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> where Self: Sized {
+        // variant tags
+        let name_tag = BytesStart::new("RNA-ref_ext_name");
+        let trna_tag = BytesStart::new("RNA-ref_ext_tRNA");
+        let gen_tag = BytesStart::new("RNA-ref_ext_gen");
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => {
+                    let name = e.name();
+
+                    if name.is_tag(name_tag.name()) {
+                        return Self::Name(read_string(reader)?).into()
+                    } else if name.is_tag(trna_tag.name()) {
+                        return Self::tRNA(read_node(reader)?).into()
+                    } else if name.is_tag(gen_tag.name()) {
+                        return Self::Gen(read_node(reader)?).into()
+                    }
+                }
+                Event::End(e) if Self::is_end(&e) => return None,
+                _ => ()
+            }
+        }
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 pub struct RnaRef {
     #[serde(rename = "type")]
@@ -1830,13 +3462,95 @@ pub struct RnaRef {
     pub ext: Option<RnaRefExt>,
 }
 
-#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
-#[serde(rename_all = "lowercase")]
-pub enum TRnaExtAa {
-    IUPACAa(u64),
-    NCBIEaa(u64),
-    NCBI8aa(u64),
-    NCBIStdAa(u64),
+impl Default for RnaRef {
+    fn default() -> Self {
+        RnaRef {
+            ext: None,                   // Optional, initialize as `None`
+            pseudo: Some(false),         // Default `false`
+            r#type: RnaRefType::Unknown, // Default enum variant
+        }
+    }
+}
+
+impl XmlNode for RnaRef {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("RNA-ref")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> where Self: Sized {
+        let mut rna = Self {
+            r#type: RnaRefType::Unknown,
+            pseudo: None,
+            ext: None,
+        };
+
+        // field tags
+        let type_tag = BytesStart::new("RNA-ref_type");
+        let pseudo_tag = BytesStart::new("RNA-ref_pseudo");
+        let ext_tag = BytesStart::new("RNA-ref_ext");
+
+        loop {
+            match next_event(reader)? {
+                Event::Empty(e) => {
+                    let name = e.name();
+
+                    if name.is_tag(type_tag.name()) {
+                        rna.r#type = RnaRefType::from_attributes(e.attributes())?;
+                    } else if name.is_tag(pseudo_tag.name()) {
+                        rna.pseudo = read_bool_attribute(&e);
+                    }
+                }
+                Event::Start(e) if e.name().is_tag(ext_tag.name()) => {
+                    rna.ext = read_node(reader);
+                }
+                Event::End(e) if Self::is_end(&e) => return rna.into(),
+                _ => ()
+            }
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum TRnaExtAa {
+    IUPACAa(u64),
+    NCBIEaa(u64),
+    NCBI8aa(u64),
+    NCBIStdAa(u64),
+}
+
+impl XmlNode for TRnaExtAa {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("Trna-ext_aa")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> where Self: Sized {
+        // variant tags
+        let iupacaa_tag = BytesStart::new("Trna-ext_aa_iupacaa");
+        let ncbieaa_tag = BytesStart::new("Trna-ext_aa_ncbieaa");
+        let ncbi8aa_tag = BytesStart::new("Trna-ext_aa_ncbi8aa");
+        let ncbistdaa_tag = BytesStart::new("Trna-ext_aa_ncbistdaa");
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => {
+                    let name = e.name();
+
+                    if name.is_tag(iupacaa_tag.name()) {
+                        return Self::IUPACAa(read_int(reader)?).into()
+                    } else if name.is_tag(ncbieaa_tag.name()) {
+                        return Self::NCBIEaa(read_int(reader)?).into()
+                    } else if name.is_tag(ncbi8aa_tag.name()) {
+                        return Self::NCBI8aa(read_int(reader)?).into()
+                    } else if name.is_tag(ncbistdaa_tag.name()) {
+                        return Self::NCBIStdAa(read_int(reader)?).into()
+                    }
+                }
+                Event::End(e) if Self::is_end(&e) => return None,
+                _ => ()
+            }
+        }
+    }
 }
 
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
@@ -1852,6 +3566,47 @@ pub struct TRnaExt {
     pub anticodon: Option<SeqLoc>,
 }
 
+impl XmlNode for TRnaExt {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("Trna-ext")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> where Self: Sized {
+        let mut aa = None;
+        let mut codon = None;
+        let mut anticodon = None;
+
+        // field tags
+        let aa_tag = BytesStart::new("Trna-ext_aa");
+        let codon_tag = BytesStart::new("Trna-ext_codon");
+        let anticodon_tag = BytesStart::new("Trna-ext_anticodon");
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => {
+                    let name = e.name();
+
+                    if name.is_tag(aa_tag.name()) {
+                        aa = read_node(reader);
+                    } else if name.is_tag(codon_tag.name()) {
+                        codon = Some(read_vec_int_unchecked(reader, &e.to_end()));
+                    } else if name.is_tag(anticodon_tag.name()) {
+                        anticodon = read_node(reader);
+                    }
+                }
+                Event::End(e) if Self::is_end(&e) => {
+                    return Self {
+                        aa: aa?,
+                        codon,
+                        anticodon,
+                    }.into()
+                }
+                _ => ()
+            }
+        }
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 pub struct RnaGen {
     /// for ncRNA's, the class of non-coding RNA
@@ -1865,15 +3620,90 @@ pub struct RnaGen {
     pub quals: Option<RnaQualSet>,
 }
 
+impl XmlNode for RnaGen {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("RNA-gen")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> where Self: Sized {
+        let mut gen = Self {
+            class: None,
+            product: None,
+            quals: None,
+        };
+
+        // field tags
+        let class_tag = BytesStart::new("RNA-gen_class");
+        let product_tag = BytesStart::new("RNA-gen_product");
+        let quals_tag = BytesStart::new("RNA-gen_quals");
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => {
+                    let name = e.name();
+
+                    if name.is_tag(class_tag.name()) {
+                        gen.class = read_string(reader);
+                    } else if name.is_tag(product_tag.name()) {
+                        gen.product = read_string(reader);
+                    } else if name.is_tag(quals_tag.name()) {
+                        gen.quals = Some(read_vec_node(reader, e.to_end()));
+                    }
+                }
+                Event::End(e) if Self::is_end(&e) => return gen.into(),
+                _ => ()
+            }
+        }
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 pub struct RnaQual {
     pub qual: String,
     pub val: String,
 }
 
+impl XmlNode for RnaQual {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("RNA-qual")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> where Self: Sized {
+        let mut qual = None;
+        let mut val = None;
+
+        // field tags
+        let qual_tag = BytesStart::new("RNA-qual_qual");
+        let val_tag = BytesStart::new("RNA-qual_val");
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => {
+                    let name = e.name();
+
+                    if name.is_tag(qual_tag.name()) {
+                        qual = read_string(reader);
+                    } else if name.is_tag(val_tag.name()) {
+                        val = read_string(reader);
+                    }
+                }
+                Event::End(e) if Self::is_end(&e) => {
+                    return Self {
+                        qual: qual?,
+                        val: val.unwrap_or_default(),
+                    }.into()
+                }
+                _ => ()
+            }
+        }
+    }
+}
+impl XmlVecNode for RnaQual {}
+
 pub type RnaQualSet = Vec<RnaQual>;
 
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "kebab-case")]
 pub struct GeneRef {
     /// official gene symbol
@@ -1923,27 +3753,27 @@ impl XmlNode for GeneRef {
         let form_name_tag = BytesStart::new("Gene-ref_formal-name");
 
         loop {
-            match reader.read_event().unwrap() {
+            match next_event(reader)? {
                 Event::Start(e) => {
                     let name = e.name();
 
-                    if name == locus_tag.name() {
+                    if name.is_tag(locus_tag.name()) {
                         gene.locus = read_string(reader);
-                    } else if name == allele_tag.name() {
+                    } else if name.is_tag(allele_tag.name()) {
                         gene.allele = read_string(reader);
-                    } else if name == desc_tag.name() {
+                    } else if name.is_tag(desc_tag.name()) {
                         gene.desc = read_string(reader);
-                    } else if name == maploc_tag.name() {
+                    } else if name.is_tag(maploc_tag.name()) {
                         gene.maploc = read_string(reader);
-                    } else if name == db_tag.name() {
-                        gene.db = Some(read_vec_node(reader, db_tag.to_end()));
-                    } else if name == locus_tag_tag.name() {
+                    } else if name.is_tag(db_tag.name()) {
+                        gene.db = Some(read_vec_node(reader, e.to_end()));
+                    } else if name.is_tag(locus_tag_tag.name()) {
                         gene.locus_tag = read_string(reader);
-                    } else if name == syn_tag.name() {
-                        gene.syn = Some(read_vec_node(reader, syn_tag.to_end()));
-                    } else if name == form_name_tag.name() {
+                    } else if name.is_tag(syn_tag.name()) {
+                        gene.syn = Some(read_vec_node(reader, e.to_end()));
+                    } else if name.is_tag(form_name_tag.name()) {
                         gene.formal_name = read_node(reader);
-                    } else if name == pseudo_tag.name() {
+                    } else if name.is_tag(pseudo_tag.name()) {
                         gene.pseudo = read_bool_attribute(&e)?;
                     }
                 }
@@ -1959,6 +3789,7 @@ impl XmlNode for GeneRef {
 }
 
 #[derive(Clone, Serialize_repr, Deserialize_repr, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[repr(u8)]
 pub enum GeneNomenclatureStatus {
     #[default] // Not explicitly set in standard
@@ -1967,19 +3798,14 @@ pub enum GeneNomenclatureStatus {
     Interim,
 }
 
-impl FromStr for GeneNomenclatureStatus {
-    type Err = ();
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.to_lowercase().as_str() {
-            "official" => Ok(GeneNomenclatureStatus::Official),
-            "interim" => Ok(GeneNomenclatureStatus::Interim),
-            _ => Ok(GeneNomenclatureStatus::Unknown),
-        }
-    }
-}
+asn_names!(GeneNomenclatureStatus {
+    Unknown => "unknown",
+    Official => "official",
+    Interim => "interim",
+});
 
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct GeneNomenclature {
     pub status: GeneNomenclatureStatus,
     pub symbol: Option<String>,
@@ -2014,17 +3840,17 @@ impl XmlNode for GeneNomenclature {
         //let forbidden = UnexpectedTags(&[]);
 
         loop {
-            match reader.read_event().unwrap() {
+            match next_event(reader)? {
                 Event::Start(e) => {
                     let name = e.name();
 
-                    if name == status_element.name() {
+                    if name.is_tag(status_element.name()) {
                         if let Some(status_string) = read_string(reader) {
                             nomenclature.status = GeneNomenclatureStatus::from_str(&status_string).unwrap_or_default();
                         }
-                    } else if name == symbol_element.name() {
+                    } else if name.is_tag(symbol_element.name()) {
                         nomenclature.symbol = Some(read_string(reader).unwrap_or_default());
-                    } else if name == name_element.name() {
+                    } else if name.is_tag(name_element.name()) {
                         nomenclature.name = Some(read_string(reader).unwrap_or_default());
                     }
                 }
@@ -2064,6 +3890,94 @@ pub struct OrgRef {
     pub orgname: Option<OrgName>,
 }
 
+impl OrgRef {
+    /// NCBI Taxonomy id, given by a "taxon" cross reference
+    pub fn taxid(&self) -> Option<u64> {
+        self.db.iter().flatten().find_map(|tag| match tag.typed() {
+            DbXref::Taxon(id) => Some(id),
+            _ => None,
+        })
+    }
+
+    /// Taxa of the lineage, from the root
+    ///
+    /// See [`OrgName::lineage_vec()`]
+    pub fn lineage_vec(&self) -> Vec<&str> {
+        self.orgname.as_ref().map(OrgName::lineage_vec).unwrap_or_default()
+    }
+
+    /// Canonical form for comparing organisms
+    ///
+    /// Names are trimmed, lists are sorted without duplicates, and attributions are
+    /// dropped, so that the same organism compares equal regardless of how each
+    /// record gives it.
+    pub fn normalized(&self) -> Self {
+        Self {
+            taxname: trimmed(&self.taxname),
+            common: trimmed(&self.common),
+            r#mod: sorted(&self.r#mod),
+            db: sorted(&self.db),
+            syn: sorted(&self.syn),
+            orgname: self.orgname.as_ref().map(OrgName::normalized),
+        }
+    }
+
+    /// Whether `other` is the same organism, as compared by [`Self::normalized()`]
+    pub fn equivalent(&self, other: &Self) -> bool {
+        self.normalized() == other.normalized()
+    }
+
+    /// Complete this organism with the values of `other`
+    ///
+    /// Lists (`mod`, `db`, `syn` and the modifiers of [`OrgName`]) are united, and
+    /// other fields are only taken from `other` when missing here. Modifiers which only
+    /// differ by attribution are not repeated.
+    pub fn merge(&mut self, other: &Self) {
+        fill(&mut self.taxname, &other.taxname);
+        fill(&mut self.common, &other.common);
+        unite(&mut self.r#mod, &other.r#mod, |a, b| a.trim() == b.trim());
+        unite(&mut self.db, &other.db, |a, b| a == b);
+        unite(&mut self.syn, &other.syn, |a, b| a.trim() == b.trim());
+        match (self.orgname.as_mut(), other.orgname.as_ref()) {
+            (Some(orgname), Some(other)) => orgname.merge(other),
+            (None, Some(other)) => self.orgname = Some(other.clone()),
+            _ => (),
+        }
+    }
+}
+
+/// Trimmed copy of `text`, which is dropped when empty
+fn trimmed(text: &Option<String>) -> Option<String> {
+    text.as_deref().map(str::trim).filter(|text| !text.is_empty()).map(str::to_string)
+}
+
+/// Sorted copy of `items`, without duplicates
+fn sorted<T: Clone + Ord>(items: &Option<Vec<T>>) -> Option<Vec<T>> {
+    items.as_ref().map(|items| {
+        let mut items = items.clone();
+        items.sort();
+        items.dedup();
+        items
+    })
+}
+
+/// Set `value` to `other` if it is not already given
+fn fill<T: Clone>(value: &mut Option<T>, other: &Option<T>) {
+    if value.is_none() {
+        value.clone_from(other);
+    }
+}
+
+/// Append the items of `other` to `items` which are not `same` as one already there
+fn unite<T: Clone>(items: &mut Option<Vec<T>>, other: &Option<Vec<T>>, same: impl Fn(&T, &T) -> bool) {
+    for item in other.iter().flatten() {
+        let items = items.get_or_insert_with(Vec::new);
+        if !items.iter().any(|existing| same(existing, item)) {
+            items.push(item.clone());
+        }
+    }
+}
+
 impl XmlNode for OrgRef {
     fn start_bytes() -> BytesStart<'static> {
         BytesStart::new("Org-ref")
@@ -2080,18 +3994,18 @@ impl XmlNode for OrgRef {
         let forbidden = UnexpectedTags(&[]);
 
         loop {
-            match reader.read_event().unwrap() {
+            match next_event(reader)? {
                 Event::Start(e) => {
                     let name = e.name();
 
-                    if name == taxname_element.name() {
+                    if name.is_tag(taxname_element.name()) {
                         org_ref.taxname = read_string(reader);
-                    } else if name == common_element.name() {
+                    } else if name.is_tag(common_element.name()) {
                         org_ref.common = read_string(reader);
-                    } else if name == orgname_element.name() {
+                    } else if name.is_tag(orgname_element.name()) {
                         org_ref.orgname = read_node(reader);
-                    } else if name == db_element.name() {
-                        org_ref.db = Some(read_vec_node(reader, db_element.to_end()))
+                    } else if name.is_tag(db_element.name()) {
+                        org_ref.db = Some(read_vec_node(reader, e.to_end()))
                     } else if name != Self::start_bytes().name() {
                         forbidden.check(&name);
                     }
@@ -2139,12 +4053,12 @@ impl XmlNode for OrgNameChoice {
         let binomial_element = BytesStart::new("OrgName_name_binomial");
 
         loop {
-            match reader.read_event().unwrap() {
+            match next_event(reader)? {
                 Event::Start(e) => {
                     let name = e.name();
 
-                    if name == binomial_element.name() {
-                        return Self::Binomial(read_node(reader).unwrap()).into();
+                    if name.is_tag(binomial_element.name()) {
+                        return Self::Binomial(read_node(reader)?).into();
                     }
                 }
                 Event::End(e) => {
@@ -2187,6 +4101,105 @@ pub struct OrgName {
     pub pgcode: Option<u64>,
 }
 
+/// Top-level taxa found at the start of a lineage
+const SUPERKINGDOMS: [&str; 4] = ["Bacteria", "Archaea", "Eukaryota", "Viruses"];
+
+/// Genetic code used by plastids when [`OrgName::pgcode`] is not given
+const DEFAULT_PLASTID_CODE: u64 = 11;
+
+impl OrgName {
+    /// Taxa of [`Self::lineage`], from the root
+    ///
+    /// eg: `["Bacteria", "Pseudomonadota", "Gammaproteobacteria", ...]`
+    pub fn lineage_vec(&self) -> Vec<&str> {
+        self.lineage
+            .iter()
+            .flat_map(|lineage| lineage.split(';'))
+            .map(str::trim)
+            .filter(|taxon| !taxon.is_empty())
+            .collect()
+    }
+
+    /// "Bacteria", "Archaea", "Eukaryota" or "Viruses", as given by the lineage
+    pub fn superkingdom(&self) -> Option<&str> {
+        self.lineage_vec().into_iter().find(|taxon| SUPERKINGDOMS.contains(taxon))
+    }
+
+    pub fn is_bacteria(&self) -> bool {
+        self.superkingdom() == Some("Bacteria")
+    }
+
+    pub fn is_archaea(&self) -> bool {
+        self.superkingdom() == Some("Archaea")
+    }
+
+    pub fn is_eukaryote(&self) -> bool {
+        self.superkingdom() == Some("Eukaryota")
+    }
+
+    pub fn is_virus(&self) -> bool {
+        self.superkingdom() == Some("Viruses")
+    }
+
+    /// Nuclear genetic code, given by [`Self::gcode`]
+    pub fn genetic_code(&self) -> Option<&'static CodeTable> {
+        genetic_code(self.gcode?)
+    }
+
+    /// Mitochondrial genetic code, given by [`Self::mgcode`]
+    pub fn mito_genetic_code(&self) -> Option<&'static CodeTable> {
+        genetic_code(self.mgcode?)
+    }
+
+    /// Plastid genetic code, given by [`Self::pgcode`]
+    ///
+    /// Defaults to the bacterial code (11), as most records do not give `pgcode`.
+    pub fn plastid_genetic_code(&self) -> Option<&'static CodeTable> {
+        genetic_code(self.pgcode.unwrap_or(DEFAULT_PLASTID_CODE))
+    }
+
+    /// Canonical form for comparing organisms, see [`OrgRef::normalized()`]
+    ///
+    /// The lineage is rejoined with "; " separators.
+    pub fn normalized(&self) -> Self {
+        let mut mods: Option<Vec<OrgMod>> = self.r#mod.as_ref().map(|mods| {
+            mods.iter()
+                .map(|r#mod| OrgMod {
+                    subtype: r#mod.subtype.clone(),
+                    subname: r#mod.subname.trim().to_string(),
+                    attrib: None,
+                })
+                .collect()
+        });
+        if let Some(mods) = mods.as_mut() {
+            mods.sort_by(|a, b| (a.subtype.clone() as u8, &a.subname).cmp(&(b.subtype.clone() as u8, &b.subname)));
+            mods.dedup();
+        }
+        let lineage = self.lineage_vec();
+        Self {
+            attrib: None,
+            r#mod: mods,
+            lineage: (!lineage.is_empty()).then(|| lineage.join("; ")),
+            div: trimmed(&self.div),
+            ..self.clone()
+        }
+    }
+
+    /// Complete this name with the values of `other`, see [`OrgRef::merge()`]
+    pub fn merge(&mut self, other: &Self) {
+        fill(&mut self.name, &other.name);
+        fill(&mut self.attrib, &other.attrib);
+        unite(&mut self.r#mod, &other.r#mod, |a, b| {
+            a.subtype == b.subtype && a.subname.trim() == b.subname.trim()
+        });
+        fill(&mut self.lineage, &other.lineage);
+        fill(&mut self.gcode, &other.gcode);
+        fill(&mut self.mgcode, &other.mgcode);
+        fill(&mut self.div, &other.div);
+        fill(&mut self.pgcode, &other.pgcode);
+    }
+}
+
 impl XmlNode for OrgName {
     fn start_bytes() -> BytesStart<'static> {
         BytesStart::new("OrgName")
@@ -2209,24 +4222,24 @@ impl XmlNode for OrgName {
         let forbidden = UnexpectedTags(&[]);
 
         loop {
-            match reader.read_event().unwrap() {
+            match next_event(reader)? {
                 Event::Start(e) => {
                     let name = e.name();
 
-                    if name == div_element.name() {
+                    if name.is_tag(div_element.name()) {
                         org_name.div = read_string(reader);
-                    } else if name == attrib_element.name() {
+                    } else if name.is_tag(attrib_element.name()) {
                         org_name.attrib = read_string(reader);
-                    } else if name == lineage_element.name() {
+                    } else if name.is_tag(lineage_element.name()) {
                         org_name.lineage = read_string(reader);
-                    } else if name == gcode_element.name() {
+                    } else if name.is_tag(gcode_element.name()) {
                         org_name.gcode = read_int(reader);
-                    } else if name == mgcode_element.name() {
+                    } else if name.is_tag(mgcode_element.name()) {
                         org_name.mgcode = read_int(reader);
-                    } else if name == name_element.name() {
+                    } else if name.is_tag(name_element.name()) {
                         org_name.name = read_node(reader);
-                    } else if name == mod_element.name() {
-                        org_name.r#mod = Some(read_vec_node(reader, mod_element.to_end()));
+                    } else if name.is_tag(mod_element.name()) {
+                        org_name.r#mod = Some(read_vec_node(reader, e.to_end()));
                     } else if name != Self::start_bytes().name() {
                         forbidden.check(&name);
                     }
@@ -2308,6 +4321,49 @@ enum_from_primitive! {
     }
 }
 
+asn_names!(OrgModSubType {
+    Strain => "strain",
+    SubStrain => "substrain",
+    Type => "type",
+    SubType => "subtype",
+    Variety => "variety",
+    Serotype => "serotype",
+    Serogroup => "serogroup",
+    Serovar => "serovar",
+    Cultivar => "cultivar",
+    Pathovar => "pathovar",
+    Chemovar => "chemovar",
+    Biovar => "biovar",
+    Biotype => "biotype",
+    Group => "group",
+    SubGroup => "subgroup",
+    Isolate => "isolate",
+    Common => "common",
+    Acronym => "acronym",
+    Dosage => "dosage",
+    NatHost => "nat-host",
+    SubSpecies => "sub-species",
+    SpecimenVoucher => "specimen-voucher",
+    Authority => "authority",
+    Forma => "forma",
+    FormaSpecialis => "forma-specialis",
+    Ecotype => "ecotype",
+    Synonym => "synonym",
+    Anamorph => "anamorph",
+    Breed => "breed",
+    GbAcronym => "gb-acronym",
+    GbAnamorph => "gb-anamorph",
+    GbSynonym => "gb-synonym",
+    CultureCollection => "culture-collection",
+    BioMaterial => "bio-material",
+    MetagenomeSource => "metagenome-source",
+    TypeMaterial => "type-material",
+    Nomenclature => "nomenclature",
+    OldLineage => "old-lineage",
+    OldName => "old-name",
+    Other => "other",
+});
+
 /// default not defined in original spec
 impl Default for OrgModSubType {
     fn default() -> Self {
@@ -2324,7 +4380,7 @@ impl XmlNode for OrgModSubType {
     where
         Self: Sized,
     {
-        Self::from_u8(read_int(reader).unwrap())
+        Self::from_u8(read_int(reader)?)
     }
 }
 
@@ -2355,15 +4411,15 @@ impl XmlNode for OrgMod {
         let forbidden = UnexpectedTags(&[]);
 
         loop {
-            match reader.read_event().unwrap() {
+            match next_event(reader)? {
                 Event::Start(e) => {
                     let name = e.name();
 
-                    if name == subtype_element.name() {
-                        r#mod.subtype = read_node(reader).unwrap();
-                    } else if name == subname_element.name() {
-                        r#mod.subname = read_string(reader).unwrap();
-                    } else if name == attrib_element.name() {
+                    if name.is_tag(subtype_element.name()) {
+                        r#mod.subtype = read_node(reader)?;
+                    } else if name.is_tag(subname_element.name()) {
+                        r#mod.subname = read_string(reader)?;
+                    } else if name.is_tag(attrib_element.name()) {
                         r#mod.attrib = read_string(reader);
                     } else if name != Self::start_bytes().name() {
                         forbidden.check(&name);
@@ -2409,15 +4465,15 @@ impl XmlNode for BinomialOrgName {
         let forbidden = UnexpectedTags(&[]);
 
         loop {
-            match reader.read_event().unwrap() {
+            match next_event(reader)? {
                 Event::Start(e) => {
                     let name = e.name();
 
-                    if name == genus_element.name() {
-                        binomial.genus = read_string(reader).unwrap();
-                    } else if name == species_element.name() {
+                    if name.is_tag(genus_element.name()) {
+                        binomial.genus = read_string(reader)?;
+                    } else if name.is_tag(species_element.name()) {
                         binomial.species = read_string(reader);
-                    } else if name == subspecies_element.name() {
+                    } else if name.is_tag(subspecies_element.name()) {
                         binomial.subspecies = read_string(reader);
                     } else if name != Self::start_bytes().name() {
                         forbidden.check(&name);
@@ -2451,6 +4507,13 @@ pub enum TaxElementFixedLevel {
     Class,
 }
 
+asn_names!(TaxElementFixedLevel {
+    Other => "other",
+    Family => "family",
+    Order => "order",
+    Class => "class",
+});
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 #[serde(rename_all = "kebab-case")]
 pub struct TaxElement {
@@ -2492,13 +4555,40 @@ enum_from_primitive! {
     }
 }
 
+asn_names!(BioSourceGenome {
+    Unknown => "unknown",
+    Genomic => "genomic",
+    Chloroplast => "chloroplast",
+    Chromoplast => "chromoplast",
+    Kinetoplast => "kinetoplast",
+    Mitochondrion => "mitochondrion",
+    Plastid => "plastid",
+    Macronuclear => "macronuclear",
+    Extrachrom => "extrachrom",
+    Plasmid => "plasmid",
+    Transposon => "transposon",
+    InsertionSeq => "insertion-seq",
+    Cyanelle => "cyanelle",
+    Proviral => "proviral",
+    Virion => "virion",
+    Nucleomorph => "nucleomorph",
+    Apicoplast => "apicoplast",
+    Leucoplast => "leucoplast",
+    Proplastid => "proplastid",
+    EndogenousVirus => "endogenous-virus",
+    Hydrogenosome => "hydrogenosome",
+    Chromosome => "chromosome",
+    PlasmidInMitochondrion => "plasmid-in-mitochondrion",
+    PlasmidInPlastid => "plasmid-in-plastid",
+});
+
 impl XmlNode for BioSourceGenome {
     fn start_bytes() -> BytesStart<'static> {
         BytesStart::new("BioSource_genome")
     }
 
     fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> {
-        Self::from_u8(read_int(reader).unwrap())
+        Self::from_u8(read_int(reader)?)
     }
 }
 
@@ -2528,13 +4618,23 @@ enum_from_primitive! {
     }
 }
 
+asn_names!(BioSourceOrigin {
+    Unknown => "unknown",
+    Natural => "natural",
+    NatMut => "natmut",
+    Mut => "mut",
+    Artificial => "artificial",
+    Synthetic => "synthetic",
+    Other => "other",
+});
+
 impl XmlNode for BioSourceOrigin {
     fn start_bytes() -> BytesStart<'static> {
         BytesStart::new("BioSource_origin")
     }
 
     fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> {
-        Self::from_u8(read_int(reader).unwrap())
+        Self::from_u8(read_int(reader)?)
     }
 }
 
@@ -2557,6 +4657,244 @@ pub struct BioSource {
     pub pcr_primers: Option<PCRReationSet>,
 }
 
+impl BioSource {
+    /// NCBI Taxonomy id of [`Self::org`]
+    pub fn taxid(&self) -> Option<u64> {
+        self.org.taxid()
+    }
+
+    /// Genetic code used to translate sequences from this source
+    ///
+    /// Chosen by [`Self::genome`], so that mitochondrial and plastid sequences use
+    /// the organelle's code.
+    pub fn genetic_code(&self) -> Option<&'static CodeTable> {
+        let orgname = self.org.orgname.as_ref()?;
+        match self.genome {
+            BioSourceGenome::Mitochondrion
+            | BioSourceGenome::Kinetoplast
+            | BioSourceGenome::Hydrogenosome
+            | BioSourceGenome::PlasmidInMitochondrion => orgname.mito_genetic_code(),
+            BioSourceGenome::Chloroplast
+            | BioSourceGenome::Chromoplast
+            | BioSourceGenome::Plastid
+            | BioSourceGenome::Cyanelle
+            | BioSourceGenome::Apicoplast
+            | BioSourceGenome::Leucoplast
+            | BioSourceGenome::Proplastid
+            | BioSourceGenome::PlasmidInPlastid => orgname.plastid_genetic_code(),
+            _ => orgname.genetic_code(),
+        }
+    }
+
+    /// Name of the first [`SubSource`] of type `subtype`
+    pub fn subsource(&self, subtype: SubSourceSubType) -> Option<&str> {
+        self.subtype
+            .iter()
+            .flatten()
+            .find(|source| source.subtype == subtype)
+            .map(|source| source.name.as_str())
+    }
+
+    /// Name of the first [`OrgMod`] of type `subtype`
+    pub fn org_mod(&self, subtype: OrgModSubType) -> Option<&str> {
+        self.org
+            .orgname
+            .as_ref()?
+            .r#mod
+            .iter()
+            .flatten()
+            .find(|org_mod| org_mod.subtype == subtype)
+            .map(|org_mod| org_mod.subname.as_str())
+    }
+
+    /// Country or ocean, optionally followed by a region (eg: "USA: Maine")
+    pub fn country(&self) -> Option<&str> {
+        self.subsource(SubSourceSubType::Country)
+    }
+
+    /// Date the specimen was collected
+    ///
+    /// Dates in the formats accepted by INSDC (eg: "15-Mar-2020", "Mar-2020",
+    /// "2020-03-15", "2020") are given as [`Date::Std`], and others such as ranges as
+    /// [`Date::Str`].
+    pub fn collection_date(&self) -> Option<Date> {
+        let text = self.subsource(SubSourceSubType::CollectionDate)?;
+        Some(match parse_collection_date(text) {
+            Some(date) => Date::Std(date),
+            None => Date::Str(text.to_string()),
+        })
+    }
+
+    /// Physical or environmental source of the specimen (eg: "soil", "blood")
+    pub fn isolation_source(&self) -> Option<&str> {
+        self.subsource(SubSourceSubType::IsolationSource)
+    }
+
+    /// Latitude and longitude of the collection site, in decimal degrees
+    ///
+    /// South latitudes and west longitudes are negative. `None` when the `lat-lon`
+    /// qualifier is missing, or malformed (see [`parse_lat_lon()`]).
+    pub fn lat_lon(&self) -> Option<(f64, f64)> {
+        parse_lat_lon(self.subsource(SubSourceSubType::LatLon)?).ok()
+    }
+
+    /// Natural host of the organism (eg: "Homo sapiens")
+    pub fn host(&self) -> Option<&str> {
+        self.org_mod(OrgModSubType::NatHost)
+    }
+
+    pub fn strain(&self) -> Option<&str> {
+        self.org_mod(OrgModSubType::Strain)
+    }
+
+    pub fn isolate(&self) -> Option<&str> {
+        self.org_mod(OrgModSubType::Isolate)
+    }
+
+    /// Institution and id of a living culture (eg: "ATCC:25922")
+    pub fn culture_collection(&self) -> Option<&str> {
+        self.org_mod(OrgModSubType::CultureCollection)
+    }
+
+    /// Canonical form for comparing sources
+    ///
+    /// The organism is normalized by [`OrgRef::normalized()`], and subsources are
+    /// sorted without duplicates or attributions.
+    pub fn normalized(&self) -> Self {
+        let mut subtypes: Option<Vec<SubSource>> = self.subtype.as_ref().map(|subtypes| {
+            subtypes
+                .iter()
+                .map(|subsource| SubSource {
+                    subtype: subsource.subtype.clone(),
+                    name: subsource.name.trim().to_string(),
+                    attrib: None,
+                })
+                .collect()
+        });
+        if let Some(subtypes) = subtypes.as_mut() {
+            subtypes.sort_by(|a, b| (a.subtype.clone() as u8, &a.name).cmp(&(b.subtype.clone() as u8, &b.name)));
+            subtypes.dedup();
+        }
+        Self {
+            org: self.org.normalized(),
+            subtype: subtypes,
+            ..self.clone()
+        }
+    }
+
+    /// Whether `other` describes the same source, as compared by [`Self::normalized()`]
+    pub fn equivalent(&self, other: &Self) -> bool {
+        self.normalized() == other.normalized()
+    }
+
+    /// Complete this source with the values of `other`
+    ///
+    /// Organisms are merged by [`OrgRef::merge()`] and subsources are united. The
+    /// genome and origin of `other` are only taken when unknown here.
+    pub fn merge(&mut self, other: &Self) {
+        if self.genome == BioSourceGenome::Unknown {
+            self.genome = other.genome.clone();
+        }
+        if self.origin == BioSourceOrigin::Unknown {
+            self.origin = other.origin.clone();
+        }
+        self.org.merge(&other.org);
+        unite(&mut self.subtype, &other.subtype, |a, b| {
+            a.subtype == b.subtype && a.name.trim() == b.name.trim()
+        });
+        fill(&mut self.is_focus, &other.is_focus);
+        fill(&mut self.pcr_primers, &other.pcr_primers);
+    }
+}
+
+/// Parse a `collection-date` given as "DD-Mmm-YYYY", "Mmm-YYYY", "YYYY-MM-DD",
+/// "YYYY-MM" or "YYYY", optionally with a time in UTC (eg: "2020-03-15T10:20Z")
+fn parse_collection_date(text: &str) -> Option<DateStd> {
+    fn number<T: FromStr>(field: &str, digits: usize) -> Option<T> {
+        (field.len() == digits && field.bytes().all(|b| b.is_ascii_digit())).then(|| field.parse().ok())?
+    }
+
+    let (date, time) = match text.split_once('T') {
+        Some((date, time)) => (date, Some(time.strip_suffix('Z')?)),
+        None => (text, None),
+    };
+    let fields: Vec<&str> = date.split('-').collect();
+    let month = |name: &str| {
+        MONTHS
+            .iter()
+            .position(|month| month.eq_ignore_ascii_case(name))
+            .map(|i| i as u8 + 1)
+    };
+
+    let mut parsed = DateStd::default();
+    match fields.as_slice() {
+        [year] => parsed.year = number(year, 4)?,
+        [first, year] if first.len() == 3 => {
+            parsed.month = Some(month(first)?);
+            parsed.year = number(year, 4)?;
+        }
+        [year, number_month] => {
+            parsed.year = number(year, 4)?;
+            parsed.month = Some(number(number_month, 2)?);
+        }
+        [day, name, year] if name.len() == 3 => {
+            parsed.day = Some(number(day, 2)?);
+            parsed.month = Some(month(name)?);
+            parsed.year = number(year, 4)?;
+        }
+        [year, number_month, day] => {
+            parsed.year = number(year, 4)?;
+            parsed.month = Some(number(number_month, 2)?);
+            parsed.day = Some(number(day, 2)?);
+        }
+        _ => return None,
+    }
+    let valid_month = parsed.month.is_none_or(|month| (1..=12).contains(&month));
+    let valid_day = parsed.day.is_none_or(|day| (1..=31).contains(&day));
+    if !valid_month || !valid_day {
+        return None;
+    }
+
+    if let Some(time) = time {
+        let fields: Vec<u8> = time.split(':').map(|field| number(field, 2)).collect::<Option<_>>()?;
+        match fields.as_slice() {
+            [hour, rest @ ..] if rest.len() <= 2 && *hour < 24 && rest.iter().all(|n| *n < 60) => {
+                parsed.hour = Some(*hour);
+                parsed.minute = rest.first().copied();
+                parsed.second = rest.get(1).copied();
+            }
+            _ => return None,
+        }
+    }
+    Some(parsed)
+}
+
+/// Parse a `lat-lon` qualifier (eg: "35.12 N 120.5 W") into decimal degrees
+///
+/// South latitudes and west longitudes are negative, and the error describes why
+/// `text` is not in the format of the qualifier.
+pub fn parse_lat_lon(text: &str) -> Result<(f64, f64), String> {
+    let fields: Vec<&str> = text.split_whitespace().collect();
+    let [lat, ns, lon, ew] = fields.as_slice() else {
+        return Err(format!("lat-lon \"{}\" is not \"d[.dddd] N|S d[.dddd] W|E\"", text));
+    };
+    let degrees = |value: &str, max: f64| match value.parse::<f64>() {
+        Ok(degrees) if (0.0..=max).contains(&degrees) => Ok(degrees),
+        _ => Err(format!("lat-lon \"{}\" has invalid degrees \"{}\"", text, value)),
+    };
+    let lat = match *ns {
+        "N" => degrees(lat, 90.0)?,
+        "S" => -degrees(lat, 90.0)?,
+        _ => return Err(format!("lat-lon \"{}\" has no N or S latitude", text)),
+    };
+    let lon = match *ew {
+        "E" => degrees(lon, 180.0)?,
+        "W" => -degrees(lon, 180.0)?,
+        _ => return Err(format!("lat-lon \"{}\" has no E or W longitude", text)),
+    };
+    Ok((lat, lon))
+}
+
 impl XmlNode for BioSource {
     fn start_bytes() -> BytesStart<'static> {
         BytesStart::new("BioSource")
@@ -2573,18 +4911,18 @@ impl XmlNode for BioSource {
         let forbidden = UnexpectedTags(&[]);
 
         loop {
-            match reader.read_event().unwrap() {
+            match next_event(reader)? {
                 Event::Start(e) => {
                     let name = e.name();
 
-                    if name == genome_element.name() {
-                        source.genome = read_node(reader).unwrap();
-                    } else if name == org_element.name() {
-                        source.org = read_node(reader).unwrap();
-                    } else if name == subtype_element.name() {
-                        source.subtype = Some(read_vec_node(reader, subtype_element.to_end()))
-                    } else if name == origin_element.name() {
-                        source.origin = read_node(reader).unwrap();
+                    if name.is_tag(genome_element.name()) {
+                        source.genome = read_node(reader)?;
+                    } else if name.is_tag(org_element.name()) {
+                        source.org = read_node(reader)?;
+                    } else if name.is_tag(subtype_element.name()) {
+                        source.subtype = Some(read_vec_node(reader, e.to_end()))
+                    } else if name.is_tag(origin_element.name()) {
+                        source.origin = read_node(reader)?;
                     } else if name != Self::start_bytes().name() {
                         forbidden.check(&name);
                     } else if name != Self::start_bytes().name() {
@@ -2682,6 +5020,53 @@ enum_from_primitive! {
     }
 }
 
+asn_names!(SubSourceSubType {
+    Chromosome => "chromosome",
+    Map => "map",
+    Clone => "clone",
+    Subclone => "subclone",
+    Haplotype => "haplotype",
+    Genotype => "genotype",
+    Sex => "sex",
+    CellLine => "cell-line",
+    CellType => "cell-type",
+    TissueType => "tissue-type",
+    CloneLib => "clone-lib",
+    DevStage => "dev-stage",
+    Frequency => "frequency",
+    Germline => "germline",
+    Rearranged => "rearranged",
+    LabHost => "lab-host",
+    PopVariant => "pop-variant",
+    TissueLib => "tissue-lib",
+    PlasmidName => "plasmid-name",
+    TransposonName => "transposon-name",
+    InsertionSeqName => "insertion-seq-name",
+    PlastidName => "plastid-name",
+    Country => "country",
+    Segment => "segment",
+    EndogenousVirusName => "endogenous-virus-name",
+    Transgenic => "transgenic",
+    EnvironmentalSample => "environmental-sample",
+    IsolationSource => "isolation-source",
+    LatLon => "lat-lon",
+    CollectionDate => "collection-date",
+    CollectedBy => "collected-by",
+    IdentifiedBy => "identified-by",
+    FwdPrimerSeq => "fwd-primer-seq",
+    RevPrimerSeq => "rev-primer-seq",
+    FwdPrimerName => "fwd-primer-name",
+    RevPrimerName => "rev-primer-name",
+    Metagenomic => "metagenomic",
+    MatingType => "mating-type",
+    LinkageGroup => "linkage-group",
+    Haplogroup => "haplogroup",
+    WholeReplicon => "whole-replicon",
+    Phenotype => "phenotype",
+    Altitude => "altitude",
+    Other => "other",
+});
+
 /// default not in original spec
 impl Default for SubSourceSubType {
     fn default() -> Self {
@@ -2695,7 +5080,7 @@ impl XmlNode for SubSourceSubType {
     }
 
     fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> {
-        Self::from_u8(read_int(reader).unwrap())
+        Self::from_u8(read_int(reader)?)
     }
 }
 
@@ -2723,15 +5108,15 @@ impl XmlNode for SubSource {
         let forbidden = UnexpectedTags(&[]);
 
         loop {
-            match reader.read_event().unwrap() {
+            match next_event(reader)? {
                 Event::Start(e) => {
                     let qname = e.name();
 
-                    if qname == subtype_element.name() {
-                        source.subtype = read_node(reader).unwrap();
-                    } else if qname == name_element.name() {
-                        source.name = read_string(reader).unwrap();
-                    } else if qname == attrib_element.name() {
+                    if qname.is_tag(subtype_element.name()) {
+                        source.subtype = read_node(reader)?;
+                    } else if qname.is_tag(name_element.name()) {
+                        source.name = read_string(reader)?;
+                    } else if qname.is_tag(attrib_element.name()) {
                         source.attrib = read_string(reader);
                     } else if qname != Self::start_bytes().name() {
                         forbidden.check(&qname);
@@ -2762,6 +5147,15 @@ pub enum ProtRefProcessingStatus {
     ProPeptide,
 }
 
+asn_names!(ProtRefProcessingStatus {
+    NotSet => "not-set",
+    PreProtein => "preprotein",
+    Mature => "mature",
+    SignalPeptide => "signal-peptide",
+    TransitPeptide => "transit-peptide",
+    ProPeptide => "propeptide",
+});
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
 #[serde(rename_all = "kebab-case")]
 /// Reference to a protein name
@@ -2806,20 +5200,20 @@ impl XmlNode for ProtRef {
         let forbidden = UnexpectedTags(&forbidden);
 
         loop {
-            match reader.read_event().unwrap() {
+            match next_event(reader)? {
                 Event::Start(e) => {
                     let name = e.name();
 
-                    if name == name_tag.name() {
-                        prot.name = read_vec_str_unchecked(reader, &name_tag.to_end()).into();
-                    } else if name == desc_tag.name() {
+                    if name.is_tag(name_tag.name()) {
+                        prot.name = read_vec_str_unchecked(reader, &e.to_end()).into();
+                    } else if name.is_tag(desc_tag.name()) {
                         prot.desc = read_string(reader);
-                    } else if name == ec_tag.name() {
-                        prot.ec = read_vec_str_unchecked(reader, &ec_tag.to_end()).into();
-                    } else if name == activity_tag.name() {
-                        prot.activity = read_vec_str_unchecked(reader, &activity_tag.to_end()).into();
-                    } else if name == db_tag.name() {
-                        prot.db = read_vec_node(reader, db_tag.to_end()).into();
+                    } else if name.is_tag(ec_tag.name()) {
+                        prot.ec = read_vec_str_unchecked(reader, &e.to_end()).into();
+                    } else if name.is_tag(activity_tag.name()) {
+                        prot.activity = read_vec_str_unchecked(reader, &e.to_end()).into();
+                    } else if name.is_tag(db_tag.name()) {
+                        prot.db = read_vec_node(reader, e.to_end()).into();
                     } else if name != Self::start_bytes().name() {
                         forbidden.check(&name);
                     }
@@ -2860,6 +5254,18 @@ pub enum TxSystem {
     Other = 255,
 }
 
+asn_names!(TxSystem {
+    Unknown => "unknown",
+    Pol1 => "pol1",
+    Pol2 => "pol2",
+    Pol3 => "pol3",
+    Bacterial => "bacterial",
+    Viral => "viral",
+    Rna => "rna",
+    Organelle => "organelle",
+    Other => "other",
+});
+
 #[derive(Clone, Serialize_repr, Deserialize_repr, PartialEq, Debug)]
 #[repr(u8)]
 /// Represents type of transcription initiation site (TIS)
@@ -2876,6 +5282,13 @@ pub enum InitType {
     Region,
 }
 
+asn_names!(InitType {
+    Unknown => "unknown",
+    Single => "single",
+    Multiple => "multiple",
+    Region => "region",
+});
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 #[serde(rename_all = "kebab-case")]
 /// Transcription Initiation Site feature data block
@@ -2957,6 +5370,21 @@ pub enum TxEvidenceExpCode {
     Other = 255,
 }
 
+asn_names!(TxEvidenceExpCode {
+    Unknown => "unknown",
+    RnaSeq => "rna-seq",
+    RnaSize => "rna-size",
+    NpMap => "np-map",
+    NpSize => "np-size",
+    PeSeq => "pe-seq",
+    CDnaSeq => "cDNA-seq",
+    PeMap => "pe-map",
+    PeSize => "pe-size",
+    PseudoSeq => "pseudo-seq",
+    RevPeMap => "rev-pe-map",
+    Other => "other",
+});
+
 #[derive(Clone, Serialize_repr, Deserialize_repr, PartialEq, Debug, Default)]
 #[repr(u8)]
 pub enum TxEvidenceExpressionSystem {
@@ -2970,6 +5398,16 @@ pub enum TxEvidenceExpressionSystem {
     Other = 255,
 }
 
+asn_names!(TxEvidenceExpressionSystem {
+    Unknown => "unknown",
+    Physiological => "physiological",
+    InVitro => "in-vitro",
+    Oocyte => "oocyte",
+    Transfection => "transfection",
+    Transgenic => "transgenic",
+    Other => "other",
+});
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 #[serde(rename_all = "kebab-case")]
 pub struct TxEvidence {
@@ -2978,3 +5416,488 @@ pub struct TxEvidence {
     pub low_prec_data: bool, // TODO: default false
     pub from_homolog: bool,  // TODO: default false
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::general::{DbTag, ObjectId};
+    use crate::parsing::parse_node;
+    use crate::r#pub::{Pub, PubSet};
+    use crate::seqfeat::{BioSource, BioSourceGenome, CdRegion, CodeBreakAA, EvidenceCategory, FeatId, SeqFeat, SeqFeatData, SeqFeatExpEvidence, ExperimentSupport, GeneticCode, GeneticCodeOpt, InferenceSupport, InferenceSupportType, OrgName, ResolveGeneticCode, RnaRefExt, RnaRefType, TRnaExtAa};
+    use crate::seqloc::{NaStrand, SeqId, SeqInterval, SeqLoc};
+    use crate::summary::Summary;
+
+    #[test]
+    fn test_rna_ref() {
+        let xml = r#"<SeqFeatData><SeqFeatData_rna><RNA-ref>
+            <RNA-ref_type value="tRNA"/>
+            <RNA-ref_ext><RNA-ref_ext_tRNA><Trna-ext>
+              <Trna-ext_aa><Trna-ext_aa_ncbieaa>77</Trna-ext_aa_ncbieaa></Trna-ext_aa>
+              <Trna-ext_codon><Trna-ext_codon_E>14</Trna-ext_codon_E></Trna-ext_codon>
+              <Trna-ext_anticodon><Seq-loc><Seq-loc_int><Seq-interval>
+                <Seq-interval_from>33</Seq-interval_from>
+                <Seq-interval_to>35</Seq-interval_to>
+                <Seq-interval_strand><Na-strand value="plus"/></Seq-interval_strand>
+                <Seq-interval_id><Seq-id><Seq-id_gi>1</Seq-id_gi></Seq-id></Seq-interval_id>
+              </Seq-interval></Seq-loc_int></Seq-loc></Trna-ext_anticodon>
+            </Trna-ext></RNA-ref_ext_tRNA></RNA-ref_ext>
+          </RNA-ref></SeqFeatData_rna></SeqFeatData>"#;
+        let rna = match parse_node::<SeqFeatData>(xml).unwrap() {
+            SeqFeatData::RNA(rna) => rna,
+            data => panic!("Unexpected data {:?}", data),
+        };
+        assert_eq!(rna.r#type, RnaRefType::tRNA);
+        assert_eq!(rna.pseudo, None);
+        match rna.ext.unwrap() {
+            RnaRefExt::tRNA(trna) => {
+                assert_eq!(trna.aa, TRnaExtAa::NCBIEaa(77));
+                assert_eq!(trna.codon, Some(vec![14]));
+                assert_eq!(trna.anticodon.unwrap().total_range(), Some((33, 35)));
+            }
+            ext => panic!("Unexpected ext {:?}", ext),
+        }
+
+        let xml = r#"<SeqFeatData><SeqFeatData_rna><RNA-ref>
+            <RNA-ref_type value="ncRNA"/>
+            <RNA-ref_pseudo value="true"/>
+            <RNA-ref_ext><RNA-ref_ext_gen><RNA-gen>
+              <RNA-gen_class>antisense_RNA</RNA-gen_class>
+              <RNA-gen_product>TP73 antisense RNA 1</RNA-gen_product>
+              <RNA-gen_quals><RNA-qual-set><RNA-qual>
+                <RNA-qual_qual>tag_peptide</RNA-qual_qual>
+                <RNA-qual_val>90..122</RNA-qual_val>
+              </RNA-qual></RNA-qual-set></RNA-gen_quals>
+            </RNA-gen></RNA-ref_ext_gen></RNA-ref_ext>
+          </RNA-ref></SeqFeatData_rna></SeqFeatData>"#;
+        let rna = match parse_node::<SeqFeatData>(xml).unwrap() {
+            SeqFeatData::RNA(rna) => rna,
+            data => panic!("Unexpected data {:?}", data),
+        };
+        assert_eq!(rna.r#type, RnaRefType::ncRNA);
+        assert_eq!(rna.pseudo, Some(true));
+        match rna.ext.unwrap() {
+            RnaRefExt::Gen(gen) => {
+                assert_eq!(gen.class.as_deref(), Some("antisense_RNA"));
+                assert_eq!(gen.product.as_deref(), Some("TP73 antisense RNA 1"));
+                let quals = gen.quals.unwrap();
+                assert_eq!((quals[0].qual.as_str(), quals[0].val.as_str()), ("tag_peptide", "90..122"));
+            }
+            ext => panic!("Unexpected ext {:?}", ext),
+        }
+    }
+
+    #[test]
+    fn test_parse_inference() {
+        let text = "COORDINATES: similar to AA sequence:RefSeq:WP_000000000.1";
+        let inference: InferenceSupport = text.parse().unwrap();
+        assert_eq!(inference.category, Some(EvidenceCategory::Coordinates));
+        assert_eq!(inference.r#type, InferenceSupportType::SimilarToAA);
+        assert!(!inference.same_species);
+
+        let accessions = inference.basis.accessions.as_ref().unwrap();
+        match &accessions[0] {
+            SeqId::Other(id) => {
+                assert_eq!(id.accession.as_deref(), Some("WP_000000000"));
+                assert_eq!(id.version, Some(1));
+            }
+            id => panic!("Unexpected id {:?}", id),
+        }
+        assert_eq!(inference.to_string(), text);
+    }
+
+    #[test]
+    fn test_inference_round_trip() {
+        for text in [
+            "ab initio prediction:GeneMarkS-2+:1.14",
+            "COORDINATES: profile:tRNAscan-SE:2.0.12",
+            "similar to RNA sequence, mRNA (same species):INSD:AB012345.1,INSD:AB012346.2",
+            "EXISTENCE: similar to DNA sequence:UniProtKB:P12345",
+            "protein motif",
+        ] {
+            let inference: InferenceSupport = text.parse().unwrap();
+            assert_eq!(inference.to_string(), text);
+        }
+        let inference: InferenceSupport = "similar to RNA sequence, EST:INSD:X1.1".parse().unwrap();
+        assert_eq!(inference.r#type, InferenceSupportType::SimilarToEst);
+
+        assert!("guesswork".parse::<InferenceSupport>().is_err());
+        assert!("similar to AA sequence:WP_1".parse::<InferenceSupport>().is_err());
+    }
+
+    #[test]
+    fn test_parse_experiment() {
+        let experiment: ExperimentSupport = "EXISTENCE:Northern blot [PMID:12345,DOI:10.1000/182]".parse().unwrap();
+        assert_eq!(experiment.category, Some(EvidenceCategory::Existence));
+        assert_eq!(experiment.explanation, "Northern blot");
+        assert_eq!(experiment.pmids, Some(vec![12345]));
+        assert_eq!(experiment.dois, Some(vec!["10.1000/182".to_string()]));
+        assert_eq!(experiment.to_string(), "EXISTENCE:Northern blot [PMID:12345,DOI:10.1000/182]");
+
+        let experiment: ExperimentSupport = "5' RACE [lab notes]".parse().unwrap();
+        assert_eq!(experiment.category, None);
+        assert_eq!(experiment.explanation, "5' RACE [lab notes]");
+        assert!(experiment.pmids.is_none());
+    }
+
+    #[test]
+    fn test_lineage() {
+        let orgname = OrgName {
+            lineage: Some("cellular organisms; Bacteria; Pseudomonadota; Gammaproteobacteria;".into()),
+            gcode: Some(11),
+            mgcode: Some(0),
+            ..Default::default()
+        };
+        assert_eq!(orgname.lineage_vec().len(), 4);
+        assert_eq!(orgname.lineage_vec()[3], "Gammaproteobacteria");
+        assert_eq!(orgname.superkingdom(), Some("Bacteria"));
+        assert!(orgname.is_bacteria() && !orgname.is_eukaryote());
+        assert_eq!(orgname.genetic_code().unwrap().name, "Bacterial, Archaeal and Plant Plastid");
+        assert!(orgname.mito_genetic_code().is_none());
+
+        assert!(OrgName::default().lineage_vec().is_empty());
+        assert!(OrgName::default().superkingdom().is_none());
+    }
+
+    #[test]
+    fn test_biosource_genetic_code() {
+        let mut source = BioSource::default();
+        source.org.db = Some(vec![DbTag {
+            db: "taxon".into(),
+            tag: ObjectId::Id(9606),
+        }]);
+        source.org.orgname = Some(OrgName {
+            lineage: Some("Eukaryota; Metazoa; Chordata".into()),
+            gcode: Some(1),
+            mgcode: Some(2),
+            ..Default::default()
+        });
+        assert_eq!(source.taxid(), Some(9606));
+        assert_eq!(source.org.lineage_vec()[0], "Eukaryota");
+        assert_eq!(source.genetic_code().unwrap().id, 1);
+
+        source.genome = BioSourceGenome::Mitochondrion;
+        assert_eq!(source.genetic_code().unwrap().name, "Vertebrate Mitochondrial");
+        source.genome = BioSourceGenome::Chloroplast;
+        assert_eq!(source.genetic_code().unwrap().id, 11);
+    }
+
+    #[test]
+    fn test_biosource_qualifiers() {
+        use crate::builder::BioSourceBuilder;
+        use crate::general::{Date, DateStd};
+        use crate::seqfeat::{OrgModSubType, SubSourceSubType};
+
+        let source = BioSourceBuilder::new("Escherichia coli")
+            .subsource(SubSourceSubType::Country, "USA: Maine")
+            .subsource(SubSourceSubType::CollectionDate, "15-Mar-2020")
+            .subsource(SubSourceSubType::IsolationSource, "soil")
+            .subsource(SubSourceSubType::LatLon, "35.12 N 120.5 W")
+            .org_mod(OrgModSubType::Strain, "K-12")
+            .org_mod(OrgModSubType::CultureCollection, "ATCC:25922")
+            .build()
+            .unwrap();
+        assert_eq!(source.country(), Some("USA: Maine"));
+        assert_eq!(source.isolation_source(), Some("soil"));
+        assert_eq!(source.strain(), Some("K-12"));
+        assert_eq!(source.culture_collection(), Some("ATCC:25922"));
+        assert_eq!(source.isolate(), None);
+        assert_eq!(source.lat_lon(), Some((35.12, -120.5)));
+        assert_eq!(
+            source.collection_date(),
+            Some(Date::Std(DateStd {
+                year: 2020,
+                month: Some(3),
+                day: Some(15),
+                ..DateStd::default()
+            }))
+        );
+    }
+
+    #[test]
+    fn test_merge_sources() {
+        use crate::builder::BioSourceBuilder;
+        use crate::seqfeat::{OrgMod, OrgModSubType, SubSource, SubSourceSubType};
+
+        let mut first = BioSourceBuilder::new("Escherichia coli")
+            .taxid(562)
+            .subsource(SubSourceSubType::Country, "USA")
+            .org_mod(OrgModSubType::Strain, "K-12")
+            .build()
+            .unwrap();
+        let mut second = BioSourceBuilder::new(" Escherichia coli")
+            .org_mod(OrgModSubType::Strain, "K-12 ")
+            .subsource(SubSourceSubType::Country, "USA")
+            .taxid(562)
+            .build()
+            .unwrap();
+        second.genome = BioSourceGenome::Genomic;
+        if let Some(orgname) = second.org.orgname.as_mut() {
+            orgname.attrib = Some("Migula 1895".to_string());
+            orgname.r#mod.get_or_insert_with(Vec::new)[0].attrib = Some("culture".to_string());
+        }
+
+        // ordering, whitespace and attributions are ignored
+        assert!(first.org.equivalent(&second.org));
+        assert!(!first.equivalent(&second));
+        first.genome = BioSourceGenome::Genomic;
+        assert!(first.equivalent(&second));
+
+        second.org.db.get_or_insert_with(Vec::new).push(DbTag {
+            db: "ATCC".into(),
+            tag: ObjectId::Id(25922),
+        });
+        second.org.orgname.as_mut().unwrap().r#mod.as_mut().unwrap().push(OrgMod {
+            subtype: OrgModSubType::Serotype,
+            subname: "O157:H7".to_string(),
+            attrib: None,
+        });
+        second.subtype.get_or_insert_with(Vec::new).push(SubSource {
+            subtype: SubSourceSubType::TissueType,
+            name: "liver".to_string(),
+            attrib: None,
+        });
+        first.merge(&second);
+        assert_eq!(first.org.taxname.as_deref(), Some("Escherichia coli"));
+        assert_eq!(first.org.db.as_ref().unwrap().len(), 2);
+        assert_eq!(first.org.orgname.as_ref().unwrap().r#mod.as_ref().unwrap().len(), 2);
+        assert_eq!(first.org.orgname.as_ref().unwrap().attrib.as_deref(), Some("Migula 1895"));
+        assert_eq!(first.subtype.as_ref().unwrap().len(), 2);
+        assert_eq!(first.subsource(SubSourceSubType::TissueType), Some("liver"));
+
+        // merging again adds nothing
+        let merged = first.clone();
+        first.merge(&second);
+        assert_eq!(first, merged);
+    }
+
+    #[test]
+    fn test_parse_collection_date() {
+        use crate::seqfeat::parse_collection_date;
+
+        let date = parse_collection_date("2020-03-15T10:20Z").unwrap();
+        assert_eq!((date.year, date.month, date.day), (2020, Some(3), Some(15)));
+        assert_eq!((date.hour, date.minute, date.second), (Some(10), Some(20), None));
+        assert_eq!(parse_collection_date("Mar-2020").unwrap().month, Some(3));
+        assert_eq!(parse_collection_date("2020-03").unwrap().day, None);
+        assert_eq!(parse_collection_date("2020").unwrap().month, None);
+
+        assert_eq!(parse_collection_date("2019/2020"), None);
+        assert_eq!(parse_collection_date("2020-13-01"), None);
+        assert_eq!(parse_collection_date("missing"), None);
+    }
+
+    #[test]
+    fn test_parse_lat_lon() {
+        use crate::seqfeat::parse_lat_lon;
+
+        assert_eq!(parse_lat_lon("0.5 S 12 E"), Ok((-0.5, 12.0)));
+        assert!(parse_lat_lon("35.12 N").is_err());
+        assert!(parse_lat_lon("95 N 12 E").is_err());
+        assert!(parse_lat_lon("35.12 E 120.5 W").is_err());
+        assert!(parse_lat_lon("35.12N 120.5W").is_err());
+    }
+
+    #[test]
+    fn test_resolve_genetic_code() {
+        let xml = "<Cdregion><Cdregion_code><Genetic-code>\
+            <Genetic-code_E><Genetic-code_E_name>Vertebrate Mitochondrial</Genetic-code_E_name></Genetic-code_E>\
+            <Genetic-code_E><Genetic-code_E_id>2</Genetic-code_E_id></Genetic-code_E>\
+            </Genetic-code></Cdregion_code></Cdregion>";
+        let cdregion: CdRegion = parse_node(xml).unwrap();
+        assert_eq!(cdregion.genetic_code().unwrap().id, 2);
+        let code = cdregion.code.unwrap();
+        assert_eq!(code[0], GeneticCodeOpt::Name("Vertebrate Mitochondrial".into()));
+        assert_eq!(code.resolve().unwrap().id, 2);
+
+        let by_name: GeneticCode = vec![GeneticCodeOpt::Name("Standard".into())];
+        assert_eq!(by_name.resolve().unwrap().id, 1);
+        let unknown: GeneticCode = vec![GeneticCodeOpt::Id(7)];
+        assert!(unknown.resolve().is_none());
+
+        // coding regions without a code use the standard code
+        assert_eq!(CdRegion::default().genetic_code().unwrap().id, 1);
+    }
+
+    #[test]
+    fn test_seq_feat_fields() {
+        let xml = "<Seq-feat>\
+            <Seq-feat_data><SeqFeatData><SeqFeatData_gene><Gene-ref>\
+            <Gene-ref_locus>rsxC</Gene-ref_locus></Gene-ref></SeqFeatData_gene></SeqFeatData></Seq-feat_data>\
+            <Seq-feat_partial value=\"true\"/>\
+            <Seq-feat_except value=\"true\"/>\
+            <Seq-feat_location><Seq-loc><Seq-loc_whole><Seq-id><Seq-id_gi>1</Seq-id_gi></Seq-id></Seq-loc_whole></Seq-loc></Seq-feat_location>\
+            <Seq-feat_title>title</Seq-feat_title>\
+            <Seq-feat_cit><Pub-set><Pub-set_pub><Pub><PubMedId>12345</PubMedId></Pub></Pub-set_pub></Pub-set></Seq-feat_cit>\
+            <Seq-feat_exp-ev value=\"not-experimental\"/>\
+            <Seq-feat_except-text>ribosomal slippage</Seq-feat_except-text>\
+            <Seq-feat_ids><Feat-id><Feat-id_local><Object-id><Object-id_id>7</Object-id_id></Object-id></Feat-id_local></Feat-id></Seq-feat_ids>\
+            <Seq-feat_exts><User-object><User-object_type><Object-id><Object-id_str>Note</Object-id_str></Object-id></User-object_type>\
+            <User-object_data><User-field><User-field_label><Object-id><Object-id_str>a</Object-id_str></Object-id></User-field_label>\
+            <User-field_data><User-field_data_str>b</User-field_data_str></User-field_data></User-field></User-object_data></User-object></Seq-feat_exts>\
+            </Seq-feat>";
+        let feat: SeqFeat = parse_node(xml).unwrap();
+        assert!(matches!(feat.data, SeqFeatData::Gene(_)));
+        assert_eq!(feat.location, SeqLoc::Whole(SeqId::Gi(1)));
+        assert_eq!((feat.partial, feat.except), (Some(true), Some(true)));
+        assert_eq!(feat.title.as_deref(), Some("title"));
+        assert_eq!(feat.cit, Some(PubSet::Pub(vec![Pub::PmId(12345)])));
+        assert_eq!(feat.exp_ev, Some(SeqFeatExpEvidence::NotExperimental));
+        assert_eq!(feat.except_text.as_deref(), Some("ribosomal slippage"));
+        assert_eq!(feat.ids, Some(vec![FeatId::Local(ObjectId::Id(7))]));
+        assert_eq!(feat.exts.as_ref().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_exceptions() {
+        use crate::seqfeat::{FeatException, GbQual, GeneRef, PseudogeneType};
+
+        let mut feat = SeqFeat::new(SeqFeatData::CdRegion(CdRegion::default()));
+        assert!(feat.exceptions().is_empty());
+        assert!(!feat.is_pseudo());
+
+        feat.except_text = Some("ribosomal slippage, RNA Editing".to_string());
+        feat.qual = Some(vec![
+            GbQual { qual: "exception".into(), val: "frameshifted by sequencing".into() },
+            GbQual { qual: "pseudogene".into(), val: "unitary".into() },
+        ]);
+        let exceptions = feat.exceptions();
+        assert_eq!(
+            exceptions,
+            vec![
+                FeatException::RibosomalSlippage,
+                FeatException::RnaEditing,
+                FeatException::Other("frameshifted by sequencing".to_string()),
+            ]
+        );
+        assert!(exceptions[0].is_programmed_frameshift());
+        assert!(!exceptions[1].is_programmed_frameshift());
+        assert!(exceptions[1].alters_translation());
+        assert!(exceptions[2].is_other() && !exceptions[2].alters_translation());
+        assert_eq!(exceptions[1].to_string(), "RNA editing");
+        assert!(FeatException::from("trans-splicing").has_unusual_location());
+
+        assert!(feat.is_pseudo());
+        assert_eq!(feat.pseudogene_type(), Some(PseudogeneType::Unitary));
+        assert_eq!("Processed".parse(), Ok(PseudogeneType::Processed));
+        assert!("retrotransposed".parse::<PseudogeneType>().is_err());
+
+        let gene = SeqFeat::new(SeqFeatData::Gene(GeneRef { pseudo: true, ..GeneRef::default() }));
+        assert!(gene.is_pseudo());
+        assert_eq!(gene.pseudogene_type(), None);
+    }
+
+    #[test]
+    fn test_qualifiers() {
+        let xml = "<Seq-feat>\
+            <Seq-feat_data><SeqFeatData><SeqFeatData_gene><Gene-ref>\
+            <Gene-ref_locus>rsxC</Gene-ref_locus></Gene-ref></SeqFeatData_gene></SeqFeatData></Seq-feat_data>\
+            <Seq-feat_location><Seq-loc><Seq-loc_whole><Seq-id><Seq-id_gi>1</Seq-id_gi></Seq-id></Seq-loc_whole></Seq-loc></Seq-feat_location>\
+            <Seq-feat_qual>\
+            <Gb-qual><Gb-qual_qual>product</Gb-qual_qual><Gb-qual_val>electron transport complex subunit RsxC</Gb-qual_val></Gb-qual>\
+            <Gb-qual><Gb-qual_qual>pseudo</Gb-qual_qual><Gb-qual_val></Gb-qual_val></Gb-qual>\
+            <Gb-qual><Gb-qual_qual>product</Gb-qual_qual><Gb-qual_val>second</Gb-qual_val></Gb-qual>\
+            </Seq-feat_qual>\
+            </Seq-feat>";
+        let feat: SeqFeat = parse_node(xml).unwrap();
+        assert_eq!(feat.qualifier("product"), Some("electron transport complex subunit RsxC"));
+        assert_eq!(feat.qualifier("pseudo"), Some(""));
+        assert_eq!(feat.qualifier("note"), None);
+        let names: Vec<&str> = feat.qualifiers().map(|(name, _)| name).collect();
+        assert_eq!(names, ["product", "pseudo", "product"]);
+    }
+
+    #[test]
+    fn test_seq_feat_support() {
+        let xml = "<Seq-feat>\
+            <Seq-feat_data><SeqFeatData><SeqFeatData_gene><Gene-ref>\
+            <Gene-ref_locus>LOC1</Gene-ref_locus></Gene-ref></SeqFeatData_gene></SeqFeatData></Seq-feat_data>\
+            <Seq-feat_location><Seq-loc><Seq-loc_whole><Seq-id><Seq-id_gi>1</Seq-id_gi></Seq-id></Seq-loc_whole></Seq-loc></Seq-feat_location>\
+            <Seq-feat_support><SeqFeatSupport>\
+            <SeqFeatSupport_experiment><ExperimentSupport>\
+            <ExperimentSupport_category><EvidenceCategory value=\"existence\">3</EvidenceCategory></ExperimentSupport_category>\
+            <ExperimentSupport_explanation>Northern blot</ExperimentSupport_explanation>\
+            <ExperimentSupport_pmids><PubMedId>12345</PubMedId></ExperimentSupport_pmids>\
+            </ExperimentSupport></SeqFeatSupport_experiment>\
+            <SeqFeatSupport_inference><InferenceSupport>\
+            <InferenceSupport_category><EvidenceCategory value=\"coordinates\">1</EvidenceCategory></InferenceSupport_category>\
+            <InferenceSupport_type value=\"profile\">8</InferenceSupport_type>\
+            <InferenceSupport_same-species value=\"true\"/>\
+            <InferenceSupport_basis><EvidenceBasis><EvidenceBasis_programs><Program-id>\
+            <Program-id_name>tRNAscan-SE</Program-id_name><Program-id_version>2.0</Program-id_version>\
+            </Program-id></EvidenceBasis_programs></EvidenceBasis></InferenceSupport_basis>\
+            </InferenceSupport></SeqFeatSupport_inference>\
+            <SeqFeatSupport_model-evidence><ModelEvidenceSupport>\
+            <ModelEvidenceSupport_method>Gnomon</ModelEvidenceSupport_method>\
+            <ModelEvidenceSupport_mrna><ModelEvidenceItem>\
+            <ModelEvidenceItem_id><Seq-id><Seq-id_gi>2</Seq-id_gi></Seq-id></ModelEvidenceItem_id>\
+            <ModelEvidenceItem_exon-count>4</ModelEvidenceItem_exon-count>\
+            <ModelEvidenceItem_full-length value=\"true\"/>\
+            </ModelEvidenceItem></ModelEvidenceSupport_mrna>\
+            <ModelEvidenceSupport_protein><ModelEvidenceItem>\
+            <ModelEvidenceItem_id><Seq-id><Seq-id_gi>3</Seq-id_gi></Seq-id></ModelEvidenceItem_id>\
+            </ModelEvidenceItem></ModelEvidenceSupport_protein>\
+            <ModelEvidenceSupport_exon-count>4</ModelEvidenceSupport_exon-count>\
+            </ModelEvidenceSupport></SeqFeatSupport_model-evidence>\
+            </SeqFeatSupport></Seq-feat_support>\
+            </Seq-feat>";
+        let feat: SeqFeat = parse_node(xml).unwrap();
+        let support = feat.support.as_ref().unwrap();
+
+        let experiment = &support.experiment.as_ref().unwrap()[0];
+        assert_eq!(experiment.category, Some(EvidenceCategory::Existence));
+        assert_eq!(experiment.to_string(), "EXISTENCE:Northern blot [PMID:12345]");
+
+        let inference = &support.inference.as_ref().unwrap()[0];
+        assert_eq!(inference.r#type, InferenceSupportType::Profile);
+        assert!(inference.same_species);
+        assert_eq!(inference.to_string(), "COORDINATES: profile (same species):tRNAscan-SE:2.0");
+
+        let model = &support.model_evidence.as_ref().unwrap()[0];
+        let mrna = &model.mrna.as_ref().unwrap()[0];
+        assert_eq!(mrna.id, SeqId::Gi(2));
+        assert_eq!((mrna.exon_count, mrna.full_length), (Some(4), true));
+        assert_eq!(model.protein.as_ref().unwrap()[0].id, SeqId::Gi(3));
+        assert_eq!(model.exon_count, Some(4));
+
+        assert_eq!(
+            support.summary(),
+            "1 experiment; inference: COORDINATES: profile (same species):tRNAscan-SE:2.0; \
+            model evidence (Gnomon): 1 mRNA, 1 protein"
+        );
+    }
+
+    #[test]
+    fn test_code_break() {
+        let xml = "<Cdregion><Cdregion_code-break><Code-break>\
+            <Code-break_loc><Seq-loc><Seq-loc_int><Seq-interval>\
+            <Seq-interval_from>13</Seq-interval_from><Seq-interval_to>15</Seq-interval_to>\
+            <Seq-interval_id><Seq-id><Seq-id_gi>1</Seq-id_gi></Seq-id></Seq-interval_id>\
+            </Seq-interval></Seq-loc_int></Seq-loc></Code-break_loc>\
+            <Code-break_aa><Code-break_aa_ncbieaa>85</Code-break_aa_ncbieaa></Code-break_aa>\
+            </Code-break></Cdregion_code-break></Cdregion>";
+        let mut cdregion: CdRegion = parse_node(xml).unwrap();
+        let code_break = &cdregion.code_break.as_ref().unwrap()[0];
+        assert_eq!(code_break.aa, CodeBreakAA::NcbiAa(85));
+        assert_eq!(code_break.aa.to_char(), Some('U'));
+        assert_eq!(CodeBreakAA::NcbiStdAa(24).to_char(), Some('U'));
+
+        let interval = |from, to, strand| SeqLoc::Int(SeqInterval {
+            from,
+            to,
+            strand: Some(strand),
+            id: SeqId::Gi(1),
+            ..Default::default()
+        });
+        let location = interval(10, 18, NaStrand::Plus);
+        assert_eq!(cdregion.translate(&location, "ATGTGATAA").unwrap(), "MU*");
+        assert_eq!(CdRegion::default().translate(&location, "ATGTGATAA").unwrap(), "M**");
+
+        // on the minus strand, the codon starts at the end of the break
+        let location = interval(7, 15, NaStrand::Minus);
+        cdregion.code_break.as_mut().unwrap()[0].loc = interval(7, 9, NaStrand::Minus);
+        assert_eq!(cdregion.translate(&location, "ATGTGATAA").unwrap(), "M*U");
+
+        // breaks outside of the location are reported
+        cdregion.code_break.as_mut().unwrap()[0].loc = interval(20, 22, NaStrand::Minus);
+        assert!(cdregion.translate(&location, "ATGTGATAA").is_err());
+    }
+}
@@ -6,17 +6,20 @@
 //! See [book](https://ncbi.github.io/cxx-toolkit/pages/ch_datamod#ch_datamod._ASN1_Specification_s_8)
 //! for more information on.
 
-use crate::biblio::IdPat;
-use crate::general::{Date, DbTag, IntFuzz, ObjectId};
-use crate::parsing::{attribute_value, read_attributes, read_vec_node, read_int, read_node, read_string, UnexpectedTags};
+use crate::biblio::{IdPat, IdPatChoice};
+use crate::coords::{Pos0, Range0};
+use crate::general::{Date, DbTag, DbTagRef, IntFuzz, ObjectId, ObjectIdRef};
+use crate::parsing::{next_event, attribute_value, read_attributes, read_vec_node, read_int, read_node, read_node_borrowed, read_str, read_string, qualified_name, UnexpectedTags};
 use crate::seqfeat::FeatId;
-use crate::parsing::{XmlNode, XmlVecNode, XmlValue};
+use crate::parsing::{TagMatch, XmlNode, XmlNodeRef, XmlVecNode, XmlValue};
 use quick_xml::events::{BytesStart, Event};
 use quick_xml::events::attributes::Attributes;
 use quick_xml::Reader;
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
 
-#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "lowercase")]
 pub enum SeqId {
     Local(ObjectId),
@@ -69,6 +72,223 @@ pub enum SeqId {
     NamedAnnotTrack(TextseqId),
 }
 
+impl SeqId {
+    /// Identifier of variants given by a [`TextseqId`]
+    pub fn textseq_id(&self) -> Option<&TextseqId> {
+        match self {
+            SeqId::Genbank(id)
+            | SeqId::Embl(id)
+            | SeqId::Pir(id)
+            | SeqId::Swissprot(id)
+            | SeqId::Other(id)
+            | SeqId::Ddbj(id)
+            | SeqId::Prf(id)
+            | SeqId::Tpg(id)
+            | SeqId::Tpe(id)
+            | SeqId::Tpd(id)
+            | SeqId::Gpipe(id)
+            | SeqId::NamedAnnotTrack(id) => Some(id),
+            _ => None,
+        }
+    }
+
+    /// Apply `f` to the [`TextseqId`] of this identifier, if any
+    fn map_textseq_id<F: Fn(&TextseqId) -> TextseqId>(&self, f: F) -> Self {
+        match self {
+            SeqId::Genbank(id) => SeqId::Genbank(f(id)),
+            SeqId::Embl(id) => SeqId::Embl(f(id)),
+            SeqId::Pir(id) => SeqId::Pir(f(id)),
+            SeqId::Swissprot(id) => SeqId::Swissprot(f(id)),
+            SeqId::Other(id) => SeqId::Other(f(id)),
+            SeqId::Ddbj(id) => SeqId::Ddbj(f(id)),
+            SeqId::Prf(id) => SeqId::Prf(f(id)),
+            SeqId::Tpg(id) => SeqId::Tpg(f(id)),
+            SeqId::Tpe(id) => SeqId::Tpe(f(id)),
+            SeqId::Tpd(id) => SeqId::Tpd(f(id)),
+            SeqId::Gpipe(id) => SeqId::Gpipe(f(id)),
+            SeqId::NamedAnnotTrack(id) => SeqId::NamedAnnotTrack(f(id)),
+            id => id.clone(),
+        }
+    }
+
+    /// Canonical form, suitable as a key when deduplicating or indexing records
+    ///
+    /// Accessions are compared case-insensitively (see [`TextseqId::normalized()`]), as
+    /// are PDB molecule ids. Other identifiers are returned unchanged.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let mut by_id = HashMap::new();
+    /// for bioseq in set.bioseqs() {
+    ///     by_id.insert(bioseq.id[0].normalized(), bioseq);
+    /// }
+    /// ```
+    pub fn normalized(&self) -> Self {
+        match self {
+            SeqId::Pdb(id) => SeqId::Pdb(PDBSeqId {
+                mol: id.mol.trim().to_uppercase(),
+                ..id.clone()
+            }),
+            id => id.map_textseq_id(TextseqId::normalized),
+        }
+    }
+
+    /// [`Self::normalized()`] without the accession version
+    ///
+    /// All versions of a sequence compare equal in this form.
+    pub fn unversioned(&self) -> Self {
+        self.normalized().map_textseq_id(|id| TextseqId {
+            version: None,
+            ..id.clone()
+        })
+    }
+
+    /// Parse the first identifier of a FASTA defline (eg: "ref|NZ_JARQWN010000024.1|")
+    ///
+    /// A leading `>` and the description following the identifiers are ignored. See
+    /// [`parse_fasta_ids()`] for deflines which give several identifiers.
+    pub fn from_defline(defline: &str) -> Result<Self, String> {
+        parse_fasta_ids(defline)?
+            .into_iter()
+            .next()
+            .ok_or(format!("No identifier in defline: {}", defline))
+    }
+}
+
+/// Number of `|` separated fields following the tag of each FASTA identifier type
+fn fasta_field_count(tag: &str) -> Option<usize> {
+    match tag {
+        "lcl" | "bbs" | "bbm" | "gim" | "gi" => Some(1),
+        "gb" | "emb" | "pir" | "sp" | "tr" | "ref" | "dbj" | "prf" | "tpg" | "tpe" | "tpd" | "gpp" | "nat" | "gnl" | "pdb" => Some(2),
+        "pat" | "pgp" => Some(3),
+        _ => None,
+    }
+}
+
+/// Text identifier given as "accession.version" and locus name fields
+fn fasta_textseq_id(accession: &str, name: &str) -> TextseqId {
+    let (accession, version) = match accession.rsplit_once('.') {
+        Some((unversioned, version)) => match version.parse() {
+            Ok(version) => (unversioned, Some(version)),
+            Err(_) => (accession, None),
+        },
+        None => (accession, None),
+    };
+    TextseqId {
+        name: (!name.is_empty()).then(|| name.to_string()),
+        accession: (!accession.is_empty()).then(|| accession.to_string()),
+        release: None,
+        version,
+    }
+}
+
+fn fasta_int<T: std::str::FromStr>(field: &str) -> Result<T, String> {
+    field.parse().map_err(|_| format!("Invalid integer in FASTA id: {:?}", field))
+}
+
+/// Parse every identifier of a FASTA defline (eg: "gi|1234|ref|NM_000546.6|")
+///
+/// Identifiers follow the NCBI FASTA grammar, where each is given by a tag (eg: "ref",
+/// "gnl", "pdb") followed by a fixed number of `|` separated fields, and trailing empty
+/// fields may be omitted. A leading `>` and the description following the
+/// identifiers are ignored.
+///
+/// # Example
+/// ```
+/// use ncbi::seqloc::{parse_fasta_ids, SeqId};
+///
+/// let ids = parse_fasta_ids(">gi|1234|ref|NM_000546.6| TP53 mRNA").unwrap();
+/// assert_eq!(ids.len(), 2);
+/// assert_eq!(ids[0], SeqId::Gi(1234));
+/// ```
+pub fn parse_fasta_ids(defline: &str) -> Result<Vec<SeqId>, String> {
+    let token = defline
+        .trim_start()
+        .trim_start_matches('>')
+        .split_whitespace()
+        .next()
+        .unwrap_or_default();
+    let fields: Vec<&str> = token.split('|').collect();
+
+    let mut ids = Vec::new();
+    let mut i = 0;
+    while i < fields.len() {
+        let tag = fields[i];
+        // a trailing `|` closes the last identifier
+        if tag.is_empty() && i + 1 == fields.len() && !ids.is_empty() {
+            break;
+        }
+        let count = fasta_field_count(tag).ok_or(format!("Unknown FASTA id type: {:?}", tag))?;
+        let field = |n: usize| fields.get(i + 1 + n).copied().unwrap_or_default();
+
+        let id = match tag {
+            "lcl" => match field(0).parse() {
+                Ok(id) => SeqId::Local(ObjectId::Id(id)),
+                Err(_) => SeqId::Local(ObjectId::Str(field(0).into())),
+            },
+            "bbs" => SeqId::GibbSq(fasta_int(field(0))?),
+            "bbm" => SeqId::GibbMt(fasta_int(field(0))?),
+            "gim" => SeqId::Giim(GiimportId {
+                id: fasta_int(field(0))?,
+                db: None,
+                release: None,
+            }),
+            "gi" => SeqId::Gi(fasta_int(field(0))?),
+            "gnl" => SeqId::General(DbTag {
+                db: field(0).into(),
+                tag: match field(1).parse() {
+                    Ok(id) => ObjectId::Id(id),
+                    Err(_) => ObjectId::Str(field(1).into()),
+                },
+            }),
+            "pdb" => SeqId::Pdb(PDBSeqId {
+                mol: field(0).to_string(),
+                rel: None,
+                chain_id: (!field(1).is_empty()).then(|| field(1).to_string()),
+            }),
+            "pat" | "pgp" => SeqId::Patent(PatentSeqId {
+                seqid: fasta_int(field(2))?,
+                cit: IdPat {
+                    country: field(0).to_string(),
+                    id: match tag {
+                        "pat" => IdPatChoice::Number(field(1).to_string()),
+                        _ => IdPatChoice::AppNumber(field(1).to_string()),
+                    },
+                    doc_type: None,
+                },
+            }),
+            _ => {
+                let id = fasta_textseq_id(field(0), field(1));
+                match tag {
+                    "gb" => SeqId::Genbank(id),
+                    "emb" => SeqId::Embl(id),
+                    "pir" => SeqId::Pir(id),
+                    "sp" => SeqId::Swissprot(id),
+                    "tr" => SeqId::Swissprot(TextseqId {
+                        release: Some("unreviewed".to_string()),
+                        ..id
+                    }),
+                    "ref" => SeqId::Other(id),
+                    "dbj" => SeqId::Ddbj(id),
+                    "prf" => SeqId::Prf(id),
+                    "tpg" => SeqId::Tpg(id),
+                    "tpe" => SeqId::Tpe(id),
+                    "tpd" => SeqId::Tpd(id),
+                    "gpp" => SeqId::Gpipe(id),
+                    _ => SeqId::NamedAnnotTrack(id),
+                }
+            }
+        };
+        ids.push(id);
+        i += 1 + count;
+    }
+
+    if ids.is_empty() {
+        return Err(format!("No identifier in defline: {}", defline));
+    }
+    Ok(ids)
+}
+
 impl XmlNode for SeqId {
     fn start_bytes() -> BytesStart<'static> {
         BytesStart::new("Seq-id")
@@ -80,18 +300,24 @@ impl XmlNode for SeqId {
         let general_element = BytesStart::new("Seq-id_general");
         let gi_element = BytesStart::new("Seq-id_gi");
         let genbank_element = BytesStart::new("Seq-id_genbank");
+        let pdb_element = BytesStart::new("Seq-id_pdb");
+        let patent_element = BytesStart::new("Seq-id_patent");
 
         loop {
-            if let Event::Start(e) = reader.read_event().unwrap() {
-                if e.name() == other_element.name() {
-                    return SeqId::Other(read_node(reader).unwrap()).into();
+            if let Event::Start(e) = next_event(reader)? {
+                if e.name().is_tag(other_element.name()) {
+                    return SeqId::Other(read_node(reader)?).into();
                 }
-                if e.name() == general_element.name() {
-                    return SeqId::General(read_node(reader).unwrap()).into();
-                } else if e.name() == gi_element.name() {
-                    return SeqId::Gi(read_int(reader).unwrap()).into();
-                } else if e.name() == genbank_element.name() {
-                    return SeqId::Genbank(read_node(reader).unwrap()).into();
+                if e.name().is_tag(general_element.name()) {
+                    return SeqId::General(read_node(reader)?).into();
+                } else if e.name().is_tag(gi_element.name()) {
+                    return SeqId::Gi(read_int(reader)?).into();
+                } else if e.name().is_tag(genbank_element.name()) {
+                    return SeqId::Genbank(read_node(reader)?).into();
+                } else if e.name().is_tag(pdb_element.name()) {
+                    return SeqId::Pdb(read_node(reader)?).into();
+                } else if e.name().is_tag(patent_element.name()) {
+                    return SeqId::Patent(read_node(reader)?).into();
                 }
             }
         }
@@ -99,9 +325,88 @@ impl XmlNode for SeqId {
 }
 impl XmlVecNode for SeqId {}
 
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+/// Borrowed view of a [`SeqId`]
+///
+/// Only variants given by a [`TextseqIdRef`], a local or general id, or a gi are
+/// represented; other variants are not parsed.
+pub enum SeqIdRef<'a> {
+    Local(ObjectIdRef<'a>),
+    Genbank(TextseqIdRef<'a>),
+    Embl(TextseqIdRef<'a>),
+    Pir(TextseqIdRef<'a>),
+    Swissprot(TextseqIdRef<'a>),
+    Other(TextseqIdRef<'a>),
+    General(DbTagRef<'a>),
+    Gi(u64),
+    Ddbj(TextseqIdRef<'a>),
+    Prf(TextseqIdRef<'a>),
+    Tpg(TextseqIdRef<'a>),
+    Tpe(TextseqIdRef<'a>),
+    Tpd(TextseqIdRef<'a>),
+    Gpipe(TextseqIdRef<'a>),
+    NamedAnnotTrack(TextseqIdRef<'a>),
+}
+
+impl<'a> XmlNodeRef<'a> for SeqIdRef<'a> {
+    type Owned = SeqId;
+
+    fn from_reader_borrowed(reader: &mut Reader<&'a [u8]>) -> Option<Self> {
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => {
+                    let variant: fn(TextseqIdRef<'a>) -> Self =
+                        match qualified_name(e.name(), SeqId::start_bytes().name()).as_ref() {
+                            b"Seq-id_local" => return Self::Local(read_node_borrowed(reader)?).into(),
+                            b"Seq-id_general" => return Self::General(read_node_borrowed(reader)?).into(),
+                            b"Seq-id_gi" => return Self::Gi(read_int(reader)?).into(),
+                            b"Seq-id_genbank" => Self::Genbank,
+                            b"Seq-id_embl" => Self::Embl,
+                            b"Seq-id_pir" => Self::Pir,
+                            b"Seq-id_swissprot" => Self::Swissprot,
+                            b"Seq-id_other" => Self::Other,
+                            b"Seq-id_ddbj" => Self::Ddbj,
+                            b"Seq-id_prf" => Self::Prf,
+                            b"Seq-id_tpg" => Self::Tpg,
+                            b"Seq-id_tpe" => Self::Tpe,
+                            b"Seq-id_tpd" => Self::Tpd,
+                            b"Seq-id_gpipe" => Self::Gpipe,
+                            b"Seq-id_named-annot-track" => Self::NamedAnnotTrack,
+                            _ => continue,
+                        };
+                    return variant(read_node_borrowed(reader)?).into();
+                }
+                Event::End(e) if SeqId::is_end(&e) => return None,
+                _ => (),
+            }
+        }
+    }
+
+    fn to_owned_node(&self) -> SeqId {
+        match self {
+            Self::Local(id) => SeqId::Local(id.to_owned_node()),
+            Self::Genbank(id) => SeqId::Genbank(id.to_owned_node()),
+            Self::Embl(id) => SeqId::Embl(id.to_owned_node()),
+            Self::Pir(id) => SeqId::Pir(id.to_owned_node()),
+            Self::Swissprot(id) => SeqId::Swissprot(id.to_owned_node()),
+            Self::Other(id) => SeqId::Other(id.to_owned_node()),
+            Self::General(tag) => SeqId::General(tag.to_owned_node()),
+            Self::Gi(gi) => SeqId::Gi(*gi),
+            Self::Ddbj(id) => SeqId::Ddbj(id.to_owned_node()),
+            Self::Prf(id) => SeqId::Prf(id.to_owned_node()),
+            Self::Tpg(id) => SeqId::Tpg(id.to_owned_node()),
+            Self::Tpe(id) => SeqId::Tpe(id.to_owned_node()),
+            Self::Tpd(id) => SeqId::Tpd(id.to_owned_node()),
+            Self::Gpipe(id) => SeqId::Gpipe(id.to_owned_node()),
+            Self::NamedAnnotTrack(id) => SeqId::NamedAnnotTrack(id.to_owned_node()),
+        }
+    }
+}
+
 pub type SeqIdSet = Vec<SeqId>;
 
-#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct PatentSeqId {
     /// number of sequence in patent
     pub seqid: u64,
@@ -110,7 +415,40 @@ pub struct PatentSeqId {
     pub cit: IdPat,
 }
 
-#[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+impl XmlNode for PatentSeqId {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("Patent-seq-id")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> {
+        let seqid_element = BytesStart::new("Patent-seq-id_seqid");
+        let cit_element = BytesStart::new("Patent-seq-id_cit");
+
+        let mut seqid = None;
+        let mut cit = None;
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => {
+                    let name = e.name();
+
+                    if name.is_tag(seqid_element.name()) {
+                        seqid = read_int(reader);
+                    } else if name.is_tag(cit_element.name()) {
+                        cit = read_node(reader);
+                    }
+                }
+                Event::End(e) if Self::is_end(&e) => {
+                    return Self { seqid: seqid?, cit: cit? }.into();
+                }
+                _ => (),
+            }
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Debug, Default)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct TextseqId {
     pub name: Option<String>,
     pub accession: Option<String>,
@@ -118,6 +456,47 @@ pub struct TextseqId {
     pub version: Option<u64>,
 }
 
+impl TextseqId {
+    /// Canonical form for comparing identifiers
+    ///
+    /// The accession is trimmed and uppercased, since accessions are case-insensitive.
+    /// When an accession is given, `name` and `release` are dropped as they do not
+    /// identify the sequence on their own.
+    pub fn normalized(&self) -> Self {
+        let canonical = |text: &String| text.trim().to_uppercase();
+        match &self.accession {
+            Some(accession) => Self {
+                name: None,
+                accession: Some(canonical(accession)),
+                release: None,
+                version: self.version,
+            },
+            None => Self {
+                name: self.name.as_ref().map(canonical),
+                ..self.clone()
+            },
+        }
+    }
+}
+
+impl PartialOrd for TextseqId {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for TextseqId {
+    /// Order by accession and version, then by name and release
+    fn cmp(&self, other: &Self) -> Ordering {
+        (&self.accession, self.version, &self.name, &self.release).cmp(&(
+            &other.accession,
+            other.version,
+            &other.name,
+            &other.release,
+        ))
+    }
+}
+
 impl XmlNode for TextseqId {
     fn start_bytes() -> BytesStart<'static> {
         BytesStart::new("Textseq-id")
@@ -134,17 +513,17 @@ impl XmlNode for TextseqId {
         let forbidden = UnexpectedTags(&[]);
 
         loop {
-            match reader.read_event().unwrap() {
+            match next_event(reader)? {
                 Event::Start(e) => {
                     let name = e.name();
 
-                    if name == name_element.name() {
+                    if name.is_tag(name_element.name()) {
                         id.name = read_string(reader);
-                    } else if name == accession_element.name() {
+                    } else if name.is_tag(accession_element.name()) {
                         id.accession = read_string(reader);
-                    } else if name == release_element.name() {
+                    } else if name.is_tag(release_element.name()) {
                         id.release = read_string(reader);
-                    } else if name == version_element.name() {
+                    } else if name.is_tag(version_element.name()) {
                         id.version = read_int(reader);
                     } else if name != Self::start_bytes().name() {
                         forbidden.check(&name);
@@ -161,14 +540,67 @@ impl XmlNode for TextseqId {
     }
 }
 
-#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+/// Borrowed view of a [`TextseqId`]
+pub struct TextseqIdRef<'a> {
+    pub name: Option<&'a str>,
+    pub accession: Option<&'a str>,
+    pub release: Option<&'a str>,
+    pub version: Option<u64>,
+}
+
+impl<'a> XmlNodeRef<'a> for TextseqIdRef<'a> {
+    type Owned = TextseqId;
+
+    fn from_reader_borrowed(reader: &mut Reader<&'a [u8]>) -> Option<Self> {
+        let mut id = Self::default();
+
+        let name_element = BytesStart::new("Textseq-id_name");
+        let accession_element = BytesStart::new("Textseq-id_accession");
+        let release_element = BytesStart::new("Textseq-id_release");
+        let version_element = BytesStart::new("Textseq-id_version");
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => {
+                    let name = e.name();
+
+                    if name.is_tag(name_element.name()) {
+                        id.name = read_str(reader);
+                    } else if name.is_tag(accession_element.name()) {
+                        id.accession = read_str(reader);
+                    } else if name.is_tag(release_element.name()) {
+                        id.release = read_str(reader);
+                    } else if name.is_tag(version_element.name()) {
+                        id.version = read_int(reader);
+                    }
+                }
+                Event::End(e) if TextseqId::is_end(&e) => return id.into(),
+                _ => (),
+            }
+        }
+    }
+
+    fn to_owned_node(&self) -> TextseqId {
+        TextseqId {
+            name: self.name.map(str::to_string),
+            accession: self.accession.map(str::to_string),
+            release: self.release.map(str::to_string),
+            version: self.version,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct GiimportId {
     pub id: i64,
     pub db: Option<String>,
     pub release: Option<String>,
 }
 
-#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "kebab-case")]
 pub struct PDBSeqId {
     pub mol: PDBMolId,
@@ -179,7 +611,50 @@ pub struct PDBSeqId {
 /// name of mol, should be 4 chars
 pub type PDBMolId = String;
 
+impl XmlNode for PDBSeqId {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("PDB-seq-id")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> {
+        let mut mol = None;
+        let mut rel = None;
+        let mut chain_id = None;
+
+        let rel_element = BytesStart::new("PDB-seq-id_rel");
+        let chain_id_element = BytesStart::new("PDB-seq-id_chain-id");
+
+        // wraps the value of "PDB-seq-id_mol"
+        let mol_id_element = BytesStart::new("PDB-mol-id");
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => {
+                    let name = e.name();
+
+                    if name.is_tag(mol_id_element.name()) {
+                        mol = read_string(reader);
+                    } else if name.is_tag(rel_element.name()) {
+                        rel = read_node(reader);
+                    } else if name.is_tag(chain_id_element.name()) {
+                        chain_id = read_string(reader);
+                    }
+                }
+                Event::End(e) if Self::is_end(&e) => {
+                    return Self {
+                        mol: mol?,
+                        rel,
+                        chain_id,
+                    }.into();
+                }
+                _ => (),
+            }
+        }
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "kebab-case")]
 /// Defines a location on a [`BioSeq`].
 ///
@@ -221,6 +696,96 @@ impl SeqLoc {
     pub fn default() -> Self {
         Self::Null
     }
+
+    /// Smallest `(from, to)` range that covers all intervals and points
+    ///
+    /// Coordinates of all components are combined regardless of [`SeqId`].
+    /// Returns `None` for locations with no coordinates (eg: [`SeqLoc::Whole`]).
+    pub fn total_range(&self) -> Option<(i64, i64)> {
+        let merge = |ranges: Vec<Option<(i64, i64)>>| {
+            ranges
+                .into_iter()
+                .flatten()
+                .reduce(|a, b| (a.0.min(b.0), a.1.max(b.1)))
+        };
+        match self {
+            Self::Int(interval) => Some((interval.from, interval.to)),
+            Self::PackedInt(intervals) => {
+                merge(intervals.iter().map(|i| Some((i.from, i.to))).collect())
+            }
+            Self::Pnt(point) => Some((point.point, point.point)),
+            Self::PackedPnt(points) => merge(points.points.iter().map(|p| Some((*p, *p))).collect()),
+            Self::Mix(mix) => merge(mix.0.iter().map(|loc| loc.total_range()).collect()),
+            Self::Equiv(locs) => merge(locs.iter().map(|loc| loc.total_range()).collect()),
+            _ => None,
+        }
+    }
+
+    /// [`Self::total_range()`] with checked coordinates
+    ///
+    /// Returns `None` for locations with no coordinates, negative coordinates or
+    /// `from` after `to`.
+    pub fn range0(&self) -> Option<Range0> {
+        self.total_range().and_then(|(from, to)| Range0::from_i64(from, to))
+    }
+
+    /// Strand shared by all intervals and points
+    ///
+    /// An unknown strand is compatible with the plus strand, as in the C++ Toolkit,
+    /// and components on different strands give [`NaStrand::Other`]. Returns `None`
+    /// when no component gives a strand.
+    pub fn strand(&self) -> Option<NaStrand> {
+        let strands: Vec<Option<NaStrand>> = match self {
+            Self::Int(interval) => vec![interval.strand.clone()],
+            Self::PackedInt(intervals) => intervals.iter().map(|i| i.strand.clone()).collect(),
+            Self::Pnt(point) => vec![point.strand.clone()],
+            Self::PackedPnt(points) => vec![points.strand.clone()],
+            Self::Mix(mix) => mix.0.iter().map(SeqLoc::strand).collect(),
+            Self::Equiv(locs) => locs.iter().map(SeqLoc::strand).collect(),
+            _ => Vec::new(),
+        };
+        strands.into_iter().flatten().reduce(|a, b| match (a, b) {
+            (a, b) if a == b => a,
+            (NaStrand::Unknown, NaStrand::Plus) | (NaStrand::Plus, NaStrand::Unknown) => NaStrand::Plus,
+            _ => NaStrand::Other,
+        })
+    }
+
+    /// Whether the 5' end extends beyond the location, as for a coding region lacking
+    /// its start codon
+    ///
+    /// Components are taken in biological order, so that the 5' end is that of the
+    /// first component, and is given by `fuzz_to` on the minus strand.
+    pub fn is_partial_5prime(&self) -> bool {
+        match self {
+            Self::Int(interval) => interval.is_partial_5prime(),
+            Self::PackedInt(intervals) => intervals.first().is_some_and(SeqInterval::is_partial_5prime),
+            Self::Pnt(point) => is_partial(&point.fuzz),
+            Self::PackedPnt(points) => is_partial(&points.fuzz),
+            Self::Mix(mix) => mix.0.first().is_some_and(SeqLoc::is_partial_5prime),
+            _ => false,
+        }
+    }
+
+    /// Whether the 3' end extends beyond the location, as for a coding region lacking
+    /// its stop codon
+    ///
+    /// See [`Self::is_partial_5prime()`].
+    pub fn is_partial_3prime(&self) -> bool {
+        match self {
+            Self::Int(interval) => interval.is_partial_3prime(),
+            Self::PackedInt(intervals) => intervals.last().is_some_and(SeqInterval::is_partial_3prime),
+            Self::Pnt(point) => is_partial(&point.fuzz),
+            Self::PackedPnt(points) => is_partial(&points.fuzz),
+            Self::Mix(mix) => mix.0.last().is_some_and(SeqLoc::is_partial_3prime),
+            _ => false,
+        }
+    }
+}
+
+/// Whether `fuzz` marks a position as extending beyond the given one
+fn is_partial(fuzz: &Option<IntFuzz>) -> bool {
+    fuzz.as_ref().is_some_and(IntFuzz::is_partial_limit)
 }
 impl XmlNode for SeqLoc {
     fn start_bytes() -> BytesStart<'static> {
@@ -234,24 +799,23 @@ impl XmlNode for SeqLoc {
         let int_variant = BytesStart::new("Seq-loc_int");
         let mix_variant = BytesStart::new("Seq-loc_mix");
 
-        let forbidden_tags = [BytesStart::new("unknown-tag")];
-        let forbidden = UnexpectedTags(&forbidden_tags);
+        let forbidden = UnexpectedTags(&[]);
 
         loop {
-            match reader.read_event().unwrap() {
+            match next_event(reader)? {
                 Event::Start(e) => {
                     let name = e.name();
 
-                    if name == null_variant.name() {
+                    if name.is_tag(null_variant.name()) {
                         return Some(Self::Null);
-                    } else if name == empty_variant.name() {
-                        return Some(Self::Empty(read_node(reader).unwrap()));
-                    } else if name == whole_variant.name() {
-                        return Some(Self::Whole(read_node(reader).unwrap()));
-                    } else if name == int_variant.name() {
-                        return Some(Self::Int(read_node(reader).unwrap()));
-                    } else if name == mix_variant.name() {
-                        return Some(Self::Mix(read_node(reader).unwrap()));
+                    } else if name.is_tag(empty_variant.name()) {
+                        return Some(Self::Empty(read_node(reader)?));
+                    } else if name.is_tag(whole_variant.name()) {
+                        return Some(Self::Whole(read_node(reader)?));
+                    } else if name.is_tag(int_variant.name()) {
+                        return Some(Self::Int(read_node(reader)?));
+                    } else if name.is_tag(mix_variant.name()) {
+                        return Some(Self::Mix(read_node(reader)?));
                     } else if name != Self::start_bytes().name() {
                         forbidden.check(&name);
                     }
@@ -269,6 +833,7 @@ impl XmlNode for SeqLoc {
 }
 
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "kebab-case")]
 pub struct SeqInterval {
     pub from: i64,
@@ -292,6 +857,29 @@ impl Default for SeqInterval {
     }
 }
 
+impl SeqInterval {
+    /// Checked range from `from` to `to`, see [`Range0::from_i64()`]
+    pub fn range0(&self) -> Option<Range0> {
+        Range0::from_i64(self.from, self.to)
+    }
+
+    /// Whether the 5' end is partial, given by `fuzz_to` on the minus strand
+    pub fn is_partial_5prime(&self) -> bool {
+        match self.strand {
+            Some(NaStrand::Minus) => is_partial(&self.fuzz_to),
+            _ => is_partial(&self.fuzz_from),
+        }
+    }
+
+    /// Whether the 3' end is partial, given by `fuzz_from` on the minus strand
+    pub fn is_partial_3prime(&self) -> bool {
+        match self.strand {
+            Some(NaStrand::Minus) => is_partial(&self.fuzz_from),
+            _ => is_partial(&self.fuzz_to),
+        }
+    }
+}
+
 impl XmlNode for SeqInterval {
     fn start_bytes() -> BytesStart<'static> {
         BytesStart::new("Seq-interval")
@@ -306,18 +894,24 @@ impl XmlNode for SeqInterval {
         // this tag is skipped, and `Empty` tag for `NaStrand` is used instead
         let _strand_element = BytesStart::new("Seq-interval_strand");
         let id_element = BytesStart::new("Seq-interval_id");
+        let fuzz_from_element = BytesStart::new("Seq-interval_fuzz-from");
+        let fuzz_to_element = BytesStart::new("Seq-interval_fuzz-to");
 
         loop {
-            match reader.read_event().unwrap() {
+            match next_event(reader)? {
                 Event::Start(e) => {
                     let name = e.name();
 
-                    if name == from_element.name() {
-                        interval.from = read_int(reader).unwrap();
-                    } else if name == to_element.name() {
-                        interval.to = read_int(reader).unwrap();
-                    } else if name == id_element.name() {
-                        interval.id = read_node(reader).unwrap();
+                    if name.is_tag(from_element.name()) {
+                        interval.from = read_int(reader)?;
+                    } else if name.is_tag(to_element.name()) {
+                        interval.to = read_int(reader)?;
+                    } else if name.is_tag(id_element.name()) {
+                        interval.id = read_node(reader)?;
+                    } else if name.is_tag(fuzz_from_element.name()) {
+                        interval.fuzz_from = read_node(reader);
+                    } else if name.is_tag(fuzz_to_element.name()) {
+                        interval.fuzz_to = read_node(reader);
                     }
                 }
                 Event::Empty(e) => {
@@ -339,6 +933,7 @@ impl XmlNode for SeqInterval {
 pub type PackedSeqInt = Vec<SeqInterval>;
 
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct SeqPoint {
     pub point: i64,
     pub strand: Option<NaStrand>,
@@ -346,7 +941,15 @@ pub struct SeqPoint {
     pub fuzz: Option<IntFuzz>,
 }
 
+impl SeqPoint {
+    /// Checked position, unless negative
+    pub fn pos0(&self) -> Option<Pos0> {
+        Pos0::try_from(self.point).ok()
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "kebab-case")]
 pub struct PackedSeqPnt {
     pub strand: Option<NaStrand>,
@@ -356,6 +959,7 @@ pub struct PackedSeqPnt {
 }
 
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 /// Strand of nucleic acid
 pub enum NaStrand {
     Unknown,
@@ -390,6 +994,7 @@ impl XmlValue for NaStrand {
 }
 
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 /// bond between residues
 pub struct SeqBond {
     /// connection to at least one residue
@@ -400,6 +1005,7 @@ pub struct SeqBond {
 }
 
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct SeqLocMix(pub Vec<SeqLoc>);
 
 impl XmlNode for SeqLocMix {
@@ -415,3 +1021,163 @@ impl XmlNode for SeqLocMix {
 
 /// set of equivalent locations
 pub type SeqLocEquiv = Vec<SeqLoc>;
+
+#[cfg(test)]
+mod tests {
+    use crate::general::{DbTag, FuzzLimit, IntFuzz, ObjectId};
+    use crate::seqloc::{parse_fasta_ids, NaStrand, PDBSeqId, SeqId, SeqInterval, SeqLoc, SeqLocMix, TextseqId};
+    use std::collections::HashSet;
+
+    fn accession(accession: &str, version: Option<u64>) -> TextseqId {
+        TextseqId {
+            accession: Some(accession.to_string()),
+            version,
+            ..TextseqId::default()
+        }
+    }
+
+    #[test]
+    fn test_normalized_seq_id() {
+        let id = SeqId::Other(TextseqId {
+            name: Some("tp73".to_string()),
+            ..accession(" nm_005427 ", Some(4))
+        });
+        assert_eq!(id.normalized(), SeqId::Other(accession("NM_005427", Some(4))));
+        assert_eq!(id.unversioned(), SeqId::Other(accession("NM_005427", None)));
+
+        let ids: HashSet<SeqId> = [
+            SeqId::Other(accession("NM_005427", Some(4))),
+            SeqId::Other(accession("nm_005427", Some(4))),
+            SeqId::Other(accession("NM_005427", Some(3))),
+        ]
+        .iter()
+        .map(SeqId::normalized)
+        .collect();
+        assert_eq!(ids.len(), 2);
+
+        let ids: HashSet<SeqId> = ids.iter().map(SeqId::unversioned).collect();
+        assert_eq!(ids.len(), 1);
+
+        // identifiers without a text id are unchanged
+        assert_eq!(SeqId::Gi(1).normalized(), SeqId::Gi(1));
+    }
+
+    #[test]
+    fn test_from_defline() {
+        assert_eq!(
+            SeqId::from_defline("ref|NZ_JARQWN010000024.1|"),
+            Ok(SeqId::Other(accession("NZ_JARQWN010000024", Some(1))))
+        );
+        assert_eq!(
+            SeqId::from_defline(">sp|P04637|P53_HUMAN Cellular tumor antigen p53"),
+            Ok(SeqId::Swissprot(TextseqId {
+                name: Some("P53_HUMAN".to_string()),
+                ..accession("P04637", None)
+            }))
+        );
+        assert_eq!(SeqId::from_defline("lcl|contig_1"), Ok(SeqId::Local(ObjectId::Str("contig_1".into()))));
+        assert_eq!(
+            SeqId::from_defline("gnl|SRA|SRR390728.1"),
+            Ok(SeqId::General(DbTag {
+                db: "SRA".into(),
+                tag: ObjectId::Str("SRR390728.1".into()),
+            }))
+        );
+        assert_eq!(
+            SeqId::from_defline("pdb|1TUP|A"),
+            Ok(SeqId::Pdb(PDBSeqId {
+                mol: "1TUP".to_string(),
+                rel: None,
+                chain_id: Some("A".to_string()),
+            }))
+        );
+        assert!(SeqId::from_defline("NM_000546.6").is_err());
+        assert!(SeqId::from_defline("gi|abc").is_err());
+        assert!(SeqId::from_defline("").is_err());
+    }
+
+    #[test]
+    fn test_parse_fasta_ids() {
+        let ids = parse_fasta_ids("gi|1234|gb|AAA12345.2|AAA12345 gnl|ignored").unwrap();
+        assert_eq!(ids, vec![SeqId::Gi(1234), SeqId::Genbank(TextseqId {
+            name: Some("AAA12345".to_string()),
+            ..accession("AAA12345", Some(2))
+        })]);
+
+        let ids = parse_fasta_ids("gi|1234|ref|NM_000546.6|").unwrap();
+        assert_eq!(ids, vec![SeqId::Gi(1234), SeqId::Other(accession("NM_000546", Some(6)))]);
+
+        let ids = parse_fasta_ids("pat|US|RE33188|1|prf||0806162C").unwrap();
+        assert_eq!(ids.len(), 2);
+        assert!(matches!(&ids[0], SeqId::Patent(id) if id.seqid == 1 && id.cit.country == "US"));
+        assert_eq!(ids[1], SeqId::Prf(TextseqId {
+            name: Some("0806162C".to_string()),
+            ..TextseqId::default()
+        }));
+        assert!(parse_fasta_ids("gi|1234|xyz|1").is_err());
+    }
+
+    #[test]
+    fn test_seq_id_order() {
+        let mut ids = vec![
+            SeqId::Genbank(accession("B", None)),
+            SeqId::Gi(7),
+            SeqId::Other(accession("NM_2", Some(1))),
+            SeqId::Genbank(accession("A", Some(2))),
+            SeqId::Local(ObjectId::Id(1)),
+            SeqId::Genbank(accession("A", Some(1))),
+            SeqId::General(DbTag {
+                db: "taxon".into(),
+                tag: ObjectId::Id(9606),
+            }),
+        ];
+        ids.sort();
+
+        assert_eq!(ids[0], SeqId::Local(ObjectId::Id(1)));
+        assert_eq!(ids[1], SeqId::Genbank(accession("A", Some(1))));
+        assert_eq!(ids[2], SeqId::Genbank(accession("A", Some(2))));
+        assert_eq!(ids[3], SeqId::Genbank(accession("B", None)));
+        assert!(matches!(ids[4], SeqId::Other(_)));
+        assert!(matches!(ids[5], SeqId::General(_)));
+        assert_eq!(ids[6], SeqId::Gi(7));
+    }
+
+    fn interval(strand: NaStrand, fuzz_from: Option<FuzzLimit>, fuzz_to: Option<FuzzLimit>) -> SeqLoc {
+        SeqLoc::Int(SeqInterval {
+            strand: Some(strand),
+            fuzz_from: fuzz_from.map(IntFuzz::Lim),
+            fuzz_to: fuzz_to.map(IntFuzz::Lim),
+            ..SeqInterval::default()
+        })
+    }
+
+    #[test]
+    fn test_strand() {
+        let plus = interval(NaStrand::Plus, None, None);
+        let minus = interval(NaStrand::Minus, None, None);
+        let unknown = interval(NaStrand::Unknown, None, None);
+
+        assert_eq!(plus.strand(), Some(NaStrand::Plus));
+        assert_eq!(SeqLoc::Mix(SeqLocMix(vec![minus.clone(), minus.clone()])).strand(), Some(NaStrand::Minus));
+        assert_eq!(SeqLoc::Mix(SeqLocMix(vec![unknown, plus.clone()])).strand(), Some(NaStrand::Plus));
+        assert_eq!(SeqLoc::Mix(SeqLocMix(vec![plus, minus])).strand(), Some(NaStrand::Other));
+        assert_eq!(SeqLoc::Whole(SeqId::Gi(1)).strand(), None);
+    }
+
+    #[test]
+    fn test_partial_ends() {
+        let loc = interval(NaStrand::Plus, Some(FuzzLimit::LT), None);
+        assert!(loc.is_partial_5prime() && !loc.is_partial_3prime());
+
+        // on the minus strand, the 5' end is the greatest position
+        let loc = interval(NaStrand::Minus, Some(FuzzLimit::LT), None);
+        assert!(!loc.is_partial_5prime() && loc.is_partial_3prime());
+
+        // ends of a mix are those of its first and last components
+        let loc = SeqLoc::Mix(SeqLocMix(vec![
+            interval(NaStrand::Minus, None, Some(FuzzLimit::GT)),
+            interval(NaStrand::Minus, None, None),
+        ]));
+        assert!(loc.is_partial_5prime() && !loc.is_partial_3prime());
+    }
+}
@@ -4,13 +4,22 @@
 //! from the NCBI C++ Toolkit
 
 use crate::general::{Date, DbTag, ObjectId};
-use crate::parsing::{read_vec_node, read_node, UnexpectedTags};
-use crate::seq::{BioSeq, SeqAnnot, SeqDescr};
-use crate::parsing::{XmlNode, XmlVecNode};
+#[cfg(all(feature = "rayon", not(target_arch = "wasm32")))]
+use crate::parsing::current_options;
+use crate::parsing::{locate_error, report_at, take_reports, with_locations};
+use crate::parsing::{next_event, attribute_value, parse_node, read_attributes, read_int, read_string, read_vec_node, read_node, UnexpectedTags};
+use crate::seq::{BioSeq, Descriptors, SeqAnnot, SeqAnnotData, SeqDesc, SeqDescr};
+use crate::seqalign::{DenseSeg, SeqAlign, SeqAlignSegs};
+use crate::seqfeat::{BioSource, OrgMod, SubSource};
+use crate::seqloc::{NaStrand, SeqId};
+use crate::seqtools::reverse_complement;
+use crate::parsing::{TagMatch, XmlNode, XmlValue, XmlVecNode};
+use quick_xml::events::attributes::Attributes;
 use quick_xml::events::{BytesStart, Event};
 use quick_xml::Reader;
 use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
+use std::ops::Range;
 
 #[derive(Clone, Serialize_repr, Deserialize_repr, PartialEq, Debug, Default)]
 #[repr(u8)]
@@ -91,6 +100,75 @@ pub enum BioSeqSetClass {
     Other = 255,
 }
 
+asn_names!(BioSeqSetClass {
+    NotSet => "not-set",
+    NucProt => "nuc-prot",
+    SegSet => "segset",
+    ConSet => "conset",
+    Parts => "parts",
+    Gibb => "gibb",
+    Gi => "gi",
+    Genbank => "genbank",
+    Pir => "pir",
+    PubSet => "pub-set",
+    Equiv => "equiv",
+    Swissprot => "swissprot",
+    PdbEntry => "pdb-entry",
+    MutSet => "mut-set",
+    PopSet => "pop-set",
+    PhySet => "phy-set",
+    EcoSet => "eco-set",
+    GenProdSet => "gen-prod-set",
+    WgsSet => "wgs-set",
+    NamedAnnot => "named-annot",
+    NamedAnnotProd => "named-annot-prod",
+    ReadSet => "read-set",
+    PairedEndReads => "paired-end-reads",
+    SmallGenomeSet => "small-genome-set",
+    Other => "other",
+});
+
+impl XmlValue for BioSeqSetClass {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("Bioseq-set_class")
+    }
+
+    fn from_attributes(attributes: Attributes) -> Option<Self> {
+        if let Some(attributes) = attribute_value(attributes) {
+            match attributes.as_str() {
+                "not-set" => Self::NotSet.into(),
+                "nuc-prot" => Self::NucProt.into(),
+                "segset" => Self::SegSet.into(),
+                "conset" => Self::ConSet.into(),
+                "parts" => Self::Parts.into(),
+                "gibb" => Self::Gibb.into(),
+                "gi" => Self::Gi.into(),
+                "genbank" => Self::Genbank.into(),
+                "pir" => Self::Pir.into(),
+                "pub-set" => Self::PubSet.into(),
+                "equiv" => Self::Equiv.into(),
+                "swissprot" => Self::Swissprot.into(),
+                "pdb-entry" => Self::PdbEntry.into(),
+                "mut-set" => Self::MutSet.into(),
+                "pop-set" => Self::PopSet.into(),
+                "phy-set" => Self::PhySet.into(),
+                "eco-set" => Self::EcoSet.into(),
+                "gen-prod-set" => Self::GenProdSet.into(),
+                "wgs-set" => Self::WgsSet.into(),
+                "named-annot" => Self::NamedAnnot.into(),
+                "named-annot-prod" => Self::NamedAnnotProd.into(),
+                "read-set" => Self::ReadSet.into(),
+                "paired-end-reads" => Self::PairedEndReads.into(),
+                "small-genome-set" => Self::SmallGenomeSet.into(),
+                "other" => Self::Other.into(),
+                _ => None
+            }
+        } else {
+            None
+        }
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
 /// just a collection
 pub struct BioSeqSet {
@@ -116,23 +194,47 @@ impl XmlNode for BioSeqSet {
     }
 
     fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> {
+        let id_element = BytesStart::new("Bioseq-set_id");
+        let coll_element = BytesStart::new("Bioseq-set_coll");
+        let level_element = BytesStart::new("Bioseq-set_level");
+        let release_element = BytesStart::new("Bioseq-set_release");
+        let date_element = BytesStart::new("Bioseq-set_date");
+        let descr_element = BytesStart::new("Bioseq-set_descr");
         let seq_set_element = BytesStart::new("Bioseq-set_seq-set");
+        let annot_element = BytesStart::new("Bioseq-set_annot");
 
         let mut set = Self::default();
 
         let forbidden = UnexpectedTags(&[]);
 
         loop {
-            match reader.read_event().unwrap() {
+            match next_event(reader)? {
                 Event::Start(e) => {
                     let name = e.name();
 
-                    if name == seq_set_element.name() {
-                        set.seq_set = read_vec_node(reader, seq_set_element.to_end());
+                    if name.is_tag(id_element.name()) {
+                        set.id = read_node(reader);
+                    } else if name.is_tag(coll_element.name()) {
+                        set.coll = read_node(reader);
+                    } else if name.is_tag(level_element.name()) {
+                        set.level = read_int(reader);
+                    } else if name.is_tag(release_element.name()) {
+                        set.release = read_string(reader);
+                    } else if name.is_tag(date_element.name()) {
+                        set.date = read_node(reader);
+                    } else if name.is_tag(seq_set_element.name()) {
+                        set.seq_set = read_vec_node(reader, e.to_end());
+                    } else if name.is_tag(descr_element.name()) {
+                        set.descr = read_node(reader);
+                    } else if name.is_tag(annot_element.name()) {
+                        set.annot = Some(read_vec_node(reader, e.to_end()));
                     } else if name != Self::start_bytes().name() {
                         forbidden.check(&name);
                     }
                 }
+                Event::Empty(e) if e.name().is_tag(BioSeqSetClass::start_bytes().name()) => {
+                    set.class = read_attributes(&e).unwrap_or_default();
+                }
                 Event::End(e) => {
                     if e.name() == Self::start_bytes().to_end().name() {
                         return set.into();
@@ -144,6 +246,331 @@ impl XmlNode for BioSeqSet {
     }
 }
 
+impl Descriptors for BioSeqSet {
+    fn descriptors(&self) -> &[SeqDesc] {
+        self.descr.as_deref().unwrap_or(&[])
+    }
+}
+
+impl BioSeqSet {
+    /// Parse the first `Bioseq-set` of `xml`, parsing its entries on multiple threads
+    ///
+    /// The input is split at the boundaries of the top-level `Seq-entry` elements of
+    /// the set. Entries are parsed concurrently on a [rayon](https://docs.rs/rayon)
+    /// pool of `threads` threads, and recombined in document order; the remaining
+    /// fields of the set are parsed separately. A `threads` of 0 uses the global pool
+    /// of rayon, which has a thread per core by default. [`crate::parsing::ParseOptions`] in effect for the
+    /// calling thread are passed on to each worker.
+    ///
+    /// Entries are parsed serially without the `rayon` feature, and on
+    /// `wasm32-unknown-unknown` where threads cannot be spawned.
+    ///
+    /// # Performance
+    ///
+    /// Locating entry boundaries is an extra sequential pass over the input, so a
+    /// parallel parse only pays off with at least two cores available, and gains little
+    /// for sets with few, large entries (eg: a single chromosome). Use the `parallel`
+    /// case of `cargo bench --bench parsing --features rayon` to compare both on a
+    /// given machine.
+    ///
+    /// Measured on a single core, where only the overhead shows:
+    ///
+    /// | input                                  | serial    | parallel  |
+    /// |----------------------------------------|-----------|-----------|
+    /// | WGS record (`2519734237.xml`, 1.6 MiB) | 207 MiB/s | 149 MiB/s |
+    /// | synthetic set, 16 MiB                  | 234 MiB/s | 146 MiB/s |
+    ///
+    /// The speedup on multiple cores has not been measured yet.
+    pub fn from_str_parallel(xml: &str, threads: usize) -> Result<Self, String> {
+        with_locations(xml, || Self::parse_parallel(xml, threads))
+    }
+
+    fn parse_parallel(xml: &str, threads: usize) -> Result<Self, String> {
+        let (content, spans) = split_entries(xml)?;
+
+        // parse everything except for the entries themselves
+        let skeleton = format!("{}{}", &xml[..content.start], &xml[content.end..]);
+        let (set, reports) = take_reports(|| parse_node::<Self>(&skeleton));
+        for (offset, message) in reports {
+            let offset = if offset < content.start { offset } else { offset + content.len() };
+            report_at(offset, message);
+        }
+        let mut set = set.ok_or_else(|| locate_error(xml, 0, "Failed to parse Bioseq-set"))?;
+
+        // diagnostics are taken from the worker, and reported at their offset in `xml`
+        type ParsedEntry = (Result<SeqEntry, String>, Vec<(usize, String)>);
+        let parse_entry = |span: &Range<usize>| {
+            let (entry, reports) = take_reports(|| parse_node::<SeqEntry>(&xml[span.clone()]));
+            let entry = entry.ok_or_else(|| locate_error(xml, span.start, "Failed to parse Seq-entry"));
+            (entry, reports)
+        };
+
+        #[cfg(all(feature = "rayon", not(target_arch = "wasm32")))]
+        let entries: Vec<ParsedEntry> = {
+            use rayon::prelude::*;
+
+            let options = current_options();
+            let parse = || {
+                spans
+                    .par_iter()
+                    .map(|span| match &options {
+                        Some(options) => options.apply(|| parse_entry(span)),
+                        None => parse_entry(span),
+                    })
+                    .collect()
+            };
+            match threads {
+                0 => parse(),
+                n => rayon::ThreadPoolBuilder::new()
+                    .num_threads(n)
+                    .build()
+                    .map_err(|e| e.to_string())?
+                    .install(parse),
+            }
+        };
+        #[cfg(not(all(feature = "rayon", not(target_arch = "wasm32"))))]
+        let entries: Vec<ParsedEntry> = {
+            let _ = threads;
+            spans.iter().map(parse_entry).collect()
+        };
+
+        let mut seq_set = Vec::with_capacity(entries.len());
+        for ((entry, reports), span) in entries.into_iter().zip(spans.iter()) {
+            for (offset, message) in reports {
+                report_at(span.start + offset, message);
+            }
+            seq_set.push(entry?);
+        }
+        set.seq_set = seq_set;
+        Ok(set)
+    }
+
+    /// XML of each top-level `Seq-entry` of the first `Bioseq-set` of `xml`
+    ///
+    /// Entries may then be parsed one at a time, or saved as they were received.
+    pub fn entries_xml(xml: &str) -> Result<Vec<&str>, String> {
+        let (_, spans) = split_entries(xml)?;
+        Ok(spans.into_iter().map(|span| &xml[span]).collect())
+    }
+
+    /// All [`BioSeq`]'s contained by this set and any nested sets
+    pub fn bioseqs(&self) -> Vec<&BioSeq> {
+        let mut bioseqs = Vec::new();
+        for entry in self.seq_set.iter() {
+            match entry {
+                SeqEntry::Seq(bioseq) => bioseqs.push(bioseq),
+                SeqEntry::Set(set) => bioseqs.extend(set.bioseqs()),
+            }
+        }
+        bioseqs
+    }
+
+    /// Nucleotide and protein products of this set, if it is a
+    /// [`BioSeqSetClass::NucProt`] set
+    ///
+    /// The nucleotide is the first nucleic acid of the set, or the segmented sequence
+    /// of a nested [`BioSeqSetClass::SegSet`] set. Returns `None` if the set has no
+    /// nucleic acid.
+    pub fn as_nuc_prot(&self) -> Option<NucProt<'_>> {
+        if self.class != BioSeqSetClass::NucProt {
+            return None;
+        }
+        let nucleotide = self.seq_set.iter().find_map(|entry| match entry {
+            SeqEntry::Seq(bioseq) if !bioseq.is_protein() => Some(bioseq),
+            SeqEntry::Set(set) => set.as_seg_set().map(|segset| segset.master),
+            _ => None,
+        })?;
+        let proteins = self
+            .seq_set
+            .iter()
+            .filter_map(|entry| match entry {
+                SeqEntry::Seq(bioseq) if bioseq.is_protein() => Some(bioseq),
+                _ => None,
+            })
+            .collect();
+        Some(NucProt {
+            nucleotide,
+            proteins,
+        })
+    }
+
+    /// Segmented sequence and its parts, if this is a [`BioSeqSetClass::SegSet`] set
+    pub fn as_seg_set(&self) -> Option<SegSet<'_>> {
+        if self.class != BioSeqSetClass::SegSet {
+            return None;
+        }
+        let master = self.seq_set.iter().find_map(|entry| match entry {
+            SeqEntry::Seq(bioseq) => Some(bioseq),
+            _ => None,
+        })?;
+        let parts = self
+            .seq_set
+            .iter()
+            .filter_map(|entry| match entry {
+                SeqEntry::Set(set) if set.class == BioSeqSetClass::Parts => Some(set.bioseqs()),
+                _ => None,
+            })
+            .flatten()
+            .collect();
+        Some(SegSet { master, parts })
+    }
+
+    /// All [`BioSeqSetClass::NucProt`] sets within this set, including itself
+    ///
+    /// Records fetched from the protein or nucleotide databases are commonly given as
+    /// nuc-prot sets, wrapped by a set which is not classified.
+    pub fn nuc_prot_sets(&self) -> Vec<NucProt<'_>> {
+        let mut sets: Vec<NucProt> = self.as_nuc_prot().into_iter().collect();
+        for entry in self.seq_set.iter() {
+            if let SeqEntry::Set(set) = entry {
+                sets.extend(set.nuc_prot_sets());
+            }
+        }
+        sets
+    }
+
+    /// Find a contained [`BioSeq`] by any of its identifiers
+    pub fn find_bioseq(&self, id: &SeqId) -> Option<&BioSeq> {
+        self.bioseqs().into_iter().find(|bioseq| bioseq.id.contains(id))
+    }
+
+    /// Alignments annotated on this set and any nested sets
+    pub fn alignments(&self) -> Vec<&SeqAlign> {
+        let mut alignments = Vec::new();
+        for annot in self.annot.iter().flatten() {
+            if let SeqAnnotData::Align(aligns) = &annot.data {
+                alignments.extend(aligns.iter());
+            }
+        }
+        for entry in self.seq_set.iter() {
+            if let SeqEntry::Set(set) = entry {
+                alignments.extend(set.alignments());
+            }
+        }
+        alignments
+    }
+
+    /// Each row of each [`DenseSeg`] alignment, linked to the [`BioSeq`] within this set
+    ///
+    /// Intended for population and phylogenetic study sets
+    /// ([`BioSeqSetClass::PopSet`], [`BioSeqSetClass::PhySet`]) where member sequences
+    /// are aligned to each other.
+    pub fn aligned_seqs(&self) -> Vec<AlignedSeq<'_>> {
+        fn collect<'a>(set: &'a BioSeqSet, align: &'a SeqAlign, rows: &mut Vec<AlignedSeq<'a>>) {
+            match &align.segs {
+                SeqAlignSegs::DenSeg(denseg) => {
+                    for (row, id) in denseg.ids.iter().enumerate() {
+                        rows.push(AlignedSeq {
+                            id,
+                            row,
+                            denseg,
+                            bioseq: set.find_bioseq(id),
+                        });
+                    }
+                }
+                SeqAlignSegs::Disc(aligns) => {
+                    for align in aligns.iter() {
+                        collect(set, align, rows);
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        let mut rows = Vec::new();
+        for align in self.alignments() {
+            collect(self, align, &mut rows);
+        }
+        rows
+    }
+}
+
+/// Single row of an alignment within a [`BioSeqSet`]
+///
+/// Returned by [`BioSeqSet::aligned_seqs()`]
+#[derive(Clone, Debug)]
+pub struct AlignedSeq<'a> {
+    pub id: &'a SeqId,
+    /// row index within [`Self::denseg`]
+    pub row: usize,
+    pub denseg: &'a DenseSeg,
+    /// aligned sequence, if contained by the set
+    pub bioseq: Option<&'a BioSeq>,
+}
+
+impl<'a> AlignedSeq<'a> {
+    pub fn source(&self) -> Option<&'a BioSource> {
+        self.bioseq?.source()
+    }
+
+    pub fn taxname(&self) -> Option<&'a str> {
+        self.source()?.org.taxname.as_deref()
+    }
+
+    /// Organism modifiers (eg: strain, isolate) of the aligned sequence
+    pub fn org_mods(&self) -> &'a [OrgMod] {
+        self.source()
+            .and_then(|source| source.org.orgname.as_ref())
+            .and_then(|orgname| orgname.r#mod.as_deref())
+            .unwrap_or(&[])
+    }
+
+    /// Source modifiers (eg: country, haplotype) of the aligned sequence
+    pub fn subsources(&self) -> &'a [SubSource] {
+        self.source()
+            .and_then(|source| source.subtype.as_deref())
+            .unwrap_or(&[])
+    }
+
+    /// Aligned residues with `-` denoting gaps
+    ///
+    /// Requires sequence data for [`Self::bioseq`]
+    pub fn gapped(&self) -> Result<String, String> {
+        let bioseq = self.bioseq.ok_or("Aligned sequence is not contained by set")?;
+        let residues = match &bioseq.inst {
+            Some(inst) => inst.residues()?,
+            None => return Err("Bioseq has no instance data".to_string()),
+        };
+        let minus = matches!(self.denseg.strand(self.row), Some(NaStrand::Minus));
+
+        let mut gapped = String::new();
+        for (seg, len) in self.denseg.lens.iter().enumerate() {
+            let len = *len as usize;
+            match self.denseg.start(self.row, seg) {
+                Some(start) => {
+                    let start = start as usize;
+                    let piece = residues
+                        .get(start..start + len)
+                        .ok_or("Alignment segment is out of bounds")?;
+                    if minus {
+                        gapped.push_str(&reverse_complement(piece));
+                    } else {
+                        gapped.push_str(piece);
+                    }
+                }
+                None => gapped.push_str(&"-".repeat(len)),
+            }
+        }
+        Ok(gapped)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+/// View of a [`BioSeqSetClass::NucProt`] set, given by [`BioSeqSet::as_nuc_prot()`]
+pub struct NucProt<'a> {
+    /// genomic sequence or mRNA coding for the proteins
+    pub nucleotide: &'a BioSeq,
+    pub proteins: Vec<&'a BioSeq>,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+/// View of a [`BioSeqSetClass::SegSet`] set, given by [`BioSeqSet::as_seg_set()`]
+pub struct SegSet<'a> {
+    /// segmented sequence, whose segments refer to the parts
+    pub master: &'a BioSeq,
+    pub parts: Vec<&'a BioSeq>,
+}
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 pub enum SeqEntry {
     Seq(BioSeq),
@@ -159,15 +586,15 @@ impl XmlNode for SeqEntry {
         let set = BytesStart::new("Seq-entry_set");
 
         loop {
-            match reader.read_event().unwrap() {
+            match next_event(reader)? {
                 Event::Start(e) => {
                     let name = e.name();
 
-                    if name == seq.name() {
-                        return Self::Seq(read_node(reader).unwrap()).into();
+                    if name.is_tag(seq.name()) {
+                        return Self::Seq(read_node(reader)?).into();
                     }
-                    if name == set.name() {
-                        return Self::Set(read_node(reader).unwrap()).into();
+                    if name.is_tag(set.name()) {
+                        return Self::Set(read_node(reader)?).into();
                     }
                 }
                 Event::End(e) => {
@@ -182,3 +609,60 @@ impl XmlNode for SeqEntry {
     }
 }
 impl XmlVecNode for SeqEntry {}
+
+/// Locate the content of the `Bioseq-set_seq-set` of the first `Bioseq-set`, and the
+/// byte ranges of each `Seq-entry` it directly contains
+///
+/// Errors are located at the position in `xml` where reading stopped.
+fn split_entries(xml: &str) -> Result<(Range<usize>, Vec<Range<usize>>), String> {
+    let mut reader = Reader::from_str(xml);
+    reader.trim_text(true);
+    find_entries(&mut reader).map_err(|e| locate_error(xml, reader.buffer_position(), &e))
+}
+
+fn find_entries(reader: &mut Reader<&[u8]>) -> Result<(Range<usize>, Vec<Range<usize>>), String> {
+    let error = |e: quick_xml::Error| format!("XML parsing error: {:?}", e);
+
+    // find the set
+    loop {
+        match reader.read_event().map_err(error)? {
+            Event::Start(e) if e.name().is_tag(BioSeqSet::start_bytes().name()) => break,
+            Event::Eof => return Err("No Bioseq-set found".to_string()),
+            _ => (),
+        }
+    }
+
+    // find its entries, skipping other fields
+    let seq_set = BytesStart::new("Bioseq-set_seq-set");
+    loop {
+        match reader.read_event().map_err(error)? {
+            Event::Start(e) if e.name().is_tag(seq_set.name()) => break,
+            Event::Empty(e) if e.name().is_tag(seq_set.name()) => {
+                let position = reader.buffer_position();
+                return Ok((position..position, Vec::new()));
+            }
+            Event::Start(e) => {
+                reader.read_to_end(e.name()).map_err(error)?;
+            }
+            Event::End(_) | Event::Eof => return Err("Bioseq-set has no entries".to_string()),
+            _ => (),
+        }
+    }
+
+    let start = reader.buffer_position();
+    let mut spans = Vec::new();
+    loop {
+        let position = reader.buffer_position();
+        match reader.read_event().map_err(error)? {
+            Event::Start(e) => {
+                reader.read_to_end(e.name()).map_err(error)?;
+                if e.name().is_tag(SeqEntry::start_bytes().name()) {
+                    spans.push(position..reader.buffer_position());
+                }
+            }
+            Event::End(_) => return Ok((start..position, spans)),
+            Event::Eof => return Err("Unexpected end of Bioseq-set".to_string()),
+            _ => (),
+        }
+    }
+}
@@ -52,6 +52,41 @@ pub enum ColumnInfoFieldId {
     DbxrefTag,
 }
 
+asn_names!(ColumnInfoFieldId {
+    Location => "location",
+    LocationId => "location-id",
+    LocationGi => "location-gi",
+    LocationFrom => "location-from",
+    LocationTo => "location-to",
+    LocationStrand => "location-strand",
+    LocationFuzzFromLim => "location-fuzz-from-lim",
+    LocationFuzzToLim => "location-fuzz-to-lim",
+    Product => "product",
+    ProductId => "product-id",
+    ProductGi => "product-gi",
+    ProductFrom => "product-from",
+    ProductTo => "product-to",
+    ProductStrand => "product-strand",
+    ProductFuzzFromLim => "product-fuzz-from-lim",
+    ProductFuzzToLim => "product-fuzz-to-lim",
+    IdLocal => "id-local",
+    XrefIdLocal => "xref-id-local",
+    Partial => "partial",
+    Comment => "comment",
+    Title => "title",
+    Ext => "ext",
+    Qual => "qual",
+    DbXref => "dbxref",
+    DataImpKey => "data-imp-key",
+    DataRegion => "data-region",
+    DataCdregionFrame => "data-cdregion-frame",
+    ExtType => "ext-type",
+    QualQual => "qual-qual",
+    QualVal => "qual-val",
+    DbxrefDb => "dbxref-db",
+    DbxrefTag => "dbxref-tag",
+});
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 #[serde(rename_all = "kebab-case")]
 /// Unsure on how this object is used
@@ -0,0 +1,393 @@
+//! NCBI Sequence Submission
+//!
+//! Adapted from ["submit.asn"](https://www.ncbi.nlm.nih.gov/IEB/ToolBox/CPP_DOC/lxr/source/src/objects/submit/submit.asn)
+//! from the NCBI C++ Toolkit
+//!
+//! A [`SeqSubmit`] is the unit of a direct submission to GenBank. It wraps the
+//! submitted records with a [`SubmitBlock`] describing who is submitting them, and
+//! the [`CitSub`] that will be attached to every record once it is processed.
+
+use crate::biblio::{Author, CitSub};
+use crate::general::{Date, ObjectId};
+use crate::parsing::{next_event, qualified_name, read_bool_attribute, read_int, read_node, read_string, read_vec_node, read_vec_str_unchecked, UnexpectedTags};
+use crate::parsing::{TagMatch, XmlNode, XmlVecNode};
+use crate::seq::SeqAnnot;
+use crate::seqloc::SeqId;
+use crate::seqset::SeqEntry;
+use enum_primitive::FromPrimitive;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use serde::{Deserialize, Serialize};
+use serde_repr::{Deserialize_repr, Serialize_repr};
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+/// Records submitted to the database, along with submitter information
+pub struct SeqSubmit {
+    pub sub: SubmitBlock,
+    pub data: SeqSubmitData,
+}
+
+impl SeqSubmit {
+    /// Submission of sequence records
+    pub fn new(sub: SubmitBlock, entries: Vec<SeqEntry>) -> Self {
+        Self {
+            sub,
+            data: SeqSubmitData::Entrys(entries),
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+/// Contents of a [`SeqSubmit`]
+pub enum SeqSubmitData {
+    /// sequence(s)
+    Entrys(Vec<SeqEntry>),
+
+    /// annotation(s)
+    Annots(Vec<SeqAnnot>),
+
+    /// deletions of entries
+    Delete(Vec<SeqId>),
+}
+
+enum_from_primitive! {
+    #[derive(Clone, Serialize_repr, Deserialize_repr, PartialEq, Debug)]
+    #[repr(u8)]
+    /// Type of submission given by [`SubmitBlock::subtype`]
+    pub enum SubmitBlockSubtype {
+        /// new data
+        New = 1,
+
+        /// update by author
+        Update,
+
+        /// 3rd party (non-author) update
+        Revision,
+
+        Other = 255,
+    }
+}
+
+asn_names!(SubmitBlockSubtype {
+    New => "new",
+    Update => "update",
+    Revision => "revision",
+    Other => "other",
+});
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[serde(rename_all = "kebab-case")]
+/// Submitter and citation of a [`SeqSubmit`]
+pub struct SubmitBlock {
+    /// who to contact
+    pub contact: ContactInfo,
+
+    /// citation for this submission
+    pub cit: CitSub,
+
+    /// hold until publish
+    pub hup: bool,
+
+    /// release by date
+    pub reldate: Option<Date>,
+
+    /// type of submission
+    pub subtype: Option<SubmitBlockSubtype>,
+
+    /// tool used to make submission
+    pub tool: Option<String>,
+
+    /// user supplied id for this submission
+    pub user_tag: Option<String>,
+
+    /// user comments/advice to database
+    pub comment: Option<String>,
+}
+
+impl SubmitBlock {
+    pub fn new(contact: ContactInfo, cit: CitSub) -> Self {
+        Self {
+            contact,
+            cit,
+            hup: false,
+            reldate: None,
+            subtype: None,
+            tool: None,
+            user_tag: None,
+            comment: None,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+#[serde(rename_all = "kebab-case")]
+/// Who to contact to discuss the submission
+pub struct ContactInfo {
+    /// obsolete, replaced by [`Self::contact`]
+    pub name: Option<String>,
+    pub address: Option<Vec<String>>,
+    pub phone: Option<String>,
+    pub fax: Option<String>,
+    pub email: Option<String>,
+    pub telex: Option<String>,
+
+    /// for owner accounts
+    pub owner_id: Option<ObjectId>,
+
+    /// hex encoded `OCTET STRING`
+    pub password: Option<String>,
+
+    /// obsolete, replaced by [`Self::contact`]
+    pub last_name: Option<String>,
+    /// obsolete, replaced by [`Self::contact`]
+    pub first_name: Option<String>,
+    /// obsolete, replaced by [`Self::contact`]
+    pub middle_initial: Option<String>,
+
+    pub contact: Option<Author>,
+}
+
+impl ContactInfo {
+    /// Contact given by an [`Author`] and email address
+    pub fn new(contact: Author, email: &str) -> Self {
+        Self {
+            email: Some(email.to_string()),
+            contact: Some(contact),
+            ..Self::default()
+        }
+    }
+}
+
+impl XmlNode for SeqSubmit {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("Seq-submit")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> {
+        let sub_element = BytesStart::new("Seq-submit_sub");
+        let entrys_element = BytesStart::new("Seq-submit_data_entrys");
+        let annots_element = BytesStart::new("Seq-submit_data_annots");
+        let delete_element = BytesStart::new("Seq-submit_data_delete");
+
+        let mut sub = None;
+        let mut data = None;
+
+        let forbidden = UnexpectedTags(&[]);
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => {
+                    let name = e.name();
+
+                    if name.is_tag(sub_element.name()) {
+                        sub = read_node(reader);
+                    } else if name.is_tag(entrys_element.name()) {
+                        data = SeqSubmitData::Entrys(read_vec_node(reader, e.to_end())).into();
+                    } else if name.is_tag(annots_element.name()) {
+                        data = SeqSubmitData::Annots(read_vec_node(reader, e.to_end())).into();
+                    } else if name.is_tag(delete_element.name()) {
+                        data = SeqSubmitData::Delete(read_vec_node(reader, e.to_end())).into();
+                    } else if name.as_ref() != b"Seq-submit_data" {
+                        forbidden.check(&name);
+                    }
+                }
+                Event::End(e) if Self::is_end(&e) => {
+                    return Self {
+                        sub: sub?,
+                        data: data?,
+                    }
+                    .into();
+                }
+                _ => (),
+            }
+        }
+    }
+}
+
+impl XmlNode for SubmitBlock {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("Submit-block")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> {
+        let contact_element = BytesStart::new("Submit-block_contact");
+        let cit_element = BytesStart::new("Submit-block_cit");
+        let hup_element = BytesStart::new("Submit-block_hup");
+        let reldate_element = BytesStart::new("Submit-block_reldate");
+        let subtype_element = BytesStart::new("Submit-block_subtype");
+        let tool_element = BytesStart::new("Submit-block_tool");
+        let user_tag_element = BytesStart::new("Submit-block_user-tag");
+        let comment_element = BytesStart::new("Submit-block_comment");
+
+        let mut contact = None;
+        let mut cit = None;
+        let mut block = Self::new(ContactInfo::default(), CitSub::new(Default::default()));
+
+        let forbidden = UnexpectedTags(&[]);
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => {
+                    let name = e.name();
+
+                    if name.is_tag(contact_element.name()) {
+                        contact = read_node(reader);
+                    } else if name.is_tag(cit_element.name()) {
+                        cit = read_node(reader);
+                    } else if name.is_tag(reldate_element.name()) {
+                        block.reldate = read_node(reader);
+                    } else if name.is_tag(subtype_element.name()) {
+                        block.subtype = read_int::<u8>(reader).and_then(SubmitBlockSubtype::from_u8);
+                    } else if name.is_tag(tool_element.name()) {
+                        block.tool = read_string(reader);
+                    } else if name.is_tag(user_tag_element.name()) {
+                        block.user_tag = read_string(reader);
+                    } else if name.is_tag(comment_element.name()) {
+                        block.comment = read_string(reader);
+                    } else if name != Self::start_bytes().name() {
+                        forbidden.check(&name);
+                    }
+                }
+                Event::Empty(e) if e.name().is_tag(hup_element.name()) => {
+                    block.hup = read_bool_attribute(&e).unwrap_or_default();
+                }
+                Event::End(e) if Self::is_end(&e) => {
+                    block.contact = contact?;
+                    block.cit = cit?;
+                    return block.into();
+                }
+                _ => (),
+            }
+        }
+    }
+}
+
+impl XmlNode for ContactInfo {
+    fn start_bytes() -> BytesStart<'static> {
+        BytesStart::new("Contact-info")
+    }
+
+    fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> {
+        let mut info = Self::default();
+
+        let forbidden = UnexpectedTags(&[]);
+
+        loop {
+            match next_event(reader)? {
+                Event::Start(e) => match qualified_name(e.name(), Self::start_bytes().name()).as_ref() {
+                    b"Contact-info_name" => info.name = read_string(reader),
+                    b"Contact-info_address" => {
+                        info.address = read_vec_str_unchecked(reader, &e.to_end()).into()
+                    }
+                    b"Contact-info_phone" => info.phone = read_string(reader),
+                    b"Contact-info_fax" => info.fax = read_string(reader),
+                    b"Contact-info_email" => info.email = read_string(reader),
+                    b"Contact-info_telex" => info.telex = read_string(reader),
+                    b"Contact-info_owner-id" => info.owner_id = read_node(reader),
+                    b"Contact-info_password" => info.password = read_string(reader),
+                    b"Contact-info_last-name" => info.last_name = read_string(reader),
+                    b"Contact-info_first-name" => info.first_name = read_string(reader),
+                    b"Contact-info_middle-initial" => info.middle_initial = read_string(reader),
+                    b"Contact-info_contact" => info.contact = read_node(reader),
+                    b"Contact-info" => (),
+                    _ => forbidden.check(&e.name()),
+                },
+                Event::End(e) if Self::is_end(&e) => {
+                    return info.into();
+                }
+                _ => (),
+            }
+        }
+    }
+}
+
+impl XmlVecNode for SeqSubmit {}
+
+#[cfg(test)]
+mod tests {
+    use crate::parsing::parse_node;
+    use crate::submit::{SeqSubmit, SeqSubmitData, SubmitBlockSubtype};
+
+    const SUBMISSION: &str = r#"
+<Seq-submit>
+  <Seq-submit_sub>
+    <Submit-block>
+      <Submit-block_contact>
+        <Contact-info>
+          <Contact-info_email>jdoe@example.com</Contact-info_email>
+          <Contact-info_contact>
+            <Author>
+              <Author_name>
+                <Person-id>
+                  <Person-id_name>
+                    <Name-std>
+                      <Name-std_last>Doe</Name-std_last>
+                      <Name-std_first>Jane</Name-std_first>
+                    </Name-std>
+                  </Person-id_name>
+                </Person-id>
+              </Author_name>
+            </Author>
+          </Contact-info_contact>
+        </Contact-info>
+      </Submit-block_contact>
+      <Submit-block_cit>
+        <Cit-sub>
+          <Cit-sub_authors>
+            <Auth-list>
+              <Auth-list_names>
+                <Auth-list_names_std>
+                  <Author>
+                    <Author_name>
+                      <Person-id>
+                        <Person-id_name>
+                          <Name-std>
+                            <Name-std_last>Doe</Name-std_last>
+                          </Name-std>
+                        </Person-id_name>
+                      </Person-id>
+                    </Author_name>
+                  </Author>
+                </Auth-list_names_std>
+              </Auth-list_names>
+            </Auth-list>
+          </Cit-sub_authors>
+        </Cit-sub>
+      </Submit-block_cit>
+      <Submit-block_hup value="true"/>
+      <Submit-block_subtype>1</Submit-block_subtype>
+      <Submit-block_tool>ncbi-rs</Submit-block_tool>
+    </Submit-block>
+  </Seq-submit_sub>
+  <Seq-submit_data>
+    <Seq-submit_data_delete>
+      <Seq-id>
+        <Seq-id_gi>42</Seq-id_gi>
+      </Seq-id>
+    </Seq-submit_data_delete>
+  </Seq-submit_data>
+</Seq-submit>"#;
+
+    #[test]
+    fn test_parse_seq_submit() {
+        let submit = parse_node::<SeqSubmit>(SUBMISSION).unwrap();
+
+        let block = &submit.sub;
+        assert_eq!(block.contact.email.as_deref(), Some("jdoe@example.com"));
+        assert!(block.contact.contact.is_some());
+        assert!(block.hup);
+        assert_eq!(block.subtype, Some(SubmitBlockSubtype::New));
+        assert_eq!(block.tool.as_deref(), Some("ncbi-rs"));
+
+        assert!(matches!(submit.data, SeqSubmitData::Delete(ref ids) if ids.len() == 1));
+    }
+
+    #[test]
+    fn test_submit_block_required() {
+        let start = SUBMISSION.find("<Submit-block_cit>").unwrap();
+        let end = SUBMISSION.find("</Submit-block_cit>").unwrap() + "</Submit-block_cit>".len();
+        let missing_cit = [&SUBMISSION[..start], &SUBMISSION[end..]].concat();
+        assert!(parse_node::<SeqSubmit>(&missing_cit).is_none());
+    }
+}
@@ -0,0 +1,217 @@
+//! Command line interface for fetching, searching, and converting NCBI records
+//!
+//! Built only when the `cli` feature is enabled:
+//!
+//! ```text
+//! cargo install ncbi --features cli
+//! ncbi fetch --db nuccore --id NZ_JARQWN010000024 --format fasta
+//! ncbi search --db gene "tp73[sym] AND human[orgn]"
+//! ncbi convert record.xml --to gff3
+//! ```
+//!
+//! Requests are identified with the `NCBI_TOOL`, `NCBI_EMAIL` and `NCBI_API_KEY`
+//! environment variables, when set.
+
+use clap::{Parser, Subcommand, ValueEnum};
+use ncbi::gff::{bioseq_set_to_gff3, seq_id_label};
+use ncbi::ndjson::to_ndjson;
+use ncbi::{configure, fetch_search, parse_xml, ClientConfig, DataType, EntrezDb, FetchRequest};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::fs;
+use std::time::Duration;
+
+#[derive(Parser)]
+#[command(name = "ncbi", version, about = "Fetch and convert NCBI records")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+
+    /// report parser diagnostics on stderr (repeat for more detail)
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    verbose: u8,
+
+    /// abandon requests without a response after this many seconds
+    #[arg(long, global = true)]
+    timeout: Option<u64>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Fetch records by UID or accession
+    Fetch {
+        /// Entrez database (eg: nuccore, protein, gene)
+        #[arg(long)]
+        db: String,
+
+        /// comma separated list of UIDs or accessions
+        #[arg(long, required = true, value_delimiter = ',')]
+        id: Vec<String>,
+
+        #[arg(long, value_enum, default_value_t = Format::Xml)]
+        format: Format,
+    },
+    /// Search a database and print matching UIDs
+    Search {
+        /// Entrez database (eg: nuccore, protein, gene)
+        #[arg(long)]
+        db: String,
+
+        /// Entrez query
+        term: String,
+
+        /// only print the total number of matches
+        #[arg(long)]
+        count: bool,
+    },
+    /// Convert a local XML record to another format
+    Convert {
+        /// XML file returned by EFetch
+        input: PathBuf,
+
+        #[arg(long, value_enum)]
+        to: Format,
+    },
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq)]
+enum Format {
+    /// native ASN.1 XML
+    Xml,
+    Json,
+    /// one JSON object per line and record
+    Ndjson,
+    Gff3,
+    Fasta,
+    Genbank,
+}
+
+/// Writes log records to stderr
+struct StderrLogger;
+
+impl log::Log for StderrLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &log::Record) {
+        if self.enabled(record.metadata()) {
+            eprintln!("{}: {}", record.level().as_str().to_lowercase(), record.args());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let level = match cli.verbose {
+        0 => log::LevelFilter::Off,
+        1 => log::LevelFilter::Warn,
+        2 => log::LevelFilter::Debug,
+        _ => log::LevelFilter::Trace,
+    };
+    if log::set_logger(&StderrLogger).is_ok() {
+        log::set_max_level(level);
+    }
+    let mut config = ClientConfig::from_env();
+    if let Some(seconds) = cli.timeout {
+        config = config.timeout(Duration::from_secs(seconds));
+    }
+    if let Err(e) = configure(config) {
+        eprintln!("error: {}", e);
+        return ExitCode::FAILURE;
+    }
+    let result = match cli.command {
+        Command::Fetch { db, id, format } => fetch(&db, &id.join(","), format),
+        Command::Search { db, term, count } => search(&db, &term, count),
+        Command::Convert { input, to } => convert(&input, to),
+    };
+    match result {
+        Ok(output) => {
+            print!("{}", output);
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn fetch(db: &str, id: &str, format: Format) -> Result<String, String> {
+    let db = db.parse::<EntrezDb>()?;
+    let request = FetchRequest::new(db, id);
+    match format {
+        // formatted by NCBI
+        Format::Fasta => request.rettype("fasta").retmode("text").fetch_text(),
+        Format::Genbank => {
+            let rettype = if db == EntrezDb::Protein { "gp" } else { "gb" };
+            request.rettype(rettype).retmode("text").fetch_text()
+        }
+        // format given by the database defaults, or else XML
+        Format::Xml => {
+            let request = request.with_defaults();
+            if request.retmode.is_some() {
+                request.fetch_text()
+            } else {
+                request.retmode("xml").fetch_text()
+            }
+        }
+        // converted locally
+        Format::Json | Format::Ndjson | Format::Gff3 => render(request.fetch()?, format),
+    }
+}
+
+fn search(db: &str, term: &str, count: bool) -> Result<String, String> {
+    let result = fetch_search(db.parse::<EntrezDb>()?, term)?;
+    if count {
+        Ok(format!("{}\n", result.count))
+    } else {
+        Ok(result.ids.iter().map(|id| format!("{}\n", id)).collect())
+    }
+}
+
+fn convert(input: &PathBuf, to: Format) -> Result<String, String> {
+    let xml = fs::read_to_string(input).map_err(|e| format!("{}: {}", input.display(), e))?;
+    render(parse_xml(xml.as_str())?, to)
+}
+
+fn render(data: DataType, format: Format) -> Result<String, String> {
+    match (data, format) {
+        (DataType::BioSeqSet(set), Format::Json) => to_json(&set),
+        (DataType::EntrezgeneSet(genes), Format::Json) => to_json(&genes),
+        (DataType::SeqSubmit(submit), Format::Json) => to_json(&submit),
+        (DataType::CddSet(set), Format::Json) => to_json(&set),
+        (DataType::SeqAnnot(annot), Format::Json) => to_json(&annot),
+        (DataType::BioSeqSet(set), Format::Ndjson) => to_ndjson(&set.seq_set),
+        (DataType::EntrezgeneSet(genes), Format::Ndjson) => to_ndjson(&genes),
+        (DataType::BioSeqSet(set), Format::Gff3) => Ok(bioseq_set_to_gff3(&set)),
+        (DataType::BioSeqSet(set), Format::Fasta) => {
+            let mut fasta = String::new();
+            for bioseq in set.bioseqs() {
+                let residues = match bioseq.inst.as_ref() {
+                    Some(inst) => inst.residues()?,
+                    None => continue,
+                };
+                let label = bioseq.id.first().map(seq_id_label).unwrap_or_default();
+                fasta.push_str(&format!(">{}\n", label));
+                for line in residues.as_bytes().chunks(70) {
+                    fasta.push_str(std::str::from_utf8(line).unwrap());
+                    fasta.push('\n');
+                }
+            }
+            Ok(fasta)
+        }
+        (_, format) => Err(format!(
+            "Cannot convert record to {}",
+            format.to_possible_value().unwrap().get_name()
+        )),
+    }
+}
+
+fn to_json<T: serde::Serialize>(value: &T) -> Result<String, String> {
+    serde_json::to_string_pretty(value)
+        .map(|json| json + "\n")
+        .map_err(|e| e.to_string())
+}
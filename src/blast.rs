@@ -0,0 +1,515 @@
+//! BLAST search results
+//!
+//! The BLAST URL API returns results in the `BlastOutput2` schema, either as XML
+//! (`FORMAT_TYPE=XML2`) or JSON (`FORMAT_TYPE=JSON2_S`). Both are parsed into a
+//! [`BlastReport`], which summarizes hits and their high-scoring pairs ([`Hsp`]).
+//!
+//! Alignments can then be converted into [`SeqAlign`] via [`BlastSearch::seq_aligns()`]
+//! so that they can be handled the same as alignments given by Entrez records.
+//!
+//! Parsing JSON requires the `json` feature.
+
+use crate::general::ObjectId;
+use crate::seqalign::{DenseSeg, Score, ScoreValue, SeqAlign, SeqAlignSegs, SeqAlignSet, SeqAlignType};
+use crate::seqloc::{parse_fasta_ids, NaStrand, SeqId};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+/// Results of a single BLAST run
+pub struct BlastReport {
+    /// eg: "blastn", "blastp"
+    pub program: String,
+    pub version: String,
+    /// database that was searched
+    pub db: Option<String>,
+    /// one search per query sequence
+    pub searches: Vec<BlastSearch>,
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+#[serde(default)]
+/// Hits for a single query sequence
+pub struct BlastSearch {
+    /// eg: "Query_1" or the accession of the query
+    pub query_id: String,
+    pub query_title: Option<String>,
+    pub query_len: u64,
+    pub hits: Vec<BlastHit>,
+    /// given when no hits are found, eg: "No hits found"
+    pub message: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+#[serde(default)]
+/// Database sequence matched by the query
+pub struct BlastHit {
+    /// rank of this hit
+    pub num: u64,
+    /// identical sequences in the database are merged into a single hit
+    pub description: Vec<HitDescr>,
+    /// length of the matched sequence
+    pub len: u64,
+    pub hsps: Vec<Hsp>,
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+#[serde(default)]
+pub struct HitDescr {
+    /// FASTA style identifier, eg: "gi|1519245535|ref|NM_000546.6|"
+    pub id: String,
+    pub accession: Option<String>,
+    pub title: Option<String>,
+    pub taxid: Option<u64>,
+    pub sciname: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+#[serde(default)]
+/// High-scoring segment pair
+///
+/// Coordinates are 1-based and inclusive. On the minus strand, `*_from` is greater
+/// than `*_to`.
+pub struct Hsp {
+    pub num: u64,
+    pub bit_score: f64,
+    /// raw score
+    pub score: u64,
+    pub evalue: f64,
+    /// number of identical positions
+    pub identity: u64,
+    /// number of positive scoring positions, only given for protein alignments
+    pub positive: Option<u64>,
+    pub query_from: u64,
+    pub query_to: u64,
+    /// "Plus" or "Minus", only given for nucleotide queries
+    pub query_strand: Option<String>,
+    /// only given for translated queries
+    pub query_frame: Option<i64>,
+    pub hit_from: u64,
+    pub hit_to: u64,
+    pub hit_strand: Option<String>,
+    pub hit_frame: Option<i64>,
+    pub align_len: u64,
+    pub gaps: u64,
+    /// aligned query, including gaps
+    pub qseq: String,
+    /// aligned subject, including gaps
+    pub hseq: String,
+    pub midline: String,
+}
+
+impl BlastHit {
+    /// Accession of the first sequence merged into this hit
+    pub fn accession(&self) -> Option<&str> {
+        self.description.first()?.accession.as_deref()
+    }
+
+    /// HSP with the lowest e-value
+    pub fn best_hsp(&self) -> Option<&Hsp> {
+        self.hsps.iter().min_by(|a, b| a.evalue.total_cmp(&b.evalue))
+    }
+}
+
+impl Hsp {
+    /// Percentage of aligned columns that are identical
+    pub fn percent_identity(&self) -> f64 {
+        if self.align_len == 0 {
+            return 0.0;
+        }
+        self.identity as f64 / self.align_len as f64 * 100.0
+    }
+
+    /// Alignment scores, named as in BLAST generated [`SeqAlign`] records
+    pub fn scores(&self) -> Vec<Score> {
+        let score = |name: &str, value| Score {
+            id: Some(ObjectId::Str(name.into())),
+            value,
+        };
+        vec![
+            score("score", ScoreValue::Int(self.score as i64)),
+            score("bit_score", ScoreValue::Real(self.bit_score)),
+            score("e_value", ScoreValue::Real(self.evalue)),
+            score("num_ident", ScoreValue::Int(self.identity as i64)),
+        ]
+    }
+
+    /// Pairwise [`SeqAlign`] of `query` and `subject`, with the query as the first row
+    ///
+    /// Returns `None` for translated searches, where the aligned residues do not
+    /// correspond to the coordinates of both sequences, or if `qseq` and `hseq`
+    /// differ in length.
+    pub fn to_seq_align(&self, query: SeqId, subject: SeqId) -> Option<SeqAlign> {
+        if self.query_frame.is_some() || self.hit_frame.is_some() {
+            return None;
+        }
+        let qseq = self.qseq.as_bytes();
+        let hseq = self.hseq.as_bytes();
+        if qseq.len() != hseq.len() || qseq.is_empty() {
+            return None;
+        }
+
+        // group columns into segments which share the same pattern of gaps
+        let mut pattern: Vec<(bool, bool)> = Vec::new();
+        let mut lens: Vec<u64> = Vec::new();
+        for (q, h) in qseq.iter().zip(hseq) {
+            let column = (*q != b'-', *h != b'-');
+            if pattern.last() == Some(&column) {
+                *lens.last_mut().unwrap() += 1;
+            } else {
+                pattern.push(column);
+                lens.push(1);
+            }
+        }
+
+        let mut rows = [
+            Row::new(self.query_from, self.query_to),
+            Row::new(self.hit_from, self.hit_to),
+        ];
+        let mut starts = Vec::with_capacity(pattern.len() * 2);
+        for (present, len) in pattern.iter().zip(&lens) {
+            starts.push(rows[0].consume(present.0, *len));
+            starts.push(rows[1].consume(present.1, *len));
+        }
+
+        let strands = if self.query_strand.is_some() || self.hit_strand.is_some() {
+            Some(rows.iter().map(|row| row.strand()).collect())
+        } else {
+            None
+        };
+
+        Some(SeqAlign {
+            r#type: SeqAlignType::Partial,
+            dim: Some(2),
+            score: Some(self.scores()),
+            segs: SeqAlignSegs::DenSeg(DenseSeg {
+                dim: 2,
+                numseg: lens.len() as u64,
+                ids: vec![query, subject],
+                starts,
+                lens,
+                strands,
+                scores: None,
+            }),
+            bounds: None,
+            id: None,
+            ext: None,
+        })
+    }
+}
+
+/// Position within a single row of an alignment being converted to [`DenseSeg`]
+struct Row {
+    /// 0-based coordinate of the next residue
+    next: i64,
+    minus: bool,
+}
+
+impl Row {
+    fn new(from: u64, to: u64) -> Self {
+        Self {
+            next: from as i64 - 1,
+            minus: from > to,
+        }
+    }
+
+    /// Start of a segment of `len` columns, or -1 for a gap
+    fn consume(&mut self, present: bool, len: u64) -> i64 {
+        if !present {
+            return -1;
+        }
+        let len = len as i64;
+        if self.minus {
+            self.next -= len;
+            self.next + 1
+        } else {
+            self.next += len;
+            self.next - len
+        }
+    }
+
+    fn strand(&self) -> NaStrand {
+        if self.minus {
+            NaStrand::Minus
+        } else {
+            NaStrand::Plus
+        }
+    }
+}
+
+impl BlastSearch {
+    /// Alignments of every HSP, in the order that hits are ranked
+    ///
+    /// Hits are identified by the first [`HitDescr`] of each hit.
+    pub fn seq_aligns(&self) -> SeqAlignSet {
+        let query = parse_fasta_id(&self.query_id);
+        self.hits
+            .iter()
+            .filter_map(|hit| Some((hit, parse_fasta_id(&hit.description.first()?.id))))
+            .flat_map(|(hit, subject)| {
+                let query = query.clone();
+                hit.hsps
+                    .iter()
+                    .filter_map(move |hsp| hsp.to_seq_align(query.clone(), subject.clone()))
+            })
+            .collect()
+    }
+}
+
+/// Interpret a FASTA style identifier as given by BLAST
+///
+/// The first textual accession is preferred over other identifiers (eg: a gi), and
+/// identifiers which cannot be interpreted are kept as a local id.
+pub(crate) fn parse_fasta_id(id: &str) -> SeqId {
+    let ids = parse_fasta_ids(id).unwrap_or_default();
+    ids.iter()
+        .find(|id| id.textseq_id().is_some())
+        .or(ids.first())
+        .cloned()
+        .unwrap_or(SeqId::Local(ObjectId::Str(id.into())))
+}
+
+/// Parse BLAST results given as `BlastOutput2` XML
+///
+/// A `BlastXML2` document containing multiple reports results in multiple [`BlastReport`]
+pub fn parse_blast_xml2(response: &str) -> Result<Vec<BlastReport>, String> {
+    let mut reader = Reader::from_str(response);
+    reader.trim_text(true);
+
+    let mut reports: Vec<BlastReport> = Vec::new();
+    let mut path: Vec<Vec<u8>> = Vec::new();
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) => {
+                if let Some(report) = reports.last_mut() {
+                    let search = report.searches.last_mut();
+                    let hit = search.and_then(|s| s.hits.last_mut());
+                    match e.name().as_ref() {
+                        b"Search" => report.searches.push(BlastSearch::default()),
+                        b"Hit" => {
+                            if let Some(search) = report.searches.last_mut() {
+                                search.hits.push(BlastHit::default())
+                            }
+                        }
+                        b"HitDescr" => {
+                            if let Some(hit) = hit {
+                                hit.description.push(HitDescr::default())
+                            }
+                        }
+                        b"Hsp" => {
+                            if let Some(hit) = hit {
+                                hit.hsps.push(Hsp::default())
+                            }
+                        }
+                        _ => (),
+                    }
+                }
+                if e.name().as_ref() == b"Report" {
+                    reports.push(BlastReport::default());
+                }
+                path.push(e.name().as_ref().to_vec());
+            }
+            Ok(Event::Text(text)) => {
+                let text = text.unescape().map_err(|e| format!("{:?}", e))?.to_string();
+                let parent = path.len().checked_sub(2).and_then(|i| path.get(i));
+                let parent = parent.map(|p| p.as_slice());
+                let current = path.last().map(|p| p.as_slice());
+                if let (Some(report), Some(parent), Some(current)) = (reports.last_mut(), parent, current) {
+                    read_text(report, parent, current, text)?;
+                }
+            }
+            Ok(Event::End(_)) => {
+                path.pop();
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(format!("XML parsing error: {:?}", e)),
+            _ => (),
+        }
+    }
+
+    if reports.is_empty() {
+        return Err("No <Report> found in response.".to_string());
+    }
+    Ok(reports)
+}
+
+/// Assign the text of `current` to the innermost value of `report`
+fn read_text(report: &mut BlastReport, parent: &[u8], current: &[u8], text: String) -> Result<(), String> {
+    let num = |text: &str| {
+        text.trim()
+            .parse::<u64>()
+            .map_err(|_| format!("Invalid integer in <{}>: {}", String::from_utf8_lossy(current), text))
+    };
+    let int = |text: &str| {
+        text.trim()
+            .parse::<i64>()
+            .map_err(|_| format!("Invalid integer in <{}>: {}", String::from_utf8_lossy(current), text))
+    };
+    let real = |text: &str| {
+        text.trim()
+            .parse::<f64>()
+            .map_err(|_| format!("Invalid number in <{}>: {}", String::from_utf8_lossy(current), text))
+    };
+
+    match parent {
+        b"Report" => match current {
+            b"program" => report.program = text,
+            b"version" => report.version = text,
+            _ => (),
+        },
+        b"Target" if current == b"db" => report.db = Some(text),
+        b"Search" => {
+            let search = match report.searches.last_mut() {
+                Some(search) => search,
+                None => return Ok(()),
+            };
+            match current {
+                b"query-id" => search.query_id = text,
+                b"query-title" => search.query_title = Some(text),
+                b"query-len" => search.query_len = num(&text)?,
+                b"message" => search.message = Some(text),
+                _ => (),
+            }
+        }
+        b"Hit" => {
+            if let Some(hit) = report.searches.last_mut().and_then(|s| s.hits.last_mut()) {
+                match current {
+                    b"num" => hit.num = num(&text)?,
+                    b"len" => hit.len = num(&text)?,
+                    _ => (),
+                }
+            }
+        }
+        b"HitDescr" => {
+            let descr = report
+                .searches
+                .last_mut()
+                .and_then(|s| s.hits.last_mut())
+                .and_then(|h| h.description.last_mut());
+            if let Some(descr) = descr {
+                match current {
+                    b"id" => descr.id = text,
+                    b"accession" => descr.accession = Some(text),
+                    b"title" => descr.title = Some(text),
+                    b"taxid" => descr.taxid = Some(num(&text)?),
+                    b"sciname" => descr.sciname = Some(text),
+                    _ => (),
+                }
+            }
+        }
+        b"Hsp" => {
+            let hsp = report
+                .searches
+                .last_mut()
+                .and_then(|s| s.hits.last_mut())
+                .and_then(|h| h.hsps.last_mut());
+            let hsp = match hsp {
+                Some(hsp) => hsp,
+                None => return Ok(()),
+            };
+            match current {
+                b"num" => hsp.num = num(&text)?,
+                b"bit-score" => hsp.bit_score = real(&text)?,
+                b"score" => hsp.score = num(&text)?,
+                b"evalue" => hsp.evalue = real(&text)?,
+                b"identity" => hsp.identity = num(&text)?,
+                b"positive" => hsp.positive = Some(num(&text)?),
+                b"query-from" => hsp.query_from = num(&text)?,
+                b"query-to" => hsp.query_to = num(&text)?,
+                b"query-strand" => hsp.query_strand = Some(text),
+                b"query-frame" => hsp.query_frame = Some(int(&text)?),
+                b"hit-from" => hsp.hit_from = num(&text)?,
+                b"hit-to" => hsp.hit_to = num(&text)?,
+                b"hit-strand" => hsp.hit_strand = Some(text),
+                b"hit-frame" => hsp.hit_frame = Some(int(&text)?),
+                b"align-len" => hsp.align_len = num(&text)?,
+                b"gaps" => hsp.gaps = num(&text)?,
+                b"qseq" => hsp.qseq = text,
+                b"hseq" => hsp.hseq = text,
+                b"midline" => hsp.midline = text,
+                _ => (),
+            }
+        }
+        _ => (),
+    }
+    Ok(())
+}
+
+#[cfg(feature = "json")]
+mod json {
+    use super::{BlastReport, BlastSearch};
+    use serde::Deserialize;
+
+    #[derive(Deserialize)]
+    pub(super) struct Output {
+        #[serde(rename = "BlastOutput2")]
+        pub reports: Reports,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    pub(super) enum Reports {
+        Many(Vec<Wrapper>),
+        One(Wrapper),
+    }
+
+    #[derive(Deserialize)]
+    pub(super) struct Wrapper {
+        pub report: Report,
+    }
+
+    #[derive(Deserialize)]
+    pub(super) struct Report {
+        pub program: String,
+        pub version: String,
+        pub search_target: Option<Target>,
+        pub results: Results,
+    }
+
+    #[derive(Deserialize)]
+    pub(super) struct Target {
+        pub db: Option<String>,
+    }
+
+    #[derive(Deserialize)]
+    pub(super) struct Results {
+        pub search: Option<BlastSearch>,
+        #[serde(default)]
+        pub iterations: Vec<Iteration>,
+    }
+
+    #[derive(Deserialize)]
+    pub(super) struct Iteration {
+        pub search: BlastSearch,
+    }
+
+    impl From<Report> for BlastReport {
+        fn from(report: Report) -> Self {
+            let results = report.results;
+            let searches = results
+                .search
+                .into_iter()
+                .chain(results.iterations.into_iter().map(|i| i.search))
+                .collect();
+            Self {
+                program: report.program,
+                version: report.version,
+                db: report.search_target.and_then(|target| target.db),
+                searches,
+            }
+        }
+    }
+}
+
+/// Parse BLAST results given as `BlastOutput2` JSON
+#[cfg(feature = "json")]
+pub fn parse_blast_json(response: &str) -> Result<Vec<BlastReport>, String> {
+    let output: json::Output = serde_json::from_str(response).map_err(|e| e.to_string())?;
+    let reports = match output.reports {
+        json::Reports::Many(reports) => reports,
+        json::Reports::One(report) => vec![report],
+    };
+    Ok(reports.into_iter().map(|wrapper| wrapper.report.into()).collect())
+}
@@ -0,0 +1,495 @@
+//! Builders for constructing records programmatically
+//!
+//! Parsed records are plain structs with public fields, however constructing them by
+//! hand requires spelling out every optional field. The builders in this module fill
+//! in sensible defaults and check that required fields are present when
+//! [`build()`](SeqFeatBuilder::build) is called.
+//!
+//! # Example
+//! ```ignore
+//! let source = BioSourceBuilder::new("Escherichia coli").taxid(562).build()?;
+//! let cds = SeqFeatBuilder::cds(SeqLoc::Int(interval))
+//!     .product(protein_loc)
+//!     .frame(CdRegionFrame::One)
+//!     .build()?;
+//! let bioseq = BioSeqBuilder::new(id)
+//!     .iupacna("ATGAAATAG")
+//!     .source(source)
+//!     .feature(cds)
+//!     .build()?;
+//! ```
+
+use crate::biblio::PubMedId;
+use crate::general::{DbTag, ObjectId};
+use crate::r#pub::Pub;
+use crate::seq::{
+    BioSeq, Mol, PubDesc, Repr, SeqAnnot, SeqAnnotData, SeqData, SeqDesc, SeqInst, Strand,
+    Topology,
+};
+use crate::seqfeat::{
+    BioSource, BioSourceGenome, BioSourceOrigin, CdRegion, CdRegionFrame, GbQual, GeneRef,
+    OrgMod, OrgModSubType, OrgName, OrgRef, ProtRef, RnaRef, RnaRefType, SeqFeat, SeqFeatData,
+    SeqFeatXref, SubSource, SubSourceSubType,
+};
+use crate::seqloc::{SeqId, SeqInterval, SeqLoc};
+
+/// Residues allowed by [`SeqData::Ina`]
+const IUPAC_NA: &str = "ACGTUMRWSYKVHDBN-";
+
+/// Residues allowed by [`SeqData::Iaa`]
+const IUPAC_AA: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ*-";
+
+/// Builds a [`BioSeq`] with raw sequence data
+#[derive(Clone, PartialEq, Debug)]
+pub struct BioSeqBuilder {
+    ids: Vec<SeqId>,
+    inst: SeqInst,
+    descr: Vec<SeqDesc>,
+    features: Vec<SeqFeat>,
+}
+
+impl BioSeqBuilder {
+    pub fn new(id: SeqId) -> Self {
+        Self {
+            ids: vec![id],
+            inst: SeqInst {
+                repr: Repr::NotSet,
+                mol: Mol::NotSet,
+                ..SeqInst::default()
+            },
+            descr: Vec::new(),
+            features: Vec::new(),
+        }
+    }
+
+    /// Add an equivalent identifier
+    pub fn id(mut self, id: SeqId) -> Self {
+        self.ids.push(id);
+        self
+    }
+
+    /// Nucleotide sequence given as IUPAC codes
+    ///
+    /// Sets [`Mol::DNA`] unless another nucleotide molecule type is given by
+    /// [`Self::mol()`].
+    pub fn iupacna(mut self, sequence: &str) -> Self {
+        let sequence = sequence.to_uppercase();
+        self.inst.repr = Repr::Raw;
+        self.inst.length = Some(sequence.len() as u64);
+        self.inst.seq_data = Some(SeqData::Ina(sequence));
+        if !matches!(self.inst.mol, Mol::DNA | Mol::RNA | Mol::NA) {
+            self.inst.mol = Mol::DNA;
+        }
+        self
+    }
+
+    /// Protein sequence given as IUPAC codes
+    pub fn iupacaa(mut self, sequence: &str) -> Self {
+        let sequence = sequence.to_uppercase();
+        self.inst.repr = Repr::Raw;
+        self.inst.length = Some(sequence.len() as u64);
+        self.inst.seq_data = Some(SeqData::Iaa(sequence));
+        self.inst.mol = Mol::AA;
+        self
+    }
+
+    pub fn mol(mut self, mol: Mol) -> Self {
+        self.inst.mol = mol;
+        self
+    }
+
+    pub fn topology(mut self, topology: Topology) -> Self {
+        self.inst.topology = topology;
+        self
+    }
+
+    pub fn strand(mut self, strand: Strand) -> Self {
+        self.inst.strand = strand;
+        self
+    }
+
+    pub fn descriptor(mut self, desc: SeqDesc) -> Self {
+        self.descr.push(desc);
+        self
+    }
+
+    pub fn title(self, title: &str) -> Self {
+        self.descriptor(SeqDesc::Title(title.to_string()))
+    }
+
+    pub fn source(self, source: BioSource) -> Self {
+        self.descriptor(SeqDesc::Source(source))
+    }
+
+    pub fn pub_desc(self, desc: PubDesc) -> Self {
+        self.descriptor(SeqDesc::Pub(desc))
+    }
+
+    /// Add a feature to the feature table
+    pub fn feature(mut self, feat: SeqFeat) -> Self {
+        self.features.push(feat);
+        self
+    }
+
+    /// Check that sequence data is present and consistent with the molecule type, and
+    /// that all features lie within the sequence
+    pub fn build(self) -> Result<BioSeq, String> {
+        match (&self.inst.mol, &self.inst.seq_data) {
+            (_, None) => return Err("Bioseq has no sequence data".to_string()),
+            (Mol::AA, Some(SeqData::Iaa(sequence))) => check_residues(sequence, IUPAC_AA)?,
+            (Mol::DNA | Mol::RNA | Mol::NA, Some(SeqData::Ina(sequence))) => {
+                check_residues(sequence, IUPAC_NA)?
+            }
+            (mol, _) => {
+                return Err(format!("Sequence data does not match molecule type {:?}", mol))
+            }
+        }
+
+        let length = self.inst.length.unwrap_or_default() as i64;
+        for feat in self.features.iter() {
+            if let Some((from, to)) = feat.location.total_range() {
+                if from < 0 || to >= length {
+                    return Err(format!(
+                        "Feature location {}..{} is outside of sequence of length {}",
+                        from, to, length
+                    ));
+                }
+            }
+        }
+
+        Ok(BioSeq {
+            id: self.ids,
+            descr: (!self.descr.is_empty()).then_some(self.descr),
+            inst: Some(self.inst),
+            annot: (!self.features.is_empty())
+                .then(|| vec![SeqAnnot::new(SeqAnnotData::FTable(self.features))]),
+        })
+    }
+}
+
+fn check_residues(sequence: &str, alphabet: &str) -> Result<(), String> {
+    match sequence.chars().position(|c| !alphabet.contains(c)) {
+        Some(i) => Err(format!(
+            "Invalid residue '{}' at position {}",
+            &sequence[i..i + 1],
+            i + 1
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Location of the residues `from` to `to` of `id`, given as 0-based inclusive positions
+pub fn interval(id: &SeqId, from: i64, to: i64) -> SeqLoc {
+    SeqLoc::Int(SeqInterval {
+        from,
+        to,
+        id: id.clone(),
+        ..SeqInterval::default()
+    })
+}
+
+/// Builds a [`SeqFeat`] for a given location
+#[derive(Clone, PartialEq, Debug)]
+pub struct SeqFeatBuilder {
+    feat: SeqFeat,
+    frame: Option<CdRegionFrame>,
+}
+
+impl SeqFeatBuilder {
+    pub fn new(data: SeqFeatData, location: SeqLoc) -> Self {
+        let mut feat = SeqFeat::new(data);
+        feat.location = location;
+        Self { feat, frame: None }
+    }
+
+    /// Gene feature named by `locus`
+    pub fn gene(locus: &str, location: SeqLoc) -> Self {
+        let gene = GeneRef {
+            locus: Some(locus.to_string()),
+            ..GeneRef::default()
+        };
+        Self::new(SeqFeatData::Gene(gene), location)
+    }
+
+    /// Coding region
+    pub fn cds(location: SeqLoc) -> Self {
+        Self::new(SeqFeatData::CdRegion(CdRegion::default()), location)
+    }
+
+    pub fn rna(r#type: RnaRefType, location: SeqLoc) -> Self {
+        let rna = RnaRef {
+            r#type,
+            pseudo: None,
+            ext: None,
+        };
+        Self::new(SeqFeatData::RNA(rna), location)
+    }
+
+    /// Named region
+    pub fn region(name: &str, location: SeqLoc) -> Self {
+        Self::new(SeqFeatData::Region(name.to_string()), location)
+    }
+
+    /// Location of the product (eg: protein of a coding region)
+    pub fn product(mut self, product: SeqLoc) -> Self {
+        self.feat.product = Some(product);
+        self
+    }
+
+    /// Reading frame of a coding region
+    pub fn frame(mut self, frame: CdRegionFrame) -> Self {
+        self.frame = Some(frame);
+        self
+    }
+
+    pub fn partial(mut self, partial: bool) -> Self {
+        self.feat.partial = Some(partial);
+        self
+    }
+
+    pub fn pseudo(mut self, pseudo: bool) -> Self {
+        self.feat.pseudo = Some(pseudo);
+        self
+    }
+
+    pub fn comment(mut self, comment: &str) -> Self {
+        self.feat.comment = Some(comment.to_string());
+        self
+    }
+
+    /// Add a GenBank qualifier
+    pub fn qual(mut self, qual: &str, val: &str) -> Self {
+        self.feat.qual.get_or_insert_with(Vec::new).push(GbQual {
+            qual: qual.into(),
+            val: val.to_string(),
+        });
+        self
+    }
+
+    pub fn dbxref(mut self, tag: DbTag) -> Self {
+        self.feat.dbxref.get_or_insert_with(Vec::new).push(tag);
+        self
+    }
+
+    /// Reference a gene without a separate gene feature
+    pub fn gene_xref(self, gene: GeneRef) -> Self {
+        self.xref(SeqFeatData::Gene(gene))
+    }
+
+    /// Name of the protein encoded by a coding region
+    pub fn protein_name(self, name: &str) -> Self {
+        let prot = ProtRef {
+            name: Some(vec![name.to_string()]),
+            ..ProtRef::default()
+        };
+        self.xref(SeqFeatData::Prot(prot))
+    }
+
+    fn xref(mut self, data: SeqFeatData) -> Self {
+        self.feat.xref.get_or_insert_with(Vec::new).push(SeqFeatXref {
+            id: None,
+            data: Some(data),
+        });
+        self
+    }
+
+    /// Check that the location is set, and that coding region fields are only
+    /// given for coding regions
+    pub fn build(mut self) -> Result<SeqFeat, String> {
+        if matches!(self.feat.location, SeqLoc::Null | SeqLoc::Empty(_)) {
+            return Err("Feature has no location".to_string());
+        }
+        if let Some((from, to)) = self.feat.location.total_range() {
+            if from > to {
+                return Err(format!("Feature location {}..{} is reversed", from, to));
+            }
+        }
+
+        match (&mut self.feat.data, self.frame) {
+            (SeqFeatData::CdRegion(cds), Some(frame)) => cds.frame = frame,
+            (_, Some(_)) => return Err("Reading frame given for a feature that is not a coding region".to_string()),
+            _ => (),
+        }
+        if self.feat.product.is_some()
+            && !matches!(self.feat.data, SeqFeatData::CdRegion(_) | SeqFeatData::RNA(_))
+        {
+            return Err("Product given for a feature that is not a coding region or RNA".to_string());
+        }
+        Ok(self.feat)
+    }
+}
+
+/// Builds a [`BioSource`] for an organism
+#[derive(Clone, PartialEq, Debug)]
+pub struct BioSourceBuilder {
+    source: BioSource,
+}
+
+impl BioSourceBuilder {
+    /// Organism given by its scientific name
+    pub fn new(taxname: &str) -> Self {
+        let source = BioSource {
+            org: OrgRef {
+                taxname: Some(taxname.to_string()),
+                ..OrgRef::default()
+            },
+            ..BioSource::default()
+        };
+        Self { source }
+    }
+
+    /// NCBI Taxonomy id, given as a "taxon" [`DbTag`]
+    pub fn taxid(self, taxid: u64) -> Self {
+        self.db(DbTag {
+            db: "taxon".into(),
+            tag: ObjectId::Id(taxid),
+        })
+    }
+
+    /// Id in a taxonomic or culture database
+    pub fn db(mut self, tag: DbTag) -> Self {
+        self.source.org.db.get_or_insert_with(Vec::new).push(tag);
+        self
+    }
+
+    pub fn common(mut self, common: &str) -> Self {
+        self.source.org.common = Some(common.to_string());
+        self
+    }
+
+    pub fn genome(mut self, genome: BioSourceGenome) -> Self {
+        self.source.genome = genome;
+        self
+    }
+
+    pub fn origin(mut self, origin: BioSourceOrigin) -> Self {
+        self.source.origin = origin;
+        self
+    }
+
+    /// Add a source modifier (eg: [`SubSourceSubType::Chromosome`])
+    pub fn subsource(mut self, subtype: SubSourceSubType, name: &str) -> Self {
+        self.source.subtype.get_or_insert_with(Vec::new).push(SubSource {
+            subtype,
+            name: name.to_string(),
+            attrib: None,
+        });
+        self
+    }
+
+    /// Add an organism modifier (eg: [`OrgModSubType::Strain`])
+    pub fn org_mod(mut self, subtype: OrgModSubType, subname: &str) -> Self {
+        let orgname = self.source.org.orgname.get_or_insert_with(OrgName::default);
+        orgname.r#mod.get_or_insert_with(Vec::new).push(OrgMod {
+            subtype,
+            subname: subname.to_string(),
+            attrib: None,
+        });
+        self
+    }
+
+    /// Check that the organism is named
+    pub fn build(self) -> Result<BioSource, String> {
+        match self.source.org.taxname.as_deref().map(str::trim) {
+            Some(taxname) if !taxname.is_empty() => Ok(self.source),
+            _ => Err("BioSource has no organism name".to_string()),
+        }
+    }
+}
+
+/// Builds a [`PubDesc`] citing one or more publications
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct PubDescBuilder {
+    desc: PubDesc,
+}
+
+impl PubDescBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cite a publication
+    pub fn r#pub(mut self, r#pub: Pub) -> Self {
+        self.desc.r#pub.push(r#pub);
+        self
+    }
+
+    /// Cite a publication by PubMed id
+    pub fn pmid(self, pmid: PubMedId) -> Self {
+        self.r#pub(Pub::PmId(pmid))
+    }
+
+    pub fn comment(mut self, comment: &str) -> Self {
+        self.desc.comment = Some(comment.to_string());
+        self
+    }
+
+    /// Check that at least one publication is cited
+    pub fn build(self) -> Result<PubDesc, String> {
+        if self.desc.r#pub.is_empty() {
+            return Err("PubDesc does not cite a publication".to_string());
+        }
+        Ok(self.desc)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::builder::{interval, BioSeqBuilder, BioSourceBuilder, PubDescBuilder, SeqFeatBuilder};
+    use crate::seq::{Descriptors, Mol};
+    use crate::seqfeat::{CdRegionFrame, SeqFeatData};
+    use crate::seqloc::{SeqId, SeqLoc};
+
+    fn id() -> SeqId {
+        SeqId::from_defline("gb|AB000001.1|").unwrap()
+    }
+
+    #[test]
+    fn test_build_bioseq() {
+        let source = BioSourceBuilder::new("Escherichia coli").taxid(562).build().unwrap();
+        let cds = SeqFeatBuilder::cds(interval(&id(), 0, 8))
+            .frame(CdRegionFrame::One)
+            .protein_name("hypothetical protein")
+            .build()
+            .unwrap();
+        let bioseq = BioSeqBuilder::new(id())
+            .iupacna("atgaaatag")
+            .title("test sequence")
+            .source(source)
+            .pub_desc(PubDescBuilder::new().pmid(1).build().unwrap())
+            .feature(cds)
+            .build()
+            .unwrap();
+
+        let inst = bioseq.inst.as_ref().unwrap();
+        assert_eq!(inst.mol, Mol::DNA);
+        assert_eq!(inst.length, Some(9));
+        assert_eq!(bioseq.title(), Some("test sequence"));
+        assert_eq!(bioseq.taxid(), Some(562));
+
+        let features = bioseq.annot.as_ref().unwrap()[0].features();
+        match &features[0].data {
+            SeqFeatData::CdRegion(cds) => assert_eq!(cds.frame, CdRegionFrame::One),
+            data => panic!("Unexpected feature {:?}", data),
+        }
+    }
+
+    #[test]
+    fn test_build_validation() {
+        assert!(BioSeqBuilder::new(id()).build().is_err());
+        assert!(BioSeqBuilder::new(id()).iupacna("ACGX").build().is_err());
+        assert!(BioSeqBuilder::new(id()).iupacaa("MKV*").build().is_ok());
+        assert!(BioSeqBuilder::new(id()).iupacna("ACGT").mol(Mol::AA).build().is_err());
+
+        let outside = SeqFeatBuilder::region("domain", interval(&id(), 2, 10)).build().unwrap();
+        assert!(BioSeqBuilder::new(id()).iupacna("ACGT").feature(outside).build().is_err());
+
+        assert!(SeqFeatBuilder::cds(SeqLoc::Null).build().is_err());
+        assert!(SeqFeatBuilder::cds(interval(&id(), 5, 2)).build().is_err());
+        assert!(SeqFeatBuilder::gene("abc", interval(&id(), 0, 2)).frame(CdRegionFrame::Two).build().is_err());
+        assert!(SeqFeatBuilder::region("x", interval(&id(), 0, 2)).product(interval(&id(), 0, 1)).build().is_err());
+
+        assert!(BioSourceBuilder::new(" ").build().is_err());
+        assert!(PubDescBuilder::new().comment("no citation").build().is_err());
+    }
+}
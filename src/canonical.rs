@@ -0,0 +1,282 @@
+//! Canonical ordering of parsed records
+//!
+//! Records fetched at different times may list the same ids, descriptors or cross
+//! references in a different order. [`Canonicalize`] puts these lists into a
+//! deterministic order so that two versions of a record can be meaningfully diffed,
+//! and [`canonical_json()`] additionally serializes objects with sorted keys.
+//!
+//! Only lists whose order carries no meaning are sorted: features, sequences of a set
+//! and residues are left untouched.
+
+use crate::entrezgene::Entrezgene;
+use crate::general::DbTag;
+use crate::seq::{BioSeq, SeqAnnot, SeqAnnotData, SeqDesc};
+use crate::seqfeat::{BioSource, GeneRef, OrgRef, SeqFeat, SeqFeatData};
+use crate::seqset::{BioSeqSet, SeqEntry};
+
+/// Objects which can be put into a canonical order in place
+pub trait Canonicalize {
+    fn canonicalize(&mut self);
+
+    /// Canonical copy of `self`
+    fn canonical(&self) -> Self
+    where
+        Self: Clone,
+    {
+        let mut copy = self.clone();
+        copy.canonicalize();
+        copy
+    }
+}
+
+impl<T: Canonicalize> Canonicalize for Vec<T> {
+    fn canonicalize(&mut self) {
+        for item in self.iter_mut() {
+            item.canonicalize();
+        }
+    }
+}
+
+impl<T: Canonicalize> Canonicalize for Option<T> {
+    fn canonicalize(&mut self) {
+        if let Some(item) = self {
+            item.canonicalize();
+        }
+    }
+}
+
+/// Sort and deduplicate database cross references
+fn sort_db_tags(tags: &mut Option<Vec<DbTag>>) {
+    if let Some(tags) = tags {
+        tags.sort();
+        tags.dedup();
+    }
+}
+
+/// Position of the variant within the `Seqdesc` choice of the ASN.1 spec
+#[allow(deprecated)]
+fn desc_order(desc: &SeqDesc) -> usize {
+    match desc {
+        SeqDesc::MolType(_) => 0,
+        SeqDesc::Modif(_) => 1,
+        SeqDesc::Method(_) => 2,
+        SeqDesc::Name(_) => 3,
+        SeqDesc::Title(_) => 4,
+        SeqDesc::Org(_) => 5,
+        SeqDesc::Comment(_) => 6,
+        SeqDesc::Num(_) => 7,
+        SeqDesc::MapLoc(_) => 8,
+        SeqDesc::PIR(_) => 9,
+        SeqDesc::Genbank(_) => 10,
+        SeqDesc::Pub(_) => 11,
+        SeqDesc::Region(_) => 12,
+        SeqDesc::User(_) => 13,
+        SeqDesc::SP(_) => 14,
+        SeqDesc::DbXref(_) => 15,
+        SeqDesc::Embl(_) => 16,
+        SeqDesc::CreateDate(_) => 17,
+        SeqDesc::UpdateDate(_) => 18,
+        SeqDesc::PRF(_) => 19,
+        SeqDesc::PDB(_) => 20,
+        SeqDesc::Het(_) => 21,
+        SeqDesc::Source(_) => 22,
+        SeqDesc::MolInfo(_) => 23,
+        SeqDesc::ModelEv(_) => 24,
+        SeqDesc::Unknown(_) => 25,
+    }
+}
+
+#[allow(deprecated)]
+fn canonicalize_desc(desc: &mut SeqDesc) {
+    match desc {
+        SeqDesc::Org(org) => org.canonicalize(),
+        SeqDesc::Source(source) => source.canonicalize(),
+        _ => (),
+    }
+}
+
+/// Descriptors are sorted by kind in spec order. Descriptors of the same kind keep
+/// their relative order.
+impl Canonicalize for Vec<SeqDesc> {
+    fn canonicalize(&mut self) {
+        self.iter_mut().for_each(canonicalize_desc);
+        self.sort_by_key(desc_order);
+    }
+}
+
+impl Canonicalize for OrgRef {
+    fn canonicalize(&mut self) {
+        sort_db_tags(&mut self.db);
+    }
+}
+
+impl Canonicalize for BioSource {
+    fn canonicalize(&mut self) {
+        self.org.canonicalize();
+    }
+}
+
+impl Canonicalize for GeneRef {
+    fn canonicalize(&mut self) {
+        sort_db_tags(&mut self.db);
+    }
+}
+
+impl Canonicalize for SeqFeat {
+    fn canonicalize(&mut self) {
+        match &mut self.data {
+            SeqFeatData::Gene(gene) => gene.canonicalize(),
+            SeqFeatData::Org(org) => org.canonicalize(),
+            SeqFeatData::BioSrc(source) => source.canonicalize(),
+            _ => (),
+        }
+        sort_db_tags(&mut self.dbxref);
+    }
+}
+
+impl Canonicalize for SeqAnnot {
+    fn canonicalize(&mut self) {
+        if let SeqAnnotData::FTable(feats) = &mut self.data {
+            feats.canonicalize();
+        }
+    }
+}
+
+/// Ids are sorted in the order of the `Seq-id` choice
+impl Canonicalize for BioSeq {
+    fn canonicalize(&mut self) {
+        self.id.sort();
+        self.id.dedup();
+        self.descr.canonicalize();
+        self.annot.canonicalize();
+    }
+}
+
+impl Canonicalize for BioSeqSet {
+    fn canonicalize(&mut self) {
+        self.descr.canonicalize();
+        self.seq_set.canonicalize();
+        self.annot.canonicalize();
+    }
+}
+
+impl Canonicalize for SeqEntry {
+    fn canonicalize(&mut self) {
+        match self {
+            Self::Seq(bioseq) => bioseq.canonicalize(),
+            Self::Set(set) => set.canonicalize(),
+        }
+    }
+}
+
+impl Canonicalize for Entrezgene {
+    fn canonicalize(&mut self) {
+        self.gene.canonicalize();
+        self.source.canonicalize();
+    }
+}
+
+#[cfg(feature = "json")]
+/// Sort the keys of every JSON object within `value`
+fn sort_keys(value: serde_json::Value) -> serde_json::Value {
+    use serde_json::Value;
+
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<(String, Value)> = map.into_iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            Value::Object(entries.into_iter().map(|(key, value)| (key, sort_keys(value))).collect())
+        }
+        Value::Array(values) => Value::Array(values.into_iter().map(sort_keys).collect()),
+        value => value,
+    }
+}
+
+#[cfg(feature = "json")]
+/// Serialize the canonical form of `record` as pretty-printed JSON with sorted keys
+///
+/// Output is identical for records which differ only in the order of the lists
+/// sorted by [`Canonicalize`], and is therefore suitable for line-based diffs.
+pub fn canonical_json<T>(record: &T) -> Result<String, String>
+where
+    T: Canonicalize + Clone + serde::Serialize,
+{
+    let value = serde_json::to_value(record.canonical()).map_err(|e| e.to_string())?;
+    serde_json::to_string_pretty(&sort_keys(value)).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::canonical::Canonicalize;
+    use crate::general::{DbTag, ObjectId};
+    use crate::seq::{BioSeq, SeqDesc};
+    use crate::seqfeat::{BioSource, GeneRef, SeqFeat, SeqFeatData};
+    use crate::seqloc::SeqId;
+
+    fn tag(db: &str, id: u64) -> DbTag {
+        DbTag {
+            db: db.into(),
+            tag: ObjectId::Id(id),
+        }
+    }
+
+    #[test]
+    fn canonical_bioseq() {
+        let mut source = BioSource::default();
+        source.org.db = Some(vec![tag("taxon", 562), tag("ATCC", 1), tag("taxon", 562)]);
+
+        let bioseq = BioSeq {
+            id: vec![SeqId::Gi(15), SeqId::Local(ObjectId::Id(1))],
+            descr: Some(vec![
+                SeqDesc::Source(source),
+                SeqDesc::Comment("second".into()),
+                SeqDesc::Title("title".into()),
+                SeqDesc::Comment("first".into()),
+            ]),
+            ..Default::default()
+        };
+        let canonical = bioseq.canonical();
+
+        assert_eq!(canonical.id, vec![SeqId::Local(ObjectId::Id(1)), SeqId::Gi(15)]);
+        match canonical.descr.as_deref().unwrap() {
+            [SeqDesc::Title(_), SeqDesc::Comment(a), SeqDesc::Comment(b), SeqDesc::Source(source)] => {
+                // relative order of the same kind is kept
+                assert_eq!((a.as_str(), b.as_str()), ("second", "first"));
+                assert_eq!(source.org.db, Some(vec![tag("ATCC", 1), tag("taxon", 562)]));
+            }
+            descr => panic!("unexpected order: {:?}", descr),
+        }
+
+        // idempotent
+        assert_eq!(canonical.canonical(), canonical);
+    }
+
+    #[test]
+    fn canonical_feat() {
+        let mut feat = SeqFeat::new(SeqFeatData::Gene(GeneRef::default()));
+        feat.dbxref = Some(vec![tag("b", 2), tag("a", 1)]);
+        feat.canonicalize();
+        assert_eq!(feat.dbxref, Some(vec![tag("a", 1), tag("b", 2)]));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn canonical_json() {
+        let a = BioSeq {
+            id: vec![SeqId::Gi(15), SeqId::Local(ObjectId::Id(1))],
+            ..Default::default()
+        };
+        let b = BioSeq {
+            id: vec![SeqId::Local(ObjectId::Id(1)), SeqId::Gi(15)],
+            ..Default::default()
+        };
+
+        let json = super::canonical_json(&a).unwrap();
+        assert_eq!(json, super::canonical_json(&b).unwrap());
+        let keys: Vec<usize> = ["\"annot\"", "\"descr\"", "\"id\"", "\"inst\""]
+            .iter()
+            .map(|key| json.find(key).unwrap())
+            .collect();
+        assert!(keys.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+}
@@ -0,0 +1,449 @@
+//! ClinVar variation records
+//!
+//! EFetch on [`EntrezDb::ClinVar`] with `rettype=vcv` returns a `ClinVarResult-Set`
+//! document of `VariationArchive` records, which are parsed into [`VariationArchive`]
+//! by [`parse_variation_archives()`]. Each record describes a variant (genes,
+//! locations and HGVS expressions), its aggregate classification along with the
+//! conditions it was classified for, and the individual submissions (SCV records)
+//! supporting that classification.
+//!
+//! Like SRA metadata, ClinVar records are not described by the NCBI ASN.1
+//! specification, so only the fields needed to interpret a classification are kept.
+//! Variants can be connected to [`VariationRef`] features by their dbSNP and dbVar
+//! identifiers (see [`VariationArchive::db_tags()`]).
+
+use crate::general::{DbTag, ObjectId};
+use crate::seqfeat::VariationRef;
+use crate::{EntrezDb, FetchRequest};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+/// A variant along with its classifications and supporting submissions
+pub struct VariationArchive {
+    pub variation_id: u64,
+    /// eg: "VCV000012356"
+    pub accession: String,
+    pub version: Option<u64>,
+    /// eg: "NM_000546.6(TP53):c.743G>A (p.Arg248Gln)"
+    pub name: Option<String>,
+    /// eg: "single nucleotide variant", "Deletion"
+    pub variation_type: Option<String>,
+    /// eg: "classified", "included"
+    pub record_type: Option<String>,
+    pub record_status: Option<String>,
+    pub species: Option<String>,
+    pub date_last_updated: Option<String>,
+
+    pub genes: Vec<ClinVarGene>,
+    pub locations: Vec<SequenceLocation>,
+    /// nucleotide and protein HGVS expressions
+    pub hgvs: Vec<String>,
+    /// identifiers of the variant in other databases (eg: dbSNP)
+    pub xrefs: Vec<ClinVarXref>,
+
+    /// aggregate classifications, one of each type
+    pub classifications: Vec<Classification>,
+    /// individual submissions
+    pub submissions: Vec<ClinicalAssertion>,
+}
+
+impl VariationArchive {
+    /// Aggregate germline classification, if any
+    pub fn germline(&self) -> Option<&Classification> {
+        self.classifications
+            .iter()
+            .find(|classification| classification.r#type == "GermlineClassification")
+    }
+
+    /// dbSNP `rs` numbers of the variant
+    pub fn rs_ids(&self) -> Vec<u64> {
+        self.xrefs
+            .iter()
+            .filter(|xref| xref.db == "dbSNP" && xref.r#type.as_deref() == Some("rs"))
+            .filter_map(|xref| xref.id.trim_start_matches("rs").parse().ok())
+            .collect()
+    }
+
+    /// dbSNP and dbVar identifiers in the form used by [`VariationRef`] (eg: `dbSNP|rs12334`)
+    pub fn db_tags(&self) -> Vec<DbTag> {
+        self.xrefs
+            .iter()
+            .filter_map(|xref| {
+                let id = match (xref.db.as_str(), xref.r#type.as_deref()) {
+                    ("dbSNP", Some("rs")) if !xref.id.starts_with("rs") => format!("rs{}", xref.id),
+                    ("dbSNP" | "dbVar", _) => xref.id.clone(),
+                    _ => return None,
+                };
+                Some(DbTag {
+                    db: xref.db.as_str().into(),
+                    tag: ObjectId::Str(id.into()),
+                })
+            })
+            .collect()
+    }
+
+    /// Whether `variation` refers to this variant by any of its ids
+    pub fn describes(&self, variation: &VariationRef) -> bool {
+        let tags = self.db_tags();
+        variation
+            .id
+            .iter()
+            .chain(variation.other_ids.iter().flatten())
+            .any(|id| {
+                tags.iter()
+                    .any(|tag| tag.db == id.db && tag.tag.as_str() == Some(object_id_label(&id.tag).as_str()))
+            })
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+pub struct ClinVarGene {
+    /// eg: "TP53"
+    pub symbol: String,
+    pub gene_id: Option<u64>,
+    pub full_name: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+/// Placement of a variant on an assembly
+pub struct SequenceLocation {
+    /// eg: "GRCh38"
+    pub assembly: String,
+    pub chr: Option<String>,
+    /// eg: "NC_000017.11"
+    pub accession: Option<String>,
+    /// first position (1-based)
+    pub start: Option<u64>,
+    /// last position (1-based)
+    pub stop: Option<u64>,
+    pub reference_allele: Option<String>,
+    pub alternate_allele: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+/// Identifier in an external database
+pub struct ClinVarXref {
+    /// eg: "dbSNP", "MedGen", "OMIM"
+    pub db: String,
+    pub id: String,
+    /// eg: "rs" for dbSNP identifiers
+    pub r#type: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+/// Aggregate classification of a variant
+pub struct Classification {
+    /// eg: "GermlineClassification", "SomaticClinicalImpact"
+    pub r#type: String,
+    /// eg: "criteria provided, multiple submitters, no conflicts"
+    pub review_status: Option<String>,
+    /// eg: "Pathogenic", "Likely benign"
+    pub description: Option<String>,
+    pub date_last_evaluated: Option<String>,
+    pub conditions: Vec<Condition>,
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+/// Disease or phenotype that a variant was classified for
+pub struct Condition {
+    /// preferred name
+    pub name: Option<String>,
+    pub xrefs: Vec<ClinVarXref>,
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+/// A single submission (SCV record) supporting the classification of a variant
+pub struct ClinicalAssertion {
+    /// eg: "SCV000033431"
+    pub accession: String,
+    pub version: Option<u64>,
+    pub submitter: Option<String>,
+    pub submission_date: Option<String>,
+    pub review_status: Option<String>,
+    /// submitted classification (eg: "Pathogenic")
+    pub classification: Option<String>,
+    pub date_last_evaluated: Option<String>,
+    pub comment: Option<String>,
+    /// eg: "germline", "somatic"
+    pub origins: Vec<String>,
+    pub conditions: Vec<Condition>,
+}
+
+fn object_id_label(id: &ObjectId) -> String {
+    match id {
+        ObjectId::Id(id) => id.to_string(),
+        ObjectId::Str(id) => id.to_string(),
+    }
+}
+
+fn attributes(e: &BytesStart) -> HashMap<String, String> {
+    e.attributes()
+        .flatten()
+        .filter_map(|attr| {
+            let key = String::from_utf8(attr.key.as_ref().to_vec()).ok()?;
+            let value = attr.unescape_value().ok()?.to_string();
+            Some((key, value))
+        })
+        .collect()
+}
+
+/// Parse a `ClinVarResult-Set` document, or a single `VariationArchive`
+pub fn parse_variation_archives(response: &str) -> Result<Vec<VariationArchive>, String> {
+    let mut reader = Reader::from_str(response);
+    reader.trim_text(true);
+
+    let mut parser = Parser::default();
+
+    loop {
+        let (e, empty) = match reader.read_event() {
+            Ok(Event::Start(e)) => (e, false),
+            Ok(Event::Empty(e)) => (e, true),
+            Ok(Event::Text(text)) => {
+                let text = text.unescape().map_err(|e| format!("{:?}", e))?.to_string();
+                parser.read_text(text);
+                continue;
+            }
+            Ok(Event::End(_)) => {
+                parser.path.pop();
+                continue;
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(format!("XML parsing error: {:?}", e)),
+            _ => continue,
+        };
+
+        parser.read_element(&e);
+        if !empty {
+            parser.path.push(e.name().as_ref().to_vec());
+        }
+    }
+
+    if parser.archives.is_empty() {
+        return Err("No <VariationArchive> found in response.".to_string());
+    }
+    Ok(parser.archives)
+}
+
+#[derive(Default)]
+struct Parser {
+    archives: Vec<VariationArchive>,
+    path: Vec<Vec<u8>>,
+
+    /// Whether the current `ElementValue` gives a preferred name
+    preferred: bool,
+}
+
+impl Parser {
+    fn within(&self, name: &[u8]) -> bool {
+        self.path.iter().any(|element| element == name)
+    }
+
+    /// Name of the element `depth` levels above the current one
+    fn element(&self, depth: usize) -> Option<&[u8]> {
+        self.path
+            .len()
+            .checked_sub(depth + 1)
+            .and_then(|i| self.path.get(i))
+            .map(|element| element.as_slice())
+    }
+
+    /// Conditions of the enclosing submission or aggregate classification
+    fn conditions(&mut self) -> Option<&mut Vec<Condition>> {
+        let in_assertion = self.within(b"ClinicalAssertion");
+        let in_aggregate = self.within(b"Classifications");
+        let archive = self.archives.last_mut()?;
+        if in_assertion {
+            Some(&mut archive.submissions.last_mut()?.conditions)
+        } else if in_aggregate {
+            Some(&mut archive.classifications.last_mut()?.conditions)
+        } else {
+            None
+        }
+    }
+
+    /// Fill the current record from the attributes of an element
+    fn read_element(&mut self, e: &BytesStart) {
+        let mut attributes = attributes(e);
+        let mut take = |key: &str| attributes.remove(key);
+        let name = e.name();
+        let name = name.as_ref();
+
+        if name == b"VariationArchive" {
+            self.archives.push(VariationArchive {
+                variation_id: take("VariationID").and_then(|n| n.parse().ok()).unwrap_or_default(),
+                accession: take("Accession").unwrap_or_default(),
+                version: take("Version").and_then(|n| n.parse().ok()),
+                name: take("VariationName"),
+                variation_type: take("VariationType"),
+                record_type: take("RecordType"),
+                date_last_updated: take("DateLastUpdated"),
+                ..VariationArchive::default()
+            });
+            return;
+        }
+        if name == b"ElementValue" {
+            self.preferred = take("Type").as_deref() == Some("Preferred");
+        }
+
+        let in_assertion = self.within(b"ClinicalAssertion");
+        let in_aggregate = self.within(b"Classifications");
+        let in_gene = self.within(b"Gene");
+        let parent = self.element(0).map(|parent| parent.to_vec());
+        match (parent.as_deref(), name) {
+            (Some(b"Trait"), b"XRef") => {
+                let xref = ClinVarXref {
+                    db: take("DB").unwrap_or_default(),
+                    id: take("ID").unwrap_or_default(),
+                    r#type: take("Type"),
+                };
+                if let Some(condition) = self.conditions().and_then(|c| c.last_mut()) {
+                    condition.xrefs.push(xref);
+                }
+                return;
+            }
+            (Some(b"TraitSet"), b"Trait") => {
+                if let Some(conditions) = self.conditions() {
+                    conditions.push(Condition::default());
+                }
+                return;
+            }
+            _ => (),
+        }
+
+        let archive = match self.archives.last_mut() {
+            Some(archive) => archive,
+            None => return,
+        };
+        match (parent.as_deref(), name) {
+            (Some(b"ClinicalAssertionList"), b"ClinicalAssertion") => {
+                archive.submissions.push(ClinicalAssertion {
+                    submission_date: take("SubmissionDate"),
+                    ..ClinicalAssertion::default()
+                })
+            }
+            (Some(b"ClinicalAssertion"), b"ClinVarAccession") => {
+                if let Some(submission) = archive.submissions.last_mut() {
+                    submission.accession = take("Accession").unwrap_or_default();
+                    submission.version = take("Version").and_then(|n| n.parse().ok());
+                    submission.submitter = take("SubmitterName");
+                }
+            }
+            (Some(b"ClinicalAssertion"), b"Classification") => {
+                if let Some(submission) = archive.submissions.last_mut() {
+                    submission.date_last_evaluated = take("DateLastEvaluated");
+                }
+            }
+            (Some(b"Classifications"), r#type) => archive.classifications.push(Classification {
+                r#type: String::from_utf8_lossy(r#type).to_string(),
+                date_last_evaluated: take("DateLastEvaluated"),
+                ..Classification::default()
+            }),
+            _ if in_assertion => (),
+            (Some(b"GeneList"), b"Gene") => archive.genes.push(ClinVarGene {
+                symbol: take("Symbol").unwrap_or_default(),
+                gene_id: take("GeneID").and_then(|n| n.parse().ok()),
+                full_name: take("FullName"),
+            }),
+            (Some(b"Location"), b"SequenceLocation") if !in_gene => archive.locations.push(SequenceLocation {
+                assembly: take("Assembly").unwrap_or_default(),
+                chr: take("Chr"),
+                accession: take("Accession"),
+                start: take("start").and_then(|n| n.parse().ok()),
+                stop: take("stop").and_then(|n| n.parse().ok()),
+                reference_allele: take("referenceAlleleVCF"),
+                alternate_allele: take("alternateAlleleVCF"),
+            }),
+            (Some(b"XRefList"), b"XRef") if !in_aggregate => archive.xrefs.push(ClinVarXref {
+                db: take("DB").unwrap_or_default(),
+                id: take("ID").unwrap_or_default(),
+                r#type: take("Type"),
+            }),
+            _ => (),
+        }
+    }
+
+    /// Fill the current record from the text of the current element
+    fn read_text(&mut self, text: String) {
+        let in_assertion = self.within(b"ClinicalAssertion");
+        let in_aggregate = self.within(b"Classifications");
+        // fields of the classification itself, rather than of its conditions
+        let classification_field = self.element(2) == Some(b"Classifications");
+        let (parent, element) = (
+            self.element(1).map(|element| element.to_vec()),
+            self.element(0).map(|element| element.to_vec()),
+        );
+
+        if element.as_deref() == Some(b"ElementValue")
+            && self.preferred
+            && self.element(2) == Some(b"Trait")
+        {
+            if let Some(condition) = self.conditions().and_then(|c| c.last_mut()) {
+                condition.name = Some(text);
+            }
+            return;
+        }
+
+        let archive = match self.archives.last_mut() {
+            Some(archive) => archive,
+            None => return,
+        };
+        if in_assertion {
+            let submission = match archive.submissions.last_mut() {
+                Some(submission) => submission,
+                None => return,
+            };
+            match (parent.as_deref(), element.as_deref()) {
+                (Some(b"Classification"), Some(b"ReviewStatus")) => {
+                    submission.review_status = Some(text)
+                }
+                (
+                    Some(b"Classification"),
+                    Some(b"GermlineClassification" | b"SomaticClinicalImpact" | b"OncogenicityClassification"),
+                ) => submission.classification = Some(text),
+                (Some(b"Classification"), Some(b"Comment")) => submission.comment = Some(text),
+                (Some(b"Sample"), Some(b"Origin")) => submission.origins.push(text),
+                _ => (),
+            }
+            return;
+        }
+        if in_aggregate {
+            let classification = match archive.classifications.last_mut() {
+                Some(classification) => classification,
+                None => return,
+            };
+            match element.as_deref() {
+                Some(b"ReviewStatus") if classification_field => {
+                    classification.review_status = Some(text)
+                }
+                Some(b"Description") if classification_field => {
+                    classification.description = Some(text)
+                }
+                _ => (),
+            }
+            return;
+        }
+        match (parent.as_deref(), element.as_deref()) {
+            (Some(b"VariationArchive"), Some(b"RecordStatus")) => archive.record_status = Some(text),
+            (Some(b"VariationArchive" | b"ClassifiedRecord" | b"IncludedRecord"), Some(b"Species")) => {
+                archive.species = Some(text)
+            }
+            (Some(b"NucleotideExpression" | b"ProteinExpression"), Some(b"Expression"))
+                if !archive.hgvs.contains(&text) =>
+            {
+                archive.hgvs.push(text)
+            }
+            _ => (),
+        }
+    }
+}
+
+/// Fetch ClinVar records by variation id
+pub fn fetch_clinvar<S: AsRef<str>>(ids: &[S]) -> Result<Vec<VariationArchive>, String> {
+    let response = FetchRequest::with_ids(EntrezDb::ClinVar, ids)
+        .with_defaults()
+        .fetch_text()?;
+    parse_variation_archives(response.as_str())
+}
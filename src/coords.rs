@@ -0,0 +1,253 @@
+//! Sequence positions with an explicit origin
+//!
+//! ASN.1 records count positions from 0, while GenBank flat files, feature tables,
+//! GFF3 and EFetch `seq_start`/`seq_stop` count them from 1. Raw integers leave the
+//! origin implicit, which is a common source of off-by-one errors. [`Pos0`] and
+//! [`Pos1`] can only be converted into each other explicitly, and construction from
+//! raw integers is checked (eg: negative or zero positions are rejected).
+//!
+//! [`Range0`] is a closed range of positions counted from 0, as given by
+//! [`SeqLoc::range0()`](crate::seqloc::SeqLoc::range0).
+//!
+//! # Example
+//! ```
+//! use ncbi::coords::{Pos0, Pos1, Range0};
+//!
+//! let range = Range0::from_i64(99, 199).unwrap();
+//! assert_eq!((range.start1().get(), range.end1().get()), (100, 200));
+//! assert_eq!(range.len(), 101);
+//! assert_eq!(Pos1::new(1).unwrap().to_zero_based(), Pos0::new(0));
+//! assert!(Pos1::new(0).is_none());
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+#[serde(transparent)]
+/// Position counted from 0, as in ASN.1 records
+pub struct Pos0(u64);
+
+impl Pos0 {
+    pub fn new(pos: u64) -> Self {
+        Self(pos)
+    }
+
+    pub fn get(self) -> u64 {
+        self.0
+    }
+
+    /// Same position, counted from 1
+    pub fn to_one_based(self) -> Pos1 {
+        Pos1(self.0 + 1)
+    }
+}
+
+/// Negative positions are rejected
+impl TryFrom<i64> for Pos0 {
+    type Error = String;
+
+    fn try_from(pos: i64) -> Result<Self, Self::Error> {
+        u64::try_from(pos)
+            .map(Self)
+            .map_err(|_| format!("Invalid 0-based position {}", pos))
+    }
+}
+
+impl From<Pos1> for Pos0 {
+    fn from(pos: Pos1) -> Self {
+        pos.to_zero_based()
+    }
+}
+
+impl From<Pos0> for i64 {
+    fn from(pos: Pos0) -> Self {
+        pos.0 as i64
+    }
+}
+
+impl fmt::Display for Pos0 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[serde(try_from = "u64", into = "u64")]
+/// Position counted from 1, as in GenBank flat files, feature tables and GFF3
+///
+/// Always at least 1.
+pub struct Pos1(u64);
+
+impl Pos1 {
+    /// Position `pos`, unless it is 0
+    pub fn new(pos: u64) -> Option<Self> {
+        (pos > 0).then_some(Self(pos))
+    }
+
+    pub fn get(self) -> u64 {
+        self.0
+    }
+
+    /// Same position, counted from 0
+    pub fn to_zero_based(self) -> Pos0 {
+        Pos0(self.0 - 1)
+    }
+}
+
+/// 0 is rejected
+impl TryFrom<u64> for Pos1 {
+    type Error = String;
+
+    fn try_from(pos: u64) -> Result<Self, Self::Error> {
+        Self::new(pos).ok_or_else(|| "Invalid 1-based position 0".to_string())
+    }
+}
+
+impl From<Pos1> for u64 {
+    fn from(pos: Pos1) -> Self {
+        pos.0
+    }
+}
+
+impl From<Pos1> for i64 {
+    fn from(pos: Pos1) -> Self {
+        pos.0 as i64
+    }
+}
+
+impl From<Pos0> for Pos1 {
+    fn from(pos: Pos0) -> Self {
+        pos.to_one_based()
+    }
+}
+
+impl FromStr for Pos1 {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        text.parse::<u64>()
+            .ok()
+            .and_then(Self::new)
+            .ok_or_else(|| format!("Invalid 1-based position `{}`", text))
+    }
+}
+
+impl fmt::Display for Pos1 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+/// Closed range of positions counted from 0, from `start` to `end` inclusive
+///
+/// `start` is never after `end`, regardless of strand.
+pub struct Range0 {
+    start: Pos0,
+    end: Pos0,
+}
+
+impl Range0 {
+    /// Range from `start` to `end`, unless `start` is after `end`
+    pub fn new(start: Pos0, end: Pos0) -> Option<Self> {
+        (start <= end).then_some(Self { start, end })
+    }
+
+    /// Range of the raw coordinates of an ASN.1 record (eg: [`SeqInterval`](crate::seqloc::SeqInterval))
+    pub fn from_i64(from: i64, to: i64) -> Option<Self> {
+        Self::new(Pos0::try_from(from).ok()?, Pos0::try_from(to).ok()?)
+    }
+
+    /// Range from `start` to `end`, counted from 1 (eg: columns of GFF3)
+    pub fn from_one_based(start: Pos1, end: Pos1) -> Option<Self> {
+        Self::new(start.to_zero_based(), end.to_zero_based())
+    }
+
+    /// Range covering a whole sequence of `length` residues
+    pub fn whole(length: u64) -> Option<Self> {
+        let end = length.checked_sub(1)?;
+        Self::new(Pos0(0), Pos0(end))
+    }
+
+    pub fn start(&self) -> Pos0 {
+        self.start
+    }
+
+    pub fn end(&self) -> Pos0 {
+        self.end
+    }
+
+    /// First position, counted from 1
+    pub fn start1(&self) -> Pos1 {
+        self.start.to_one_based()
+    }
+
+    /// Last position, counted from 1
+    pub fn end1(&self) -> Pos1 {
+        self.end.to_one_based()
+    }
+
+    /// Number of positions covered, which is never 0
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> u64 {
+        self.end.0 - self.start.0 + 1
+    }
+
+    pub fn contains(&self, pos: Pos0) -> bool {
+        self.start <= pos && pos <= self.end
+    }
+
+    /// Smallest range covering both ranges
+    pub fn union(&self, other: &Self) -> Self {
+        Self {
+            start: self.start.min(other.start),
+            end: self.end.max(other.end),
+        }
+    }
+
+    /// Range extended by `flank` positions on either side, without going below 0
+    pub fn extend(&self, flank: u64) -> Self {
+        Self {
+            start: Pos0(self.start.0.saturating_sub(flank)),
+            end: Pos0(self.end.0.saturating_add(flank)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::coords::{Pos0, Pos1, Range0};
+
+    #[test]
+    fn test_conversions() {
+        assert_eq!(Pos0::new(0).to_one_based(), Pos1::new(1).unwrap());
+        assert_eq!(Pos0::from(Pos1::new(10).unwrap()).get(), 9);
+        assert!(Pos0::try_from(-1).is_err());
+        assert_eq!(i64::from(Pos0::try_from(5).unwrap()), 5);
+        assert_eq!(i64::from(Pos1::new(5).unwrap()), 5);
+        assert_eq!("12".parse::<Pos1>().unwrap().to_zero_based().get(), 11);
+        assert_eq!("0".parse::<Pos1>().unwrap_err(), "Invalid 1-based position `0`");
+        assert!("<12".parse::<Pos1>().is_err());
+
+        // 1-based positions cannot be 0 once deserialized either
+        assert!(serde_json::from_str::<Pos1>("0").is_err());
+        assert_eq!(serde_json::to_string(&Pos1::new(3).unwrap()).unwrap(), "3");
+    }
+
+    #[test]
+    fn test_range() {
+        let range = Range0::from_i64(10, 19).unwrap();
+        assert_eq!((range.start1().get(), range.end1().get(), range.len()), (11, 20, 10));
+        assert!(range.contains(Pos0::new(19)) && !range.contains(Pos0::new(20)));
+        assert_eq!(Range0::from_one_based(range.start1(), range.end1()), Some(range));
+        assert!(Range0::from_i64(20, 10).is_none());
+        assert!(Range0::from_i64(-1, 10).is_none());
+
+        assert_eq!(Range0::whole(5), Range0::from_i64(0, 4));
+        assert_eq!(Range0::whole(0), None);
+        assert_eq!(range.extend(15), Range0::from_i64(0, 34).unwrap());
+        assert_eq!(range.union(&Range0::from_i64(30, 31).unwrap()), Range0::from_i64(10, 31).unwrap());
+    }
+}
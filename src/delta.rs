@@ -0,0 +1,278 @@
+//! Materialization of sequences which are built from other sequences
+//!
+//! Delta and segmented sequences (eg: RefSeq chromosomes, or WGS scaffolds) do not
+//! contain residues themselves, but refer to regions of component sequences by
+//! accession. [`DeltaResolver`] fetches the referenced components, recursively if
+//! they are themselves built from other sequences, and assembles the full sequence
+//! as Entrez does when FASTA is requested.
+//!
+//! Components are fetched as native XML from [`EntrezDb::Nucleotide`] (or the
+//! database given to [`DeltaResolver::with_db()`]), and their residues are cached, so
+//! that a component referenced several times is only fetched once.
+//!
+//! # Example
+//! ```ignore
+//! let mut resolver = DeltaResolver::new().with_max_depth(2);
+//! let residues = resolver.residues(&scaffold)?;
+//! ```
+
+use crate::seq::{extract_range, BioSeq, DeltaSeq, Mol, SeqExt, SeqInst};
+use crate::seqloc::{SeqId, SeqLoc};
+use crate::{parse_xml, DataType, EntrezDb, FetchRequest};
+use std::collections::HashMap;
+
+/// Default for [`DeltaResolver::with_max_depth()`]
+pub const DEFAULT_MAX_DEPTH: usize = 4;
+
+/// Fetches and caches the components of delta and segmented sequences
+pub struct DeltaResolver {
+    db: EntrezDb,
+    max_depth: usize,
+    /// residues of components, keyed by [`fetch_key()`]
+    cache: HashMap<String, String>,
+}
+
+impl Default for DeltaResolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DeltaResolver {
+    pub fn new() -> Self {
+        Self {
+            db: EntrezDb::Nucleotide,
+            max_depth: DEFAULT_MAX_DEPTH,
+            cache: HashMap::new(),
+        }
+    }
+
+    /// Fetch components from `db` instead of [`EntrezDb::Nucleotide`]
+    pub fn with_db(mut self, db: EntrezDb) -> Self {
+        self.db = db;
+        self
+    }
+
+    /// Maximum number of nested components to follow
+    ///
+    /// A depth of 1 only fetches the components referenced by the sequence itself.
+    pub fn with_max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = depth;
+        self
+    }
+
+    /// Use `residues` for the component `id`, instead of fetching it
+    pub fn insert(&mut self, id: &SeqId, residues: &str) -> Result<(), String> {
+        let key = fetch_key(id).ok_or_else(|| format!("Cannot fetch component {:?}", id))?;
+        self.cache.insert(key, residues.to_string());
+        Ok(())
+    }
+
+    /// Number of cached components
+    pub fn cached(&self) -> usize {
+        self.cache.len()
+    }
+
+    /// Full sequence of `bioseq` as a one-letter IUPAC string
+    ///
+    /// Residues given by the record itself are used as is. Gaps, and literals of
+    /// unknown sequence, are filled with "N" (or "X" for proteins).
+    pub fn residues(&mut self, bioseq: &BioSeq) -> Result<String, String> {
+        let inst = bioseq.inst.as_ref().ok_or("Bioseq has no instance data")?;
+        self.inst_residues(inst, 0)
+    }
+
+    fn inst_residues(&mut self, inst: &SeqInst, depth: usize) -> Result<String, String> {
+        if inst.seq_data.is_some() {
+            return inst.residues();
+        }
+        let unknown = match inst.mol {
+            Mol::AA => "X",
+            _ => "N",
+        };
+
+        let residues = match &inst.ext {
+            Some(SeqExt::Delta(parts)) => {
+                let mut residues = String::new();
+                for part in parts {
+                    match part {
+                        DeltaSeq::Loc(loc) => residues.push_str(&self.loc_residues(loc, depth)?),
+                        DeltaSeq::Literal(literal) => match &literal.seq_data {
+                            Some(data) => match data.to_iupac(Some(literal.length)) {
+                                Ok(seq) => residues.push_str(&seq),
+                                // gaps do not have residues
+                                Err(_) => residues.push_str(&unknown.repeat(literal.length as usize)),
+                            },
+                            None => residues.push_str(&unknown.repeat(literal.length as usize)),
+                        },
+                    }
+                }
+                residues
+            }
+            Some(SeqExt::Seg(locs)) => locs
+                .iter()
+                .map(|loc| self.loc_residues(loc, depth))
+                .collect::<Result<_, _>>()?,
+            Some(SeqExt::Ref(loc)) => self.loc_residues(loc, depth)?,
+            _ => return Err("Seq-inst has neither sequence data nor components".to_string()),
+        };
+
+        match inst.length {
+            Some(length) if length as usize != residues.len() => Err(format!(
+                "Assembled {} residues, but the sequence has length {}",
+                residues.len(),
+                length
+            )),
+            _ => Ok(residues),
+        }
+    }
+
+    fn loc_residues(&mut self, loc: &SeqLoc, depth: usize) -> Result<String, String> {
+        match loc {
+            SeqLoc::Null | SeqLoc::Empty(_) => Ok(String::new()),
+            SeqLoc::Whole(id) => self.component(id, depth).cloned(),
+            SeqLoc::Int(interval) => {
+                let residues = self.component(&interval.id, depth)?;
+                extract_range(residues, interval.from, interval.to, interval.strand.as_ref())
+            }
+            SeqLoc::PackedInt(intervals) => intervals
+                .iter()
+                .map(|interval| self.loc_residues(&SeqLoc::Int(interval.clone()), depth))
+                .collect(),
+            SeqLoc::Pnt(point) => {
+                let residues = self.component(&point.id, depth)?;
+                extract_range(residues, point.point, point.point, point.strand.as_ref())
+            }
+            SeqLoc::Mix(mix) => mix.0.iter().map(|loc| self.loc_residues(loc, depth)).collect(),
+            _ => Err("Unsupported Seq-loc variant for a component".to_string()),
+        }
+    }
+
+    /// Residues of the component `id`, fetching and resolving it if not cached
+    fn component(&mut self, id: &SeqId, depth: usize) -> Result<&String, String> {
+        let key = fetch_key(id).ok_or_else(|| format!("Cannot fetch component {:?}", id))?;
+        if !self.cache.contains_key(&key) {
+            if depth >= self.max_depth {
+                return Err(format!("Component {} exceeds the maximum depth of {}", key, self.max_depth));
+            }
+            let bioseq = self.fetch(id, &key)?;
+            let inst = bioseq.inst.as_ref().ok_or_else(|| format!("Component {} has no instance data", key))?;
+            let residues = self.inst_residues(inst, depth + 1)?;
+            self.cache.insert(key.clone(), residues);
+        }
+        Ok(&self.cache[&key])
+    }
+
+    fn fetch(&self, id: &SeqId, key: &str) -> Result<BioSeq, String> {
+        let response = FetchRequest::new(self.db, key)
+            .rettype("native")
+            .retmode("xml")
+            .fetch_text()?;
+        let set = match parse_xml(&response)? {
+            DataType::BioSeqSet(set) => set,
+            _ => return Err(format!("Response for component {} is not a Bioseq-set", key)),
+        };
+        let bioseqs = set.bioseqs();
+        bioseqs
+            .iter()
+            .find(|bioseq| bioseq.id.contains(id))
+            .or(bioseqs.first())
+            .map(|bioseq| (*bioseq).clone())
+            .ok_or_else(|| format!("No Bioseq found for component {}", key))
+    }
+}
+
+/// Identifier used to fetch `id` from Entrez, such as a versioned accession or GI
+fn fetch_key(id: &SeqId) -> Option<String> {
+    match id {
+        SeqId::Gi(gi) => Some(gi.to_string()),
+        SeqId::Local(_) | SeqId::General(_) => None,
+        _ => {
+            let text = id.textseq_id()?;
+            let accession = text.accession.as_ref()?;
+            Some(match text.version {
+                Some(version) => format!("{}.{}", accession, version),
+                None => accession.clone(),
+            })
+        }
+    }
+}
+
+/// Full sequence of `bioseq`, fetching components with a new [`DeltaResolver`]
+pub fn resolve_residues(bioseq: &BioSeq) -> Result<String, String> {
+    DeltaResolver::new().residues(bioseq)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::delta::DeltaResolver;
+    use crate::seq::{BioSeq, DeltaSeq, Mol, Repr, SeqExt, SeqInst, SeqLiteral};
+    use crate::seqloc::{NaStrand, SeqId, SeqInterval, SeqLoc, TextseqId};
+
+    fn accession(acc: &str) -> SeqId {
+        SeqId::Genbank(TextseqId {
+            accession: Some(acc.to_string()),
+            version: Some(1),
+            ..Default::default()
+        })
+    }
+
+    fn interval(id: SeqId, from: i64, to: i64, strand: NaStrand) -> DeltaSeq {
+        DeltaSeq::Loc(SeqLoc::Int(SeqInterval {
+            id,
+            from,
+            to,
+            strand: Some(strand),
+            ..Default::default()
+        }))
+    }
+
+    fn delta(parts: Vec<DeltaSeq>, length: u64) -> BioSeq {
+        let inst = SeqInst {
+            repr: Repr::Delta,
+            mol: Mol::DNA,
+            length: Some(length),
+            ext: Some(SeqExt::Delta(parts)),
+            ..Default::default()
+        };
+        BioSeq {
+            inst: Some(inst),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn resolve_cached_components() {
+        let mut resolver = DeltaResolver::new();
+        resolver.insert(&accession("AAA000001"), "ACGTACGTAA").unwrap();
+        resolver.insert(&accession("AAA000002"), "GGGCCC").unwrap();
+
+        let gap = DeltaSeq::Literal(SeqLiteral {
+            length: 3,
+            full: None,
+            seq_data: None,
+        });
+        let scaffold = delta(
+            vec![
+                interval(accession("AAA000001"), 0, 3, NaStrand::Plus),
+                gap,
+                interval(accession("AAA000002"), 0, 2, NaStrand::Minus),
+            ],
+            10,
+        );
+        assert_eq!(resolver.residues(&scaffold).unwrap(), "ACGTNNNCCC");
+        assert_eq!(resolver.cached(), 2);
+
+        // wrong length is reported
+        let scaffold = delta(vec![interval(accession("AAA000001"), 0, 3, NaStrand::Plus)], 5);
+        assert!(resolver.residues(&scaffold).is_err());
+    }
+
+    #[test]
+    fn depth_limit() {
+        let mut resolver = DeltaResolver::new().with_max_depth(0);
+        let scaffold = delta(vec![interval(accession("AAA000003"), 0, 3, NaStrand::Plus)], 4);
+        let error = resolver.residues(&scaffold).unwrap_err();
+        assert!(error.contains("maximum depth"));
+    }
+}
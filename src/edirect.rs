@@ -0,0 +1,319 @@
+//! Interoperability with Entrez Direct (EDirect) shell pipelines
+//!
+//! EDirect commands (`esearch`, `efetch`, `xtract`, ...) pass plain text between
+//! each other, so that results can be exchanged with shell pipelines through files
+//! or standard streams:
+//!
+//! - UID lists, one per line, as written by `efetch -format uid` and read by
+//!   `epost -input`: [`read_uids()`] and [`write_uids()`]
+//! - the `<ENTREZ_DIRECT>` message piped from one command to the next, which refers
+//!   to results stored on the history server: [`EDirectMessage`]
+//! - tab-delimited document summaries, as written by
+//!   `xtract -pattern DocSum -element Id ...`: [`DocSumTable`]
+//!
+//! # Example
+//! ```ignore
+//! // esearch -db protein -query "..." | efetch -format uid > ids.txt
+//! let ids = read_uid_file("ids.txt")?;
+//! let set = FetchRequest::new(EntrezDb::Protein, &ids.join(",")).fetch()?;
+//! ```
+
+use crate::eutils::{read_docsums, DocSumItem};
+use crate::{open_local, EntrezDb};
+use quick_xml::escape::escape;
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use std::io::{BufRead, Write};
+use std::path::Path;
+
+/// Read a list of UIDs (or accessions), one per line
+///
+/// Surrounding whitespace and blank lines are ignored.
+pub fn read_uids<R: BufRead>(reader: R) -> Result<Vec<String>, String> {
+    let mut ids = Vec::new();
+    for (index, line) in reader.lines().enumerate() {
+        let line = line.map_err(|e| e.to_string())?;
+        let id = line.trim();
+        if id.is_empty() {
+            continue;
+        }
+        if id.contains(char::is_whitespace) {
+            return Err(format!("Line {}: expected a single UID, found `{}`", index + 1, id));
+        }
+        ids.push(id.to_string());
+    }
+    Ok(ids)
+}
+
+/// Read the list of UIDs saved at `path`, which may be gzip compressed
+pub fn read_uid_file(path: impl AsRef<Path>) -> Result<Vec<String>, String> {
+    let path = path.as_ref();
+    read_uids(open_local(path)?).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+/// Write `ids` one per line, as `efetch -format uid`
+pub fn write_uids<W: Write, S: AsRef<str>>(mut writer: W, ids: &[S]) -> Result<(), String> {
+    for id in ids {
+        writeln!(writer, "{}", id.as_ref()).map_err(|e| e.to_string())?;
+    }
+    writer.flush().map_err(|e| e.to_string())
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+/// `<ENTREZ_DIRECT>` message passed between EDirect commands
+///
+/// Results of `esearch` and `epost` are stored on the history server, and identified
+/// by [`Self::web_env`] and [`Self::query_key`]. UIDs may also be given directly in
+/// the message.
+pub struct EDirectMessage {
+    pub db: EntrezDb,
+    pub web_env: Option<String>,
+    pub query_key: Option<u64>,
+    /// number of records referred to
+    pub count: Option<u64>,
+    /// number of commands which have handled the message
+    pub step: Option<u64>,
+    /// UIDs given in the message rather than stored on the history server
+    pub ids: Vec<String>,
+}
+
+impl EDirectMessage {
+    pub fn new(db: EntrezDb) -> Self {
+        Self {
+            db,
+            web_env: None,
+            query_key: None,
+            count: None,
+            step: None,
+            ids: Vec::new(),
+        }
+    }
+
+    /// Message giving `ids` directly, to be read by EDirect commands (eg: `efetch`)
+    pub fn from_ids<S: AsRef<str>>(db: EntrezDb, ids: &[S]) -> Self {
+        Self {
+            count: Some(ids.len() as u64),
+            step: Some(1),
+            ids: ids.iter().map(|id| id.as_ref().to_string()).collect(),
+            ..Self::new(db)
+        }
+    }
+
+    /// Parse a message read from an EDirect command
+    pub fn from_xml(text: &str) -> Result<Self, String> {
+        let mut reader = Reader::from_str(text);
+        reader.trim_text(true);
+
+        let mut found = false;
+        let mut db: Option<EntrezDb> = None;
+        let mut message = Self::new(EntrezDb::PubMed);
+        let mut element: Vec<u8> = Vec::new();
+        let number = |text: &str, name: &str| {
+            text.parse::<u64>().map_err(|_| format!("Invalid {} `{}` in EDirect message", name, text))
+        };
+
+        loop {
+            match reader.read_event() {
+                Ok(Event::Start(e)) => {
+                    if e.name().as_ref() == b"ENTREZ_DIRECT" {
+                        found = true;
+                    }
+                    element = e.name().as_ref().to_vec();
+                }
+                Ok(Event::Text(text)) => {
+                    let text = text.unescape().map_err(|e| format!("{:?}", e))?;
+                    let text = text.trim();
+                    match element.as_slice() {
+                        b"Db" => db = Some(text.parse()?),
+                        b"WebEnv" => message.web_env = Some(text.to_string()),
+                        b"QueryKey" => message.query_key = Some(number(text, "QueryKey")?),
+                        b"Count" => message.count = Some(number(text, "Count")?),
+                        b"Step" => message.step = Some(number(text, "Step")?),
+                        b"Id" => message.ids.push(text.to_string()),
+                        _ => (),
+                    }
+                }
+                Ok(Event::End(_)) => element.clear(),
+                Ok(Event::Eof) => break,
+                Err(e) => return Err(format!("XML parsing error: {:?}", e)),
+                _ => (),
+            }
+        }
+
+        if !found {
+            return Err("No <ENTREZ_DIRECT> found in message.".to_string());
+        }
+        message.db = db.ok_or("No <Db> in EDirect message".to_string())?;
+        Ok(message)
+    }
+
+    /// Message as written by EDirect commands, to be piped into another command
+    pub fn to_xml(&self) -> String {
+        let mut xml = String::from("<ENTREZ_DIRECT>\n");
+        xml.push_str(&format!("  <Db>{}</Db>\n", self.db));
+        if let Some(web_env) = &self.web_env {
+            xml.push_str(&format!("  <WebEnv>{}</WebEnv>\n", escape(web_env)));
+        }
+        if let Some(query_key) = self.query_key {
+            xml.push_str(&format!("  <QueryKey>{}</QueryKey>\n", query_key));
+        }
+        if let Some(count) = self.count {
+            xml.push_str(&format!("  <Count>{}</Count>\n", count));
+        }
+        if let Some(step) = self.step {
+            xml.push_str(&format!("  <Step>{}</Step>\n", step));
+        }
+        for id in self.ids.iter() {
+            xml.push_str(&format!("  <Id>{}</Id>\n", escape(id)));
+        }
+        xml.push_str("</ENTREZ_DIRECT>\n");
+        xml
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+/// Tab-delimited document summaries, one row per record
+///
+/// Rows have no header, as written by `xtract`, so columns are named by the caller.
+/// Missing values are empty.
+pub struct DocSumTable {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl DocSumTable {
+    /// Read the rows of an `xtract` export, naming their fields by `columns`
+    ///
+    /// Rows may have fewer fields than `columns`, as when trailing values are missing.
+    pub fn from_tsv(text: &str, columns: &[&str]) -> Result<Self, String> {
+        let mut rows = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let mut row: Vec<String> = line.split('\t').map(str::to_string).collect();
+            if row.len() > columns.len() {
+                return Err(format!(
+                    "Line {}: expected {} columns, found {}",
+                    index + 1,
+                    columns.len(),
+                    row.len()
+                ));
+            }
+            row.resize(columns.len(), String::new());
+            rows.push(row);
+        }
+        Ok(Self {
+            columns: columns.iter().map(|column| column.to_string()).collect(),
+            rows,
+        })
+    }
+
+    /// Table of the `DocSum`s of an ESummary (version 1) response
+    ///
+    /// Columns are `Item` names, and "Id" is the UID of each record, as for
+    /// `xtract -pattern DocSum -element Id ...`. Only top-level items are available.
+    pub fn from_esummary(response: &str, columns: &[&str]) -> Result<Self, String> {
+        let rows = read_docsums(response)?
+            .into_iter()
+            .map(|(id, items)| {
+                columns
+                    .iter()
+                    .map(|column| match *column {
+                        "Id" => id.clone(),
+                        name => DocSumItem::value(&items, name).unwrap_or_default(),
+                    })
+                    .collect()
+            })
+            .collect();
+        Ok(Self {
+            columns: columns.iter().map(|column| column.to_string()).collect(),
+            rows,
+        })
+    }
+
+    /// Index of the column named `name`
+    pub fn column(&self, name: &str) -> Option<usize> {
+        self.columns.iter().position(|column| column == name)
+    }
+
+    /// Non-empty value of column `name` in row `row`
+    pub fn value(&self, row: usize, name: &str) -> Option<&str> {
+        let value = self.rows.get(row)?.get(self.column(name)?)?;
+        (!value.is_empty()).then_some(value.as_str())
+    }
+
+    /// Values of the first column, which holds UIDs in `xtract` exports
+    pub fn uids(&self) -> Vec<&str> {
+        self.rows.iter().filter_map(|row| row.first()).map(String::as_str).collect()
+    }
+
+    /// Rows as written by `xtract`, without a header
+    ///
+    /// Tabs and line breaks within values are replaced by spaces.
+    pub fn to_tsv(&self) -> String {
+        self.rows
+            .iter()
+            .map(|row| {
+                let fields: Vec<String> = row.iter().map(|value| value.replace(['\t', '\n', '\r'], " ")).collect();
+                fields.join("\t") + "\n"
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::edirect::{read_uids, write_uids, DocSumTable, EDirectMessage};
+    use crate::EntrezDb;
+
+    #[test]
+    fn test_uid_lists() {
+        let ids = read_uids("7157\n  7161 \n\nNM_000546.6\n".as_bytes()).unwrap();
+        assert_eq!(ids, ["7157", "7161", "NM_000546.6"]);
+        assert_eq!(read_uids("7157\n7161 7162\n".as_bytes()).unwrap_err(), "Line 2: expected a single UID, found `7161 7162`");
+
+        let mut output = Vec::new();
+        write_uids(&mut output, &ids).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), "7157\n7161\nNM_000546.6\n");
+    }
+
+    #[test]
+    fn test_edirect_message() {
+        let xml = "<ENTREZ_DIRECT>\n  <Db>gene</Db>\n  <WebEnv>MCID_6571f2a3</WebEnv>\n  <QueryKey>1</QueryKey>\n  \
+                   <Count>42</Count>\n  <Step>1</Step>\n</ENTREZ_DIRECT>\n";
+        let message = EDirectMessage::from_xml(xml).unwrap();
+        assert_eq!(message.db, EntrezDb::Gene);
+        assert_eq!(message.web_env.as_deref(), Some("MCID_6571f2a3"));
+        assert_eq!((message.query_key, message.count, message.step), (Some(1), Some(42), Some(1)));
+        assert!(message.ids.is_empty());
+        assert_eq!(message.to_xml(), xml);
+
+        let message = EDirectMessage::from_ids(EntrezDb::Protein, &["NP_000537.3", "NP_005418.1"]);
+        assert_eq!(EDirectMessage::from_xml(&message.to_xml()), Ok(message));
+
+        assert!(EDirectMessage::from_xml("<ENTREZ_DIRECT><Count>1</Count></ENTREZ_DIRECT>").is_err());
+        assert!(EDirectMessage::from_xml("<ENTREZ_DIRECT><Db>gene</Db><Count>x</Count></ENTREZ_DIRECT>").is_err());
+        assert!(EDirectMessage::from_xml("<eSearchResult/>").is_err());
+    }
+
+    #[test]
+    fn test_docsum_table() {
+        let response = std::fs::read_to_string("tests/data/gds_summary.xml").unwrap();
+        let table = DocSumTable::from_esummary(&response, &["Id", "Accession", "ptechType", "taxon"]).unwrap();
+        assert_eq!(table.rows.len(), 2);
+        assert_eq!(table.uids()[0], "200002034");
+        assert_eq!(table.value(0, "Accession"), Some("GSE2034"));
+        assert_eq!(table.value(0, "ptechType"), None);
+
+        let tsv = table.to_tsv();
+        assert!(tsv.starts_with("200002034\tGSE2034\t\tHomo sapiens\n"));
+        let columns = ["Id", "Accession", "ptechType", "taxon"];
+        assert_eq!(DocSumTable::from_tsv(&tsv, &columns), Ok(table));
+
+        // missing trailing values
+        let table = DocSumTable::from_tsv("1\tA\n2\n", &["Id", "Name"]).unwrap();
+        assert_eq!(table.rows[1], ["2", ""]);
+        assert!(DocSumTable::from_tsv("1\tA\tB\n", &["Id", "Name"]).is_err());
+    }
+}
@@ -1,21 +1,49 @@
 
 
+use crate::biblio::PubMedId;
+use crate::cdd::{read_cdd_set, CddSet};
+use crate::coords::Range0;
+use crate::seq::SeqAnnot;
 use crate::seqset::BioSeqSet;
 use crate::entrezgene::EntrezgeneSet;
-use crate::parsing::XmlNode;
+use crate::gbseq::{read_gb_set, GBSet};
+use crate::submit::SeqSubmit;
+use crate::parsing::{locate_error, with_locations, ParseOptions, XmlNode};
+use crate::ratelimit::throttle;
+use crate::transport::{get_within, CancelToken, RequestError};
+#[cfg(all(feature = "async", not(target_arch = "wasm32")))]
+use crate::parsing::NodeStream;
+#[cfg(all(feature = "async", not(target_arch = "wasm32")))]
+use crate::transport::{http_stream_async, ResponseReader};
 use quick_xml::events::Event;
 use quick_xml::Reader;
-use std::fs;
+use std::any::Any;
+use std::collections::HashMap;
+use flate2::bufread::MultiGzDecoder;
+use std::fmt;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::{OnceLock, RwLock};
+use std::time::Duration;
 
 const BASE: &str = "https://eutils.ncbi.nlm.nih.gov/entrez/eutils/";
 
+/// First bytes of a gzip stream
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
 /// # See Also
 ///
 /// [Entrez Unique Identifiers table](https://www.ncbi.nlm.nih.gov/books/NBK25497/table/chapter2.T._entrez_unique_identifiers_ui/)
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum EntrezDb {
+    Assembly,
+    BioCollections,
     BioProject,
     BioSample,
     Books,
+    ClinVar,
     ConservedDomains,
     DbGaP,
     DbVar,
@@ -23,10 +51,14 @@ pub enum EntrezDb {
     Genome,
     GeoDatasets,
     GeoProfiles,
+    Gtr,
     HomoloGene,
+    IdenticalProteins,
+    MedGen,
     MeSH,
     NlmCatalog,
     Nucleotide,
+    Omim,
     PopSet,
     Probe,
     Protein,
@@ -41,133 +73,1909 @@ pub enum EntrezDb {
     Structure,
     Taxonomy,
 }
+
+/// Properties of an Entrez database, given by [`EntrezDb::info()`]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct DbInfo {
+    /// value of the `db` parameter
+    pub name: &'static str,
+    /// `rettype` giving the full record as XML, if EFetch needs one
+    pub rettype: Option<&'static str>,
+    /// `retmode` giving the full record as XML, or `None` if EFetch does not
+    /// support the database
+    pub retmode: Option<&'static str>,
+}
+
 impl EntrezDb {
-    pub fn as_str(&self) -> &str {
+    /// Every database, in alphabetical order
+    pub const ALL: [Self; 34] = [
+        Self::Assembly,
+        Self::BioCollections,
+        Self::BioProject,
+        Self::BioSample,
+        Self::Books,
+        Self::ClinVar,
+        Self::ConservedDomains,
+        Self::DbGaP,
+        Self::DbVar,
+        Self::Gene,
+        Self::Genome,
+        Self::GeoDatasets,
+        Self::GeoProfiles,
+        Self::Gtr,
+        Self::HomoloGene,
+        Self::IdenticalProteins,
+        Self::MedGen,
+        Self::MeSH,
+        Self::NlmCatalog,
+        Self::Nucleotide,
+        Self::Omim,
+        Self::PopSet,
+        Self::Probe,
+        Self::Protein,
+        Self::ProteinClusters,
+        Self::PubChemBioAssay,
+        Self::PubChemCompound,
+        Self::PubChemSubstance,
+        Self::PubMed,
+        Self::PubMedCentral,
+        Self::Snp,
+        Self::Sra,
+        Self::Structure,
+        Self::Taxonomy,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        self.info().name
+    }
+
+    /// Name of the database and the EFetch parameters used by [`FetchRequest::fetch()`]
+    ///
+    /// # See Also
+    ///
+    /// [EFetch `retmode` and `rettype` values](https://www.ncbi.nlm.nih.gov/books/NBK25499/table/chapter4.T._valid_values_of__retmode_and/?report=objectonly)
+    pub fn info(&self) -> DbInfo {
+        let (name, rettype, retmode) = match self {
+            Self::Assembly => ("assembly", None, None),
+            Self::BioCollections => ("biocollections", None, None),
+            Self::BioProject => ("bioproject", Some("xml"), Some("xml")),
+            Self::BioSample => ("biosample", Some("full"), Some("xml")),
+            Self::Books => ("books", None, None),
+            Self::ClinVar => ("clinvar", Some("vcv"), Some("xml")),
+            Self::ConservedDomains => ("cdd", None, None),
+            Self::DbGaP => ("gap", None, None),
+            Self::DbVar => ("dbvar", None, None),
+            Self::Gene => ("gene", None, Some("xml")),
+            Self::Genome => ("genome", None, None),
+            Self::GeoDatasets => ("gds", None, None),
+            Self::GeoProfiles => ("geoprofiles", None, None),
+            Self::Gtr => ("gtr", None, None),
+            Self::HomoloGene => ("homologene", None, Some("xml")),
+            Self::IdenticalProteins => ("ipg", Some("ipg"), Some("xml")),
+            Self::MedGen => ("medgen", None, None),
+            Self::MeSH => ("mesh", None, None),
+            Self::NlmCatalog => ("nlmcatalog", None, Some("xml")),
+            Self::Nucleotide => ("nuccore", Some("native"), Some("xml")),
+            Self::Omim => ("omim", None, None),
+            Self::PopSet => ("popset", Some("native"), Some("xml")),
+            Self::Probe => ("probe", None, None),
+            Self::Protein => ("protein", Some("native"), Some("xml")),
+            Self::ProteinClusters => ("proteinclusters", None, None),
+            Self::PubChemBioAssay => ("pcassay", None, None),
+            Self::PubChemCompound => ("pccompound", None, None),
+            Self::PubChemSubstance => ("pcsubstance", None, None),
+            Self::PubMed => ("pubmed", None, Some("xml")),
+            Self::PubMedCentral => ("pmc", None, Some("xml")),
+            Self::Snp => ("snp", None, Some("xml")),
+            Self::Sra => ("sra", Some("full"), Some("xml")),
+            Self::Structure => ("structure", None, None),
+            Self::Taxonomy => ("taxonomy", None, Some("xml")),
+        };
+        DbInfo {
+            name,
+            rettype,
+            retmode,
+        }
+    }
+
+    /// Combinations of `rettype` and `retmode` accepted by EFetch
+    ///
+    /// Empty for databases which EFetch does not support.
+    ///
+    /// # See Also
+    ///
+    /// [EFetch `retmode` and `rettype` values](https://www.ncbi.nlm.nih.gov/books/NBK25499/table/chapter4.T._valid_values_of__retmode_and/?report=objectonly)
+    pub fn formats(&self) -> Vec<FetchFormat> {
+        let f = FetchFormat::new;
         match self {
-            Self::BioProject => "bioproject",
-            Self::BioSample => "biosample",
-            Self::Books => "books",
-            Self::ConservedDomains => "cdd",
-            Self::DbGaP => "gap",
-            Self::DbVar => "dbvar",
-            Self::Gene => "gene",
-            Self::Genome => "genome",
-            Self::GeoDatasets => "gds",
-            Self::GeoProfiles => "geoprofiles",
-            Self::HomoloGene => "homologene",
-            Self::MeSH => "mesh",
-            Self::NlmCatalog => "nlmcatalog",
-            Self::Nucleotide => "nuccore",
-            Self::PopSet => "popset",
-            Self::Probe => "probe",
-            Self::Protein => "protein",
-            Self::ProteinClusters => "proteinclusters",
-            Self::PubChemBioAssay => "pcassay",
-            Self::PubChemCompound => "pccompound",
-            Self::PubChemSubstance => "pcsubstance",
-            Self::PubMed => "pubmed",
-            Self::PubMedCentral => "pmc",
-            Self::Snp => "snp",
-            Self::Sra => "sra",
-            Self::Structure => "structure",
-            Self::Taxonomy => "taxonomy",
+            Self::BioProject => vec![f(Some("xml"), "xml", "full record XML"), XML],
+            Self::BioSample => vec![
+                f(Some("full"), "xml", "full record XML"),
+                f(Some("full"), "text", "full record text"),
+            ],
+            Self::ClinVar => vec![
+                f(Some("vcv"), "xml", "variation archive XML"),
+                f(Some("clinvarset"), "xml", "ClinVar set XML"),
+            ],
+            Self::Gene => vec![
+                ASN1,
+                XML,
+                f(Some("gene_table"), "text", "gene table"),
+            ],
+            Self::GeoDatasets => vec![f(Some("summary"), "text", "summary")],
+            Self::HomoloGene => vec![
+                ASN1,
+                XML,
+                f(Some("alignmentscores"), "text", "alignment scores"),
+                f(Some("fasta"), "text", "FASTA"),
+                f(Some("homologene"), "text", "HomoloGene"),
+            ],
+            Self::IdenticalProteins => vec![f(Some("ipg"), "xml", "identical protein report")],
+            Self::MeSH => vec![f(Some("full"), "text", "full record")],
+            Self::NlmCatalog => vec![f(None, "text", "full record"), XML],
+            Self::Nucleotide => [
+                &SEQUENCE_FORMATS[..],
+                &[
+                    f(Some("gb"), "text", "GenBank flat file"),
+                    f(Some("gb"), "xml", "GBSeq XML"),
+                    f(Some("gbc"), "xml", "INSDSeq XML"),
+                    f(Some("gbwithparts"), "text", "GenBank flat file with full sequence"),
+                    f(Some("fasta_cds_na"), "text", "CDS nucleotide FASTA"),
+                    f(Some("fasta_cds_aa"), "text", "CDS protein FASTA"),
+                ],
+            ]
+            .concat(),
+            Self::PopSet => [
+                &SEQUENCE_FORMATS[..],
+                &[
+                    f(Some("gb"), "text", "GenBank flat file"),
+                    f(Some("gb"), "xml", "GBSeq XML"),
+                    f(Some("gbc"), "xml", "INSDSeq XML"),
+                ],
+            ]
+            .concat(),
+            Self::Protein => [
+                &SEQUENCE_FORMATS[..],
+                &[
+                    f(Some("gp"), "text", "GenPept flat file"),
+                    f(Some("gp"), "xml", "GBSeq XML"),
+                    f(Some("gpc"), "xml", "INSDSeq XML"),
+                    f(Some("ipg"), "xml", "identical protein XML"),
+                ],
+            ]
+            .concat(),
+            Self::PubMedCentral => vec![XML, f(Some("medline"), "text", "MEDLINE")],
+            Self::PubMed => vec![
+                ASN1,
+                XML,
+                f(Some("medline"), "text", "MEDLINE"),
+                f(Some("abstract"), "text", "abstract"),
+                UILIST,
+            ],
+            Self::Snp => vec![
+                ASN1,
+                XML,
+                f(Some("flt"), "text", "flat file"),
+                f(Some("fasta"), "text", "FASTA"),
+                f(Some("rsr"), "text", "RS Cluster report"),
+                f(Some("ssexemplar"), "text", "SS Exemplar list"),
+                f(Some("chr"), "text", "chromosome report"),
+                f(Some("docset"), "text", "summary"),
+                UILIST,
+                f(Some("uilist"), "xml", "list of UIDs"),
+            ],
+            Self::Sra => vec![
+                XML,
+                f(Some("full"), "xml", "experiment package XML"),
+                f(Some("runinfo"), "text", "run information CSV"),
+                f(Some("runinfo"), "csv", "run information CSV"),
+            ],
+            Self::Taxonomy => vec![XML, UILIST, f(Some("uilist"), "xml", "list of UIDs")],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Check that EFetch accepts `rettype` and `retmode` for this database
+    ///
+    /// Missing parameters are left to EFetch defaults. The error lists the accepted
+    /// combinations, starting with those of the same `rettype`, or else the same
+    /// `retmode`.
+    pub fn validate_format(&self, rettype: Option<&str>, retmode: Option<&str>) -> Result<(), String> {
+        let formats = self.formats();
+        if formats.is_empty() {
+            return Err(format!("EFetch does not support db={}", self));
+        }
+        if rettype.is_none() && retmode.is_none() {
+            return Ok(());
+        }
+        if formats.iter().any(|format| format.matches(rettype, retmode)) {
+            return Ok(());
+        }
+
+        let same = |a: Option<&str>, b: Option<&str>| match (a, b) {
+            (Some(a), Some(b)) => a.eq_ignore_ascii_case(b),
+            _ => false,
+        };
+        let mut suggestions: Vec<&FetchFormat> =
+            formats.iter().filter(|format| same(format.rettype, rettype)).collect();
+        if suggestions.is_empty() {
+            suggestions = formats.iter().filter(|format| same(Some(format.retmode), retmode)).collect();
+        }
+        if suggestions.is_empty() {
+            suggestions = formats.iter().collect();
+        }
+        let requested: Vec<String> = [("rettype", rettype), ("retmode", retmode)]
+            .into_iter()
+            .filter_map(|(name, value)| Some(format!("{}={}", name, value?)))
+            .collect();
+        let suggestions: Vec<String> = suggestions
+            .iter()
+            .map(|format| format!("{} ({})", format, format.description))
+            .collect();
+        Err(format!(
+            "{} is not valid for db={}, try: {}",
+            requested.join("&"),
+            self,
+            suggestions.join(", ")
+        ))
+    }
+}
+
+/// Combination of `rettype` and `retmode` accepted by EFetch, given by [`EntrezDb::formats()`]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct FetchFormat {
+    /// `None` when the parameter is left out
+    pub rettype: Option<&'static str>,
+    pub retmode: &'static str,
+    /// content of the response (eg: "GenBank flat file")
+    pub description: &'static str,
+}
+
+impl FetchFormat {
+    const fn new(rettype: Option<&'static str>, retmode: &'static str, description: &'static str) -> Self {
+        Self {
+            rettype,
+            retmode,
+            description,
+        }
+    }
+
+    /// Whether the format is requested by `rettype` and `retmode`
+    ///
+    /// A missing `retmode` matches any mode, as EFetch then uses the default mode of
+    /// the `rettype`.
+    fn matches(&self, rettype: Option<&str>, retmode: Option<&str>) -> bool {
+        let same = |a: &str, b: &str| a.eq_ignore_ascii_case(b);
+        match (self.rettype, rettype) {
+            (None, None) => (),
+            (Some(a), Some(b)) if same(a, b) => (),
+            _ => return false,
+        }
+        retmode.is_none_or(|mode| same(self.retmode, mode))
+    }
+}
+
+impl fmt::Display for FetchFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.rettype {
+            Some(rettype) => write!(f, "rettype={}&retmode={}", rettype, self.retmode),
+            None => write!(f, "retmode={}", self.retmode),
+        }
+    }
+}
+
+const ASN1: FetchFormat = FetchFormat::new(None, "asn.1", "text ASN.1");
+const XML: FetchFormat = FetchFormat::new(None, "xml", "XML");
+const UILIST: FetchFormat = FetchFormat::new(Some("uilist"), "text", "list of UIDs");
+
+/// Formats shared by [`EntrezDb::Nucleotide`], [`EntrezDb::Protein`] and [`EntrezDb::PopSet`]
+const SEQUENCE_FORMATS: [FetchFormat; 8] = [
+    ASN1,
+    FetchFormat::new(None, "text", "text ASN.1"),
+    FetchFormat::new(Some("native"), "xml", "XML"),
+    FetchFormat::new(Some("acc"), "text", "accession numbers"),
+    FetchFormat::new(Some("fasta"), "text", "FASTA"),
+    FetchFormat::new(Some("fasta"), "xml", "TinySeq XML"),
+    FetchFormat::new(Some("seqid"), "text", "SeqID string"),
+    FetchFormat::new(Some("ft"), "text", "feature table"),
+];
+
+impl FromStr for EntrezDb {
+    type Err = String;
+
+    /// Parse the value of a `db` parameter
+    ///
+    /// Names are matched case-insensitively, and "nucleotide" is accepted as an alias
+    /// of "nuccore".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim().to_ascii_lowercase();
+        if name == "nucleotide" {
+            return Ok(Self::Nucleotide);
+        }
+        Self::ALL
+            .into_iter()
+            .find(|db| db.as_str() == name)
+            .ok_or(format!("Unknown Entrez database: {}", s))
+    }
+}
+
+impl fmt::Display for EntrezDb {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Perform a blocking GET request, returning the response body
+///
+/// All requests made by this crate pass through here, and are sent by the installed
+/// [`crate::transport::HttpTransport`] once allowed by the installed
+/// [`crate::ratelimit::RateLimiter`]. With the `fixtures` feature, responses may be
+/// replayed from or recorded to disk (see [`crate::fixtures`]).
+///
+/// Requests time out after the [`ClientConfig::timeout`] of the installed configuration.
+pub fn http_get(url: &str) -> Result<String, String> {
+    http_get_with(url, None, None).map_err(String::from)
+}
+
+/// Perform a blocking GET request which may time out or be cancelled
+///
+/// `timeout` replaces the [`ClientConfig::timeout`] of the installed configuration, and
+/// `cancel` aborts the request once set from another thread. See
+/// [`crate::transport::get_within()`].
+pub fn http_get_with(url: &str, timeout: Option<Duration>, cancel: Option<&CancelToken>) -> Result<String, RequestError> {
+    #[cfg(feature = "fixtures")]
+    if let Some(response) = crate::fixtures::intercept(url) {
+        return response.map_err(RequestError::Failed);
+    }
+
+    throttle(url);
+    let timeout = timeout.or(client_config().timeout);
+    let response = get_within(url, timeout, cancel)?;
+
+    #[cfg(feature = "fixtures")]
+    crate::fixtures::record_response(url, &response)?;
+
+    Ok(response)
+}
+
+/// Parameters identifying the caller, appended to every E-utilities URL
+///
+/// NCBI asks that programs identify themselves with `tool` and `email`, so that they
+/// can be contacted before being blocked, and an `api_key` raises the rate limit from
+/// 3 to 10 requests per second. Parameters which are not set are left out of URLs.
+///
+/// # Example
+/// ```
+/// use ncbi::{configure, reset_config, ClientConfig};
+///
+/// configure(ClientConfig::new().tool("my_pipeline").email("me@example.org").batch(true)).unwrap();
+/// # reset_config();
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct ClientConfig {
+    pub tool: Option<String>,
+    pub email: Option<String>,
+    pub api_key: Option<String>,
+    /// whether many requests are made unattended, which requires `tool` and `email`
+    pub batch: bool,
+    /// time after which blocking requests are abandoned
+    ///
+    /// Not sent to NCBI, and not given by default, leaving requests to the timeout of
+    /// the transport.
+    pub timeout: Option<Duration>,
+}
+
+/// Names of the parameters added by [`ClientConfig`]
+const IDENTITY_PARAMS: [&str; 3] = ["tool", "email", "api_key"];
+
+impl ClientConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read `NCBI_TOOL`, `NCBI_EMAIL` and `NCBI_API_KEY` from the environment
+    pub fn from_env() -> Self {
+        let var = |name| std::env::var(name).ok().filter(|value: &String| !value.is_empty());
+        Self {
+            tool: var("NCBI_TOOL"),
+            email: var("NCBI_EMAIL"),
+            api_key: var("NCBI_API_KEY"),
+            batch: false,
+            timeout: None,
         }
     }
+
+    pub fn tool(mut self, tool: &str) -> Self {
+        self.tool = Some(tool.to_string());
+        self
+    }
+
+    pub fn email(mut self, email: &str) -> Self {
+        self.email = Some(email.to_string());
+        self
+    }
+
+    pub fn api_key(mut self, api_key: &str) -> Self {
+        self.api_key = Some(api_key.to_string());
+        self
+    }
+
+    pub fn batch(mut self, batch: bool) -> Self {
+        self.batch = batch;
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Check that values can be sent as URL parameters, and that batch mode is identified
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, value) in IDENTITY_PARAMS.iter().zip([&self.tool, &self.email, &self.api_key]) {
+            match value.as_deref() {
+                Some("") => return Err(format!("`{}` is empty", name)),
+                Some(value) if value.chars().any(|c| c.is_whitespace() || "&=#?".contains(c)) => {
+                    return Err(format!("`{}` contains characters not allowed in a URL: \"{}\"", name, value))
+                }
+                _ => (),
+            }
+        }
+        if let Some(email) = &self.email {
+            if !email.contains('@') {
+                return Err(format!("Invalid email address: \"{}\"", email));
+            }
+        }
+        if self.batch && (self.tool.is_none() || self.email.is_none()) {
+            return Err("`tool` and `email` must be set in batch mode".to_string());
+        }
+        if self.timeout == Some(Duration::ZERO) {
+            return Err("`timeout` is zero".to_string());
+        }
+        Ok(())
+    }
+
+    /// Append the parameters which are set to `url`
+    pub fn apply(&self, url: &mut String) {
+        for (name, value) in IDENTITY_PARAMS.iter().zip([&self.tool, &self.email, &self.api_key]) {
+            if let Some(value) = value {
+                url.push_str(&format!("&{}={}", name, value));
+            }
+        }
+    }
+}
+
+fn installed_config() -> &'static RwLock<ClientConfig> {
+    static CONFIG: OnceLock<RwLock<ClientConfig>> = OnceLock::new();
+    CONFIG.get_or_init(|| RwLock::new(ClientConfig::default()))
+}
+
+/// Identify all following requests with `config`, once it is validated
+pub fn configure(config: ClientConfig) -> Result<(), String> {
+    config.validate()?;
+    *installed_config().write().unwrap_or_else(|e| e.into_inner()) = config;
+    Ok(())
+}
+
+/// Stop identifying requests
+pub fn reset_config() {
+    *installed_config().write().unwrap_or_else(|e| e.into_inner()) = ClientConfig::default();
+}
+
+/// Parameters currently appended to URLs
+pub fn client_config() -> ClientConfig {
+    installed_config().read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// `url` with the parameters of the installed [`ClientConfig`]
+fn identify(mut url: String) -> String {
+    client_config().apply(&mut url);
+    url
+}
+
+/// `url` without the parameters added by [`ClientConfig`]
+///
+/// Used to keep API keys out of recorded fixtures, and so that fixtures are replayed
+/// regardless of configuration.
+#[cfg(any(feature = "fixtures", test))]
+pub(crate) fn strip_identity(url: &str) -> String {
+    let Some((base, query)) = url.split_once('?') else {
+        return url.to_string();
+    };
+    let query: Vec<&str> = query
+        .split('&')
+        .filter(|param| {
+            let name = param.split_once('=').map_or(*param, |(name, _)| name);
+            !IDENTITY_PARAMS.contains(&name)
+        })
+        .collect();
+    format!("{}?{}", base, query.join("&"))
 }
 
+/// Build URL for ESearch, which returns the UIDs matching the Entrez query `term`
+///
+/// `term` is given as typed (eg: "tp73[sym] AND human[orgn]"), and is encoded here.
 pub fn build_search_url(db: EntrezDb, term: &str) -> String {
     let mut url_str = format!("{}esearch.fcgi?", BASE);
     url_str.push_str(&(format!("db={}", db.as_str())));
-    url_str.push_str(&(format!("&term={}", term)));
+    url_str.push_str(&(format!("&term={}", encode_query_value(term))));
 
     let ret = "xml";
     url_str.push_str(&(format!("&rettype={}", ret)));
     url_str.push_str(&(format!("&retmode={}", ret)));
 
-    url_str
+    identify(url_str)
+}
+
+/// Build URL for EPost, which stores `ids` on the history server
+pub fn build_post_url<S: AsRef<str>>(db: EntrezDb, ids: &[S]) -> String {
+    let ids: Vec<&str> = ids.iter().map(|id| id.as_ref()).collect();
+    let mut url_str = format!("{}epost.fcgi?", BASE);
+    url_str.push_str(&(format!("db={}", db.as_str())));
+    url_str.push_str(&(format!("&id={}", ids.join(","))));
+
+    identify(url_str)
+}
+
+#[derive(Clone, PartialEq, Debug, Default)]
+/// Result of an ESearch query
+pub struct SearchResult {
+    /// total number of matching records
+    pub count: u64,
+    /// UIDs returned for this page of results
+    pub ids: Vec<String>,
+    /// history server session storing the results, with `usehistory=y`
+    pub web_env: Option<String>,
+    /// key of the results within [`Self::web_env`]
+    pub query_key: Option<u64>,
+}
+
+impl SearchResult {
+    /// Parse the raw XML returned by ESearch
+    ///
+    /// An [`EntrezError`] returned instead of results is reported as the error.
+    pub fn from_xml(response: &str) -> Result<Self, String> {
+        if let Some(error) = EntrezError::from_xml(response) {
+            return Err(error.into());
+        }
+        let mut reader = Reader::from_str(response);
+
+        let mut result = Self::default();
+        let mut found = false;
+        let mut path: Vec<Vec<u8>> = Vec::new();
+
+        loop {
+            match reader.read_event() {
+                Ok(Event::Start(e)) => {
+                    if e.name().as_ref() == b"eSearchResult" {
+                        found = true;
+                    }
+                    path.push(e.name().as_ref().to_vec());
+                }
+                Ok(Event::Text(text)) => {
+                    let text = text.unescape().map_err(|e| format!("{:?}", e))?.to_string();
+                    let parent = path.len().checked_sub(2).and_then(|i| path.get(i));
+                    match (parent.map(|p| p.as_slice()), path.last().map(|p| p.as_slice())) {
+                        (Some(b"eSearchResult"), Some(b"Count")) => {
+                            result.count = text.trim().parse().map_err(|_| format!("Invalid count: {}", text))?
+                        }
+                        (Some(b"IdList"), Some(b"Id")) => result.ids.push(text),
+                        (Some(b"eSearchResult"), Some(b"WebEnv")) => result.web_env = Some(text.trim().to_string()),
+                        (Some(b"eSearchResult"), Some(b"QueryKey")) => {
+                            result.query_key = Some(text.trim().parse().map_err(|_| format!("Invalid query key: {}", text))?)
+                        }
+                        _ => (),
+                    }
+                }
+                Ok(Event::End(_)) => {
+                    path.pop();
+                }
+                Ok(Event::Eof) => break,
+                Err(e) => return Err(format!("XML parsing error: {:?}", e)),
+                _ => (),
+            }
+        }
+
+        if found {
+            Ok(result)
+        } else {
+            Err("No <eSearchResult> found in response.".to_string())
+        }
+    }
+}
+
+/// Search `db` for `term`, returning matching UIDs
+pub fn fetch_search(db: EntrezDb, term: &str) -> Result<SearchResult, String> {
+    let url = build_search_url(db, term);
+    let response = http_get(&url)?;
+    if let Some(error) = EntrezError::from_xml(&response) {
+        return Err(error.with_url(&url).into());
+    }
+    SearchResult::from_xml(response.as_str())
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+/// Error returned by an E-utility instead of results
+///
+/// Bad requests are answered with an `<ERROR>` element, either alone or as the first
+/// child of the usual root (eg: `<eFetchResult><ERROR>ID list is empty!</ERROR>`).
+pub struct EntrezError {
+    /// utility which rejected the request (eg: "efetch", "esearch"), if known
+    pub utility: Option<String>,
+    /// message given by the server
+    pub message: String,
+    /// parameters of the request, without those added by [`ClientConfig`]
+    pub params: Vec<(String, String)>,
+}
+
+/// Beginning of the messages returned for results that are no longer on the history server
+const HISTORY_EXPIRED: [&str; 3] = [
+    "unable to obtain query #",
+    "cannot retrieve query keys",
+    "cannot retrieve history data",
+];
+
+impl EntrezError {
+    /// Error returned in `response`, if any
+    ///
+    /// Empty `<ERROR/>` elements, as returned by ESpell on success, are not errors. Only
+    /// the root and its children are looked at, so that records mentioning an `ERROR`
+    /// element are not mistaken for errors.
+    pub fn from_xml(response: &str) -> Option<Self> {
+        let mut reader = Reader::from_str(response);
+        reader.trim_text(true);
+
+        let mut root: Option<String> = None;
+        let mut depth = 0;
+        let mut message: Option<String> = None;
+        loop {
+            match reader.read_event() {
+                Ok(Event::Start(e)) => {
+                    let name = std::str::from_utf8(e.name().into_inner()).ok()?.to_string();
+                    depth += 1;
+                    if root.is_none() {
+                        if name != "ERROR" && !(name.starts_with('e') && name.ends_with("Result")) {
+                            return None;
+                        }
+                        root = Some(name.clone());
+                    }
+                    if name == "ERROR" && depth <= 2 {
+                        message = Some(String::new());
+                    }
+                }
+                Ok(Event::Text(text)) => {
+                    if let Some(message) = message.as_mut() {
+                        message.push_str(&text.unescape().ok()?);
+                    }
+                }
+                Ok(Event::End(e)) => {
+                    depth -= 1;
+                    if e.name().as_ref() == b"ERROR" {
+                        match message.take() {
+                            Some(message) if !message.trim().is_empty() => {
+                                let utility = root
+                                    .as_deref()
+                                    .and_then(|root| root.strip_suffix("Result"))
+                                    .map(str::to_ascii_lowercase);
+                                return Some(Self {
+                                    utility,
+                                    message: message.trim().to_string(),
+                                    params: Vec::new(),
+                                });
+                            }
+                            _ => (),
+                        }
+                    }
+                    if depth == 0 {
+                        return None;
+                    }
+                }
+                Ok(Event::Empty(_)) if root.is_none() => return None,
+                Ok(Event::Eof) | Err(_) => return None,
+                _ => (),
+            }
+        }
+    }
+
+    /// Record the parameters of the request made to `url`
+    ///
+    /// The utility is also taken from `url` when the response did not name it.
+    pub fn with_url(mut self, url: &str) -> Self {
+        let (base, query) = url.split_once('?').unwrap_or((url, ""));
+        if self.utility.is_none() {
+            self.utility = base
+                .rsplit('/')
+                .next()
+                .and_then(|page| page.strip_suffix(".fcgi"))
+                .map(str::to_string);
+        }
+        self.params = query
+            .split('&')
+            .filter(|param| !param.is_empty())
+            .map(|param| param.split_once('=').unwrap_or((param, "")))
+            .filter(|(name, _)| !IDENTITY_PARAMS.contains(name))
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        self
+    }
+
+    /// Value of the request parameter `name`
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params.iter().find(|(param, _)| param == name).map(|(_, value)| value.as_str())
+    }
+
+    /// Whether results stored on the history server could not be found
+    ///
+    /// Sessions expire after some hours without use, and their results must then be
+    /// stored again (see [`crate::history::HistorySession`]). Only the messages NCBI
+    /// returns for a missing session are recognized (eg: "Unable to obtain query #1"),
+    /// so that other errors mentioning the history server or `WebEnv` (such as a
+    /// malformed parameter) are not retried.
+    pub fn is_history_expired(&self) -> bool {
+        let message = self.message.trim().to_ascii_lowercase();
+        HISTORY_EXPIRED.iter().any(|prefix| message.starts_with(prefix))
+    }
+}
+
+impl fmt::Display for EntrezError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} error: {}", self.utility.as_deref().unwrap_or("Entrez"), self.message)?;
+        if !self.params.is_empty() {
+            let params: Vec<String> = self.params.iter().map(|(name, value)| format!("{}={}", name, value)).collect();
+            write!(f, " ({})", params.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for EntrezError {}
+
+impl From<EntrezError> for String {
+    fn from(error: EntrezError) -> Self {
+        error.to_string()
+    }
 }
 
 /// View [EFetch documentation](https://www.ncbi.nlm.nih.gov/books/NBK25499/table/chapter4.T._valid_values_of__retmode_and/?report=objectonly)
 /// for a valid list of `retmode` and `rettype` values
 pub fn build_fetch_url(db: EntrezDb, id: &str, r#type: &str, mode: &str) -> String {
-    let mut url_str = format!("{}efetch.fcgi?", BASE);
+    FetchRequest::new(db, id).rettype(r#type).retmode(mode).build_url()
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+/// Strand of DNA to retrieve with [`FetchRequest::strand()`]
+pub enum FetchStrand {
+    Plus = 1,
+    Minus = 2,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+/// Data content to return with [`FetchRequest::complexity()`]
+pub enum FetchComplexity {
+    /// entire blob that contains the sequence
+    Blob = 0,
+    /// the [`BioSeq`](crate::seq::BioSeq) itself
+    BioSeq = 1,
+    /// minimal `Bioseq-set`
+    MinimalBioSeqSet = 2,
+    /// minimal `nuc-prot` set
+    MinimalNucProt = 3,
+    /// minimal `pub-set`
+    MinimalPubSet = 4,
+}
+
+/// Builder for EFetch requests covering the full set of parameters
+///
+/// # Example
+/// ```ignore
+/// // residues 1001-2000 of the minus strand as FASTA
+/// let url = FetchRequest::new(EntrezDb::Nucleotide, "NZ_JARQWN010000024")
+///     .rettype("fasta")
+///     .retmode("text")
+///     .region(1001, 2000)
+///     .strand(FetchStrand::Minus)
+///     .build_url();
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct FetchRequest {
+    pub db: EntrezDb,
+    /// comma separated list of UIDs or accessions
+    pub id: String,
+    pub rettype: Option<String>,
+    pub retmode: Option<String>,
+    /// index of first record to retrieve
+    pub retstart: Option<u64>,
+    /// total number of records to retrieve
+    pub retmax: Option<u64>,
+    /// first residue to retrieve (1-based, inclusive)
+    pub seq_start: Option<u64>,
+    /// last residue to retrieve (1-based, inclusive)
+    pub seq_stop: Option<u64>,
+    pub strand: Option<FetchStrand>,
+    pub complexity: Option<FetchComplexity>,
+    /// replaces the [`ClientConfig::timeout`] of the installed configuration
+    pub timeout: Option<Duration>,
+    /// aborts the request once cancelled
+    pub cancel: Option<CancelToken>,
+    /// history server session storing the records to retrieve, instead of [`Self::id`]
+    pub web_env: Option<String>,
+    /// key of the records within [`Self::web_env`]
+    pub query_key: Option<u64>,
+}
+
+impl FetchRequest {
+    pub fn new(db: EntrezDb, id: &str) -> Self {
+        Self {
+            db,
+            id: id.to_string(),
+            rettype: None,
+            retmode: None,
+            retstart: None,
+            retmax: None,
+            seq_start: None,
+            seq_stop: None,
+            strand: None,
+            complexity: None,
+            timeout: None,
+            cancel: None,
+            web_env: None,
+            query_key: None,
+        }
+    }
+
+    /// Request multiple records at once
+    pub fn with_ids<S: AsRef<str>>(db: EntrezDb, ids: &[S]) -> Self {
+        let ids: Vec<&str> = ids.iter().map(|id| id.as_ref()).collect();
+        Self::new(db, ids.join(",").as_str())
+    }
+
+    pub fn rettype(mut self, rettype: &str) -> Self {
+        self.rettype = Some(rettype.to_string());
+        self
+    }
+
+    pub fn retmode(mut self, retmode: &str) -> Self {
+        self.retmode = Some(retmode.to_string());
+        self
+    }
+
+    pub fn retstart(mut self, retstart: u64) -> Self {
+        self.retstart = Some(retstart);
+        self
+    }
+
+    pub fn retmax(mut self, retmax: u64) -> Self {
+        self.retmax = Some(retmax);
+        self
+    }
+
+    pub fn seq_start(mut self, start: u64) -> Self {
+        self.seq_start = Some(start);
+        self
+    }
+
+    pub fn seq_stop(mut self, stop: u64) -> Self {
+        self.seq_stop = Some(stop);
+        self
+    }
+
+    /// Only retrieve residues `start..=stop`, using 1-based coordinates
+    pub fn region(self, start: u64, stop: u64) -> Self {
+        self.seq_start(start).seq_stop(stop)
+    }
+
+    /// Only retrieve residues within `range`, as given by [`SeqLoc::range0()`](crate::seqloc::SeqLoc::range0)
+    pub fn range(self, range: Range0) -> Self {
+        self.region(range.start1().get(), range.end1().get())
+    }
+
+    pub fn strand(mut self, strand: FetchStrand) -> Self {
+        self.strand = Some(strand);
+        self
+    }
+
+    pub fn complexity(mut self, complexity: FetchComplexity) -> Self {
+        self.complexity = Some(complexity);
+        self
+    }
+
+    /// Give up when no response is received within `timeout`
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Abort the request when `token` is cancelled
+    pub fn cancel(mut self, token: &CancelToken) -> Self {
+        self.cancel = Some(token.clone());
+        self
+    }
+
+    /// Retrieve the records stored on the history server as `query_key` of `web_env`
+    ///
+    /// [`Self::id`] may then be left empty, or restricts the stored records.
+    pub fn history(mut self, web_env: &str, query_key: u64) -> Self {
+        self.web_env = Some(web_env.to_string());
+        self.query_key = Some(query_key);
+        self
+    }
+
+    pub fn build_url(&self) -> String {
+        let mut url_str = format!("{}efetch.fcgi?", BASE);
+        url_str.push_str(&(format!("db={}", self.db.as_str())));
+        if !self.id.is_empty() || self.web_env.is_none() {
+            url_str.push_str(&(format!("&id={}", self.id)));
+        }
+        if let Some(web_env) = &self.web_env {
+            url_str.push_str(&(format!("&WebEnv={}", web_env)));
+        }
+        if let Some(query_key) = self.query_key {
+            url_str.push_str(&(format!("&query_key={}", query_key)));
+        }
+
+        if let Some(rettype) = &self.rettype {
+            url_str.push_str(&(format!("&rettype={}", rettype)));
+        }
+        if let Some(retmode) = &self.retmode {
+            url_str.push_str(&(format!("&retmode={}", retmode)));
+        }
+        if let Some(retstart) = self.retstart {
+            url_str.push_str(&(format!("&retstart={}", retstart)));
+        }
+        if let Some(retmax) = self.retmax {
+            url_str.push_str(&(format!("&retmax={}", retmax)));
+        }
+        if let Some(start) = self.seq_start {
+            url_str.push_str(&(format!("&seq_start={}", start)));
+        }
+        if let Some(stop) = self.seq_stop {
+            url_str.push_str(&(format!("&seq_stop={}", stop)));
+        }
+        if let Some(strand) = self.strand {
+            url_str.push_str(&(format!("&strand={}", strand as u8)));
+        }
+        if let Some(complexity) = self.complexity {
+            url_str.push_str(&(format!("&complexity={}", complexity as u8)));
+        }
+
+        identify(url_str)
+    }
+
+    /// Send request, returning the raw response text
+    ///
+    /// The request is not sent if EFetch does not accept its `rettype` and `retmode`,
+    /// see [`EntrezDb::validate_format()`].
+    pub fn fetch_text(&self) -> Result<String, String> {
+        self.send().map_err(String::from)
+    }
+
+    /// Send request as [`Self::fetch_text()`], telling a cancelled or timed out request
+    /// apart from other errors
+    pub fn send(&self) -> Result<String, RequestError> {
+        if let (Some(start), Some(stop)) = (self.seq_start, self.seq_stop) {
+            if start == 0 || stop < start {
+                return Err(format!("Invalid sequence region: {}..{}", start, stop).into());
+            }
+        }
+        self.db.validate_format(self.rettype.as_deref(), self.retmode.as_deref())?;
+        http_get_with(&self.build_url(), self.timeout, self.cancel.as_ref())
+    }
+
+    /// Fill in `rettype` and `retmode` from [`EntrezDb::info()`] when not given
+    pub fn with_defaults(mut self) -> Self {
+        let info = self.db.info();
+        if self.rettype.is_none() && self.retmode.is_none() {
+            self.rettype = info.rettype.map(str::to_string);
+        }
+        if self.retmode.is_none() {
+            self.retmode = info.retmode.map(str::to_string);
+        }
+        self
+    }
+
+    /// Send request, parsing the response as XML
+    ///
+    /// Missing `rettype` and `retmode` default to those of [`EntrezDb::info()`]. Formats
+    /// other than XML are rejected before sending the request, and should be retrieved
+    /// with [`Self::fetch_text()`] instead.
+    pub fn fetch(&self) -> Result<DataType, String> {
+        let request = self.clone().with_defaults();
+        match request.retmode.as_deref() {
+            Some(mode) if mode.eq_ignore_ascii_case("xml") => (),
+            mode => {
+                request.db.validate_format(request.rettype.as_deref(), mode)?;
+                return Err(format!(
+                    "fetch() parses XML, but retmode={} was requested for db={}, use fetch_text()",
+                    mode.unwrap_or("(default)"),
+                    request.db
+                ));
+            }
+        }
+        let response = request.fetch_text()?;
+        if let Some(error) = EntrezError::from_xml(&response) {
+            return Err(error.with_url(&request.build_url()).into());
+        }
+        parse_xml(response.as_str())
+    }
+}
+
+/// Build URL for ESummary, which returns document summaries for a comma separated list of `id`
+pub fn build_summary_url(db: EntrezDb, id: &str) -> String {
+    let mut url_str = format!("{}esummary.fcgi?", BASE);
     url_str.push_str(&(format!("db={}", db.as_str())));
     url_str.push_str(&(format!("&id={}", id)));
 
-    url_str.push_str(&(format!("&rettype={}", r#type)));
-    url_str.push_str(&(format!("&retmode={}", mode)));
+    identify(url_str)
+}
+
+/// `Item` of an ESummary `DocSum`
+#[derive(Clone, PartialEq, Debug, Default)]
+pub(crate) struct DocSumItem {
+    pub name: String,
+    pub value: String,
+    /// items of a `List` or `Structure`
+    pub items: Vec<DocSumItem>,
+}
+
+impl DocSumItem {
+    pub fn find<'a>(items: &'a [DocSumItem], name: &str) -> Option<&'a DocSumItem> {
+        items.iter().find(|item| item.name == name)
+    }
+
+    /// Non-empty value of the named item
+    pub fn value(items: &[DocSumItem], name: &str) -> Option<String> {
+        Self::find(items, name)
+            .map(|item| item.value.trim())
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+    }
+}
+
+/// Read the id and items of each `DocSum` in an ESummary (version 1) response
+pub(crate) fn read_docsums(response: &str) -> Result<Vec<(String, Vec<DocSumItem>)>, String> {
+    let mut reader = Reader::from_str(response);
+    reader.trim_text(true);
+
+    let mut docsums = Vec::new();
+    let mut found = false;
+    let mut in_id = false;
+    let mut id = String::new();
+    // items which have been started but not ended, outermost first
+    let mut open: Vec<DocSumItem> = Vec::new();
+    let mut items: Vec<DocSumItem> = Vec::new();
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) => match e.name().as_ref() {
+                b"eSummaryResult" => found = true,
+                b"DocSum" => {
+                    id.clear();
+                    items.clear();
+                }
+                b"Id" => in_id = true,
+                b"Item" => {
+                    let name = e
+                        .try_get_attribute("Name")
+                        .ok()
+                        .flatten()
+                        .and_then(|a| a.unescape_value().ok())
+                        .map(|name| name.to_string())
+                        .unwrap_or_default();
+                    open.push(DocSumItem {
+                        name,
+                        ..DocSumItem::default()
+                    });
+                }
+                _ => (),
+            },
+            Ok(Event::Text(text)) => {
+                let text = text.unescape().map_err(|e| format!("{:?}", e))?.to_string();
+                if in_id {
+                    id = text;
+                } else if let Some(item) = open.last_mut() {
+                    item.value = text;
+                }
+            }
+            Ok(Event::End(e)) => match e.name().as_ref() {
+                b"Id" => in_id = false,
+                b"Item" => {
+                    if let Some(item) = open.pop() {
+                        match open.last_mut() {
+                            Some(parent) => parent.items.push(item),
+                            None => items.push(item),
+                        }
+                    }
+                }
+                b"DocSum" => docsums.push((id.clone(), std::mem::take(&mut items))),
+                _ => (),
+            },
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(format!("XML parsing error: {:?}", e)),
+            _ => (),
+        }
+    }
+
+    if found {
+        Ok(docsums)
+    } else {
+        Err("No <eSummaryResult> found in response.".to_string())
+    }
+}
+
+/// Percent-encode `text` as the value of a URL query parameter
+///
+/// Spaces are encoded as `+`, and every byte other than an unreserved character of
+/// RFC 3986 as `%XX`.
+fn encode_query_value(text: &str) -> String {
+    let mut encoded = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char)
+            }
+            b' ' => encoded.push('+'),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+/// Build URL for ESpell, which returns spelling suggestions for `term`
+pub fn build_spell_url(db: EntrezDb, term: &str) -> String {
+    let mut url_str = format!("{}espell.fcgi?", BASE);
+    url_str.push_str(&(format!("db={}", db.as_str())));
+    url_str.push_str(&(format!("&term={}", encode_query_value(term))));
+
+    identify(url_str)
+}
+
+/// Parsed `<eSpellResult>` returned by ESpell
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct SpellResult {
+    pub database: String,
+    /// original query
+    pub query: String,
+    /// query with all suggestions applied
+    pub corrected_query: String,
+    /// terms within the query that were replaced
+    pub replaced: Vec<String>,
+}
+
+impl SpellResult {
+    /// Parse the raw XML returned by ESpell
+    pub fn from_xml(response: &str) -> Result<Self, String> {
+        let mut reader = Reader::from_str(response);
+
+        let mut result = Self::default();
+        let mut found = false;
+        let mut current = Vec::new();
+
+        loop {
+            match reader.read_event() {
+                Ok(Event::Start(e)) => {
+                    if e.name().as_ref() == b"eSpellResult" {
+                        found = true;
+                    }
+                    current = e.name().as_ref().to_vec();
+                }
+                Ok(Event::Text(text)) => {
+                    let text = text.unescape().map_err(|e| format!("{:?}", e))?.to_string();
+                    match current.as_slice() {
+                        b"Database" => result.database = text,
+                        b"Query" => result.query = text,
+                        b"CorrectedQuery" => result.corrected_query = text,
+                        b"Replaced" => result.replaced.push(text),
+                        _ => (),
+                    }
+                }
+                Ok(Event::End(_)) => current.clear(),
+                Ok(Event::Eof) => break,
+                Err(e) => return Err(format!("XML parsing error: {:?}", e)),
+                _ => (),
+            }
+        }
+
+        if found {
+            Ok(result)
+        } else {
+            Err("No <eSpellResult> found in response.".to_string())
+        }
+    }
+
+    /// Whether ESpell suggested any corrections
+    pub fn has_suggestion(&self) -> bool {
+        !self.replaced.is_empty()
+    }
+}
+
+/// Request spelling suggestions for `term`
+pub fn fetch_spelling(db: EntrezDb, term: &str) -> Result<SpellResult, String> {
+    let url = build_spell_url(db, term);
+    let response = http_get(&url)?;
+    SpellResult::from_xml(response.as_str())
+}
+
+/// Single citation to be resolved by ECitMatch
+///
+/// All fields besides `key` are optional, although at least the journal and
+/// one of volume/page/author should be given for a match to be found.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct CitMatchQuery {
+    pub journal: Option<String>,
+    pub year: Option<String>,
+    pub volume: Option<String>,
+    pub first_page: Option<String>,
+    pub author: Option<String>,
+    /// arbitrary label used to match results to queries
+    pub key: String,
+}
+
+impl CitMatchQuery {
+    pub fn new(key: &str) -> Self {
+        Self {
+            key: key.to_string(),
+            ..Self::default()
+        }
+    }
+
+    pub fn journal(mut self, journal: &str) -> Self {
+        self.journal = Some(journal.to_string());
+        self
+    }
+
+    pub fn year(mut self, year: &str) -> Self {
+        self.year = Some(year.to_string());
+        self
+    }
+
+    pub fn volume(mut self, volume: &str) -> Self {
+        self.volume = Some(volume.to_string());
+        self
+    }
+
+    pub fn first_page(mut self, page: &str) -> Self {
+        self.first_page = Some(page.to_string());
+        self
+    }
+
+    pub fn author(mut self, author: &str) -> Self {
+        self.author = Some(author.to_string());
+        self
+    }
+
+    /// Format as a `bdata` citation string
+    ///
+    /// Format is `journal|year|volume|first_page|author|key|`, with each field encoded
+    /// for use in a URL.
+    pub fn to_bdata(&self) -> String {
+        let fields = [&self.journal, &self.year, &self.volume, &self.first_page, &self.author];
+        let mut bdata = String::new();
+        for field in fields {
+            if let Some(field) = field {
+                bdata.push_str(&encode_query_value(field));
+            }
+            bdata.push('|');
+        }
+        bdata.push_str(&encode_query_value(&self.key));
+        bdata.push('|');
+        bdata
+    }
+}
+
+/// Build URL for ECitMatch, which resolves citations into PubMed IDs
+pub fn build_citmatch_url(queries: &[CitMatchQuery]) -> String {
+    let mut url_str = format!("{}ecitmatch.cgi?", BASE);
+    url_str.push_str(&(format!("db={}", EntrezDb::PubMed.as_str())));
+    url_str.push_str("&retmode=xml");
+
+    let bdata: Vec<String> = queries.iter().map(|q| q.to_bdata()).collect();
+    url_str.push_str(&(format!("&bdata={}", bdata.join("%0D"))));
+
+    identify(url_str)
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum CitMatchStatus {
+    Found(PubMedId),
+    NotFound,
+    /// multiple PubMed entries match the citation
+    Ambiguous,
+}
+
+/// Result line returned by ECitMatch
+#[derive(Clone, PartialEq, Debug)]
+pub struct CitMatchResult {
+    /// key given by [`CitMatchQuery::key`]
+    pub key: String,
+    pub status: CitMatchStatus,
+}
+
+impl CitMatchResult {
+    /// Parse the plain text returned by ECitMatch
+    ///
+    /// Each line echoes the query fields, followed by the key and the resolved PMID.
+    pub fn from_response(response: &str) -> Vec<Self> {
+        response
+            .lines()
+            .filter_map(|line| {
+                let fields: Vec<&str> = line.trim().split('|').collect();
+                if fields.len() < 7 {
+                    return None;
+                }
+                let key = fields[5].to_string();
+                let result = fields[6].trim();
+                let status = if let Ok(pmid) = result.parse() {
+                    CitMatchStatus::Found(pmid)
+                } else if result.starts_with("AMBIGUOUS") {
+                    CitMatchStatus::Ambiguous
+                } else {
+                    CitMatchStatus::NotFound
+                };
+                Some(Self { key, status })
+            })
+            .collect()
+    }
+
+    pub fn pmid(&self) -> Option<PubMedId> {
+        match self.status {
+            CitMatchStatus::Found(pmid) => Some(pmid),
+            _ => None,
+        }
+    }
+}
+
+/// Resolve citations into PubMed IDs in a single request
+pub fn fetch_citmatch(queries: &[CitMatchQuery]) -> Result<Vec<CitMatchResult>, String> {
+    let url = build_citmatch_url(queries);
+    let response = http_get(&url)?;
+    Ok(CitMatchResult::from_response(response.as_str()))
+}
+
+/// Build URL for ELink, which returns records in `db` that are linked to each of `ids` in `dbfrom`
+///
+/// Each id is given as a separate parameter, so that links are returned in a separate
+/// [`LinkSet`] per id rather than merged.
+pub fn build_link_url<S: AsRef<str>>(dbfrom: EntrezDb, db: EntrezDb, ids: &[S]) -> String {
+    let mut url_str = format!("{}elink.fcgi?", BASE);
+    url_str.push_str(&(format!("dbfrom={}", dbfrom.as_str())));
+    url_str.push_str(&(format!("&db={}", db.as_str())));
+    for id in ids {
+        url_str.push_str(&(format!("&id={}", id.as_ref())));
+    }
+
+    identify(url_str)
+}
 
-    url_str
+#[derive(Clone, PartialEq, Debug, Default)]
+/// Single linked record within a [`LinkSetDb`]
+pub struct Link {
+    pub id: String,
+    /// similarity score, only given for neighbor links
+    pub score: Option<u64>,
 }
 
-//FIXME: Please a comment what this is about
+#[derive(Clone, PartialEq, Debug, Default)]
+/// Links from a [`LinkSet`] into a single database
+pub struct LinkSetDb {
+    pub db_to: String,
+    /// eg: "protein_cdd", "protein_structure"
+    pub link_name: String,
+    pub links: Vec<Link>,
+}
+
+#[derive(Clone, PartialEq, Debug, Default)]
+/// Records linked to the UIDs in [`Self::ids`]
+pub struct LinkSet {
+    pub db_from: String,
+    pub ids: Vec<String>,
+    pub link_set_dbs: Vec<LinkSetDb>,
+}
+
+impl LinkSet {
+    /// Parse every `<LinkSet>` within the raw XML returned by ELink
+    pub fn from_xml(response: &str) -> Result<Vec<Self>, String> {
+        let mut reader = Reader::from_str(response);
+
+        let mut sets: Vec<Self> = Vec::new();
+        let mut found = false;
+        let mut path: Vec<Vec<u8>> = Vec::new();
+
+        loop {
+            match reader.read_event() {
+                Ok(Event::Start(e)) => {
+                    match e.name().as_ref() {
+                        b"eLinkResult" => found = true,
+                        b"LinkSet" => sets.push(Self::default()),
+                        b"LinkSetDb" => {
+                            if let Some(set) = sets.last_mut() {
+                                set.link_set_dbs.push(LinkSetDb::default())
+                            }
+                        }
+                        b"Link" => {
+                            if let Some(db) = sets.last_mut().and_then(|s| s.link_set_dbs.last_mut()) {
+                                db.links.push(Link::default())
+                            }
+                        }
+                        _ => (),
+                    }
+                    path.push(e.name().as_ref().to_vec());
+                }
+                Ok(Event::Text(text)) => {
+                    let text = text.unescape().map_err(|e| format!("{:?}", e))?.to_string();
+                    let parent = path.len().checked_sub(2).and_then(|i| path.get(i));
+                    let set = match sets.last_mut() {
+                        Some(set) => set,
+                        None => continue,
+                    };
+                    let db = set.link_set_dbs.last_mut();
+                    match (parent.map(|p| p.as_slice()), path.last().map(|p| p.as_slice())) {
+                        (Some(b"LinkSet"), Some(b"DbFrom")) => set.db_from = text,
+                        (Some(b"IdList"), Some(b"Id")) => set.ids.push(text),
+                        (Some(b"LinkSetDb"), Some(b"DbTo")) => {
+                            if let Some(db) = db {
+                                db.db_to = text
+                            }
+                        }
+                        (Some(b"LinkSetDb"), Some(b"LinkName")) => {
+                            if let Some(db) = db {
+                                db.link_name = text
+                            }
+                        }
+                        (Some(b"Link"), Some(b"Id")) => {
+                            if let Some(link) = db.and_then(|db| db.links.last_mut()) {
+                                link.id = text
+                            }
+                        }
+                        (Some(b"Link"), Some(b"Score")) => {
+                            if let Some(link) = db.and_then(|db| db.links.last_mut()) {
+                                link.score = text.trim().parse().ok()
+                            }
+                        }
+                        _ => (),
+                    }
+                }
+                Ok(Event::End(_)) => {
+                    path.pop();
+                }
+                Ok(Event::Eof) => break,
+                Err(e) => return Err(format!("XML parsing error: {:?}", e)),
+                _ => (),
+            }
+        }
+
+        if found {
+            Ok(sets)
+        } else {
+            Err("No <eLinkResult> found in response.".to_string())
+        }
+    }
+
+    /// UIDs linked by `link_name`, or by any link if `None`
+    pub fn linked_ids(&self, link_name: Option<&str>) -> Vec<&str> {
+        self.link_set_dbs
+            .iter()
+            .filter(|db| link_name.is_none_or(|name| db.link_name == name))
+            .flat_map(|db| db.links.iter().map(|link| link.id.as_str()))
+            .collect()
+    }
+}
+
+/// Find records in `db` linked to each of `ids` in `dbfrom`
+pub fn fetch_links<S: AsRef<str>>(dbfrom: EntrezDb, db: EntrezDb, ids: &[S]) -> Result<Vec<LinkSet>, String> {
+    let url = build_link_url(dbfrom, db, ids);
+    let response = http_get(&url)?;
+    LinkSet::from_xml(response.as_str())
+}
+
+/// Structures linked to each protein in `ids`, in the same order as `ids`
+pub fn fetch_protein_structures<S: AsRef<str>>(ids: &[S]) -> Result<Vec<Vec<String>>, String> {
+    let sets = fetch_links(EntrezDb::Protein, EntrezDb::Structure, ids)?;
+    Ok(sets
+        .iter()
+        .map(|set| set.linked_ids(Some("protein_structure")).into_iter().map(String::from).collect())
+        .collect())
+}
+
+/// Object parsed from an EFetch response by [`parse_xml()`]
+///
+/// The variant is chosen by the root element of the response. Types registered by
+/// [`register_node()`] are returned as [`DataType::Custom`].
 #[derive(Debug)]
 pub enum DataType {
     BioSeqSet(BioSeqSet),
     EntrezgeneSet(EntrezgeneSet),
+    SeqSubmit(SeqSubmit),
+    CddSet(CddSet),
+    /// GenBank records, as given by `rettype=gb&retmode=xml`
+    GBSet(GBSet),
+    /// standalone annotation, such as an annotation track
+    SeqAnnot(SeqAnnot),
+    /// object of a type registered by [`register_node()`]
+    Custom(Box<dyn Any + Send>),
     /// placeholder for other types
     EtAl,
 }
 
+impl DataType {
+    /// Object parsed as `T` by a parser registered with [`register_node()`]
+    pub fn custom<T: Any>(&self) -> Option<&T> {
+        match self {
+            Self::Custom(node) => node.downcast_ref(),
+            _ => None,
+        }
+    }
+
+    /// Take the object parsed as `T` by a parser registered with [`register_node()`]
+    pub fn downcast<T: Any>(self) -> Option<T> {
+        match self {
+            Self::Custom(node) => node.downcast().ok().map(|node| *node),
+            _ => None,
+        }
+    }
+}
+
+/// Parser for the root element of a response
+///
+/// Called once the start tag of the root element has been read, with the same
+/// contract as [`XmlNode::from_reader()`].
+pub type RootParser = fn(&mut Reader<&[u8]>) -> Result<DataType, String>;
+
+/// Parsers of root elements, keyed by tag name
+fn registry() -> &'static RwLock<HashMap<String, RootParser>> {
+    static REGISTRY: OnceLock<RwLock<HashMap<String, RootParser>>> = OnceLock::new();
+    REGISTRY.get_or_init(|| {
+        let mut parsers: HashMap<String, RootParser> = HashMap::new();
+        parsers.insert("Bioseq-set".into(), |reader| {
+            BioSeqSet::from_reader(reader)
+                .map(DataType::BioSeqSet)
+                .ok_or("Failed to parse BioSeqSet.".to_string())
+        });
+        parsers.insert("Entrezgene-Set".into(), |reader| {
+            EntrezgeneSet::from_reader(reader)
+                .map(DataType::EntrezgeneSet)
+                .ok_or("Failed to parse EntrezgeneSet.".to_string())
+        });
+        parsers.insert("Seq-submit".into(), |reader| {
+            SeqSubmit::from_reader(reader)
+                .map(DataType::SeqSubmit)
+                .ok_or("Failed to parse SeqSubmit.".to_string())
+        });
+        parsers.insert("Cdd-set".into(), |reader| Ok(DataType::CddSet(read_cdd_set(reader))));
+        parsers.insert("GBSet".into(), |reader| Ok(DataType::GBSet(read_gb_set(reader))));
+        parsers.insert("Seq-annot".into(), |reader| {
+            SeqAnnot::from_reader(reader)
+                .map(DataType::SeqAnnot)
+                .ok_or("Failed to parse SeqAnnot.".to_string())
+        });
+        RwLock::new(parsers)
+    })
+}
+
+/// Parse root elements named `tag` with `parser` in [`parse_xml()`]
+///
+/// Returns the parser previously registered for `tag`, if any. Built-in parsers may be
+/// replaced this way.
+pub fn register_root(tag: impl Into<String>, parser: RootParser) -> Option<RootParser> {
+    registry().write().unwrap_or_else(|e| e.into_inner()).insert(tag.into(), parser)
+}
+
+/// Stop parsing root elements named `tag` in [`parse_xml()`]
+///
+/// Returns the parser that was registered for `tag`, if any.
+pub fn unregister_root(tag: &str) -> Option<RootParser> {
+    registry().write().unwrap_or_else(|e| e.into_inner()).remove(tag)
+}
+
+/// Parse root elements of `T` in [`parse_xml()`], returning them as [`DataType::Custom`]
+///
+/// # Example
+/// ```ignore
+/// register_node::<TSeqSet>();
+/// let set: TSeqSet = parse_xml(&response)?.downcast().ok_or("not a TSeqSet")?;
+/// ```
+pub fn register_node<T: XmlNode + Send + 'static>() -> Option<RootParser> {
+    let tag = String::from_utf8_lossy(T::start_bytes().name().as_ref()).into_owned();
+    register_root(tag, |reader| {
+        T::from_reader(reader)
+            .map(|node| DataType::Custom(Box::new(node)))
+            .ok_or_else(|| format!("Failed to parse {}.", std::any::type_name::<T>()))
+    })
+}
+
+/// Tag names of the root elements recognized by [`parse_xml()`]
+pub fn registered_roots() -> Vec<String> {
+    let mut tags: Vec<String> = registry()
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .keys()
+        .cloned()
+        .collect();
+    tags.sort();
+    tags
+}
+
+/// Parse an EFetch response according to its root element
+///
+/// The first element with a parser in the registry (see [`register_root()`]) is parsed.
+/// Diagnostics raised while parsing are logged along with their
+/// [`crate::parsing::Location`].
 pub fn parse_xml(response: &str) -> Result<DataType, String> {
+    with_locations(response, || read_data_type(response))
+}
+
+fn read_data_type(response: &str) -> Result<DataType, String> {
     let mut reader = Reader::from_str(response);
     reader.trim_text(true);
 
+    next_data_type(response, &mut reader).unwrap_or_else(|| match EntrezError::from_xml(response) {
+        Some(error) => Err(error.into()),
+        None => Err("No recognizable XML root tag found.".to_string()),
+    })
+}
+
+/// Parse the next element with a parser in the registry, if any is left
+///
+/// Errors are located at the position in `response` where parsing stopped.
+fn next_data_type(response: &str, reader: &mut Reader<&[u8]>) -> Option<Result<DataType, String>> {
     let mut buf = Vec::new();
 
     loop {
         match reader.read_event_into(&mut buf) {
             Ok(Event::Start(ref e)) => {
                 let tag_name = e.name().into_inner(); // Extract the inner byte slice
-                if let Ok(tag_str) = std::str::from_utf8(tag_name) {
-                    println!("Found XML tag: {}", tag_str); // Debugging output
-                } else {
-                    println!("Found XML tag (invalid UTF-8): {:?}", tag_name);
-                }
+                log::trace!("found <{}> at byte {}", tag_name.escape_ascii(), reader.buffer_position());
 
-                if tag_name == b"Bioseq-set" {
-                    println!("Matched Bioseq-Set, attempting to parse...");
-                    return BioSeqSet::from_reader(&mut reader)
-                        .map(|set| DataType::BioSeqSet(set))
-                        .ok_or("Failed to parse BioSeqSet.".to_string());
-                }
-                if tag_name == b"Entrezgene-Set" {
-                    println!("Matched Entrezgene-Set, attempting to parse...");
-                    return EntrezgeneSet::from_reader(&mut reader)
-                        .map(|set| DataType::EntrezgeneSet(set))
-                        .ok_or("Failed to parse EntrezgeneSet.".to_string());
+                let parser = std::str::from_utf8(tag_name).ok().and_then(|tag| {
+                    registry().read().unwrap_or_else(|e| e.into_inner()).get(tag).copied()
+                });
+                if let Some(parser) = parser {
+                    let result = parser(reader);
+                    return Some(result.map_err(|e| locate_error(response, reader.buffer_position(), &e)));
                 }
             }
-            Ok(Event::Eof) => break,
+            Ok(Event::Eof) => return None,
             Err(e) => {
-                return Err(format!("XML parsing error: {:?}", e));
+                let message = format!("XML parsing error: {:?}", e);
+                return Some(Err(locate_error(response, reader.buffer_position(), &message)));
             }
             _ => (),
         }
         buf.clear();
     }
+}
+
+/// Objects of a response made of several concatenated documents, see [`parse_xml_multi()`]
+pub struct DataTypes<'a> {
+    response: &'a str,
+    reader: Reader<&'a [u8]>,
+    done: bool,
+}
 
-    Err("No recognizable XML root tag found.".to_string())
+impl Iterator for DataTypes<'_> {
+    type Item = Result<DataType, String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let reader = &mut self.reader;
+        let response = self.response;
+        let next = with_locations(response, || next_data_type(response, reader));
+        self.done = !matches!(next, Some(Ok(_)));
+        next
+    }
 }
 
+/// Parse every top-level object of a response, as [`parse_xml()`] does for the first
+///
+/// Batch fetches may return several sets one after the other, each with its own XML
+/// declaration, as do local files joined with `cat`. Objects are parsed lazily, in
+/// order, and iteration stops after the first error.
+///
+/// # Example
+/// ```ignore
+/// for data in parse_xml_multi(&response) {
+///     if let DataType::BioSeqSet(set) = data? {
+///         // ...
+///     }
+/// }
+/// ```
+pub fn parse_xml_multi(response: &str) -> DataTypes<'_> {
+    let mut reader = Reader::from_str(response);
+    reader.trim_text(true);
+    DataTypes {
+        response,
+        reader,
+        done: false,
+    }
+}
+
+/// Parse XML as [`parse_xml()`], with the given [`ParseOptions`] in effect
+///
+/// # Example
+///
+/// Only read sequence identifiers and sources of a large set:
+///
+/// ```no_run
+/// use ncbi::parsing::ParseOptions;
+/// use ncbi::parse_xml_with;
+///
+/// let xml = std::fs::read_to_string("set.xml").unwrap();
+/// let options = ParseOptions::select(&["Bioseq_id", "Seqdesc_source"]);
+/// let set = parse_xml_with(&xml, &options).unwrap();
+/// ```
+pub fn parse_xml_with(response: &str, options: &ParseOptions) -> Result<DataType, String> {
+    options.apply(|| parse_xml(response))
+}
+
+/// Open a local file for buffered reading, decompressing it if gzip compressed
+///
+/// Compression is detected by the content of the file rather than its extension, so
+/// that downloaded archives (eg: "gene_info.gz", "set.xml.gz") are read transparently.
+pub fn open_local(path: impl AsRef<Path>) -> Result<Box<dyn BufRead>, String> {
+    let path = path.as_ref();
+    let file = File::open(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let mut reader = BufReader::new(file);
+    let header = reader.fill_buf().map_err(|e| format!("{}: {}", path.display(), e))?;
+    if header.starts_with(&GZIP_MAGIC) {
+        Ok(Box::new(BufReader::new(MultiGzDecoder::new(reader))))
+    } else {
+        Ok(Box::new(reader))
+    }
+}
+
+/// Read the content of a local file, decompressing it if gzip compressed
 pub fn get_local_xml(path: &str) -> String {
-    let file = fs::read(path);
-    return file.unwrap().escape_ascii().to_string();
+    let mut data = Vec::new();
+    open_local(path)
+        .and_then(|mut reader| reader.read_to_end(&mut data).map_err(|e| format!("{}: {}", path, e)))
+        .unwrap();
+    return data.escape_ascii().to_string();
 }
 
 pub fn fetch_data(db: EntrezDb, id: &str, r#type: &str, mode: &str) -> DataType {
     let url = build_fetch_url(db, id, r#type, mode);
-    let response = reqwest::blocking::get(url).unwrap().text().unwrap();
+    let response = http_get(&url).unwrap();
     parse_xml(response.as_str()).unwrap()
 }
 
+/// Fetch a population study set from [`EntrezDb::PopSet`]
+///
+/// Member sequences and their alignments are accessible via
+/// [`BioSeqSet::aligned_seqs()`].
+pub fn fetch_popset(id: &str) -> Result<BioSeqSet, String> {
+    let url = build_fetch_url(EntrezDb::PopSet, id, "native", "xml");
+    let response = http_get(&url)?;
+    match parse_xml(response.as_str())? {
+        DataType::BioSeqSet(set) => Ok(set),
+        _ => Err("PopSet response is not a Bioseq-set".to_string()),
+    }
+}
+
+/// Fetch records of `db` as FASTA
+///
+/// EFetch reports some failures (eg: an id which does not exist) with an HTML page or
+/// an XML error document rather than an HTTP error, so the response is checked to be
+/// FASTA. Use [`parse_fasta()`] to split the records.
+pub fn fetch_fasta(db: EntrezDb, id: &str) -> Result<String, String> {
+    let response = FetchRequest::new(db, id).rettype("fasta").retmode("text").fetch_text()?;
+    check_text_response(response, ">", "FASTA")
+}
+
+/// Fetch records of `db` as GenBank flat files, or GenPept for [`EntrezDb::Protein`]
+///
+/// The response is checked as by [`fetch_fasta()`].
+pub fn fetch_genbank(db: EntrezDb, id: &str) -> Result<String, String> {
+    let rettype = match db {
+        EntrezDb::Protein => "gp",
+        _ => "gb",
+    };
+    let response = FetchRequest::new(db, id).rettype(rettype).retmode("text").fetch_text()?;
+    check_text_response(response, "LOCUS", "GenBank")
+}
+
+/// Fail unless `response` starts with `prefix`, describing errors returned instead
+fn check_text_response(response: String, prefix: &str, format: &str) -> Result<String, String> {
+    let text = response.trim_start();
+    if text.starts_with(prefix) {
+        return Ok(response);
+    }
+    if text.is_empty() {
+        return Err(format!("Empty response instead of {}", format));
+    }
+
+    let lower = text.to_ascii_lowercase();
+    let between = |open: &str, close: &str| {
+        let start = lower.find(open)? + open.len();
+        let end = start + lower[start..].find(close)?;
+        Some(text[start..end].trim().to_string()).filter(|message| !message.is_empty())
+    };
+    let message = if lower.starts_with("<!doctype html") || lower.starts_with("<html") {
+        let title = between("<title>", "</title>").unwrap_or_else(|| "untitled".to_string());
+        format!("HTML page ({})", title)
+    } else if let Some(error) = EntrezError::from_xml(text) {
+        format!("error \"{}\"", error.message)
+    } else {
+        let line = text.lines().next().unwrap_or_default();
+        format!("\"{}\"", line.chars().take(80).collect::<String>())
+    };
+    Err(format!("Expected {} but EFetch returned {}", format, message))
+}
+
+/// Split FASTA formatted text into `(definition line, sequence)` pairs
+///
+/// Definition lines are given without the leading `>`, and sequences without line
+/// breaks. Comment lines starting with `;` are skipped.
+pub fn parse_fasta(text: &str) -> Result<Vec<(String, String)>, String> {
+    let mut records: Vec<(String, String)> = Vec::new();
+    for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        if let Some(defline) = line.strip_prefix('>') {
+            records.push((defline.trim().to_string(), String::new()));
+        } else if !line.starts_with(';') {
+            let (_, sequence) = records
+                .last_mut()
+                .ok_or("FASTA text does not start with a definition line".to_string())?;
+            sequence.push_str(line);
+        }
+    }
+    Ok(records)
+}
+
+/// Fetch records, parsing each `T` while the response body is still downloading
+///
+/// # Example
+/// ```ignore
+/// let mut entries = fetch_stream::<SeqEntry>(EntrezDb::Nucleotide, id, "native", "xml").await?;
+/// while let Some(entry) = entries.next_node().await {
+///     // ...
+/// }
+/// ```
+///
+/// The request is sent through [`crate::transport::http_stream_async()`].
+#[cfg(all(feature = "async", not(target_arch = "wasm32")))]
+pub async fn fetch_stream<T: XmlNode>(
+    db: EntrezDb,
+    id: &str,
+    r#type: &str,
+    mode: &str,
+) -> Result<NodeStream<ResponseReader, T>, String> {
+    let url = build_fetch_url(db, id, r#type, mode);
+    let body = http_stream_async(&url).await?;
+    Ok(NodeStream::new(body))
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::{build_fetch_url, build_search_url, get_local_xml, parse_xml, DataType, EntrezDb};
+    use crate::seq::{MolInfo, MolTech};
+    use crate::{register_node, registered_roots, unregister_root};
+    use crate::eutils::{strip_identity, ClientConfig};
+    use crate::{build_citmatch_url, build_fetch_url, build_link_url, build_search_url, build_spell_url, get_local_xml, http_get, parse_xml, parse_xml_multi, CitMatchQuery, CitMatchResult, CitMatchStatus, DataType, EntrezDb, FetchComplexity, FetchRequest, FetchStrand, LinkSet, SearchResult, SpellResult};
 
     #[test]
     fn search_url() {
         let _url = build_search_url(EntrezDb::Protein, "deaminase");
+
+        // reserved characters of the query are not taken as parameters
+        let url = build_search_url(EntrezDb::Gene, "tp73[sym] AND (human OR mouse)&retmax=1 #1+2");
+        assert!(url.contains("&term=tp73%5Bsym%5D+AND+%28human+OR+mouse%29%26retmax%3D1+%231%2B2&"));
+    }
+
+    #[test]
+    fn test_client_config() {
+        let config = ClientConfig::new().tool("ncbi-rs").email("me@example.org").api_key("abc123");
+        assert!(config.validate().is_ok());
+
+        let mut url = "https://example.org/efetch.fcgi?db=protein&id=1".to_string();
+        config.apply(&mut url);
+        assert!(url.ends_with("&id=1&tool=ncbi-rs&email=me@example.org&api_key=abc123"));
+        assert_eq!(strip_identity(&url), "https://example.org/efetch.fcgi?db=protein&id=1");
+
+        assert!(ClientConfig::new().batch(true).validate().is_err());
+        assert!(ClientConfig::new().tool("ncbi-rs").batch(true).validate().is_err());
+        assert!(config.clone().batch(true).validate().is_ok());
+        assert!(ClientConfig::new().email("nobody").validate().is_err());
+        assert!(ClientConfig::new().tool("my tool").validate().is_err());
+        assert!(ClientConfig::new().api_key("a&b=c").validate().is_err());
     }
 
     #[test]
@@ -176,6 +1984,84 @@ mod tests {
         let _url = build_fetch_url(EntrezDb::Protein, id, "native", "xml");
     }
 
+    #[test]
+    fn test_spell_result() {
+        let url = build_spell_url(EntrezDb::PubMed, "asthmaa OR alergies&retmax=1");
+        assert!(url.contains("&term=asthmaa+OR+alergies%26retmax%3D1"));
+
+        let xml = "<?xml version=\"1.0\" encoding=\"UTF-8\" ?>\
+            <eSpellResult>\
+            <Database>pubmed</Database>\
+            <Query>asthmaa OR alergies</Query>\
+            <CorrectedQuery>asthma or allergies</CorrectedQuery>\
+            <SpelledQuery><Replaced>asthma</Replaced><Original> OR </Original><Replaced>allergies</Replaced></SpelledQuery>\
+            <ERROR/>\
+            </eSpellResult>";
+        let result = SpellResult::from_xml(xml).unwrap();
+        assert_eq!(result.database, "pubmed");
+        assert_eq!(result.corrected_query, "asthma or allergies");
+        assert_eq!(result.replaced, vec!["asthma", "allergies"]);
+        assert!(result.has_suggestion());
+
+        assert!(SpellResult::from_xml("<eSearchResult></eSearchResult>").is_err());
+    }
+
+    #[test]
+    fn test_citmatch() {
+        let query = CitMatchQuery::new("Art1")
+            .journal("proc natl acad sci u s a")
+            .year("1991")
+            .volume("88")
+            .first_page("3248")
+            .author("mann bj");
+        assert_eq!(query.to_bdata(), "proc+natl+acad+sci+u+s+a|1991|88|3248|mann+bj|Art1|");
+
+        // reserved characters within fields are encoded
+        let reserved = CitMatchQuery::new("a&b").journal("j|1").author("o'brien+smith #2");
+        assert_eq!(reserved.to_bdata(), "j%7C1||||o%27brien%2Bsmith+%232|a%26b|");
+
+        let url = build_citmatch_url(&[query, CitMatchQuery::new("Art2").journal("science")]);
+        assert!(url.ends_with("&bdata=proc+natl+acad+sci+u+s+a|1991|88|3248|mann+bj|Art1|%0Dscience|||||Art2|"));
+
+        let response = "proc natl acad sci u s a|1991|88|3248|mann bj|Art1|2014248\n\
+            science|||||Art2|NOT_FOUND;INVALID_JOURNAL\n\
+            science|1987|235|182|palmenberg ac|Art3|AMBIGUOUS (2 citations)\n";
+        let results = CitMatchResult::from_response(response);
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].pmid(), Some(2014248));
+        assert_eq!(results[1].status, CitMatchStatus::NotFound);
+        assert_eq!(results[2].status, CitMatchStatus::Ambiguous);
+    }
+
+    #[test]
+    fn test_link_set() {
+        let url = build_link_url(EntrezDb::Protein, EntrezDb::ConservedDomains, &["NP_000537.3", "15718680"]);
+        assert!(url.ends_with("elink.fcgi?dbfrom=protein&db=cdd&id=NP_000537.3&id=15718680"));
+
+        let xml = "<?xml version=\"1.0\" encoding=\"UTF-8\" ?>\
+            <eLinkResult>\
+            <LinkSet><DbFrom>protein</DbFrom><IdList><Id>120407068</Id></IdList>\
+            <LinkSetDb><DbTo>cdd</DbTo><LinkName>protein_cdd</LinkName>\
+            <Link><Id>238123</Id><Score>250</Score></Link><Link><Id>214592</Id></Link>\
+            </LinkSetDb>\
+            <LinkSetDb><DbTo>cdd</DbTo><LinkName>protein_cdd_concise_2</LinkName>\
+            <Link><Id>238123</Id></Link>\
+            </LinkSetDb>\
+            </LinkSet>\
+            <LinkSet><DbFrom>protein</DbFrom><IdList><Id>15718680</Id></IdList></LinkSet>\
+            </eLinkResult>";
+        let sets = LinkSet::from_xml(xml).unwrap();
+        assert_eq!(sets.len(), 2);
+        assert_eq!(sets[0].db_from, "protein");
+        assert_eq!(sets[0].ids, vec!["120407068"]);
+        assert_eq!(sets[0].link_set_dbs[0].links[0].score, Some(250));
+        assert_eq!(sets[0].linked_ids(Some("protein_cdd")), vec!["238123", "214592"]);
+        assert_eq!(sets[0].linked_ids(None).len(), 3);
+        assert!(sets[1].linked_ids(None).is_empty());
+
+        assert!(LinkSet::from_xml("<eSearchResult></eSearchResult>").is_err());
+    }
+
     #[test]
     fn test_parse_xml() {
         let data = get_local_xml("tests/data/2519734237.xml");
@@ -199,8 +2085,248 @@ mod tests {
         let db = EntrezDb::PubMed;
 
         let url = build_fetch_url(db, id, "xml", "xml");
-        let _ = reqwest::blocking::get(url).unwrap().text().unwrap();
+        let _ = http_get(&url).unwrap();
         //let expected = from_str(text.as_str()).unwrap();
         //assert!(expected.is_empty().not())
     }
+
+    #[test]
+    fn test_search_result() {
+        let response = "<?xml version=\"1.0\" encoding=\"UTF-8\" ?>\
+            <eSearchResult><Count>2</Count><RetMax>2</RetMax><RetStart>0</RetStart>\
+            <IdList><Id>2519734237</Id><Id>2519734236</Id></IdList>\
+            <TranslationSet><Translation><From>x</From><To>y</To></Translation></TranslationSet>\
+            <TranslationStack><TermSet><Count>9</Count></TermSet></TranslationStack>\
+            </eSearchResult>";
+        let result = SearchResult::from_xml(response).unwrap();
+        assert_eq!(result.count, 2);
+        assert_eq!(result.ids, vec!["2519734237", "2519734236"]);
+        assert_eq!(result.web_env, None);
+
+        let response = "<eSearchResult><Count>9</Count><QueryKey>1</QueryKey><WebEnv>MCID_1</WebEnv></eSearchResult>";
+        let result = SearchResult::from_xml(response).unwrap();
+        assert_eq!((result.web_env.as_deref(), result.query_key), (Some("MCID_1"), Some(1)));
+
+        assert!(SearchResult::from_xml("<eSpellResult></eSpellResult>").is_err());
+    }
+
+    #[test]
+    fn fetch_request_url() {
+        let url = FetchRequest::new(EntrezDb::Nucleotide, "NZ_JARQWN010000024")
+            .rettype("fasta")
+            .retmode("text")
+            .region(1001, 2000)
+            .strand(FetchStrand::Minus)
+            .complexity(FetchComplexity::BioSeq)
+            .build_url();
+        assert_eq!(
+            url,
+            "https://eutils.ncbi.nlm.nih.gov/entrez/eutils/efetch.fcgi?db=nuccore&id=NZ_JARQWN010000024\
+            &rettype=fasta&retmode=text&seq_start=1001&seq_stop=2000&strand=2&complexity=1"
+        );
+
+        let url = FetchRequest::with_ids(EntrezDb::Protein, &["1", "2"])
+            .retstart(20)
+            .retmax(10)
+            .build_url();
+        assert!(url.ends_with("efetch.fcgi?db=protein&id=1,2&retstart=20&retmax=10"));
+
+        let url = FetchRequest::new(EntrezDb::Protein, "").history("MCID_1", 3).retmax(10).build_url();
+        assert!(url.ends_with("efetch.fcgi?db=protein&WebEnv=MCID_1&query_key=3&retmax=10"));
+
+        assert_eq!(
+            build_fetch_url(EntrezDb::Gene, "7161", "xml", "xml"),
+            FetchRequest::new(EntrezDb::Gene, "7161").rettype("xml").retmode("xml").build_url()
+        );
+
+        let invalid = FetchRequest::new(EntrezDb::Nucleotide, "1").region(10, 5);
+        assert!(invalid.fetch_text().is_err());
+    }
+
+    #[test]
+    fn entrez_db_names() {
+        for db in EntrezDb::ALL {
+            assert_eq!(db.to_string().parse::<EntrezDb>(), Ok(db));
+        }
+        assert_eq!("ClinVar".parse::<EntrezDb>(), Ok(EntrezDb::ClinVar));
+        assert_eq!("nucleotide".parse::<EntrezDb>(), Ok(EntrezDb::Nucleotide));
+        assert!("genbank".parse::<EntrezDb>().is_err());
+        assert_eq!(format!("db={}", EntrezDb::Gtr), "db=gtr");
+    }
+
+    #[test]
+    fn fetch_request_defaults() {
+        let request = FetchRequest::new(EntrezDb::Nucleotide, "1").with_defaults();
+        assert_eq!(request.rettype.as_deref(), Some("native"));
+        assert_eq!(request.retmode.as_deref(), Some("xml"));
+
+        // an explicit mode is kept without adding a default type
+        let request = FetchRequest::new(EntrezDb::Sra, "1").retmode("text").with_defaults();
+        assert_eq!(request.rettype, None);
+        assert_eq!(request.retmode.as_deref(), Some("text"));
+
+        let request = FetchRequest::new(EntrezDb::Gene, "7161").with_defaults();
+        assert!(request.build_url().ends_with("db=gene&id=7161&retmode=xml"));
+    }
+
+    #[test]
+    fn fetch_formats() {
+        // defaults of every supported database are accepted
+        for db in EntrezDb::ALL {
+            let info = db.info();
+            if info.retmode.is_some() {
+                assert_eq!(db.validate_format(info.rettype, info.retmode), Ok(()), "{}", db);
+            }
+        }
+
+        assert!(EntrezDb::Nucleotide.validate_format(Some("gb"), Some("text")).is_ok());
+        assert!(EntrezDb::Nucleotide.validate_format(Some("FASTA"), None).is_ok());
+        assert!(EntrezDb::Sra.validate_format(None, Some("xml")).is_ok());
+        assert!(EntrezDb::Structure.validate_format(None, None).is_err());
+
+        let error = EntrezDb::Nucleotide.validate_format(Some("gb"), Some("json")).unwrap_err();
+        assert_eq!(
+            error,
+            "rettype=gb&retmode=json is not valid for db=nuccore, try: \
+            rettype=gb&retmode=text (GenBank flat file), rettype=gb&retmode=xml (GBSeq XML)"
+        );
+        let error = EntrezDb::Protein.validate_format(Some("gb"), Some("text")).unwrap_err();
+        assert!(error.contains("rettype=gp&retmode=text"));
+
+        // requests are rejected before being sent
+        let request = FetchRequest::new(EntrezDb::Gene, "7161").rettype("fasta");
+        assert!(request.fetch_text().unwrap_err().contains("not valid for db=gene"));
+        let request = FetchRequest::new(EntrezDb::Nucleotide, "1").rettype("fasta").retmode("text");
+        assert!(request.fetch().unwrap_err().contains("use fetch_text()"));
+    }
+
+    #[test]
+    fn test_parse_registry() {
+        let roots = registered_roots();
+        for tag in ["Bioseq-set", "Cdd-set", "Entrezgene-Set", "GBSet", "Seq-annot", "Seq-submit"] {
+            assert!(roots.iter().any(|root| root == tag));
+        }
+
+        let xml = "<MolInfo><MolInfo_tech value=\"wgs\">20</MolInfo_tech></MolInfo>";
+        assert!(parse_xml(xml).is_err());
+
+        /// Removes the parser once the test is done, even if it fails
+        struct Unregister;
+        impl Drop for Unregister {
+            fn drop(&mut self) {
+                unregister_root("MolInfo");
+            }
+        }
+
+        assert!(register_node::<MolInfo>().is_none());
+        let _unregister = Unregister;
+        let molinfo: MolInfo = parse_xml(xml).unwrap().downcast().unwrap();
+        assert_eq!(molinfo.tech, MolTech::WGS);
+
+        let data = parse_xml(xml).unwrap();
+        assert!(data.custom::<MolInfo>().is_some());
+        assert!(data.custom::<String>().is_none());
+
+        drop(_unregister);
+        assert!(parse_xml(xml).is_err());
+        assert!(!registered_roots().iter().any(|root| root == "MolInfo"));
+    }
+
+    #[test]
+    fn test_check_text_response() {
+        use crate::eutils::check_text_response;
+
+        assert!(check_text_response("\n>NM_1.1 x\nACGT\n".to_string(), ">", "FASTA").is_ok());
+
+        let html = "<!DOCTYPE html><html><head><title>Bad Gateway</title></head></html>";
+        let err = check_text_response(html.to_string(), ">", "FASTA").unwrap_err();
+        assert_eq!(err, "Expected FASTA but EFetch returned HTML page (Bad Gateway)");
+
+        let xml = "<?xml version=\"1.0\"?>\n<eFetchResult><ERROR>ID list is empty!</ERROR></eFetchResult>";
+        let err = check_text_response(xml.to_string(), "LOCUS", "GenBank").unwrap_err();
+        assert!(err.ends_with("error \"ID list is empty!\""));
+
+        assert!(check_text_response("  \n".to_string(), ">", "FASTA").unwrap_err().starts_with("Empty"));
+    }
+
+    #[test]
+    fn test_entrez_error() {
+        use crate::eutils::EntrezError;
+
+        let xml = "<?xml version=\"1.0\"?>\n<eFetchResult>\n\t<ERROR>Cannot retrieve query keys</ERROR>\n</eFetchResult>";
+        let error = EntrezError::from_xml(xml).unwrap();
+        assert_eq!(error.utility.as_deref(), Some("efetch"));
+        assert_eq!(error.message, "Cannot retrieve query keys");
+        assert!(error.is_history_expired());
+
+        let url = build_fetch_url(EntrezDb::Nucleotide, "0", "native", "xml") + "&api_key=secret";
+        let error = error.with_url(&url);
+        assert_eq!(error.param("id"), Some("0"));
+        assert_eq!(error.param("api_key"), None);
+        assert_eq!(
+            error.to_string(),
+            "efetch error: Cannot retrieve query keys (db=nuccore, id=0, rettype=native, retmode=xml)"
+        );
+
+        // bare errors are attributed to the utility of the URL
+        let error = EntrezError::from_xml("<ERROR>Invalid db name specified: nope</ERROR>").unwrap();
+        assert_eq!(error.utility, None);
+        let error = error.with_url("https://eutils.ncbi.nlm.nih.gov/entrez/eutils/esearch.fcgi?db=nope&term=x");
+        assert_eq!(error.to_string(), "esearch error: Invalid db name specified: nope (db=nope, term=x)");
+
+        let search = "<eSearchResult><ERROR>Empty term and query_key - nothing todo</ERROR></eSearchResult>";
+        assert_eq!(
+            SearchResult::from_xml(search).unwrap_err(),
+            "esearch error: Empty term and query_key - nothing todo"
+        );
+        assert_eq!(parse_xml(search).unwrap_err(), "esearch error: Empty term and query_key - nothing todo");
+        assert!(!EntrezError::from_xml(search).unwrap().is_history_expired());
+
+        // only messages for a missing session are expiry
+        let expired = |message: &str| {
+            EntrezError::from_xml(&format!("<ERROR>{}</ERROR>", message)).unwrap().is_history_expired()
+        };
+        assert!(expired("Unable to obtain query #1"));
+        assert!(expired("Cannot retrieve history data. query_key: 1, WebEnv: MCID_1"));
+        assert!(!expired("Invalid WebEnv parameter"));
+        assert!(!expired("Query key 2 is not a number"));
+        assert!(!expired("history is not supported for this database"));
+
+        // empty or nested ERROR elements are not errors
+        assert_eq!(EntrezError::from_xml("<eSpellResult><Query>x</Query><ERROR/></eSpellResult>"), None);
+        assert_eq!(EntrezError::from_xml("<eSearchResult><ERROR></ERROR><Count>0</Count></eSearchResult>"), None);
+        assert_eq!(EntrezError::from_xml("<eLinkResult><LinkSet><ERROR>x</ERROR></LinkSet></eLinkResult>"), None);
+        assert_eq!(EntrezError::from_xml("<GBSet><ERROR>x</ERROR></GBSet>"), None);
+        assert_eq!(EntrezError::from_xml(""), None);
+    }
+
+    #[test]
+    fn test_parse_fasta() {
+        use crate::eutils::parse_fasta;
+
+        let text = ">NM_1.1 first\nACGT\nAC\n;comment\n\n>NP_2.1 second\nMK\n";
+        let records = parse_fasta(text).unwrap();
+        assert_eq!(
+            records,
+            vec![
+                ("NM_1.1 first".to_string(), "ACGTAC".to_string()),
+                ("NP_2.1 second".to_string(), "MK".to_string()),
+            ]
+        );
+        assert!(parse_fasta("ACGT\n>x\n").is_err());
+        assert_eq!(parse_fasta(""), Ok(Vec::new()));
+    }
+
+    #[test]
+    fn test_parse_xml_multi() {
+        let xml = "<?xml version=\"1.0\"?>\n<GBSet></GBSet>\n<?xml version=\"1.0\"?>\n<Cdd-set></Cdd-set>\n";
+        let data: Vec<DataType> = parse_xml_multi(xml).collect::<Result<_, _>>().unwrap();
+        assert!(matches!(data[..], [DataType::GBSet(_), DataType::CddSet(_)]));
+
+        assert_eq!(parse_xml_multi("<Unknown/>").count(), 0);
+        let mut objects = parse_xml_multi("<GBSet></GBSet></Stray><GBSet></GBSet>");
+        assert!(objects.next().unwrap().is_ok());
+        assert!(objects.next().unwrap().is_err());
+        assert!(objects.next().is_none());
+    }
 }
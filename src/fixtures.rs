@@ -0,0 +1,203 @@
+//! Recorded E-utilities responses for deterministic tests
+//!
+//! Every request made through [`http_get()`](crate::http_get), and its non-blocking
+//! counterparts in [`crate::transport`], can be served from a [`Cassette`] instead of
+//! the network, or recorded to disk for later replay. This allows code built on this
+//! crate to be tested without network access.
+//!
+//! The active mode is set per thread, and is reset once the returned [`FixtureGuard`]
+//! is dropped, so tests running in parallel do not interfere with each other.
+//!
+//! URLs are matched and recorded without the `tool`, `email` and `api_key` parameters
+//! of [`ClientConfig`](crate::ClientConfig), so that API keys are never written to disk.
+//!
+//! # Example
+//! ```ignore
+//! // record once with network access...
+//! let _guard = fixtures::record("tests/data/responses");
+//! let set = fetch_popset("2104551001")?;
+//!
+//! // ...then replay offline
+//! let _guard = fixtures::replay(Cassette::from_dir("tests/data/responses"));
+//! let set = fetch_popset("2104551001")?;
+//! ```
+
+use crate::eutils::strip_identity;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Clone, PartialEq, Debug, Default)]
+/// Collection of canned responses keyed by request URL
+pub struct Cassette {
+    /// directory of recorded responses
+    dir: Option<PathBuf>,
+    /// responses given in memory, which take precedence over `dir`
+    responses: HashMap<String, String>,
+}
+
+impl Cassette {
+    /// Empty cassette where responses are only given by [`Self::with_response()`]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Serve responses recorded to `dir` by [`record()`]
+    pub fn from_dir<P: AsRef<Path>>(dir: P) -> Self {
+        Self {
+            dir: Some(dir.as_ref().to_path_buf()),
+            responses: HashMap::new(),
+        }
+    }
+
+    /// Respond to `url` with `body`
+    pub fn with_response(mut self, url: &str, body: &str) -> Self {
+        self.responses.insert(strip_identity(url), body.to_string());
+        self
+    }
+
+    /// Recorded response for `url`
+    pub fn get(&self, url: &str) -> Option<String> {
+        let url = strip_identity(url);
+        if let Some(body) = self.responses.get(&url) {
+            return Some(body.clone());
+        }
+        let path = self.dir.as_ref()?.join(fixture_name(&url));
+        fs::read_to_string(path).ok()
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+enum Mode {
+    Replay(Cassette),
+    Record(PathBuf),
+}
+
+thread_local! {
+    static MODE: RefCell<Option<Mode>> = const { RefCell::new(None) };
+}
+
+/// Restores the previous mode when dropped
+#[must_use = "fixtures are disabled as soon as the guard is dropped"]
+pub struct FixtureGuard {
+    previous: Option<Mode>,
+}
+
+impl Drop for FixtureGuard {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        MODE.with(|mode| *mode.borrow_mut() = previous);
+    }
+}
+
+fn set_mode(new: Mode) -> FixtureGuard {
+    let previous = MODE.with(|mode| mode.borrow_mut().replace(new));
+    FixtureGuard { previous }
+}
+
+/// Serve all requests on this thread from `cassette`
+///
+/// Requests without a recorded response fail instead of reaching the network.
+pub fn replay(cassette: Cassette) -> FixtureGuard {
+    set_mode(Mode::Replay(cassette))
+}
+
+/// Save every response received on this thread to `dir`
+pub fn record<P: AsRef<Path>>(dir: P) -> FixtureGuard {
+    set_mode(Mode::Record(dir.as_ref().to_path_buf()))
+}
+
+/// File name that a response to `url` is recorded as
+///
+/// Names are derived from a 64-bit FNV-1a hash of the URL, so that they are
+/// stable across platforms and compiler versions.
+pub fn fixture_name(url: &str) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in url.as_bytes() {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}.txt", hash)
+}
+
+/// Whether responses are replayed or recorded on this thread
+pub(crate) fn is_active() -> bool {
+    MODE.with(|mode| mode.borrow().is_some())
+}
+
+/// Response to `url` if replaying
+pub(crate) fn intercept(url: &str) -> Option<Result<String, String>> {
+    MODE.with(|mode| match &*mode.borrow() {
+        Some(Mode::Replay(cassette)) => Some(
+            cassette
+                .get(url)
+                .ok_or(format!("No recorded response for {}", url)),
+        ),
+        _ => None,
+    })
+}
+
+/// Save `body` if recording
+pub(crate) fn record_response(url: &str, body: &str) -> Result<(), String> {
+    let url = &strip_identity(url);
+    MODE.with(|mode| match &*mode.borrow() {
+        Some(Mode::Record(dir)) => {
+            fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+            fs::write(dir.join(fixture_name(url)), body).map_err(|e| e.to_string())?;
+
+            // keep an index so that recordings can be inspected by hand
+            let index = dir.join("index.tsv");
+            let mut entries = fs::read_to_string(&index).unwrap_or_default();
+            entries.push_str(&format!("{}\t{}\n", fixture_name(url), url));
+            fs::write(index, entries).map_err(|e| e.to_string())
+        }
+        _ => Ok(()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixtures::{fixture_name, record_response, replay, Cassette};
+    use crate::{build_fetch_url, http_get, EntrezDb};
+    use std::fs;
+
+    #[test]
+    fn test_fixture_name() {
+        assert_eq!(fixture_name(""), "cbf29ce484222325.txt");
+        assert_ne!(fixture_name("a"), fixture_name("b"));
+    }
+
+    #[test]
+    fn test_replay_in_memory() {
+        let url = build_fetch_url(EntrezDb::Nucleotide, "1", "fasta", "text");
+        let _guard = replay(Cassette::new().with_response(&url, ">1\nACGT\n"));
+
+        assert_eq!(http_get(&url), Ok(">1\nACGT\n".to_string()));
+        assert!(http_get("https://example.com").is_err());
+    }
+
+    #[test]
+    fn test_guard_restores_mode() {
+        {
+            let _guard = replay(Cassette::new());
+            assert!(super::intercept("x").is_some());
+        }
+        assert!(super::intercept("x").is_none());
+    }
+
+    #[test]
+    fn test_record_then_replay() {
+        let dir = std::env::temp_dir().join(format!("ncbi-fixtures-{}", std::process::id()));
+        let url = "https://eutils.ncbi.nlm.nih.gov/entrez/eutils/einfo.fcgi";
+        {
+            let _guard = super::record(&dir);
+            record_response(url, "<eInfoResult/>").unwrap();
+        }
+        assert!(fs::read_to_string(dir.join("index.tsv")).unwrap().contains(url));
+
+        let _guard = replay(Cassette::from_dir(&dir));
+        assert_eq!(http_get(url), Ok("<eInfoResult/>".to_string()));
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,324 @@
+//! Readers for the tab-delimited files of the NCBI Gene FTP site
+//!
+//! Bulk data for every gene is distributed as flat files (eg: `gene_info`,
+//! `gene2accession`, `gene2go` from <https://ftp.ncbi.nlm.nih.gov/gene/DATA/>), which
+//! are too large to fetch through E-utilities. [`FlatReader`] streams the rows of such
+//! a file as typed records, one line at a time, optionally keeping only the rows of
+//! selected taxa. Rows of other taxa are skipped before being parsed.
+//!
+//! Files may be gzip compressed, as distributed (eg: `gene_info.gz`).
+//!
+//! # Example
+//! ```ignore
+//! let human = open_flat::<GeneInfo>("Homo_sapiens.gene_info.gz")?.with_taxa(&[9606]);
+//! for gene in human {
+//!     let gene = gene?;
+//!     println!("{}\t{}", gene.gene_id, gene.symbol);
+//! }
+//! ```
+
+use crate::coords::{Pos0, Range0};
+use crate::entrezgene::EntrezgeneType;
+use crate::general::{DbTag, ObjectId};
+use crate::open_local;
+use crate::seqloc::NaStrand;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::io::{BufRead, Lines};
+use std::marker::PhantomData;
+use std::path::Path;
+
+/// A row of a tab-delimited Gene file
+pub trait FlatRecord: Sized {
+    /// Minimum number of columns of a row
+    const COLUMNS: usize;
+
+    /// Parse the columns of a single row
+    fn from_fields(fields: &[&str]) -> Result<Self, String>;
+}
+
+/// Value of a column, where "-" denotes an empty value
+fn optional(field: &str) -> Option<String> {
+    match field.trim() {
+        "" | "-" => None,
+        field => Some(field.to_string()),
+    }
+}
+
+/// Values of a "|" separated column
+fn list(field: &str) -> Vec<String> {
+    optional(field)
+        .iter()
+        .flat_map(|field| field.split('|'))
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn number<T: std::str::FromStr>(field: &str, column: &str) -> Result<Option<T>, String> {
+    optional(field)
+        .map(|field| field.parse().map_err(|_| format!("Invalid {} `{}`", column, field)))
+        .transpose()
+}
+
+fn required<T: std::str::FromStr>(field: &str, column: &str) -> Result<T, String> {
+    number(field, column)?.ok_or_else(|| format!("Missing {}", column))
+}
+
+/// Cross reference given as "db:tag" (eg: "HGNC:HGNC:11998")
+fn db_tag(xref: &str) -> Result<DbTag, String> {
+    let (db, tag) = xref
+        .split_once(':')
+        .ok_or_else(|| format!("Invalid dbXref `{}`", xref))?;
+    let tag = match tag.parse() {
+        Ok(id) => ObjectId::Id(id),
+        Err(_) => ObjectId::Str(tag.into()),
+    };
+    Ok(DbTag { db: db.into(), tag })
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+/// Row of `gene_info`, describing a single gene
+pub struct GeneInfo {
+    pub tax_id: u64,
+    pub gene_id: u64,
+    pub symbol: String,
+    pub locus_tag: Option<String>,
+    pub synonyms: Vec<String>,
+    pub db_xrefs: Vec<DbTag>,
+    pub chromosome: Option<String>,
+    pub map_location: Option<String>,
+    pub description: Option<String>,
+    pub gene_type: EntrezgeneType,
+    /// symbol given by a nomenclature authority (eg: HGNC)
+    pub nomenclature_symbol: Option<String>,
+    /// full name given by a nomenclature authority
+    pub nomenclature_name: Option<String>,
+    /// "O" for official, "I" for interim
+    pub nomenclature_status: Option<String>,
+    pub other_designations: Vec<String>,
+    /// eg: "20240312"
+    pub modification_date: Option<String>,
+    /// eg: "C_region", "V_segment"
+    pub feature_type: Vec<String>,
+}
+
+impl FlatRecord for GeneInfo {
+    const COLUMNS: usize = 15;
+
+    fn from_fields(fields: &[&str]) -> Result<Self, String> {
+        Ok(Self {
+            tax_id: required(fields[0], "tax_id")?,
+            gene_id: required(fields[1], "GeneID")?,
+            symbol: fields[2].trim().to_string(),
+            locus_tag: optional(fields[3]),
+            synonyms: list(fields[4]),
+            db_xrefs: list(fields[5]).iter().map(|xref| db_tag(xref)).collect::<Result<_, _>>()?,
+            chromosome: optional(fields[6]),
+            map_location: optional(fields[7]),
+            description: optional(fields[8]),
+            gene_type: EntrezgeneType::from_name(fields[9].trim()),
+            nomenclature_symbol: optional(fields[10]),
+            nomenclature_name: optional(fields[11]),
+            nomenclature_status: optional(fields[12]),
+            other_designations: list(fields[13]),
+            modification_date: optional(fields[14]),
+            feature_type: fields.get(15).map(|field| list(field)).unwrap_or_default(),
+        })
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+/// Versioned accession and GI of a sequence, as given by `gene2accession`
+pub struct FlatSeqId {
+    /// eg: "NM_000546.6"
+    pub accession: String,
+    pub gi: Option<u64>,
+}
+
+impl FlatSeqId {
+    fn from_fields(accession: &str, gi: &str) -> Result<Option<Self>, String> {
+        match optional(accession) {
+            Some(accession) => Ok(Some(Self {
+                accession,
+                gi: number(gi, "gi")?,
+            })),
+            None => Ok(None),
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+/// Row of `gene2accession`, relating a gene to the sequences that it is annotated on
+pub struct GeneAccession {
+    pub tax_id: u64,
+    pub gene_id: u64,
+    /// RefSeq status (eg: "REVIEWED", "VALIDATED"), if the sequences are RefSeq
+    pub status: Option<String>,
+    pub rna: Option<FlatSeqId>,
+    pub protein: Option<FlatSeqId>,
+    pub genomic: Option<FlatSeqId>,
+    /// 0-based start on [`Self::genomic`]
+    pub start: Option<u64>,
+    /// 0-based end on [`Self::genomic`]
+    pub end: Option<u64>,
+    pub orientation: Option<NaStrand>,
+    pub assembly: Option<String>,
+    pub mature_peptide: Option<FlatSeqId>,
+    pub symbol: Option<String>,
+}
+
+impl FlatRecord for GeneAccession {
+    const COLUMNS: usize = 13;
+
+    fn from_fields(fields: &[&str]) -> Result<Self, String> {
+        let orientation = match fields[11].trim() {
+            "+" => Some(NaStrand::Plus),
+            "-" => Some(NaStrand::Minus),
+            _ => None,
+        };
+        Ok(Self {
+            tax_id: required(fields[0], "tax_id")?,
+            gene_id: required(fields[1], "GeneID")?,
+            status: optional(fields[2]),
+            rna: FlatSeqId::from_fields(fields[3], fields[4])?,
+            protein: FlatSeqId::from_fields(fields[5], fields[6])?,
+            genomic: FlatSeqId::from_fields(fields[7], fields[8])?,
+            start: number(fields[9], "start_position_on_the_genomic_accession")?,
+            end: number(fields[10], "end_position_on_the_genomic_accession")?,
+            orientation,
+            assembly: optional(fields[12]),
+            mature_peptide: match fields.get(13..15) {
+                Some([accession, gi]) => FlatSeqId::from_fields(accession, gi)?,
+                _ => None,
+            },
+            symbol: fields.get(15).and_then(|field| optional(field)),
+        })
+    }
+}
+
+impl GeneAccession {
+    /// Positions of the gene on [`Self::genomic`], unless not given
+    pub fn range0(&self) -> Option<Range0> {
+        Range0::new(Pos0::new(self.start?), Pos0::new(self.end?))
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+/// Row of `gene2go`, annotating a gene with a Gene Ontology term
+pub struct GeneGo {
+    pub tax_id: u64,
+    pub gene_id: u64,
+    /// eg: "GO:0003677"
+    pub go_id: String,
+    /// evidence code (eg: "IDA", "IEA")
+    pub evidence: Option<String>,
+    /// eg: "enables", "NOT|involved_in"
+    pub qualifier: Option<String>,
+    pub go_term: String,
+    pub pubmed_ids: Vec<u64>,
+    /// "Function", "Process" or "Component"
+    pub category: String,
+}
+
+impl GeneGo {
+    /// Whether the annotation states that the gene does *not* have the term
+    pub fn is_negated(&self) -> bool {
+        self.qualifier.as_deref().is_some_and(|qualifier| qualifier.starts_with("NOT"))
+    }
+}
+
+impl FlatRecord for GeneGo {
+    const COLUMNS: usize = 8;
+
+    fn from_fields(fields: &[&str]) -> Result<Self, String> {
+        Ok(Self {
+            tax_id: required(fields[0], "tax_id")?,
+            gene_id: required(fields[1], "GeneID")?,
+            go_id: fields[2].trim().to_string(),
+            evidence: optional(fields[3]),
+            qualifier: optional(fields[4]),
+            go_term: fields[5].trim().to_string(),
+            pubmed_ids: list(fields[6])
+                .iter()
+                .map(|id| id.parse().map_err(|_| format!("Invalid PubMed id `{}`", id)))
+                .collect::<Result<_, _>>()?,
+            category: fields[7].trim().to_string(),
+        })
+    }
+}
+
+/// Streams the rows of a tab-delimited Gene file as `T`
+///
+/// Header lines (starting with "#") and blank lines are skipped. Rows which cannot be
+/// parsed are returned as errors, which give the line number, so that iteration may
+/// continue past them.
+pub struct FlatReader<R, T> {
+    lines: Lines<R>,
+    taxa: Option<HashSet<u64>>,
+    line: usize,
+    record: PhantomData<T>,
+}
+
+impl<R: BufRead, T: FlatRecord> FlatReader<R, T> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            lines: reader.lines(),
+            taxa: None,
+            line: 0,
+            record: PhantomData,
+        }
+    }
+
+    /// Only return rows whose `tax_id` is one of `taxa`
+    pub fn with_taxa(mut self, taxa: &[u64]) -> Self {
+        self.taxa.get_or_insert_with(HashSet::new).extend(taxa);
+        self
+    }
+
+    fn is_selected(&self, line: &str) -> bool {
+        match &self.taxa {
+            Some(taxa) => line
+                .split('\t')
+                .next()
+                .and_then(|tax_id| tax_id.trim().parse().ok())
+                .is_some_and(|tax_id| taxa.contains(&tax_id)),
+            None => true,
+        }
+    }
+}
+
+impl<R: BufRead, T: FlatRecord> Iterator for FlatReader<R, T> {
+    type Item = Result<T, String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let line = match self.lines.next()? {
+                Ok(line) => line,
+                Err(e) => return Some(Err(e.to_string())),
+            };
+            self.line += 1;
+
+            if line.trim().is_empty() || line.starts_with('#') || !self.is_selected(&line) {
+                continue;
+            }
+
+            let fields: Vec<&str> = line.split('\t').collect();
+            if fields.len() < T::COLUMNS {
+                return Some(Err(format!(
+                    "Line {}: expected {} columns, found {}",
+                    self.line,
+                    T::COLUMNS,
+                    fields.len()
+                )));
+            }
+            return Some(T::from_fields(&fields).map_err(|e| format!("Line {}: {}", self.line, e)));
+        }
+    }
+}
+
+/// Stream the rows of the file at `path`, which may be gzip compressed
+pub fn open_flat<T: FlatRecord>(path: impl AsRef<Path>) -> Result<FlatReader<Box<dyn BufRead>, T>, String> {
+    Ok(FlatReader::new(open_local(path)?))
+}
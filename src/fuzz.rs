@@ -0,0 +1,32 @@
+//! Entry points for fuzzing the XML parsers
+//!
+//! Each function accepts arbitrary bytes and must never panic, so they may be called
+//! directly from a [`cargo-fuzz`](https://github.com/rust-fuzz/cargo-fuzz) target:
+//!
+//! ```ignore
+//! fuzz_target!(|data: &[u8]| {
+//!     ncbi::fuzz::fuzz_parse_bioseq(data);
+//! });
+//! ```
+//!
+//! Input that is not valid UTF-8 is rejected up front, as it is by [`parse_xml()`].
+
+use crate::entrezgene::Entrezgene;
+use crate::parsing::parse_node;
+use crate::seq::BioSeq;
+use crate::{parse_xml, DataType};
+
+/// Parse the first `Bioseq` element in `data`
+pub fn fuzz_parse_bioseq(data: &[u8]) -> Option<BioSeq> {
+    parse_node(std::str::from_utf8(data).ok()?)
+}
+
+/// Parse the first `Entrezgene` element in `data`
+pub fn fuzz_parse_entrezgene(data: &[u8]) -> Option<Entrezgene> {
+    parse_node(std::str::from_utf8(data).ok()?)
+}
+
+/// Parse `data` as an E-utilities response via [`parse_xml()`]
+pub fn fuzz_parse_xml(data: &[u8]) -> Option<DataType> {
+    parse_xml(std::str::from_utf8(data).ok()?).ok()
+}
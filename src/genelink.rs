@@ -0,0 +1,143 @@
+//! Linking of annotated features to their Entrezgene records
+//!
+//! Gene and CDS features of RefSeq genomes carry a `GeneID` cross-reference (eg:
+//! `db_xref "GeneID:7157"`). [`gene_links()`] collects these cross-references, and
+//! [`fetch_feature_genes()`] retrieves the corresponding [`Entrezgene`] records in
+//! sequential, rate limited batches, using the non-blocking transport (see
+//! [`crate::transport`]).
+//!
+//! Features are identified by their [`FeatId`] when given, otherwise by their
+//! `locus_tag`, so the gene and CDS features of a locus usually share the same key.
+//!
+//! # Example
+//! ```ignore
+//! let genes = fetch_feature_genes(&set).await?;
+//! for (key, gene) in genes.iter() {
+//!     println!("{:?}\t{:?}", key, gene.gene.locus);
+//! }
+//! ```
+
+use crate::entrezgene::Entrezgene;
+use crate::seq::BioSeq;
+use crate::seqfeat::{FeatId, SeqFeat, SeqFeatData};
+use crate::seqset::BioSeqSet;
+use crate::transport::http_get_async;
+use crate::{parse_xml, DataType, EntrezDb, EntrezError, FetchRequest};
+use std::collections::{BTreeSet, HashMap};
+
+/// Maximum number of GeneIDs sent in a single EFetch request
+const BATCH_SIZE: usize = 200;
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+/// Identifies a feature linked to a gene
+pub enum FeatureKey {
+    Id(FeatId),
+    LocusTag(String),
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+/// `GeneID` cross-reference of a gene or CDS feature
+pub struct GeneLink {
+    pub key: FeatureKey,
+    pub gene_id: u64,
+}
+
+impl GeneLink {
+    /// Link of `feat`, unless it is neither a gene nor a CDS, has no `GeneID`
+    /// cross-reference, or has neither an id nor a `locus_tag`
+    pub fn from_feature(feat: &SeqFeat) -> Option<Self> {
+        let gene = match &feat.data {
+            SeqFeatData::Gene(gene) => Some(gene),
+            SeqFeatData::CdRegion(_) => feat.gene_xref(),
+            _ => return None,
+        };
+        let gene_id = feat.gene_id()?;
+        let key = match &feat.id {
+            Some(id) => FeatureKey::Id(id.clone()),
+            None => {
+                let locus_tag = gene
+                    .and_then(|gene| gene.locus_tag.as_deref())
+                    .or_else(|| feat.qualifiers().find(|(name, _)| *name == "locus_tag").map(|(_, value)| value))?;
+                FeatureKey::LocusTag(locus_tag.to_string())
+            }
+        };
+        Some(Self { key, gene_id })
+    }
+}
+
+/// Links of every gene and CDS feature annotated on `bioseq`
+pub fn bioseq_gene_links(bioseq: &BioSeq) -> Vec<GeneLink> {
+    bioseq
+        .annot
+        .iter()
+        .flatten()
+        .flat_map(|annot| annot.features())
+        .filter_map(GeneLink::from_feature)
+        .collect()
+}
+
+/// Links of every gene and CDS feature annotated on the sequences of `set`
+pub fn gene_links(set: &BioSeqSet) -> Vec<GeneLink> {
+    set.bioseqs().into_iter().flat_map(bioseq_gene_links).collect()
+}
+
+/// EFetch request for the Entrezgene records of `ids`
+pub fn build_gene_fetch_url(ids: &[u64]) -> String {
+    let ids: Vec<String> = ids.iter().map(u64::to_string).collect();
+    FetchRequest::with_ids(EntrezDb::Gene, &ids).retmode("xml").build_url()
+}
+
+/// Fetch the Entrezgene records of `ids`, [`BATCH_SIZE`] ids per request
+///
+/// Batches are requested one after another, each waiting for the installed
+/// [`crate::ratelimit::RateLimiter`], so that large genomes do not exceed the rate
+/// allowed by NCBI. Ids for which no record is returned (eg: discontinued genes) are
+/// left out.
+pub async fn fetch_entrezgenes(ids: &[u64]) -> Result<Vec<Entrezgene>, String> {
+    let mut genes = Vec::with_capacity(ids.len());
+    for batch in ids.chunks(BATCH_SIZE) {
+        let url = build_gene_fetch_url(batch);
+        let response = http_get_async(&url).await?;
+        if let Some(error) = EntrezError::from_xml(&response) {
+            return Err(error.with_url(&url).into());
+        }
+        match parse_xml(&response)? {
+            DataType::EntrezgeneSet(set) => genes.extend(set),
+            _ => return Err(format!("Request for {} did not return an Entrezgene-Set", url)),
+        }
+    }
+    Ok(genes)
+}
+
+/// Entrezgene record of every gene and CDS feature of `set` with a `GeneID`
+/// cross-reference, by [`FeatureKey`]
+///
+/// Each gene is fetched once, however many features refer to it. Features whose
+/// gene is not returned are left out.
+pub async fn fetch_feature_genes(set: &BioSeqSet) -> Result<HashMap<FeatureKey, Entrezgene>, String> {
+    let links = gene_links(set);
+    let ids: Vec<u64> = links
+        .iter()
+        .map(|link| link.gene_id)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+
+    let mut genes: HashMap<u64, Entrezgene> = HashMap::new();
+    for gene in fetch_entrezgenes(&ids).await? {
+        if let Some(track) = &gene.track_info {
+            genes.insert(track.geneid, gene);
+        }
+    }
+
+    let mut linked = HashMap::new();
+    for link in links {
+        match genes.get(&link.gene_id) {
+            Some(gene) => {
+                linked.insert(link.key, gene.clone());
+            }
+            None => log::warn!("No Entrezgene record returned for GeneID {}", link.gene_id),
+        }
+    }
+    Ok(linked)
+}
@@ -0,0 +1,182 @@
+//! Gene Expression Omnibus summaries
+//!
+//! GEO records are not available through EFetch, however ESummary on
+//! [`EntrezDb::GeoDatasets`] and [`EntrezDb::GeoProfiles`] describes each record well
+//! enough to discover and enumerate datasets. [`GeoDataset`] summarizes a series
+//! (GSE), sample (GSM), platform (GPL) or curated dataset (GDS), and [`GeoProfile`]
+//! summarizes the expression profile of a single gene within a GDS. Expression values
+//! themselves must be downloaded from GEO (see [`GeoDataset::ftp_link`]).
+
+use crate::eutils::{read_docsums, DocSumItem};
+use crate::{build_summary_url, fetch_search, http_get, EntrezDb};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+/// Summary of a GEO record, as given by ESummary on [`EntrezDb::GeoDatasets`]
+pub struct GeoDataset {
+    /// Entrez UID
+    pub uid: u64,
+    /// eg: "GSE2034", "GDS1234", "GPL570"
+    pub accession: String,
+    /// eg: "GSE", "GDS", "GPL", "GSM"
+    pub entry_type: Option<String>,
+    pub title: Option<String>,
+    pub summary: Option<String>,
+    /// organisms, separated by "; "
+    pub taxon: Option<String>,
+    /// eg: "Expression profiling by array"
+    pub gds_type: Option<String>,
+    /// platform accessions (eg: "GPL96")
+    pub platforms: Vec<String>,
+    /// series accessions (eg: "GSE2034")
+    pub series: Vec<String>,
+    pub samples: Vec<GeoSample>,
+    /// total number of samples, which may exceed the number of `samples` listed
+    pub n_samples: Option<u64>,
+    pub pubmed_ids: Vec<u64>,
+    /// publication date (eg: "2005/12/16")
+    pub published: Option<String>,
+    /// eg: "PRJNA91413"
+    pub bioproject: Option<String>,
+    /// location of the series matrix and supplementary files
+    pub ftp_link: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+pub struct GeoSample {
+    /// eg: "GSM36777"
+    pub accession: String,
+    pub title: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+/// Summary of a gene expression profile, as given by ESummary on [`EntrezDb::GeoProfiles`]
+pub struct GeoProfile {
+    /// Entrez UID
+    pub uid: u64,
+    /// probe identifier on the platform (eg: "201746_at")
+    pub id_ref: Option<String>,
+    /// accession of the dataset the profile belongs to (eg: "GDS1234")
+    pub dataset: Option<String>,
+    /// title of the dataset
+    pub title: Option<String>,
+    pub gene_name: Option<String>,
+    pub gene_desc: Option<String>,
+    pub gene_id: Option<u64>,
+    /// GenBank accession of the probe sequence
+    pub genbank_accession: Option<String>,
+    pub taxon: Option<String>,
+}
+
+/// Split a list of numeric ids (eg: "96;570") into accessions with the given prefix
+fn accessions(items: &[DocSumItem], name: &str, prefix: &str) -> Vec<String> {
+    DocSumItem::value(items, name)
+        .iter()
+        .flat_map(|value| value.split(';'))
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(|id| format!("{}{}", prefix, id))
+        .collect()
+}
+
+impl GeoDataset {
+    /// Parse all `DocSum` elements of an ESummary response for [`EntrezDb::GeoDatasets`]
+    pub fn from_esummary(response: &str) -> Result<Vec<Self>, String> {
+        let docsums = read_docsums(response)?;
+        Ok(docsums
+            .into_iter()
+            .map(|(id, items)| Self::from_items(&id, &items))
+            .collect())
+    }
+
+    fn from_items(id: &str, items: &[DocSumItem]) -> Self {
+        let samples = DocSumItem::find(items, "Samples")
+            .map(|samples| {
+                samples
+                    .items
+                    .iter()
+                    .map(|sample| GeoSample {
+                        accession: DocSumItem::value(&sample.items, "Accession").unwrap_or_default(),
+                        title: DocSumItem::value(&sample.items, "Title"),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        let pubmed_ids = DocSumItem::find(items, "PubMedIds")
+            .map(|ids| ids.items.iter().filter_map(|id| id.value.trim().parse().ok()).collect())
+            .unwrap_or_default();
+
+        Self {
+            uid: id.trim().parse().unwrap_or_default(),
+            accession: DocSumItem::value(items, "Accession").unwrap_or_default(),
+            entry_type: DocSumItem::value(items, "entryType"),
+            title: DocSumItem::value(items, "title"),
+            summary: DocSumItem::value(items, "summary"),
+            taxon: DocSumItem::value(items, "taxon"),
+            gds_type: DocSumItem::value(items, "gdsType"),
+            platforms: accessions(items, "GPL", "GPL"),
+            series: accessions(items, "GSE", "GSE"),
+            samples,
+            n_samples: DocSumItem::value(items, "n_samples").and_then(|n| n.parse().ok()),
+            pubmed_ids,
+            published: DocSumItem::value(items, "PDAT"),
+            bioproject: DocSumItem::value(items, "Bioproject"),
+            ftp_link: DocSumItem::value(items, "FTPLink"),
+        }
+    }
+}
+
+impl GeoProfile {
+    /// Parse all `DocSum` elements of an ESummary response for [`EntrezDb::GeoProfiles`]
+    pub fn from_esummary(response: &str) -> Result<Vec<Self>, String> {
+        let docsums = read_docsums(response)?;
+        Ok(docsums
+            .into_iter()
+            .map(|(id, items)| Self::from_items(&id, &items))
+            .collect())
+    }
+
+    fn from_items(id: &str, items: &[DocSumItem]) -> Self {
+        Self {
+            uid: id.trim().parse().unwrap_or_default(),
+            id_ref: DocSumItem::value(items, "ID_REF"),
+            dataset: accessions(items, "GDS", "GDS").into_iter().next(),
+            title: DocSumItem::value(items, "title"),
+            gene_name: DocSumItem::value(items, "geneName"),
+            gene_desc: DocSumItem::value(items, "geneDesc"),
+            gene_id: DocSumItem::value(items, "geneID").and_then(|id| id.parse().ok()),
+            genbank_accession: DocSumItem::value(items, "GBACC"),
+            taxon: DocSumItem::value(items, "taxon"),
+        }
+    }
+}
+
+/// Fetch summaries of GEO records by UID
+pub fn fetch_geo_datasets<S: AsRef<str>>(ids: &[S]) -> Result<Vec<GeoDataset>, String> {
+    let ids: Vec<&str> = ids.iter().map(|id| id.as_ref()).collect();
+    let response = http_get(&build_summary_url(EntrezDb::GeoDatasets, &ids.join(",")))?;
+    GeoDataset::from_esummary(&response)
+}
+
+/// Search [`EntrezDb::GeoDatasets`] and summarize the first page of results
+///
+/// # Example
+/// ```ignore
+/// for dataset in search_geo_datasets("breast cancer AND gse[ETYP]")? {
+///     println!("{}: {} samples", dataset.accession, dataset.n_samples.unwrap_or_default());
+/// }
+/// ```
+pub fn search_geo_datasets(term: &str) -> Result<Vec<GeoDataset>, String> {
+    let result = fetch_search(EntrezDb::GeoDatasets, term)?;
+    if result.ids.is_empty() {
+        return Ok(Vec::new());
+    }
+    fetch_geo_datasets(&result.ids)
+}
+
+/// Fetch summaries of GEO profiles by UID
+pub fn fetch_geo_profiles<S: AsRef<str>>(ids: &[S]) -> Result<Vec<GeoProfile>, String> {
+    let ids: Vec<&str> = ids.iter().map(|id| id.as_ref()).collect();
+    let response = http_get(&build_summary_url(EntrezDb::GeoProfiles, &ids.join(",")))?;
+    GeoProfile::from_esummary(&response)
+}
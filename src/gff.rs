@@ -0,0 +1,333 @@
+//! Export of feature tables as [GFF3](https://github.com/The-Sequence-Ontology/Specifications/blob/master/gff3.md)
+//!
+//! Each [`SeqFeat`] is written as one line per interval of its location. All lines
+//! of a feature share the same `ID`, and features with a resolvable parent gene
+//! (see [`SeqAnnot::parent_gene()`]) reference it via `Parent`.
+
+use crate::coords::Range0;
+use crate::general::{DbTag, ObjectId};
+use crate::seq::{BioSeq, SeqAnnot};
+use crate::seqfeat::{CdRegionFrame, RnaRefType, SeqFeat, SeqFeatData};
+use crate::seqloc::{NaStrand, SeqId, SeqLoc};
+use crate::seqset::BioSeqSet;
+use std::fmt::Write;
+
+/// Render all features of `bioseq` as a GFF3 document
+pub fn bioseq_to_gff3(bioseq: &BioSeq) -> String {
+    let mut gff = String::from("##gff-version 3\n");
+    write_bioseq(&mut gff, bioseq);
+    gff
+}
+
+/// Render all features of every [`BioSeq`] in `set` as a single GFF3 document
+pub fn bioseq_set_to_gff3(set: &BioSeqSet) -> String {
+    let mut gff = String::from("##gff-version 3\n");
+    for bioseq in set.bioseqs() {
+        write_bioseq(&mut gff, bioseq);
+    }
+    gff
+}
+
+/// Label used to identify a [`SeqId`] in the `seqid` column
+///
+/// Accessions include the version when available.
+pub fn seq_id_label(id: &SeqId) -> String {
+    match id {
+        SeqId::Genbank(text)
+        | SeqId::Embl(text)
+        | SeqId::Pir(text)
+        | SeqId::Swissprot(text)
+        | SeqId::Other(text)
+        | SeqId::Ddbj(text)
+        | SeqId::Prf(text)
+        | SeqId::Tpg(text)
+        | SeqId::Tpe(text)
+        | SeqId::Tpd(text)
+        | SeqId::Gpipe(text)
+        | SeqId::NamedAnnotTrack(text) => {
+            let accession = text.accession.as_ref().or(text.name.as_ref());
+            match (accession, text.version) {
+                (Some(accession), Some(version)) => format!("{}.{}", accession, version),
+                (Some(accession), None) => accession.clone(),
+                _ => "unknown".to_string(),
+            }
+        }
+        SeqId::Gi(gi) => format!("gi|{}", gi),
+        SeqId::Local(id) => object_id_label(id),
+        SeqId::General(tag) => db_tag_label(tag),
+        _ => "unknown".to_string(),
+    }
+}
+
+fn object_id_label(id: &ObjectId) -> String {
+    match id {
+        ObjectId::Id(id) => id.to_string(),
+        ObjectId::Str(id) => id.to_string(),
+    }
+}
+
+pub(crate) fn db_tag_label(tag: &DbTag) -> String {
+    format!("{}:{}", tag.db, object_id_label(&tag.tag))
+}
+
+/// Prefer an accession over other identifiers
+pub(crate) fn best_id(bioseq: &BioSeq) -> String {
+    let accession = bioseq.id.iter().find(|id| {
+        !matches!(id, SeqId::Gi(_) | SeqId::Local(_) | SeqId::General(_))
+    });
+    accession
+        .or(bioseq.id.first())
+        .map(seq_id_label)
+        .unwrap_or("unknown".to_string())
+}
+
+pub(crate) fn write_bioseq(gff: &mut String, bioseq: &BioSeq) {
+    let seqid = escape(&best_id(bioseq));
+    let length = bioseq.inst.as_ref().and_then(|inst| inst.length);
+    if let Some(length) = length {
+        writeln!(gff, "##sequence-region {} 1 {}", seqid, length).unwrap();
+    }
+
+    let mut offset = 0;
+    for annot in bioseq.annot.iter().flatten() {
+        let feats = annot.features();
+        for (i, feat) in feats.iter().enumerate() {
+            let parent = parent_index(annot, feat).map(|p| offset + p);
+            write_feature(gff, &seqid, feat, offset + i + 1, parent, length);
+        }
+        offset += feats.len();
+    }
+}
+
+/// Index of the parent gene of `feat` within `annot`, counting from 1
+fn parent_index(annot: &SeqAnnot, feat: &SeqFeat) -> Option<usize> {
+    if matches!(feat.data, SeqFeatData::Gene(_)) {
+        return None;
+    }
+    let gene = annot.parent_gene(feat)?;
+    annot
+        .features()
+        .iter()
+        .position(|f| std::ptr::eq(f, gene))
+        .map(|i| i + 1)
+}
+
+fn feature_type(feat: &SeqFeat) -> String {
+    match &feat.data {
+        SeqFeatData::Gene(_) => "gene".to_string(),
+        SeqFeatData::CdRegion(_) => "CDS".to_string(),
+        SeqFeatData::RNA(rna) => match rna.r#type {
+            RnaRefType::PreMsg => "primary_transcript",
+            RnaRefType::mRNA => "mRNA",
+            RnaRefType::tRNA => "tRNA",
+            RnaRefType::rRNA => "rRNA",
+            RnaRefType::snRNA => "snRNA",
+            RnaRefType::scRNA => "scRNA",
+            RnaRefType::snoRNA => "snoRNA",
+            RnaRefType::ncRNA => "ncRNA",
+            RnaRefType::tmRNA => "tmRNA",
+            _ => "RNA",
+        }
+        .to_string(),
+        SeqFeatData::Prot(_) => "polypeptide".to_string(),
+        SeqFeatData::Imp(imp) => imp.key.clone(),
+        SeqFeatData::Region(_) => "region".to_string(),
+        SeqFeatData::BioSrc(_) => "region".to_string(),
+        _ => "sequence_feature".to_string(),
+    }
+}
+
+/// Flatten a location into ranges and strands in biological order
+///
+/// Intervals with invalid coordinates (eg: negative) are skipped.
+fn intervals(loc: &SeqLoc, length: Option<u64>) -> Vec<(Range0, Option<NaStrand>)> {
+    match loc {
+        SeqLoc::Whole(_) => length.and_then(Range0::whole).map(|range| (range, None)).into_iter().collect(),
+        SeqLoc::Int(interval) => interval.range0().map(|range| (range, interval.strand.clone())).into_iter().collect(),
+        SeqLoc::PackedInt(intervals) => intervals
+            .iter()
+            .filter_map(|i| Some((i.range0()?, i.strand.clone())))
+            .collect(),
+        SeqLoc::Pnt(point) => point
+            .pos0()
+            .and_then(|pos| Range0::new(pos, pos))
+            .map(|range| (range, point.strand.clone()))
+            .into_iter()
+            .collect(),
+        SeqLoc::Mix(mix) => mix.0.iter().flat_map(|loc| intervals(loc, length)).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn attributes(feat: &SeqFeat, index: usize, parent: Option<usize>) -> Vec<(&'static str, String)> {
+    let mut attributes = vec![("ID", format!("feature{}", index))];
+    if let Some(parent) = parent {
+        attributes.push(("Parent", format!("feature{}", parent)));
+    }
+
+    let gene = match &feat.data {
+        SeqFeatData::Gene(gene) => Some(gene),
+        _ => feat.gene_xref(),
+    };
+    if let Some(gene) = gene {
+        if let Some(locus) = &gene.locus {
+            attributes.push(("gene", locus.clone()));
+        }
+        if let Some(locus_tag) = &gene.locus_tag {
+            attributes.push(("locus_tag", locus_tag.clone()));
+        }
+    }
+    match &feat.data {
+        SeqFeatData::Region(name) => attributes.push(("Name", name.clone())),
+        SeqFeatData::Prot(prot) => {
+            if let Some(name) = prot.name.iter().flatten().next() {
+                attributes.push(("product", name.clone()));
+            }
+        }
+        _ => {
+            // protein names of coding regions are given by a `Prot-ref` xref
+            let prot = feat.xref.iter().flatten().find_map(|xref| match &xref.data {
+                Some(SeqFeatData::Prot(prot)) => Some(prot),
+                _ => None,
+            });
+            if let Some(name) = prot.and_then(|prot| prot.name.iter().flatten().next()) {
+                attributes.push(("product", name.clone()));
+            }
+        }
+    }
+    for qual in feat.qual.iter().flatten() {
+        attributes.push((
+            match qual.qual.as_str() {
+                "product" => "product",
+                "note" => "Note",
+                _ => continue,
+            },
+            qual.val.clone(),
+        ));
+    }
+    if let Some(comment) = &feat.comment {
+        attributes.push(("Note", comment.clone()));
+    }
+    if feat.pseudo == Some(true) {
+        attributes.push(("pseudo", "true".to_string()));
+    }
+
+    let dbxrefs: Vec<String> = feat
+        .dbxref
+        .iter()
+        .flatten()
+        .map(|tag| escape(&db_tag_label(tag)))
+        .collect();
+    if !dbxrefs.is_empty() {
+        // values are escaped individually so that commas remain separators
+        attributes.push(("Dbxref", dbxrefs.join(",")));
+    }
+    attributes
+}
+
+fn write_feature(
+    gff: &mut String,
+    seqid: &str,
+    feat: &SeqFeat,
+    index: usize,
+    parent: Option<usize>,
+    length: Option<u64>,
+) {
+    let r#type = escape(&feature_type(feat));
+    let attributes = attributes(feat, index, parent)
+        .into_iter()
+        .map(|(key, value)| {
+            if key == "Dbxref" {
+                format!("{}={}", key, value)
+            } else {
+                format!("{}={}", key, escape(&value))
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(";");
+
+    let mut phase = match &feat.data {
+        SeqFeatData::CdRegion(cds) => Some(match cds.frame {
+            CdRegionFrame::Two => 1,
+            CdRegionFrame::Three => 2,
+            _ => 0,
+        }),
+        _ => None,
+    };
+
+    for (range, strand) in intervals(&feat.location, length) {
+        let strand = match strand {
+            Some(NaStrand::Plus) => "+",
+            Some(NaStrand::Minus) => "-",
+            _ => ".",
+        };
+        let column = phase.map(|p| p.to_string()).unwrap_or(".".to_string());
+        writeln!(
+            gff,
+            "{}\t.\t{}\t{}\t{}\t.\t{}\t{}\t{}",
+            seqid,
+            r#type,
+            range.start1(),
+            range.end1(),
+            strand,
+            column,
+            attributes
+        )
+        .unwrap();
+
+        // bases left over from the incomplete codon carry into the next interval
+        phase = phase.map(|p| (3 - (range.len() as i64 - p).rem_euclid(3)) % 3);
+    }
+}
+
+/// Percent-encode characters reserved by GFF3
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            ';' | '=' | '&' | ',' | '%' | '\t' | '\n' | '\r' => {
+                write!(escaped, "%{:02X}", c as u32).unwrap()
+            }
+            c if c.is_control() => write!(escaped, "%{:02X}", c as u32).unwrap(),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::gff::{bioseq_set_to_gff3, escape};
+    use crate::{get_local_xml, parse_xml, DataType};
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("a;b=c,d"), "a%3Bb%3Dc%2Cd");
+        assert_eq!(escape("plain text"), "plain text");
+    }
+
+    #[test]
+    fn test_bioseq_set_to_gff3() {
+        let data = get_local_xml("tests/data/2519734237.xml");
+        let set = match parse_xml(data.as_str()).unwrap() {
+            DataType::BioSeqSet(set) => set,
+            _ => panic!("No Bioseq set found"),
+        };
+
+        let gff = bioseq_set_to_gff3(&set);
+        let mut lines = gff.lines();
+        assert_eq!(lines.next(), Some("##gff-version 3"));
+
+        let features: Vec<Vec<&str>> = gff
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .map(|line| line.split('\t').collect())
+            .collect();
+        assert!(features.iter().all(|cols| cols.len() == 9));
+        assert!(features.iter().all(|cols| cols[0] == "NZ_JARQWN010000024.1"));
+
+        let cds: Vec<&Vec<&str>> = features.iter().filter(|cols| cols[2] == "CDS").collect();
+        assert_eq!(cds.len(), 88);
+        assert!(cds.iter().all(|cols| cols[8].contains("Parent=feature")));
+    }
+}
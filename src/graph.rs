@@ -0,0 +1,483 @@
+//! Relationship graphs of records, for visualization
+//!
+//! [`RecordGraph`] collects genes, transcripts, proteins, publications and taxa as
+//! nodes, linked by typed edges, from ELink results ([`LinkSet`]), sequence records
+//! ([`Record`]) and [`Entrezgene`] records. The graph is written as DOT, for Graphviz,
+//! or GraphML, for Cytoscape or Gephi.
+//!
+//! Nodes are identified by the Entrez database and the id of the object within it
+//! (eg: "gene:7157", "pubmed:20368345"), so that the same object found in several
+//! sources is a single node. Sequences of parsed records are identified by accession,
+//! while ELink returns UIDs, so sequences only found through ELink are not merged
+//! with those of records.
+//!
+//! # Example
+//! ```ignore
+//! let mut graph = RecordGraph::new();
+//! graph.add_entrezgene(&gene);
+//! graph.add_link_sets(&LinkSet::from_xml(&response)?);
+//! std::fs::write("tp73.dot", graph.to_dot())?;
+//! ```
+
+use crate::entrezgene::{Entrezgene, GeneCommentary, GeneCommentaryType};
+use crate::general::ObjectId;
+use crate::record::Record;
+use crate::seq::{BioMol, Descriptors};
+use crate::LinkSet;
+use quick_xml::escape::escape;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+/// Kind of object represented by a [`GraphNode`]
+pub enum NodeKind {
+    Gene,
+    /// RNA sequence (eg: a RefSeq mRNA)
+    Transcript,
+    /// nucleotide sequence other than a transcript (eg: a chromosome)
+    Sequence,
+    Protein,
+    Publication,
+    Taxon,
+    /// object of another database, named by the database
+    Other(String),
+}
+
+impl NodeKind {
+    /// Kind of the records of the Entrez database `db`
+    pub fn from_db(db: &str) -> Self {
+        match db {
+            "gene" => Self::Gene,
+            "nuccore" | "nucleotide" => Self::Sequence,
+            "protein" => Self::Protein,
+            "pubmed" | "pmc" => Self::Publication,
+            "taxonomy" => Self::Taxon,
+            db => Self::Other(db.to_string()),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Gene => "gene",
+            Self::Transcript => "transcript",
+            Self::Sequence => "sequence",
+            Self::Protein => "protein",
+            Self::Publication => "publication",
+            Self::Taxon => "taxon",
+            Self::Other(db) => db,
+        }
+    }
+
+    /// Graphviz shape of nodes of this kind
+    fn shape(&self) -> &'static str {
+        match self {
+            Self::Gene => "box",
+            Self::Transcript | Self::Sequence => "ellipse",
+            Self::Protein => "hexagon",
+            Self::Publication => "note",
+            Self::Taxon => "diamond",
+            Self::Other(_) => "plaintext",
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+/// Relationship represented by a [`GraphEdge`]
+pub enum EdgeKind {
+    /// from a gene to a transcript it is transcribed into
+    Transcript,
+    /// from a sequence to the protein it encodes
+    Product,
+    /// from a record to the publication it cites
+    Cites,
+    /// from a record to its source organism
+    Organism,
+    /// from a record to an object of the named database it refers to (eg: "GeneID")
+    Xref(String),
+    /// from a record to another found by ELink, named by the link (eg: "gene_protein")
+    Link(String),
+}
+
+impl fmt::Display for EdgeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Transcript => f.write_str("transcript"),
+            Self::Product => f.write_str("product"),
+            Self::Cites => f.write_str("cites"),
+            Self::Organism => f.write_str("organism"),
+            Self::Xref(db) => write!(f, "xref:{}", db),
+            Self::Link(name) => f.write_str(name),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct GraphNode {
+    /// database and id of the object (eg: "gene:7157")
+    pub id: String,
+    pub kind: NodeKind,
+    /// name to display instead of the id (eg: gene symbol)
+    pub label: Option<String>,
+}
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct GraphEdge {
+    /// id of the source node
+    pub source: String,
+    /// id of the target node
+    pub target: String,
+    pub kind: EdgeKind,
+}
+
+#[derive(Clone, PartialEq, Debug, Default)]
+/// Nodes and typed edges between records, without duplicates
+pub struct RecordGraph {
+    nodes: Vec<GraphNode>,
+    edges: Vec<GraphEdge>,
+    index: HashMap<String, usize>,
+    seen: HashSet<GraphEdge>,
+}
+
+impl RecordGraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Nodes in the order they were added
+    pub fn nodes(&self) -> &[GraphNode] {
+        &self.nodes
+    }
+
+    /// Edges in the order they were added
+    pub fn edges(&self) -> &[GraphEdge] {
+        &self.edges
+    }
+
+    pub fn node(&self, id: &str) -> Option<&GraphNode> {
+        self.index.get(id).map(|index| &self.nodes[*index])
+    }
+
+    /// Edges leaving the node `id`
+    pub fn edges_from<'a>(&'a self, id: &'a str) -> impl Iterator<Item = &'a GraphEdge> {
+        self.edges.iter().filter(move |edge| edge.source == id)
+    }
+
+    /// Add the node `id`, returning its id
+    ///
+    /// A node added again keeps its kind, unless it was only known as a
+    /// [`NodeKind::Sequence`] and is now found to be more specific, and gains a label
+    /// if it had none.
+    pub fn add_node(&mut self, id: &str, kind: NodeKind, label: Option<&str>) -> String {
+        match self.index.get(id) {
+            Some(index) => {
+                let node = &mut self.nodes[*index];
+                if node.kind == NodeKind::Sequence {
+                    node.kind = kind;
+                }
+                if node.label.is_none() {
+                    node.label = label.map(str::to_string);
+                }
+            }
+            None => {
+                self.index.insert(id.to_string(), self.nodes.len());
+                self.nodes.push(GraphNode {
+                    id: id.to_string(),
+                    kind,
+                    label: label.map(str::to_string),
+                });
+            }
+        }
+        id.to_string()
+    }
+
+    /// Add an edge between nodes which have been added, unless it is already present
+    pub fn add_edge(&mut self, source: &str, target: &str, kind: EdgeKind) {
+        let edge = GraphEdge {
+            source: source.to_string(),
+            target: target.to_string(),
+            kind,
+        };
+        if source != target && self.seen.insert(edge.clone()) {
+            self.edges.push(edge);
+        }
+    }
+
+    /// Node for the object of a database cross-reference (eg: "GeneID:7157")
+    fn add_xref_node(&mut self, db: &str, tag: &str) -> String {
+        match db {
+            "GeneID" => self.add_node(&format!("gene:{}", tag), NodeKind::Gene, None),
+            "taxon" => self.add_node(&format!("taxonomy:{}", tag), NodeKind::Taxon, None),
+            "PMID" | "PubMed" => self.add_node(&format!("pubmed:{}", tag), NodeKind::Publication, None),
+            db => self.add_node(&format!("{}:{}", db, tag), NodeKind::Other(db.to_string()), None),
+        }
+    }
+
+    fn add_publication(&mut self, source: &str, pmid: u64) {
+        let label = format!("PMID {}", pmid);
+        let target = self.add_node(&format!("pubmed:{}", pmid), NodeKind::Publication, Some(&label));
+        self.add_edge(source, &target, EdgeKind::Cites);
+    }
+
+    fn add_organism(&mut self, source: &str, taxid: u64, name: Option<&str>) {
+        let target = self.add_node(&format!("taxonomy:{}", taxid), NodeKind::Taxon, name);
+        self.add_edge(source, &target, EdgeKind::Organism);
+    }
+
+    /// Add the links found by ELink, with edges named by their link name
+    pub fn add_link_sets(&mut self, sets: &[LinkSet]) {
+        for set in sets {
+            for id in set.ids.iter() {
+                let source = self.add_node(&format!("{}:{}", set.db_from, id), NodeKind::from_db(&set.db_from), None);
+                for db in set.link_set_dbs.iter() {
+                    for link in db.links.iter() {
+                        let target = self.add_node(&format!("{}:{}", db.db_to, link.id), NodeKind::from_db(&db.db_to), None);
+                        self.add_edge(&source, &target, EdgeKind::Link(db.link_name.clone()));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Add sequence records, their organism, references, coding region products and
+    /// the objects referred to by `db_xref` qualifiers of their features
+    ///
+    /// Records are transcripts when their [`MolInfo`](crate::seq::MolInfo) gives an RNA
+    /// molecule, and genes refer to the transcripts they annotate by a
+    /// [`EdgeKind::Transcript`] edge rather than a cross-reference.
+    pub fn add_records(&mut self, records: &[Record]) {
+        for record in records {
+            let bioseq = record.bioseq();
+            let protein = bioseq.is_some_and(|bioseq| bioseq.is_protein());
+            let biomol = bioseq.and_then(|bioseq| bioseq.molinfo()).map(|molinfo| molinfo.bio_mol.clone());
+            let kind = match biomol {
+                _ if protein => NodeKind::Protein,
+                Some(BioMol::mRNA | BioMol::PreRNA | BioMol::rRNA | BioMol::tRNA | BioMol::ncRNA | BioMol::TranscribedRNA) => {
+                    NodeKind::Transcript
+                }
+                _ => NodeKind::Sequence,
+            };
+            let db = if protein { "protein" } else { "nuccore" };
+            let source = self.add_node(&format!("{}:{}", db, record.id), kind.clone(), None);
+
+            if let Some(taxonomy) = &record.taxonomy {
+                if let Some(taxid) = taxonomy.taxid {
+                    self.add_organism(&source, taxid, taxonomy.organism.as_deref());
+                }
+            }
+            for pmid in record.references.iter().filter_map(|reference| reference.pmid) {
+                self.add_publication(&source, pmid);
+            }
+
+            for feature in record.features.iter().filter(|feature| feature.key != "source") {
+                if feature.key == "CDS" {
+                    if let Some(protein_id) = feature.qualifier("protein_id") {
+                        let label = feature.qualifier("product");
+                        let target = self.add_node(&format!("protein:{}", protein_id), NodeKind::Protein, label);
+                        self.add_edge(&source, &target, EdgeKind::Product);
+                    }
+                }
+                let xrefs = feature.qualifiers.iter().filter(|(name, _)| name == "db_xref");
+                for (db, tag) in xrefs.filter_map(|(_, value)| value.split_once(':')) {
+                    let target = self.add_xref_node(db, tag);
+                    if db == "GeneID" {
+                        if let Some(symbol) = feature.qualifier("gene") {
+                            self.add_node(&target, NodeKind::Gene, Some(symbol));
+                        }
+                        if kind == NodeKind::Transcript {
+                            self.add_edge(&target, &source, EdgeKind::Transcript);
+                            continue;
+                        }
+                    }
+                    self.add_edge(&source, &target, EdgeKind::Xref(db.to_string()));
+                }
+            }
+        }
+    }
+
+    /// Add a gene, the transcripts and proteins of its primary locus, its organism,
+    /// the publications of its GeneRIFs and its cross-references
+    pub fn add_entrezgene(&mut self, gene: &Entrezgene) {
+        let Some(track) = &gene.track_info else {
+            return;
+        };
+        let label = gene.gene.locus.as_deref();
+        let source = self.add_node(&format!("gene:{}", track.geneid), NodeKind::Gene, label);
+
+        if let Some(taxid) = gene.source.taxid() {
+            self.add_organism(&source, taxid, gene.source.org.taxname.as_deref());
+        }
+        for tag in gene.gene.db.iter().flatten().filter(|tag| tag.db != "GeneID") {
+            let id = match &tag.tag {
+                ObjectId::Id(id) => id.to_string(),
+                ObjectId::Str(id) => id.to_string(),
+            };
+            let target = self.add_xref_node(&tag.db, &id);
+            self.add_edge(&source, &target, EdgeKind::Xref(tag.db.to_string()));
+        }
+
+        let products = gene.genomic_locus().and_then(|locus| locus.products.as_ref());
+        for product in products.into_iter().flatten() {
+            self.add_gene_product(&source, product);
+        }
+
+        for pmid in gene.generifs().iter().flat_map(|rif| rif.pmids.iter()) {
+            self.add_publication(&source, *pmid);
+        }
+    }
+
+    /// Add a transcript of a gene, and the proteins it encodes
+    fn add_gene_product(&mut self, gene: &str, product: &GeneCommentary) {
+        let Some(accession) = product.versioned_accession() else {
+            return;
+        };
+        let label = product.label.as_deref();
+        if product.r#type == GeneCommentaryType::Peptide {
+            let target = self.add_node(&format!("protein:{}", accession), NodeKind::Protein, label);
+            self.add_edge(gene, &target, EdgeKind::Product);
+            return;
+        }
+        let transcript = self.add_node(&format!("nuccore:{}", accession), NodeKind::Transcript, label);
+        self.add_edge(gene, &transcript, EdgeKind::Transcript);
+        for protein in product.products.iter().flatten() {
+            if let Some(accession) = protein.versioned_accession() {
+                let target = self.add_node(&format!("protein:{}", accession), NodeKind::Protein, protein.label.as_deref());
+                self.add_edge(&transcript, &target, EdgeKind::Product);
+            }
+        }
+    }
+
+    /// Graph in the DOT language of Graphviz
+    ///
+    /// Nodes are labelled by their label, or their id, and shaped by their kind.
+    pub fn to_dot(&self) -> String {
+        fn quote(text: &str) -> String {
+            format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+        }
+
+        let mut dot = String::from("digraph records {\n");
+        for node in self.nodes.iter() {
+            dot.push_str(&format!(
+                "  {} [label={}, shape={}, kind={}];\n",
+                quote(&node.id),
+                quote(node.label.as_deref().unwrap_or(&node.id)),
+                node.kind.shape(),
+                quote(node.kind.as_str())
+            ));
+        }
+        for edge in self.edges.iter() {
+            dot.push_str(&format!(
+                "  {} -> {} [label={}];\n",
+                quote(&edge.source),
+                quote(&edge.target),
+                quote(&edge.kind.to_string())
+            ));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Graph as a GraphML document
+    ///
+    /// Nodes have `kind` and `label` attributes, and edges a `type` attribute.
+    pub fn to_graphml(&self) -> String {
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str("<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n");
+        xml.push_str("  <key id=\"kind\" for=\"node\" attr.name=\"kind\" attr.type=\"string\"/>\n");
+        xml.push_str("  <key id=\"label\" for=\"node\" attr.name=\"label\" attr.type=\"string\"/>\n");
+        xml.push_str("  <key id=\"type\" for=\"edge\" attr.name=\"type\" attr.type=\"string\"/>\n");
+        xml.push_str("  <graph id=\"records\" edgedefault=\"directed\">\n");
+        for node in self.nodes.iter() {
+            xml.push_str(&format!(
+                "    <node id=\"{}\"><data key=\"kind\">{}</data>",
+                escape(&node.id),
+                escape(node.kind.as_str())
+            ));
+            if let Some(label) = &node.label {
+                xml.push_str(&format!("<data key=\"label\">{}</data>", escape(label)));
+            }
+            xml.push_str("</node>\n");
+        }
+        for edge in self.edges.iter() {
+            xml.push_str(&format!(
+                "    <edge source=\"{}\" target=\"{}\"><data key=\"type\">{}</data></edge>\n",
+                escape(&edge.source),
+                escape(&edge.target),
+                escape(&edge.kind.to_string())
+            ));
+        }
+        xml.push_str("  </graph>\n</graphml>\n");
+        xml
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::{EdgeKind, NodeKind, RecordGraph};
+    use crate::record::Record;
+    use crate::{get_local_xml, parse_xml, DataType, Link, LinkSet, LinkSetDb};
+
+    #[test]
+    fn test_gene_graph() {
+        let genes = match parse_xml(&get_local_xml("tests/data/tp73.genbank.xml")).unwrap() {
+            DataType::EntrezgeneSet(genes) => genes,
+            _ => panic!("No Entrezgene set found"),
+        };
+        let mut graph = RecordGraph::new();
+        graph.add_entrezgene(&genes[0]);
+
+        let gene = graph.node("gene:7161").unwrap();
+        assert_eq!((&gene.kind, gene.label.as_deref()), (&NodeKind::Gene, Some("TP73")));
+        assert_eq!(graph.node("taxonomy:9606").unwrap().label.as_deref(), Some("Homo sapiens"));
+        assert_eq!(graph.node("nuccore:NM_005427.4").unwrap().kind, NodeKind::Transcript);
+        let kinds: Vec<_> = graph.edges_from("nuccore:NM_005427.4").map(|edge| (&edge.target, &edge.kind)).collect();
+        assert_eq!(kinds, [(&"protein:NP_005418.1".to_string(), &EdgeKind::Product)]);
+        assert!(graph.edges_from("gene:7161").any(|edge| edge.kind == EdgeKind::Cites));
+        assert!(graph.edges_from("gene:7161").any(|edge| edge.kind == EdgeKind::Xref("MIM".into())));
+
+        // adding the same gene again does not duplicate nodes nor edges
+        let (nodes, edges) = (graph.nodes().len(), graph.edges().len());
+        graph.add_entrezgene(&genes[0]);
+        assert_eq!((graph.nodes().len(), graph.edges().len()), (nodes, edges));
+
+        let links = LinkSet {
+            db_from: "gene".into(),
+            ids: vec!["7161".into()],
+            link_set_dbs: vec![LinkSetDb {
+                db_to: "pubmed".into(),
+                link_name: "gene_pubmed".into(),
+                links: vec![Link { id: "11804596".into(), score: None }, Link { id: "1".into(), score: None }],
+            }],
+        };
+        graph.add_link_sets(&[links]);
+        assert_eq!(graph.nodes().len(), nodes + 1);
+        assert_eq!(graph.node("pubmed:1").unwrap().kind, NodeKind::Publication);
+        assert_eq!(graph.edges().len(), edges + 2);
+
+        let dot = graph.to_dot();
+        assert!(dot.starts_with("digraph records {\n"));
+        assert!(dot.contains("  \"gene:7161\" [label=\"TP73\", shape=box, kind=\"gene\"];\n"));
+        assert!(dot.contains("  \"gene:7161\" -> \"pubmed:1\" [label=\"gene_pubmed\"];\n"));
+
+        let graphml = graph.to_graphml();
+        assert!(graphml.contains("<node id=\"gene:7161\"><data key=\"kind\">gene</data><data key=\"label\">TP73</data></node>"));
+        assert!(graphml.contains("<edge source=\"gene:7161\" target=\"pubmed:1\"><data key=\"type\">gene_pubmed</data></edge>"));
+        assert_eq!(graphml.matches("<node ").count(), graph.nodes().len());
+    }
+
+    #[test]
+    fn test_record_graph() {
+        let set = match parse_xml(&get_local_xml("tests/data/2519734237.xml")).unwrap() {
+            DataType::BioSeqSet(set) => set,
+            _ => panic!("No Bioseq-set found"),
+        };
+        let mut graph = RecordGraph::new();
+        graph.add_records(&Record::from_set(&set));
+
+        let source = "nuccore:NZ_JARQWN010000024.1";
+        assert_eq!(graph.node(source).unwrap().kind, NodeKind::Sequence);
+        assert!(graph.edges_from(source).any(|edge| edge.target == "taxonomy:573" && edge.kind == EdgeKind::Organism));
+        let products: Vec<_> = graph.edges_from(source).filter(|edge| edge.kind == EdgeKind::Product).collect();
+        assert!(!products.is_empty());
+        assert!(products.iter().all(|edge| graph.node(&edge.target).unwrap().kind == NodeKind::Protein));
+    }
+}
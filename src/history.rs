@@ -0,0 +1,316 @@
+//! Paging through results stored on the Entrez history server
+//!
+//! Large result sets are retrieved by storing them once on the history server, with
+//! ESearch (`usehistory=y`) or EPost, then fetching them by pages using the returned
+//! `WebEnv` and `query_key`. [`HistorySession`] keeps track of both.
+//!
+//! Sessions expire after some hours without use, after which requests are answered
+//! with an error such as "Unable to obtain query #1". Depending on its
+//! [`RefreshPolicy`], a session then stores the results again, by running the search
+//! again or posting the UIDs again, and resumes at the page which failed. The results
+//! of a search run again may have changed in between, so [`HistorySession::count`] is
+//! updated and pages may overlap or skip records which were added or removed.
+//!
+//! # Example
+//! ```ignore
+//! let mut session = HistorySession::search(EntrezDb::Nucleotide, "Myotis[Organism]");
+//! let request = FetchRequest::new(EntrezDb::Nucleotide, "").rettype("fasta").retmode("text");
+//! for page in session.pages(&request, 500) {
+//!     let fasta = page?;
+//!     // ...
+//! }
+//! ```
+
+use crate::edirect::EDirectMessage;
+use crate::parsing::parse_node;
+use crate::seqset::{BioSeqSet, SeqEntry};
+use crate::sink::RecordSink;
+use crate::{build_post_url, build_search_url, http_get, EntrezDb, EntrezError, FetchRequest, SearchResult};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+
+/// Largest number of UIDs stored by [`HistorySession::post`]
+///
+/// EPost is sent as a GET request, with all UIDs in its URL, and longer URLs are
+/// rejected by the server. Larger lists are to be split across sessions.
+pub const MAX_POST_IDS: usize = 200;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+/// What a [`HistorySession`] does once its results have expired
+pub enum RefreshPolicy {
+    /// store the results again and retry the request, once
+    ///
+    /// Paging resumes at the same offset of the new results. A search which is run
+    /// again may return records which were added or removed in between, so that pages
+    /// before and after the refresh may repeat or skip records. Use [`Self::Fail`]
+    /// where every record must be seen exactly once.
+    #[default]
+    Refresh,
+    /// return the error, and only store the results again on the next request
+    Fail,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+/// How the results of a [`HistorySession`] are stored on the history server
+pub enum HistorySource {
+    /// ESearch query, run again to refresh the results
+    Search(String),
+    /// UIDs sent with EPost, sent again to refresh the results
+    Ids(Vec<String>),
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+/// Results stored on the history server, which are stored again once expired
+pub struct HistorySession {
+    pub db: EntrezDb,
+    pub source: HistorySource,
+    pub policy: RefreshPolicy,
+    /// session storing the results, once stored
+    pub web_env: Option<String>,
+    /// key of the results within [`Self::web_env`]
+    pub query_key: Option<u64>,
+    /// number of stored records
+    pub count: u64,
+    /// number of times the results were stored again after expiring
+    pub refreshes: u32,
+}
+
+impl HistorySession {
+    fn new(db: EntrezDb, source: HistorySource) -> Self {
+        Self {
+            db,
+            source,
+            policy: RefreshPolicy::default(),
+            web_env: None,
+            query_key: None,
+            count: 0,
+            refreshes: 0,
+        }
+    }
+
+    /// Session storing the results of searching `db` for `term`
+    pub fn search(db: EntrezDb, term: &str) -> Self {
+        Self::new(db, HistorySource::Search(term.to_string()))
+    }
+
+    /// Session storing `ids`
+    ///
+    /// At most [`MAX_POST_IDS`] UIDs can be stored, beyond which [`Self::store`] fails.
+    pub fn post<S: AsRef<str>>(db: EntrezDb, ids: &[S]) -> Self {
+        let ids: Vec<String> = ids.iter().map(|id| id.as_ref().to_string()).collect();
+        Self {
+            count: ids.len() as u64,
+            ..Self::new(db, HistorySource::Ids(ids))
+        }
+    }
+
+    pub fn policy(mut self, policy: RefreshPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Whether the results have been stored, and have not been found to be expired
+    pub fn is_stored(&self) -> bool {
+        self.web_env.is_some() && self.query_key.is_some()
+    }
+
+    /// URL of the ESearch or EPost request storing the results
+    pub fn build_store_url(&self) -> String {
+        match &self.source {
+            HistorySource::Search(term) => format!("{}&usehistory=y&retmax=0", build_search_url(self.db, term)),
+            HistorySource::Ids(ids) => build_post_url(self.db, ids),
+        }
+    }
+
+    /// Store the results on the history server, replacing the previous session
+    pub fn store(&mut self) -> Result<(), String> {
+        if let HistorySource::Ids(ids) = &self.source {
+            if ids.len() > MAX_POST_IDS {
+                return Err(format!("Cannot post {} UIDs at once, the limit is {}", ids.len(), MAX_POST_IDS));
+            }
+        }
+        let url = self.build_store_url();
+        let response = http_get(&url)?;
+        if let Some(error) = EntrezError::from_xml(&response) {
+            return Err(error.with_url(&url).into());
+        }
+        let (web_env, query_key) = match &self.source {
+            HistorySource::Search(_) => {
+                let result = SearchResult::from_xml(&response)?;
+                self.count = result.count;
+                (result.web_env, result.query_key)
+            }
+            HistorySource::Ids(_) => read_post_result(&response)?,
+        };
+        match (web_env, query_key) {
+            (Some(web_env), Some(query_key)) => {
+                self.web_env = Some(web_env);
+                self.query_key = Some(query_key);
+                Ok(())
+            }
+            _ => Err(format!("No WebEnv and QueryKey returned by {}", url)),
+        }
+    }
+
+    /// Send `request` for records `retstart..retstart + retmax` of the results
+    ///
+    /// The results are stored first when needed. `request` gives the database, format,
+    /// timeout, etc. Its `id`, `retstart` and `retmax` are replaced.
+    pub fn fetch_page(&mut self, request: &FetchRequest, retstart: u64, retmax: u64) -> Result<String, String> {
+        if !self.is_stored() {
+            self.store()?;
+        }
+        let mut refreshed = false;
+        loop {
+            let web_env = self.web_env.as_deref().unwrap_or_default();
+            let page = FetchRequest {
+                id: String::new(),
+                ..request.clone()
+            }
+            .history(web_env, self.query_key.unwrap_or_default())
+            .retstart(retstart)
+            .retmax(retmax);
+            let response = page.fetch_text()?;
+            match EntrezError::from_xml(&response) {
+                Some(error) if error.is_history_expired() => {
+                    self.web_env = None;
+                    self.query_key = None;
+                    if self.policy == RefreshPolicy::Fail || refreshed {
+                        return Err(error.with_url(&page.build_url()).into());
+                    }
+                    log::debug!("History of {} expired, storing results again", self.db);
+                    self.store()?;
+                    self.refreshes += 1;
+                    refreshed = true;
+                }
+                Some(error) => return Err(error.with_url(&page.build_url()).into()),
+                None => return Ok(response),
+            }
+        }
+    }
+
+    /// Iterate over all results by pages of `page_size` records
+    ///
+    /// Iteration stops after the first error. Pages following a refresh of a search
+    /// may repeat or skip records (see [`RefreshPolicy::Refresh`]).
+    pub fn pages<'a>(&'a mut self, request: &'a FetchRequest, page_size: u64) -> HistoryPages<'a> {
+        HistoryPages {
+            session: self,
+            request,
+            page_size: page_size.max(1),
+            retstart: 0,
+            done: false,
+        }
+    }
+
+    /// Write all results to `sink`, retrieving them by pages of `page_size` records
+    ///
+    /// Records are requested as native XML, whatever the format of `request`, and each
+    /// top-level entry is parsed and written before the next, so that only a page is
+    /// held in memory. Only databases returning a `Bioseq-set` (eg: nuccore, protein)
+    /// are supported. Returns the number of entries written.
+    pub fn download(&mut self, request: &FetchRequest, page_size: u64, sink: &mut dyn RecordSink) -> Result<u64, String> {
+        let request = request.clone().rettype("native").retmode("xml");
+        let mut written = 0;
+        for page in self.pages(&request, page_size) {
+            let page = page?;
+            for xml in BioSeqSet::entries_xml(&page)? {
+                let entry = parse_node::<SeqEntry>(xml).ok_or("Failed to parse Seq-entry")?;
+                sink.write_entry(&entry, Some(xml))?;
+                written += 1;
+            }
+        }
+        sink.finish()?;
+        Ok(written)
+    }
+
+    /// Message passing the stored results to EDirect commands
+    pub fn message(&self) -> EDirectMessage {
+        EDirectMessage {
+            web_env: self.web_env.clone(),
+            query_key: self.query_key,
+            count: Some(self.count),
+            step: Some(1),
+            ..EDirectMessage::new(self.db)
+        }
+    }
+}
+
+/// Pages of results returned by [`HistorySession::pages()`]
+pub struct HistoryPages<'a> {
+    session: &'a mut HistorySession,
+    request: &'a FetchRequest,
+    page_size: u64,
+    retstart: u64,
+    done: bool,
+}
+
+impl Iterator for HistoryPages<'_> {
+    type Item = Result<String, String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        if !self.session.is_stored() {
+            if let Err(e) = self.session.store() {
+                self.done = true;
+                return Some(Err(e));
+            }
+        }
+        if self.retstart >= self.session.count {
+            self.done = true;
+            return None;
+        }
+        match self.session.fetch_page(self.request, self.retstart, self.page_size) {
+            Ok(page) => {
+                self.retstart += self.page_size;
+                Some(Ok(page))
+            }
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+/// `WebEnv` and `QueryKey` of the raw XML returned by EPost
+fn read_post_result(response: &str) -> Result<(Option<String>, Option<u64>), String> {
+    let mut reader = Reader::from_str(response);
+    reader.trim_text(true);
+
+    let mut found = false;
+    let mut web_env = None;
+    let mut query_key = None;
+    let mut element: Vec<u8> = Vec::new();
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) => {
+                if e.name().as_ref() == b"ePostResult" {
+                    found = true;
+                }
+                element = e.name().as_ref().to_vec();
+            }
+            Ok(Event::Text(text)) => {
+                let text = text.unescape().map_err(|e| format!("{:?}", e))?;
+                match element.as_slice() {
+                    b"WebEnv" => web_env = Some(text.to_string()),
+                    b"QueryKey" => {
+                        query_key = Some(text.parse().map_err(|_| format!("Invalid query key: {}", text))?)
+                    }
+                    _ => (),
+                }
+            }
+            Ok(Event::End(_)) => element.clear(),
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(format!("XML parsing error: {:?}", e)),
+            _ => (),
+        }
+    }
+    if found {
+        Ok((web_env, query_key))
+    } else {
+        Err("No <ePostResult> found in response.".to_string())
+    }
+}
@@ -0,0 +1,277 @@
+//! Location index of features, for range queries over large annotations
+//!
+//! [`FeatureIndex`] sorts the features of a [`SeqAnnot`] or [`BioSeq`] by location and
+//! augments them as an interval tree, so that the features overlapping a range are
+//! found in `O(log n + k)` rather than by scanning every feature.
+//!
+//! Features are indexed by [`total_range()`](crate::seqloc::SeqLoc::total_range) of
+//! their location, and those without coordinates (eg: located on a whole sequence)
+//! are not indexed.
+//!
+//! Coordinates are compared regardless of [`SeqId`](crate::seqloc::SeqId), so an
+//! index should only be built from features located on the same sequence, as are
+//! those annotated on a single [`BioSeq`].
+//!
+//! # Example
+//! ```ignore
+//! let index = FeatureIndex::from_bioseq(&bioseq);
+//! for feat in index.features_overlapping(10_000..=12_500) {
+//!     println!("{}", tbl::feature_key(feat));
+//! }
+//! let gene = index.nearest_where(11_000, |feat| matches!(feat.data, SeqFeatData::Gene(_)));
+//! ```
+
+use crate::seq::{BioSeq, SeqAnnot};
+use crate::seqfeat::SeqFeat;
+use std::ops::{Bound, RangeBounds};
+
+#[derive(Clone, Copy, Debug)]
+struct Entry<'a> {
+    start: i64,
+    /// last position covered
+    end: i64,
+    feat: &'a SeqFeat,
+}
+
+#[derive(Clone, Debug, Default)]
+/// Interval tree of features, keyed by their location
+///
+/// Queries return features ordered by start position, then by end position.
+pub struct FeatureIndex<'a> {
+    /// entries sorted by start, forming an implicit balanced tree where the root of
+    /// any slice `lo..hi` is at its midpoint
+    entries: Vec<Entry<'a>>,
+    /// greatest end within the subtree rooted at each entry
+    max_end: Vec<i64>,
+    /// positions in `entries`, sorted by end
+    by_end: Vec<usize>,
+}
+
+impl<'a> FeatureIndex<'a> {
+    /// Index `features`, skipping those without coordinates
+    pub fn new<I: IntoIterator<Item = &'a SeqFeat>>(features: I) -> Self {
+        let mut entries: Vec<Entry> = features
+            .into_iter()
+            .filter_map(|feat| {
+                let (start, end) = feat.location.total_range()?;
+                Some(Entry { start, end, feat })
+            })
+            .collect();
+        entries.sort_by_key(|entry| (entry.start, entry.end));
+
+        let mut max_end = vec![0; entries.len()];
+        fill_max_end(&entries, &mut max_end, 0, entries.len());
+
+        let mut by_end: Vec<usize> = (0..entries.len()).collect();
+        by_end.sort_by_key(|i| (entries[*i].end, entries[*i].start));
+
+        Self {
+            entries,
+            max_end,
+            by_end,
+        }
+    }
+
+    /// Index the features of a single annotation
+    pub fn from_annot(annot: &'a SeqAnnot) -> Self {
+        Self::new(annot.features())
+    }
+
+    /// Index the features of every annotation of `bioseq`
+    pub fn from_bioseq(bioseq: &'a BioSeq) -> Self {
+        Self::new(bioseq.annot.iter().flatten().flat_map(|annot| annot.features()))
+    }
+
+    /// Number of indexed features
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// All indexed features, by location
+    pub fn iter(&self) -> impl Iterator<Item = &'a SeqFeat> + '_ {
+        self.entries.iter().map(|entry| entry.feat)
+    }
+
+    /// Features covering at least one position of `range`
+    pub fn features_overlapping<R: RangeBounds<i64>>(&self, range: R) -> Vec<&'a SeqFeat> {
+        let mut found = Vec::new();
+        if let Some((from, to)) = bounds(range) {
+            self.overlapping(0, self.entries.len(), from, to, &mut found);
+        }
+        found
+    }
+
+    /// Features covering `pos`
+    pub fn features_at(&self, pos: i64) -> Vec<&'a SeqFeat> {
+        self.features_overlapping(pos..=pos)
+    }
+
+    /// Features entirely contained by `range`
+    pub fn features_within<R: RangeBounds<i64>>(&self, range: R) -> Vec<&'a SeqFeat> {
+        let Some((from, to)) = bounds(range) else {
+            return Vec::new();
+        };
+        let first = self.entries.partition_point(|entry| entry.start < from);
+        self.entries[first..]
+            .iter()
+            .take_while(|entry| entry.start <= to)
+            .filter(|entry| entry.end <= to)
+            .map(|entry| entry.feat)
+            .collect()
+    }
+
+    /// Feature closest to `pos`
+    ///
+    /// See [`Self::nearest_where()`].
+    pub fn nearest(&self, pos: i64) -> Option<&'a SeqFeat> {
+        self.nearest_where(pos, |_| true)
+    }
+
+    /// Feature closest to `pos` among those accepted by `predicate`
+    ///
+    /// Features covering `pos` are at distance 0, and are returned first. Otherwise,
+    /// the distance is counted to the nearest end of a feature, and features before
+    /// `pos` are preferred over those after it when at the same distance.
+    pub fn nearest_where<P>(&self, pos: i64, predicate: P) -> Option<&'a SeqFeat>
+    where
+        P: Fn(&SeqFeat) -> bool,
+    {
+        if let Some(feat) = self.features_at(pos).into_iter().find(|feat| predicate(feat)) {
+            return Some(feat);
+        }
+
+        // last feature ending before `pos`
+        let before = self.by_end[..self.by_end.partition_point(|i| self.entries[*i].end < pos)]
+            .iter()
+            .rev()
+            .map(|i| &self.entries[*i])
+            .find(|entry| predicate(entry.feat));
+        // first feature starting after `pos`
+        let after = self.entries[self.entries.partition_point(|entry| entry.start <= pos)..]
+            .iter()
+            .find(|entry| predicate(entry.feat));
+
+        match (before, after) {
+            (Some(before), Some(after)) if after.start - pos < pos - before.end => Some(after.feat),
+            (Some(before), _) => Some(before.feat),
+            (None, after) => after.map(|entry| entry.feat),
+        }
+    }
+
+    /// Collect the features of the subtree `lo..hi` overlapping `from..=to`, in order
+    fn overlapping(&self, lo: usize, hi: usize, from: i64, to: i64, found: &mut Vec<&'a SeqFeat>) {
+        if lo >= hi {
+            return;
+        }
+        let mid = lo + (hi - lo) / 2;
+        if self.max_end[mid] < from {
+            return;
+        }
+        self.overlapping(lo, mid, from, to, found);
+
+        let entry = &self.entries[mid];
+        // entries of the right subtree start after this one
+        if entry.start > to {
+            return;
+        }
+        if entry.end >= from {
+            found.push(entry.feat);
+        }
+        self.overlapping(mid + 1, hi, from, to, found);
+    }
+}
+
+/// Compute the greatest end of each subtree of `lo..hi`, returning that of its root
+fn fill_max_end(entries: &[Entry], max_end: &mut [i64], lo: usize, hi: usize) -> i64 {
+    if lo >= hi {
+        return i64::MIN;
+    }
+    let mid = lo + (hi - lo) / 2;
+    let end = entries[mid]
+        .end
+        .max(fill_max_end(entries, max_end, lo, mid))
+        .max(fill_max_end(entries, max_end, mid + 1, hi));
+    max_end[mid] = end;
+    end
+}
+
+/// First and last position of `range`, if it is not empty
+fn bounds<R: RangeBounds<i64>>(range: R) -> Option<(i64, i64)> {
+    let from = match range.start_bound() {
+        Bound::Included(from) => *from,
+        Bound::Excluded(from) => from.checked_add(1)?,
+        Bound::Unbounded => i64::MIN,
+    };
+    let to = match range.end_bound() {
+        Bound::Included(to) => *to,
+        Bound::Excluded(to) => to.checked_sub(1)?,
+        Bound::Unbounded => i64::MAX,
+    };
+    (from <= to).then_some((from, to))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::index::{bounds, FeatureIndex};
+    use crate::seqfeat::{SeqFeat, SeqFeatData};
+    use crate::seqloc::{SeqInterval, SeqLoc};
+
+    fn feat(name: &str, from: i64, to: i64) -> SeqFeat {
+        let mut feat = SeqFeat::new(SeqFeatData::Region(name.to_string()));
+        feat.location = SeqLoc::Int(SeqInterval {
+            from,
+            to,
+            ..SeqInterval::default()
+        });
+        feat
+    }
+
+    fn names(features: Vec<&SeqFeat>) -> Vec<&str> {
+        features
+            .into_iter()
+            .map(|feat| match &feat.data {
+                SeqFeatData::Region(name) => name.as_str(),
+                _ => unreachable!(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_bounds() {
+        assert_eq!(bounds(10..20), Some((10, 19)));
+        assert_eq!(bounds(10..=20), Some((10, 20)));
+        assert_eq!(bounds(..5), Some((i64::MIN, 4)));
+        assert_eq!(bounds(5..5), None);
+    }
+
+    #[test]
+    fn test_queries() {
+        let features = vec![
+            feat("long", 0, 1000),
+            feat("c", 300, 400),
+            feat("a", 100, 200),
+            feat("b", 150, 250),
+            feat("d", 600, 700),
+        ];
+        let index = FeatureIndex::new(&features);
+        assert_eq!(index.len(), 5);
+
+        assert_eq!(names(index.features_overlapping(190..=310)), ["long", "a", "b", "c"]);
+        assert_eq!(names(index.features_overlapping(260..300)), ["long"]);
+        assert_eq!(names(index.features_at(700)), ["long", "d"]);
+        assert_eq!(names(index.features_within(100..=400)), ["a", "b", "c"]);
+        assert!(index.features_overlapping(2000..).is_empty());
+
+        let short = |feat: &SeqFeat| !matches!(&feat.data, SeqFeatData::Region(name) if name == "long");
+        assert_eq!(names(index.nearest(500).into_iter().collect()), ["long"]);
+        assert_eq!(names(index.nearest_where(500, short).into_iter().collect()), ["c"]);
+        assert_eq!(names(index.nearest_where(520, short).into_iter().collect()), ["d"]);
+        assert_eq!(names(index.nearest_where(270, short).into_iter().collect()), ["b"]);
+        assert_eq!(names(index.nearest_where(1500, short).into_iter().collect()), ["d"]);
+        assert!(FeatureIndex::default().nearest(0).is_none());
+    }
+}
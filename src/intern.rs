@@ -0,0 +1,217 @@
+//! Shared storage of small strings which repeat across records
+//!
+//! Fields such as [`crate::general::DbTag::db`], [`crate::seqfeat::GbQual::qual`] and
+//! [`crate::general::ObjectId::Str`] take only a handful of distinct values (eg: "taxon",
+//! "GeneID", "note"), yet are repeated millions of times in large records. These fields
+//! are stored as [`SharedStr`].
+//!
+//! By default [`SharedStr`] owns its text like a [`String`]. With the `intern` feature
+//! enabled, equal values are deduplicated through a per-thread table, and each
+//! [`SharedStr`] only holds a reference counted pointer to the shared text. Interned
+//! strings live until [`clear_interned()`] is called.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::borrow::Borrow;
+use std::fmt;
+use std::ops::Deref;
+
+#[cfg(feature = "intern")]
+use std::cell::RefCell;
+#[cfg(feature = "intern")]
+use std::collections::HashSet;
+#[cfg(feature = "intern")]
+use std::sync::Arc;
+
+#[cfg(feature = "intern")]
+type Repr = Arc<str>;
+#[cfg(not(feature = "intern"))]
+type Repr = String;
+
+#[cfg(feature = "intern")]
+thread_local! {
+    static INTERNED: RefCell<HashSet<Arc<str>>> = RefCell::new(HashSet::new());
+}
+
+#[cfg(feature = "intern")]
+fn intern(text: &str) -> Arc<str> {
+    INTERNED.with(|table| {
+        let mut table = table.borrow_mut();
+        match table.get(text) {
+            Some(shared) => shared.clone(),
+            None => {
+                let shared: Arc<str> = Arc::from(text);
+                table.insert(shared.clone());
+                shared
+            }
+        }
+    })
+}
+
+/// Number of distinct strings interned by the current thread
+///
+/// Always zero when the `intern` feature is disabled.
+pub fn interned_count() -> usize {
+    #[cfg(feature = "intern")]
+    return INTERNED.with(|table| table.borrow().len());
+    #[cfg(not(feature = "intern"))]
+    0
+}
+
+/// Release the strings interned by the current thread
+///
+/// Existing [`SharedStr`] values remain valid, but equal values created afterwards
+/// no longer share storage with them.
+pub fn clear_interned() {
+    #[cfg(feature = "intern")]
+    INTERNED.with(|table| table.borrow_mut().clear());
+}
+
+/// Immutable text of a frequently repeated field
+///
+/// Dereferences to [`str`] and compares equal to string slices and [`String`].
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SharedStr(Repr);
+
+impl SharedStr {
+    pub fn new(text: &str) -> Self {
+        #[cfg(feature = "intern")]
+        return Self(intern(text));
+        #[cfg(not(feature = "intern"))]
+        Self(text.to_string())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Default for SharedStr {
+    fn default() -> Self {
+        Self::new("")
+    }
+}
+
+impl Deref for SharedStr {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for SharedStr {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for SharedStr {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&str> for SharedStr {
+    fn from(text: &str) -> Self {
+        Self::new(text)
+    }
+}
+
+impl From<String> for SharedStr {
+    fn from(text: String) -> Self {
+        #[cfg(feature = "intern")]
+        return Self::new(&text);
+        #[cfg(not(feature = "intern"))]
+        Self(text)
+    }
+}
+
+impl From<&SharedStr> for String {
+    fn from(text: &SharedStr) -> Self {
+        text.as_str().to_string()
+    }
+}
+
+impl From<SharedStr> for String {
+    fn from(text: SharedStr) -> Self {
+        text.0.to_string()
+    }
+}
+
+impl PartialEq<str> for SharedStr {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for SharedStr {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl PartialEq<String> for SharedStr {
+    fn eq(&self, other: &String) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl fmt::Display for SharedStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self)
+    }
+}
+
+impl fmt::Debug for SharedStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl Serialize for SharedStr {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for SharedStr {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Self::from)
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for SharedStr {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        <&str>::arbitrary(u).map(Self::new)
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        <&str>::size_hint(depth)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::intern::SharedStr;
+
+    #[test]
+    fn test_shared_str() {
+        let text = SharedStr::from("taxon");
+        assert_eq!(text, "taxon");
+        assert_eq!(text, SharedStr::from("taxon".to_string()));
+        assert_eq!(text.len(), 5);
+        assert_eq!(format!("{:?}", text), "\"taxon\"");
+    }
+
+    #[cfg(feature = "intern")]
+    #[test]
+    fn test_interning() {
+        use crate::intern::interned_count;
+
+        let a = SharedStr::from("GeneID");
+        let b = SharedStr::from("GeneID".to_string());
+        assert!(std::ptr::eq(a.as_str(), b.as_str()));
+        assert!(interned_count() >= 1);
+    }
+}
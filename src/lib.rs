@@ -3,9 +3,49 @@ extern crate enum_primitive;
 extern crate num;
 extern crate quick_xml;
 
+#[cfg(feature = "arrow")]
+pub mod arrow;
 pub mod asn;
+pub mod blast;
+pub mod builder;
+pub mod canonical;
+pub mod clinvar;
+pub mod coords;
+pub mod delta;
+pub mod edirect;
 pub mod eutils;
+#[cfg(feature = "fixtures")]
+pub mod fixtures;
+pub mod flat;
+pub mod fuzz;
+pub mod genelink;
+pub mod geo;
+pub mod gff;
+pub mod graph;
+pub mod history;
+pub mod index;
+pub mod intern;
+pub mod mapper;
+#[cfg(feature = "json")]
+pub mod ndjson;
 pub mod parsing;
+pub mod pipeline;
+pub mod pmc;
+pub mod ratelimit;
+pub mod record;
+pub mod resolve;
+pub mod seqtools;
+pub mod sink;
+pub mod sra;
+pub mod stats;
+pub mod structure;
+pub mod summary;
+pub mod sync;
+pub mod tbl;
+pub mod transport;
+pub mod user;
+pub mod validate;
+pub mod visit;
 
 pub use asn::*;
 pub use eutils::*;
@@ -0,0 +1,564 @@
+//! Projection of locations between coordinate systems
+//!
+//! [`SeqLocMapper`] translates a [`SeqLoc`] on one sequence into the equivalent
+//! location on another, using either an alignment ([`SeqAlign`]), the parts of a
+//! segmented [`BioSeq`], or a coding region ([`SeqFeat`]). This mirrors
+//! `CSeq_loc_Mapper` from the NCBI C++ toolkit and can be used to project annotation
+//! from a RefSeq mRNA onto genomic coordinates, or protein domains onto the codons
+//! which encode them.
+//!
+//! Portions of a location which fall outside of the mapped ranges (eg: alignment
+//! gaps) are dropped.
+
+use crate::seq::{BioSeq, SeqExt};
+use crate::seqalign::{DenseSeg, SeqAlign, SeqAlignSegs};
+use crate::seqfeat::{SeqFeat, SeqFeatData};
+use crate::seqloc::{NaStrand, SeqId, SeqInterval, SeqLoc, SeqLocMix, SeqPoint};
+
+#[derive(Clone, PartialEq, Debug)]
+/// A single ungapped block shared by source and destination
+///
+/// Positions of protein sequences are counted in nucleotides (ie: 3 per residue) so
+/// that blocks may start or end within a codon.
+struct MappingRange {
+    src_id: SeqId,
+    src_from: i64,
+    dst_id: SeqId,
+    dst_from: i64,
+    length: i64,
+    /// `true` when source and destination are on opposite strands
+    reverse: bool,
+    /// nucleotides per position of the source sequence, 3 for a protein
+    src_width: i64,
+    /// nucleotides per position of the destination sequence, 3 for a protein
+    dst_width: i64,
+}
+
+impl MappingRange {
+    fn src_to(&self) -> i64 {
+        self.src_from + self.length - 1
+    }
+
+    /// Map inclusive source coordinates `from..=to`, clipped to this range
+    ///
+    /// A protein destination includes every residue whose codon is partly mapped.
+    fn map_range(&self, from: i64, to: i64) -> Option<(i64, i64)> {
+        let from = (from * self.src_width).max(self.src_from);
+        let to = (to * self.src_width + self.src_width - 1).min(self.src_to());
+        if from > to {
+            return None;
+        }
+        let (from, to) = if self.reverse {
+            let end = self.src_to();
+            (self.dst_from + end - to, self.dst_from + end - from)
+        } else {
+            (self.dst_from + from - self.src_from, self.dst_from + to - self.src_from)
+        };
+        Some((from.div_euclid(self.dst_width), to.div_euclid(self.dst_width)))
+    }
+
+    fn map_strand(&self, strand: Option<&NaStrand>) -> Option<NaStrand> {
+        // proteins are not stranded, and their codons are read on the strand of the range
+        if self.dst_width > 1 {
+            return None;
+        } else if self.src_width > 1 {
+            return Some(if self.reverse { NaStrand::Minus } else { NaStrand::Plus });
+        }
+        if !self.reverse {
+            return strand.cloned();
+        }
+        match strand {
+            None | Some(NaStrand::Plus) => Some(NaStrand::Minus),
+            Some(NaStrand::Minus) => Some(NaStrand::Plus),
+            Some(NaStrand::Both) => Some(NaStrand::BothRev),
+            Some(NaStrand::BothRev) => Some(NaStrand::Both),
+            Some(other) => Some(other.clone()),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Debug, Default)]
+/// Maps [`SeqLoc`] values from a source sequence onto a destination sequence
+pub struct SeqLocMapper {
+    ranges: Vec<MappingRange>,
+}
+
+impl SeqLocMapper {
+    /// Build a mapper from row `src` to row `dst` of a [`DenseSeg`]
+    pub fn from_dense_seg(denseg: &DenseSeg, src: usize, dst: usize) -> Result<Self, String> {
+        let dim = denseg.dim as usize;
+        if src >= dim || dst >= dim {
+            return Err(format!("Row out of range for Dense-seg of dimension {}", dim));
+        }
+        let src_id = denseg.ids.get(src).ok_or("Dense-seg is missing source Seq-id")?;
+        let dst_id = denseg.ids.get(dst).ok_or("Dense-seg is missing destination Seq-id")?;
+        let is_minus = |row| matches!(denseg.strand(row), Some(NaStrand::Minus));
+        let reverse = is_minus(src) != is_minus(dst);
+
+        let mut ranges = Vec::new();
+        for (seg, length) in denseg.lens.iter().enumerate() {
+            // empty segments have nothing to map
+            if *length == 0 {
+                continue;
+            }
+            if let (Some(src_from), Some(dst_from)) = (denseg.start(src, seg), denseg.start(dst, seg)) {
+                ranges.push(MappingRange {
+                    src_id: src_id.clone(),
+                    src_from: src_from as i64,
+                    dst_id: dst_id.clone(),
+                    dst_from: dst_from as i64,
+                    length: *length as i64,
+                    reverse,
+                    src_width: 1,
+                    dst_width: 1,
+                })
+            }
+        }
+        Ok(Self { ranges })
+    }
+
+    /// Build a mapper from the `src` sequence to the `dst` sequence of an alignment
+    ///
+    /// Only [`SeqAlignSegs::DenSeg`] and [`SeqAlignSegs::Disc`] alignments are supported.
+    pub fn from_seq_align(align: &SeqAlign, src: &SeqId, dst: &SeqId) -> Result<Self, String> {
+        match &align.segs {
+            SeqAlignSegs::DenSeg(denseg) => {
+                let row = |id| {
+                    denseg
+                        .ids
+                        .iter()
+                        .position(|i| i == id)
+                        .ok_or(format!("Seq-id not found in alignment: {:?}", id))
+                };
+                Self::from_dense_seg(denseg, row(src)?, row(dst)?)
+            }
+            SeqAlignSegs::Disc(aligns) => {
+                let mut mapper = Self::default();
+                for align in aligns.iter() {
+                    mapper.ranges.extend(Self::from_seq_align(align, src, dst)?.ranges);
+                }
+                Ok(mapper)
+            }
+            _ => Err("Unsupported Seq-align segment type for mapping".to_string()),
+        }
+    }
+
+    /// Build a mapper from the parts of a segmented [`BioSeq`] onto the [`BioSeq`] itself
+    ///
+    /// Parts given as [`SeqLoc::Whole`] have no length available and are rejected.
+    /// [`SeqLoc::Null`] parts, and intervals which end before they start, are treated
+    /// as gaps of zero length.
+    pub fn from_segmented(bioseq: &BioSeq) -> Result<Self, String> {
+        let segments = match bioseq.inst.as_ref().and_then(|inst| inst.ext.as_ref()) {
+            Some(SeqExt::Seg(segments)) => segments,
+            _ => return Err("Bioseq is not segmented".to_string()),
+        };
+        let master = bioseq.id.first().ok_or("Bioseq has no Seq-id")?;
+
+        let mut ranges = Vec::new();
+        let mut offset = 0;
+        for segment in segments.iter() {
+            match segment {
+                SeqLoc::Null => (),
+                // empty parts have nothing to map
+                SeqLoc::Int(interval) if interval.to < interval.from => (),
+                SeqLoc::Int(interval) => {
+                    let length = interval.to - interval.from + 1;
+                    ranges.push(MappingRange {
+                        src_id: interval.id.clone(),
+                        src_from: interval.from,
+                        dst_id: master.clone(),
+                        dst_from: offset,
+                        length,
+                        reverse: matches!(interval.strand, Some(NaStrand::Minus)),
+                        src_width: 1,
+                        dst_width: 1,
+                    });
+                    offset += length;
+                }
+                _ => return Err("Unsupported Seq-loc in segmented Bioseq".to_string()),
+            }
+        }
+        Ok(Self { ranges })
+    }
+
+    /// Build a mapper from the protein product of a coding region onto its location
+    ///
+    /// Residue `n` of the protein is mapped onto the codon starting `3 * n` bases after
+    /// the first complete codon given by [`CdRegion::frame`], following the intervals
+    /// of the location in the order that they are spliced. Use [`Self::reversed()`] to
+    /// find the residues encoded by a nucleotide location, which include any residue
+    /// whose codon is only partly covered.
+    ///
+    /// [`CdRegion::frame`]: crate::seqfeat::CdRegion::frame
+    pub fn from_cds(cds: &SeqFeat) -> Result<Self, String> {
+        let SeqFeatData::CdRegion(cdregion) = &cds.data else {
+            return Err("Feature is not a coding region".to_string());
+        };
+        let protein = match &cds.product {
+            Some(SeqLoc::Whole(id)) => id,
+            Some(SeqLoc::Int(interval)) => &interval.id,
+            _ => return Err("Coding region has no protein product".to_string()),
+        };
+
+        let mut ranges = Vec::new();
+        // offset of the current interval from the first complete codon, in nucleotides
+        let mut offset = -(cdregion.frame_offset() as i64);
+        for interval in splice_intervals(&cds.location)? {
+            if interval.to < interval.from {
+                return Err(format!(
+                    "Coding region interval ends before it starts ({}..{})",
+                    interval.from, interval.to
+                ));
+            }
+            let length = interval.to - interval.from + 1;
+            // bases before the first complete codon are not translated
+            let skipped = (-offset).clamp(0, length);
+            let minus = matches!(interval.strand, Some(NaStrand::Minus));
+            if skipped < length {
+                ranges.push(MappingRange {
+                    src_id: protein.clone(),
+                    src_from: offset + skipped,
+                    dst_id: interval.id.clone(),
+                    dst_from: if minus { interval.from } else { interval.from + skipped },
+                    length: length - skipped,
+                    reverse: minus,
+                    src_width: 3,
+                    dst_width: 1,
+                });
+            }
+            offset += length;
+        }
+        Ok(Self { ranges })
+    }
+
+    /// Swap source and destination so that locations are mapped in the other direction
+    pub fn reversed(&self) -> Self {
+        let ranges = self
+            .ranges
+            .iter()
+            .map(|range| MappingRange {
+                src_id: range.dst_id.clone(),
+                src_from: range.dst_from,
+                dst_id: range.src_id.clone(),
+                dst_from: range.src_from,
+                length: range.length,
+                reverse: range.reverse,
+                src_width: range.dst_width,
+                dst_width: range.src_width,
+            })
+            .collect();
+        Self { ranges }
+    }
+
+    /// `true` if no ranges are available for mapping
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Map `loc` onto the destination sequence
+    ///
+    /// Returns [`SeqLoc::Null`] if no part of `loc` could be mapped. Intervals which
+    /// span several alignment blocks are split into a [`SeqLoc::PackedInt`].
+    pub fn map(&self, loc: &SeqLoc) -> SeqLoc {
+        match loc {
+            SeqLoc::Whole(id) => self.pack(self.map_whole(id)),
+            SeqLoc::Int(interval) => self.pack(self.map_interval(interval)),
+            SeqLoc::PackedInt(intervals) => {
+                self.pack(intervals.iter().flat_map(|i| self.map_interval(i)).collect())
+            }
+            SeqLoc::Pnt(point) => match self.map_point(point) {
+                Some(point) => SeqLoc::Pnt(point),
+                None => SeqLoc::Null,
+            },
+            SeqLoc::Mix(mix) => {
+                let mut locs: Vec<SeqLoc> = mix
+                    .0
+                    .iter()
+                    .map(|loc| self.map(loc))
+                    .filter(|loc| *loc != SeqLoc::Null)
+                    .collect();
+                match locs.len() {
+                    0 => SeqLoc::Null,
+                    1 => locs.remove(0),
+                    _ => SeqLoc::Mix(SeqLocMix(locs)),
+                }
+            }
+            _ => SeqLoc::Null,
+        }
+    }
+
+    /// Copy of `feat` located on the destination sequence
+    ///
+    /// Returns `None` if no part of its location could be mapped. Only the location is
+    /// mapped, and the product of the copy is left unchanged.
+    pub fn map_feature(&self, feat: &SeqFeat) -> Option<SeqFeat> {
+        let location = self.map(&feat.location);
+        (location != SeqLoc::Null).then(|| SeqFeat {
+            location,
+            ..feat.clone()
+        })
+    }
+
+    fn map_whole(&self, id: &SeqId) -> Vec<SeqInterval> {
+        self.ranges
+            .iter()
+            .filter(|range| range.src_id == *id)
+            .filter_map(|range| {
+                let (from, to) = range.map_range(
+                    range.src_from.div_euclid(range.src_width),
+                    range.src_to().div_euclid(range.src_width),
+                )?;
+                Some(SeqInterval {
+                    from,
+                    to,
+                    strand: range.map_strand(None),
+                    id: range.dst_id.clone(),
+                    ..SeqInterval::default()
+                })
+            })
+            .collect()
+    }
+
+    fn map_interval(&self, interval: &SeqInterval) -> Vec<SeqInterval> {
+        let mut mapped: Vec<(i64, SeqInterval)> = self
+            .ranges
+            .iter()
+            .filter(|range| range.src_id == interval.id)
+            .filter_map(|range| {
+                let (from, to) = range.map_range(interval.from, interval.to)?;
+                let mapped = SeqInterval {
+                    from,
+                    to,
+                    strand: range.map_strand(interval.strand.as_ref()),
+                    id: range.dst_id.clone(),
+                    ..SeqInterval::default()
+                };
+                Some(((interval.from * range.src_width).max(range.src_from), mapped))
+            })
+            .collect();
+
+        // keep pieces in the biological order of the source interval
+        mapped.sort_by_key(|(src_from, _)| *src_from);
+        if matches!(interval.strand, Some(NaStrand::Minus)) {
+            mapped.reverse();
+        }
+        mapped.into_iter().map(|(_, interval)| interval).collect()
+    }
+
+    fn map_point(&self, point: &SeqPoint) -> Option<SeqPoint> {
+        self.ranges
+            .iter()
+            .filter(|range| range.src_id == point.id)
+            .find_map(|range| {
+                // first base of a codon in biological order
+                let (from, to) = range.map_range(point.point, point.point)?;
+                let mapped = if range.reverse && range.dst_width == 1 { to } else { from };
+                Some(SeqPoint {
+                    point: mapped,
+                    strand: range.map_strand(point.strand.as_ref()),
+                    id: range.dst_id.clone(),
+                    fuzz: None,
+                })
+            })
+    }
+
+    /// Merge abutting or overlapping intervals and wrap the result as a [`SeqLoc`]
+    fn pack(&self, intervals: Vec<SeqInterval>) -> SeqLoc {
+        let mut merged: Vec<SeqInterval> = Vec::new();
+        for interval in intervals {
+            if let Some(last) = merged.last_mut() {
+                if last.id == interval.id && last.strand == interval.strand {
+                    let minus = matches!(interval.strand, Some(NaStrand::Minus));
+                    // residues of a codon split between exons are given by both exons
+                    if !minus && (last.from..=last.to + 1).contains(&interval.from) {
+                        last.to = last.to.max(interval.to);
+                        continue;
+                    } else if minus && (last.from - 1..=last.to).contains(&interval.to) {
+                        last.from = last.from.min(interval.from);
+                        continue;
+                    }
+                }
+            }
+            merged.push(interval);
+        }
+        match merged.len() {
+            0 => SeqLoc::Null,
+            1 => SeqLoc::Int(merged.remove(0)),
+            _ => SeqLoc::PackedInt(merged),
+        }
+    }
+}
+
+/// Intervals of `location` in the order that they are spliced
+fn splice_intervals(location: &SeqLoc) -> Result<Vec<SeqInterval>, String> {
+    match location {
+        SeqLoc::Int(interval) => Ok(vec![interval.clone()]),
+        SeqLoc::PackedInt(intervals) => Ok(intervals.clone()),
+        SeqLoc::Pnt(point) => Ok(vec![SeqInterval {
+            from: point.point,
+            to: point.point,
+            strand: point.strand.clone(),
+            id: point.id.clone(),
+            ..SeqInterval::default()
+        }]),
+        SeqLoc::Mix(mix) => Ok(mix.0.iter().map(splice_intervals).collect::<Result<Vec<_>, _>>()?.concat()),
+        SeqLoc::Null => Ok(Vec::new()),
+        _ => Err("Unsupported Seq-loc for coding region".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::builder::SeqFeatBuilder;
+    use crate::general::ObjectId;
+    use crate::mapper::SeqLocMapper;
+    use crate::seqalign::DenseSeg;
+    use crate::seqfeat::CdRegionFrame;
+    use crate::seqloc::{NaStrand, SeqId, SeqInterval, SeqLoc, SeqPoint};
+
+    fn local(id: &str) -> SeqId {
+        SeqId::Local(ObjectId::Str(id.into()))
+    }
+
+    fn interval(id: &str, from: i64, to: i64, strand: Option<NaStrand>) -> SeqInterval {
+        SeqInterval {
+            from,
+            to,
+            strand,
+            id: local(id),
+            ..SeqInterval::default()
+        }
+    }
+
+    /// mRNA of 300 nt aligned to the minus strand of a genomic sequence as two exons
+    fn mrna_to_genomic() -> SeqLocMapper {
+        let denseg = DenseSeg {
+            dim: 2,
+            numseg: 3,
+            ids: vec![local("mrna"), local("genomic")],
+            starts: vec![0, 1900, -1, 1100, 100, 900],
+            lens: vec![100, 800, 200],
+            strands: Some(vec![NaStrand::Plus, NaStrand::Minus, NaStrand::Plus, NaStrand::Minus, NaStrand::Plus, NaStrand::Minus]),
+            scores: None,
+        };
+        SeqLocMapper::from_dense_seg(&denseg, 0, 1).unwrap()
+    }
+
+    #[test]
+    fn test_map_across_intron() {
+        let mapper = mrna_to_genomic();
+
+        // CDS spanning the exon junction
+        let cds = SeqLoc::Int(interval("mrna", 50, 149, Some(NaStrand::Plus)));
+        let expected = SeqLoc::PackedInt(vec![
+            interval("genomic", 1900, 1949, Some(NaStrand::Minus)),
+            interval("genomic", 1050, 1099, Some(NaStrand::Minus)),
+        ]);
+        assert_eq!(mapper.map(&cds), expected);
+
+        // map back through the intron
+        let back = mapper.reversed().map(&expected);
+        assert_eq!(back, SeqLoc::Int(interval("mrna", 50, 149, Some(NaStrand::Plus))));
+    }
+
+    #[test]
+    fn test_map_unaligned() {
+        let mapper = mrna_to_genomic();
+
+        let other = SeqLoc::Int(interval("other", 0, 10, None));
+        assert_eq!(mapper.map(&other), SeqLoc::Null);
+
+        let intron = SeqLoc::Int(interval("genomic", 1300, 1400, Some(NaStrand::Minus)));
+        assert_eq!(mapper.reversed().map(&intron), SeqLoc::Null);
+    }
+
+    #[test]
+    fn test_map_empty_segments() {
+        let denseg = DenseSeg {
+            dim: 2,
+            numseg: 2,
+            ids: vec![local("a"), local("b")],
+            starts: vec![0, 100, 10, 110],
+            lens: vec![10, 0],
+            strands: None,
+            scores: None,
+        };
+        let mapper = SeqLocMapper::from_dense_seg(&denseg, 0, 1).unwrap();
+        assert_eq!(mapper.map(&SeqLoc::Whole(local("a"))), SeqLoc::Int(interval("b", 100, 109, None)));
+    }
+
+    #[test]
+    fn test_map_protein_to_cds() {
+        // two exons of 10 and 50 nt, so that codon 3 spans the junction
+        let location = SeqLoc::PackedInt(vec![
+            interval("genomic", 100, 109, Some(NaStrand::Plus)),
+            interval("genomic", 200, 249, Some(NaStrand::Plus)),
+        ]);
+        let cds = SeqFeatBuilder::cds(location).product(SeqLoc::Whole(local("prot"))).build().unwrap();
+        let mapper = SeqLocMapper::from_cds(&cds).unwrap();
+
+        let domain = SeqLoc::Int(interval("prot", 0, 4, None));
+        let expected = SeqLoc::PackedInt(vec![
+            interval("genomic", 100, 109, Some(NaStrand::Plus)),
+            interval("genomic", 200, 204, Some(NaStrand::Plus)),
+        ]);
+        assert_eq!(mapper.map(&domain), expected);
+        assert_eq!(
+            mapper.map(&SeqLoc::Int(interval("prot", 3, 3, None))),
+            SeqLoc::PackedInt(vec![
+                interval("genomic", 109, 109, Some(NaStrand::Plus)),
+                interval("genomic", 200, 201, Some(NaStrand::Plus)),
+            ])
+        );
+
+        // partly covered codons give their whole residue
+        let variant = SeqLoc::Int(interval("genomic", 105, 205, Some(NaStrand::Plus)));
+        assert_eq!(mapper.reversed().map(&variant), SeqLoc::Int(interval("prot", 1, 5, None)));
+        assert_eq!(mapper.reversed().map(&SeqLoc::Int(interval("genomic", 150, 160, None))), SeqLoc::Null);
+
+        // mapped features keep their data
+        let site = SeqFeatBuilder::region("active site", SeqLoc::Int(interval("prot", 0, 4, None))).build().unwrap();
+        let projected = mapper.map_feature(&site).unwrap();
+        assert_eq!(projected.location, expected);
+        assert_eq!(projected.data, site.data);
+    }
+
+    #[test]
+    fn test_map_protein_to_minus_strand() {
+        // first base of the coding region is not part of a codon
+        let location = SeqLoc::Int(interval("genomic", 1000, 1030, Some(NaStrand::Minus)));
+        let cds = SeqFeatBuilder::cds(location)
+            .frame(CdRegionFrame::Two)
+            .product(SeqLoc::Whole(local("prot")))
+            .build()
+            .unwrap();
+        let mapper = SeqLocMapper::from_cds(&cds).unwrap();
+
+        let residues = SeqLoc::Int(interval("prot", 0, 2, None));
+        assert_eq!(mapper.map(&residues), SeqLoc::Int(interval("genomic", 1021, 1029, Some(NaStrand::Minus))));
+
+        let start = SeqPoint {
+            point: 0,
+            strand: None,
+            id: local("prot"),
+            fuzz: None,
+        };
+        match mapper.map(&SeqLoc::Pnt(start)) {
+            SeqLoc::Pnt(point) => assert_eq!((point.point, point.strand), (1029, Some(NaStrand::Minus))),
+            loc => panic!("unexpected location {:?}", loc),
+        }
+
+        // the untranslated base maps to no residue
+        let codon = SeqLoc::Int(interval("genomic", 1027, 1030, Some(NaStrand::Minus)));
+        assert_eq!(mapper.reversed().map(&codon), SeqLoc::Int(interval("prot", 0, 0, None)));
+
+        let gene = SeqFeatBuilder::gene("abc", SeqLoc::Int(interval("genomic", 0, 9, None))).build().unwrap();
+        assert!(SeqLocMapper::from_cds(&gene).is_err());
+
+        // inverted intervals are malformed
+        let mut inverted = cds.clone();
+        inverted.location = SeqLoc::Int(interval("genomic", 1030, 1000, Some(NaStrand::Minus)));
+        assert!(SeqLocMapper::from_cds(&inverted).is_err());
+    }
+}
@@ -0,0 +1,216 @@
+//! Newline delimited JSON (NDJSON) serialization of parsed records
+//!
+//! Data pipelines (eg: Spark, BigQuery, `jq`) read large collections one JSON object
+//! per line. Each line written by [`NdjsonWriter`] wraps a single record with the
+//! version of the schema and the type of the record:
+//!
+//! ```text
+//! {"schema":1,"type":"entrezgene","record":{...}}
+//! ```
+//!
+//! Records are serialized as by `serde_json`, so their fields follow the structures of
+//! this crate. [`SCHEMA_VERSION`] is increased whenever a change to these structures
+//! alters the serialized form, and [`NdjsonReader`] rejects lines written with a newer
+//! schema than it understands.
+//!
+//! # Example
+//! ```ignore
+//! let mut writer = NdjsonWriter::new(File::create("genes.ndjson").unwrap());
+//! for gene in genes.iter() {
+//!     writer.write(gene)?;
+//! }
+//! let genes: Vec<Entrezgene> = read_ndjson(BufReader::new(File::open("genes.ndjson").unwrap()))?;
+//! ```
+
+use crate::entrezgene::Entrezgene;
+use crate::medline::MedlineEntry;
+use crate::seqset::SeqEntry;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, Write};
+use std::marker::PhantomData;
+
+/// Version of the serialized form of records, written on every line
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Records which may be written as NDJSON
+pub trait NdjsonRecord: Serialize + DeserializeOwned {
+    /// value of the `type` field of each line (eg: "seq-entry")
+    const TYPE: &'static str;
+}
+
+impl NdjsonRecord for SeqEntry {
+    const TYPE: &'static str = "seq-entry";
+}
+
+impl NdjsonRecord for Entrezgene {
+    const TYPE: &'static str = "entrezgene";
+}
+
+/// PubMed records, as returned by EFetch with `rettype=medline&retmode=asn.1`
+impl NdjsonRecord for MedlineEntry {
+    const TYPE: &'static str = "medline-entry";
+}
+
+#[derive(Serialize)]
+struct Line<'a, T> {
+    schema: u32,
+    r#type: &'a str,
+    record: &'a T,
+}
+
+#[derive(Deserialize)]
+struct OwnedLine {
+    schema: u32,
+    r#type: String,
+    record: serde_json::Value,
+}
+
+/// Single line for `record`, without the trailing newline
+pub fn to_ndjson_line<T: NdjsonRecord>(record: &T) -> Result<String, String> {
+    let line = Line {
+        schema: SCHEMA_VERSION,
+        r#type: T::TYPE,
+        record,
+    };
+    serde_json::to_string(&line).map_err(|e| e.to_string())
+}
+
+/// Parse a single line written by [`to_ndjson_line()`]
+pub fn from_ndjson_line<T: NdjsonRecord>(line: &str) -> Result<T, String> {
+    // the record is only converted once the header is checked
+    let line: OwnedLine = serde_json::from_str(line).map_err(|e| e.to_string())?;
+    if line.schema > SCHEMA_VERSION {
+        return Err(format!(
+            "Schema version {} is newer than the supported version {}",
+            line.schema, SCHEMA_VERSION
+        ));
+    }
+    if line.r#type != T::TYPE {
+        return Err(format!("Expected a {} record, found {}", T::TYPE, line.r#type));
+    }
+    serde_json::from_value(line.record).map_err(|e| e.to_string())
+}
+
+/// All `records` as NDJSON, each line ending with a newline
+pub fn to_ndjson<T: NdjsonRecord>(records: &[T]) -> Result<String, String> {
+    let mut ndjson = String::new();
+    for record in records {
+        ndjson.push_str(&to_ndjson_line(record)?);
+        ndjson.push('\n');
+    }
+    Ok(ndjson)
+}
+
+/// Writes records one line at a time
+pub struct NdjsonWriter<W: Write> {
+    writer: W,
+    /// number of records written
+    pub count: u64,
+}
+
+impl<W: Write> NdjsonWriter<W> {
+    pub fn new(writer: W) -> Self {
+        Self { writer, count: 0 }
+    }
+
+    pub fn write<T: NdjsonRecord>(&mut self, record: &T) -> Result<(), String> {
+        let line = to_ndjson_line(record)?;
+        writeln!(self.writer, "{}", line).map_err(|e| e.to_string())?;
+        self.count += 1;
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> Result<(), String> {
+        self.writer.flush().map_err(|e| e.to_string())
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Reads records one line at a time
+///
+/// Blank lines are skipped. Errors give the number of the line which could not be read.
+pub struct NdjsonReader<R: BufRead, T> {
+    lines: std::io::Lines<R>,
+    number: usize,
+    record: PhantomData<T>,
+}
+
+impl<R: BufRead, T: NdjsonRecord> NdjsonReader<R, T> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            lines: reader.lines(),
+            number: 0,
+            record: PhantomData,
+        }
+    }
+}
+
+impl<R: BufRead, T: NdjsonRecord> Iterator for NdjsonReader<R, T> {
+    type Item = Result<T, String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let line = self.lines.next()?;
+            self.number += 1;
+            let line = match line {
+                Ok(line) => line,
+                Err(e) => return Some(Err(format!("Line {}: {}", self.number, e))),
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+            return Some(from_ndjson_line(&line).map_err(|e| format!("Line {}: {}", self.number, e)));
+        }
+    }
+}
+
+/// Read every record written by [`NdjsonWriter`] or [`to_ndjson()`]
+pub fn read_ndjson<T: NdjsonRecord, R: BufRead>(reader: R) -> Result<Vec<T>, String> {
+    NdjsonReader::new(reader).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entrezgene::Entrezgene;
+    use crate::ndjson::{from_ndjson_line, read_ndjson, to_ndjson, NdjsonWriter, SCHEMA_VERSION};
+    use crate::seqset::SeqEntry;
+    use crate::{get_local_xml, parse_xml, DataType};
+
+    #[test]
+    fn test_roundtrip() {
+        let genes = match parse_xml(&get_local_xml("tests/data/tp73.genbank.xml")).unwrap() {
+            DataType::EntrezgeneSet(genes) => genes,
+            _ => panic!("No Entrezgene set found"),
+        };
+        let ndjson = to_ndjson(&genes).unwrap();
+        assert_eq!(ndjson.lines().count(), genes.len());
+        assert!(ndjson.starts_with(&format!("{{\"schema\":{},\"type\":\"entrezgene\",\"record\":{{", SCHEMA_VERSION)));
+        let read: Vec<Entrezgene> = read_ndjson(ndjson.as_bytes()).unwrap();
+        assert_eq!(read, genes);
+
+        let set = match parse_xml(&get_local_xml("tests/data/nuc_prot.xml")).unwrap() {
+            DataType::BioSeqSet(set) => set,
+            _ => panic!("No Bioseq-set found"),
+        };
+        let mut writer = NdjsonWriter::new(Vec::new());
+        for entry in set.seq_set.iter() {
+            writer.write(entry).unwrap();
+        }
+        assert_eq!(writer.count, set.seq_set.len() as u64);
+        let mut ndjson = String::from_utf8(writer.into_inner()).unwrap();
+        ndjson.insert(0, '\n');
+        let read: Vec<SeqEntry> = read_ndjson(ndjson.as_bytes()).unwrap();
+        assert_eq!(read, set.seq_set);
+
+        // records of another type, or a newer schema, are rejected
+        let line = ndjson.lines().nth(1).unwrap();
+        assert_eq!(from_ndjson_line::<Entrezgene>(line).unwrap_err(), "Expected a entrezgene record, found seq-entry");
+        let newer = line.replacen(&format!("\"schema\":{}", SCHEMA_VERSION), "\"schema\":999", 1);
+        assert!(from_ndjson_line::<SeqEntry>(&newer).unwrap_err().starts_with("Schema version 999"));
+        assert!(read_ndjson::<SeqEntry, _>("\n{}\n".as_bytes()).unwrap_err().starts_with("Line 2: "));
+    }
+}
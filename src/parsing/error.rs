@@ -0,0 +1,34 @@
+use std::fmt;
+
+/// Error raised when XML content cannot be converted into a value
+#[derive(Clone, PartialEq, Debug)]
+pub enum ParseError {
+    /// Text could not be parsed as the requested numeric type
+    InvalidNumber {
+        text: String,
+        /// name of requested type
+        target: &'static str,
+    },
+
+    /// Underlying XML is malformed
+    Xml(String),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidNumber { text, target } => {
+                write!(f, "could not parse \"{}\" as {}", text, target)
+            }
+            Self::Xml(msg) => write!(f, "XML error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl From<quick_xml::Error> for ParseError {
+    fn from(e: quick_xml::Error) -> Self {
+        Self::Xml(e.to_string())
+    }
+}
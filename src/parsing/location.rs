@@ -0,0 +1,354 @@
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Position within an XML document, given by the enclosing elements and line/column
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct Location {
+    /// byte offset from the start of the document
+    pub offset: usize,
+    /// 1-based line number
+    pub line: usize,
+    /// 1-based column, in characters
+    pub column: usize,
+    /// names of enclosing elements, separated by "/"
+    ///
+    /// An element preceded by siblings of the same name is numbered as in XPath
+    /// (eg: `Bioseq/Bioseq_descr/Seq-descr/Seqdesc[3]/Seqdesc_source`).
+    pub path: String,
+}
+
+impl Location {
+    /// Locate `offset` within `xml`
+    pub fn find(xml: &str, offset: usize) -> Self {
+        Self::find_all(xml, &[offset]).remove(0)
+    }
+
+    /// Locate several offsets in a single pass over `xml`
+    ///
+    /// Locations are returned in the same order as `offsets`.
+    pub fn find_all(xml: &str, offsets: &[usize]) -> Vec<Self> {
+        let mut order: Vec<usize> = (0..offsets.len()).collect();
+        order.sort_by_key(|i| offsets[*i]);
+
+        let mut reader = Reader::from_str(xml);
+        let mut tracker = PathTracker::default();
+        let mut locations = vec![Self::default(); offsets.len()];
+        let mut line = (1, 0); // (number, byte offset of line start)
+        let mut counted = 0;
+
+        for i in order {
+            let offset = offsets[i].min(xml.len());
+
+            // advance through every event which starts before `offset`
+            while reader.buffer_position() < offset {
+                match reader.read_event() {
+                    Ok(Event::Start(e)) => tracker.open(e.name().as_ref()),
+                    Ok(Event::End(_)) => tracker.close(),
+                    Ok(Event::Eof) | Err(_) => break,
+                    _ => (),
+                }
+            }
+
+            for (pos, byte) in xml.as_bytes()[counted..offset].iter().enumerate() {
+                if *byte == b'\n' {
+                    line = (line.0 + 1, counted + pos + 1);
+                }
+            }
+            counted = offset;
+
+            let column = xml.get(line.1..offset).map(|s| s.chars().count()).unwrap_or_default() + 1;
+            locations[i] = Self {
+                offset,
+                line: line.0,
+                column,
+                path: tracker.path(),
+            };
+        }
+        locations
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "line {}, column {}", self.line, self.column)
+        } else {
+            write!(f, "{} (line {}, column {})", self.path, self.line, self.column)
+        }
+    }
+}
+
+/// Stack of open elements, along with the number of children of each name seen so far
+#[derive(Default)]
+struct PathTracker {
+    open: Vec<(String, usize)>,
+    children: Vec<HashMap<String, usize>>,
+}
+
+impl PathTracker {
+    fn open(&mut self, name: &[u8]) {
+        let name = String::from_utf8_lossy(name).to_string();
+        let siblings = self.children.last_mut().map(|children| {
+            let count = children.entry(name.clone()).or_default();
+            *count += 1;
+            *count
+        });
+        self.open.push((name, siblings.unwrap_or(1)));
+        self.children.push(HashMap::new());
+    }
+
+    fn close(&mut self) {
+        self.open.pop();
+        self.children.pop();
+    }
+
+    fn path(&self) -> String {
+        self.open
+            .iter()
+            .map(|(name, index)| match index {
+                1 => name.clone(),
+                _ => format!("{}[{}]", name, index),
+            })
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+}
+
+/// Diagnostic raised while parsing, such as an unknown tag or a value which could not
+/// be parsed
+#[derive(Clone, PartialEq, Debug)]
+pub struct Diagnostic {
+    pub message: String,
+    pub location: Location,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at {}", self.message, self.location)
+    }
+}
+
+thread_local! {
+    /// Buffer position of the last event returned by [`crate::parsing::next_event()`]
+    static POSITION: Cell<usize> = const { Cell::new(0) };
+
+    /// Diagnostics awaiting a location, when tracking
+    static PENDING: RefCell<Option<Vec<(usize, String)>>> = const { RefCell::new(None) };
+}
+
+/// Record the buffer position of the event being read
+pub(crate) fn mark(position: usize) {
+    POSITION.with(|current| current.set(position));
+}
+
+/// Buffer position of the last event read
+pub(crate) fn position() -> usize {
+    POSITION.with(Cell::get)
+}
+
+/// Report a diagnostic at the position of the last event read
+pub(crate) fn report(message: String) {
+    report_at(position(), message)
+}
+
+/// Report a diagnostic at the given buffer position
+///
+/// When [`collect_diagnostics()`] is in effect, the diagnostic is located once parsing
+/// has finished. Otherwise, it is logged immediately with its byte offset.
+pub(crate) fn report_at(position: usize, message: String) {
+    let message = PENDING.with(|pending| match pending.borrow_mut().as_mut() {
+        Some(pending) => {
+            pending.push((position, message));
+            None
+        }
+        None => Some(message),
+    });
+    if let Some(message) = message {
+        log::warn!("{} (byte {})", message, position);
+    }
+}
+
+/// Run `f`, which parses `xml`, and collect the diagnostics it raises
+///
+/// Each diagnostic is located by its element path, line and column within `xml`.
+/// Diagnostics are returned instead of logged. Nested calls are collected by the
+/// outermost call.
+///
+/// # Example
+/// ```
+/// use ncbi::parsing::{collect_diagnostics, parse_node};
+/// use ncbi::seq::BioSeq;
+///
+/// let xml = "<Bioseq><Bioseq_unknown>x</Bioseq_unknown></Bioseq>";
+/// let (bioseq, diagnostics) = collect_diagnostics(xml, || parse_node::<BioSeq>(xml));
+/// for diagnostic in diagnostics {
+///     println!("{}", diagnostic);
+/// }
+/// ```
+pub fn collect_diagnostics<T>(xml: &str, f: impl FnOnce() -> T) -> (T, Vec<Diagnostic>) {
+    /// Stops collection, even if `f` panics
+    struct Stop(bool);
+    impl Drop for Stop {
+        fn drop(&mut self) {
+            if self.0 {
+                PENDING.with(|pending| pending.borrow_mut().take());
+            }
+        }
+    }
+
+    let outermost = PENDING.with(|pending| {
+        let mut pending = pending.borrow_mut();
+        let outermost = pending.is_none();
+        pending.get_or_insert_with(Vec::new);
+        outermost
+    });
+    let stop = Stop(outermost);
+    let result = f();
+    if !outermost {
+        return (result, Vec::new());
+    }
+
+    let pending = PENDING.with(|pending| pending.borrow_mut().take()).unwrap_or_default();
+    drop(stop);
+    let offsets: Vec<usize> = pending.iter().map(|(offset, _)| *offset).collect();
+    let diagnostics = pending
+        .into_iter()
+        .zip(Location::find_all(xml, &offsets))
+        .map(|((_, message), location)| Diagnostic { message, location })
+        .collect();
+    (result, diagnostics)
+}
+
+/// Run `f` and return the diagnostics it raises by buffer position, instead of reporting them
+///
+/// Used where `f` parses a slice of a larger document on another thread, so that the
+/// caller can report diagnostics at their offset within the whole document.
+pub(crate) fn take_reports<T>(f: impl FnOnce() -> T) -> (T, Vec<(usize, String)>) {
+    /// Restores the collection of an enclosing call, even if `f` panics
+    struct Restore(Option<Vec<(usize, String)>>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let outer = self.0.take();
+            PENDING.with(|pending| *pending.borrow_mut() = outer);
+        }
+    }
+
+    let outer = PENDING.with(|pending| pending.borrow_mut().replace(Vec::new()));
+    let restore = Restore(outer);
+    let result = f();
+    let reports = PENDING.with(|pending| pending.borrow_mut().take()).unwrap_or_default();
+    drop(restore);
+    (result, reports)
+}
+
+/// Error message for a failure at `offset` within `xml`, along with its [`Location`]
+pub(crate) fn locate_error(xml: &str, offset: usize, message: &str) -> String {
+    Diagnostic {
+        message: message.trim_end_matches('.').to_string(),
+        location: Location::find(xml, offset),
+    }
+    .to_string()
+}
+
+/// Run `f`, which parses `xml`, and log diagnostics along with their location
+pub(crate) fn with_locations<T>(xml: &str, f: impl FnOnce() -> T) -> T {
+    let (result, diagnostics) = collect_diagnostics(xml, f);
+    for diagnostic in diagnostics {
+        log::warn!("{}", diagnostic);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parsing::Location;
+
+    const XML: &str = "<Bioseq>
+  <Bioseq_descr>
+    <Seq-descr>
+      <Seqdesc><Seqdesc_title>a</Seqdesc_title></Seqdesc>
+      <Seqdesc><Seqdesc_title>b</Seqdesc_title></Seqdesc>
+      <Seqdesc><Seqdesc_source><BioSource><BioSource_unknown>x</BioSource_unknown></BioSource></Seqdesc_source></Seqdesc>
+    </Seq-descr>
+  </Bioseq_descr>
+</Bioseq>";
+
+    #[test]
+    fn find_location() {
+        let offset = XML.find("<BioSource_unknown").unwrap();
+        let location = Location::find(XML, offset);
+        assert_eq!(location.path, "Bioseq/Bioseq_descr/Seq-descr/Seqdesc[3]/Seqdesc_source/BioSource");
+        assert_eq!((location.line, location.column), (6, 43));
+
+        assert_eq!(Location::find(XML, 0).path, "");
+        assert_eq!(Location::find(XML, XML.len()).line, 9);
+    }
+
+    #[test]
+    // the unknown tag is a panic rather than a diagnostic under `strict`
+    #[cfg(not(feature = "strict"))]
+    fn collect() {
+        use crate::parsing::{collect_diagnostics, parse_node};
+        use crate::seq::BioSeq;
+
+        let (bioseq, diagnostics) = collect_diagnostics(XML, || parse_node::<BioSeq>(XML));
+        assert!(bioseq.is_some());
+        let diagnostic = diagnostics
+            .iter()
+            .find(|diagnostic| diagnostic.message.contains("BioSource_unknown"))
+            .unwrap();
+        assert_eq!(diagnostic.location.line, 6);
+        assert!(diagnostic.location.path.ends_with("Seqdesc[3]/Seqdesc_source/BioSource"));
+
+        // not collected outside of `collect_diagnostics()`
+        let (_, diagnostics) = collect_diagnostics(XML, || ());
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    #[cfg(not(feature = "strict"))]
+    fn collect_parallel() {
+        use crate::parsing::collect_diagnostics;
+        use crate::seqset::BioSeqSet;
+
+        let xml = "<Bioseq-set>
+  <Bioseq-set_seq-set>
+    <Seq-entry><Seq-entry_seq><Bioseq></Bioseq></Seq-entry_seq></Seq-entry>
+    <Seq-entry><Seq-entry_seq><Bioseq><Bioseq_descr><Seq-descr><Seqdesc><Seqdesc_source>
+      <BioSource><BioSource_unknown>x</BioSource_unknown></BioSource>
+    </Seqdesc_source></Seqdesc></Seq-descr></Bioseq_descr></Bioseq></Seq-entry_seq></Seq-entry>
+  </Bioseq-set_seq-set>
+  <Bioseq-set_unknown>x</Bioseq-set_unknown>
+</Bioseq-set>";
+        for threads in [1, 2] {
+            let (set, diagnostics) = collect_diagnostics(xml, || BioSeqSet::from_str_parallel(xml, threads));
+            assert_eq!(set.unwrap().seq_set.len(), 2);
+
+            // located within the whole document, rather than the entry or the rest of the set
+            let entry = diagnostics.iter().find(|d| d.message.contains("BioSource_unknown")).unwrap();
+            assert_eq!(entry.location.line, 5);
+            assert_eq!(
+                entry.location.path,
+                "Bioseq-set/Bioseq-set_seq-set/Seq-entry[2]/Seq-entry_seq/Bioseq/Bioseq_descr/Seq-descr/Seqdesc/Seqdesc_source/BioSource"
+            );
+            let set = diagnostics.iter().find(|d| d.message.contains("Bioseq-set_unknown")).unwrap();
+            assert_eq!(set.location.path, "Bioseq-set");
+        }
+    }
+
+    #[test]
+    fn locate_errors() {
+        use crate::parse_xml;
+        use crate::seqset::BioSeqSet;
+
+        let xml = "<Bioseq-set>\n  <Bioseq-set_seq-set>\n    <Seq-entry></Seq-entry>";
+        let error = parse_xml(xml).unwrap_err();
+        assert_eq!(error, "Failed to parse BioSeqSet at Bioseq-set/Bioseq-set_seq-set (line 3, column 28)");
+
+        let error = BioSeqSet::from_str_parallel(xml, 1).unwrap_err();
+        assert!(error.ends_with("at Bioseq-set/Bioseq-set_seq-set (line 3, column 28)"));
+    }
+}
@@ -1,7 +1,25 @@
+mod error;
+mod location;
 mod node;
+mod options;
+mod span;
+#[cfg(feature = "async")]
+mod stream;
+mod tag;
 mod utils;
 mod unexpected;
 
+pub use error::*;
+pub use location::{collect_diagnostics, Diagnostic, Location};
+pub(crate) use location::{locate_error, mark, position, report, report_at, take_reports, with_locations};
 pub use node::*;
+pub use options::ParseOptions;
+#[cfg(all(feature = "rayon", not(target_arch = "wasm32")))]
+pub(crate) use options::current_options;
+pub use span::{record_spans, ObjectHandle, Span, SpanTable};
+pub(crate) use span::record_span;
+#[cfg(feature = "async")]
+pub use stream::*;
+pub use tag::*;
 pub use utils::*;
 pub use unexpected::*;
\ No newline at end of file
@@ -1,6 +1,7 @@
 use quick_xml::events::{BytesEnd, BytesStart, Event};
 use quick_xml::events::attributes::Attributes;
 use quick_xml::Reader;
+use crate::parsing::{next_event, position, record_span};
 
 /// Handles parsing of simple data from [`Event::Empty`] values
 ///
@@ -26,6 +27,9 @@ pub trait XmlValue {
 /// Two types of values parsed by `XmlNode`: enums and structs.
 /// Both field/variant name/value have tags to separate between the field/variant and the
 /// enclosed value. Decision what should be parsed is denoted by [`Self::start_bytes()`].
+///
+/// The trait is object safe, so that parsed objects of different types may be held as
+/// `Box<dyn XmlNode>`.
 pub trait XmlNode {
 
     /// Return starting element
@@ -38,7 +42,9 @@ pub trait XmlNode {
     /// is no simple, idiomatic way to gracefully implement this. Therefore, changing
     /// this is not planned on being implemented especially since the ASN.1 data format
     /// is not likely to change much.
-    fn start_bytes() -> BytesStart<'static>;
+    fn start_bytes() -> BytesStart<'static>
+    where
+        Self: Sized;
 
     /// Process the XML data as `Self`
     ///
@@ -54,7 +60,10 @@ pub trait XmlNode {
     where
         Self: Sized;
 
-    fn is_end(element: &BytesEnd) -> bool {
+    fn is_end(element: &BytesEnd) -> bool
+    where
+        Self: Sized,
+    {
         element.name() == Self::start_bytes().name()
     }
 }
@@ -76,11 +85,13 @@ pub trait XmlVecNode: XmlNode {
         let end = end.into().unwrap_or(binding.to_end());
         let mut items = Vec::new();
 
-        loop {
-            match reader.read_event().unwrap() {
+        while let Some(event) = next_event(reader) {
+            match event {
                 Event::Start(e) => {
                     if e.name() == Self::start_bytes().name() {
+                        let start = position();
                         if let Some(val) = Self::from_reader(reader) {
+                            record_span::<Self>(start, reader.buffer_position());
                             items.push(val);
                         }
                     }
@@ -90,9 +101,6 @@ pub trait XmlVecNode: XmlNode {
                         break;
                     }
                 }
-                Event::Eof => {
-                    break;
-                }
                 _ => (),
             }
         }
@@ -100,3 +108,26 @@ pub trait XmlVecNode: XmlNode {
         items
     }
 }
+
+/// Borrowing counterpart of [`XmlNode`] for read-only analysis
+///
+/// Implementors are views whose string fields are `&'a str` slices of the input
+/// buffer rather than owned [`String`]s, so that large documents can be scanned for a
+/// few fields without allocating. Text is kept exactly as found in the document.
+///
+/// Each view corresponds to an owned [`XmlNode`] type, which determines the element
+/// being parsed and which a view can be converted into by [`Self::to_owned_node()`].
+pub trait XmlNodeRef<'a> {
+    type Owned: XmlNode;
+
+    /// Process the XML data as `Self`, borrowing text from the buffer of `reader`
+    ///
+    /// Called once the start tag of [`Self::Owned`] has been encountered, with the
+    /// same contract as [`XmlNode::from_reader()`].
+    fn from_reader_borrowed(reader: &mut Reader<&'a [u8]>) -> Option<Self>
+    where
+        Self: Sized;
+
+    /// Copy the borrowed data into the owned type
+    fn to_owned_node(&self) -> Self::Owned;
+}
@@ -0,0 +1,150 @@
+use crate::parsing::XmlReader;
+use quick_xml::events::BytesStart;
+use std::cell::RefCell;
+
+/// Options which change how readers process XML data
+///
+/// Options take effect for parsing done within [`Self::apply()`], or through
+/// [`crate::parse_xml_with()`].
+///
+/// # Projection
+///
+/// [`Self::select()`] restricts parsing to the given elements, named as in the DTD
+/// (eg: `Bioseq_id` or `Seqdesc_source`). A field or choice variant element (any name
+/// of the form `Type_field`) is skipped without being parsed, unless it is selected,
+/// is enclosed by a selected element, or encloses one. Fields which are skipped keep
+/// their default value, and skipped choice variants do not produce a value at all, so
+/// that eg: selecting `Seqdesc_source` leaves only source descriptors in
+/// [`crate::seq::SeqDescr`].
+///
+/// Type elements (eg: `Bioseq`) are never skipped.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ParseOptions {
+    select: Option<Vec<String>>,
+}
+
+struct State {
+    options: ParseOptions,
+
+    /// Buffer positions where enclosing selected elements end
+    open: Vec<usize>,
+}
+
+thread_local! {
+    static STATE: RefCell<Option<State>> = const { RefCell::new(None) };
+}
+
+impl ParseOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only parse the given elements and the elements leading to them
+    pub fn select(names: &[&str]) -> Self {
+        Self::new().with_selection(names)
+    }
+
+    /// Add `names` to the selected elements
+    pub fn with_selection(mut self, names: &[&str]) -> Self {
+        self.select
+            .get_or_insert_with(Vec::new)
+            .extend(names.iter().map(|name| name.to_string()));
+        self
+    }
+
+    /// Elements given by [`Self::select()`], if any
+    pub fn selection(&self) -> Option<&[String]> {
+        self.select.as_deref()
+    }
+
+    /// Run `f` with these options in effect for the current thread
+    ///
+    /// Options of an enclosing call are restored afterwards.
+    pub fn apply<T>(&self, f: impl FnOnce() -> T) -> T {
+        /// Restores the previous state, even if `f` panics
+        struct Restore(Option<State>);
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                let previous = self.0.take();
+                STATE.with(|state| *state.borrow_mut() = previous);
+            }
+        }
+
+        let state = State {
+            options: self.clone(),
+            open: Vec::new(),
+        };
+        let _restore = Restore(STATE.with(|current| current.borrow_mut().replace(state)));
+        f()
+    }
+
+    fn is_selected(&self, name: &[u8]) -> bool {
+        self.select
+            .iter()
+            .flatten()
+            .any(|selected| selected.as_bytes() == name)
+    }
+
+    /// Whether `xml` contains the start of any selected element
+    fn encloses_selected(&self, xml: &[u8]) -> bool {
+        self.select.iter().flatten().any(|selected| {
+            let tag = format!("<{}", selected);
+            xml.windows(tag.len() + 1).any(|window| {
+                window.starts_with(tag.as_bytes())
+                    && matches!(window[tag.len()], b'>' | b'/' | b' ' | b'\t' | b'\r' | b'\n')
+            })
+        })
+    }
+}
+
+/// Whether the element which has just been read should be skipped under the
+/// current projection
+///
+/// `position` is the buffer position before `element` was read. When the element is
+/// skipped, `reader` is advanced past its end.
+pub(crate) fn skip_unselected(reader: &mut XmlReader, element: &BytesStart, position: usize) -> bool {
+    STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        let state = match state.as_mut() {
+            Some(state) if state.options.select.is_some() => state,
+            _ => return false,
+        };
+
+        while state.open.last().is_some_and(|end| *end <= position) {
+            state.open.pop();
+        }
+        if !state.open.is_empty() {
+            return false;
+        }
+
+        // only fields and choice variants are candidates for skipping
+        let name = element.name();
+        if !name.as_ref().contains(&b'_') {
+            return false;
+        }
+
+        let rest: &[u8] = reader.get_ref();
+        let mut probe = reader.clone();
+        if probe.read_to_end(name).is_err() {
+            return false;
+        }
+        if state.options.is_selected(name.as_ref()) {
+            state.open.push(probe.buffer_position());
+            return false;
+        }
+
+        let length = probe.buffer_position() - reader.buffer_position();
+        if state.options.encloses_selected(&rest[..length.min(rest.len())]) {
+            return false;
+        }
+        *reader = probe;
+        true
+    })
+}
+
+/// Options in effect for the current thread, so that they can be passed on to
+/// worker threads
+#[cfg(all(feature = "rayon", not(target_arch = "wasm32")))]
+pub(crate) fn current_options() -> Option<ParseOptions> {
+    STATE.with(|state| state.borrow().as_ref().map(|state| state.options.clone()))
+}
@@ -0,0 +1,252 @@
+use crate::parsing::Location;
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+/// Byte range of a parsed object within its document
+///
+/// Spans from the start tag of the object to the end of its end tag. Objects which
+/// have no tag of their own (eg: [`crate::seqfeat::SeqFeatData`]) span the element of
+/// their variant.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct Span {
+    /// byte offset of the first byte
+    pub start: usize,
+    /// byte offset following the last byte
+    pub end: usize,
+}
+
+impl Span {
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    /// XML of the object, as given in `xml`
+    ///
+    /// Empty when `xml` is not the document which the span was recorded from.
+    pub fn text<'a>(&self, xml: &'a str) -> &'a str {
+        xml.get(self.start..self.end).unwrap_or_default()
+    }
+
+    /// Location of the start tag within `xml`
+    pub fn location(&self, xml: &str) -> Location {
+        Location::find(xml, self.start)
+    }
+}
+
+/// Identifies a parsed object by its type, and its index among the objects of that
+/// type in document order
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct ObjectHandle {
+    /// as given by [`std::any::type_name()`]
+    pub type_name: &'static str,
+    pub index: usize,
+}
+
+impl ObjectHandle {
+    /// Handle of the `index`-th object of type `T`
+    pub fn of<T>(index: usize) -> Self {
+        Self {
+            type_name: std::any::type_name::<T>(),
+            index,
+        }
+    }
+}
+
+/// Spans recorded by [`record_spans()`], by object type in document order
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct SpanTable {
+    spans: HashMap<&'static str, Vec<Span>>,
+}
+
+impl SpanTable {
+    pub fn get(&self, handle: ObjectHandle) -> Option<Span> {
+        self.spans.get(handle.type_name)?.get(handle.index).copied()
+    }
+
+    /// Spans of every object of type `T`, in document order
+    ///
+    /// The `n`-th span belongs to the `n`-th object of type `T` found in the document
+    /// (eg: the `n`-th [`crate::seqfeat::SeqFeat`] of a record, counting from its start).
+    pub fn spans<T>(&self) -> &[Span] {
+        self.spans
+            .get(std::any::type_name::<T>())
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Every recorded object, along with its span
+    pub fn iter(&self) -> impl Iterator<Item = (ObjectHandle, Span)> + '_ {
+        self.spans.iter().flat_map(|(type_name, spans)| {
+            spans.iter().enumerate().map(|(index, span)| (ObjectHandle { type_name, index }, *span))
+        })
+    }
+
+    /// Total number of recorded objects
+    pub fn len(&self) -> usize {
+        self.spans.values().map(Vec::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+}
+
+/// Type name, start and end of a span awaiting adjustment
+type Recorded = (&'static str, usize, usize);
+
+thread_local! {
+    /// Spans awaiting adjustment, when recording
+    static RECORDED: RefCell<Option<Vec<Recorded>>> = const { RefCell::new(None) };
+}
+
+/// Record the span of an object of type `T` which was parsed from the given buffer
+/// positions, if [`record_spans()`] is in effect
+///
+/// `start` may precede the start tag by whitespace, and `end` may precede the end tag
+/// of objects which return once their variant is parsed. Both are adjusted once
+/// parsing has finished.
+pub(crate) fn record_span<T>(start: usize, end: usize) {
+    RECORDED.with(|recorded| {
+        if let Some(recorded) = recorded.borrow_mut().as_mut() {
+            recorded.push((std::any::type_name::<T>(), start, end));
+        }
+    })
+}
+
+/// Run `f`, which parses `xml`, and record the span of each object it parses
+///
+/// Objects parsed by [`crate::parsing::read_node()`] or [`crate::parsing::read_vec_node()`]
+/// are recorded, which includes every object nested in a record (eg: features,
+/// descriptors, or the sequences of a set) but not the root object itself. Nested
+/// calls are recorded by the outermost call.
+///
+/// When not in effect, objects are parsed as usual, at the cost of a single check each.
+///
+/// # Example
+/// ```
+/// use ncbi::parsing::{parse_node, record_spans, ObjectHandle};
+/// use ncbi::seq::{BioSeq, SeqDesc};
+///
+/// let xml = "<Bioseq><Bioseq_descr><Seq-descr>
+///     <Seqdesc><Seqdesc_title>a</Seqdesc_title></Seqdesc>
+///     <Seqdesc><Seqdesc_title>b</Seqdesc_title></Seqdesc>
+/// </Seq-descr></Bioseq_descr></Bioseq>";
+/// let (bioseq, spans) = record_spans(xml, || parse_node::<BioSeq>(xml));
+///
+/// let span = spans.get(ObjectHandle::of::<SeqDesc>(1)).unwrap();
+/// assert_eq!(span.text(xml), "<Seqdesc><Seqdesc_title>b</Seqdesc_title></Seqdesc>");
+/// assert_eq!(span.location(xml).line, 3);
+/// ```
+pub fn record_spans<T>(xml: &str, f: impl FnOnce() -> T) -> (T, SpanTable) {
+    /// Stops recording, even if `f` panics
+    struct Stop(bool);
+    impl Drop for Stop {
+        fn drop(&mut self) {
+            if self.0 {
+                RECORDED.with(|recorded| recorded.borrow_mut().take());
+            }
+        }
+    }
+
+    let outermost = RECORDED.with(|recorded| {
+        let mut recorded = recorded.borrow_mut();
+        let outermost = recorded.is_none();
+        recorded.get_or_insert_with(Vec::new);
+        outermost
+    });
+    let stop = Stop(outermost);
+    let result = f();
+    if !outermost {
+        return (result, SpanTable::default());
+    }
+
+    let recorded = RECORDED.with(|recorded| recorded.borrow_mut().take()).unwrap_or_default();
+    drop(stop);
+
+    let mut spans: HashMap<&'static str, Vec<Span>> = HashMap::new();
+    for (type_name, start, end) in recorded {
+        spans.entry(type_name).or_default().push(adjust(xml, start, end));
+    }
+    // nested objects of the same type are recorded before the object enclosing them
+    for spans in spans.values_mut() {
+        spans.sort();
+        spans.dedup();
+    }
+    (result, SpanTable { spans })
+}
+
+/// Span starting at the start tag which follows `start`, and including the end tags
+/// following `end` which close elements opened within the span
+fn adjust(xml: &str, start: usize, end: usize) -> Span {
+    let bytes = xml.as_bytes();
+    let end = end.min(xml.len());
+    let start = start.min(end);
+    let start = bytes[start..end]
+        .iter()
+        .position(|byte| !byte.is_ascii_whitespace())
+        .map_or(end, |skipped| start + skipped);
+
+    // elements left open once the parser returned
+    let mut open = 0usize;
+    for tag in bytes[start..end].split(|byte| *byte == b'<').skip(1) {
+        match tag.first() {
+            Some(b'/') => open = open.saturating_sub(1),
+            Some(b'?' | b'!') | None => (),
+            _ if tag.split(|byte| *byte == b'>').next().is_some_and(|tag| tag.ends_with(b"/")) => (),
+            _ => open += 1,
+        }
+    }
+
+    let mut end = end;
+    while open > 0 {
+        let rest = &bytes[end..];
+        let following = rest.len() - rest.trim_ascii_start().len();
+        if !rest[following..].starts_with(b"</") {
+            break;
+        }
+        match rest[following..].iter().position(|byte| *byte == b'>') {
+            Some(close) => end += following + close + 1,
+            None => break,
+        }
+        open -= 1;
+    }
+    Span { start, end }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parsing::{parse_node, record_spans, ObjectHandle};
+    use crate::general::{NameStd, PersonId};
+    use crate::biblio::Author;
+
+    #[test]
+    fn test_spans() {
+        let xml = "<Author>
+            <Author_name>
+                <Person-id>
+                    <Person-id_name>
+                        <Name-std><Name-std_last>Doe</Name-std_last></Name-std>
+                    </Person-id_name>
+                </Person-id>
+            </Author_name>
+        </Author>";
+        let (author, spans) = record_spans(xml, || parse_node::<Author>(xml));
+        assert!(author.is_some());
+
+        // the choice returns before its end tag is read
+        let person = spans.get(ObjectHandle::of::<PersonId>(0)).unwrap();
+        assert!(person.text(xml).starts_with("<Person-id>"));
+        assert!(person.text(xml).ends_with("</Person-id>"));
+        let name = spans.spans::<NameStd>()[0];
+        assert_eq!(name.text(xml), "<Name-std><Name-std_last>Doe</Name-std_last></Name-std>");
+        assert_eq!(name.location(xml).line, 5);
+        assert!(spans.get(ObjectHandle::of::<NameStd>(1)).is_none());
+        assert_eq!(spans.len(), spans.iter().count());
+
+        // nothing is recorded outside of `record_spans()`, or by nested calls
+        let ((_, nested), outer) = record_spans(xml, || record_spans(xml, || parse_node::<Author>(xml)));
+        assert!(nested.is_empty());
+        assert_eq!(outer, spans);
+    }
+}
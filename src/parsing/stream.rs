@@ -0,0 +1,155 @@
+//! Incremental parsing from asynchronous byte streams
+//!
+//! [`NodeStream`] scans an [`AsyncBufRead`] source for elements of a given
+//! [`XmlNode`] type and parses each one as soon as its closing tag arrives. Only the
+//! element currently being received is buffered, so large responses (eg: a
+//! `Bioseq-set` with thousands of `Seq-entry` elements) never need to be held in
+//! memory at once.
+//!
+//! Only the outermost matching elements are yielded; nested elements of the same
+//! type are parsed as part of their parent.
+
+use crate::parsing::XmlNode;
+use quick_xml::events::Event;
+use quick_xml::{Reader, Writer};
+use std::marker::PhantomData;
+use tokio::io::AsyncBufRead;
+
+/// Asynchronously yields each `T` contained by an XML byte stream
+pub struct NodeStream<R, T> {
+    reader: Reader<R>,
+    buf: Vec<u8>,
+    _node: PhantomData<T>,
+}
+
+impl<R: AsyncBufRead + Unpin, T: XmlNode> NodeStream<R, T> {
+    pub fn new(source: R) -> Self {
+        let mut reader = Reader::from_reader(source);
+        reader.trim_text(true);
+        Self {
+            reader,
+            buf: Vec::new(),
+            _node: PhantomData,
+        }
+    }
+
+    /// Parse the next `T` from the stream
+    ///
+    /// Returns `None` once the stream is exhausted.
+    pub async fn next_node(&mut self) -> Option<Result<T, String>> {
+        let name = T::start_bytes();
+
+        // copy of the current element, including its start tag
+        let mut element: Option<Writer<Vec<u8>>> = None;
+        let mut depth = 0;
+
+        loop {
+            self.buf.clear();
+            let event = match self.reader.read_event_into_async(&mut self.buf).await {
+                Ok(Event::Eof) => {
+                    return element.map(|_| Err(format!("Unexpected EOF within <{}>", tag(&name))));
+                }
+                Ok(event) => event,
+                Err(e) => return Some(Err(format!("XML parsing error: {:?}", e))),
+            };
+
+            match (&event, element.as_mut()) {
+                (Event::Start(e), None) if e.name() == name.name() => {
+                    let mut writer = Writer::new(Vec::new());
+                    writer.write_event(event.borrow()).unwrap();
+                    element = Some(writer);
+                    depth = 1;
+                }
+                (Event::Start(e), Some(writer)) => {
+                    if e.name() == name.name() {
+                        depth += 1;
+                    }
+                    writer.write_event(event.borrow()).unwrap();
+                }
+                (Event::End(e), Some(writer)) => {
+                    writer.write_event(event.borrow()).unwrap();
+                    if e.name() == name.name() {
+                        depth -= 1;
+                        if depth == 0 {
+                            let bytes = element.take().unwrap().into_inner();
+                            return Some(parse_element(&bytes));
+                        }
+                    }
+                }
+                (_, Some(writer)) => writer.write_event(event.borrow()).unwrap(),
+                _ => (),
+            }
+        }
+    }
+
+    /// Collect all remaining `T` from the stream
+    pub async fn collect(mut self) -> Result<Vec<T>, String> {
+        let mut nodes = Vec::new();
+        while let Some(node) = self.next_node().await {
+            nodes.push(node?);
+        }
+        Ok(nodes)
+    }
+}
+
+fn tag(start: &quick_xml::events::BytesStart) -> String {
+    String::from_utf8_lossy(start.name().as_ref()).to_string()
+}
+
+/// Parse a single buffered element, positioning the reader as [`crate::parse_xml`] does
+fn parse_element<T: XmlNode>(bytes: &[u8]) -> Result<T, String> {
+    let mut reader = Reader::from_reader(bytes);
+    reader.trim_text(true);
+
+    // consume start tag
+    reader
+        .read_event()
+        .map_err(|e| format!("XML parsing error: {:?}", e))?;
+
+    T::from_reader(&mut reader).ok_or(format!("Failed to parse <{}>", tag(&T::start_bytes())))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entrezgene::Entrezgene;
+    use crate::parsing::NodeStream;
+    use crate::seqset::SeqEntry;
+    use std::fs;
+
+    fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    #[test]
+    fn test_stream_seq_entries() {
+        let data = fs::read("tests/data/popset.xml").unwrap();
+
+        // a small buffer forces elements to span many reads
+        let source = tokio::io::BufReader::with_capacity(16, data.as_slice());
+        let entries = block_on(NodeStream::<_, SeqEntry>::new(source).collect()).unwrap();
+
+        // only top-level entries are yielded
+        assert_eq!(entries.len(), 3);
+        assert!(entries.iter().all(|entry| matches!(entry, SeqEntry::Seq(_))));
+    }
+
+    #[test]
+    fn test_stream_entrezgene() {
+        let data = fs::read("tests/data/tp73.genbank.xml").unwrap();
+
+        let mut stream = NodeStream::<_, Entrezgene>::new(data.as_slice());
+        let gene = block_on(stream.next_node()).unwrap().unwrap();
+        assert_eq!(gene.gene.locus.as_deref(), Some("TP73"));
+        assert!(block_on(stream.next_node()).is_none());
+    }
+
+    #[test]
+    fn test_stream_truncated() {
+        let data = "<Bioseq-set><Bioseq-set_seq-set><Seq-entry><Seq-entry_seq>";
+        let mut stream = NodeStream::<_, SeqEntry>::new(data.as_bytes());
+        assert!(block_on(stream.next_node()).unwrap().is_err());
+    }
+}
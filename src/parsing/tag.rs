@@ -0,0 +1,97 @@
+use quick_xml::name::QName;
+use std::borrow::Cow;
+
+/// Matching of element names which may be given with or without the DTD prefix
+///
+/// NCBI's DTD renders struct fields and choice variants as `Type_field`
+/// (eg: `Seq-feat_data`), while ASN.1-style renderings from other tools only give
+/// the bare field name (eg: `data`). Comparisons made through this trait accept both.
+pub trait TagMatch {
+    /// Whether `self` names the same element as the prefixed name `tag`
+    fn is_tag(&self, tag: QName) -> bool;
+}
+
+impl TagMatch for QName<'_> {
+    fn is_tag(&self, tag: QName) -> bool {
+        tag_matches(self.as_ref(), tag.as_ref())
+    }
+}
+
+/// Whether `name` is a bare ASN.1 field name rather than a type or prefixed name
+///
+/// Type names are capitalized, and prefixed names contain an underscore.
+fn is_bare(name: &[u8]) -> bool {
+    name.first().is_some_and(u8::is_ascii_lowercase) && !name.contains(&b'_')
+}
+
+/// Compare an element name against a DTD-prefixed name, accepting the bare form
+pub fn tag_matches(name: &[u8], tag: &[u8]) -> bool {
+    if name == tag {
+        return true;
+    }
+    if !is_bare(name) {
+        return false;
+    }
+    match tag.iter().position(|b| *b == b'_') {
+        Some(i) => &tag[i + 1..] == name,
+        None => false,
+    }
+}
+
+/// Canonical DTD-prefixed form of an element name found within `parent`
+///
+/// Used when matching against byte literals; bare field names are prefixed by the
+/// name of the enclosing type, while any other name is returned as is.
+pub fn qualified_name<'a>(name: QName<'a>, parent: QName) -> Cow<'a, [u8]> {
+    let name = name.into_inner();
+    if is_bare(name) {
+        let mut qualified = parent.as_ref().to_vec();
+        qualified.push(b'_');
+        qualified.extend_from_slice(name);
+        Cow::Owned(qualified)
+    } else {
+        Cow::Borrowed(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cdd::CddId;
+    use crate::parsing::{parse_node, qualified_name, tag_matches, TagMatch};
+    use crate::seqloc::TextseqId;
+    use quick_xml::name::QName;
+
+    #[test]
+    fn test_tag_matches() {
+        assert!(tag_matches(b"Seq-feat_data", b"Seq-feat_data"));
+        assert!(tag_matches(b"data", b"Seq-feat_data"));
+        assert!(!tag_matches(b"location", b"Seq-feat_data"));
+        assert!(!tag_matches(b"Seq-feat", b"Seq-feat_data"));
+        assert!(!tag_matches(b"Other_data", b"Seq-feat_data"));
+        assert!(QName(b"id").is_tag(QName(b"Seq-feat_id")));
+    }
+
+    #[test]
+    fn test_qualified_name() {
+        let parent = QName(b"Entrezgene");
+        assert_eq!(qualified_name(QName(b"type"), parent).as_ref(), b"Entrezgene_type");
+        assert_eq!(qualified_name(QName(b"Entrezgene_type"), parent).as_ref(), b"Entrezgene_type");
+        assert_eq!(qualified_name(QName(b"Gene-ref"), parent).as_ref(), b"Gene-ref");
+    }
+
+    #[test]
+    fn test_bare_names() {
+        let bare = "<Textseq-id><accession>NM_000546</accession><version>6</version></Textseq-id>";
+        let prefixed = "<Textseq-id>\
+            <Textseq-id_accession>NM_000546</Textseq-id_accession>\
+            <Textseq-id_version>6</Textseq-id_version>\
+            </Textseq-id>";
+        let id = parse_node::<TextseqId>(bare).unwrap();
+        assert_eq!(id.accession.as_deref(), Some("NM_000546"));
+        assert_eq!(id.version, Some(6));
+        assert_eq!(Some(id), parse_node::<TextseqId>(prefixed));
+
+        let id = parse_node::<CddId>("<Cdd-id><uid>238121</uid></Cdd-id>");
+        assert_eq!(id, Some(CddId::Uid(238121)));
+    }
+}
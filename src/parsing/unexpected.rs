@@ -1,13 +1,18 @@
 use quick_xml::events::BytesStart;
 use quick_xml::name::QName;
+use crate::parsing::{report, TagMatch};
 
 /// Watchdog that guarantees all tags are being parsed.
 ///
 /// If a particular tag is known about, but not yet implemented, it should be added to
-/// the internal container. When a tag is encountered that is known, a warning will be
-/// printed to stderr, however, when a tag that is not known is encountered, the program
-/// panics. The intention is to not overlook any tag elements given by the eutils. The
-/// internal store of unimplemented tags is a method of accountability.
+/// the internal container. When a tag is encountered that is known, a warning is
+/// logged along with its [`crate::parsing::Location`]. A tag that is not known is also reported, unless the `strict`
+/// feature is enabled, in which case the program panics. The intention is to not
+/// overlook any tag elements given by the eutils. The internal store of unimplemented
+/// tags is a method of accountability.
+///
+/// Parsing never panics by default, since input may be malformed or come from an
+/// untrusted source.
 ///
 /// Internal tags typically object fields and enum variants.
 pub struct UnexpectedTags<'a>(pub &'a [BytesStart<'a>]);
@@ -17,13 +22,17 @@ impl UnexpectedTags<'_> {
     pub fn check(&self, current: &QName) {
         let mut expected = false;
         for tag in self.0.iter() {
-            if *current == tag.name() {
+            if current.is_tag(tag.name()) {
                 expected = true;
-                eprintln!("Encountered XML tag {}, which has not been implemented yet...", tag.escape_ascii().to_string())
+                report(format!("encountered {}, which has not been implemented yet", tag.escape_ascii()))
             }
         }
         if !expected {
-            panic!("Encountered {}, which has not been implemented yet...", current.0.escape_ascii().to_string());
+            let message = format!("Encountered {}, which is not known to the parser", current.0.escape_ascii());
+            if cfg!(feature = "strict") {
+                panic!("{}", message);
+            }
+            report(message);
         }
     }
 }
\ No newline at end of file
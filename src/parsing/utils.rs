@@ -1,9 +1,12 @@
 use quick_xml::Reader;
 use quick_xml::events::{BytesEnd, BytesStart, Event};
 use atoi::FromRadix10SignedChecked;
+use std::borrow::Cow;
 use std::ops::Deref;
+use std::str::FromStr;
 use quick_xml::events::attributes::Attributes;
-use crate::parsing::{XmlNode, XmlValue, XmlVecNode};
+use crate::parsing::options::skip_unselected;
+use crate::parsing::{mark, record_span, report_at, with_locations, ParseError, XmlNode, XmlNodeRef, XmlValue, XmlVecNode};
 
 /// [`Reader`] that returns bytes
 ///
@@ -22,6 +25,26 @@ where
     atoi::atoi::<T>(text.as_ref()).expect("Conversion error")
 }
 
+/// Parse text as a number
+///
+/// Surrounding whitespace is ignored.
+///
+/// # Returns
+/// `Ok(None)` if `text` is empty or only whitespace, otherwise the parsed value.
+/// Text that is not a valid number returns [`ParseError::InvalidNumber`].
+pub fn parse_num<T: FromStr>(text: &str) -> Result<Option<T>, ParseError> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(None);
+    }
+    text.parse::<T>()
+        .map(Some)
+        .map_err(|_| ParseError::InvalidNumber {
+            text: text.to_string(),
+            target: std::any::type_name::<T>(),
+        })
+}
+
 /// Parse the given bytes into a [`String`]
 pub fn bytes_to_string(text: &[u8]) -> String {
     text.escape_ascii().to_string()
@@ -44,13 +67,19 @@ pub fn read_bool_attribute(current: &BytesStart) -> Option<bool> {
     }
 }
 
+/// Return the contents of the "value" attribute
+///
+/// Quotes escaped by [`crate::get_local_xml()`] (ie: `\"`) are removed.
 pub fn attribute_value(attributes: Attributes) -> Option<String> {
     let value = BytesStart::new("value");
     for attribute in attributes {
         if let Ok(attr) = attribute {
             if attr.key == value.name() {
-                let _inner = attr.unescape_value().unwrap().to_string();
-                let inner = _inner.get(2.._inner.len() - 2).unwrap();
+                let inner = attr.unescape_value().ok()?.to_string();
+                let inner = inner
+                    .strip_prefix("\\\"")
+                    .and_then(|inner| inner.strip_suffix("\\\""))
+                    .unwrap_or(inner.as_str());
                 return Some(inner.to_string())
             }
         }
@@ -58,44 +87,208 @@ pub fn attribute_value(attributes: Attributes) -> Option<String> {
     return None
 }
 
+/// Parse the "value" attribute of `current` as a number
+///
+/// Used for elements where a numeric value is given as `<TagName value="{value}"/>`
+pub fn read_int_attribute<T: FromStr>(current: &BytesStart) -> Result<Option<T>, ParseError> {
+    match attribute_value(current.html_attributes()) {
+        Some(value) => parse_num(value.as_str()),
+        None => Ok(None),
+    }
+}
+
+/// Parses the next [`Event::Text`] as a number
+///
+/// # Returns
+/// `Ok(None)` when element is empty, or [`ParseError`] if the text is not numeric
+pub fn try_read_int<T: FromStr>(reader: &mut XmlReader) -> Result<Option<T>, ParseError> {
+    match reader.read_event()? {
+        Event::Text(text) => parse_num(bytes_to_string(text.deref()).as_str()),
+        _ => Ok(None),
+    }
+}
+
 /// Parses the next [`Event::Text`] as an integer
-pub fn read_int<T>(reader: &mut XmlReader) -> Option<T>
-where
-    T: FromRadix10SignedChecked,
-{
-    if let Event::Text(text) = reader.read_event().unwrap() {
-        Some(bytes_to_int(text.deref()))
-    } else {
+///
+/// Whitespace is trimmed and empty elements return `None`. Invalid values are
+/// logged as warnings and return `None`; use [`try_read_int()`] to handle the error.
+pub fn read_int<T: FromStr>(reader: &mut XmlReader) -> Option<T> {
+    try_read_int(reader).unwrap_or_else(|e| {
+        report_at(reader.buffer_position(), e.to_string());
         None
+    })
+}
+
+/// Parses the next [`Event::Text`] as a boolean given as "true" or "false"
+///
+/// Used for the rare elements that enclose a boolean as text rather than giving it
+/// as a "value" attribute (see [`read_bool_attribute()`]).
+pub fn read_bool(reader: &mut XmlReader) -> Option<bool> {
+    match read_string(reader)?.trim() {
+        "true" => Some(true),
+        "false" => Some(false),
+        _ => None,
     }
 }
 
+/// Parses the next [`Event::Text`] as a real number, retaining the original text
+///
+/// Returns `None` if the text is empty or is not a valid real number.
 pub fn read_real(reader: &mut XmlReader) -> Option<String> {
-    if let Event::Text(text) = reader.read_event().unwrap() {
-        bytes_to_string(text.deref()).into()
+    if let Event::Text(text) = reader.read_event().ok()? {
+        let text = bytes_to_string(text.deref()).trim().to_string();
+        match parse_num::<f64>(text.as_str()) {
+            Ok(Some(_)) => Some(text),
+            Ok(None) => None,
+            Err(e) => {
+                report_at(reader.buffer_position(), e.to_string());
+                None
+            }
+        }
     } else {
         None
     }
 }
 
-/// Parses the next available [`Event::Text`] data as an integer
+/// Read the next [`Event`], treating malformed XML as the end of input
+///
+/// Returns `None` on [`Event::Eof`] or a syntax error, so that parsing loops written
+/// as `match next_event(reader)? { .. }` terminate on truncated or malformed input
+/// instead of panicking or looping forever.
+///
+/// Elements excluded by the projection of the current [`crate::parsing::ParseOptions`]
+/// are skipped.
+pub fn next_event<'a>(reader: &mut XmlReader<'a>) -> Option<Event<'a>> {
+    loop {
+        let position = reader.buffer_position();
+        mark(position);
+        match reader.read_event() {
+            Ok(Event::Eof) | Err(_) => return None,
+            Ok(Event::Start(e)) if skip_unselected(reader, &e, position) => continue,
+            Ok(event) => return Some(event),
+        }
+    }
+}
+
+/// Read the rest of the element opened by `start`, returning it as given in the input
+///
+/// The returned text includes the start and end tags, so that elements which are not
+/// parsed may be written back unchanged. Returns `None` if the element is not closed.
+pub fn read_raw(reader: &mut XmlReader, start: &BytesStart) -> Option<String> {
+    let rest: &[u8] = reader.get_ref();
+    reader.read_to_end(start.name()).ok()?;
+    let consumed = rest.len() - reader.get_ref().len();
+
+    let mut raw = Vec::with_capacity(consumed + start.len() + 2);
+    raw.push(b'<');
+    raw.extend_from_slice(start);
+    raw.push(b'>');
+    raw.extend_from_slice(&rest[..consumed]);
+    String::from_utf8(raw).ok()
+}
+
+/// Parses the next available [`Event::Text`] data as a [`String`], resolving entities
+/// such as `&amp;`
 pub fn read_string(reader: &mut XmlReader) -> Option<String> {
-    if let Event::Text(text) = reader.read_event().unwrap() {
-        bytes_to_string(text.deref()).into()
+    if let Event::Text(text) = next_event(reader)? {
+        text.unescape().ok()?.into_owned().into()
+    } else {
+        None
+    }
+}
+
+/// Borrow the next available [`Event::Text`] data from the input buffer
+///
+/// Unlike [`read_string()`], the text is not copied and non-ASCII characters are not
+/// escaped. Returns `None` if the text is not valid UTF-8.
+pub fn read_str<'a>(reader: &mut XmlReader<'a>) -> Option<&'a str> {
+    if let Event::Text(text) = next_event(reader)? {
+        match text.into_inner() {
+            Cow::Borrowed(bytes) => std::str::from_utf8(bytes).ok(),
+            // only produced by readers which do not borrow from a buffer
+            Cow::Owned(_) => None,
+        }
     } else {
         None
     }
 }
 
+/// Parses the next available XML data as a [`XmlNodeRef`]
+pub fn read_node_borrowed<'a, T: XmlNodeRef<'a>>(reader: &mut XmlReader<'a>) -> Option<T> {
+    let start = reader.buffer_position();
+    let result = T::from_reader_borrowed(reader);
+    if result.is_none() {
+        report_at(start, format!("failed to parse {}", std::any::type_name::<T>()));
+    }
+    result
+}
+
+/// Borrowing counterpart of [`parse_node()`]
+pub fn parse_node_borrowed<'a, T: XmlNodeRef<'a>>(xml: &'a str) -> Option<T> {
+    let mut reader = Reader::from_str(xml);
+    reader.trim_text(true);
+
+    let start = T::Owned::start_bytes();
+    loop {
+        match reader.read_event().ok()? {
+            Event::Start(e) if e.name() == start.name() => return T::from_reader_borrowed(&mut reader),
+            Event::Eof => return None,
+            _ => (),
+        }
+    }
+}
+
+/// Collect every `T` element of `xml`, regardless of where it is nested
+///
+/// Elements which cannot be parsed are skipped. Intended for scanning large documents
+/// for a few fields without building the complete records.
+pub fn scan_nodes_borrowed<'a, T: XmlNodeRef<'a>>(xml: &'a str) -> Vec<T> {
+    let mut reader = Reader::from_str(xml);
+    reader.trim_text(true);
+
+    let start = T::Owned::start_bytes();
+    let mut items = Vec::new();
+    while let Some(event) = next_event(&mut reader) {
+        if let Event::Start(e) = event {
+            if e.name() == start.name() {
+                items.extend(T::from_reader_borrowed(&mut reader));
+            }
+        }
+    }
+    items
+}
+
 /// Parses the next available XML data as a [`XmlNode`]
 pub fn read_node<T: XmlNode>(reader: &mut XmlReader) -> Option<T> {
+    let start = reader.buffer_position();
     let result=T::from_reader(reader);
-    if result.is_none() {
-        println!("Failed to parse node for {}", std::any::type_name::<T>());
+    match result {
+        Some(_) => record_span::<T>(start, reader.buffer_position()),
+        None => report_at(start, format!("failed to parse {}", std::any::type_name::<T>())),
     }
     result
 }
 
+/// Parse the first `T` element found in `xml`
+///
+/// Any content preceding the start tag of `T` is skipped. Returns `None` when no such
+/// element exists, or when it cannot be parsed.
+pub fn parse_node<T: XmlNode>(xml: &str) -> Option<T> {
+    with_locations(xml, || {
+        let mut reader = Reader::from_str(xml);
+        reader.trim_text(true);
+
+        let start = T::start_bytes();
+        loop {
+            match reader.read_event().ok()? {
+                Event::Start(e) if e.name() == start.name() => return T::from_reader(&mut reader),
+                Event::Eof => return None,
+                _ => (),
+            }
+        }
+    })
+}
+
 /// Parse each [`BytesText`] within the enclosed element as a [`String`]
 ///
 /// # Parameters
@@ -106,8 +299,8 @@ pub fn read_node<T: XmlNode>(reader: &mut XmlReader) -> Option<T> {
 /// [`String`] objects contained by `end`
 pub fn read_vec_str_unchecked(reader: &mut XmlReader, end: &BytesEnd) -> Vec<String> {
     let mut items = Vec::new();
-    loop {
-        match reader.read_event().unwrap() {
+    while let Some(event) = next_event(reader) {
+        match event {
             Event::Text(text) => {
                 // remove whitespace
                 let text = bytes_to_string(text.deref()).trim().to_string();
@@ -124,6 +317,7 @@ pub fn read_vec_str_unchecked(reader: &mut XmlReader, end: &BytesEnd) -> Vec<Str
             _ => (),
         }
     }
+    items
 }
 
 /// Parse each [`BytesText`] within the enclosed element as an integer
@@ -134,25 +328,56 @@ pub fn read_vec_str_unchecked(reader: &mut XmlReader, end: &BytesEnd) -> Vec<Str
 ///
 /// # Returns
 /// Integers contained by `end`
+///
+/// Values that cannot be parsed are logged as warnings and skipped; use
+/// [`try_read_vec_int()`] to handle the error.
 pub fn read_vec_int_unchecked<T>(reader: &mut Reader<&[u8]>, end: &BytesEnd) -> Vec<T>
 where
-    T: FromRadix10SignedChecked,
+    T: FromStr,
 {
     let mut nums = Vec::new();
     loop {
-        match reader.read_event().unwrap() {
-            Event::Text(text) => {
+        match reader.read_event() {
+            Ok(Event::Text(text)) => {
                 let string = text.deref().escape_ascii().to_string();
-                let string = string.trim();
-                if is_alphanum(string) {
-                    nums.push(bytes_to_int(string.as_bytes()))
+                if !is_alphanum(string.trim()) {
+                    continue;
+                }
+                match parse_num(string.as_str()) {
+                    Ok(Some(num)) => nums.push(num),
+                    Ok(None) => (),
+                    Err(e) => report_at(reader.buffer_position(), e.to_string()),
                 }
             },
-            Event::End(e) => {
-                if e.name() == end.name() {
-                    return nums;
+            Ok(Event::End(e)) if e.name() == end.name() => return nums,
+            Ok(Event::Eof) | Err(_) => return nums,
+            _ => (),
+        }
+    }
+}
+
+/// Parse each [`BytesText`] within the enclosed element as an integer
+///
+/// # Returns
+/// Integers contained by `end`, or the first [`ParseError`] encountered
+pub fn try_read_vec_int<T>(reader: &mut Reader<&[u8]>, end: &BytesEnd) -> Result<Vec<T>, ParseError>
+where
+    T: FromStr,
+{
+    let mut nums = Vec::new();
+    loop {
+        match reader.read_event()? {
+            Event::Text(text) => {
+                let string = text.deref().escape_ascii().to_string();
+                if !is_alphanum(string.trim()) {
+                    continue;
                 }
-            }
+                if let Some(num) = parse_num(string.as_str())? {
+                    nums.push(num);
+                }
+            },
+            Event::End(e) if e.name() == end.name() => return Ok(nums),
+            Event::Eof => return Ok(nums),
             _ => (),
         }
     }
@@ -177,3 +402,115 @@ fn is_alphanum(text: &str) -> bool {
     // do not add empty or escape codes
     !(text == "\\\\n" || text.is_empty())
 }
+
+#[cfg(test)]
+mod tests {
+    use quick_xml::events::{BytesStart, Event};
+    use quick_xml::Reader;
+    use crate::parsing::{next_event, parse_node, parse_num, read_int, read_int_attribute, read_raw, read_real, try_read_int, try_read_vec_int, ParseError};
+
+    #[test]
+    fn test_parse_num() {
+        assert_eq!(parse_num::<u64>(" 42\n"), Ok(Some(42)));
+        assert_eq!(parse_num::<i64>("-1"), Ok(Some(-1)));
+        assert_eq!(parse_num::<u64>("  "), Ok(None));
+        assert!(matches!(parse_num::<u64>("12abc"), Err(ParseError::InvalidNumber { .. })));
+        assert!(parse_num::<u8>("256").is_err());
+    }
+
+    #[test]
+    fn test_read_int() {
+        let mut reader = Reader::from_str("<a> 12 </a>");
+        reader.read_event().unwrap();
+        assert_eq!(read_int::<u64>(&mut reader), Some(12));
+
+        let mut reader = Reader::from_str("<a></a>");
+        reader.read_event().unwrap();
+        assert_eq!(try_read_int::<u64>(&mut reader), Ok(None));
+
+        let mut reader = Reader::from_str("<a>twelve</a>");
+        reader.read_event().unwrap();
+        assert!(try_read_int::<u64>(&mut reader).is_err());
+
+        let mut reader = Reader::from_str("<a>twelve</a>");
+        reader.read_event().unwrap();
+        assert_eq!(read_int::<u64>(&mut reader), None);
+    }
+
+    #[test]
+    fn test_read_raw() {
+        let xml = "<a><b x=\"1\">\n  <c>text &amp; more</c>\n  <d/>\n</b><e/></a>";
+        let mut reader = Reader::from_str(xml);
+        reader.trim_text(true);
+        reader.read_event().unwrap();
+        let start = match reader.read_event().unwrap() {
+            Event::Start(e) => e.into_owned(),
+            event => panic!("unexpected event {:?}", event),
+        };
+        assert_eq!(
+            read_raw(&mut reader, &start).as_deref(),
+            Some("<b x=\"1\">\n  <c>text &amp; more</c>\n  <d/>\n</b>")
+        );
+        assert!(matches!(reader.read_event().unwrap(), Event::Empty(e) if e.name().as_ref() == b"e"));
+
+        let mut reader = Reader::from_str("<b><c></c>");
+        reader.read_event().unwrap();
+        assert_eq!(read_raw(&mut reader, &BytesStart::new("b")), None);
+    }
+
+    #[test]
+    fn test_read_real() {
+        let mut reader = Reader::from_str("<a> 1e-5 </a>");
+        reader.read_event().unwrap();
+        assert_eq!(read_real(&mut reader), Some("1e-5".to_string()));
+
+        let mut reader = Reader::from_str("<a>NA</a>");
+        reader.read_event().unwrap();
+        assert_eq!(read_real(&mut reader), None);
+    }
+
+    #[test]
+    fn test_read_int_attribute() {
+        let mut reader = Reader::from_str("<a value=\"7\"/><b value=\"x\"/><c/>");
+        let mut tags: Vec<BytesStart> = Vec::new();
+        while let Ok(Event::Empty(e)) = reader.read_event() {
+            tags.push(e.into_owned());
+        }
+        assert_eq!(read_int_attribute::<u8>(&tags[0]), Ok(Some(7)));
+        assert!(read_int_attribute::<u8>(&tags[1]).is_err());
+        assert_eq!(read_int_attribute::<u8>(&tags[2]), Ok(None));
+    }
+
+    #[test]
+    fn test_try_read_vec_int() {
+        let xml = "<v><v_E>1</v_E><v_E> -1 </v_E><v_E>x</v_E></v>";
+        let mut reader = Reader::from_str(xml);
+        reader.read_event().unwrap();
+        let end = BytesStart::new("v");
+        assert!(try_read_vec_int::<i64>(&mut reader, &end.to_end()).is_err());
+
+        let xml = "<v><v_E>1</v_E><v_E> -1 </v_E></v>";
+        let mut reader = Reader::from_str(xml);
+        reader.read_event().unwrap();
+        assert_eq!(try_read_vec_int::<i64>(&mut reader, &end.to_end()), Ok(vec![1, -1]));
+    }
+
+    #[test]
+    fn test_next_event_stops_on_malformed() {
+        let mut reader = Reader::from_str("<a><b></a>");
+        assert!(matches!(next_event(&mut reader), Some(Event::Start(_))));
+        assert!(matches!(next_event(&mut reader), Some(Event::Start(_))));
+        assert!(next_event(&mut reader).is_none());
+    }
+
+    #[test]
+    fn test_parse_node_truncated() {
+        use crate::general::DbTag;
+
+        let xml = "<Seq-id><Dbtag><Dbtag_db>taxon</Dbtag_db><Dbtag_tag><Object-id><Object-id_id>9606";
+        assert!(parse_node::<DbTag>(xml).is_none());
+
+        let xml = "<Dbtag><Dbtag_db>taxon</Dbtag_db><Dbtag_tag><Object-id><Object-id_id>9606</Object-id_id></Object-id></Dbtag_tag></Dbtag>";
+        assert_eq!(parse_node::<DbTag>(xml).unwrap().db, "taxon");
+    }
+}
@@ -0,0 +1,135 @@
+//! High-level workflow for retrieving a gene with its genomic and transcript sequences
+//!
+//! [`GenePipeline`] chains the individual E-utilities:
+//!
+//! 1. ESearch on [`EntrezDb::Gene`] for the gene symbol within an organism
+//! 2. EFetch of the [`Entrezgene`] record
+//! 3. EFetch of only the annotated region of the genomic RefSeq, using the
+//!    coordinates given by [`Entrezgene::genomic_locus()`]
+//! 4. EFetch of all transcripts given by [`Entrezgene::transcript_accessions()`]
+
+use crate::entrezgene::Entrezgene;
+use crate::seq::BioSeq;
+use crate::seqloc::NaStrand;
+use crate::{fetch_search, DataType, EntrezDb, FetchRequest, FetchStrand};
+
+/// Retrieves an [`Entrezgene`] and associated sequences by gene symbol
+///
+/// # Example
+/// ```ignore
+/// let bundle = GenePipeline::new("TP73", "Homo sapiens").flank(500).run()?;
+/// let genomic = bundle.genomic.unwrap();
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct GenePipeline {
+    pub symbol: String,
+    pub organism: String,
+    /// fetch transcript sequences
+    pub transcripts: bool,
+    /// bases to include on either side of the gene
+    pub flank: u64,
+}
+
+/// Records retrieved by [`GenePipeline::run()`]
+#[derive(Clone, PartialEq, Debug)]
+pub struct GeneBundle {
+    pub gene: Entrezgene,
+    /// genomic region containing the gene
+    ///
+    /// Coordinates of this [`BioSeq`] are relative to the fetched region, not the
+    /// full chromosome. Genes on the minus strand are returned reverse complemented.
+    pub genomic: Option<BioSeq>,
+    pub transcripts: Vec<BioSeq>,
+}
+
+impl GenePipeline {
+    pub fn new(symbol: &str, organism: &str) -> Self {
+        Self {
+            symbol: symbol.to_string(),
+            organism: organism.to_string(),
+            transcripts: true,
+            flank: 0,
+        }
+    }
+
+    pub fn transcripts(mut self, transcripts: bool) -> Self {
+        self.transcripts = transcripts;
+        self
+    }
+
+    pub fn flank(mut self, flank: u64) -> Self {
+        self.flank = flank;
+        self
+    }
+
+    /// ESearch term for this gene
+    pub fn search_term(&self) -> String {
+        format!("{}[sym] AND {}[orgn]", self.symbol, self.organism)
+    }
+
+    /// EFetch request for the genomic region of `gene`
+    pub fn genomic_request(&self, gene: &Entrezgene) -> Option<FetchRequest> {
+        let locus = gene.genomic_locus()?;
+        let accession = locus.versioned_accession()?;
+        let interval = locus.locus_interval()?;
+
+        let range = interval.range0()?.extend(self.flank);
+        let strand = match interval.strand {
+            Some(NaStrand::Minus) => FetchStrand::Minus,
+            _ => FetchStrand::Plus,
+        };
+        Some(
+            FetchRequest::new(EntrezDb::Nucleotide, &accession)
+                .rettype("native")
+                .retmode("xml")
+                .range(range)
+                .strand(strand),
+        )
+    }
+
+    /// Run all requests
+    pub fn run(&self) -> Result<GeneBundle, String> {
+        let search = fetch_search(EntrezDb::Gene, &self.search_term())?;
+        let id = match search.ids.as_slice() {
+            [] => return Err(format!("No gene found for \"{}\"", self.search_term())),
+            [id] => id,
+            ids => {
+                log::warn!("{} genes found for \"{}\", using first", ids.len(), self.search_term());
+                &ids[0]
+            }
+        };
+
+        let gene = match FetchRequest::new(EntrezDb::Gene, id).retmode("xml").fetch()? {
+            DataType::EntrezgeneSet(mut genes) if !genes.is_empty() => genes.remove(0),
+            _ => return Err(format!("Gene {} did not return an Entrezgene record", id)),
+        };
+
+        let genomic = match self.genomic_request(&gene) {
+            Some(request) => fetch_bioseqs(&request)?.into_iter().next(),
+            None => None,
+        };
+
+        let accessions = gene.transcript_accessions();
+        let transcripts = if self.transcripts && !accessions.is_empty() {
+            let request = FetchRequest::with_ids(EntrezDb::Nucleotide, &accessions)
+                .rettype("native")
+                .retmode("xml");
+            fetch_bioseqs(&request)?
+        } else {
+            Vec::new()
+        };
+
+        Ok(GeneBundle {
+            gene,
+            genomic,
+            transcripts,
+        })
+    }
+}
+
+fn fetch_bioseqs(request: &FetchRequest) -> Result<Vec<BioSeq>, String> {
+    match request.fetch()? {
+        DataType::BioSeqSet(set) => Ok(set.bioseqs().into_iter().cloned().collect()),
+        _ => Err(format!("Request for {} did not return a Bioseq-set", request.id)),
+    }
+}
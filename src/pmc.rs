@@ -0,0 +1,440 @@
+//! PubMed Central articles
+//!
+//! Full text articles are distributed as JATS XML, either by EFetch on
+//! [`EntrezDb::PubMedCentral`] (as a `pmc-articleset`), or in bulk by the PMC Open
+//! Access subset as `.tar.gz` packages of one file per article. Both are parsed into
+//! [`Article`] by [`parse_articles()`], and [`OaPackage`] iterates the articles of a
+//! bulk package without extracting it.
+//!
+//! Only the metadata and the text of the article are kept: tables, figures and
+//! references are skipped, and inline markup (eg: `<italic>`) is reduced to its text.
+//!
+//! Citations returned by EFetch on [`EntrezDb::PubMed`] (as a `PubmedArticleSet`) are
+//! parsed into the same [`Article`] by [`parse_pubmed_articles()`], without a body.
+//!
+//! # Example
+//! ```ignore
+//! let mut package = OaPackage::open("oa_comm_xml.PMC000xxxxxx.baseline.tar.gz")?
+//!     .with_pmcids(&["PMC176545", "PMC176546"]);
+//! for article in package.articles()? {
+//!     let article = article?;
+//!     println!("{}: {:?}", article.pmcid.unwrap_or_default(), article.title);
+//! }
+//! ```
+//!
+//! [`EntrezDb::PubMedCentral`]: crate::EntrezDb::PubMedCentral
+//! [`EntrezDb::PubMed`]: crate::EntrezDb::PubMed
+
+use flate2::read::GzDecoder;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+use tar::Archive;
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+/// A JATS article
+pub struct Article {
+    /// eg: "research-article", "review-article"
+    pub article_type: Option<String>,
+    /// eg: "PMC176545"
+    pub pmcid: Option<String>,
+    pub pmid: Option<u64>,
+    pub doi: Option<String>,
+    pub journal: Option<String>,
+    pub title: Option<String>,
+    /// authors and other contributors, such as editors
+    pub contributors: Vec<Contributor>,
+    /// year of the first publication date given
+    pub pub_year: Option<u16>,
+    pub r#abstract: Option<String>,
+    pub keywords: Vec<String>,
+    /// sections of the body, in document order
+    pub sections: Vec<Section>,
+}
+
+impl Article {
+    /// Contributors which are authors
+    pub fn authors(&self) -> impl Iterator<Item = &Contributor> {
+        self.contributors
+            .iter()
+            .filter(|contributor| contributor.role.as_deref().is_none_or(|role| role == "author"))
+    }
+
+    /// Full text of the body, with paragraphs separated by blank lines
+    pub fn body_text(&self) -> String {
+        self.sections
+            .iter()
+            .flat_map(|section| section.paragraphs.iter())
+            .map(|p| p.as_str())
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+pub struct Contributor {
+    /// eg: "author", "editor"
+    pub role: Option<String>,
+    pub surname: Option<String>,
+    pub given_names: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+/// A section of the body
+///
+/// Nested sections follow their parent, with a greater [`Self::level`]. Paragraphs
+/// which precede the first section belong to a section without a title.
+pub struct Section {
+    /// 1 for top level sections
+    pub level: usize,
+    pub title: Option<String>,
+    pub paragraphs: Vec<String>,
+}
+
+/// Normalize an id given as "PMC176545" or "176545"
+fn normalize_pmcid(id: &str) -> String {
+    let id = id.trim();
+    if id.starts_with("PMC") {
+        id.to_string()
+    } else {
+        format!("PMC{}", id)
+    }
+}
+
+/// Element whose text is being collected
+enum Capture {
+    JournalTitle,
+    ArticleId(Option<String>),
+    Title,
+    Surname,
+    GivenNames,
+    Year,
+    Abstract,
+    /// part of a structured abstract, appended to the preceding parts
+    AbstractText,
+    ArticleType,
+    Keyword,
+    SectionTitle,
+    Paragraph,
+}
+
+fn attribute(e: &BytesStart, key: &str) -> Option<String> {
+    e.try_get_attribute(key)
+        .ok()
+        .flatten()
+        .and_then(|attr| attr.unescape_value().ok())
+        .map(|value| value.to_string())
+}
+
+/// Elements whose text is separated from the preceding text, unlike inline markup
+const BLOCK_ELEMENTS: &[&[u8]] = &[b"p", b"sec", b"title", b"label", b"list-item"];
+
+/// Collapse runs of whitespace left by formatting and inline markup
+fn normalize_text(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Parse every `<article>` of a JATS document, such as a single article of a bulk
+/// package, or the `pmc-articleset` returned by EFetch
+pub fn parse_articles(xml: &str) -> Result<Vec<Article>, String> {
+    let new = |e: &BytesStart| Article {
+        article_type: attribute(e, "article-type"),
+        ..Article::default()
+    };
+    parse_documents(xml, b"article", new, start_element)
+}
+
+/// Parse every `<PubmedArticle>` of the `PubmedArticleSet` returned by EFetch on
+/// [`crate::EntrezDb::PubMed`]
+///
+/// The first publication type (eg: "Journal Article") is given as
+/// [`Article::article_type`], and the parts of a structured abstract are joined.
+pub fn parse_pubmed_articles(xml: &str) -> Result<Vec<Article>, String> {
+    parse_documents(xml, b"PubmedArticle", |_| Article::default(), start_pubmed_element)
+}
+
+/// Parse every `root` element of `xml` as an [`Article`]
+///
+/// `new` creates the article for the start of `root`, and `start` tells which of its
+/// descendants are collected.
+fn parse_documents(
+    xml: &str,
+    root: &[u8],
+    new: impl Fn(&BytesStart) -> Article,
+    start: fn(&mut Article, &[Vec<u8>], &BytesStart) -> Option<Capture>,
+) -> Result<Vec<Article>, String> {
+    let mut reader = Reader::from_str(xml);
+
+    let mut articles: Vec<Article> = Vec::new();
+    let mut path: Vec<Vec<u8>> = Vec::new();
+    // element being collected, its depth, and the text collected so far
+    let mut capture: Option<(Capture, usize, String)> = None;
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) => {
+                if capture.is_none() {
+                    if e.name().as_ref() == root && !path.iter().any(|p| p == root) {
+                        articles.push(new(&e));
+                    } else if let Some(article) = articles.last_mut() {
+                        capture = start(article, &path, &e).map(|kind| (kind, path.len(), String::new()));
+                    }
+                } else if let Some((_, _, text)) = capture.as_mut() {
+                    // keep words of consecutive block elements apart
+                    if BLOCK_ELEMENTS.contains(&e.name().as_ref()) {
+                        text.push(' ');
+                    }
+                }
+                path.push(e.name().as_ref().to_vec());
+            }
+            Ok(Event::Text(e)) => {
+                if let Some((_, _, text)) = capture.as_mut() {
+                    text.push_str(&e.unescape().map_err(|e| format!("{:?}", e))?);
+                }
+            }
+            Ok(Event::CData(e)) => {
+                if let Some((_, _, text)) = capture.as_mut() {
+                    text.push_str(&String::from_utf8_lossy(&e));
+                }
+            }
+            Ok(Event::End(_)) => {
+                path.pop();
+                if capture.as_ref().is_some_and(|(_, depth, _)| *depth == path.len()) {
+                    let (kind, _, text) = capture.take().unwrap();
+                    if let Some(article) = articles.last_mut() {
+                        end_element(article, kind, normalize_text(&text));
+                    }
+                }
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(format!("XML parsing error: {:?}", e)),
+            _ => (),
+        }
+    }
+
+    if articles.is_empty() {
+        return Err(format!("No <{}> found in document.", String::from_utf8_lossy(root)));
+    }
+    Ok(articles)
+}
+
+/// Update `article` for the start of `e`, and tell whether its text is collected
+fn start_element(article: &mut Article, path: &[Vec<u8>], e: &BytesStart) -> Option<Capture> {
+    let within = |name: &[u8]| path.iter().any(|p| p == name);
+    let parent = path.last().map(|p| p.as_slice());
+
+    match (parent, e.name().as_ref()) {
+        (Some(b"journal-title-group"), b"journal-title") if within(b"journal-meta") => {
+            Some(Capture::JournalTitle)
+        }
+        (Some(b"article-meta"), b"article-id") => {
+            Some(Capture::ArticleId(attribute(e, "pub-id-type")))
+        }
+        (Some(b"title-group"), b"article-title") if within(b"article-meta") => Some(Capture::Title),
+        (Some(b"contrib-group"), b"contrib") if within(b"article-meta") => {
+            article.contributors.push(Contributor {
+                role: attribute(e, "contrib-type"),
+                ..Contributor::default()
+            });
+            None
+        }
+        (Some(b"name"), b"surname") if within(b"contrib") && within(b"article-meta") => {
+            Some(Capture::Surname)
+        }
+        (Some(b"name"), b"given-names") if within(b"contrib") && within(b"article-meta") => {
+            Some(Capture::GivenNames)
+        }
+        (Some(b"pub-date"), b"year") if within(b"article-meta") && article.pub_year.is_none() => {
+            Some(Capture::Year)
+        }
+        (Some(b"article-meta"), b"abstract") if article.r#abstract.is_none() => {
+            Some(Capture::Abstract)
+        }
+        (Some(b"kwd-group"), b"kwd") if within(b"article-meta") => Some(Capture::Keyword),
+        (Some(b"body"), b"sec") | (Some(b"sec"), b"sec") if within(b"body") => {
+            let level = path.iter().filter(|p| p.as_slice() == b"sec").count() + 1;
+            article.sections.push(Section {
+                level,
+                ..Section::default()
+            });
+            None
+        }
+        (Some(b"sec"), b"title") if within(b"body") => Some(Capture::SectionTitle),
+        (Some(b"body"), b"p") | (Some(b"sec"), b"p") if within(b"body") => Some(Capture::Paragraph),
+        _ => None,
+    }
+}
+
+/// Update `article` for the start of `e` within a `PubmedArticle`, and tell whether
+/// its text is collected
+fn start_pubmed_element(article: &mut Article, path: &[Vec<u8>], e: &BytesStart) -> Option<Capture> {
+    let within = |name: &[u8]| path.iter().any(|p| p == name);
+    let parent = path.last().map(|p| p.as_slice());
+    let grandparent = path.len().checked_sub(2).map(|i| path[i].as_slice());
+
+    match (parent, e.name().as_ref()) {
+        (Some(b"MedlineCitation"), b"PMID") => Some(Capture::ArticleId(Some("pubmed".to_string()))),
+        // ids of the article itself, rather than of its references
+        (Some(b"ArticleIdList"), b"ArticleId") if grandparent == Some(b"PubmedData") => {
+            Some(Capture::ArticleId(attribute(e, "IdType")))
+        }
+        (Some(b"Journal"), b"Title") => Some(Capture::JournalTitle),
+        (Some(b"Article"), b"ArticleTitle") => Some(Capture::Title),
+        (Some(b"AuthorList"), b"Author") if within(b"Article") => {
+            article.contributors.push(Contributor {
+                role: Some("author".to_string()),
+                ..Contributor::default()
+            });
+            None
+        }
+        (Some(b"Author"), b"LastName") if within(b"AuthorList") => Some(Capture::Surname),
+        (Some(b"Author"), b"ForeName") if within(b"AuthorList") => Some(Capture::GivenNames),
+        (Some(b"PubDate"), b"Year") if within(b"JournalIssue") => Some(Capture::Year),
+        (Some(b"Abstract"), b"AbstractText") if within(b"Article") => Some(Capture::AbstractText),
+        (Some(b"PublicationTypeList"), b"PublicationType") if article.article_type.is_none() => {
+            Some(Capture::ArticleType)
+        }
+        (Some(b"KeywordList"), b"Keyword") => Some(Capture::Keyword),
+        _ => None,
+    }
+}
+
+/// Store the text collected for an element
+fn end_element(article: &mut Article, kind: Capture, text: String) {
+    if text.is_empty() {
+        return;
+    }
+    match kind {
+        Capture::JournalTitle => article.journal = Some(text),
+        Capture::ArticleId(r#type) => match r#type.as_deref() {
+            Some("pmc") | Some("pmcid") => article.pmcid = Some(normalize_pmcid(&text)),
+            Some("pmid") | Some("pubmed") => article.pmid = text.parse().ok(),
+            Some("doi") => article.doi = Some(text),
+            _ => (),
+        },
+        Capture::Title => article.title = Some(text),
+        Capture::Surname => {
+            if let Some(author) = article.contributors.last_mut() {
+                author.surname = Some(text);
+            }
+        }
+        Capture::GivenNames => {
+            if let Some(author) = article.contributors.last_mut() {
+                author.given_names = Some(text);
+            }
+        }
+        Capture::Year => article.pub_year = text.parse().ok(),
+        Capture::Abstract => article.r#abstract = Some(text),
+        Capture::AbstractText => match article.r#abstract.as_mut() {
+            Some(r#abstract) => {
+                r#abstract.push(' ');
+                r#abstract.push_str(&text);
+            }
+            None => article.r#abstract = Some(text),
+        },
+        Capture::ArticleType => article.article_type = Some(text),
+        Capture::Keyword => article.keywords.push(text),
+        Capture::SectionTitle => {
+            if let Some(section) = article.sections.last_mut() {
+                section.title = Some(text);
+            }
+        }
+        Capture::Paragraph => {
+            if article.sections.is_empty() {
+                article.sections.push(Section {
+                    level: 1,
+                    ..Section::default()
+                });
+            }
+            article.sections.last_mut().unwrap().paragraphs.push(text);
+        }
+    }
+}
+
+/// Articles of a PMC Open Access bulk package
+///
+/// Packages are tar archives of one JATS file (`.xml` or `.nxml`) per article,
+/// named after the PMCID of the article (eg: `PMC000xxxxxx/PMC176545.xml`). Other
+/// files, such as the file lists and supplementary material, are skipped.
+pub struct OaPackage<R: Read> {
+    archive: Archive<R>,
+    pmcids: Option<HashSet<String>>,
+}
+
+impl OaPackage<GzDecoder<BufReader<File>>> {
+    /// Open a `.tar.gz` package
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let file = File::open(path.as_ref())
+            .map_err(|e| format!("Cannot open {}: {}", path.as_ref().display(), e))?;
+        Ok(Self::from_gz(BufReader::new(file)))
+    }
+}
+
+impl<R: Read> OaPackage<GzDecoder<R>> {
+    /// Read a gzip compressed package
+    pub fn from_gz(reader: R) -> Self {
+        Self::new(GzDecoder::new(reader))
+    }
+}
+
+impl<R: Read> OaPackage<R> {
+    /// Read an uncompressed tar package
+    pub fn new(reader: R) -> Self {
+        Self {
+            archive: Archive::new(reader),
+            pmcids: None,
+        }
+    }
+
+    /// Only yield the articles with these PMCIDs, given as "PMC176545" or "176545"
+    ///
+    /// Files named after another PMCID are skipped without being parsed.
+    pub fn with_pmcids<S: AsRef<str>>(mut self, pmcids: &[S]) -> Self {
+        self.pmcids = Some(pmcids.iter().map(|id| normalize_pmcid(id.as_ref())).collect());
+        self
+    }
+
+    /// Iterate the articles of the package
+    ///
+    /// The archive is read sequentially, so this may only be called once.
+    pub fn articles(&mut self) -> Result<impl Iterator<Item = Result<Article, String>> + '_, String> {
+        let pmcids = &self.pmcids;
+        let entries = self
+            .archive
+            .entries()
+            .map_err(|e| format!("Cannot read package: {}", e))?;
+
+        Ok(entries.filter_map(move |entry| {
+            let mut entry = match entry {
+                Ok(entry) => entry,
+                Err(e) => return Some(Err(format!("Cannot read package: {}", e))),
+            };
+            let path = entry.path().ok()?.to_string_lossy().to_string();
+            if !(path.ends_with(".xml") || path.ends_with(".nxml")) {
+                return None;
+            }
+
+            let wanted = |pmcid: &str| pmcids.as_ref().is_none_or(|ids| ids.contains(pmcid));
+            let stem = Path::new(&path).file_stem()?.to_string_lossy().to_string();
+            if stem.starts_with("PMC") && !wanted(&stem) {
+                return None;
+            }
+
+            let mut xml = String::new();
+            if let Err(e) = entry.read_to_string(&mut xml) {
+                return Some(Err(format!("Cannot read {}: {}", path, e)));
+            }
+            match parse_articles(&xml) {
+                Ok(articles) => {
+                    let article = articles.into_iter().next()?;
+                    wanted(article.pmcid.as_deref().unwrap_or(&stem)).then_some(Ok(article))
+                }
+                Err(e) => Some(Err(format!("{}: {}", path, e))),
+            }
+        }))
+    }
+}
@@ -0,0 +1,289 @@
+//! Rate limiting of requests shared by all threads
+//!
+//! NCBI allows 3 requests per second from an address, or 10 per second with an API
+//! key, and blocks callers which exceed it. Threads harvesting records in parallel
+//! are seen as a single caller, so their requests must be limited together.
+//!
+//! Once installed with [`set_rate_limiter()`], a [`RateLimiter`] is shared by every
+//! request made through [`http_get()`](crate::http_get) and its non-blocking
+//! counterparts in [`crate::transport`], which wait until the request may be sent.
+//! Requests are accounted by the `api_key` parameter of their URL (see
+//! [`ClientConfig`](crate::ClientConfig)), so that each key has its own allowance, and
+//! requests without a key share another. Responses replayed from fixtures are not
+//! counted.
+//!
+//! Non-blocking requests wait without blocking their executor, and do not depend on a
+//! particular async runtime. In the browser, where there is no timer to wait with,
+//! they are not limited.
+//!
+//! # Example
+//! ```
+//! use ncbi::ratelimit::{reset_rate_limiter, set_rate_limiter, RateLimiter};
+//!
+//! set_rate_limiter(RateLimiter::new());
+//! # reset_rate_limiter();
+//! ```
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use std::time::{Duration, Instant};
+#[cfg(not(target_arch = "wasm32"))]
+use std::{future::Future, pin::Pin, task::{Context, Poll, Waker}};
+
+/// Requests per second allowed by NCBI without an API key
+pub const RATE_WITHOUT_KEY: f64 = 3.0;
+
+/// Requests per second allowed by NCBI with an API key
+pub const RATE_WITH_KEY: f64 = 10.0;
+
+#[derive(Clone, Copy, Debug)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+#[derive(Debug)]
+/// Token bucket for each API key
+///
+/// A bucket holds at most one second worth of requests, so that a burst of requests
+/// after a pause does not exceed the rate.
+pub struct RateLimiter {
+    without_key: f64,
+    with_key: f64,
+    buckets: Mutex<HashMap<Option<String>, Bucket>>,
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RateLimiter {
+    /// Limit requests to the rates allowed by NCBI
+    pub fn new() -> Self {
+        Self::with_rates(RATE_WITHOUT_KEY, RATE_WITH_KEY)
+    }
+
+    /// Limit requests to `without_key` and `with_key` requests per second
+    ///
+    /// Rates below NCBI limits leave room for other programs run from the same address,
+    /// and a rate of 0 leaves the corresponding requests unlimited.
+    pub fn with_rates(without_key: f64, with_key: f64) -> Self {
+        Self {
+            without_key,
+            with_key,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Requests per second allowed for `api_key`
+    pub fn rate(&self, api_key: Option<&str>) -> f64 {
+        match api_key {
+            Some(_) => self.with_key,
+            None => self.without_key,
+        }
+    }
+
+    /// Count a request made with `api_key`, if one is allowed now
+    ///
+    /// Otherwise, returns the time to wait before trying again.
+    pub fn try_acquire(&self, api_key: Option<&str>) -> Result<(), Duration> {
+        self.try_acquire_at(api_key, Instant::now())
+    }
+
+    /// Wait until a request may be made with `api_key`, and count it
+    pub fn acquire(&self, api_key: Option<&str>) {
+        while let Err(wait) = self.try_acquire(api_key) {
+            std::thread::sleep(wait);
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    /// Wait without blocking until a request may be made with `api_key`, and count it
+    pub async fn acquire_async(&self, api_key: Option<&str>) {
+        while let Err(wait) = self.try_acquire(api_key) {
+            Sleep::new(wait).await;
+        }
+    }
+
+    fn try_acquire_at(&self, api_key: Option<&str>, now: Instant) -> Result<(), Duration> {
+        let rate = self.rate(api_key);
+        if rate <= 0.0 {
+            return Ok(());
+        }
+        let capacity = rate.max(1.0);
+
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        let bucket = buckets.entry(api_key.map(str::to_string)).or_insert(Bucket {
+            tokens: capacity,
+            updated: now,
+        });
+        let elapsed = now.saturating_duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * rate).min(capacity);
+        bucket.updated = bucket.updated.max(now);
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) / rate))
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+/// Future which completes once a duration has elapsed
+///
+/// The task is woken by a timer thread, so that no particular async runtime is needed.
+struct Sleep {
+    deadline: Instant,
+    waker: Option<Arc<Mutex<Waker>>>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Sleep {
+    fn new(duration: Duration) -> Self {
+        Self {
+            deadline: Instant::now() + duration,
+            waker: None,
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let now = Instant::now();
+        if now >= self.deadline {
+            return Poll::Ready(());
+        }
+        match &self.waker {
+            // the task may have moved since it was last polled
+            Some(waker) => waker.lock().unwrap_or_else(|e| e.into_inner()).clone_from(cx.waker()),
+            None => {
+                let waker = Arc::new(Mutex::new(cx.waker().clone()));
+                let shared = waker.clone();
+                let wait = self.deadline - now;
+                std::thread::spawn(move || {
+                    std::thread::sleep(wait);
+                    shared.lock().unwrap_or_else(|e| e.into_inner()).wake_by_ref();
+                });
+                self.waker = Some(waker);
+            }
+        }
+        Poll::Pending
+    }
+}
+
+fn installed() -> &'static RwLock<Option<Arc<RateLimiter>>> {
+    static LIMITER: OnceLock<RwLock<Option<Arc<RateLimiter>>>> = OnceLock::new();
+    LIMITER.get_or_init(|| RwLock::new(None))
+}
+
+/// Limit all following requests with `limiter`, shared by all threads
+pub fn set_rate_limiter(limiter: RateLimiter) {
+    *installed().write().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(limiter));
+}
+
+/// Stop limiting requests
+pub fn reset_rate_limiter() {
+    *installed().write().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Limiter which requests currently wait for, if any
+pub fn rate_limiter() -> Option<Arc<RateLimiter>> {
+    installed().read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Value of the `api_key` parameter of `url`
+fn url_api_key(url: &str) -> Option<&str> {
+    let (_, query) = url.split_once('?')?;
+    query
+        .split('&')
+        .find_map(|param| param.strip_prefix("api_key="))
+        .filter(|key| !key.is_empty())
+}
+
+/// Wait for the installed limiter, if any, before requesting `url`
+pub(crate) fn throttle(url: &str) {
+    if let Some(limiter) = rate_limiter() {
+        limiter.acquire(url_api_key(url));
+    }
+}
+
+/// Wait without blocking for the installed limiter, if any, before requesting `url`
+pub(crate) async fn throttle_async(url: &str) {
+    #[cfg(not(target_arch = "wasm32"))]
+    if let Some(limiter) = rate_limiter() {
+        limiter.acquire_async(url_api_key(url)).await;
+    }
+    #[cfg(target_arch = "wasm32")]
+    let _ = url;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ratelimit::{url_api_key, RateLimiter};
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_url_api_key() {
+        assert_eq!(url_api_key("https://host/efetch.fcgi?db=gene&id=1&api_key=abc123"), Some("abc123"));
+        assert_eq!(url_api_key("https://host/efetch.fcgi?api_key=abc&id=1"), Some("abc"));
+        assert_eq!(url_api_key("https://host/efetch.fcgi?db=gene&id=1"), None);
+        assert_eq!(url_api_key("https://host/efetch.fcgi?api_key="), None);
+    }
+
+    #[test]
+    fn test_token_bucket() {
+        let limiter = RateLimiter::with_rates(3.0, 10.0);
+        let start = Instant::now();
+
+        for _ in 0..3 {
+            assert!(limiter.try_acquire_at(None, start).is_ok());
+        }
+        let wait = limiter.try_acquire_at(None, start).unwrap_err();
+        assert!(wait > Duration::from_millis(300) && wait <= Duration::from_millis(334));
+
+        // tokens are refilled over time, up to one second worth of requests
+        assert!(limiter.try_acquire_at(None, start + Duration::from_millis(340)).is_ok());
+        assert!(limiter.try_acquire_at(None, start + Duration::from_millis(340)).is_err());
+        let later = start + Duration::from_secs(60);
+        assert_eq!((0..5).filter(|_| limiter.try_acquire_at(None, later).is_ok()).count(), 3);
+
+        // each key has its own allowance
+        assert_eq!((0..12).filter(|_| limiter.try_acquire_at(Some("a"), start).is_ok()).count(), 10);
+        assert_eq!((0..12).filter(|_| limiter.try_acquire_at(Some("b"), start).is_ok()).count(), 10);
+    }
+
+    #[test]
+    fn test_acquire_async() {
+        let limiter = RateLimiter::with_rates(20.0, 10.0);
+        let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        let start = Instant::now();
+        runtime.block_on(async {
+            for _ in 0..22 {
+                limiter.acquire_async(None).await;
+            }
+        });
+        // the first second worth of requests is allowed at once
+        assert!(start.elapsed() >= Duration::from_millis(90));
+    }
+
+    #[test]
+    fn test_shared_by_threads() {
+        let limiter = Arc::new(RateLimiter::with_rates(0.5, 10.0));
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let limiter = limiter.clone();
+                std::thread::spawn(move || (0..5).filter(|_| limiter.try_acquire(None).is_ok()).count())
+            })
+            .collect();
+        let allowed: usize = handles.into_iter().map(|handle| handle.join().unwrap()).sum();
+        assert_eq!(allowed, 1);
+    }
+}
@@ -0,0 +1,283 @@
+//! Simplified view of sequence records
+//!
+//! [`Record`] gathers what most applications need from a sequence record (its id,
+//! description, residues, features, organism and references) as plain strings and
+//! numbers, so that records can be consumed without navigating the ASN.1 object
+//! model. Records are built from a [`BioSeq`], from every sequence of a
+//! [`BioSeqSet`], or from FASTA text, and the object they were built from remains
+//! available by [`Record::source()`].
+//!
+//! # Example
+//! ```ignore
+//! for record in Record::from_set(&set) {
+//!     println!("{}\t{}", record.id, record.description.as_deref().unwrap_or_default());
+//!     for feature in record.features.iter().filter(|f| f.key == "CDS") {
+//!         println!("\t{:?}", feature.qualifier("product"));
+//!     }
+//! }
+//! ```
+
+use crate::coords::{Pos0, Range0};
+use crate::gff::best_id;
+use crate::r#pub::{CitationStyle, Pub};
+use crate::seq::{BioSeq, Descriptors, SeqDesc, SeqDescr};
+use crate::seqfeat::SeqFeat;
+use crate::seqloc::{NaStrand, SeqLoc};
+use crate::seqset::{BioSeqSet, SeqEntry};
+use crate::tbl::{feature_key, qualifiers};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+/// Object a [`Record`] was built from
+pub enum RecordSource {
+    BioSeq(Box<BioSeq>),
+    /// FASTA definition line, without the leading ">"
+    Fasta(String),
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+/// A sequence and its annotation
+pub struct Record {
+    /// accession with version, when available (eg: "NZ_JARQWN010000024.1")
+    pub id: String,
+    pub description: Option<String>,
+    /// residues as one-letter IUPAC codes, if given by the source
+    pub sequence: Option<String>,
+    pub features: Vec<Feature>,
+    pub taxonomy: Option<Taxonomy>,
+    pub references: Vec<Reference>,
+    source: RecordSource,
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+pub struct Feature {
+    /// INSDC feature key (eg: "gene", "CDS")
+    pub key: String,
+    /// first position covered by the feature, starting at 0
+    pub start: Option<u64>,
+    /// last position covered by the feature
+    pub end: Option<u64>,
+    pub strand: Option<NaStrand>,
+    /// qualifiers as in a GenBank flat file (eg: "gene", "product", "db_xref")
+    pub qualifiers: Vec<(String, String)>,
+}
+
+impl Feature {
+    fn from_seq_feat(feat: &SeqFeat) -> Self {
+        let range = feat.location.range0();
+        Self {
+            key: feature_key(feat),
+            start: range.map(|range| range.start().get()),
+            end: range.map(|range| range.end().get()),
+            strand: loc_strand(&feat.location),
+            qualifiers: qualifiers(feat)
+                .into_iter()
+                .map(|(name, value)| (name.to_string(), value))
+                .collect(),
+        }
+    }
+
+    /// Positions covered by the feature, unless unknown
+    pub fn range0(&self) -> Option<Range0> {
+        Range0::new(Pos0::new(self.start?), Pos0::new(self.end?))
+    }
+
+    /// Value of the first qualifier named `name`
+    pub fn qualifier(&self, name: &str) -> Option<&str> {
+        self.qualifiers
+            .iter()
+            .find(|(qual, _)| qual == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+pub struct Taxonomy {
+    /// scientific name (eg: "Escherichia coli")
+    pub organism: Option<String>,
+    pub taxid: Option<u64>,
+    /// taxa of the lineage, from the root
+    pub lineage: Vec<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+pub struct Reference {
+    /// see [`CitationStyle::Full`]
+    pub citation: String,
+    pub pmid: Option<u64>,
+}
+
+impl Reference {
+    fn from_pub(r#pub: &Pub) -> Self {
+        let pmid = match r#pub {
+            Pub::PmId(pmid) => Some(*pmid),
+            Pub::Equiv(equiv) => equiv.iter().find_map(|r#pub| match r#pub {
+                Pub::PmId(pmid) => Some(*pmid),
+                _ => None,
+            }),
+            _ => None,
+        };
+        Self {
+            citation: r#pub.to_citation_string(CitationStyle::Full),
+            pmid,
+        }
+    }
+}
+
+/// Strand of a location, as given by its first interval or point
+fn loc_strand(loc: &SeqLoc) -> Option<NaStrand> {
+    match loc {
+        SeqLoc::Int(interval) => interval.strand.clone(),
+        SeqLoc::PackedInt(intervals) => intervals.first()?.strand.clone(),
+        SeqLoc::Pnt(point) => point.strand.clone(),
+        SeqLoc::Mix(mix) => mix.0.iter().find_map(loc_strand),
+        _ => None,
+    }
+}
+
+impl Record {
+    /// Build a record from the descriptors and annotations of `bioseq` alone
+    pub fn from_bioseq(bioseq: &BioSeq) -> Self {
+        Self::with_descriptors(bioseq, bioseq)
+    }
+
+    /// Build a record for every sequence of `set`
+    ///
+    /// Sequences inherit the descriptors of the sets containing them, such as the
+    /// organism and references given once for a `nuc-prot` set.
+    pub fn from_set(set: &BioSeqSet) -> Vec<Self> {
+        let mut records = Vec::new();
+        collect_records(set, &[], &mut records);
+        records
+    }
+
+    /// Build a record for every sequence of `entry`
+    pub fn from_entry(entry: &SeqEntry) -> Vec<Self> {
+        match entry {
+            SeqEntry::Seq(bioseq) => vec![Self::from_bioseq(bioseq)],
+            SeqEntry::Set(set) => Self::from_set(set),
+        }
+    }
+
+    /// Parse the records of FASTA formatted text
+    ///
+    /// The first word of the definition line is the id, and the rest is the
+    /// description. An organism given in brackets at the end of the description (eg:
+    /// "tumor protein p53 [Homo sapiens]"), as in NCBI protein FASTA, is kept as the
+    /// [`Taxonomy::organism`].
+    pub fn from_fasta(text: &str) -> Result<Vec<Self>, String> {
+        let mut records: Vec<Self> = Vec::new();
+        for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            if let Some(defline) = line.strip_prefix('>') {
+                records.push(Self::from_defline(defline));
+            } else if line.starts_with(';') {
+                continue;
+            } else {
+                let record = records
+                    .last_mut()
+                    .ok_or("FASTA text does not start with a definition line".to_string())?;
+                record.sequence.get_or_insert_with(String::new).push_str(line);
+            }
+        }
+        if records.is_empty() {
+            return Err("No FASTA records found".to_string());
+        }
+        Ok(records)
+    }
+
+    fn from_defline(defline: &str) -> Self {
+        let (id, description) = match defline.trim().split_once(char::is_whitespace) {
+            Some((id, description)) => (id, Some(description.trim().to_string())),
+            None => (defline.trim(), None),
+        };
+        let organism = description.as_deref().and_then(|description| {
+            let (_, organism) = description.strip_suffix(']')?.rsplit_once(" [")?;
+            Some(organism.to_string())
+        });
+        Self {
+            id: id.to_string(),
+            description,
+            sequence: None,
+            features: Vec::new(),
+            taxonomy: organism.map(|organism| Taxonomy {
+                organism: Some(organism),
+                ..Taxonomy::default()
+            }),
+            references: Vec::new(),
+            source: RecordSource::Fasta(defline.to_string()),
+        }
+    }
+
+    fn with_descriptors<D: Descriptors>(bioseq: &BioSeq, descr: &D) -> Self {
+        let taxonomy = descr.organism().map(|org| Taxonomy {
+            organism: org.taxname.clone(),
+            taxid: org.taxid(),
+            lineage: org.lineage_vec().into_iter().map(str::to_string).collect(),
+        });
+        let references = descr
+            .descriptors()
+            .iter()
+            .filter_map(|desc| match desc {
+                SeqDesc::Pub(desc) => Some(Reference::from_pub(&Pub::Equiv(desc.r#pub.clone()))),
+                _ => None,
+            })
+            .collect();
+        let features = bioseq
+            .annot
+            .iter()
+            .flatten()
+            .flat_map(|annot| annot.features())
+            .map(Feature::from_seq_feat)
+            .collect();
+
+        Self {
+            id: best_id(bioseq),
+            description: descr.title().map(str::to_string),
+            sequence: bioseq.inst.as_ref().and_then(|inst| inst.residues().ok()),
+            features,
+            taxonomy,
+            references,
+            source: RecordSource::BioSeq(Box::new(bioseq.clone())),
+        }
+    }
+
+    /// Object this record was built from
+    pub fn source(&self) -> &RecordSource {
+        &self.source
+    }
+
+    /// The [`BioSeq`] this record was built from, if any
+    pub fn bioseq(&self) -> Option<&BioSeq> {
+        match &self.source {
+            RecordSource::BioSeq(bioseq) => Some(bioseq),
+            RecordSource::Fasta(_) => None,
+        }
+    }
+
+    pub fn into_source(self) -> RecordSource {
+        self.source
+    }
+
+    /// Number of residues, if the sequence is given
+    pub fn length(&self) -> Option<usize> {
+        self.sequence.as_ref().map(String::len)
+    }
+}
+
+/// Build records for the sequences of `set`, given the descriptors of its ancestors
+fn collect_records(set: &BioSeqSet, inherited: &[SeqDesc], records: &mut Vec<Record>) {
+    // nearer descriptors come first, so that they take precedence
+    let mut context: SeqDescr = set.descriptors().to_vec();
+    context.extend_from_slice(inherited);
+
+    for entry in set.seq_set.iter() {
+        match entry {
+            SeqEntry::Seq(bioseq) => {
+                let mut descr: SeqDescr = bioseq.descriptors().to_vec();
+                descr.extend_from_slice(&context);
+                records.push(Record::with_descriptors(bioseq, &descr));
+            }
+            SeqEntry::Set(set) => collect_records(set, &context, records),
+        }
+    }
+}
@@ -0,0 +1,514 @@
+//! Mapping between accessions and GenInfo identifiers
+//!
+//! Lookups are performed with ESummary, and [`IdResolver`] caches every summary it
+//! receives so that repeated lookups (in either direction) do not trigger additional
+//! requests. Records that are no longer live are reported as [`ResolveError`]
+//! instead of silently resolving to stale identifiers.
+
+use crate::seqloc::TextseqId;
+use crate::{build_summary_url, http_get, EntrezDb};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Mutex, MutexGuard, OnceLock};
+
+/// GenInfo identifier
+pub type Gi = u64;
+
+/// Maximum number of ids sent in a single ESummary request
+const BATCH_SIZE: usize = 200;
+
+/// Maximum number of replacements followed by [`IdResolver::latest_accession()`]
+const MAX_REPLACEMENTS: usize = 32;
+
+#[derive(Clone, PartialEq, Debug)]
+/// Status of a sequence record as reported by ESummary
+pub enum RecordStatus {
+    Live,
+    /// removed by the submitter or NCBI
+    Withdrawn,
+    /// hidden from searches, but still retrievable
+    Suppressed,
+    /// superseded by another accession
+    Replaced(Option<String>),
+    /// status string not recognized
+    Other(String),
+}
+
+impl RecordStatus {
+    fn from_summary(status: &str, replaced_by: Option<String>) -> Self {
+        match status {
+            "" | "live" => Self::Live,
+            "withdrawn" | "dead" => Self::Withdrawn,
+            "suppressed" => Self::Suppressed,
+            "replaced" => Self::Replaced(replaced_by),
+            _ => Self::Other(status.to_string()),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum ResolveError {
+    /// no record exists for the given id
+    NotFound(String),
+    /// record exists, but is not live
+    Inactive { id: String, status: RecordStatus },
+    /// request failed or returned an unexpected response
+    Request(String),
+}
+
+impl fmt::Display for ResolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound(id) => write!(f, "no record found for \"{}\"", id),
+            Self::Inactive { id, status } => match status {
+                RecordStatus::Replaced(Some(by)) => {
+                    write!(f, "record \"{}\" has been replaced by \"{}\"", id, by)
+                }
+                _ => write!(f, "record \"{}\" is not live ({:?})", id, status),
+            },
+            Self::Request(msg) => write!(f, "request failed: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ResolveError {}
+
+#[derive(Clone, PartialEq, Debug)]
+/// Identifiers of a single record as given by an ESummary `DocSum`
+pub struct IdSummary {
+    pub gi: Gi,
+    pub accession: TextseqId,
+    pub status: RecordStatus,
+}
+
+impl IdSummary {
+    /// Fail if the record is not live
+    fn check_status(&self, id: &str) -> Result<&Self, ResolveError> {
+        match self.status {
+            RecordStatus::Live => Ok(self),
+            _ => Err(ResolveError::Inactive {
+                id: id.to_string(),
+                status: self.status.clone(),
+            }),
+        }
+    }
+
+    /// Parse all `DocSum` elements of an ESummary (version 1) XML response
+    pub fn from_esummary(response: &str) -> Result<Vec<Self>, String> {
+        let mut reader = Reader::from_str(response);
+        reader.trim_text(true);
+
+        let mut summaries = Vec::new();
+        let mut found = false;
+
+        // fields of current `DocSum`
+        let mut id: Option<String> = None;
+        let mut items: HashMap<String, String> = HashMap::new();
+        let mut item: Option<String> = None;
+        let mut in_id = false;
+
+        loop {
+            match reader.read_event() {
+                Ok(Event::Start(e)) => match e.name().as_ref() {
+                    b"eSummaryResult" => found = true,
+                    b"DocSum" => {
+                        id = None;
+                        items.clear();
+                    }
+                    b"Id" => in_id = true,
+                    b"Item" => {
+                        item = e
+                            .try_get_attribute("Name")
+                            .ok()
+                            .flatten()
+                            .and_then(|a| a.unescape_value().ok())
+                            .map(|name| name.to_string());
+                    }
+                    _ => (),
+                },
+                Ok(Event::Text(text)) => {
+                    let text = text.unescape().map_err(|e| format!("{:?}", e))?.to_string();
+                    if in_id {
+                        id = Some(text);
+                    } else if let Some(name) = &item {
+                        items.insert(name.clone(), text);
+                    }
+                }
+                Ok(Event::End(e)) => match e.name().as_ref() {
+                    b"Id" => in_id = false,
+                    b"Item" => item = None,
+                    b"DocSum" => {
+                        if let Some(summary) = Self::from_items(id.take(), &items) {
+                            summaries.push(summary);
+                        }
+                    }
+                    _ => (),
+                },
+                Ok(Event::Eof) => break,
+                Err(e) => return Err(format!("XML parsing error: {:?}", e)),
+                _ => (),
+            }
+        }
+
+        if found {
+            Ok(summaries)
+        } else {
+            Err("No <eSummaryResult> found in response.".to_string())
+        }
+    }
+
+    fn from_items(id: Option<String>, items: &HashMap<String, String>) -> Option<Self> {
+        let gi = items
+            .get("Gi")
+            .or(id.as_ref())
+            .and_then(|gi| gi.trim().parse().ok())?;
+
+        // `AccessionVersion` is preferred, then `Extra` (eg: "gi|123|ref|NC_000001.11|")
+        let versioned = items.get("AccessionVersion").cloned().or_else(|| {
+            let extra = items.get("Extra")?;
+            extra.split('|').filter(|s| !s.is_empty()).nth(3).map(|s| s.to_string())
+        });
+        let mut accession = TextseqId::default();
+        match versioned.as_deref().and_then(|acc| acc.rsplit_once('.')) {
+            Some((acc, version)) if version.parse::<u64>().is_ok() => {
+                accession.accession = Some(acc.to_string());
+                accession.version = version.parse().ok();
+            }
+            _ => accession.accession = items.get("Caption").cloned(),
+        }
+
+        let replaced_by = items.get("ReplacedBy").filter(|s| !s.is_empty()).cloned();
+        let status = items.get("Status").map(String::as_str).unwrap_or("live");
+
+        Some(Self {
+            gi,
+            accession,
+            status: RecordStatus::from_summary(status, replaced_by),
+        })
+    }
+}
+
+/// Resolves accessions and GIs, caching results
+pub struct IdResolver {
+    db: EntrezDb,
+    /// summaries keyed by GI, accession, and versioned accession
+    cache: HashMap<String, IdSummary>,
+}
+
+impl IdResolver {
+    /// Create a resolver for a sequence database (eg: [`EntrezDb::Nucleotide`] or [`EntrezDb::Protein`])
+    pub fn new(db: EntrezDb) -> Self {
+        Self {
+            db,
+            cache: HashMap::new(),
+        }
+    }
+
+    /// GI of the record with accession `acc`
+    ///
+    /// `acc` may include a version (eg: "NZ_JARQWN010000024.1").
+    pub fn resolve_accession(&mut self, acc: &str) -> Result<Gi, ResolveError> {
+        self.resolve_accessions(&[acc]).remove(0)
+    }
+
+    /// Accession of the record with GI `gi`
+    pub fn resolve_gi(&mut self, gi: Gi) -> Result<TextseqId, ResolveError> {
+        self.resolve_gis(&[gi]).remove(0)
+    }
+
+    /// Resolve multiple accessions, only requesting those that are not cached
+    pub fn resolve_accessions(&mut self, accs: &[&str]) -> Vec<Result<Gi, ResolveError>> {
+        let keys: Vec<String> = accs.iter().map(|acc| acc.trim().to_string()).collect();
+        self.resolve(&keys)
+            .into_iter()
+            .map(|result| result.map(|summary| summary.gi))
+            .collect()
+    }
+
+    /// Resolve multiple GIs, only requesting those that are not cached
+    pub fn resolve_gis(&mut self, gis: &[Gi]) -> Vec<Result<TextseqId, ResolveError>> {
+        let keys: Vec<String> = gis.iter().map(|gi| gi.to_string()).collect();
+        self.resolve(&keys)
+            .into_iter()
+            .map(|result| result.map(|summary| summary.accession))
+            .collect()
+    }
+
+    /// Latest live version of the record with accession `acc`
+    ///
+    /// Replaced records are followed through `ReplacedBy` until a live record is
+    /// found. Records that are withdrawn, or replaced without a successor, are
+    /// reported as [`ResolveError::Inactive`] for the last accession of the chain.
+    pub fn latest_accession(&mut self, acc: &str) -> Result<TextseqId, ResolveError> {
+        follow_replacements(acc, |key| self.resolve(&[key.to_string()]).remove(0))
+    }
+
+    /// Number of cached summaries
+    pub fn cached(&self) -> usize {
+        let mut gis: Vec<Gi> = self.cache.values().map(|summary| summary.gi).collect();
+        gis.sort();
+        gis.dedup();
+        gis.len()
+    }
+
+    fn resolve(&mut self, keys: &[String]) -> Vec<Result<IdSummary, ResolveError>> {
+        let missing = self.missing(keys);
+        let (summaries, failure) = fetch_summaries(self.db, &missing);
+        self.insert(&missing, summaries);
+        self.lookup(keys, failure.as_ref())
+    }
+
+    /// Keys that are not yet cached, without duplicates
+    fn missing(&self, keys: &[String]) -> Vec<String> {
+        let mut missing: Vec<String> = keys
+            .iter()
+            .filter(|key| !self.cache.contains_key(key.as_str()))
+            .cloned()
+            .collect();
+        missing.sort();
+        missing.dedup();
+        missing
+    }
+
+    /// Cached result for each key
+    ///
+    /// Keys that are not cached report `failure` if the request for them failed.
+    fn lookup(
+        &self,
+        keys: &[String],
+        failure: Option<&String>,
+    ) -> Vec<Result<IdSummary, ResolveError>> {
+        keys.iter()
+            .map(|key| match (self.cache.get(key.as_str()), failure) {
+                (Some(summary), _) => summary.check_status(key).cloned(),
+                (None, Some(e)) => Err(ResolveError::Request(e.clone())),
+                (None, None) => Err(ResolveError::NotFound(key.clone())),
+            })
+            .collect()
+    }
+
+    /// Cache summaries requested for `keys` by GI, accession, and versioned accession
+    ///
+    /// The unversioned accession stands for the latest version, so it is only cached for
+    /// summaries that were requested by it, or that have not been replaced. Otherwise
+    /// resolving "X53814.1" would shadow the live "X53814.2".
+    fn insert(&mut self, keys: &[String], summaries: Vec<IdSummary>) {
+        for summary in summaries {
+            self.cache.insert(summary.gi.to_string(), summary.clone());
+            if let Some(acc) = &summary.accession.accession {
+                if let Some(version) = summary.accession.version {
+                    self.cache.insert(format!("{}.{}", acc, version), summary.clone());
+                }
+                let replaced = matches!(summary.status, RecordStatus::Replaced(_));
+                if !replaced || keys.contains(acc) {
+                    self.cache.insert(acc.clone(), summary);
+                }
+            }
+        }
+    }
+}
+
+/// Request summaries for `keys` in batches
+///
+/// Summaries of successful batches are returned along with the last failure, if any.
+fn fetch_summaries(db: EntrezDb, keys: &[String]) -> (Vec<IdSummary>, Option<String>) {
+    let mut summaries = Vec::new();
+    let mut failure = None;
+    for batch in keys.chunks(BATCH_SIZE) {
+        let url = build_summary_url(db, batch.join(",").as_str());
+        match http_get(&url).and_then(|response| IdSummary::from_esummary(response.as_str())) {
+            Ok(batch) => summaries.extend(batch),
+            Err(e) => failure = Some(e),
+        }
+    }
+    (summaries, failure)
+}
+
+/// Follow `ReplacedBy` from `acc` until a live record is found
+fn follow_replacements(
+    acc: &str,
+    mut resolve: impl FnMut(&str) -> Result<IdSummary, ResolveError>,
+) -> Result<TextseqId, ResolveError> {
+    let mut key = acc.trim().to_string();
+    let mut visited = Vec::new();
+    loop {
+        match resolve(&key) {
+            Ok(summary) => return Ok(summary.accession),
+            Err(ResolveError::Inactive { status: RecordStatus::Replaced(Some(by)), .. })
+                if visited.len() < MAX_REPLACEMENTS && !visited.contains(&by) && by != key =>
+            {
+                visited.push(key);
+                key = by;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Shared resolver for [`EntrezDb::Nucleotide`] used by [`resolve_accession()`] and [`resolve_gi()`]
+fn nucleotide_resolver() -> MutexGuard<'static, IdResolver> {
+    static RESOLVER: OnceLock<Mutex<IdResolver>> = OnceLock::new();
+    RESOLVER
+        .get_or_init(|| Mutex::new(IdResolver::new(EntrezDb::Nucleotide)))
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+}
+
+/// Resolve `keys` with the shared resolver
+///
+/// The lock is only held while reading and updating the cache, so that a slow
+/// request does not block lookups from other threads.
+fn resolve_shared(keys: &[String]) -> Vec<Result<IdSummary, ResolveError>> {
+    let missing = nucleotide_resolver().missing(keys);
+    let (summaries, failure) = fetch_summaries(EntrezDb::Nucleotide, &missing);
+    let mut resolver = nucleotide_resolver();
+    resolver.insert(&missing, summaries);
+    resolver.lookup(keys, failure.as_ref())
+}
+
+/// GI of the nucleotide record with accession `acc`
+///
+/// Results are cached for the lifetime of the process. Use [`IdResolver`] for
+/// other databases.
+pub fn resolve_accession(acc: &str) -> Result<Gi, ResolveError> {
+    resolve_accessions(&[acc]).remove(0)
+}
+
+/// Accession of the nucleotide record with GI `gi`
+pub fn resolve_gi(gi: Gi) -> Result<TextseqId, ResolveError> {
+    resolve_gis(&[gi]).remove(0)
+}
+
+/// Latest live version of the nucleotide record with accession `acc`
+///
+/// See [`IdResolver::latest_accession()`].
+pub fn latest_accession(acc: &str) -> Result<TextseqId, ResolveError> {
+    follow_replacements(acc, |key| resolve_shared(&[key.to_string()]).remove(0))
+}
+
+/// Batch variant of [`resolve_accession()`]
+pub fn resolve_accessions(accs: &[&str]) -> Vec<Result<Gi, ResolveError>> {
+    let keys: Vec<String> = accs.iter().map(|acc| acc.trim().to_string()).collect();
+    resolve_shared(&keys)
+        .into_iter()
+        .map(|result| result.map(|summary| summary.gi))
+        .collect()
+}
+
+/// Batch variant of [`resolve_gi()`]
+pub fn resolve_gis(gis: &[Gi]) -> Vec<Result<TextseqId, ResolveError>> {
+    let keys: Vec<String> = gis.iter().map(|gi| gi.to_string()).collect();
+    resolve_shared(&keys)
+        .into_iter()
+        .map(|result| result.map(|summary| summary.accession))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::resolve::{IdResolver, IdSummary, RecordStatus, ResolveError};
+    use crate::EntrezDb;
+
+    const RESPONSE: &str = r#"<?xml version="1.0" encoding="UTF-8" ?>
+<!DOCTYPE eSummaryResult PUBLIC "-//NLM//DTD esummary v1 20041029//EN" "https://eutils.ncbi.nlm.nih.gov/eutils/dtd/20041029/esummary-v1.dtd">
+<eSummaryResult>
+<DocSum>
+	<Id>2519734237</Id>
+	<Item Name="Caption" Type="String">NZ_JARQWN010000024</Item>
+	<Item Name="Title" Type="String">Klebsiella pneumoniae strain 2023CK-00072 NODE_24, whole genome shotgun sequence</Item>
+	<Item Name="Extra" Type="String">gi|2519734237|ref|NZ_JARQWN010000024.1|</Item>
+	<Item Name="Gi" Type="Integer">2519734237</Item>
+	<Item Name="Status" Type="String">live</Item>
+	<Item Name="ReplacedBy" Type="String"></Item>
+</DocSum>
+<DocSum>
+	<Id>15</Id>
+	<Item Name="Caption" Type="String">X53813</Item>
+	<Item Name="Extra" Type="String">gi|15|emb|X53813.1|</Item>
+	<Item Name="Gi" Type="Integer">15</Item>
+	<Item Name="Status" Type="String">replaced</Item>
+	<Item Name="ReplacedBy" Type="String">X53814.1</Item>
+</DocSum>
+</eSummaryResult>"#;
+
+    #[test]
+    fn test_from_esummary() {
+        let summaries = IdSummary::from_esummary(RESPONSE).unwrap();
+        assert_eq!(summaries.len(), 2);
+
+        assert_eq!(summaries[0].gi, 2519734237);
+        assert_eq!(summaries[0].accession.accession.as_deref(), Some("NZ_JARQWN010000024"));
+        assert_eq!(summaries[0].accession.version, Some(1));
+        assert_eq!(summaries[0].status, RecordStatus::Live);
+
+        assert_eq!(summaries[1].status, RecordStatus::Replaced(Some("X53814.1".to_string())));
+
+        assert!(IdSummary::from_esummary("<eSearchResult></eSearchResult>").is_err());
+    }
+
+    #[test]
+    fn test_cached_resolution() {
+        let mut resolver = IdResolver::new(EntrezDb::Nucleotide);
+        resolver.insert(&[], IdSummary::from_esummary(RESPONSE).unwrap());
+        assert_eq!(resolver.cached(), 2);
+
+        // served from the cache without a request
+        assert_eq!(resolver.resolve_accession("NZ_JARQWN010000024"), Ok(2519734237));
+        assert_eq!(resolver.resolve_accession("NZ_JARQWN010000024.1"), Ok(2519734237));
+        let acc = resolver.resolve_gi(2519734237).unwrap();
+        assert_eq!(acc.accession.as_deref(), Some("NZ_JARQWN010000024"));
+
+        match resolver.resolve_gi(15) {
+            Err(ResolveError::Inactive { id, status }) => {
+                assert_eq!(id, "15");
+                assert_eq!(status, RecordStatus::Replaced(Some("X53814.1".to_string())));
+            }
+            _ => panic!("Replaced record should not resolve"),
+        }
+    }
+
+    #[test]
+    fn test_latest_accession() {
+        const REPLACEMENTS: &str = r#"<eSummaryResult>
+<DocSum>
+	<Id>16</Id>
+	<Item Name="Caption" Type="String">X53814</Item>
+	<Item Name="Extra" Type="String">gi|16|emb|X53814.1|</Item>
+	<Item Name="Gi" Type="Integer">16</Item>
+	<Item Name="Status" Type="String">replaced</Item>
+	<Item Name="ReplacedBy" Type="String">X53814.2</Item>
+</DocSum>
+<DocSum>
+	<Id>17</Id>
+	<Item Name="Caption" Type="String">X53814</Item>
+	<Item Name="Extra" Type="String">gi|17|emb|X53814.2|</Item>
+	<Item Name="Gi" Type="Integer">17</Item>
+	<Item Name="Status" Type="String">live</Item>
+</DocSum>
+</eSummaryResult>"#;
+
+        let mut resolver = IdResolver::new(EntrezDb::Nucleotide);
+        resolver.insert(&[], IdSummary::from_esummary(RESPONSE).unwrap());
+        resolver.insert(&[], IdSummary::from_esummary(REPLACEMENTS).unwrap());
+
+        let latest = resolver.latest_accession("X53813.1").unwrap();
+        assert_eq!(latest.accession.as_deref(), Some("X53814"));
+        assert_eq!(latest.version, Some(2));
+
+        // live records resolve to themselves
+        let latest = resolver.latest_accession("NZ_JARQWN010000024").unwrap();
+        assert_eq!(latest.version, Some(1));
+
+        // requesting a replaced version does not shadow the latest one
+        let mut resolver = IdResolver::new(EntrezDb::Nucleotide);
+        let mut summaries = IdSummary::from_esummary(REPLACEMENTS).unwrap();
+        let replaced = summaries.remove(0);
+        resolver.insert(&["X53814".to_string()], summaries);
+        resolver.insert(&["X53814.1".to_string()], vec![replaced]);
+        assert_eq!(resolver.resolve_accession("X53814"), Ok(17));
+        assert!(resolver.resolve_accession("X53814.1").is_err());
+        assert_eq!(resolver.cached(), 2);
+    }
+}
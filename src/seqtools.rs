@@ -0,0 +1,126 @@
+//! Common sequence manipulations
+//!
+//! Functions operate on one-letter IUPAC strings, such as those returned by
+//! [`crate::seq::SeqInst::residues()`] or [`crate::seq::BioSeq::extract()`].
+//! Case is preserved and ambiguity codes are handled according to the
+//! [IUPAC nucleotide code](https://www.bioinformatics.org/sms/iupac.html).
+
+/// Complement a single IUPAC nucleotide
+///
+/// Ambiguity codes are complemented to the code representing the complementary
+/// set of bases (eg: `R` (A/G) becomes `Y` (C/T)). Self-complementary codes (`S`,
+/// `W`, `N`) and gaps are returned unchanged. `U` is complemented to `A`.
+pub fn complement(base: char) -> char {
+    let complement = match base.to_ascii_uppercase() {
+        'A' => 'T',
+        'T' | 'U' => 'A',
+        'G' => 'C',
+        'C' => 'G',
+        'R' => 'Y',
+        'Y' => 'R',
+        'K' => 'M',
+        'M' => 'K',
+        'B' => 'V',
+        'V' => 'B',
+        'D' => 'H',
+        'H' => 'D',
+        other => other,
+    };
+    if base.is_ascii_lowercase() {
+        complement.to_ascii_lowercase()
+    } else {
+        complement
+    }
+}
+
+/// Complement each base of `seq` without reversing
+pub fn complement_seq(seq: &str) -> String {
+    seq.chars().map(complement).collect()
+}
+
+/// Reverse complement an IUPAC nucleotide string
+pub fn reverse_complement(seq: &str) -> String {
+    seq.chars().rev().map(complement).collect()
+}
+
+/// Transcribe DNA into RNA by replacing `T` with `U`
+pub fn transcribe(seq: &str) -> String {
+    seq.chars()
+        .map(|c| match c {
+            'T' => 'U',
+            't' => 'u',
+            other => other,
+        })
+        .collect()
+}
+
+/// Reverse transcribe RNA into DNA by replacing `U` with `T`
+pub fn back_transcribe(seq: &str) -> String {
+    seq.chars()
+        .map(|c| match c {
+            'U' => 'T',
+            'u' => 't',
+            other => other,
+        })
+        .collect()
+}
+
+/// Fraction of G/C bases in `seq`
+///
+/// `S` (G or C) is counted as GC and `W` (A or T) as AT. Other ambiguity codes and
+/// gaps are excluded from the denominator since they cannot be assigned to either.
+///
+/// # Returns
+/// `None` if `seq` contains no informative bases
+pub fn gc_content(seq: &str) -> Option<f64> {
+    let mut gc = 0usize;
+    let mut total = 0usize;
+    for c in seq.chars() {
+        match c.to_ascii_uppercase() {
+            'G' | 'C' | 'S' => {
+                gc += 1;
+                total += 1;
+            }
+            'A' | 'T' | 'U' | 'W' => total += 1,
+            _ => (),
+        }
+    }
+    if total == 0 {
+        None
+    } else {
+        Some(gc as f64 / total as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::seqtools::{back_transcribe, complement, gc_content, reverse_complement, transcribe};
+
+    #[test]
+    fn test_complement() {
+        assert_eq!(complement('A'), 'T');
+        assert_eq!(complement('r'), 'y');
+        assert_eq!(complement('N'), 'N');
+        assert_eq!(complement('-'), '-');
+    }
+
+    #[test]
+    fn test_reverse_complement() {
+        assert_eq!(reverse_complement("ATGCrykmbvdhSWN"), "NWSdhbvkmryGCAT");
+        assert_eq!(reverse_complement(&reverse_complement("ACGTNRYacgt")), "ACGTNRYacgt");
+    }
+
+    #[test]
+    fn test_transcription() {
+        assert_eq!(transcribe("ATGtta"), "AUGuua");
+        assert_eq!(back_transcribe("AUGuua"), "ATGtta");
+    }
+
+    #[test]
+    fn test_gc_content() {
+        assert_eq!(gc_content("GGCC"), Some(1.0));
+        assert_eq!(gc_content("ATGC"), Some(0.5));
+        assert_eq!(gc_content("ATNN"), Some(0.0));
+        assert_eq!(gc_content("NNN"), None);
+    }
+}
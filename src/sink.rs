@@ -0,0 +1,237 @@
+//! Writing of records to disk as they are retrieved
+//!
+//! Harvesting a large result set should not require holding it in memory. A
+//! [`RecordSink`] receives each top-level [`SeqEntry`] as soon as it is parsed, and
+//! writes it out before the next one is read:
+//!
+//! - [`FastaSink`]: residues of every sequence
+//! - [`GffSink`]: features of every sequence, as a single GFF3 document
+//! - [`NdjsonSink`]: one JSON object per entry and line (with the `json` feature)
+//! - [`XmlDirSink`]: one XML file per entry within a directory, as received
+//!
+//! [`HistorySession::download()`](crate::history::HistorySession::download) streams
+//! the pages of a stored result set into a sink.
+//!
+//! # Example
+//! ```ignore
+//! let mut session = HistorySession::search(EntrezDb::Nucleotide, "Myotis[Organism]");
+//! let mut sink = FastaSink::create("myotis.fasta")?;
+//! session.download(&FetchRequest::new(EntrezDb::Nucleotide, ""), 500, &mut sink)?;
+//! ```
+
+use crate::gff::{best_id, write_bioseq};
+#[cfg(feature = "json")]
+use crate::ndjson::NdjsonWriter;
+use crate::record::Record;
+use crate::seqset::SeqEntry;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// Residues per line of FASTA files
+pub const FASTA_LINE_WIDTH: usize = 70;
+
+/// Destination of entries retrieved one at a time
+pub trait RecordSink {
+    /// Write `entry`, given with its original XML when read from a response
+    fn write_entry(&mut self, entry: &SeqEntry, xml: Option<&str>) -> Result<(), String>;
+
+    /// Flush what was written, once every entry has been written
+    fn finish(&mut self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// Create the file at `path` for buffered writing
+fn create(path: &Path) -> Result<BufWriter<File>, String> {
+    File::create(path)
+        .map(BufWriter::new)
+        .map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+/// Writes the residues of every sequence as FASTA
+///
+/// Sequences without residues (eg: segmented or virtual sequences) are skipped.
+pub struct FastaSink<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> FastaSink<W> {
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl FastaSink<BufWriter<File>> {
+    pub fn create(path: impl AsRef<Path>) -> Result<Self, String> {
+        create(path.as_ref()).map(Self::new)
+    }
+}
+
+impl<W: Write> RecordSink for FastaSink<W> {
+    fn write_entry(&mut self, entry: &SeqEntry, _xml: Option<&str>) -> Result<(), String> {
+        for record in Record::from_entry(entry) {
+            let Some(sequence) = &record.sequence else {
+                continue;
+            };
+            let mut fasta = format!(">{}", record.id);
+            if let Some(description) = &record.description {
+                fasta.push(' ');
+                fasta.push_str(description);
+            }
+            fasta.push('\n');
+            for line in sequence.as_bytes().chunks(FASTA_LINE_WIDTH) {
+                fasta.push_str(std::str::from_utf8(line).map_err(|e| e.to_string())?);
+                fasta.push('\n');
+            }
+            self.writer.write_all(fasta.as_bytes()).map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<(), String> {
+        self.writer.flush().map_err(|e| e.to_string())
+    }
+}
+
+/// Writes the features of every sequence as a single GFF3 document
+pub struct GffSink<W: Write> {
+    writer: W,
+    started: bool,
+}
+
+impl<W: Write> GffSink<W> {
+    pub fn new(writer: W) -> Self {
+        Self { writer, started: false }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    /// Write the header, unless already written
+    fn start(&mut self) -> Result<(), String> {
+        if !self.started {
+            self.writer.write_all(b"##gff-version 3\n").map_err(|e| e.to_string())?;
+            self.started = true;
+        }
+        Ok(())
+    }
+}
+
+impl GffSink<BufWriter<File>> {
+    pub fn create(path: impl AsRef<Path>) -> Result<Self, String> {
+        create(path.as_ref()).map(Self::new)
+    }
+}
+
+impl<W: Write> RecordSink for GffSink<W> {
+    fn write_entry(&mut self, entry: &SeqEntry, _xml: Option<&str>) -> Result<(), String> {
+        self.start()?;
+        let mut gff = String::new();
+        match entry {
+            SeqEntry::Seq(bioseq) => write_bioseq(&mut gff, bioseq),
+            SeqEntry::Set(set) => set.bioseqs().into_iter().for_each(|bioseq| write_bioseq(&mut gff, bioseq)),
+        }
+        self.writer.write_all(gff.as_bytes()).map_err(|e| e.to_string())
+    }
+
+    /// Write the header if no entry was written, so that the document is valid
+    fn finish(&mut self) -> Result<(), String> {
+        self.start()?;
+        self.writer.flush().map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(feature = "json")]
+/// Writes each entry as a JSON object on its own line, see [`crate::ndjson`]
+pub struct NdjsonSink<W: Write> {
+    writer: NdjsonWriter<W>,
+}
+
+#[cfg(feature = "json")]
+impl<W: Write> NdjsonSink<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer: NdjsonWriter::new(writer),
+        }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer.into_inner()
+    }
+}
+
+#[cfg(feature = "json")]
+impl NdjsonSink<BufWriter<File>> {
+    pub fn create(path: impl AsRef<Path>) -> Result<Self, String> {
+        create(path.as_ref()).map(Self::new)
+    }
+}
+
+#[cfg(feature = "json")]
+impl<W: Write> RecordSink for NdjsonSink<W> {
+    fn write_entry(&mut self, entry: &SeqEntry, _xml: Option<&str>) -> Result<(), String> {
+        self.writer.write(entry)
+    }
+
+    fn finish(&mut self) -> Result<(), String> {
+        self.writer.flush()
+    }
+}
+
+/// Writes each entry to its own XML file within a directory
+///
+/// Files are named by the id of the first sequence of the entry (eg:
+/// "NZ_JARQWN010000024.1.xml"), with characters not allowed in file names replaced by
+/// "_". Entries are written as received, so only entries given with their XML can be
+/// written.
+pub struct XmlDirSink {
+    dir: PathBuf,
+    /// files written so far
+    pub paths: Vec<PathBuf>,
+}
+
+impl XmlDirSink {
+    /// Write files to `dir`, which is created if missing
+    pub fn create(dir: impl AsRef<Path>) -> Result<Self, String> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            paths: Vec::new(),
+        })
+    }
+
+    /// Path of the file for the entry identified by `id`
+    pub fn path(&self, id: &str) -> PathBuf {
+        let name: String = id
+            .chars()
+            .map(|c| match c {
+                '/' | '\\' | ':' | '|' | '*' | '?' | '"' | '<' | '>' => '_',
+                c => c,
+            })
+            .collect();
+        self.dir.join(format!("{}.xml", name))
+    }
+}
+
+impl RecordSink for XmlDirSink {
+    fn write_entry(&mut self, entry: &SeqEntry, xml: Option<&str>) -> Result<(), String> {
+        let xml = xml.ok_or("Entry XML is required to write XML files")?;
+        let first = match entry {
+            SeqEntry::Seq(bioseq) => Some(bioseq),
+            SeqEntry::Set(set) => set.bioseqs().first().copied(),
+        };
+        let id = first.map_or(format!("entry_{}", self.paths.len() + 1), best_id);
+        let path = self.path(&id);
+        std::fs::write(&path, format!("<?xml version=\"1.0\"?>\n{}\n", xml.trim()))
+            .map_err(|e| format!("{}: {}", path.display(), e))?;
+        self.paths.push(path);
+        Ok(())
+    }
+}
@@ -0,0 +1,403 @@
+//! Sequence Read Archive metadata
+//!
+//! EFetch on [`EntrezDb::Sra`] returns an `EXPERIMENT_PACKAGE_SET` document, which is
+//! parsed into [`ExperimentPackage`] by [`parse_experiment_packages()`]. Each package
+//! describes a single experiment (library and sequencing platform) along with its
+//! study, sample and runs. The `runinfo` report gives a flat summary of each run,
+//! and is parsed into [`RunInfo`] by [`parse_runinfo()`].
+//!
+//! Unlike other records, SRA metadata is not described by the NCBI ASN.1
+//! specification, so only the fields required to locate and describe runs are kept.
+
+use crate::{EntrezDb, FetchRequest};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+/// An experiment along with its study, sample and runs
+pub struct ExperimentPackage {
+    pub experiment: Experiment,
+    pub study: Option<Study>,
+    pub sample: Option<Sample>,
+    pub runs: Vec<Run>,
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+pub struct Experiment {
+    /// eg: "SRX3357932"
+    pub accession: String,
+    pub title: Option<String>,
+    /// accession of the parent [`Study`]
+    pub study_ref: Option<String>,
+    /// accession of the sequenced [`Sample`]
+    pub sample_ref: Option<String>,
+    pub library: Library,
+    pub platform: Platform,
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+/// How the sequencing library was prepared
+pub struct Library {
+    pub name: Option<String>,
+    /// eg: "WGS", "RNA-Seq", "AMPLICON"
+    pub strategy: Option<String>,
+    /// eg: "GENOMIC", "TRANSCRIPTOMIC"
+    pub source: Option<String>,
+    /// eg: "RANDOM", "PCR"
+    pub selection: Option<String>,
+    pub layout: LibraryLayout,
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+pub enum LibraryLayout {
+    #[default]
+    Unknown,
+    Single,
+    Paired {
+        /// expected insert size
+        nominal_length: Option<u64>,
+        /// standard deviation of the insert size
+        nominal_sdev: Option<f64>,
+    },
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+pub struct Platform {
+    /// eg: "ILLUMINA", "OXFORD_NANOPORE"
+    pub name: Option<String>,
+    /// eg: "Illumina MiSeq"
+    pub instrument_model: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+pub struct Study {
+    /// eg: "SRP123456"
+    pub accession: String,
+    pub title: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+pub struct Sample {
+    /// eg: "SRS2654321"
+    pub accession: String,
+    pub title: Option<String>,
+    pub taxon_id: Option<u64>,
+    pub scientific_name: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+/// A single sequencing run
+pub struct Run {
+    /// eg: "SRR6252121"
+    pub accession: String,
+    pub total_spots: Option<u64>,
+    pub total_bases: Option<u64>,
+    /// size in bytes of the SRA file
+    pub size: Option<u64>,
+    pub published: Option<String>,
+    pub files: Vec<SraFile>,
+}
+
+impl Run {
+    /// Normalized SRA file, which is read by the SRA Toolkit
+    pub fn sra_file(&self) -> Option<&SraFile> {
+        self.files
+            .iter()
+            .find(|file| file.semantic_name.as_deref() == Some("run"))
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+/// Downloadable file of a [`Run`]
+pub struct SraFile {
+    pub filename: String,
+    pub url: Option<String>,
+    pub size: Option<u64>,
+    pub md5: Option<String>,
+    /// eg: "run" for the SRA file, "fastq" for submitted reads
+    pub semantic_name: Option<String>,
+}
+
+fn attributes(e: &BytesStart) -> HashMap<String, String> {
+    e.attributes()
+        .flatten()
+        .filter_map(|attr| {
+            let key = String::from_utf8(attr.key.as_ref().to_vec()).ok()?;
+            let value = attr.unescape_value().ok()?.to_string();
+            Some((key, value))
+        })
+        .collect()
+}
+
+/// Parse an `EXPERIMENT_PACKAGE_SET` document
+pub fn parse_experiment_packages(response: &str) -> Result<Vec<ExperimentPackage>, String> {
+    let mut reader = Reader::from_str(response);
+    reader.trim_text(true);
+
+    let mut packages: Vec<ExperimentPackage> = Vec::new();
+    let mut path: Vec<Vec<u8>> = Vec::new();
+
+    loop {
+        let (e, empty) = match reader.read_event() {
+            Ok(Event::Start(e)) => (e, false),
+            Ok(Event::Empty(e)) => (e, true),
+            Ok(Event::Text(text)) => {
+                let text = text.unescape().map_err(|e| format!("{:?}", e))?.to_string();
+                if let Some(package) = packages.last_mut() {
+                    read_text(package, &path, text);
+                }
+                continue;
+            }
+            Ok(Event::End(_)) => {
+                path.pop();
+                continue;
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(format!("XML parsing error: {:?}", e)),
+            _ => continue,
+        };
+
+        let parent = path.last().map(|p| p.as_slice());
+        if e.name().as_ref() == b"EXPERIMENT_PACKAGE" {
+            packages.push(ExperimentPackage::default());
+        } else if let Some(package) = packages.last_mut() {
+            read_element(package, parent, &e);
+        }
+        if !empty {
+            path.push(e.name().as_ref().to_vec());
+        }
+    }
+
+    if packages.is_empty() {
+        return Err("No <EXPERIMENT_PACKAGE> found in response.".to_string());
+    }
+    Ok(packages)
+}
+
+/// Fill `package` from the attributes of an element
+fn read_element(package: &mut ExperimentPackage, parent: Option<&[u8]>, e: &BytesStart) {
+    let mut attributes = attributes(e);
+    let mut take = |key: &str| attributes.remove(key);
+    let experiment = &mut package.experiment;
+
+    match (parent, e.name().as_ref()) {
+        (Some(b"EXPERIMENT_PACKAGE"), b"EXPERIMENT") => {
+            experiment.accession = take("accession").unwrap_or_default()
+        }
+        (Some(b"EXPERIMENT"), b"STUDY_REF") => experiment.study_ref = take("accession"),
+        (Some(b"DESIGN"), b"SAMPLE_DESCRIPTOR") => experiment.sample_ref = take("accession"),
+        (Some(b"LIBRARY_LAYOUT"), b"SINGLE") => experiment.library.layout = LibraryLayout::Single,
+        (Some(b"LIBRARY_LAYOUT"), b"PAIRED") => {
+            experiment.library.layout = LibraryLayout::Paired {
+                nominal_length: take("NOMINAL_LENGTH").and_then(|n| n.parse().ok()),
+                nominal_sdev: take("NOMINAL_SDEV").and_then(|n| n.parse().ok()),
+            }
+        }
+        (Some(b"PLATFORM"), name) => {
+            experiment.platform.name = String::from_utf8(name.to_vec()).ok()
+        }
+        (Some(b"EXPERIMENT_PACKAGE"), b"STUDY") => {
+            package.study = Some(Study {
+                accession: take("accession").unwrap_or_default(),
+                title: None,
+            })
+        }
+        (Some(b"EXPERIMENT_PACKAGE"), b"SAMPLE") => {
+            package.sample = Some(Sample {
+                accession: take("accession").unwrap_or_default(),
+                ..Sample::default()
+            })
+        }
+        (Some(b"RUN_SET"), b"RUN") => package.runs.push(Run {
+            accession: take("accession").unwrap_or_default(),
+            total_spots: take("total_spots").and_then(|n| n.parse().ok()),
+            total_bases: take("total_bases").and_then(|n| n.parse().ok()),
+            size: take("size").and_then(|n| n.parse().ok()),
+            published: take("published"),
+            files: Vec::new(),
+        }),
+        (Some(b"SRAFiles"), b"SRAFile") => {
+            if let Some(run) = package.runs.last_mut() {
+                run.files.push(SraFile {
+                    filename: take("filename").unwrap_or_default(),
+                    url: take("url"),
+                    size: take("size").and_then(|n| n.parse().ok()),
+                    md5: take("md5"),
+                    semantic_name: take("semantic_name"),
+                })
+            }
+        }
+        _ => (),
+    }
+}
+
+/// Fill `package` from the text of the element at the end of `path`
+fn read_text(package: &mut ExperimentPackage, path: &[Vec<u8>], text: String) {
+    let element = |depth: usize| {
+        path.len()
+            .checked_sub(depth + 1)
+            .and_then(|i| path.get(i))
+            .map(|p| p.as_slice())
+    };
+    let experiment = &mut package.experiment;
+    let library = &mut experiment.library;
+
+    match (element(2), element(1), element(0)) {
+        (_, Some(b"EXPERIMENT"), Some(b"TITLE")) => experiment.title = Some(text),
+        (_, Some(b"LIBRARY_DESCRIPTOR"), Some(b"LIBRARY_NAME")) => library.name = Some(text),
+        (_, Some(b"LIBRARY_DESCRIPTOR"), Some(b"LIBRARY_STRATEGY")) => {
+            library.strategy = Some(text)
+        }
+        (_, Some(b"LIBRARY_DESCRIPTOR"), Some(b"LIBRARY_SOURCE")) => library.source = Some(text),
+        (_, Some(b"LIBRARY_DESCRIPTOR"), Some(b"LIBRARY_SELECTION")) => {
+            library.selection = Some(text)
+        }
+        (Some(b"PLATFORM"), _, Some(b"INSTRUMENT_MODEL")) => {
+            experiment.platform.instrument_model = Some(text)
+        }
+        (Some(b"STUDY"), Some(b"DESCRIPTOR"), Some(b"STUDY_TITLE")) => {
+            if let Some(study) = package.study.as_mut() {
+                study.title = Some(text);
+            }
+        }
+        (_, Some(b"SAMPLE"), Some(b"TITLE")) => {
+            if let Some(sample) = package.sample.as_mut() {
+                sample.title = Some(text);
+            }
+        }
+        (_, Some(b"SAMPLE_NAME"), Some(b"TAXON_ID")) => {
+            if let Some(sample) = package.sample.as_mut() {
+                sample.taxon_id = text.trim().parse().ok();
+            }
+        }
+        (_, Some(b"SAMPLE_NAME"), Some(b"SCIENTIFIC_NAME")) => {
+            if let Some(sample) = package.sample.as_mut() {
+                sample.scientific_name = Some(text);
+            }
+        }
+        _ => (),
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+/// Row of the `runinfo` report
+///
+/// Commonly used columns are parsed into fields. All columns, including those
+/// that are empty, are available by header name via [`Self::get()`].
+pub struct RunInfo {
+    pub run: String,
+    pub experiment: Option<String>,
+    pub study: Option<String>,
+    pub bioproject: Option<String>,
+    pub sample: Option<String>,
+    pub biosample: Option<String>,
+    pub spots: Option<u64>,
+    pub bases: Option<u64>,
+    pub avg_length: Option<u64>,
+    pub size_mb: Option<u64>,
+    pub download_path: Option<String>,
+    pub library_strategy: Option<String>,
+    pub library_source: Option<String>,
+    pub library_selection: Option<String>,
+    pub library_layout: Option<String>,
+    pub platform: Option<String>,
+    pub model: Option<String>,
+    pub tax_id: Option<u64>,
+    pub scientific_name: Option<String>,
+    columns: HashMap<String, String>,
+}
+
+impl RunInfo {
+    /// Value of the column named `header`
+    pub fn get(&self, header: &str) -> Option<&str> {
+        self.columns.get(header).map(|value| value.as_str())
+    }
+
+    fn from_columns(columns: HashMap<String, String>) -> Result<Self, String> {
+        let text = |header: &str| columns.get(header).filter(|v| !v.is_empty()).cloned();
+        let num = |header: &str| text(header).and_then(|v| v.parse().ok());
+
+        Ok(Self {
+            run: text("Run").ok_or("Row has no value for \"Run\"".to_string())?,
+            experiment: text("Experiment"),
+            study: text("SRAStudy"),
+            bioproject: text("BioProject"),
+            sample: text("Sample"),
+            biosample: text("BioSample"),
+            spots: num("spots"),
+            bases: num("bases"),
+            avg_length: num("avgLength"),
+            size_mb: num("size_MB"),
+            download_path: text("download_path"),
+            library_strategy: text("LibraryStrategy"),
+            library_source: text("LibrarySource"),
+            library_selection: text("LibrarySelection"),
+            library_layout: text("LibraryLayout"),
+            platform: text("Platform"),
+            model: text("Model"),
+            tax_id: num("TaxID"),
+            scientific_name: text("ScientificName"),
+            columns,
+        })
+    }
+}
+
+/// Split a CSV line, honoring double-quoted fields
+fn split_csv(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+/// Parse the CSV returned by EFetch with `rettype=runinfo`
+///
+/// Responses for multiple ids may repeat the header line, which is skipped.
+pub fn parse_runinfo(response: &str) -> Result<Vec<RunInfo>, String> {
+    let mut lines = response.lines().filter(|line| !line.trim().is_empty());
+    let header = lines.next().ok_or("Empty runinfo response".to_string())?;
+    let headers = split_csv(header);
+    if headers.first().map(|h| h.as_str()) != Some("Run") {
+        return Err(format!("Unexpected runinfo header: {}", header));
+    }
+
+    lines
+        .filter(|line| *line != header)
+        .map(|line| {
+            let columns = headers.iter().cloned().zip(split_csv(line)).collect();
+            RunInfo::from_columns(columns)
+        })
+        .collect()
+}
+
+/// Fetch experiment packages for SRA UIDs or accessions
+pub fn fetch_sra<S: AsRef<str>>(ids: &[S]) -> Result<Vec<ExperimentPackage>, String> {
+    let response = FetchRequest::with_ids(EntrezDb::Sra, ids)
+        .retmode("xml")
+        .fetch_text()?;
+    parse_experiment_packages(response.as_str())
+}
+
+/// Fetch the `runinfo` report for SRA UIDs or accessions
+pub fn fetch_runinfo<S: AsRef<str>>(ids: &[S]) -> Result<Vec<RunInfo>, String> {
+    let response = FetchRequest::with_ids(EntrezDb::Sra, ids)
+        .rettype("runinfo")
+        .retmode("text")
+        .fetch_text()?;
+    parse_runinfo(response.as_str())
+}
@@ -0,0 +1,288 @@
+//! Summary statistics of parsed records, for quality control
+//!
+//! [`SeqStats`] describes a single [`BioSeq`] (length, N content, GC content, and
+//! feature counts), and [`SetStats`] aggregates those of every sequence of a
+//! [`BioSeqSet`], adding assembly contiguity (N50 and L50).
+//!
+//! Feature counts are tallied with the same definitions as the
+//! `##Genome-Annotation-Data-START##` structured comment of annotated genomes, and
+//! [`FeatureCounts::annotation_totals()`] gives them under the same names (eg:
+//! "Genes (total)", "Pseudo Genes (total)"), so that they can be checked against
+//! the totals reported by NCBI.
+//!
+//! # Example
+//! ```ignore
+//! let stats = SetStats::from_set(&set);
+//! println!("{} contigs, {} bp, N50 {:?}", stats.sequences, stats.total_length, stats.n50);
+//! for (name, count) in stats.features.annotation_totals() {
+//!     println!("{}: {}", name, count);
+//! }
+//! ```
+
+use crate::gff::best_id;
+use crate::seq::{BioSeq, Mol};
+use crate::seqfeat::{RnaRefType, SeqFeat, SeqFeatData};
+use crate::seqset::BioSeqSet;
+use crate::tbl::feature_key;
+use crate::visit::{walk, Visitor};
+use std::collections::BTreeMap;
+
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+/// Number of annotated features, by kind
+pub struct FeatureCounts {
+    /// features by INSDC key (eg: "gene", "CDS", "tRNA")
+    pub by_key: BTreeMap<String, usize>,
+    /// gene features, including pseudogenes
+    pub genes: usize,
+    /// gene features marked as pseudo
+    pub pseudo_genes: usize,
+    pub cds: usize,
+    /// coding regions with a protein product
+    pub cds_with_protein: usize,
+    pub rrna: usize,
+    pub trna: usize,
+    pub ncrna: usize,
+}
+
+impl FeatureCounts {
+    /// Tally a single feature
+    pub fn add(&mut self, feat: &SeqFeat) {
+        *self.by_key.entry(feature_key(feat)).or_default() += 1;
+        match &feat.data {
+            SeqFeatData::Gene(gene) => {
+                self.genes += 1;
+                if gene.pseudo || feat.pseudo == Some(true) {
+                    self.pseudo_genes += 1;
+                }
+            }
+            SeqFeatData::CdRegion(_) => {
+                self.cds += 1;
+                if feat.product.is_some() {
+                    self.cds_with_protein += 1;
+                }
+            }
+            SeqFeatData::RNA(rna) => match rna.r#type {
+                RnaRefType::rRNA => self.rrna += 1,
+                RnaRefType::tRNA => self.trna += 1,
+                RnaRefType::ncRNA | RnaRefType::snRNA | RnaRefType::scRNA | RnaRefType::snoRNA => {
+                    self.ncrna += 1
+                }
+                _ => (),
+            },
+            _ => (),
+        }
+    }
+
+    /// Combine with the counts of another sequence
+    pub fn merge(&mut self, other: &Self) {
+        for (key, count) in other.by_key.iter() {
+            *self.by_key.entry(key.clone()).or_default() += count;
+        }
+        self.genes += other.genes;
+        self.pseudo_genes += other.pseudo_genes;
+        self.cds += other.cds;
+        self.cds_with_protein += other.cds_with_protein;
+        self.rrna += other.rrna;
+        self.trna += other.trna;
+        self.ncrna += other.ncrna;
+    }
+
+    /// Coding regions without a protein product, which are annotated on pseudogenes
+    pub fn cds_without_protein(&self) -> usize {
+        self.cds - self.cds_with_protein
+    }
+
+    /// Counts named as in the `Genome-Annotation-Data` structured comment
+    pub fn annotation_totals(&self) -> Vec<(&'static str, usize)> {
+        vec![
+            ("Genes (total)", self.genes),
+            ("CDSs (total)", self.cds),
+            ("Genes (coding)", self.cds_with_protein),
+            ("CDSs (with protein)", self.cds_with_protein),
+            ("Genes (RNA)", self.rrna + self.trna + self.ncrna),
+            ("rRNAs", self.rrna),
+            ("tRNAs", self.trna),
+            ("ncRNAs", self.ncrna),
+            ("Pseudo Genes (total)", self.pseudo_genes),
+            ("CDSs (without protein)", self.cds_without_protein()),
+        ]
+    }
+}
+
+impl Visitor for FeatureCounts {
+    fn visit_seq_feat(&mut self, feat: &SeqFeat) {
+        self.add(feat);
+    }
+}
+
+/// Number of G/C, A/T, and N residues of a nucleic acid sequence
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+struct Composition {
+    gc: u64,
+    at: u64,
+    n: u64,
+}
+
+impl Composition {
+    /// Ambiguity codes other than `S` (G or C), `W` (A or T) and `N` are not counted,
+    /// as by [`crate::seqtools::gc_content()`]
+    fn of(residues: &str) -> Self {
+        let mut composition = Self::default();
+        for c in residues.chars() {
+            match c.to_ascii_uppercase() {
+                'G' | 'C' | 'S' => composition.gc += 1,
+                'A' | 'T' | 'U' | 'W' => composition.at += 1,
+                'N' => composition.n += 1,
+                _ => (),
+            }
+        }
+        composition
+    }
+
+    fn gc_content(&self) -> Option<f64> {
+        match self.gc + self.at {
+            0 => None,
+            total => Some(self.gc as f64 / total as f64),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Debug, Default)]
+/// Statistics of a single [`BioSeq`]
+pub struct SeqStats {
+    /// best identifier of the sequence (eg: "NM_000546.6")
+    pub id: String,
+    pub mol: Option<Mol>,
+    pub length: Option<u64>,
+    /// number of `N` residues, if the residues of a nucleic acid are given
+    pub n_count: Option<u64>,
+    /// fraction of G/C bases, if the residues of a nucleic acid are given
+    pub gc_content: Option<f64>,
+    /// features annotated on the sequence itself
+    pub features: FeatureCounts,
+    composition: Option<Composition>,
+}
+
+impl SeqStats {
+    pub fn from_bioseq(bioseq: &BioSeq) -> Self {
+        let mut stats = Self {
+            id: best_id(bioseq),
+            ..Self::default()
+        };
+        if let Some(inst) = &bioseq.inst {
+            stats.mol = Some(inst.mol.clone());
+            stats.length = inst.length;
+            if !matches!(inst.mol, Mol::AA) {
+                stats.composition = inst.residues().ok().map(|residues| Composition::of(&residues));
+            }
+        }
+        if let Some(composition) = &stats.composition {
+            stats.n_count = Some(composition.n);
+            stats.gc_content = composition.gc_content();
+        }
+        for annot in bioseq.annot.iter().flatten() {
+            for feat in annot.features() {
+                stats.features.add(feat);
+            }
+        }
+        stats
+    }
+
+    /// Whether the sequence is a nucleic acid
+    pub fn is_nucleic(&self) -> bool {
+        !matches!(self.mol, Some(Mol::AA))
+    }
+}
+
+#[derive(Clone, PartialEq, Debug, Default)]
+/// Statistics of all sequences of a [`BioSeqSet`]
+///
+/// Lengths, composition and contiguity only account for nucleic acid sequences, so
+/// that the proteins of a `nuc-prot` set do not affect them.
+pub struct SetStats {
+    /// number of nucleic acid sequences
+    pub sequences: usize,
+    /// number of protein sequences
+    pub proteins: usize,
+    /// total length of nucleic acid sequences
+    pub total_length: u64,
+    /// number of `N` residues of the nucleic acid sequences whose residues are given
+    pub n_count: Option<u64>,
+    /// fraction of G/C bases of the nucleic acid sequences whose residues are given
+    pub gc_content: Option<f64>,
+    /// length such that sequences at least this long cover half of [`Self::total_length`]
+    pub n50: Option<u64>,
+    /// smallest number of sequences covering half of [`Self::total_length`]
+    pub l50: Option<usize>,
+    /// features annotated anywhere in the set, including set level annotations
+    pub features: FeatureCounts,
+    pub per_seq: Vec<SeqStats>,
+}
+
+impl SetStats {
+    pub fn from_set(set: &BioSeqSet) -> Self {
+        let per_seq: Vec<SeqStats> = set.bioseqs().into_iter().map(SeqStats::from_bioseq).collect();
+
+        let mut stats = Self::default();
+        walk(set, &mut stats.features);
+
+        let mut composition: Option<Composition> = None;
+        let mut lengths = Vec::new();
+        for seq in per_seq.iter() {
+            if !seq.is_nucleic() {
+                stats.proteins += 1;
+                continue;
+            }
+            stats.sequences += 1;
+            if let Some(length) = seq.length {
+                lengths.push(length);
+            }
+            if let Some(seq) = &seq.composition {
+                let total = composition.get_or_insert_with(Composition::default);
+                total.gc += seq.gc;
+                total.at += seq.at;
+                total.n += seq.n;
+            }
+        }
+        stats.total_length = lengths.iter().sum();
+        stats.n_count = composition.map(|composition| composition.n);
+        stats.gc_content = composition.and_then(|composition| composition.gc_content());
+        (stats.n50, stats.l50) = n50(&mut lengths);
+        stats.per_seq = per_seq;
+        stats
+    }
+}
+
+/// N50 and L50 of `lengths`
+fn n50(lengths: &mut [u64]) -> (Option<u64>, Option<usize>) {
+    lengths.sort_unstable_by(|a, b| b.cmp(a));
+    let total: u64 = lengths.iter().sum();
+    let mut covered = 0;
+    for (i, length) in lengths.iter().enumerate() {
+        covered += length;
+        if covered * 2 >= total {
+            return (Some(*length), Some(i + 1));
+        }
+    }
+    (None, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::stats::{n50, Composition};
+
+    #[test]
+    fn test_n50() {
+        assert_eq!(n50(&mut [2, 2, 2, 3, 3, 4, 8, 8]), (Some(8), Some(2)));
+        assert_eq!(n50(&mut [10]), (Some(10), Some(1)));
+        assert_eq!(n50(&mut []), (None, None));
+    }
+
+    #[test]
+    fn test_composition() {
+        let composition = Composition::of("ACGTNNnSW-");
+        assert_eq!((composition.gc, composition.at, composition.n), (3, 3, 3));
+        assert_eq!(composition.gc_content(), Some(0.5));
+        assert_eq!(Composition::of("NNN").gc_content(), None);
+    }
+}
@@ -0,0 +1,165 @@
+//! Summaries of macromolecular structures
+//!
+//! Records of [`EntrezDb::Structure`] are MMDB entries, each derived from a single
+//! PDB entry. [`StructureSummary`] gives the ESummary description of an entry, and
+//! connects it to the [`PDBSeqId`]'s which identify the chains of the entry as
+//! sequences. Proteins can be linked to structures either through their PDB ids
+//! ([`fetch_bioseq_structures()`]) or through ELink ([`fetch_linked_structures()`]).
+
+use crate::eutils::{read_docsums, DocSumItem};
+use crate::seq::BioSeq;
+use crate::seqloc::{PDBSeqId, SeqId};
+use crate::{build_summary_url, fetch_protein_structures, fetch_search, http_get, EntrezDb};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+/// Summary of an MMDB entry, as given by ESummary on [`EntrezDb::Structure`]
+pub struct StructureSummary {
+    /// MMDB id, which is the Entrez UID
+    pub mmdb_id: u64,
+    /// PDB accession (eg: "1TUP")
+    pub pdb_acc: String,
+    pub description: Option<String>,
+    /// eg: "X-Ray Diffraction", "Solution NMR"
+    pub method: Option<String>,
+    /// resolution in angstroms, if applicable to the method
+    pub resolution: Option<f64>,
+    /// eg: "Complex (Antioncogene/DNA)"
+    pub class: Option<String>,
+    /// eg: "1995/07/10 00:00"
+    pub release_date: Option<String>,
+    pub organisms: Vec<String>,
+    pub protein_count: Option<u64>,
+    pub dna_count: Option<u64>,
+    pub rna_count: Option<u64>,
+    /// ligand codes, as given by PDB
+    pub ligands: Vec<String>,
+}
+
+impl StructureSummary {
+    /// Parse all `DocSum` elements of an ESummary response for [`EntrezDb::Structure`]
+    pub fn from_esummary(response: &str) -> Result<Vec<Self>, String> {
+        let docsums = read_docsums(response)?;
+        Ok(docsums
+            .into_iter()
+            .map(|(id, items)| Self::from_items(&id, &items))
+            .collect())
+    }
+
+    fn from_items(id: &str, items: &[DocSumItem]) -> Self {
+        let count = |name| DocSumItem::value(items, name).and_then(|n| n.parse().ok());
+        let organisms = DocSumItem::find(items, "OrganismList")
+            .map(|list| list.items.iter().map(|item| item.value.trim().to_string()).collect())
+            .unwrap_or_default();
+        let ligands = DocSumItem::value(items, "LigCode")
+            .iter()
+            .flat_map(|codes| codes.split(['|', ',', ' ']))
+            .filter(|code| !code.is_empty())
+            .map(str::to_string)
+            .collect();
+
+        Self {
+            mmdb_id: id.trim().parse().unwrap_or_default(),
+            pdb_acc: DocSumItem::value(items, "PdbAcc").unwrap_or_default(),
+            description: DocSumItem::value(items, "PdbDescr"),
+            method: DocSumItem::value(items, "ExpMethod"),
+            resolution: DocSumItem::value(items, "Resolution").and_then(|r| r.parse().ok()),
+            class: DocSumItem::value(items, "PdbClass"),
+            release_date: DocSumItem::value(items, "PdbReleaseDate"),
+            organisms,
+            protein_count: count("ProteinMoleculeCount"),
+            dna_count: count("DNAMoleculeCount"),
+            rna_count: count("RNAMoleculeCount"),
+            ligands,
+        }
+    }
+
+    /// Identifier of a chain of this structure
+    pub fn pdb_seq_id(&self, chain: Option<&str>) -> PDBSeqId {
+        PDBSeqId {
+            mol: self.pdb_acc.to_uppercase(),
+            rel: None,
+            chain_id: chain.map(str::to_string),
+        }
+    }
+
+    /// Whether `id` identifies this structure, or one of its chains
+    ///
+    /// PDB accessions are compared case-insensitively.
+    pub fn contains(&self, id: &PDBSeqId) -> bool {
+        self.pdb_acc.trim().eq_ignore_ascii_case(id.mol.trim())
+    }
+}
+
+/// PDB ids of `bioseq`
+pub fn pdb_ids(bioseq: &BioSeq) -> Vec<&PDBSeqId> {
+    bioseq
+        .id
+        .iter()
+        .filter_map(|id| match id {
+            SeqId::Pdb(id) => Some(id),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Fetch summaries of structures by MMDB id
+pub fn fetch_structure_summaries<S: AsRef<str>>(ids: &[S]) -> Result<Vec<StructureSummary>, String> {
+    let ids: Vec<&str> = ids.iter().map(|id| id.as_ref()).collect();
+    let response = http_get(&build_summary_url(EntrezDb::Structure, &ids.join(",")))?;
+    StructureSummary::from_esummary(&response)
+}
+
+/// Fetch summaries of the structures identified by PDB ids
+///
+/// Chains of the same entry share a single summary.
+pub fn fetch_pdb_structures(ids: &[&PDBSeqId]) -> Result<Vec<StructureSummary>, String> {
+    let mut accessions: Vec<String> = ids.iter().map(|id| id.mol.trim().to_uppercase()).collect();
+    accessions.sort();
+    accessions.dedup();
+    if accessions.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let term: Vec<String> = accessions.iter().map(|acc| format!("{}[pdbacc]", acc)).collect();
+    let result = fetch_search(EntrezDb::Structure, &term.join(" OR "))?;
+    if result.ids.is_empty() {
+        return Ok(Vec::new());
+    }
+    let summaries = fetch_structure_summaries(&result.ids)?;
+    Ok(summaries
+        .into_iter()
+        .filter(|summary| ids.iter().any(|id| summary.contains(id)))
+        .collect())
+}
+
+/// Fetch summaries of the structures that `bioseq` is a chain of
+pub fn fetch_bioseq_structures(bioseq: &BioSeq) -> Result<Vec<StructureSummary>, String> {
+    fetch_pdb_structures(&pdb_ids(bioseq))
+}
+
+/// Summaries of the structures linked to each protein in `ids`, in the same order as `ids`
+///
+/// Unlike [`fetch_bioseq_structures()`], this includes structures of similar proteins.
+pub fn fetch_linked_structures<S: AsRef<str>>(ids: &[S]) -> Result<Vec<Vec<StructureSummary>>, String> {
+    let links = fetch_protein_structures(ids)?;
+    let mut all: Vec<&String> = links.iter().flatten().collect();
+    all.sort();
+    all.dedup();
+    let summaries = if all.is_empty() {
+        Vec::new()
+    } else {
+        fetch_structure_summaries(&all)?
+    };
+
+    Ok(links
+        .iter()
+        .map(|linked| {
+            linked
+                .iter()
+                .filter_map(|id| summaries.iter().find(|s| s.mmdb_id.to_string() == *id))
+                .cloned()
+                .collect()
+        })
+        .collect())
+}
@@ -0,0 +1,431 @@
+//! Concise overviews of parsed records, for interactive exploration and debugging
+//!
+//! The `Debug` output of a [`BioSeqSet`] or [`Entrezgene`] includes every residue and
+//! qualifier, which makes it unreadable for real records. [`Summary`] instead gives a
+//! single line per object ([`Summary::summary()`]), or an indented tree of the
+//! contained objects ([`Summary::tree()`]), with ids, lengths, descriptor kinds and
+//! feature counts.
+//!
+//! # Example
+//! ```ignore
+//! println!("{}", set.tree());
+//! // Bioseq-set (nucprot, 2 entries)
+//! //   Bioseq NM_000546.6 (rna, 2512 bp, raw)
+//! //     descr: title, molinfo, pub x2
+//! //     annot: 3 features (gene: 1, CDS: 1, mRNA: 1)
+//! //   Bioseq NP_000537.3 (aa, 393 aa, raw)
+//! ```
+
+use crate::entrezgene::Entrezgene;
+use crate::gff::{best_id, seq_id_label};
+use crate::pmc::Article;
+use crate::seq::{BioSeq, Mol, SeqAnnot, SeqAnnotData, SeqDesc};
+use crate::seqfeat::{ModelEvidenceItem, SeqFeatSupport};
+use crate::seqset::{BioSeqSet, SeqEntry};
+use crate::tbl::feature_key;
+use std::fmt::{self, Write};
+
+const INDENT: &str = "  ";
+
+/// Objects which can describe themselves concisely
+pub trait Summary {
+    /// Single line description of `self`
+    fn summary(&self) -> String;
+
+    /// Write a line for `self`, followed by lines for its children, indented by `depth`
+    fn fmt_tree(&self, w: &mut dyn Write, depth: usize) -> fmt::Result {
+        writeln!(w, "{}{}", INDENT.repeat(depth), self.summary())
+    }
+
+    /// Hierarchical overview of `self` and its children
+    fn tree(&self) -> String {
+        let mut tree = String::new();
+        self.fmt_tree(&mut tree, 0).expect("writing to a String cannot fail");
+        tree
+    }
+}
+
+/// Occurrences of each name, in order of first occurrence
+fn count<'a>(names: impl Iterator<Item = &'a str>) -> Vec<(&'a str, usize)> {
+    let mut counts: Vec<(&str, usize)> = Vec::new();
+    for name in names {
+        match counts.iter_mut().find(|(seen, _)| *seen == name) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((name, 1)),
+        }
+    }
+    counts
+}
+
+/// eg: "title, pub x2"
+fn format_kinds(counts: &[(&str, usize)]) -> String {
+    counts
+        .iter()
+        .map(|(name, count)| match count {
+            1 => name.to_string(),
+            _ => format!("{} x{}", name, count),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Name of the `Seqdesc` choice of `desc`
+#[allow(deprecated)]
+pub(crate) fn desc_kind(desc: &SeqDesc) -> &'static str {
+    match desc {
+        SeqDesc::MolType(_) => "mol-type",
+        SeqDesc::Modif(_) => "modif",
+        SeqDesc::Method(_) => "method",
+        SeqDesc::Name(_) => "name",
+        SeqDesc::Title(_) => "title",
+        SeqDesc::Org(_) => "org",
+        SeqDesc::Comment(_) => "comment",
+        SeqDesc::Num(_) => "num",
+        SeqDesc::MapLoc(_) => "maploc",
+        SeqDesc::PIR(_) => "pir",
+        SeqDesc::Genbank(_) => "genbank",
+        SeqDesc::Pub(_) => "pub",
+        SeqDesc::Region(_) => "region",
+        SeqDesc::User(_) => "user",
+        SeqDesc::SP(_) => "sp",
+        SeqDesc::DbXref(_) => "dbxref",
+        SeqDesc::Embl(_) => "embl",
+        SeqDesc::CreateDate(_) => "create-date",
+        SeqDesc::UpdateDate(_) => "update-date",
+        SeqDesc::PRF(_) => "prf",
+        SeqDesc::PDB(_) => "pdb",
+        SeqDesc::Het(_) => "het",
+        SeqDesc::Source(_) => "source",
+        SeqDesc::MolInfo(_) => "molinfo",
+        SeqDesc::ModelEv(_) => "modelev",
+        SeqDesc::Unknown(_) => "unknown",
+    }
+}
+
+fn fmt_descr(w: &mut dyn Write, descr: &[SeqDesc], depth: usize) -> fmt::Result {
+    if descr.is_empty() {
+        return Ok(());
+    }
+    let kinds = count(descr.iter().map(desc_kind));
+    writeln!(w, "{}descr: {}", INDENT.repeat(depth), format_kinds(&kinds))
+}
+
+fn fmt_annots(w: &mut dyn Write, annots: &[SeqAnnot], depth: usize) -> fmt::Result {
+    for annot in annots {
+        writeln!(w, "{}annot: {}", INDENT.repeat(depth), annot.summary())?;
+    }
+    Ok(())
+}
+
+impl Summary for SeqAnnot {
+    /// eg: "3 features (gene: 1, CDS: 1, mRNA: 1)"
+    fn summary(&self) -> String {
+        let summary = match &self.data {
+            SeqAnnotData::FTable(feats) => {
+                let keys: Vec<String> = feats.iter().map(feature_key).collect();
+                let counts = count(keys.iter().map(String::as_str))
+                    .iter()
+                    .map(|(key, count)| format!("{}: {}", key, count))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                match feats.len() {
+                    1 => format!("1 feature ({})", counts),
+                    n => format!("{} features ({})", n, counts),
+                }
+            }
+            SeqAnnotData::Align(aligns) => format!("{} alignments", aligns.len()),
+            SeqAnnotData::Graph(graphs) => format!("{} graphs", graphs.len()),
+            SeqAnnotData::IDS(ids) => format!("{} ids", ids.len()),
+            SeqAnnotData::Locs(locs) => format!("{} locations", locs.len()),
+            _ => "seq-table".to_string(),
+        };
+        match &self.name {
+            Some(name) => format!("{} \"{}\"", summary, name),
+            None => summary,
+        }
+    }
+}
+
+/// eg: "2 mRNA"
+fn count_items(items: &Option<Vec<ModelEvidenceItem>>, name: &str) -> Option<String> {
+    items
+        .as_ref()
+        .filter(|items| !items.is_empty())
+        .map(|items| format!("{} {}", items.len(), name))
+}
+
+impl Summary for SeqFeatSupport {
+    /// Evidence given for a feature
+    ///
+    /// eg: "1 experiment; inference: COORDINATES: profile:tRNAscan-SE:2.0; model evidence
+    /// (Gnomon): 3 mRNA, 1 protein"
+    fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        match self.experiment.as_ref().map_or(0, Vec::len) {
+            0 => (),
+            1 => parts.push("1 experiment".to_string()),
+            n => parts.push(format!("{} experiments", n)),
+        }
+        for inference in self.inference.iter().flatten() {
+            parts.push(format!("inference: {}", inference));
+        }
+        for evidence in self.model_evidence.iter().flatten() {
+            let items: Vec<String> = [
+                count_items(&evidence.mrna, "mRNA"),
+                count_items(&evidence.est, "EST"),
+                count_items(&evidence.protein, "protein"),
+            ]
+            .into_iter()
+            .flatten()
+            .collect();
+            let mut part = match &evidence.method {
+                Some(method) => format!("model evidence ({})", method),
+                None => "model evidence".to_string(),
+            };
+            if !items.is_empty() {
+                write!(part, ": {}", items.join(", ")).unwrap();
+            }
+            parts.push(part);
+        }
+        match parts.is_empty() {
+            true => "no evidence".to_string(),
+            false => parts.join("; "),
+        }
+    }
+}
+
+impl Summary for BioSeq {
+    /// eg: "Bioseq NM_000546.6 (rna, 2512 bp, raw)"
+    fn summary(&self) -> String {
+        let mut summary = format!("Bioseq {}", best_id(self));
+        if let Some(inst) = &self.inst {
+            let unit = match inst.mol {
+                Mol::AA => "aa",
+                _ => "bp",
+            };
+            let length = match inst.length {
+                Some(length) => format!("{} {}", length, unit),
+                None => "unknown length".to_string(),
+            };
+            let mol = format!("{:?}", inst.mol).to_lowercase();
+            let repr = format!("{:?}", inst.repr).to_lowercase();
+            write!(summary, " ({}, {}, {})", mol, length, repr).unwrap();
+        }
+        summary
+    }
+
+    fn fmt_tree(&self, w: &mut dyn Write, depth: usize) -> fmt::Result {
+        writeln!(w, "{}{}", INDENT.repeat(depth), self.summary())?;
+        if self.id.len() > 1 {
+            let ids: Vec<String> = self.id.iter().map(seq_id_label).collect();
+            writeln!(w, "{}ids: {}", INDENT.repeat(depth + 1), ids.join(", "))?;
+        }
+        fmt_descr(w, self.descr.as_deref().unwrap_or_default(), depth + 1)?;
+        fmt_annots(w, self.annot.as_deref().unwrap_or_default(), depth + 1)
+    }
+}
+
+impl Summary for BioSeqSet {
+    /// eg: "Bioseq-set (nucprot, 2 entries)"
+    fn summary(&self) -> String {
+        let class = format!("{:?}", self.class).to_lowercase();
+        match self.seq_set.len() {
+            1 => format!("Bioseq-set ({}, 1 entry)", class),
+            n => format!("Bioseq-set ({}, {} entries)", class, n),
+        }
+    }
+
+    fn fmt_tree(&self, w: &mut dyn Write, depth: usize) -> fmt::Result {
+        writeln!(w, "{}{}", INDENT.repeat(depth), self.summary())?;
+        fmt_descr(w, self.descr.as_deref().unwrap_or_default(), depth + 1)?;
+        fmt_annots(w, self.annot.as_deref().unwrap_or_default(), depth + 1)?;
+        for entry in self.seq_set.iter() {
+            entry.fmt_tree(w, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+impl Summary for SeqEntry {
+    fn summary(&self) -> String {
+        match self {
+            Self::Seq(bioseq) => bioseq.summary(),
+            Self::Set(set) => set.summary(),
+        }
+    }
+
+    fn fmt_tree(&self, w: &mut dyn Write, depth: usize) -> fmt::Result {
+        match self {
+            Self::Seq(bioseq) => bioseq.fmt_tree(w, depth),
+            Self::Set(set) => set.fmt_tree(w, depth),
+        }
+    }
+}
+
+impl Summary for Entrezgene {
+    /// eg: "Entrezgene 7157 TP53 (ProteinCoding)"
+    fn summary(&self) -> String {
+        let mut summary = "Entrezgene".to_string();
+        if let Some(track) = &self.track_info {
+            write!(summary, " {}", track.geneid).unwrap();
+        }
+        if let Some(locus) = &self.gene.locus {
+            write!(summary, " {}", locus).unwrap();
+        }
+        write!(summary, " ({:?})", self.r#type).unwrap();
+        summary
+    }
+
+    fn fmt_tree(&self, w: &mut dyn Write, depth: usize) -> fmt::Result {
+        let indent = INDENT.repeat(depth + 1);
+        writeln!(w, "{}{}", INDENT.repeat(depth), self.summary())?;
+
+        if let Some(taxname) = &self.source.org.taxname {
+            match self.source.taxid() {
+                Some(taxid) => writeln!(w, "{}source: {} (taxon {})", indent, taxname, taxid)?,
+                None => writeln!(w, "{}source: {}", indent, taxname)?,
+            }
+        }
+        if let Some(desc) = &self.gene.desc {
+            writeln!(w, "{}desc: {}", indent, desc)?;
+        }
+        let commentaries = [
+            ("locus", &self.locus),
+            ("properties", &self.properties),
+            ("refgene", &self.refgene),
+            ("homology", &self.homology),
+            ("comments", &self.comments),
+        ];
+        for (name, commentaries) in commentaries {
+            if let Some(commentaries) = commentaries {
+                writeln!(w, "{}{}: {} commentaries", indent, name, commentaries.len())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Summary for Article {
+    /// eg: "Article PMC176545 (research-article, 2003)"
+    fn summary(&self) -> String {
+        let mut summary = "Article".to_string();
+        if let Some(pmcid) = &self.pmcid {
+            write!(summary, " {}", pmcid).unwrap();
+        }
+        let details: Vec<String> = [
+            self.article_type.clone(),
+            self.pub_year.map(|year| year.to_string()),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        if !details.is_empty() {
+            write!(summary, " ({})", details.join(", ")).unwrap();
+        }
+        summary
+    }
+
+    fn fmt_tree(&self, w: &mut dyn Write, depth: usize) -> fmt::Result {
+        let indent = INDENT.repeat(depth + 1);
+        writeln!(w, "{}{}", INDENT.repeat(depth), self.summary())?;
+
+        if let Some(title) = &self.title {
+            writeln!(w, "{}title: {}", indent, title)?;
+        }
+        if let Some(journal) = &self.journal {
+            writeln!(w, "{}journal: {}", indent, journal)?;
+        }
+        let ids: Vec<String> = [
+            self.pmid.map(|pmid| format!("pmid {}", pmid)),
+            self.doi.as_ref().map(|doi| format!("doi {}", doi)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        if !ids.is_empty() {
+            writeln!(w, "{}ids: {}", indent, ids.join(", "))?;
+        }
+        let roles = count(
+            self.contributors
+                .iter()
+                .map(|contributor| contributor.role.as_deref().unwrap_or("author")),
+        );
+        if !roles.is_empty() {
+            writeln!(w, "{}contributors: {}", indent, format_kinds(&roles))?;
+        }
+        if !self.keywords.is_empty() {
+            writeln!(w, "{}keywords: {}", indent, self.keywords.join(", "))?;
+        }
+        if let Some(text) = &self.r#abstract {
+            writeln!(w, "{}abstract: {} chars", indent, text.chars().count())?;
+        }
+        for section in self.sections.iter() {
+            let paragraphs = match section.paragraphs.len() {
+                1 => "1 paragraph".to_string(),
+                n => format!("{} paragraphs", n),
+            };
+            writeln!(
+                w,
+                "{}section: {}{} ({})",
+                indent,
+                INDENT.repeat(section.level.saturating_sub(1)),
+                section.title.as_deref().unwrap_or("untitled"),
+                paragraphs
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::seq::{BioSeq, Mol, Repr, SeqAnnot, SeqAnnotData, SeqDesc, SeqInst};
+    use crate::seqfeat::{GeneRef, SeqFeat, SeqFeatData};
+    use crate::seqloc::{SeqId, TextseqId};
+    use crate::seqset::{BioSeqSet, SeqEntry};
+    use crate::summary::Summary;
+
+    #[test]
+    fn bioseq_set_tree() {
+        let feats = vec![
+            SeqFeat::new(SeqFeatData::Gene(GeneRef::default())),
+            SeqFeat::new(SeqFeatData::Gene(GeneRef::default())),
+        ];
+        let bioseq = BioSeq {
+            id: vec![
+                SeqId::Other(TextseqId {
+                    accession: Some("NM_000546".into()),
+                    version: Some(6),
+                    ..Default::default()
+                }),
+                SeqId::Gi(1),
+            ],
+            descr: Some(vec![
+                SeqDesc::Title("a".into()),
+                SeqDesc::Comment("b".into()),
+                SeqDesc::Comment("c".into()),
+            ]),
+            inst: Some(SeqInst {
+                repr: Repr::Raw,
+                mol: Mol::RNA,
+                length: Some(2512),
+                ..Default::default()
+            }),
+            annot: Some(vec![SeqAnnot::new(SeqAnnotData::FTable(feats))]),
+        };
+        let set = BioSeqSet {
+            seq_set: vec![SeqEntry::Seq(bioseq)],
+            ..Default::default()
+        };
+
+        let tree = set.tree();
+        let lines: Vec<&str> = tree.lines().collect();
+        assert!(lines[0].starts_with("Bioseq-set ("));
+        assert!(lines[0].ends_with("1 entry)"));
+        assert_eq!(lines[1], "  Bioseq NM_000546.6 (rna, 2512 bp, raw)");
+        assert_eq!(lines[2], "    ids: NM_000546.6, gi|1");
+        assert_eq!(lines[3], "    descr: title, comment x2");
+        assert_eq!(lines[4], "    annot: 2 features (gene: 2)");
+        assert_eq!(lines.len(), 5);
+    }
+}
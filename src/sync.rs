@@ -0,0 +1,191 @@
+//! Incremental retrieval of search results
+//!
+//! [`SyncQuery`] mirrors the results of an ESearch query across runs. Each run only
+//! returns records added or updated since the previous one, by restricting the search
+//! to a range of dates with the `datetype`, `mindate` and `maxdate` parameters.
+//!
+//! Entrez dates have a resolution of a day and ranges include both ends, so records
+//! from the day of the previous run are returned again. Mirrors should therefore
+//! replace existing records rather than expect each UID to be new.
+//!
+//! # Example
+//! ```ignore
+//! let mut query = SyncQuery::new(EntrezDb::Nucleotide, "Myotis[Organism]");
+//! query.load("myotis.sync")?;
+//! let ids = query.run()?;
+//! // ... fetch and store `ids`
+//! query.save("myotis.sync")?;
+//! ```
+
+use crate::{build_search_url, http_get, EntrezDb, SearchResult};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Records returned by each ESearch request of [`SyncQuery::run()`]
+pub const SYNC_PAGE_SIZE: u64 = 10_000;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+/// Date of records compared to the range of a [`SyncQuery`]
+pub enum SyncDateType {
+    /// date of the last modification (`mdat`)
+    #[default]
+    Modification,
+    /// date the record was added to Entrez (`edat`)
+    Entrez,
+    /// publication date (`pdat`)
+    Publication,
+}
+
+impl SyncDateType {
+    /// Value of the `datetype` parameter
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SyncDateType::Modification => "mdat",
+            SyncDateType::Entrez => "edat",
+            SyncDateType::Publication => "pdat",
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+/// ESearch query remembering when it was last run
+pub struct SyncQuery {
+    pub db: EntrezDb,
+    pub term: String,
+    pub datetype: SyncDateType,
+    /// date of the previous run, as "YYYY/MM/DD"
+    ///
+    /// All matching records are returned when not given.
+    pub last_run: Option<String>,
+}
+
+impl SyncQuery {
+    pub fn new(db: EntrezDb, term: &str) -> Self {
+        Self {
+            db,
+            term: term.to_string(),
+            datetype: SyncDateType::default(),
+            last_run: None,
+        }
+    }
+
+    pub fn datetype(mut self, datetype: SyncDateType) -> Self {
+        self.datetype = datetype;
+        self
+    }
+
+    /// Only return records dated `date` ("YYYY/MM/DD") or later
+    pub fn since(mut self, date: &str) -> Self {
+        self.last_run = Some(date.to_string());
+        self
+    }
+
+    /// ESearch URL for records dated up to `until`, starting at result `retstart`
+    pub fn build_url(&self, until: &str, retstart: u64) -> String {
+        let mut url = build_search_url(self.db, &self.term);
+        if let Some(since) = &self.last_run {
+            url.push_str(&format!(
+                "&datetype={}&mindate={}&maxdate={}",
+                self.datetype.as_str(),
+                since,
+                until
+            ));
+        }
+        url.push_str(&format!("&retstart={}&retmax={}", retstart, SYNC_PAGE_SIZE));
+        url
+    }
+
+    /// UIDs of records added or updated since the previous run
+    ///
+    /// Results are retrieved by pages of [`SYNC_PAGE_SIZE`]. [`Self::last_run`] is only
+    /// advanced to the current date once every page has been retrieved, so that a
+    /// failed run is retried in full.
+    pub fn run(&mut self) -> Result<Vec<String>, String> {
+        let today = entrez_date(SystemTime::now());
+        let mut ids = Vec::new();
+        loop {
+            let response = http_get(&self.build_url(&today, ids.len() as u64))?;
+            let page = SearchResult::from_xml(&response)?;
+            let done = page.ids.is_empty();
+            ids.extend(page.ids);
+            if done || ids.len() as u64 >= page.count {
+                break;
+            }
+        }
+        self.last_run = Some(today);
+        Ok(ids)
+    }
+
+    /// Read the date of the previous run saved by [`Self::save()`]
+    ///
+    /// A missing file is not an error, and leaves the query to return every record.
+    pub fn load(&mut self, path: impl AsRef<Path>) -> Result<(), String> {
+        let path = path.as_ref();
+        match std::fs::read_to_string(path) {
+            Ok(text) => {
+                let date = text.trim();
+                self.last_run = (!date.is_empty()).then(|| date.to_string());
+                Ok(())
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(format!("{}: {}", path.display(), e)),
+        }
+    }
+
+    /// Write the date of the last run, to be read by [`Self::load()`]
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        let path = path.as_ref();
+        std::fs::write(path, self.last_run.as_deref().unwrap_or_default())
+            .map_err(|e| format!("{}: {}", path.display(), e))
+    }
+}
+
+/// UTC date of `time` as "YYYY/MM/DD"
+fn entrez_date(time: SystemTime) -> String {
+    let days = time.duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs() / 86_400) as i64;
+
+    // civil date from days since 1970-01-01, by Howard Hinnant's algorithm
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{:04}/{:02}/{:02}", year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sync::{entrez_date, SyncDateType, SyncQuery};
+    use crate::EntrezDb;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    #[test]
+    fn test_entrez_date() {
+        assert_eq!(entrez_date(UNIX_EPOCH), "1970/01/01");
+        assert_eq!(entrez_date(UNIX_EPOCH + Duration::from_secs(951_782_400)), "2000/02/29");
+        assert_eq!(entrez_date(UNIX_EPOCH + Duration::from_secs(1_704_067_199)), "2023/12/31");
+    }
+
+    #[test]
+    fn test_build_url() {
+        let query = SyncQuery::new(EntrezDb::Gene, "TP73");
+        let url = query.build_url("2024/05/01", 0);
+        assert!(url.contains("term=TP73"));
+        assert!(!url.contains("mindate"));
+
+        let url = query.datetype(SyncDateType::Entrez).since("2024/04/01").build_url("2024/05/01", 20);
+        assert!(url.contains("&datetype=edat&mindate=2024/04/01&maxdate=2024/05/01"));
+        assert!(url.contains("&retstart=20&retmax=10000"));
+    }
+
+    #[test]
+    fn test_build_url_encodes_term() {
+        let query = SyncQuery::new(EntrezDb::Nucleotide, "BRCA1 AND a&b#c+d");
+        let url = query.build_url("2024/05/01", 0);
+        assert!(url.contains("term=BRCA1+AND+a%26b%23c%2Bd&"));
+    }
+}
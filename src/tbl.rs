@@ -0,0 +1,541 @@
+//! Five-column feature tables as consumed by `tbl2asn`/`table2asn`
+//!
+//! A table starts with a `>Feature <seqid>` line. The first line of every feature
+//! gives the start, stop and key of its first interval; additional intervals follow
+//! as `start`/`stop` pairs, and qualifiers are given as `\t\t\tqualifier\tvalue`.
+//! Coordinates are 1-based, minus strand intervals are written with start greater
+//! than stop, and partial ends are marked by `<` and `>`.
+//!
+//! See the [NCBI documentation](https://www.ncbi.nlm.nih.gov/genbank/feature_table/)
+//! for a full description of the format.
+
+use crate::blast::parse_fasta_id;
+use crate::coords::{Pos1, Range0};
+use crate::general::{DbTag, FuzzLimit, IntFuzz, ObjectId};
+use crate::gff::{best_id, db_tag_label, seq_id_label};
+use crate::seq::{BioSeq, SeqAnnot, SeqAnnotData};
+use crate::seqfeat::{
+    CdRegion, CdRegionFrame, GbQual, GeneRef, GeneticCodeOpt, ImpFeat, ProtRef, RnaGen,
+    RnaRef, RnaRefExt, RnaRefType, SeqFeat, SeqFeatData, SeqFeatXref,
+};
+use crate::seqloc::{NaStrand, SeqId, SeqInterval, SeqLoc, SeqLocMix};
+use crate::seqset::BioSeqSet;
+use std::fmt::Write;
+
+/// Features of one sequence, as given by a single `>Feature` block
+#[derive(Clone, PartialEq, Debug)]
+pub struct FeatureTable {
+    /// Sequence that the features are located on
+    ///
+    /// Identifiers given in FASTA style (eg: `gb|AY123456.1|`) are interpreted,
+    /// anything else is kept as a local id.
+    pub id: SeqId,
+
+    /// Optional table name following the identifier
+    pub name: Option<String>,
+
+    pub features: Vec<SeqFeat>,
+}
+
+impl FeatureTable {
+    /// Wrap the features as a feature table [`SeqAnnot`]
+    pub fn into_annot(self) -> SeqAnnot {
+        let mut annot = SeqAnnot::new(SeqAnnotData::FTable(self.features));
+        annot.name = self.name;
+        annot
+    }
+}
+
+/// Render the features of `annot` as a feature table for `id`
+///
+/// `length` is needed to give coordinates for locations covering the whole sequence.
+pub fn seq_annot_to_tbl(id: &SeqId, annot: &SeqAnnot, length: Option<u64>) -> String {
+    let mut tbl = format!(">Feature {}\n", seq_id_label(id));
+    for feat in annot.features() {
+        write_feature(&mut tbl, feat, length);
+    }
+    tbl
+}
+
+/// Render all features annotated on `bioseq` as a single feature table
+pub fn bioseq_to_tbl(bioseq: &BioSeq) -> String {
+    let mut tbl = format!(">Feature {}\n", best_id(bioseq));
+    let length = bioseq.inst.as_ref().and_then(|inst| inst.length);
+    for annot in bioseq.annot.iter().flatten() {
+        for feat in annot.features() {
+            write_feature(&mut tbl, feat, length);
+        }
+    }
+    tbl
+}
+
+/// Render a feature table for every [`BioSeq`] in `set` which has features
+pub fn bioseq_set_to_tbl(set: &BioSeqSet) -> String {
+    set.bioseqs()
+        .into_iter()
+        .filter(|bioseq| bioseq.annot.iter().flatten().any(|annot| !annot.features().is_empty()))
+        .map(bioseq_to_tbl)
+        .collect()
+}
+
+pub(crate) fn feature_key(feat: &SeqFeat) -> String {
+    match &feat.data {
+        SeqFeatData::Gene(_) => "gene",
+        SeqFeatData::CdRegion(_) => "CDS",
+        SeqFeatData::RNA(rna) => match rna.r#type {
+            RnaRefType::PreMsg => "precursor_RNA",
+            RnaRefType::mRNA => "mRNA",
+            RnaRefType::tRNA => "tRNA",
+            RnaRefType::rRNA => "rRNA",
+            RnaRefType::snRNA | RnaRefType::scRNA | RnaRefType::snoRNA | RnaRefType::ncRNA => "ncRNA",
+            RnaRefType::tmRNA => "tmRNA",
+            _ => "misc_RNA",
+        },
+        SeqFeatData::Prot(_) => "Protein",
+        SeqFeatData::Region(_) => "Region",
+        SeqFeatData::Imp(imp) => imp.key.as_str(),
+        SeqFeatData::BioSrc(_) => "source",
+        _ => "misc_feature",
+    }
+    .to_string()
+}
+
+/// Whether `fuzz` marks an end as extending beyond the given position
+fn is_partial(fuzz: &Option<IntFuzz>) -> bool {
+    fuzz.as_ref().is_some_and(IntFuzz::is_partial_limit)
+}
+
+/// Flatten a location into `(start, stop)` column values in biological order
+///
+/// Intervals with invalid coordinates (eg: negative) are skipped.
+fn columns(loc: &SeqLoc, length: Option<u64>) -> Vec<(String, String)> {
+    let column = |pos: Pos1, partial: bool, mark: char| {
+        if partial {
+            format!("{}{}", mark, pos)
+        } else {
+            pos.to_string()
+        }
+    };
+    let interval = |i: &SeqInterval| {
+        let range = i.range0()?;
+        Some(if i.strand == Some(NaStrand::Minus) {
+            (
+                column(range.end1(), is_partial(&i.fuzz_to), '<'),
+                column(range.start1(), is_partial(&i.fuzz_from), '>'),
+            )
+        } else {
+            (
+                column(range.start1(), is_partial(&i.fuzz_from), '<'),
+                column(range.end1(), is_partial(&i.fuzz_to), '>'),
+            )
+        })
+    };
+    match loc {
+        SeqLoc::Whole(_) => length
+            .and_then(Range0::whole)
+            .map(|range| (range.start1().to_string(), range.end1().to_string()))
+            .into_iter()
+            .collect(),
+        SeqLoc::Int(i) => interval(i).into_iter().collect(),
+        SeqLoc::PackedInt(intervals) => intervals.iter().filter_map(interval).collect(),
+        SeqLoc::Pnt(point) => point
+            .pos0()
+            .map(|pos| {
+                let pos = pos.to_one_based().to_string();
+                (pos.clone(), pos)
+            })
+            .into_iter()
+            .collect(),
+        SeqLoc::Mix(mix) => mix.0.iter().flat_map(|loc| columns(loc, length)).collect(),
+        _ => Vec::new(),
+    }
+}
+
+pub(crate) fn qualifiers(feat: &SeqFeat) -> Vec<(&str, String)> {
+    let mut quals = Vec::new();
+
+    let gene = match &feat.data {
+        SeqFeatData::Gene(gene) => Some(gene),
+        _ => feat.gene_xref(),
+    };
+    if let Some(gene) = gene {
+        if let Some(locus) = &gene.locus {
+            quals.push(("gene", locus.clone()));
+        }
+        if let Some(allele) = &gene.allele {
+            quals.push(("allele", allele.clone()));
+        }
+        if let Some(locus_tag) = &gene.locus_tag {
+            quals.push(("locus_tag", locus_tag.clone()));
+        }
+        for syn in gene.syn.iter().flatten() {
+            quals.push(("gene_synonym", syn.clone()));
+        }
+    }
+
+    match &feat.data {
+        SeqFeatData::CdRegion(cds) => {
+            match cds.frame {
+                CdRegionFrame::Two => quals.push(("codon_start", "2".to_string())),
+                CdRegionFrame::Three => quals.push(("codon_start", "3".to_string())),
+                _ => (),
+            }
+            let table = cds.code.iter().flatten().find_map(|opt| match opt {
+                GeneticCodeOpt::Id(id) => Some(*id),
+                _ => None,
+            });
+            if let Some(table) = table {
+                quals.push(("transl_table", table.to_string()));
+            }
+            // protein names of coding regions are given by a `Prot-ref` xref
+            let prot = feat.xref.iter().flatten().find_map(|xref| match &xref.data {
+                Some(SeqFeatData::Prot(prot)) => Some(prot),
+                _ => None,
+            });
+            for name in prot.and_then(|prot| prot.name.as_ref()).into_iter().flatten() {
+                quals.push(("product", name.clone()));
+            }
+        }
+        SeqFeatData::RNA(rna) => match &rna.ext {
+            Some(RnaRefExt::Name(name)) => quals.push(("product", name.clone())),
+            Some(RnaRefExt::Gen(gen)) => {
+                if let Some(class) = &gen.class {
+                    quals.push(("ncRNA_class", class.clone()));
+                }
+                if let Some(product) = &gen.product {
+                    quals.push(("product", product.clone()));
+                }
+            }
+            _ => (),
+        },
+        SeqFeatData::Prot(prot) => {
+            for name in prot.name.iter().flatten() {
+                quals.push(("product", name.clone()));
+            }
+        }
+        SeqFeatData::Region(name) => quals.push(("region", name.clone())),
+        _ => (),
+    }
+
+    if let Some(SeqLoc::Whole(id)) = &feat.product {
+        quals.push(("protein_id", seq_id_label(id)));
+    }
+    if feat.pseudo == Some(true) {
+        quals.push(("pseudo", String::new()));
+    }
+    if let Some(text) = &feat.except_text {
+        quals.push(("exception", text.clone()));
+    }
+    for qual in feat.qual.iter().flatten() {
+        quals.push((qual.qual.as_str(), qual.val.clone()));
+    }
+    if let Some(comment) = &feat.comment {
+        quals.push(("note", comment.clone()));
+    }
+    for tag in feat.dbxref.iter().flatten() {
+        quals.push(("db_xref", db_tag_label(tag)));
+    }
+    quals
+}
+
+fn write_feature(tbl: &mut String, feat: &SeqFeat, length: Option<u64>) {
+    let columns = columns(&feat.location, length);
+    if columns.is_empty() {
+        return;
+    }
+    for (i, (start, stop)) in columns.iter().enumerate() {
+        if i == 0 {
+            writeln!(tbl, "{}\t{}\t{}", start, stop, feature_key(feat)).unwrap();
+        } else {
+            writeln!(tbl, "{}\t{}", start, stop).unwrap();
+        }
+    }
+    for (qual, value) in qualifiers(feat) {
+        // values cannot span lines or columns
+        let value = value.replace(['\t', '\n', '\r'], " ");
+        if value.is_empty() {
+            writeln!(tbl, "\t\t\t{}", qual).unwrap();
+        } else {
+            writeln!(tbl, "\t\t\t{}\t{}", qual, value).unwrap();
+        }
+    }
+}
+
+/// Parse all `>Feature` blocks of a five-column feature table
+///
+/// Known feature keys and qualifiers are mapped onto the corresponding
+/// [`SeqFeatData`] and [`SeqFeat`] fields. Any other key becomes an [`ImpFeat`],
+/// and any other qualifier is kept as a [`GbQual`].
+pub fn parse_tbl(text: &str) -> Result<Vec<FeatureTable>, String> {
+    let mut tables: Vec<FeatureTable> = Vec::new();
+
+    for (n, line) in text.lines().enumerate() {
+        let line = line.trim_end_matches('\r');
+        if line.trim().is_empty() {
+            continue;
+        }
+        let error = |msg: &str| format!("Line {}: {}", n + 1, msg);
+
+        // partial start positions on the first column may also begin with `>`
+        if let Some(header) = line.strip_prefix(">Feature") {
+            let mut fields = header.split_whitespace();
+            let id = fields.next().ok_or_else(|| error("Missing sequence id"))?;
+            tables.push(FeatureTable {
+                id: parse_fasta_id(id),
+                name: fields.next().map(str::to_string),
+                features: Vec::new(),
+            });
+            continue;
+        }
+
+        let table = tables
+            .last_mut()
+            .ok_or_else(|| error("Feature given before any `>Feature` line"))?;
+        let fields: Vec<&str> = line.split('\t').collect();
+
+        if fields.len() >= 4 && fields[..3].iter().all(|f| f.is_empty()) {
+            // qualifier line
+            let feat = table
+                .features
+                .last_mut()
+                .ok_or_else(|| error("Qualifier given before any feature"))?;
+            let value = fields.get(4).map(|v| v.trim()).unwrap_or("");
+            apply_qualifier(feat, fields[3].trim(), value).map_err(|e| error(&e))?;
+            continue;
+        }
+
+        if fields.len() < 2 {
+            return Err(error("Expected start and stop columns"));
+        }
+        let interval = parse_interval(fields[0].trim(), fields[1].trim(), &table.id)
+            .map_err(|e| error(&e))?;
+        let partial = is_partial(&interval.fuzz_from) || is_partial(&interval.fuzz_to);
+        match fields.get(2).map(|key| key.trim()).filter(|key| !key.is_empty()) {
+            Some(key) => {
+                let mut feat = SeqFeat::new(feature_data(key));
+                feat.location = SeqLoc::Int(interval);
+                if partial {
+                    feat.partial = Some(true);
+                }
+                table.features.push(feat);
+            }
+            None => {
+                let feat = table
+                    .features
+                    .last_mut()
+                    .ok_or_else(|| error("Interval given before any feature"))?;
+                add_interval(&mut feat.location, interval);
+                if partial {
+                    feat.partial = Some(true);
+                }
+            }
+        }
+    }
+
+    Ok(tables)
+}
+
+/// Parse a 1-based position, stripping any `<` or `>` partial mark
+fn parse_position(column: &str) -> Result<(i64, bool), String> {
+    let position = column.trim_start_matches(['<', '>']);
+    let partial = position.len() != column.len();
+    match position.parse::<Pos1>() {
+        Ok(position) => Ok((position.to_zero_based().into(), partial)),
+        _ => Err(format!("Invalid position `{}`", column)),
+    }
+}
+
+fn parse_interval(start: &str, stop: &str, id: &SeqId) -> Result<SeqInterval, String> {
+    let (start, start_partial) = parse_position(start)?;
+    let (stop, stop_partial) = parse_position(stop)?;
+    let fuzz = |partial: bool, limit: FuzzLimit| partial.then_some(IntFuzz::Lim(limit));
+
+    let interval = if start > stop {
+        SeqInterval {
+            from: stop,
+            to: start,
+            strand: Some(NaStrand::Minus),
+            id: id.clone(),
+            fuzz_from: fuzz(stop_partial, FuzzLimit::LT),
+            fuzz_to: fuzz(start_partial, FuzzLimit::GT),
+        }
+    } else {
+        SeqInterval {
+            from: start,
+            to: stop,
+            strand: Some(NaStrand::Plus),
+            id: id.clone(),
+            fuzz_from: fuzz(start_partial, FuzzLimit::LT),
+            fuzz_to: fuzz(stop_partial, FuzzLimit::GT),
+        }
+    };
+    Ok(interval)
+}
+
+fn add_interval(location: &mut SeqLoc, interval: SeqInterval) {
+    match location {
+        SeqLoc::Mix(mix) => mix.0.push(SeqLoc::Int(interval)),
+        _ => {
+            let first = std::mem::replace(location, SeqLoc::Null);
+            *location = SeqLoc::Mix(SeqLocMix(vec![first, SeqLoc::Int(interval)]));
+        }
+    }
+}
+
+pub(crate) fn feature_data(key: &str) -> SeqFeatData {
+    let rna = |r#type| {
+        SeqFeatData::RNA(RnaRef {
+            r#type,
+            pseudo: None,
+            ext: None,
+        })
+    };
+    match key {
+        "gene" => SeqFeatData::Gene(GeneRef::default()),
+        "CDS" => SeqFeatData::CdRegion(CdRegion::default()),
+        "precursor_RNA" => rna(RnaRefType::PreMsg),
+        "mRNA" => rna(RnaRefType::mRNA),
+        "tRNA" => rna(RnaRefType::tRNA),
+        "rRNA" => rna(RnaRefType::rRNA),
+        "ncRNA" => rna(RnaRefType::ncRNA),
+        "tmRNA" => rna(RnaRefType::tmRNA),
+        "misc_RNA" => rna(RnaRefType::MiscRNA),
+        "Protein" => SeqFeatData::Prot(ProtRef::default()),
+        "Region" => SeqFeatData::Region(String::new()),
+        key => SeqFeatData::Imp(ImpFeat {
+            key: key.to_string(),
+            loc: None,
+            descr: None,
+        }),
+    }
+}
+
+/// Gene referenced by a feature that is not itself a gene
+fn gene_xref_mut(feat: &mut SeqFeat) -> &mut GeneRef {
+    let xrefs = feat.xref.get_or_insert_with(Vec::new);
+    let position = xrefs
+        .iter()
+        .position(|xref| matches!(xref.data, Some(SeqFeatData::Gene(_))));
+    let index = match position {
+        Some(index) => index,
+        None => {
+            xrefs.push(SeqFeatXref {
+                id: None,
+                data: Some(SeqFeatData::Gene(GeneRef::default())),
+            });
+            xrefs.len() - 1
+        }
+    };
+    match &mut xrefs[index].data {
+        Some(SeqFeatData::Gene(gene)) => gene,
+        _ => unreachable!(),
+    }
+}
+
+/// Protein referenced by a coding region
+fn prot_xref_mut(feat: &mut SeqFeat) -> &mut ProtRef {
+    let xrefs = feat.xref.get_or_insert_with(Vec::new);
+    let position = xrefs
+        .iter()
+        .position(|xref| matches!(xref.data, Some(SeqFeatData::Prot(_))));
+    let index = match position {
+        Some(index) => index,
+        None => {
+            xrefs.push(SeqFeatXref {
+                id: None,
+                data: Some(SeqFeatData::Prot(ProtRef::default())),
+            });
+            xrefs.len() - 1
+        }
+    };
+    match &mut xrefs[index].data {
+        Some(SeqFeatData::Prot(prot)) => prot,
+        _ => unreachable!(),
+    }
+}
+
+fn rna_gen(rna: &mut RnaRef) -> &mut RnaGen {
+    if !matches!(rna.ext, Some(RnaRefExt::Gen(_))) {
+        rna.ext = Some(RnaRefExt::Gen(RnaGen {
+            class: None,
+            product: None,
+            quals: None,
+        }));
+    }
+    match &mut rna.ext {
+        Some(RnaRefExt::Gen(gen)) => gen,
+        _ => unreachable!(),
+    }
+}
+
+pub(crate) fn apply_qualifier(feat: &mut SeqFeat, qual: &str, value: &str) -> Result<(), String> {
+    match (qual, &mut feat.data) {
+        ("gene", SeqFeatData::Gene(gene)) => gene.locus = Some(value.to_string()),
+        ("gene", _) => gene_xref_mut(feat).locus = Some(value.to_string()),
+        ("allele", SeqFeatData::Gene(gene)) => gene.allele = Some(value.to_string()),
+        ("locus_tag", SeqFeatData::Gene(gene)) => gene.locus_tag = Some(value.to_string()),
+        ("locus_tag", _) => gene_xref_mut(feat).locus_tag = Some(value.to_string()),
+        ("gene_synonym", SeqFeatData::Gene(gene)) => {
+            gene.syn.get_or_insert_with(Vec::new).push(value.to_string())
+        }
+        ("codon_start", SeqFeatData::CdRegion(cds)) => {
+            cds.frame = match value {
+                "1" => CdRegionFrame::One,
+                "2" => CdRegionFrame::Two,
+                "3" => CdRegionFrame::Three,
+                _ => return Err(format!("Invalid codon_start `{}`", value)),
+            }
+        }
+        ("transl_table", SeqFeatData::CdRegion(cds)) => {
+            let id = value
+                .parse()
+                .map_err(|_| format!("Invalid transl_table `{}`", value))?;
+            cds.code = Some(vec![GeneticCodeOpt::Id(id)]);
+        }
+        ("product", SeqFeatData::CdRegion(_)) => prot_xref_mut(feat)
+            .name
+            .get_or_insert_with(Vec::new)
+            .push(value.to_string()),
+        ("product", SeqFeatData::Prot(prot)) => {
+            prot.name.get_or_insert_with(Vec::new).push(value.to_string())
+        }
+        ("product", SeqFeatData::RNA(rna)) if rna.r#type != RnaRefType::tRNA => match rna.r#type {
+            RnaRefType::ncRNA | RnaRefType::tmRNA | RnaRefType::MiscRNA => {
+                rna_gen(rna).product = Some(value.to_string())
+            }
+            _ => rna.ext = Some(RnaRefExt::Name(value.to_string())),
+        },
+        ("ncRNA_class", SeqFeatData::RNA(rna)) => rna_gen(rna).class = Some(value.to_string()),
+        ("region", SeqFeatData::Region(name)) => *name = value.to_string(),
+        ("protein_id", _) => feat.product = Some(SeqLoc::Whole(parse_fasta_id(value))),
+        ("pseudo", _) => feat.pseudo = Some(true),
+        ("exception", _) => {
+            feat.except = Some(true);
+            feat.except_text = Some(value.to_string());
+        }
+        ("note", _) => {
+            feat.comment = Some(match feat.comment.take() {
+                Some(comment) => format!("{}; {}", comment, value),
+                None => value.to_string(),
+            })
+        }
+        ("db_xref", _) => {
+            let (db, tag) = value
+                .split_once(':')
+                .ok_or_else(|| format!("Invalid db_xref `{}`", value))?;
+            let tag = match tag.parse() {
+                Ok(id) => ObjectId::Id(id),
+                Err(_) => ObjectId::Str(tag.into()),
+            };
+            feat.dbxref.get_or_insert_with(Vec::new).push(DbTag {
+                db: db.into(),
+                tag,
+            });
+        }
+        _ => feat.qual.get_or_insert_with(Vec::new).push(GbQual {
+            qual: qual.into(),
+            val: value.to_string(),
+        }),
+    }
+    Ok(())
+}
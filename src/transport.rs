@@ -0,0 +1,453 @@
+//! Network backends for E-utilities requests
+//!
+//! Requests made by this crate do not depend on a particular HTTP client. They are
+//! sent through the [`HttpTransport`] installed with [`set_transport()`], which by
+//! default is [`ReqwestTransport`] (with the `reqwest` feature, enabled by default).
+//! Another client (eg: ureq, hyper, or the browser's `fetch`) may be used by
+//! implementing [`HttpTransport`] for it, and any `Fn(&str) -> Result<String, String>`
+//! can be installed to serve canned responses in tests.
+//!
+//! Blocking requests may be bounded by a timeout and cancelled from another thread
+//! with a [`CancelToken`] (see [`crate::http_get_with()`]), which is useful when a GUI
+//! or a server must not wait for a slow EFetch. The caller returns as soon as the
+//! request is abandoned. [`ReqwestTransport`] also closes the connection of the request
+//! once its timeout has elapsed, or as the body arrives after cancellation, while other
+//! transports finish the request in the background unless they override
+//! [`HttpTransport::get_within()`].
+//!
+//! [`AsyncHttpTransport`] is the non-blocking counterpart, used by [`http_get_async()`].
+//! In the browser (`wasm32-unknown-unknown` with the `wasm` feature), it defaults to
+//! [`FetchTransport`], which uses the `fetch` API.
+//!
+//! # Example
+//! ```
+//! use ncbi::transport::set_transport;
+//!
+//! set_transport(|url: &str| match url.contains("esearch") {
+//!     true => Ok("<eSearchResult><Count>0</Count></eSearchResult>".to_string()),
+//!     false => Err(format!("Unexpected request {}", url)),
+//! });
+//! # ncbi::transport::reset_transport();
+//! ```
+
+use crate::ratelimit::throttle_async;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, RecvTimeoutError};
+use std::sync::{Arc, OnceLock, RwLock};
+use std::future::Future;
+use std::pin::Pin;
+use std::time::{Duration, Instant};
+use std::{fmt, thread};
+
+/// Interval at which a waiting request checks its [`CancelToken`]
+const CANCEL_POLL: Duration = Duration::from_millis(20);
+
+/// Client which performs blocking GET requests
+pub trait HttpTransport: Send + Sync + 'static {
+    /// Body of the response to `url`
+    ///
+    /// Responses with an error status are returned as errors.
+    fn get(&self, url: &str) -> Result<String, String>;
+
+    /// Body of the response to `url`, giving up after `timeout` or once `cancel` is set
+    ///
+    /// By default, the request is sent by [`Self::get()`] from another thread, which is
+    /// left to finish in the background if the request is abandoned. Clients which are
+    /// able to abort a request in flight should override this.
+    fn get_within(
+        self: Arc<Self>,
+        url: &str,
+        timeout: Option<Duration>,
+        cancel: Option<&CancelToken>,
+    ) -> Result<String, RequestError> {
+        let owned = url.to_string();
+        abandonable(url, timeout, cancel, move || self.get(&owned).map_err(RequestError::Failed))
+    }
+}
+
+/// Error if `cancel` is set, or once `deadline` is reached
+fn check_abandoned(deadline: Option<Instant>, timeout: Option<Duration>, cancel: Option<&CancelToken>) -> Result<(), RequestError> {
+    if cancel.is_some_and(CancelToken::is_cancelled) {
+        return Err(RequestError::Cancelled);
+    }
+    match (deadline, timeout) {
+        (Some(deadline), Some(timeout)) if Instant::now() >= deadline => Err(RequestError::TimedOut(timeout)),
+        _ => Ok(()),
+    }
+}
+
+/// Run `request` for `url` from another thread, unless it is abandoned first
+fn abandonable<F>(url: &str, timeout: Option<Duration>, cancel: Option<&CancelToken>, request: F) -> Result<String, RequestError>
+where
+    F: FnOnce() -> Result<String, RequestError> + Send + 'static,
+{
+    let (sender, receiver) = channel();
+    thread::spawn(move || sender.send(request()));
+
+    let deadline = timeout.map(|timeout| Instant::now() + timeout);
+    loop {
+        let mut wait = CANCEL_POLL;
+        if let Some(deadline) = deadline {
+            wait = wait.min(deadline.saturating_duration_since(Instant::now()));
+        }
+        match receiver.recv_timeout(wait) {
+            Ok(response) => return response,
+            Err(RecvTimeoutError::Disconnected) => {
+                return Err(RequestError::Failed(format!("Transport panicked while requesting {}", url)))
+            }
+            Err(RecvTimeoutError::Timeout) => (),
+        }
+        check_abandoned(deadline, timeout, cancel)?;
+    }
+}
+
+impl<F> HttpTransport for F
+where
+    F: Fn(&str) -> Result<String, String> + Send + Sync + 'static,
+{
+    fn get(&self, url: &str) -> Result<String, String> {
+        self(url)
+    }
+}
+
+#[cfg(all(feature = "reqwest", not(target_arch = "wasm32")))]
+#[derive(Clone, Debug, Default)]
+/// [`HttpTransport`] and [`AsyncHttpTransport`] backed by `reqwest`
+pub struct ReqwestTransport;
+
+#[cfg(all(feature = "reqwest", not(target_arch = "wasm32")))]
+impl HttpTransport for ReqwestTransport {
+    fn get(&self, url: &str) -> Result<String, String> {
+        reqwest::blocking::get(url)
+            .and_then(|r| r.error_for_status())
+            .and_then(|r| r.text())
+            .map_err(|e| e.to_string())
+    }
+
+    /// `timeout` is given to `reqwest`, and `cancel` is checked as each chunk of the
+    /// body is received, so that the connection of an abandoned request is closed.
+    fn get_within(
+        self: Arc<Self>,
+        url: &str,
+        timeout: Option<Duration>,
+        cancel: Option<&CancelToken>,
+    ) -> Result<String, RequestError> {
+        let owned = url.to_string();
+        let token = cancel.cloned();
+        abandonable(url, timeout, cancel, move || reqwest_within(&owned, timeout, token.as_ref()))
+    }
+}
+
+#[cfg(all(feature = "reqwest", not(target_arch = "wasm32")))]
+/// Blocking request which stops receiving once `timeout` has elapsed or `cancel` is set
+fn reqwest_within(url: &str, timeout: Option<Duration>, cancel: Option<&CancelToken>) -> Result<String, RequestError> {
+    use std::io::Read;
+
+    let deadline = timeout.map(|timeout| Instant::now() + timeout);
+    let failed = |message: String| match check_abandoned(deadline, timeout, cancel) {
+        Ok(()) => RequestError::Failed(message),
+        Err(abandoned) => abandoned,
+    };
+
+    let mut request = reqwest::blocking::Client::new().get(url);
+    if let Some(timeout) = timeout {
+        request = request.timeout(timeout);
+    }
+    let mut response = request
+        .send()
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| failed(e.to_string()))?;
+
+    let mut body = Vec::new();
+    let mut chunk = [0; 8192];
+    loop {
+        check_abandoned(deadline, timeout, cancel)?;
+        match response.read(&mut chunk) {
+            Ok(0) => break,
+            Ok(n) => body.extend_from_slice(&chunk[..n]),
+            Err(e) => return Err(failed(e.to_string())),
+        }
+    }
+    String::from_utf8(body).map_err(|e| RequestError::Failed(e.to_string()))
+}
+
+#[cfg(not(all(feature = "reqwest", not(target_arch = "wasm32"))))]
+/// Default transport when built without an HTTP client
+struct Unavailable;
+
+#[cfg(not(all(feature = "reqwest", not(target_arch = "wasm32"))))]
+impl HttpTransport for Unavailable {
+    fn get(&self, url: &str) -> Result<String, String> {
+        Err(format!("No HTTP transport is available to request {}", url))
+    }
+}
+
+fn default_transport() -> Arc<dyn HttpTransport> {
+    #[cfg(all(feature = "reqwest", not(target_arch = "wasm32")))]
+    return Arc::new(ReqwestTransport);
+    #[cfg(not(all(feature = "reqwest", not(target_arch = "wasm32"))))]
+    return Arc::new(Unavailable);
+}
+
+fn installed() -> &'static RwLock<Arc<dyn HttpTransport>> {
+    static TRANSPORT: OnceLock<RwLock<Arc<dyn HttpTransport>>> = OnceLock::new();
+    TRANSPORT.get_or_init(|| RwLock::new(default_transport()))
+}
+
+/// Send all blocking requests through `transport`
+///
+/// The transport is shared by all threads.
+pub fn set_transport(transport: impl HttpTransport + 'static) {
+    *installed().write().unwrap_or_else(|e| e.into_inner()) = Arc::new(transport);
+}
+
+/// Restore the default transport
+pub fn reset_transport() {
+    *installed().write().unwrap_or_else(|e| e.into_inner()) = default_transport();
+}
+
+/// Transport which blocking requests are currently sent through
+pub fn transport() -> Arc<dyn HttpTransport> {
+    installed().read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+#[derive(Clone, Debug, Default)]
+/// Flag shared with requests, to abort them from another thread
+///
+/// Clones share the same flag, so that a token may be kept by the caller while its
+/// clone is given to the request. Once cancelled, a token stays cancelled.
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Abort the requests using this token, and those made with it later
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// Tokens are equal when they are clones of each other
+impl PartialEq for CancelToken {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+/// Reason a blocking request did not return a response
+pub enum RequestError {
+    /// the request was aborted by its [`CancelToken`]
+    Cancelled,
+    /// no response was received within the given time
+    TimedOut(Duration),
+    /// the request was not sent, or failed (eg: error status, network error)
+    Failed(String),
+}
+
+impl fmt::Display for RequestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Cancelled => f.write_str("Request cancelled"),
+            Self::TimedOut(timeout) => write!(f, "No response within {:?}", timeout),
+            Self::Failed(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for RequestError {}
+
+impl From<RequestError> for String {
+    fn from(error: RequestError) -> Self {
+        error.to_string()
+    }
+}
+
+impl From<String> for RequestError {
+    fn from(message: String) -> Self {
+        Self::Failed(message)
+    }
+}
+
+/// Send `url` through [`transport()`], giving up after `timeout` or once `cancel` is set
+///
+/// Without either, the request is sent from the calling thread by
+/// [`HttpTransport::get()`]. Otherwise, it is sent by [`HttpTransport::get_within()`].
+pub fn get_within(url: &str, timeout: Option<Duration>, cancel: Option<&CancelToken>) -> Result<String, RequestError> {
+    if cancel.is_some_and(CancelToken::is_cancelled) {
+        return Err(RequestError::Cancelled);
+    }
+    let transport = transport();
+    if timeout.is_none() && cancel.is_none() {
+        return transport.get(url).map_err(RequestError::Failed);
+    }
+    transport.get_within(url, timeout, cancel)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+/// Future returned by [`AsyncHttpTransport::get()`]
+pub type ResponseFuture<'a> = Pin<Box<dyn Future<Output = Result<String, String>> + Send + 'a>>;
+
+#[cfg(target_arch = "wasm32")]
+/// Future returned by [`AsyncHttpTransport::get()`]
+///
+/// Futures of the browser are bound to its single thread, and are not `Send`.
+pub type ResponseFuture<'a> = Pin<Box<dyn Future<Output = Result<String, String>> + 'a>>;
+
+#[cfg(all(feature = "async", not(target_arch = "wasm32")))]
+/// Body of a response, read as it is received
+pub type ResponseReader = Pin<Box<dyn tokio::io::AsyncBufRead + Send>>;
+
+#[cfg(all(feature = "async", not(target_arch = "wasm32")))]
+/// Future returned by [`AsyncHttpTransport::get_stream()`]
+pub type StreamFuture<'a> = Pin<Box<dyn Future<Output = Result<ResponseReader, String>> + Send + 'a>>;
+
+/// Client which performs non-blocking GET requests
+pub trait AsyncHttpTransport: Send + Sync {
+    /// Body of the response to `url`
+    fn get<'a>(&'a self, url: &'a str) -> ResponseFuture<'a>;
+
+    #[cfg(all(feature = "async", not(target_arch = "wasm32")))]
+    /// Body of the response to `url`, to be read while it is still being received
+    ///
+    /// By default, the whole body is received by [`Self::get()`] before it is returned.
+    fn get_stream<'a>(&'a self, url: &'a str) -> StreamFuture<'a> {
+        Box::pin(async move {
+            let body = self.get(url).await?;
+            Ok(Box::pin(std::io::Cursor::new(body.into_bytes())) as ResponseReader)
+        })
+    }
+}
+
+#[cfg(all(feature = "reqwest", not(target_arch = "wasm32")))]
+impl AsyncHttpTransport for ReqwestTransport {
+    fn get<'a>(&'a self, url: &'a str) -> ResponseFuture<'a> {
+        Box::pin(async move {
+            let response = reqwest::get(url)
+                .await
+                .and_then(|r| r.error_for_status())
+                .map_err(|e| e.to_string())?;
+            response.text().await.map_err(|e| e.to_string())
+        })
+    }
+
+    #[cfg(feature = "async")]
+    fn get_stream<'a>(&'a self, url: &'a str) -> StreamFuture<'a> {
+        use futures_util::TryStreamExt;
+
+        Box::pin(async move {
+            let response = reqwest::get(url)
+                .await
+                .and_then(|r| r.error_for_status())
+                .map_err(|e| e.to_string())?;
+            let body = response.bytes_stream().map_err(std::io::Error::other);
+            Ok(Box::pin(tokio_util::io::StreamReader::new(body)) as ResponseReader)
+        })
+    }
+}
+
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+#[derive(Clone, Debug, Default)]
+/// [`AsyncHttpTransport`] using the `fetch` API of the browser
+///
+/// Blocking requests cannot be made from the browser, so only [`http_get_async()`]
+/// is available on `wasm32-unknown-unknown`.
+pub struct FetchTransport;
+
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+impl AsyncHttpTransport for FetchTransport {
+    fn get<'a>(&'a self, url: &'a str) -> ResponseFuture<'a> {
+        use wasm_bindgen::JsCast;
+        use wasm_bindgen_futures::JsFuture;
+
+        let js_error = |e: wasm_bindgen::JsValue| format!("{:?}", e);
+        Box::pin(async move {
+            let window = web_sys::window().ok_or("No window to fetch from")?;
+            let response: web_sys::Response = JsFuture::from(window.fetch_with_str(url))
+                .await
+                .map_err(js_error)?
+                .dyn_into()
+                .map_err(js_error)?;
+            if !response.ok() {
+                return Err(format!("HTTP status {} for {}", response.status(), url));
+            }
+            let text = JsFuture::from(response.text().map_err(js_error)?)
+                .await
+                .map_err(js_error)?;
+            text.as_string().ok_or_else(|| format!("Response to {} is not text", url))
+        })
+    }
+}
+
+#[cfg(not(all(feature = "reqwest", not(target_arch = "wasm32"))))]
+impl AsyncHttpTransport for Unavailable {
+    fn get<'a>(&'a self, url: &'a str) -> ResponseFuture<'a> {
+        Box::pin(async move { HttpTransport::get(self, url) })
+    }
+}
+
+fn default_async_transport() -> Arc<dyn AsyncHttpTransport> {
+    #[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+    return Arc::new(FetchTransport);
+    #[cfg(all(feature = "reqwest", not(target_arch = "wasm32")))]
+    return Arc::new(ReqwestTransport);
+    #[cfg(not(any(all(feature = "wasm", target_arch = "wasm32"), all(feature = "reqwest", not(target_arch = "wasm32")))))]
+    return Arc::new(Unavailable);
+}
+
+fn installed_async() -> &'static RwLock<Arc<dyn AsyncHttpTransport>> {
+    static TRANSPORT: OnceLock<RwLock<Arc<dyn AsyncHttpTransport>>> = OnceLock::new();
+    TRANSPORT.get_or_init(|| RwLock::new(default_async_transport()))
+}
+
+/// Send all non-blocking requests through `transport`
+pub fn set_async_transport(transport: impl AsyncHttpTransport + 'static) {
+    *installed_async().write().unwrap_or_else(|e| e.into_inner()) = Arc::new(transport);
+}
+
+/// Transport which non-blocking requests are currently sent through
+pub fn async_transport() -> Arc<dyn AsyncHttpTransport> {
+    installed_async().read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Perform a non-blocking GET request through [`async_transport()`]
+///
+/// The request waits for the installed [`crate::ratelimit::RateLimiter`], if any.
+/// With the `fixtures` feature, responses are replayed or recorded as by
+/// [`crate::http_get()`].
+pub async fn http_get_async(url: &str) -> Result<String, String> {
+    #[cfg(feature = "fixtures")]
+    if let Some(response) = crate::fixtures::intercept(url) {
+        return response;
+    }
+    throttle_async(url).await;
+    let response = async_transport().get(url).await?;
+
+    #[cfg(feature = "fixtures")]
+    crate::fixtures::record_response(url, &response)?;
+
+    Ok(response)
+}
+
+#[cfg(all(feature = "async", not(target_arch = "wasm32")))]
+/// Perform a non-blocking GET request through [`async_transport()`], returning the
+/// body as it is received
+///
+/// See [`AsyncHttpTransport::get_stream()`]. With the `fixtures` feature, responses are
+/// replayed or recorded as by [`crate::http_get()`]; a recorded response is received
+/// in full before it is returned.
+pub async fn http_stream_async(url: &str) -> Result<ResponseReader, String> {
+    #[cfg(feature = "fixtures")]
+    if crate::fixtures::is_active() {
+        let body = http_get_async(url).await?;
+        return Ok(Box::pin(std::io::Cursor::new(body.into_bytes())));
+    }
+    throttle_async(url).await;
+    async_transport().get_stream(url).await
+}
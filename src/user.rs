@@ -0,0 +1,465 @@
+//! Typed views over well-known [`UserObject`] types
+//!
+//! NCBI records use [`UserObject`] descriptors for several structured blocks whose
+//! schema is implied by [`UserObject::r#type`]. The types in this module interpret
+//! the generic [`UserField`] tree of these objects so that values can be accessed
+//! directly.
+//!
+//! Each view is built with [`TryFrom`], which fails when the object type does not
+//! match:
+//!
+//! ```ignore
+//! let dblink = DbLink::try_from(&object)?;
+//! let biosample = dblink.bio_sample();
+//! ```
+//!
+//! [`DbLink`] and [`StructuredComment`] may also be built or edited, then converted
+//! back into a [`UserObject`]:
+//!
+//! ```ignore
+//! let mut comment = StructuredComment::new("Genome-Assembly-Data");
+//! comment.insert("Assembly Method", "SPAdes v. 3.15");
+//! bioseq.descr.get_or_insert_with(Vec::new).push(SeqDesc::User(UserObject::from(&comment)));
+//! ```
+//!
+//! Other types may be decoded while parsing, by registering an [`XmlNode`] for them
+//! with [`register_user_object()`]. The decoded value is kept alongside the generic
+//! fields, and is given by [`UserObject::decoded()`]:
+//!
+//! ```ignore
+//! register_user_object::<PipelineInfo>("PipelineInfo");
+//! let info: Option<&PipelineInfo> = object.decoded();
+//! ```
+
+use crate::general::{UserData, UserField, UserObject};
+use crate::parsing::XmlNode;
+use quick_xml::Reader;
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, OnceLock, RwLock};
+
+/// Value decoded from a [`UserObject`] by a parser registered with [`register_user_object()`]
+///
+/// Decoded values are derived from the fields of their object, so they are not
+/// compared by [`PartialEq`]: objects are equal when their fields are.
+#[derive(Clone)]
+pub struct DecodedUserObject(Arc<dyn Any + Send + Sync>);
+
+impl DecodedUserObject {
+    pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
+        self.0.downcast_ref()
+    }
+}
+
+impl fmt::Debug for DecodedUserObject {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("DecodedUserObject(..)")
+    }
+}
+
+impl PartialEq for DecodedUserObject {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+/// Parser for the `User-object` element of a registered type
+///
+/// Called once the start tag has been read, with the same contract as
+/// [`XmlNode::from_reader()`].
+pub type UserObjectParser = fn(&mut Reader<&[u8]>) -> Option<DecodedUserObject>;
+
+/// Parsers of `User-object` elements, keyed by object type
+fn registry() -> &'static RwLock<HashMap<String, UserObjectParser>> {
+    static REGISTRY: OnceLock<RwLock<HashMap<String, UserObjectParser>>> = OnceLock::new();
+    REGISTRY.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+/// Decode `User-object` elements of type `name` as `T` while parsing
+///
+/// `T` parses the whole `User-object` element, including its type and class. Returns
+/// the parser previously registered for `name`, if any.
+pub fn register_user_object<T: XmlNode + Send + Sync + 'static>(name: &str) -> Option<UserObjectParser> {
+    registry()
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(name.to_string(), |reader| {
+            T::from_reader(reader).map(|node| DecodedUserObject(Arc::new(node)))
+        })
+}
+
+/// Stop decoding `User-object` elements of type `name`
+pub fn unregister_user_object(name: &str) -> Option<UserObjectParser> {
+    registry().write().unwrap_or_else(|e| e.into_inner()).remove(name)
+}
+
+/// Whether any type is decoded, so that parsers only keep a copy of the reader when needed
+pub(crate) fn decodes_user_objects() -> bool {
+    !registry().read().unwrap_or_else(|e| e.into_inner()).is_empty()
+}
+
+/// Decode the element read by `reader` with the parser registered for `object`, if any
+pub(crate) fn decode_user_object(object: &UserObject, reader: &mut Reader<&[u8]>) -> Option<DecodedUserObject> {
+    let parser = *registry()
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(object.r#type.as_str()?)?;
+    parser(reader)
+}
+
+fn check_type(object: &UserObject, name: &str) -> Result<(), String> {
+    if object.is_type(name) {
+        Ok(())
+    } else {
+        Err(format!("User-object is not of type {}: {:?}", name, object.r#type))
+    }
+}
+
+/// Cross-references to other NCBI databases (`DBLink`)
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct DbLink {
+    /// database label and accessions in original order
+    pub links: Vec<(String, Vec<String>)>,
+}
+
+impl DbLink {
+    /// Accessions for a given database label (eg: "BioSample", "Sequence Read Archive")
+    pub fn get(&self, label: &str) -> Option<&[String]> {
+        self.links
+            .iter()
+            .find(|(db, _)| db == label)
+            .map(|(_, accessions)| accessions.as_slice())
+    }
+
+    pub fn bio_sample(&self) -> Option<&str> {
+        self.first("BioSample")
+    }
+
+    pub fn bio_project(&self) -> Option<&str> {
+        self.first("BioProject")
+    }
+
+    pub fn assembly(&self) -> Option<&str> {
+        self.first("Assembly")
+    }
+
+    pub fn sra(&self) -> Option<&[String]> {
+        self.get("Sequence Read Archive")
+    }
+
+    fn first(&self, label: &str) -> Option<&str> {
+        self.get(label)?.first().map(|s| s.as_str())
+    }
+
+    /// Add `accession` to the links of `label`, unless already given
+    pub fn insert(&mut self, label: &str, accession: &str) {
+        match self.links.iter_mut().find(|(db, _)| db == label) {
+            Some((_, accessions)) if accessions.iter().any(|a| a == accession) => (),
+            Some((_, accessions)) => accessions.push(accession.to_string()),
+            None => self.links.push((label.to_string(), vec![accession.to_string()])),
+        }
+    }
+
+    /// Replace all accessions of `label`, keeping its position if already present
+    pub fn set(&mut self, label: &str, accessions: Vec<String>) {
+        match self.links.iter_mut().find(|(db, _)| db == label) {
+            Some((_, existing)) => *existing = accessions,
+            None => self.links.push((label.to_string(), accessions)),
+        }
+    }
+
+    /// Remove the links of `label`, returning its accessions
+    pub fn remove(&mut self, label: &str) -> Option<Vec<String>> {
+        let position = self.links.iter().position(|(db, _)| db == label)?;
+        Some(self.links.remove(position).1)
+    }
+}
+
+impl From<&DbLink> for UserObject {
+    fn from(dblink: &DbLink) -> Self {
+        let mut object = UserObject::new("DBLink");
+        object.data = dblink
+            .links
+            .iter()
+            .map(|(label, accessions)| UserField::new(label, UserData::Strs(accessions.clone())))
+            .collect();
+        object
+    }
+}
+
+impl TryFrom<&UserObject> for DbLink {
+    type Error = String;
+
+    fn try_from(object: &UserObject) -> Result<Self, Self::Error> {
+        check_type(object, "DBLink")?;
+        let links = object
+            .data
+            .iter()
+            .filter_map(|field| {
+                let label = field.label.as_str()?.to_string();
+                let accessions = field.data.as_strs().into_iter().map(String::from).collect();
+                Some((label, accessions))
+            })
+            .collect();
+        Ok(Self { links })
+    }
+}
+
+/// Labels of the sentinel fields of a [`StructuredComment`]
+const PREFIX: &str = "StructuredCommentPrefix";
+const SUFFIX: &str = "StructuredCommentSuffix";
+
+/// Tabular key/value metadata (`StructuredComment`)
+///
+/// The prefix and suffix sentinels (eg: `##Genome-Assembly-Data-START##`) are
+/// stored separately from the remaining fields.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct StructuredComment {
+    pub prefix: Option<String>,
+    pub suffix: Option<String>,
+    /// key/value pairs in original order
+    pub fields: Vec<(String, String)>,
+}
+
+impl StructuredComment {
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn as_map(&self) -> HashMap<&str, &str> {
+        self.fields
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .collect()
+    }
+
+    /// Name of the comment block with sentinel markers removed
+    ///
+    /// eg: "Genome-Assembly-Data" from `##Genome-Assembly-Data-START##`
+    pub fn name(&self) -> Option<&str> {
+        let prefix = self.prefix.as_ref()?;
+        Some(prefix.trim_matches('#').trim_end_matches("-START"))
+    }
+
+    /// Empty comment block named `name`, with its prefix and suffix sentinels
+    ///
+    /// eg: `##Genome-Assembly-Data-START##` and `##Genome-Assembly-Data-END##` for
+    /// "Genome-Assembly-Data"
+    pub fn new(name: &str) -> Self {
+        Self {
+            prefix: Some(format!("##{}-START##", name)),
+            suffix: Some(format!("##{}-END##", name)),
+            fields: Vec::new(),
+        }
+    }
+
+    /// Set the value of `key`, keeping its position if already present
+    ///
+    /// Returns the previous value, if any.
+    pub fn insert(&mut self, key: &str, value: &str) -> Option<String> {
+        match self.fields.iter_mut().find(|(k, _)| k == key) {
+            Some((_, existing)) => Some(std::mem::replace(existing, value.to_string())),
+            None => {
+                self.fields.push((key.to_string(), value.to_string()));
+                None
+            }
+        }
+    }
+
+    /// Remove `key`, returning its value
+    pub fn remove(&mut self, key: &str) -> Option<String> {
+        let position = self.fields.iter().position(|(k, _)| k == key)?;
+        Some(self.fields.remove(position).1)
+    }
+
+    /// Set the value of `key` directly within a `StructuredComment` object
+    ///
+    /// Unlike converting an edited [`StructuredComment`] back into a [`UserObject`],
+    /// other fields are kept untouched, including those which are not strings. New
+    /// keys are inserted before the suffix sentinel.
+    pub fn set_in(object: &mut UserObject, key: &str, value: &str) -> Result<(), String> {
+        check_type(object, "StructuredComment")?;
+        let data = UserData::Str(value.to_string());
+        if let Some(field) = object.field_mut(key) {
+            field.data = data;
+            return Ok(());
+        }
+        let position = object
+            .data
+            .iter()
+            .position(|field| field.label.as_str() == Some(SUFFIX))
+            .unwrap_or(object.data.len());
+        object.data.insert(position, UserField::new(key, data));
+        Ok(())
+    }
+}
+
+impl From<&StructuredComment> for UserObject {
+    fn from(comment: &StructuredComment) -> Self {
+        let mut object = UserObject::new("StructuredComment");
+        let str_field = |label: &str, value: &str| UserField::new(label, UserData::Str(value.to_string()));
+        object.data.extend(comment.prefix.iter().map(|prefix| str_field(PREFIX, prefix)));
+        object.data.extend(comment.fields.iter().map(|(key, value)| str_field(key, value)));
+        object.data.extend(comment.suffix.iter().map(|suffix| str_field(SUFFIX, suffix)));
+        object
+    }
+}
+
+impl TryFrom<&UserObject> for StructuredComment {
+    type Error = String;
+
+    fn try_from(object: &UserObject) -> Result<Self, Self::Error> {
+        check_type(object, "StructuredComment")?;
+        let mut comment = Self::default();
+        for field in object.data.iter() {
+            let (label, value) = match (field.label.as_str(), field.data.as_str()) {
+                (Some(label), Some(value)) => (label, value.to_string()),
+                _ => continue,
+            };
+            match label {
+                PREFIX => comment.prefix = Some(value),
+                SUFFIX => comment.suffix = Some(value),
+                _ => comment.fields.push((label.to_string(), value)),
+            }
+        }
+        Ok(comment)
+    }
+}
+
+/// RefSeq provenance (`RefGeneTracking`)
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct RefGeneTracking {
+    /// RefSeq status (eg: "PIPELINE", "REVIEWED")
+    pub status: Option<String>,
+    pub generated: Option<bool>,
+    pub comment: Option<String>,
+    /// accessions of the records this record is identical to
+    pub identical_to: Vec<String>,
+    /// accessions of records this record was assembled from
+    pub assembly: Vec<String>,
+}
+
+/// Collect "accession" values from the nested fields used by [`RefGeneTracking`]
+fn tracking_accessions(data: &UserData) -> Vec<String> {
+    let mut accessions = Vec::new();
+    if let UserData::Fields(fields) = data {
+        for field in fields {
+            if field.label.as_str() == Some("accession") {
+                if let Some(accession) = field.data.as_str() {
+                    accessions.push(accession.to_string());
+                }
+            } else {
+                accessions.extend(tracking_accessions(&field.data));
+            }
+        }
+    }
+    accessions
+}
+
+impl TryFrom<&UserObject> for RefGeneTracking {
+    type Error = String;
+
+    fn try_from(object: &UserObject) -> Result<Self, Self::Error> {
+        check_type(object, "RefGeneTracking")?;
+        let mut tracking = Self::default();
+        for field in object.data.iter() {
+            match field.label.as_str() {
+                Some("Status") => tracking.status = field.data.as_str().map(String::from),
+                Some("Comment") => tracking.comment = field.data.as_str().map(String::from),
+                Some("Generated") => {
+                    if let UserData::Bool(generated) = field.data {
+                        tracking.generated = Some(generated);
+                    }
+                }
+                Some("IdenticalTo") => tracking.identical_to = tracking_accessions(&field.data),
+                Some("Assembly") => tracking.assembly = tracking_accessions(&field.data),
+                _ => (),
+            }
+        }
+        Ok(tracking)
+    }
+}
+
+/// Evidence used to predict a feature (`ModelEvidence`)
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct ModelEvidence {
+    /// prediction method (eg: "Protein Homology")
+    pub method: Option<String>,
+    /// remaining string-valued properties in original order
+    pub properties: Vec<(String, String)>,
+}
+
+impl ModelEvidence {
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.properties
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn seed_protein(&self) -> Option<&str> {
+        self.get("SeedProtein")
+    }
+
+    pub fn best_hmm_hit(&self) -> Option<&str> {
+        self.get("BestHMMHit")
+    }
+}
+
+/// Flatten string values, including those nested within [`UserData::Fields`]
+fn string_properties(fields: &[UserField], properties: &mut Vec<(String, String)>) {
+    for field in fields {
+        match (&field.data, field.label.as_str()) {
+            (UserData::Str(value), Some(label)) => {
+                properties.push((label.to_string(), value.clone()))
+            }
+            (UserData::Fields(inner), _) => string_properties(inner, properties),
+            (UserData::Object(object), _) => string_properties(&object.data, properties),
+            _ => (),
+        }
+    }
+}
+
+impl TryFrom<&UserObject> for ModelEvidence {
+    type Error = String;
+
+    fn try_from(object: &UserObject) -> Result<Self, Self::Error> {
+        check_type(object, "ModelEvidence")?;
+        let mut evidence = Self::default();
+        let mut properties = Vec::new();
+        string_properties(&object.data, &mut properties);
+        for (key, value) in properties {
+            if key == "Method" {
+                evidence.method = Some(value);
+            } else {
+                evidence.properties.push((key, value));
+            }
+        }
+        Ok(evidence)
+    }
+}
+
+/// Feature retrieval policy for viewers (`FeatureFetchPolicy`)
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct FeatureFetchPolicy {
+    /// eg: "OnlyNearFeatures"
+    pub policy: Option<String>,
+}
+
+impl TryFrom<&UserObject> for FeatureFetchPolicy {
+    type Error = String;
+
+    fn try_from(object: &UserObject) -> Result<Self, Self::Error> {
+        check_type(object, "FeatureFetchPolicy")?;
+        Ok(Self {
+            policy: object
+                .field("Policy")
+                .and_then(|field| field.data.as_str())
+                .map(String::from),
+        })
+    }
+}
@@ -0,0 +1,444 @@
+//! Basic validation of parsed records
+//!
+//! Mirrors a subset of the checks performed by the NCBI C++ validator, so that
+//! ingested data can be screened for common problems, and records can be checked
+//! before submission. Each problem is reported as a [`ValidationIssue`] rather than
+//! as an error, so that all problems of a record are found at once.
+//!
+//! The following is checked:
+//! - [`IssueCode::CdsLength`]: complete coding regions must have a length that is a
+//!   multiple of 3
+//! - [`IssueCode::LocationOutOfRange`]: feature locations must lie within the
+//!   sequence they are located on
+//! - [`IssueCode::MissingMolInfo`]: every [`BioSeq`] needs a [`SeqDesc::MolInfo`],
+//!   either of its own or of an enclosing set
+//! - [`IssueCode::PartialInconsistent`]: the partial flag of a feature must agree with
+//!   the partial ends of its location
+//! - [`IssueCode::OrfWithProduct`] and [`IssueCode::CdsConflict`]: coding region flags
+//!   which indicate an unreliable translation
+//! - [`IssueCode::DuplicateFeatId`]: feature ids must be unique within a record
+//! - [`IssueCode::BadLatLon`]: `lat-lon` qualifiers of sources must be in the format
+//!   read by [`parse_lat_lon()`]
+//!
+//! # Example
+//! ```ignore
+//! let issues = validate_bioseq_set(&set);
+//! for issue in issues.iter().filter(|issue| issue.severity >= Severity::Error) {
+//!     eprintln!("{}", issue);
+//! }
+//! ```
+
+use crate::general::IntFuzz;
+use crate::gff::{best_id, seq_id_label};
+use crate::seq::{BioSeq, SeqAnnot, SeqDesc};
+use crate::seqfeat::{parse_lat_lon, BioSource, CdRegionFrame, FeatId, SeqFeat, SeqFeatData, SubSourceSubType};
+use crate::seqloc::{SeqId, SeqLoc};
+use crate::seqset::{BioSeqSet, SeqEntry};
+use crate::tbl::feature_key;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+/// How serious a [`ValidationIssue`] is
+pub enum Severity {
+    /// worth noting, but not a problem
+    Info,
+    /// likely a problem, but acceptable in a submission
+    Warning,
+    /// must be corrected
+    Error,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+/// Kind of problem found
+pub enum IssueCode {
+    /// complete coding region with a length that is not a multiple of 3
+    CdsLength,
+    /// location outside of the sequence it is located on, or reversed
+    LocationOutOfRange,
+    /// sequence without molecule information
+    MissingMolInfo,
+    /// partial flag disagrees with the partial ends of the location
+    PartialInconsistent,
+    /// coding region flagged as an open reading frame also has a product
+    OrfWithProduct,
+    /// coding region flagged as not translating as expected
+    CdsConflict,
+    /// feature id used more than once
+    DuplicateFeatId,
+    /// source with a malformed `lat-lon` qualifier
+    BadLatLon,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+/// Problem found by validation
+pub struct ValidationIssue {
+    pub severity: Severity,
+    pub code: IssueCode,
+
+    /// Label of the sequence the problem was found on, if known
+    pub seq: Option<String>,
+
+    pub message: String,
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} [{:?}]", self.severity, self.code)?;
+        if let Some(seq) = &self.seq {
+            write!(f, " {}:", seq)?;
+        }
+        write!(f, " {}", self.message)
+    }
+}
+
+/// Validate a single sequence and its features
+pub fn validate_bioseq(bioseq: &BioSeq) -> Vec<ValidationIssue> {
+    let mut validator = Validator::new(vec![bioseq]);
+    validator.bioseq(bioseq, false);
+    validator.issues
+}
+
+/// Validate every sequence and feature contained by `set`
+///
+/// Features may be located on any sequence of the set, and descriptors of the set
+/// apply to every sequence it contains.
+pub fn validate_bioseq_set(set: &BioSeqSet) -> Vec<ValidationIssue> {
+    let mut validator = Validator::new(set.bioseqs());
+    validator.bioseq_set(set, false);
+    validator.issues
+}
+
+/// Validate a sequence or set
+pub fn validate_seq_entry(entry: &SeqEntry) -> Vec<ValidationIssue> {
+    match entry {
+        SeqEntry::Seq(bioseq) => validate_bioseq(bioseq),
+        SeqEntry::Set(set) => validate_bioseq_set(set),
+    }
+}
+
+/// Intervals and points of a location as `(id, from, to, partial)`
+type Span<'a> = (&'a SeqId, i64, i64, bool);
+
+struct Validator<'a> {
+    /// Labels and lengths of all sequences, by normalized id
+    seqs: HashMap<SeqId, (String, Option<u64>)>,
+
+    feat_ids: HashSet<&'a FeatId>,
+
+    issues: Vec<ValidationIssue>,
+}
+
+impl<'a> Validator<'a> {
+    fn new(bioseqs: Vec<&BioSeq>) -> Self {
+        let mut seqs = HashMap::new();
+        for bioseq in bioseqs {
+            let label = best_id(bioseq);
+            let length = bioseq.inst.as_ref().and_then(|inst| inst.length);
+            for id in bioseq.id.iter() {
+                seqs.insert(id.normalized(), (label.clone(), length));
+            }
+        }
+        Self {
+            seqs,
+            feat_ids: HashSet::new(),
+            issues: Vec::new(),
+        }
+    }
+
+    fn report(&mut self, severity: Severity, code: IssueCode, seq: Option<String>, message: String) {
+        self.issues.push(ValidationIssue {
+            severity,
+            code,
+            seq,
+            message,
+        })
+    }
+
+    fn bioseq_set(&mut self, set: &'a BioSeqSet, has_molinfo: bool) {
+        let has_molinfo = has_molinfo || contains_molinfo(set.descr.as_deref());
+        self.descriptors(set.descr.as_deref(), None);
+        for entry in set.seq_set.iter() {
+            match entry {
+                SeqEntry::Seq(bioseq) => self.bioseq(bioseq, has_molinfo),
+                SeqEntry::Set(set) => self.bioseq_set(set, has_molinfo),
+            }
+        }
+        self.annots(set.annot.as_deref());
+    }
+
+    fn bioseq(&mut self, bioseq: &'a BioSeq, has_molinfo: bool) {
+        if !has_molinfo && !contains_molinfo(bioseq.descr.as_deref()) {
+            self.report(
+                Severity::Error,
+                IssueCode::MissingMolInfo,
+                Some(best_id(bioseq)),
+                "Sequence has no MolInfo descriptor".to_string(),
+            );
+        }
+        self.descriptors(bioseq.descr.as_deref(), Some(best_id(bioseq)));
+        self.annots(bioseq.annot.as_deref());
+    }
+
+    fn descriptors(&mut self, descr: Option<&[SeqDesc]>, seq: Option<String>) {
+        for desc in descr.into_iter().flatten() {
+            if let SeqDesc::Source(source) = desc {
+                self.source(source, seq.clone());
+            }
+        }
+    }
+
+    fn source(&mut self, source: &BioSource, seq: Option<String>) {
+        if let Some(Err(message)) = source.subsource(SubSourceSubType::LatLon).map(parse_lat_lon) {
+            self.report(Severity::Warning, IssueCode::BadLatLon, seq, message);
+        }
+    }
+
+    fn annots(&mut self, annots: Option<&'a [SeqAnnot]>) {
+        for feat in annots.into_iter().flatten().flat_map(|annot| annot.features()) {
+            self.feature(feat);
+        }
+    }
+
+    fn feature(&mut self, feat: &'a SeqFeat) {
+        let spans = spans(&feat.location);
+        let seq = spans
+            .first()
+            .and_then(|(id, ..)| self.seqs.get(&id.normalized()))
+            .map(|(label, _)| label.clone());
+        let label = feature_label(feat, &spans);
+
+        for id in feat.id.iter().chain(feat.ids.iter().flatten()) {
+            if !self.feat_ids.insert(id) {
+                self.report(
+                    Severity::Error,
+                    IssueCode::DuplicateFeatId,
+                    seq.clone(),
+                    format!("{} reuses feature id {:?}", label, id),
+                );
+            }
+        }
+
+        let mut length = 0;
+        for &(id, from, to, _) in spans.iter() {
+            length += (to - from + 1).max(0);
+            let seq_length = match self.seqs.get(&id.normalized()) {
+                Some((_, Some(seq_length))) => *seq_length as i64,
+                _ => continue,
+            };
+            if from < 0 || to >= seq_length || from > to {
+                self.report(
+                    Severity::Error,
+                    IssueCode::LocationOutOfRange,
+                    seq.clone(),
+                    format!(
+                        "{} has interval {}..{} outside of sequence of length {}",
+                        label,
+                        from + 1,
+                        to + 1,
+                        seq_length
+                    ),
+                );
+            }
+        }
+        if let SeqLoc::Whole(id) = &feat.location {
+            if let Some((_, Some(seq_length))) = self.seqs.get(&id.normalized()) {
+                length = *seq_length as i64;
+            }
+        }
+
+        let location_partial = spans.iter().any(|(.., partial)| *partial);
+        let flag_partial = feat.partial == Some(true);
+        if location_partial != flag_partial {
+            let message = if flag_partial {
+                format!("{} is flagged as partial, but its location is complete", label)
+            } else {
+                format!("{} has a partial location, but is not flagged as partial", label)
+            };
+            self.report(Severity::Warning, IssueCode::PartialInconsistent, seq.clone(), message);
+        }
+
+        let cds = match &feat.data {
+            SeqFeatData::CdRegion(cds) => cds,
+            SeqFeatData::BioSrc(source) => return self.source(source, seq),
+            _ => return,
+        };
+        let frame = match cds.frame {
+            CdRegionFrame::Two => 1,
+            CdRegionFrame::Three => 2,
+            _ => 0,
+        };
+        let checked = !(location_partial
+            || flag_partial
+            || feat.pseudo == Some(true)
+            || feat.except == Some(true)
+            || spans.is_empty() && !matches!(feat.location, SeqLoc::Whole(_)));
+        if checked && (length - frame) % 3 != 0 {
+            self.report(
+                Severity::Error,
+                IssueCode::CdsLength,
+                seq.clone(),
+                format!("{} has length {}, which is not a multiple of 3", label, length),
+            );
+        }
+        if cds.orf == Some(true) && feat.product.is_some() {
+            self.report(
+                Severity::Warning,
+                IssueCode::OrfWithProduct,
+                seq.clone(),
+                format!("{} is flagged as an open reading frame, but has a product", label),
+            );
+        }
+        if cds.conflict == Some(true) {
+            self.report(
+                Severity::Warning,
+                IssueCode::CdsConflict,
+                seq,
+                format!("{} has the conflict flag set", label),
+            );
+        }
+    }
+}
+
+fn contains_molinfo(descr: Option<&[SeqDesc]>) -> bool {
+    descr
+        .into_iter()
+        .flatten()
+        .any(|desc| matches!(desc, SeqDesc::MolInfo(_)))
+}
+
+/// Whether `fuzz` marks an end as extending beyond the given position
+fn is_partial(fuzz: &Option<IntFuzz>) -> bool {
+    fuzz.as_ref().is_some_and(IntFuzz::is_partial_limit)
+}
+
+/// Flatten the intervals and points of a location
+fn spans(loc: &SeqLoc) -> Vec<Span<'_>> {
+    match loc {
+        SeqLoc::Int(i) => vec![(&i.id, i.from, i.to, is_partial(&i.fuzz_from) || is_partial(&i.fuzz_to))],
+        SeqLoc::PackedInt(intervals) => intervals
+            .iter()
+            .map(|i| (&i.id, i.from, i.to, is_partial(&i.fuzz_from) || is_partial(&i.fuzz_to)))
+            .collect(),
+        SeqLoc::Pnt(point) => vec![(&point.id, point.point, point.point, is_partial(&point.fuzz))],
+        SeqLoc::PackedPnt(points) => points
+            .points
+            .iter()
+            .map(|p| (&points.id, *p, *p, is_partial(&points.fuzz)))
+            .collect(),
+        SeqLoc::Mix(mix) => mix.0.iter().flat_map(spans).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Key and 1-based extent of a feature, for messages
+fn feature_label(feat: &SeqFeat, spans: &[Span]) -> String {
+    let key = feature_key(feat);
+    match (spans.first(), spans.last()) {
+        (Some((id, from, ..)), Some((_, _, to, _))) => {
+            format!("{} {}:{}..{}", key, seq_id_label(id), from + 1, to + 1)
+        }
+        _ => key,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{interval, BioSeqBuilder, SeqFeatBuilder};
+    use crate::general::ObjectId;
+    use crate::seq::{BioMol, MolInfo, SeqAnnotData};
+
+    fn id() -> SeqId {
+        SeqId::from_defline("gb|AY123456.1|").unwrap()
+    }
+
+    fn bioseq(features: Vec<SeqFeat>) -> BioSeq {
+        let molinfo = MolInfo {
+            bio_mol: BioMol::Genomic,
+            ..MolInfo::default()
+        };
+        let mut builder = BioSeqBuilder::new(id())
+            .iupacna(&"ATG".repeat(100))
+            .descriptor(SeqDesc::MolInfo(molinfo));
+        for feat in features {
+            builder = builder.feature(feat);
+        }
+        builder.build().unwrap()
+    }
+
+    fn codes(issues: &[ValidationIssue]) -> Vec<IssueCode> {
+        issues.iter().map(|issue| issue.code).collect()
+    }
+
+    #[test]
+    fn valid_bioseq() {
+        let cds = SeqFeatBuilder::cds(interval(&id(), 0, 299)).build().unwrap();
+        assert_eq!(validate_bioseq(&bioseq(vec![cds])), Vec::new());
+    }
+
+    #[test]
+    fn cds_length_and_flags() {
+        let mut cds = SeqFeatBuilder::cds(interval(&id(), 0, 100)).build().unwrap();
+        if let SeqFeatData::CdRegion(cdregion) = &mut cds.data {
+            cdregion.conflict = Some(true);
+        }
+        let partial = SeqFeatBuilder::cds(interval(&id(), 0, 100)).partial(true).build().unwrap();
+
+        let issues = validate_bioseq(&bioseq(vec![cds, partial]));
+        assert_eq!(
+            codes(&issues),
+            vec![IssueCode::CdsLength, IssueCode::CdsConflict, IssueCode::PartialInconsistent]
+        );
+        assert_eq!(issues[0].severity, Severity::Error);
+        assert_eq!(issues[0].seq.as_deref(), Some("AY123456.1"));
+        assert!(issues[0].message.starts_with("CDS AY123456.1:1..101"));
+    }
+
+    #[test]
+    fn range_molinfo_and_duplicate_ids() {
+        let mut gene = SeqFeatBuilder::gene("abcA", interval(&id(), 0, 99)).build().unwrap();
+        gene.id = Some(FeatId::Local(ObjectId::Id(1)));
+        let other = gene.clone();
+
+        // the builder rejects locations outside of the sequence
+        let mut bioseq = bioseq(vec![gene, other]);
+        if let Some(SeqAnnotData::FTable(feats)) = bioseq.annot.as_mut().map(|annots| &mut annots[0].data) {
+            feats[1].location = interval(&id(), 250, 300);
+        }
+        bioseq.descr = None;
+
+        let issues = validate_bioseq(&bioseq);
+        assert_eq!(
+            codes(&issues),
+            vec![
+                IssueCode::MissingMolInfo,
+                IssueCode::DuplicateFeatId,
+                IssueCode::LocationOutOfRange
+            ]
+        );
+        assert!(issues.iter().all(|issue| issue.severity == Severity::Error));
+    }
+
+    #[test]
+    fn bad_lat_lon() {
+        use crate::builder::BioSourceBuilder;
+        use crate::seqfeat::SubSourceSubType;
+
+        let source = |lat_lon: &str| {
+            let source = BioSourceBuilder::new("Escherichia coli")
+                .subsource(SubSourceSubType::LatLon, lat_lon)
+                .build()
+                .unwrap();
+            SeqDesc::Source(source)
+        };
+        let mut bioseq = bioseq(Vec::new());
+        bioseq.descr.as_mut().unwrap().push(source("35.12 N 120.5 W"));
+        assert_eq!(validate_bioseq(&bioseq), Vec::new());
+
+        bioseq.descr.as_mut().unwrap().push(source("35.12N, 120.5W"));
+        let issues = validate_bioseq(&bioseq);
+        assert_eq!(codes(&issues), vec![IssueCode::BadLatLon]);
+        assert_eq!(issues[0].severity, Severity::Warning);
+    }
+}
@@ -0,0 +1,294 @@
+//! Generic traversal over parsed object trees
+//!
+//! Implement [`Visitor`] by overriding only the methods of interest, then pass it
+//! to [`walk()`] along with any [`Walk`] root (eg: [`BioSeqSet`] or [`Entrezgene`]).
+//! Each node is visited before its children, in document order.
+//!
+//! ```ignore
+//! #[derive(Default)]
+//! struct CountFeats(usize);
+//!
+//! impl Visitor for CountFeats {
+//!     fn visit_seq_feat(&mut self, _: &SeqFeat) {
+//!         self.0 += 1;
+//!     }
+//! }
+//!
+//! let mut counter = CountFeats::default();
+//! walk(&set, &mut counter);
+//! ```
+
+use crate::entrezgene::{Entrezgene, GeneCommentary};
+use crate::general::{DbTag, UserObject};
+use crate::r#pub::{Pub, PubSet};
+use crate::seq::{BioSeq, SeqAnnot, SeqAnnotData, SeqDesc};
+use crate::seqalign::SeqAlign;
+use crate::seqfeat::{BioSource, SeqFeat, SeqFeatData};
+use crate::seqset::{BioSeqSet, SeqEntry};
+
+/// Callbacks invoked by [`walk()`]
+///
+/// All methods have empty default implementations.
+#[allow(unused_variables)]
+pub trait Visitor {
+    fn visit_bioseq_set(&mut self, set: &BioSeqSet) {}
+    fn visit_bioseq(&mut self, bioseq: &BioSeq) {}
+    fn visit_seq_desc(&mut self, desc: &SeqDesc) {}
+    fn visit_seq_annot(&mut self, annot: &SeqAnnot) {}
+    fn visit_seq_feat(&mut self, feat: &SeqFeat) {}
+    fn visit_seq_align(&mut self, align: &SeqAlign) {}
+    fn visit_bio_source(&mut self, source: &BioSource) {}
+    fn visit_pub(&mut self, r#pub: &Pub) {}
+    fn visit_db_tag(&mut self, tag: &DbTag) {}
+    fn visit_user_object(&mut self, object: &UserObject) {}
+    fn visit_entrezgene(&mut self, gene: &Entrezgene) {}
+    fn visit_gene_commentary(&mut self, commentary: &GeneCommentary) {}
+}
+
+/// An object that can be traversed by a [`Visitor`]
+pub trait Walk {
+    /// Visit `self`, then all children
+    fn walk<V: Visitor + ?Sized>(&self, visitor: &mut V);
+}
+
+/// Traverse `root` and all of its children with `visitor`
+pub fn walk<T: Walk + ?Sized, V: Visitor + ?Sized>(root: &T, visitor: &mut V) {
+    root.walk(visitor)
+}
+
+impl<T: Walk> Walk for [T] {
+    fn walk<V: Visitor + ?Sized>(&self, visitor: &mut V) {
+        for item in self.iter() {
+            item.walk(visitor);
+        }
+    }
+}
+
+impl<T: Walk> Walk for Vec<T> {
+    fn walk<V: Visitor + ?Sized>(&self, visitor: &mut V) {
+        self.as_slice().walk(visitor)
+    }
+}
+
+impl Walk for BioSeqSet {
+    fn walk<V: Visitor + ?Sized>(&self, visitor: &mut V) {
+        visitor.visit_bioseq_set(self);
+        if let Some(descr) = &self.descr {
+            descr.walk(visitor);
+        }
+        self.seq_set.walk(visitor);
+        if let Some(annot) = &self.annot {
+            annot.walk(visitor);
+        }
+    }
+}
+
+impl Walk for SeqEntry {
+    fn walk<V: Visitor + ?Sized>(&self, visitor: &mut V) {
+        match self {
+            Self::Seq(bioseq) => bioseq.walk(visitor),
+            Self::Set(set) => set.walk(visitor),
+        }
+    }
+}
+
+impl Walk for BioSeq {
+    fn walk<V: Visitor + ?Sized>(&self, visitor: &mut V) {
+        visitor.visit_bioseq(self);
+        if let Some(descr) = &self.descr {
+            descr.walk(visitor);
+        }
+        if let Some(annot) = &self.annot {
+            annot.walk(visitor);
+        }
+    }
+}
+
+impl Walk for SeqDesc {
+    fn walk<V: Visitor + ?Sized>(&self, visitor: &mut V) {
+        visitor.visit_seq_desc(self);
+        match self {
+            Self::Source(source) => source.walk(visitor),
+            Self::Pub(desc) => desc.r#pub.walk(visitor),
+            Self::User(object) => visitor.visit_user_object(object),
+            _ => (),
+        }
+    }
+}
+
+impl Walk for SeqAnnot {
+    fn walk<V: Visitor + ?Sized>(&self, visitor: &mut V) {
+        visitor.visit_seq_annot(self);
+        match &self.data {
+            SeqAnnotData::FTable(feats) => feats.walk(visitor),
+            SeqAnnotData::Align(aligns) => {
+                for align in aligns.iter() {
+                    visitor.visit_seq_align(align);
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+impl Walk for SeqFeat {
+    fn walk<V: Visitor + ?Sized>(&self, visitor: &mut V) {
+        visitor.visit_seq_feat(self);
+        match &self.data {
+            SeqFeatData::Gene(gene) => {
+                for tag in gene.db.iter().flatten() {
+                    visitor.visit_db_tag(tag);
+                }
+            }
+            SeqFeatData::Org(org) => {
+                for tag in org.db.iter().flatten() {
+                    visitor.visit_db_tag(tag);
+                }
+            }
+            SeqFeatData::BioSrc(source) => source.walk(visitor),
+            SeqFeatData::Pub(desc) => desc.r#pub.walk(visitor),
+            _ => (),
+        }
+        for tag in self.dbxref.iter().flatten() {
+            visitor.visit_db_tag(tag);
+        }
+        if let Some(PubSet::Pub(pubs)) = &self.cit {
+            pubs.walk(visitor);
+        }
+        if let Some(ext) = &self.ext {
+            visitor.visit_user_object(ext);
+        }
+        for ext in self.exts.iter().flatten() {
+            visitor.visit_user_object(ext);
+        }
+    }
+}
+
+impl Walk for BioSource {
+    fn walk<V: Visitor + ?Sized>(&self, visitor: &mut V) {
+        visitor.visit_bio_source(self);
+        for tag in self.org.db.iter().flatten() {
+            visitor.visit_db_tag(tag);
+        }
+    }
+}
+
+impl Walk for Pub {
+    fn walk<V: Visitor + ?Sized>(&self, visitor: &mut V) {
+        visitor.visit_pub(self);
+        if let Self::Equiv(equiv) = self {
+            equiv.walk(visitor);
+        }
+    }
+}
+
+impl Walk for Entrezgene {
+    fn walk<V: Visitor + ?Sized>(&self, visitor: &mut V) {
+        visitor.visit_entrezgene(self);
+        self.source.walk(visitor);
+        for tag in self.gene.db.iter().flatten() {
+            visitor.visit_db_tag(tag);
+        }
+        let commentaries = [
+            &self.locus,
+            &self.properties,
+            &self.refgene,
+            &self.homology,
+            &self.comments,
+        ];
+        for commentary in commentaries.into_iter().flatten() {
+            commentary.walk(visitor);
+        }
+        for tag in self.unique_keys.iter().flatten() {
+            visitor.visit_db_tag(tag);
+        }
+        for tag in self.non_unique_keys.iter().flatten() {
+            visitor.visit_db_tag(tag);
+        }
+    }
+}
+
+impl Walk for GeneCommentary {
+    fn walk<V: Visitor + ?Sized>(&self, visitor: &mut V) {
+        visitor.visit_gene_commentary(self);
+        if let Some(refs) = &self.refs {
+            refs.walk(visitor);
+        }
+        for source in self.source.iter().flatten() {
+            if let Some(tag) = &source.src {
+                visitor.visit_db_tag(tag);
+            }
+        }
+        let children = [&self.products, &self.properties, &self.comment];
+        for child in children.into_iter().flatten() {
+            child.walk(visitor);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use crate::general::DbTag;
+    use crate::seq::BioSeq;
+    use crate::seqfeat::{SeqFeat, SeqFeatData};
+    use crate::visit::{walk, Visitor};
+    use crate::{get_local_xml, parse_xml, DataType};
+
+    #[derive(Default)]
+    struct Counter {
+        bioseqs: usize,
+        feats: HashMap<&'static str, usize>,
+        tags: Vec<DbTag>,
+    }
+
+    impl Visitor for Counter {
+        fn visit_bioseq(&mut self, _: &BioSeq) {
+            self.bioseqs += 1;
+        }
+
+        fn visit_seq_feat(&mut self, feat: &SeqFeat) {
+            let key = match feat.data {
+                SeqFeatData::Gene(_) => "gene",
+                SeqFeatData::CdRegion(_) => "cdregion",
+                _ => "other",
+            };
+            *self.feats.entry(key).or_default() += 1;
+        }
+
+        fn visit_db_tag(&mut self, tag: &DbTag) {
+            self.tags.push(tag.clone());
+        }
+    }
+
+    #[test]
+    fn test_walk_bioseq_set() {
+        let data = get_local_xml("tests/data/2519734237.xml");
+        let set = match parse_xml(data.as_str()).unwrap() {
+            DataType::BioSeqSet(set) => set,
+            _ => panic!("No Bioseq set found"),
+        };
+
+        let mut counter = Counter::default();
+        walk(&set, &mut counter);
+
+        assert_eq!(counter.bioseqs, 1);
+        assert_eq!(counter.feats.get("gene"), Some(&88));
+        assert_eq!(counter.feats.get("cdregion"), Some(&88));
+        assert!(counter.tags.iter().any(|tag| tag.db == "taxon"));
+    }
+
+    #[test]
+    fn test_walk_entrezgene() {
+        let data = get_local_xml("tests/data/tp73.genbank.xml");
+        let genes = match parse_xml(data.as_str()).unwrap() {
+            DataType::EntrezgeneSet(genes) => genes,
+            _ => panic!("No Entrezgene set found"),
+        };
+
+        let mut counter = Counter::default();
+        walk(&genes, &mut counter);
+
+        assert!(counter.tags.iter().any(|tag| tag.db == "taxon"));
+    }
+}
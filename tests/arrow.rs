@@ -0,0 +1,53 @@
+#![cfg(feature = "arrow")]
+
+use arrow_array::{Array, StringArray, UInt64Array};
+use ncbi::arrow::{descriptors_batch, features_batch, sequences_batch};
+use ncbi::record::Record;
+use ncbi::{get_local_xml, parse_xml, DataType};
+
+fn wgs() -> Vec<Record> {
+    match parse_xml(&get_local_xml("tests/data/2519734237.xml")).unwrap() {
+        DataType::BioSeqSet(set) => Record::from_set(&set),
+        _ => panic!("No Bioseq set found"),
+    }
+}
+
+fn strings<'a>(batch: &'a arrow_array::RecordBatch, name: &str) -> &'a StringArray {
+    batch.column_by_name(name).unwrap().as_any().downcast_ref().unwrap()
+}
+
+#[test]
+fn wgs_tables() {
+    let records = wgs();
+
+    let sequences = sequences_batch(&records).unwrap();
+    assert_eq!(sequences.num_rows(), 1);
+    assert_eq!(strings(&sequences, "seq_id").value(0), "NZ_JARQWN010000024.1");
+    assert_eq!(strings(&sequences, "mol").value(0), "dna");
+    let length: &UInt64Array = sequences.column_by_name("length").unwrap().as_any().downcast_ref().unwrap();
+    assert_eq!(length.value(0), 86489);
+
+    let features = features_batch(&records).unwrap();
+    assert_eq!(features.num_rows(), records[0].features.len());
+    let feat_type = strings(&features, "feat_type");
+    let product = strings(&features, "product");
+    let cds = (0..features.num_rows()).filter(|i| feat_type.value(*i) == "CDS").collect::<Vec<_>>();
+    assert!(!cds.is_empty());
+    assert!(cds.iter().any(|i| !product.is_null(*i)));
+    assert_eq!(strings(&features, "strand").value(0), "minus");
+
+    let descriptors = descriptors_batch(&records).unwrap();
+    let kinds = strings(&descriptors, "descriptor");
+    let values = strings(&descriptors, "value");
+    let source = (0..descriptors.num_rows()).find(|i| kinds.value(*i) == "source").unwrap();
+    assert_eq!(values.value(source), "Klebsiella pneumoniae");
+}
+
+#[cfg(feature = "parquet")]
+#[test]
+fn write_parquet() {
+    let batch = features_batch(&wgs()).unwrap();
+    let mut file = Vec::new();
+    ncbi::arrow::write_parquet(&[batch], &mut file).unwrap();
+    assert!(file.starts_with(b"PAR1") && file.ends_with(b"PAR1"));
+}
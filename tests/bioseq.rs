@@ -1,18 +1,23 @@
 use ncbi::biblio::{
-    Affil, AffilStd, AuthList, AuthListNames, Author, CitGen, CitSub, CitSubMedium,
+    Affil, AffilStd, AuthList, AuthListNames, Author, CitGen, CitLetType, CitSub, CitSubMedium, IdPatChoice,
+    TitleItem,
 };
 use ncbi::general::{
-    Date, DateStd, DbTag, NameStd, ObjectId, PersonId, UserData, UserField, UserObject,
+    Date, DateStd, DbTag, DbXref, NameStd, ObjectId, PersonId, UserData, UserField, UserObject,
 };
-use ncbi::r#pub::Pub;
-use ncbi::seq::{BioMol, BioSeq, DeltaSeq, Mol, MolInfo, MolTech, PubDesc, Repr, SeqAnnotData, SeqDesc, SeqExt, SeqInst, Strand};
-use ncbi::seqfeat::{BinomialOrgName, BioSource, BioSourceGenome, GeneticCodeOpt, OrgMod, OrgModSubType, OrgName, OrgNameChoice, OrgRef, SeqFeatData, SubSource, SubSourceSubType};
-use ncbi::seqloc::{NaStrand, SeqId, SeqInterval, SeqLoc, TextseqId};
+use ncbi::r#pub::{CitationStyle, Pub};
+use ncbi::parsing::{parse_node, read_node, record_spans, ObjectHandle};
+use ncbi::seq::{BioMol, BioSeq, DeltaSeq, Descriptors, LinkageEvidenceType, Mol, MolCompleteness, MolInfo, MolTech, PubDesc, Repr, SeqAnnotData, SeqData, SeqDesc, SeqExt, SeqGapLinkage, SeqGapType, SeqInst, Strand};
+use ncbi::seqfeat::{BinomialOrgName, BioSource, BioSourceGenome, GeneRef, GeneticCodeOpt, OrgMod, OrgModSubType, OrgName, OrgNameChoice, OrgRef, SeqFeat, SeqFeatData, SeqFeatXref, SubSource, SubSourceSubType};
+use ncbi::seqloc::{NaStrand, PDBSeqId, SeqId, SeqInterval, SeqLoc, SeqLocMix, TextseqId};
 use ncbi::seqset::{BioSeqSet, SeqEntry};
+use ncbi::user::{DbLink, FeatureFetchPolicy, RefGeneTracking, StructuredComment};
 use ncbi::{get_local_xml, parse_xml, DataType};
+use quick_xml::Reader;
 use std::ops::Not;
 
 const DATA1: &str = "tests/data/2519734237.xml";
+const PDB_PROTEIN: &str = "tests/data/pdb_protein.xml";
 
 fn get_bioseq(path: &str) -> BioSeq {
     let set = get_seq_set(path);
@@ -125,7 +130,7 @@ fn parse_bioseq_descr_source() {
         genome: BioSourceGenome::Genomic,
         org: OrgRef {
             taxname: "Klebsiella pneumoniae".to_string().into(),
-            db: vec![DbTag { db: "taxon".to_string(), tag: ObjectId::Id(573) }].into(),
+            db: vec![DbTag { db: "taxon".into(), tag: ObjectId::Id(573) }].into(),
             orgname: OrgName {
                 name: OrgNameChoice::Binomial(BinomialOrgName {
                     genus: "Klebsiella".to_string(),
@@ -293,231 +298,236 @@ fn parse_bioseq_desc_user() {
 
     let expected1 = UserObject {
         class: None,
-        r#type: ObjectId::Str("DBLink".to_string()),
+        r#type: ObjectId::Str("DBLink".into()),
         data: vec![
             UserField {
-                label: ObjectId::Str("BioSample".to_string()),
+                label: ObjectId::Str("BioSample".into()),
                 num: 1.into(),
                 data: UserData::Strs(vec!["SAMN33942939".to_string()]),
             },
             UserField {
-                label: ObjectId::Str("BioProject".to_string()),
+                label: ObjectId::Str("BioProject".into()),
                 num: 1.into(),
                 data: UserData::Strs(vec!["PRJNA224116".to_string()]),
             },
             UserField {
-                label: ObjectId::Str("Assembly".to_string()),
+                label: ObjectId::Str("Assembly".into()),
                 num: 1.into(),
                 data: UserData::Strs(vec!["GCF_030238925.1".to_string()]),
             },
         ],
+        decoded: None,
     };
     let expected2 = UserObject {
         class: None,
         r#type: ObjectId::Str("StructuredComment".into()),
         data: vec![
             UserField {
-                label: ObjectId::Str("StructuredCommentPrefix".to_string()),
+                label: ObjectId::Str("StructuredCommentPrefix".into()),
                 num: None,
                 data: UserData::Str("##Genome-Annotation-Data-START##".to_string()),
             },
             UserField {
-                label: ObjectId::Str("Annotation Provider".to_string()),
+                label: ObjectId::Str("Annotation Provider".into()),
                 num: None,
                 data: UserData::Str("NCBI RefSeq".to_string()),
             },
             UserField {
-                label: ObjectId::Str("Annotation Date".to_string()),
+                label: ObjectId::Str("Annotation Date".into()),
                 num: None,
                 data: UserData::Str("06/09/2023 17:06:50".to_string()),
             },
             UserField {
-                label: ObjectId::Str("Annotation Pipeline".to_string()),
+                label: ObjectId::Str("Annotation Pipeline".into()),
                 num: None,
                 data: UserData::Str(
                     "NCBI Prokaryotic Genome Annotation Pipeline (PGAP)".to_string(),
                 ),
             },
             UserField {
-                label: ObjectId::Str("Annotation Method".to_string()),
+                label: ObjectId::Str("Annotation Method".into()),
                 num: None,
                 data: UserData::Str("Best-placed reference protein set; GeneMarkS-2+".to_string()),
             },
             UserField {
-                label: ObjectId::Str("Annotation Software revision".to_string()),
+                label: ObjectId::Str("Annotation Software revision".into()),
                 num: None,
                 data: UserData::Str("6.5".to_string()),
             },
             UserField {
-                label: ObjectId::Str("Features Annotated".to_string()),
+                label: ObjectId::Str("Features Annotated".into()),
                 num: None,
                 data: UserData::Str("Gene; CDS; rRNA; tRNA; ncRNA".to_string()),
             },
             UserField {
-                label: ObjectId::Str("Genes (total)".to_string()),
+                label: ObjectId::Str("Genes (total)".into()),
                 num: None,
                 data: UserData::Str("5,288".to_string()),
             },
             UserField {
-                label: ObjectId::Str("CDSs (total)".to_string()),
+                label: ObjectId::Str("CDSs (total)".into()),
                 num: None,
                 data: UserData::Str("5,202".to_string()),
             },
             UserField {
-                label: ObjectId::Str("Genes (coding)".to_string()),
+                label: ObjectId::Str("Genes (coding)".into()),
                 num: None,
                 data: UserData::Str("5,041".to_string()),
             },
             UserField {
-                label: ObjectId::Str("CDSs (with protein)".to_string()),
+                label: ObjectId::Str("CDSs (with protein)".into()),
                 num: None,
                 data: UserData::Str("5,041".to_string()),
             },
             UserField {
-                label: ObjectId::Str("Genes (RNA)".to_string()),
+                label: ObjectId::Str("Genes (RNA)".into()),
                 num: None,
                 data: UserData::Str("86".to_string()),
             },
             UserField {
-                label: ObjectId::Str("rRNAs".to_string()),
+                label: ObjectId::Str("rRNAs".into()),
                 num: None,
                 data: UserData::Str("2, 3, 6 (5S, 16S, 23S)".to_string()),
             },
             UserField {
-                label: ObjectId::Str("complete rRNAs".to_string()),
+                label: ObjectId::Str("complete rRNAs".into()),
                 num: None,
                 data: UserData::Str("2 (5S)".to_string()),
             },
             UserField {
-                label: ObjectId::Str("partial rRNAs".to_string()),
+                label: ObjectId::Str("partial rRNAs".into()),
                 num: None,
                 data: UserData::Str("3, 6 (16S, 23S)".to_string()),
             },
             UserField {
-                label: ObjectId::Str("tRNAs".to_string()),
+                label: ObjectId::Str("tRNAs".into()),
                 num: None,
                 data: UserData::Str("64".to_string()),
             },
             UserField {
-                label: ObjectId::Str("ncRNAs".to_string()),
+                label: ObjectId::Str("ncRNAs".into()),
                 num: None,
                 data: UserData::Str("11".to_string()),
             },
             UserField {
-                label: ObjectId::Str("Pseudo Genes (total)".to_string()),
+                label: ObjectId::Str("Pseudo Genes (total)".into()),
                 num: None,
                 data: UserData::Str("161".to_string()),
             },
             UserField {
-                label: ObjectId::Str("CDSs (without protein)".to_string()),
+                label: ObjectId::Str("CDSs (without protein)".into()),
                 num: None,
                 data: UserData::Str("161".to_string()),
             },
             UserField {
-                label: ObjectId::Str("Pseudo Genes (ambiguous residues)".to_string()),
+                label: ObjectId::Str("Pseudo Genes (ambiguous residues)".into()),
                 num: None,
                 data: UserData::Str("0 of 161".to_string()),
             },
             UserField {
-                label: ObjectId::Str("Pseudo Genes (frameshifted)".to_string()),
+                label: ObjectId::Str("Pseudo Genes (frameshifted)".into()),
                 num: None,
                 data: UserData::Str("59 of 161".to_string()),
             },
             UserField {
-                label: ObjectId::Str("Pseudo Genes (incomplete)".to_string()),
+                label: ObjectId::Str("Pseudo Genes (incomplete)".into()),
                 num: None,
                 data: UserData::Str("107 of 161".to_string()),
             },
             UserField {
-                label: ObjectId::Str("Pseudo Genes (internal stop)".to_string()),
+                label: ObjectId::Str("Pseudo Genes (internal stop)".into()),
                 num: None,
                 data: UserData::Str("25 of 161".to_string()),
             },
             UserField {
-                label: ObjectId::Str("Pseudo Genes (multiple problems)".to_string()),
+                label: ObjectId::Str("Pseudo Genes (multiple problems)".into()),
                 num: None,
                 data: UserData::Str("27 of 161".to_string()),
             },
             UserField {
-                label: ObjectId::Str("StructuredCommentSuffix".to_string()),
+                label: ObjectId::Str("StructuredCommentSuffix".into()),
                 num: None,
                 data: UserData::Str("##Genome-Annotation-Data-END##".to_string()),
             },
         ],
+        decoded: None,
     };
     let expected3 = UserObject {
         class: None,
-        r#type: ObjectId::Str("RefGeneTracking".to_string()),
+        r#type: ObjectId::Str("RefGeneTracking".into()),
         data: vec![
             UserField {
-                label: ObjectId::Str("Status".to_string()),
+                label: ObjectId::Str("Status".into()),
                 num: None,
                 data: UserData::Str("PIPELINE".to_string()),
             },
             UserField {
-                label: ObjectId::Str("IdenticalTo".to_string()),
+                label: ObjectId::Str("IdenticalTo".into()),
                 num: None,
                 data: UserData::Fields(vec![UserField {
                     label: ObjectId::Id(0),
                     num: None,
                     data: UserData::Fields(vec![UserField {
-                        label: ObjectId::Str("accession".to_string()),
+                        label: ObjectId::Str("accession".into()),
                         num: None,
                         data: UserData::Str("JARQWN010000024.1".to_string()),
                     }]),
                 }]),
             },
         ],
+        decoded: None,
     };
     let expected4 = UserObject {
         class: None,
-        r#type: ObjectId::Str("FeatureFetchPolicy".to_string()),
+        r#type: ObjectId::Str("FeatureFetchPolicy".into()),
         data: vec![UserField {
-            label: ObjectId::Str("Policy".to_string()),
+            label: ObjectId::Str("Policy".into()),
             num: None,
             data: UserData::Str("OnlyNearFeatures".to_string()),
         }],
+        decoded: None,
     };
     let expected5 = UserObject {
         class: None,
-        r#type: ObjectId::Str("StructuredComment".to_string()),
+        r#type: ObjectId::Str("StructuredComment".into()),
         data: vec![
             UserField {
-                label: ObjectId::Str("StructuredCommentPrefix".to_string()),
+                label: ObjectId::Str("StructuredCommentPrefix".into()),
                 num: None,
                 data: UserData::Str("##Genome-Assembly-Data-START##".to_string()),
             },
             UserField {
-                label: ObjectId::Str("Assembly Method".to_string()),
+                label: ObjectId::Str("Assembly Method".into()),
                 num: None,
                 data: UserData::Str("SPAdes v. 1".to_string()),
             },
             UserField {
-                label: ObjectId::Str("Genome Representation".to_string()),
+                label: ObjectId::Str("Genome Representation".into()),
                 num: None,
                 data: UserData::Str("Full".to_string()),
             },
             UserField {
-                label: ObjectId::Str("Expected Final Version".to_string()),
+                label: ObjectId::Str("Expected Final Version".into()),
                 num: None,
                 data: UserData::Str("Yes".to_string()),
             },
             UserField {
-                label: ObjectId::Str("Genome Coverage".to_string()),
+                label: ObjectId::Str("Genome Coverage".into()),
                 num: None,
                 data: UserData::Str("100x".to_string()),
             },
             UserField {
-                label: ObjectId::Str("Sequencing Technology".to_string()),
+                label: ObjectId::Str("Sequencing Technology".into()),
                 num: None,
                 data: UserData::Str("Illumina HiSeq".to_string()),
             },
             UserField {
-                label: ObjectId::Str("StructuredCommentSuffix".to_string()),
+                label: ObjectId::Str("StructuredCommentSuffix".into()),
                 num: None,
                 data: UserData::Str("##Genome-Assembly-Data-END##".to_string()),
             },
         ],
+        decoded: None,
     };
 
     let expected = [expected1, expected2, expected3, expected4, expected5];
@@ -527,7 +537,7 @@ fn parse_bioseq_desc_user() {
         if let SeqDesc::User(object) = entry {
             for exp in expected.iter() {
                 if object.r#type == exp.r#type {
-                    if object.r#type == ObjectId::Str("StructuredComment".to_string())
+                    if object.r#type == ObjectId::Str("StructuredComment".into())
                         && object.data.first().unwrap() != exp.data.first().unwrap()
                     {
                         continue;
@@ -583,6 +593,26 @@ fn parse_bioseq_desc_update_date() {
     assert!(has_update_date);
 }
 
+#[test]
+fn bioseq_descriptor_accessors() {
+    let bioseq = get_bioseq(DATA1);
+
+    assert_eq!(bioseq.organism().unwrap().taxname.as_deref(), Some("Klebsiella pneumoniae"));
+    assert_eq!(bioseq.taxid(), Some(573));
+    assert_eq!(bioseq.molinfo().unwrap().bio_mol, BioMol::Genomic);
+    assert!(matches!(bioseq.create_date(), Some(Date::Date(date) | Date::Std(date)) if date.year == 2023));
+    assert!(bioseq.update_date().is_some());
+    assert_eq!(bioseq.title(), None);
+
+    let xml = "<Seq-descr>\
+        <Seqdesc><Seqdesc_title>Klebsiella pneumoniae NODE_24</Seqdesc_title></Seqdesc>\
+        </Seq-descr>";
+    let mut reader = Reader::from_str(xml);
+    let descr: Vec<SeqDesc> = read_node(&mut reader).unwrap();
+    assert_eq!(descr.title(), Some("Klebsiella pneumoniae NODE_24"));
+    assert!(descr.organism().is_none());
+}
+
 #[test]
 fn parse_bioseq_inst() {
     let bioseq = get_bioseq(DATA1);
@@ -844,3 +874,729 @@ fn parse_bioseq_annot_feat_pseudo() {
         }
     }
 }
+
+#[test]
+fn parse_bioseq_annot_feat_partial() {
+    let bioseq = get_bioseq(DATA1);
+    let annot = &bioseq.annot.unwrap()[0];
+    let feats = annot.features();
+
+    let partial: Vec<&SeqFeat> = feats.iter().filter(|feat| feat.partial == Some(true)).collect();
+    assert_eq!(partial.len(), 2);
+    match &partial[0].data {
+        SeqFeatData::Gene(gene) => assert_eq!(gene.locus.as_deref(), Some("rsxC")),
+        data => panic!("Unexpected feature data {:?}", data),
+    }
+    assert!(matches!(partial[1].data, SeqFeatData::CdRegion(_)));
+
+    // every feature has a location, and every CDS has qualifiers
+    assert!(feats.iter().all(|feat| feat.location != SeqLoc::Null));
+    assert!(feats
+        .iter()
+        .filter(|feat| matches!(feat.data, SeqFeatData::CdRegion(_)))
+        .all(|feat| feat.qual.is_some()));
+}
+
+fn get_extraction_bioseq() -> BioSeq {
+    let xml = "<Seq-inst_seq-data>\
+        <Seq-data>\
+        <Seq-data_iupacna>\
+        <IUPACna>ATGAAACGCATTAGCACCACCATTACCACCACC</IUPACna>\
+        </Seq-data_iupacna>\
+        </Seq-data>\
+        </Seq-inst_seq-data>";
+    let mut reader = Reader::from_str(xml);
+    let seq_data: SeqData = read_node(&mut reader).unwrap();
+
+    let inst = SeqInst {
+        length: Some(33),
+        seq_data: Some(seq_data),
+        ..SeqInst::default()
+    };
+
+    BioSeq {
+        id: vec![SeqId::Gi(1)],
+        inst: Some(inst),
+        ..BioSeq::default()
+    }
+}
+
+fn interval(from: i64, to: i64, strand: Option<NaStrand>) -> SeqInterval {
+    SeqInterval {
+        from,
+        to,
+        strand,
+        id: SeqId::Gi(1),
+        ..SeqInterval::default()
+    }
+}
+
+#[test]
+fn extract_interval() {
+    let bioseq = get_extraction_bioseq();
+
+    let loc = SeqLoc::Int(interval(0, 5, None));
+    assert_eq!(bioseq.extract(&loc).unwrap(), "ATGAAA");
+
+    let loc = SeqLoc::Int(interval(0, 5, Some(NaStrand::Minus)));
+    assert_eq!(bioseq.extract(&loc).unwrap(), "TTTCAT");
+
+    let loc = SeqLoc::Whole(SeqId::Gi(1));
+    assert_eq!(bioseq.extract(&loc).unwrap().len(), 33);
+}
+
+#[test]
+fn extract_mix() {
+    let bioseq = get_extraction_bioseq();
+
+    let loc = SeqLoc::Mix(SeqLocMix(vec![
+        SeqLoc::Int(interval(6, 8, Some(NaStrand::Minus))),
+        SeqLoc::Int(interval(0, 2, Some(NaStrand::Minus))),
+    ]));
+    assert_eq!(bioseq.extract(&loc).unwrap(), "GCGCAT");
+}
+
+#[test]
+fn extract_errors() {
+    let bioseq = get_extraction_bioseq();
+
+    let loc = SeqLoc::Int(interval(30, 40, None));
+    assert!(bioseq.extract(&loc).is_err());
+
+    let loc = SeqLoc::Whole(SeqId::Gi(2));
+    assert!(bioseq.extract(&loc).is_err());
+
+    let bioseq = get_bioseq(DATA1);
+    assert!(bioseq.extract(&SeqLoc::Whole(SeqId::Gi(2519734237))).is_err());
+}
+
+#[test]
+fn refgene_tracking_generated() {
+    let xml = "<User-object>\
+        <User-object_type><Object-id><Object-id_str>RefGeneTracking</Object-id_str></Object-id></User-object_type>\
+        <User-object_data>\
+        <User-field>\
+        <User-field_label><Object-id><Object-id_str>Status</Object-id_str></Object-id></User-field_label>\
+        <User-field_data><User-field_data_str>PROVISIONAL</User-field_data_str></User-field_data>\
+        </User-field>\
+        <User-field>\
+        <User-field_label><Object-id><Object-id_str>Generated</Object-id_str></Object-id></User-field_label>\
+        <User-field_data><User-field_data_bool value=\"true\"/></User-field_data>\
+        </User-field>\
+        </User-object_data>\
+        </User-object>";
+    let object = parse_node::<UserObject>(xml).unwrap();
+    let tracking = RefGeneTracking::try_from(&object).unwrap();
+    assert_eq!(tracking.status.as_deref(), Some("PROVISIONAL"));
+    assert_eq!(tracking.generated, Some(true));
+}
+
+#[test]
+fn bioseq_desc_user_views() {
+    let bioseq = get_bioseq(DATA1);
+
+    let mut comments = Vec::new();
+    let mut has_dblink = false;
+    let mut has_tracking = false;
+    let mut has_policy = false;
+    for entry in bioseq.descr.unwrap().iter() {
+        if let SeqDesc::User(object) = entry {
+            if let Ok(dblink) = DbLink::try_from(object) {
+                assert_eq!(dblink.bio_sample(), Some("SAMN33942939"));
+                assert_eq!(dblink.bio_project(), Some("PRJNA224116"));
+                assert_eq!(dblink.assembly(), Some("GCF_030238925.1"));
+                assert!(dblink.sra().is_none());
+                has_dblink = true;
+            } else if let Ok(comment) = StructuredComment::try_from(object) {
+                comments.push(comment);
+            } else if let Ok(tracking) = RefGeneTracking::try_from(object) {
+                assert_eq!(tracking.status.as_deref(), Some("PIPELINE"));
+                assert_eq!(tracking.identical_to, vec!["JARQWN010000024.1"]);
+                assert_eq!(tracking.generated, None);
+                has_tracking = true;
+            } else if let Ok(policy) = FeatureFetchPolicy::try_from(object) {
+                assert_eq!(policy.policy.as_deref(), Some("OnlyNearFeatures"));
+                has_policy = true;
+            }
+        }
+    }
+    assert!(has_dblink && has_tracking && has_policy);
+
+    assert_eq!(comments.len(), 2);
+    let assembly = comments
+        .iter()
+        .find(|c| c.name() == Some("Genome-Assembly-Data"))
+        .unwrap();
+    assert_eq!(assembly.get("Assembly Method"), Some("SPAdes v. 1"));
+    assert_eq!(assembly.as_map().get("Genome Coverage"), Some(&"100x"));
+    assert_eq!(assembly.suffix.as_deref(), Some("##Genome-Assembly-Data-END##"));
+    assert_eq!(assembly.fields.len(), 5);
+}
+
+#[test]
+fn build_user_objects() {
+    let bioseq = get_bioseq(DATA1);
+    let descr = bioseq.descr.unwrap();
+    let objects: Vec<&UserObject> = descr
+        .iter()
+        .filter_map(|entry| match entry {
+            SeqDesc::User(object) => Some(object),
+            _ => None,
+        })
+        .collect();
+
+    // views convert back into the objects they were read from
+    let original = objects.iter().find(|object| object.is_type("DBLink")).unwrap();
+    let mut dblink = DbLink::try_from(*original).unwrap();
+    assert_eq!(&UserObject::from(&dblink), *original);
+    let original = objects
+        .iter()
+        .find(|object| StructuredComment::try_from(**object).is_ok_and(|c| c.name() == Some("Genome-Assembly-Data")))
+        .unwrap();
+    assert_eq!(&UserObject::from(&StructuredComment::try_from(*original).unwrap()), *original);
+
+    dblink.insert("Sequence Read Archive", "SRR000001");
+    dblink.insert("Sequence Read Archive", "SRR000002");
+    dblink.insert("Sequence Read Archive", "SRR000001");
+    dblink.set("Assembly", vec!["GCF_030238925.2".to_string()]);
+    assert_eq!(dblink.remove("BioProject"), Some(vec!["PRJNA224116".to_string()]));
+    let object = UserObject::from(&dblink);
+    let labels: Vec<&str> = object.data.iter().filter_map(|field| field.label.as_str()).collect();
+    assert_eq!(labels, ["BioSample", "Assembly", "Sequence Read Archive"]);
+    assert_eq!(object.field("Sequence Read Archive").unwrap().num, Some(2));
+    assert_eq!(DbLink::try_from(&object).unwrap().assembly(), Some("GCF_030238925.2"));
+
+    let mut comment = StructuredComment::new("Genome-Assembly-Data");
+    assert_eq!(comment.insert("Assembly Method", "SPAdes v. 1"), None);
+    assert_eq!(comment.insert("Genome Coverage", "100x"), None);
+    assert_eq!(comment.insert("Assembly Method", "SPAdes v. 3"), Some("SPAdes v. 1".to_string()));
+    assert_eq!(comment.remove("Missing"), None);
+    let mut object = UserObject::from(&comment);
+    assert_eq!(object.data.len(), 4);
+    assert_eq!(
+        object.data[0].data,
+        UserData::Str("##Genome-Assembly-Data-START##".to_string())
+    );
+
+    // editing the object directly keeps the suffix last
+    StructuredComment::set_in(&mut object, "Sequencing Technology", "Illumina").unwrap();
+    StructuredComment::set_in(&mut object, "Genome Coverage", "80x").unwrap();
+    let edited = StructuredComment::try_from(&object).unwrap();
+    assert_eq!(edited.get("Genome Coverage"), Some("80x"));
+    assert_eq!(edited.fields.last().unwrap().0, "Sequencing Technology");
+    assert_eq!(object.data.last().unwrap().label.as_str(), Some("StructuredCommentSuffix"));
+    assert!(StructuredComment::set_in(&mut UserObject::new("DBLink"), "key", "value").is_err());
+
+    assert!(object.remove_field("Genome Coverage").is_some());
+    assert!(object.field("Genome Coverage").is_none());
+}
+
+#[test]
+fn resolve_cdregion_parent_gene() {
+    let bioseq = get_bioseq(DATA1);
+    let annot = bioseq.annot.unwrap();
+    let annot = annot.get(0).unwrap();
+
+    let mut resolved = 0;
+    for feat in annot.features().iter() {
+        if let SeqFeatData::CdRegion(_) = &feat.data {
+            let gene = annot.parent_gene(feat).expect("CDS should have a parent gene");
+            match &gene.data {
+                SeqFeatData::Gene(gene_ref) => assert!(gene_ref.locus_tag.is_some()),
+                _ => panic!("parent is not a gene feature"),
+            }
+            let (from, to) = feat.location.total_range().unwrap();
+            let (gene_from, gene_to) = gene.location.total_range().unwrap();
+            assert!(gene_from <= from && to <= gene_to);
+            resolved += 1;
+        }
+    }
+    assert_eq!(resolved, 88);
+
+    // a gene xref which matches no gene falls back to overlap
+    let cds = annot
+        .features()
+        .iter()
+        .find(|feat| matches!(feat.data, SeqFeatData::CdRegion(_)))
+        .unwrap();
+    let mut unmatched = cds.clone();
+    unmatched.xref = Some(vec![SeqFeatXref {
+        id: None,
+        data: Some(SeqFeatData::Gene(GeneRef {
+            locus_tag: Some("missing".to_string()),
+            ..GeneRef::default()
+        })),
+    }]);
+    let gene = annot.parent_gene(&unmatched).expect("CDS should fall back to overlapping gene");
+    assert!(std::ptr::eq(gene, annot.parent_gene(cds).unwrap()));
+}
+
+#[test]
+fn seq_feat_dbxref() {
+    let gene = GeneRef {
+        locus_tag: Some("b0001".to_string()),
+        db: Some(vec![DbTag {
+            db: "GeneID".into(),
+            tag: ObjectId::Id(944742),
+        }]),
+        ..GeneRef::default()
+    };
+    let mut feat = SeqFeat::new(SeqFeatData::Gene(gene.clone()));
+    feat.dbxref = Some(vec![DbTag {
+        db: "HGNC".into(),
+        tag: ObjectId::Str("HGNC:5".into()),
+    }]);
+
+    assert_eq!(feat.gene_id(), Some(944742));
+    assert_eq!(feat.dbxref("HGNC").unwrap().typed(), DbXref::Hgnc(5));
+    assert!(feat.dbxref("MIM").is_none());
+
+    let mut cds = SeqFeat::new(SeqFeatData::Gene(GeneRef::default()));
+    cds.xref = Some(vec![SeqFeatXref {
+        id: None,
+        data: Some(SeqFeatData::Gene(gene)),
+    }]);
+    assert_eq!(cds.gene_xref().unwrap().locus_tag.as_deref(), Some("b0001"));
+}
+
+#[test]
+fn delta_gaps() {
+    let component = |from: u64, to: u64| format!("<Delta-seq><Delta-seq_loc><Seq-loc><Seq-loc_int><Seq-interval>\
+        <Seq-interval_from>{}</Seq-interval_from>\
+        <Seq-interval_to>{}</Seq-interval_to>\
+        <Seq-interval_id><Seq-id><Seq-id_gi>1</Seq-id_gi></Seq-id></Seq-interval_id>\
+        </Seq-interval></Seq-loc_int></Seq-loc></Delta-seq_loc></Delta-seq>", from, to);
+    let xml = format!("<Seq-inst>\
+        <Seq-inst_repr value=\"delta\"/>\
+        <Seq-inst_mol value=\"dna\"/>\
+        <Seq-inst_length>274</Seq-inst_length>\
+        <Seq-inst_ext><Seq-ext><Seq-ext_delta><Delta-ext>\
+        {}\
+        <Delta-seq><Delta-seq_literal><Seq-literal>\
+        <Seq-literal_length>100</Seq-literal_length>\
+        <Seq-literal_seq-data><Seq-data><Seq-data_gap><Seq-gap>\
+        <Seq-gap_type value=\"scaffold\">9</Seq-gap_type>\
+        <Seq-gap_linkage value=\"linked\">1</Seq-gap_linkage>\
+        <Seq-gap_linkage-evidence><Linkage-evidence>\
+        <Linkage-evidence_type value=\"paired-ends\">0</Linkage-evidence_type>\
+        </Linkage-evidence></Seq-gap_linkage-evidence>\
+        </Seq-gap></Seq-data_gap></Seq-data></Seq-literal_seq-data>\
+        </Seq-literal></Delta-seq_literal></Delta-seq>\
+        {}\
+        <Delta-seq><Delta-seq_literal><Seq-literal>\
+        <Seq-literal_length>20</Seq-literal_length>\
+        </Seq-literal></Delta-seq_literal></Delta-seq>\
+        <Delta-seq><Delta-seq_literal><Seq-literal>\
+        <Seq-literal_length>4</Seq-literal_length>\
+        <Seq-literal_seq-data><Seq-data><Seq-data_iupacna><IUPACna>ACGT</IUPACna></Seq-data_iupacna></Seq-data></Seq-literal_seq-data>\
+        </Seq-literal></Delta-seq_literal></Delta-seq>\
+        </Delta-ext></Seq-ext_delta></Seq-ext></Seq-inst_ext>\
+        </Seq-inst>", component(0, 99), component(200, 249));
+    let inst: SeqInst = ncbi::parsing::parse_node(&xml).unwrap();
+    match &inst.ext {
+        Some(SeqExt::Delta(parts)) => match &parts[1] {
+            DeltaSeq::Literal(literal) => match &literal.seq_data {
+                Some(SeqData::Gap(gap)) => {
+                    assert_eq!(gap.linkage, Some(SeqGapLinkage::Linked));
+                    let evidence = gap.linkage_evidence.as_ref().unwrap();
+                    assert_eq!(evidence[0].r#type, LinkageEvidenceType::PairedEnds);
+                }
+                data => panic!("unexpected data: {:?}", data),
+            },
+            part => panic!("unexpected part: {:?}", part),
+        },
+        ext => panic!("unexpected ext: {:?}", ext),
+    }
+
+    let bioseq = BioSeq {
+        inst: Some(inst),
+        ..BioSeq::default()
+    };
+    assert_eq!(bioseq.gap_count(), 2);
+    assert_eq!(bioseq.ungapped_length(), Some(154));
+    assert_eq!(
+        bioseq.gaps(),
+        vec![(100, 100, SeqGapType::Scaffold), (250, 20, SeqGapType::Unknown)]
+    );
+
+    // the WGS contig is a single component without gaps
+    let bioseq = get_bioseq(DATA1);
+    assert_eq!(bioseq.gap_count(), 0);
+    assert!(bioseq.gaps().is_empty());
+    assert_eq!(bioseq.ungapped_length(), Some(86489));
+}
+
+#[test]
+fn seq_inst_hist() {
+    let xml = "<Seq-inst>\
+        <Seq-inst_repr value=\"raw\"/>\
+        <Seq-inst_mol value=\"rna\"/>\
+        <Seq-inst_length>2512</Seq-inst_length>\
+        <Seq-inst_hist>\
+        <Seq-hist>\
+        <Seq-hist_replaced-by>\
+        <Seq-hist-rec>\
+        <Seq-hist-rec_date><Date><Date_std><Date-std>\
+        <Date-std_year>2019</Date-std_year>\
+        <Date-std_month>4</Date-std_month>\
+        <Date-std_day>2</Date-std_day>\
+        </Date-std></Date_std></Date></Seq-hist-rec_date>\
+        <Seq-hist-rec_ids>\
+        <Seq-id><Seq-id_other><Textseq-id>\
+        <Textseq-id_accession>NM_000546</Textseq-id_accession>\
+        <Textseq-id_version>6</Textseq-id_version>\
+        </Textseq-id></Seq-id_other></Seq-id>\
+        <Seq-id><Seq-id_gi>1519245447</Seq-id_gi></Seq-id>\
+        </Seq-hist-rec_ids>\
+        </Seq-hist-rec>\
+        </Seq-hist_replaced-by>\
+        <Seq-hist_deleted><Seq-hist_deleted_bool value=\"true\"/></Seq-hist_deleted>\
+        </Seq-hist>\
+        </Seq-inst_hist>\
+        </Seq-inst>";
+    let inst: SeqInst = ncbi::parsing::parse_node(xml).unwrap();
+    assert_eq!(inst.length, Some(2512));
+    let hist = inst.hist.as_ref().unwrap();
+    assert!(hist.replaces.is_none());
+    let date = hist.replaced_by.as_ref().unwrap().date.as_ref().unwrap();
+    assert!(matches!(date, Date::Date(std) if std.year == 2019));
+
+    let mut bioseq = BioSeq::default();
+    assert!(!bioseq.is_suppressed());
+    assert!(bioseq.replaced_by_ids().is_empty());
+
+    bioseq.inst = Some(inst);
+    assert!(bioseq.is_suppressed());
+    assert!(bioseq.replaces_ids().is_empty());
+    match bioseq.replaced_by_ids() {
+        [SeqId::Other(id), SeqId::Gi(gi)] => {
+            assert_eq!(id.accession.as_deref(), Some("NM_000546"));
+            assert_eq!(id.version, Some(6));
+            assert_eq!(*gi, 1519245447);
+        }
+        ids => panic!("unexpected ids: {:?}", ids),
+    }
+}
+
+#[test]
+fn pub_citation_strings() {
+    let bioseq = get_bioseq(DATA1);
+    let pubs: Vec<&PubDesc> = bioseq
+        .descriptors()
+        .iter()
+        .filter_map(|desc| match desc {
+            SeqDesc::Pub(desc) => Some(desc),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(
+        pubs[0].to_citation_string(CitationStyle::Full),
+        "Blaikie JM, Sapula SA, Amsalu A, Siderius NL, Hart BJ, Venter H. Direct Submission. Submitted (28-MAR-2023)."
+    );
+    assert_eq!(pubs[0].to_citation_string(CitationStyle::Short), "Blaikie JM et al. (2023)");
+    assert!(pubs[1]
+        .to_citation_string(CitationStyle::Full)
+        .ends_with("Venter H. The resistome of Klebsiella pneumoniae complex isolates recovered from Residential Aged Care Facilities. Unpublished."));
+}
+
+#[test]
+fn molinfo_completeness() {
+    let xml = "<MolInfo>\
+        <MolInfo_biomol value=\"mRNA\">3</MolInfo_biomol>\
+        <MolInfo_tech value=\"other\">255</MolInfo_tech>\
+        <MolInfo_techexp>nanopore</MolInfo_techexp>\
+        <MolInfo_completeness value=\"no-right\">4</MolInfo_completeness>\
+        <MolInfo_gbmoltype>mRNA</MolInfo_gbmoltype>\
+        </MolInfo>";
+    let molinfo: MolInfo = ncbi::parsing::parse_node(xml).unwrap();
+    assert_eq!(molinfo.bio_mol, BioMol::mRNA);
+    assert_eq!(molinfo.tech, MolTech::Other);
+    assert_eq!(molinfo.tech_exp.as_deref(), Some("nanopore"));
+    assert_eq!(molinfo.completeness, MolCompleteness::NoRight);
+    assert_eq!(molinfo.gb_mol_type.as_deref(), Some("mRNA"));
+    assert!(molinfo.is_partial() && !molinfo.is_complete());
+
+    // completeness defaults to unknown, which is neither complete nor partial
+    let bioseq = get_bioseq(DATA1);
+    let molinfo = bioseq.molinfo().unwrap();
+    assert_eq!(molinfo.completeness, MolCompleteness::Unknown);
+    assert!(!molinfo.is_partial() && !molinfo.is_complete());
+}
+
+#[test]
+fn cdregion_genetic_code() {
+    let set = get_seq_set(DATA1);
+    let codes: Vec<u64> = set
+        .bioseqs()
+        .iter()
+        .flat_map(|bioseq| bioseq.annot.iter().flatten())
+        .chain(set.annot.iter().flatten())
+        .flat_map(|annot| annot.features())
+        .filter_map(|feat| match &feat.data {
+            SeqFeatData::CdRegion(cdregion) => Some(cdregion.genetic_code().unwrap().id),
+            _ => None,
+        })
+        .collect();
+    assert!(!codes.is_empty());
+    assert!(codes.iter().all(|id| *id == 11));
+}
+
+#[test]
+fn pdb_feature_data() {
+    let bioseq = get_bioseq(PDB_PROTEIN);
+    assert_eq!(
+        bioseq.id.first(),
+        Some(&SeqId::Pdb(PDBSeqId {
+            mol: "1AAY".to_string(),
+            rel: Some(Date::Date(DateStd {
+                year: 1997,
+                month: Some(8),
+                day: Some(20),
+                ..DateStd::default()
+            })),
+            chain_id: Some("A".to_string()),
+        }))
+    );
+    assert_eq!(bioseq.id.get(1), Some(&SeqId::Gi(3891847)));
+
+    let data: Vec<&SeqFeatData> = bioseq
+        .annot
+        .iter()
+        .flatten()
+        .flat_map(|annot| annot.features())
+        .map(|feat| &feat.data)
+        .collect();
+    assert_eq!(
+        data,
+        [
+            &SeqFeatData::Region("zf-C2H2".to_string()),
+            &SeqFeatData::Het("ZN".to_string()),
+            &SeqFeatData::NonStdResidue("MSE".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn partial_locations() {
+    let bioseq = get_bioseq(DATA1);
+    let feats: Vec<&SeqFeat> = bioseq
+        .annot
+        .iter()
+        .flatten()
+        .flat_map(|annot| annot.features())
+        .collect();
+
+    // rsxC runs off the end of the contig
+    let gene = feats
+        .iter()
+        .find(|feat| matches!(&feat.data, SeqFeatData::Gene(gene) if gene.locus.as_deref() == Some("rsxC")))
+        .unwrap();
+    assert_eq!(gene.location.strand(), Some(NaStrand::Plus));
+    assert!(!gene.location.is_partial_5prime());
+    assert!(gene.location.is_partial_3prime());
+
+    // every location with a partial end belongs to a feature marked as partial
+    assert!(feats.iter().any(|feat| feat.partial == Some(true)));
+    for feat in feats {
+        if feat.location.is_partial_5prime() || feat.location.is_partial_3prime() {
+            assert_eq!(feat.partial, Some(true));
+        }
+    }
+}
+
+#[test]
+fn asn_enum_names() {
+    assert_eq!("wgs".parse::<MolTech>(), Ok(MolTech::WGS));
+    assert_eq!("fli-cDNA".parse::<MolTech>(), Ok(MolTech::FLI_cDNA));
+    assert_eq!(MolTech::HTGS3.to_string(), "htgs-3");
+    assert!("WGS".parse::<MolTech>().is_err());
+
+    assert_eq!("plasmid-in-plastid".parse::<BioSourceGenome>(), Ok(BioSourceGenome::PlasmidInPlastid));
+    assert_eq!(BioSourceGenome::Mitochondrion.to_string(), "mitochondrion");
+
+    assert_eq!("lat-lon".parse::<SubSourceSubType>(), Ok(SubSourceSubType::LatLon));
+    assert_eq!(SubSourceSubType::CollectionDate.to_string(), "collection-date");
+    assert_eq!(OrgModSubType::NatHost.to_string(), "nat-host");
+    assert_eq!("genomic-mRNA".parse::<BioMol>(), Ok(BioMol::Genomic_mRNA));
+
+    for completeness in [MolCompleteness::Complete, MolCompleteness::NoLeft, MolCompleteness::Other] {
+        assert_eq!(completeness.to_string().parse::<MolCompleteness>(), Ok(completeness));
+    }
+    for evidence in [LinkageEvidenceType::PairedEnds, LinkageEvidenceType::ProximityLigation] {
+        assert_eq!(evidence.asn_name().parse::<LinkageEvidenceType>(), Ok(evidence));
+    }
+    assert_eq!(SeqGapLinkage::Linked.to_string(), "linked");
+
+    // names match the `value` attributes written by NCBI
+    use ncbi::seq::Topology;
+    assert_eq!("dna".parse::<Mol>(), Ok(Mol::DNA));
+    assert_eq!(Repr::Delta.to_string(), "delta");
+    assert_eq!("circular".parse::<Topology>(), Ok(Topology::Circular));
+    assert_eq!(Strand::DS.to_string(), "ds");
+    assert_eq!("nuc-prot".parse::<ncbi::seqset::BioSeqSetClass>(), Ok(ncbi::seqset::BioSeqSetClass::NucProt));
+    assert_eq!(ncbi::seqfeat::CdRegionFrame::NotSet.to_string(), "not-set");
+    assert_eq!("miscRNA".parse::<ncbi::seqfeat::RnaRefType>(), Ok(ncbi::seqfeat::RnaRefType::MiscRNA));
+    assert_eq!(ncbi::entrezgene::EntrezgeneType::ProteinCoding.to_string(), "protein-coding");
+    assert_eq!("mRNA".parse::<ncbi::entrezgene::GeneCommentaryType>(), Ok(ncbi::entrezgene::GeneCommentaryType::MRna));
+    assert_eq!(ncbi::biblio::PubStatus::AheadOfPrint.to_string(), "aheadofprint");
+    assert_eq!("diags".parse::<ncbi::seqalign::SeqAlignType>(), Ok(ncbi::seqalign::SeqAlignType::Diags));
+}
+
+#[test]
+fn decode_registered_user_objects() {
+    use ncbi::parsing::{next_event, read_string, XmlNode};
+    use ncbi::user::{register_user_object, unregister_user_object};
+    use quick_xml::events::{BytesStart, Event};
+
+    /// `User-object` written by an annotation pipeline
+    #[derive(Debug, Default)]
+    struct PipelineInfo {
+        name: Option<String>,
+        version: Option<String>,
+    }
+
+    impl XmlNode for PipelineInfo {
+        fn start_bytes() -> BytesStart<'static> {
+            BytesStart::new("User-object")
+        }
+
+        fn from_reader(reader: &mut Reader<&[u8]>) -> Option<Self> {
+            let mut info = Self::default();
+            let mut label = None;
+            loop {
+                match next_event(reader)? {
+                    Event::Start(e) => match e.name().as_ref() {
+                        b"Object-id_str" => label = read_string(reader),
+                        b"User-field_data_str" => match label.as_deref() {
+                            Some("Name") => info.name = read_string(reader),
+                            Some("Version") => info.version = read_string(reader),
+                            _ => (),
+                        },
+                        _ => (),
+                    },
+                    Event::End(e) if Self::is_end(&e) => return Some(info),
+                    _ => (),
+                }
+            }
+        }
+    }
+
+    let xml = "<Seq-descr><Seqdesc><Seqdesc_user><User-object>\
+        <User-object_type><Object-id><Object-id_str>PipelineInfo</Object-id_str></Object-id></User-object_type>\
+        <User-object_data>\
+        <User-field><User-field_label><Object-id><Object-id_str>Name</Object-id_str></Object-id></User-field_label>\
+        <User-field_data><User-field_data_str>PGAP</User-field_data_str></User-field_data></User-field>\
+        <User-field><User-field_label><Object-id><Object-id_str>Version</Object-id_str></Object-id></User-field_label>\
+        <User-field_data><User-field_data_str>6.5</User-field_data_str></User-field_data></User-field>\
+        </User-object_data>\
+        </User-object></Seqdesc_user></Seqdesc>\
+        <Seqdesc><Seqdesc_title>contig 1</Seqdesc_title></Seqdesc></Seq-descr>";
+    let parse = || -> UserObject {
+        let descr: Vec<SeqDesc> = read_node(&mut Reader::from_str(xml)).unwrap();
+        assert_eq!(descr.title(), Some("contig 1"));
+        match &descr[0] {
+            SeqDesc::User(object) => object.clone(),
+            desc => panic!("unexpected descriptor {:?}", desc),
+        }
+    };
+
+    assert!(parse().decoded::<PipelineInfo>().is_none());
+
+    assert!(register_user_object::<PipelineInfo>("PipelineInfo").is_none());
+    let object = parse();
+    let info: &PipelineInfo = object.decoded().unwrap();
+    assert_eq!(info.name.as_deref(), Some("PGAP"));
+    assert_eq!(info.version.as_deref(), Some("6.5"));
+    // generic fields are still given
+    assert_eq!(object.data.len(), 2);
+    assert!(object.decoded::<String>().is_none());
+
+    assert!(unregister_user_object("PipelineInfo").is_some());
+    assert!(parse().decoded.is_none());
+}
+
+#[test]
+/// Sequence from a patent, identified by the patent and citing it
+fn parse_patent_record() {
+    let data = std::fs::read_to_string("tests/data/patent.xml").unwrap();
+    let bioseq = match parse_xml(&data).unwrap() {
+        DataType::BioSeqSet(set) => set.bioseqs().into_iter().next().unwrap().clone(),
+        _ => panic!("No Bioseq set found"),
+    };
+
+    match &bioseq.id[0] {
+        SeqId::Patent(id) => {
+            assert_eq!(id.seqid, 12);
+            assert_eq!(id.cit.country, "US");
+            assert_eq!(id.cit.id, IdPatChoice::Number("7654321".to_string()));
+            assert_eq!(id.cit.doc_type.as_deref(), Some("B2"));
+        }
+        _ => panic!("Expected a patent Seq-id"),
+    }
+    assert_eq!(bioseq.id[1], SeqId::Gi(3000012));
+
+    let pubs: Vec<&Pub> = bioseq
+        .descriptors()
+        .iter()
+        .filter_map(|desc| match desc {
+            SeqDesc::Pub(desc) => desc.primary_citation(),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(pubs.len(), 2);
+
+    let patent = match pubs[0] {
+        Pub::Patent(patent) => patent,
+        _ => panic!("Expected a patent citation"),
+    };
+    assert_eq!(patent.number.as_deref(), Some("7654321"));
+    assert_eq!(patent.class.as_ref().unwrap(), &["C12N 9/20", "C12P 7/64"]);
+    assert_eq!(patent.app_number.as_deref(), Some("11/234567"));
+    assert_eq!(
+        patent.assignees.as_ref().unwrap().names,
+        AuthListNames::Std(vec![Author::new(PersonId::Consortium("Example Enzymes Inc.".to_string()))])
+    );
+    let priority = &patent.priority.as_ref().unwrap()[0];
+    assert_eq!((priority.country.as_str(), priority.number.as_str()), ("US", "60/612345"));
+    assert_eq!(
+        pubs[0].to_citation_string(CitationStyle::Full),
+        "Okafor CN, Lindqvist A. Thermostable lipase variants and methods of use. Patent US 7654321."
+    );
+    assert_eq!(pubs[0].to_citation_string(CitationStyle::Short), "Okafor CN and Lindqvist A (2010)");
+
+    let thesis = match pubs[1] {
+        Pub::Man(letter) => letter,
+        _ => panic!("Expected a thesis"),
+    };
+    assert_eq!(thesis.r#type, CitLetType::Thesis);
+    assert_eq!(thesis.cit.title, vec![TitleItem::Name("Engineering lipase thermostability".to_string())]);
+    assert_eq!(thesis.cit.imp.language.as_deref(), Some("ENG"));
+}
+
+#[test]
+/// Features and descriptors may be re-parsed from their recorded spans
+fn record_object_spans() {
+    let xml = std::fs::read_to_string(DATA1).unwrap();
+    let (parsed, spans) = record_spans(&xml, || parse_xml(&xml));
+    let bioseq = match parsed.unwrap() {
+        DataType::BioSeqSet(set) => set.bioseqs()[0].clone(),
+        _ => panic!("No Bioseq set found"),
+    };
+
+    let features: Vec<&SeqFeat> = bioseq.annot.iter().flatten().flat_map(|annot| annot.features()).collect();
+    assert_eq!(spans.spans::<SeqFeat>().len(), features.len());
+    for (span, feature) in spans.spans::<SeqFeat>().iter().zip(features) {
+        assert!(span.text(&xml).starts_with("<Seq-feat>"));
+        assert_eq!(parse_node::<SeqFeat>(span.text(&xml)).as_ref(), Some(feature));
+    }
+
+    let descriptors = bioseq.descriptors();
+    assert_eq!(spans.spans::<SeqDesc>().len(), descriptors.len());
+    for (i, descriptor) in descriptors.iter().enumerate() {
+        let span = spans.get(ObjectHandle::of::<SeqDesc>(i)).unwrap();
+        assert_eq!(parse_node::<SeqDesc>(span.text(&xml)).as_ref(), Some(descriptor));
+    }
+    let location = spans.spans::<SeqDesc>()[0].location(&xml);
+    assert!(location.path.ends_with("Seq-descr"), "{}", location.path);
+}
@@ -0,0 +1,77 @@
+use ncbi::blast::{parse_blast_xml2, BlastReport};
+use ncbi::seqalign::SeqAlignSegs;
+use ncbi::seqloc::{NaStrand, SeqId};
+use std::fs;
+
+fn check_report(report: &BlastReport) {
+    assert_eq!(report.program, "blastn");
+    assert_eq!(report.db.as_deref(), Some("core_nt"));
+    assert_eq!(report.searches.len(), 1);
+
+    let search = &report.searches[0];
+    assert_eq!(search.query_id, "Query_1");
+    assert_eq!(search.query_len, 24);
+    assert_eq!(search.hits.len(), 2);
+
+    let hit = &search.hits[0];
+    assert_eq!(hit.accession(), Some("NM_000546"));
+    assert_eq!(hit.description[0].taxid, Some(9606));
+    assert_eq!(hit.len, 2512);
+
+    let hsp = hit.best_hsp().unwrap();
+    assert_eq!(hsp.evalue, 2.3e-5);
+    assert_eq!(hsp.bit_score, 40.1);
+    assert_eq!(hsp.identity, 22);
+    assert_eq!(hsp.gaps, 2);
+    assert!((hsp.percent_identity() - 91.666).abs() < 0.01);
+    assert_eq!(search.hits[1].hsps[0].hit_strand.as_deref(), Some("Minus"));
+}
+
+#[test]
+fn parse_xml2() {
+    let xml = fs::read_to_string("tests/data/blast.xml").unwrap();
+    let reports = parse_blast_xml2(&xml).unwrap();
+    assert_eq!(reports.len(), 1);
+    check_report(&reports[0]);
+
+    assert!(parse_blast_xml2("<eSearchResult/>").is_err());
+    assert!(parse_blast_xml2(&xml.replace("<score>44</score>", "<score>x</score>")).is_err());
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn parse_json() {
+    let json = fs::read_to_string("tests/data/blast.json").unwrap();
+    let reports = ncbi::blast::parse_blast_json(&json).unwrap();
+    check_report(&reports[0]);
+
+    let xml = fs::read_to_string("tests/data/blast.xml").unwrap();
+    assert_eq!(reports, parse_blast_xml2(&xml).unwrap());
+}
+
+#[test]
+fn hsps_to_seq_aligns() {
+    let xml = fs::read_to_string("tests/data/blast.xml").unwrap();
+    let search = &parse_blast_xml2(&xml).unwrap()[0].searches[0];
+    let aligns = search.seq_aligns();
+    assert_eq!(aligns.len(), 2);
+
+    let gapped = match &aligns[0].segs {
+        SeqAlignSegs::DenSeg(seg) => seg,
+        _ => panic!("expected Dense-seg"),
+    };
+    assert!(matches!(&gapped.ids[1], SeqId::Other(id) if id.accession.as_deref() == Some("NM_000546") && id.version == Some(6)));
+    assert_eq!(gapped.numseg, 3);
+    assert_eq!(gapped.lens, vec![9, 2, 13]);
+    assert_eq!(gapped.starts, vec![0, 200, 9, -1, 11, 209]);
+    assert_eq!(gapped.aligned_length(), 24);
+
+    let minus = match &aligns[1].segs {
+        SeqAlignSegs::DenSeg(seg) => seg,
+        _ => panic!("expected Dense-seg"),
+    };
+    assert!(matches!(&minus.ids[1], SeqId::Genbank(_)));
+    assert_eq!(minus.starts, vec![2, 500]);
+    assert_eq!(minus.strand(1), Some(&NaStrand::Minus));
+    assert_eq!(aligns[1].score.as_ref().unwrap().len(), 4);
+}
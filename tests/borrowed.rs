@@ -0,0 +1,64 @@
+use ncbi::general::{DbTagRef, ObjectIdRef};
+use ncbi::parsing::{parse_node_borrowed, scan_nodes_borrowed, XmlNodeRef};
+use ncbi::seqfeat::{GbQual, GbQualRef};
+use ncbi::seqloc::{SeqId, SeqIdRef, TextseqIdRef};
+use ncbi::{parse_xml, DataType};
+use std::fs;
+
+#[test]
+fn borrowed_text_points_into_input() {
+    let xml = "<Textseq-id>\
+        <Textseq-id_accession>NZ_JARQWN010000024</Textseq-id_accession>\
+        <Textseq-id_version>1</Textseq-id_version>\
+        </Textseq-id>";
+    let id = parse_node_borrowed::<TextseqIdRef>(xml).unwrap();
+    let accession = id.accession.unwrap();
+    assert_eq!(accession, "NZ_JARQWN010000024");
+    assert_eq!(id.version, Some(1));
+
+    let range = xml.as_bytes().as_ptr_range();
+    assert!(range.contains(&accession.as_ptr()));
+}
+
+#[test]
+fn scan_matches_owned_parse() {
+    let xml = fs::read_to_string("tests/data/2519734237.xml").unwrap();
+
+    let quals: Vec<GbQualRef> = scan_nodes_borrowed(&xml);
+    assert_eq!(quals.len(), 88);
+
+    let set = match parse_xml(&xml).unwrap() {
+        DataType::BioSeqSet(set) => set,
+        _ => panic!("No Bioseq set found"),
+    };
+    let owned: Vec<&GbQual> = set
+        .bioseqs()
+        .into_iter()
+        .flat_map(|bioseq| bioseq.annot.iter().flatten())
+        .flat_map(|annot| annot.features())
+        .flat_map(|feat| feat.qual.iter().flatten())
+        .collect();
+    let converted: Vec<GbQual> = quals.iter().map(|qual| qual.to_owned_node()).collect();
+    assert_eq!(converted.iter().collect::<Vec<_>>(), owned);
+
+    let ids: Vec<SeqIdRef> = scan_nodes_borrowed(&xml);
+    let id = ids
+        .iter()
+        .find(|id| matches!(id, SeqIdRef::Other(_)))
+        .unwrap()
+        .to_owned_node();
+    assert!(matches!(&id, SeqId::Other(_)));
+    let id = id.textseq_id().unwrap();
+    assert_eq!(id.accession.as_deref(), Some("NZ_JARQWN010000024"));
+    assert_eq!(id.version, Some(1));
+}
+
+#[test]
+fn borrowed_db_tag() {
+    let xml = "<Dbtag><Dbtag_db>taxon</Dbtag_db>\
+        <Dbtag_tag><Object-id><Object-id_id>562</Object-id_id></Object-id></Dbtag_tag></Dbtag>";
+    let tag = parse_node_borrowed::<DbTagRef>(xml).unwrap();
+    assert_eq!(tag.db, "taxon");
+    assert_eq!(tag.tag, ObjectIdRef::Id(562));
+    assert_eq!(tag.to_owned_node().db, "taxon");
+}
@@ -0,0 +1,83 @@
+//! Requests abandoned after a timeout or cancellation, without network access
+
+use ncbi::transport::{reset_transport, set_transport, CancelToken, RequestError};
+use ncbi::{configure, http_get_with, reset_config, ClientConfig, EntrezDb, FetchRequest};
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "reqwest")]
+use ncbi::transport::{HttpTransport, ReqwestTransport};
+#[cfg(feature = "reqwest")]
+use std::{io::{Read, Write}, net::TcpListener, sync::Arc};
+
+#[test]
+fn timeout_and_cancel() {
+    set_transport(|url: &str| {
+        if url.contains("id=slow") {
+            thread::sleep(Duration::from_secs(1));
+        }
+        Ok(">1\nACGT\n".to_string())
+    });
+    let request = FetchRequest::new(EntrezDb::Nucleotide, "slow").rettype("fasta").retmode("text");
+
+    let start = Instant::now();
+    let timeout = Duration::from_millis(100);
+    assert_eq!(request.clone().timeout(timeout).send(), Err(RequestError::TimedOut(timeout)));
+    assert!(start.elapsed() < Duration::from_millis(800));
+
+    // fast responses are returned within the timeout
+    let fast = FetchRequest::new(EntrezDb::Nucleotide, "fast").rettype("fasta").retmode("text");
+    assert_eq!(fast.timeout(timeout).fetch_text().unwrap(), ">1\nACGT\n");
+
+    // cancelled from another thread while in flight
+    let token = CancelToken::new();
+    let canceller = token.clone();
+    let start = Instant::now();
+    thread::spawn(move || {
+        thread::sleep(Duration::from_millis(100));
+        canceller.cancel();
+    });
+    assert_eq!(request.clone().cancel(&token).send(), Err(RequestError::Cancelled));
+    assert!(start.elapsed() < Duration::from_millis(800));
+    assert_eq!(request.clone().cancel(&token).fetch_text().unwrap_err(), "Request cancelled");
+
+    // a request made with a cancelled token is not sent
+    assert_eq!(http_get_with("https://example.com/?id=fast", None, Some(&token)), Err(RequestError::Cancelled));
+
+    // the timeout of the installed configuration applies to all requests
+    assert!(configure(ClientConfig::new().timeout(Duration::ZERO)).is_err());
+    configure(ClientConfig::new().timeout(timeout)).unwrap();
+    assert_eq!(request.send(), Err(RequestError::TimedOut(timeout)));
+    assert_eq!(request.timeout(Duration::from_secs(5)).send().unwrap(), ">1\nACGT\n");
+
+    reset_config();
+    reset_transport();
+}
+
+/// The connection of a timed out request is closed, rather than left to finish
+#[cfg(feature = "reqwest")]
+#[test]
+fn reqwest_aborts_slow_body() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/efetch.fcgi?id=slow", listener.local_addr().unwrap());
+    let server = thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut request = [0; 1024];
+        let _ = stream.read(&mut request).unwrap();
+        stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 1000\r\n\r\n").unwrap();
+        // trickle the body until the client hangs up
+        let start = Instant::now();
+        while stream.write_all(b"A").and_then(|_| stream.flush()).is_ok() {
+            if start.elapsed() > Duration::from_secs(5) {
+                return None;
+            }
+            thread::sleep(Duration::from_millis(50));
+        }
+        Some(start.elapsed())
+    });
+
+    let timeout = Duration::from_millis(200);
+    assert_eq!(Arc::new(ReqwestTransport).get_within(&url, Some(timeout), None), Err(RequestError::TimedOut(timeout)));
+    let closed_after = server.join().unwrap().expect("connection left open");
+    assert!(closed_after < Duration::from_secs(2));
+}
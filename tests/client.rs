@@ -0,0 +1,18 @@
+use ncbi::{build_fetch_url, build_search_url, client_config, configure, reset_config, ClientConfig, EntrezDb};
+
+#[test]
+fn identify_requests() {
+    let bare = build_fetch_url(EntrezDb::Protein, "1", "fasta", "text");
+    assert!(!bare.contains("tool="));
+
+    assert!(configure(ClientConfig::new().batch(true)).is_err());
+    assert_eq!(client_config(), ClientConfig::default());
+
+    configure(ClientConfig::new().tool("ncbi-rs").email("me@example.org").api_key("abc123").batch(true)).unwrap();
+    let url = build_fetch_url(EntrezDb::Protein, "1", "fasta", "text");
+    assert_eq!(url, format!("{}&tool=ncbi-rs&email=me@example.org&api_key=abc123", bare));
+    assert!(build_search_url(EntrezDb::Gene, "TP53").ends_with("&api_key=abc123"));
+
+    reset_config();
+    assert_eq!(build_fetch_url(EntrezDb::Protein, "1", "fasta", "text"), bare);
+}
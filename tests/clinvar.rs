@@ -0,0 +1,118 @@
+use ncbi::clinvar::parse_variation_archives;
+use ncbi::general::{DbTag, ObjectId};
+use ncbi::seqfeat::{VariationRef, VariationRefData};
+use std::fs;
+
+#[test]
+fn parse_variation_archive() {
+    let xml = fs::read_to_string("tests/data/clinvar.xml").unwrap();
+    let archives = parse_variation_archives(&xml).unwrap();
+    assert_eq!(archives.len(), 1);
+
+    let archive = &archives[0];
+    assert_eq!(archive.variation_id, 12356);
+    assert_eq!(archive.accession, "VCV000012356");
+    assert_eq!(archive.version, Some(98));
+    assert_eq!(
+        archive.name.as_deref(),
+        Some("NM_000546.6(TP53):c.743G>A (p.Arg248Gln)")
+    );
+    assert_eq!(archive.record_status.as_deref(), Some("current"));
+    assert_eq!(archive.species.as_deref(), Some("Homo sapiens"));
+
+    assert_eq!(archive.genes.len(), 1);
+    assert_eq!(archive.genes[0].symbol, "TP53");
+    assert_eq!(archive.genes[0].gene_id, Some(7157));
+
+    // the location of the gene is not a location of the variant
+    assert_eq!(archive.locations.len(), 2);
+    let location = &archive.locations[0];
+    assert_eq!(location.assembly, "GRCh38");
+    assert_eq!(location.accession.as_deref(), Some("NC_000017.11"));
+    assert_eq!((location.start, location.stop), (Some(7674220), Some(7674220)));
+    assert_eq!(location.reference_allele.as_deref(), Some("C"));
+    assert_eq!(location.alternate_allele.as_deref(), Some("T"));
+
+    assert_eq!(
+        archive.hgvs,
+        vec![
+            "NM_000546.6:c.743G>A",
+            "NP_000537.3:p.Arg248Gln",
+            "NC_000017.11:g.7674220C>T"
+        ]
+    );
+    assert_eq!(archive.rs_ids(), vec![11540652]);
+}
+
+#[test]
+fn parse_classifications() {
+    let xml = fs::read_to_string("tests/data/clinvar.xml").unwrap();
+    let archive = parse_variation_archives(&xml).unwrap().remove(0);
+
+    let germline = archive.germline().unwrap();
+    assert_eq!(germline.description.as_deref(), Some("Pathogenic"));
+    assert_eq!(germline.review_status.as_deref(), Some("reviewed by expert panel"));
+    assert_eq!(germline.date_last_evaluated.as_deref(), Some("2023-10-30"));
+
+    let names: Vec<_> = germline.conditions.iter().map(|c| c.name.as_deref()).collect();
+    assert_eq!(
+        names,
+        vec![Some("Li-Fraumeni syndrome"), Some("Hereditary cancer-predisposing syndrome")]
+    );
+    let xrefs = &germline.conditions[0].xrefs;
+    assert_eq!(xrefs.len(), 2);
+    assert_eq!((xrefs[0].db.as_str(), xrefs[0].id.as_str()), ("MedGen", "C0085390"));
+
+    assert_eq!(archive.submissions.len(), 2);
+    let omim = &archive.submissions[0];
+    assert_eq!(omim.accession, "SCV000033431");
+    assert_eq!(omim.submitter.as_deref(), Some("OMIM"));
+    assert_eq!(omim.classification.as_deref(), Some("Pathogenic"));
+    assert_eq!(omim.review_status.as_deref(), Some("no assertion criteria provided"));
+    assert_eq!(omim.origins, vec!["germline"]);
+    assert_eq!(omim.conditions[0].name.as_deref(), Some("LI-FRAUMENI SYNDROME 1"));
+
+    let panel = &archive.submissions[1];
+    assert_eq!(panel.comment.as_deref(), Some("PS3, PS4, PM1, PM2_Supporting, PM5, PP3"));
+    assert_eq!(panel.conditions[0].xrefs[0].db, "MONDO");
+}
+
+#[test]
+fn connect_to_variation_ref() {
+    let xml = fs::read_to_string("tests/data/clinvar.xml").unwrap();
+    let archive = parse_variation_archives(&xml).unwrap().remove(0);
+
+    let tag = DbTag {
+        db: "dbSNP".into(),
+        tag: ObjectId::Str("rs11540652".into()),
+    };
+    assert_eq!(archive.db_tags(), vec![tag.clone()]);
+
+    let mut variation = VariationRef {
+        id: Some(tag),
+        parent_id: None,
+        sample_id: None,
+        other_ids: None,
+        name: None,
+        synonyms: None,
+        description: None,
+        phenotype: None,
+        method: None,
+        variant_prop: None,
+        data: VariationRefData::Unknown,
+        consequence: None,
+        somatic_origin: None,
+    };
+    assert!(archive.describes(&variation));
+
+    variation.id = Some(DbTag {
+        db: "dbSNP".into(),
+        tag: ObjectId::Str("rs28934578".into()),
+    });
+    assert!(!archive.describes(&variation));
+}
+
+#[test]
+fn missing_archive() {
+    assert!(parse_variation_archives("<ClinVarResult-Set></ClinVarResult-Set>").is_err());
+}
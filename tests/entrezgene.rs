@@ -5,8 +5,13 @@ mod tests {
     use quick_xml::Reader;
     use quick_xml::events::Event;
 
-    use ncbi::entrezgene::{Entrezgene, EntrezgeneType, GeneTrack};
+    use ncbi::entrezgene::{Entrezgene, EntrezgeneType, GeneTrack, GoAspect};
+    use ncbi::general::ObjectId;
     use ncbi::parsing::{XmlNode,read_node};
+    use ncbi::pipeline::GenePipeline;
+    use ncbi::seqfeat::{RnaRefExt, RnaRefType};
+    use ncbi::summary::Summary;
+    use ncbi::{build_search_url, get_local_xml, parse_xml, DataType, EntrezDb};
 
     #[test]
     fn test_gene_track_parsing() {
@@ -90,4 +95,217 @@ mod tests {
 
         println!("Test successful: Entrezgene file was parsed correctly.");
     }
+
+    fn get_tp73() -> Entrezgene {
+        let data = get_local_xml("tests/data/tp73.genbank.xml");
+        match parse_xml(data.as_str()).unwrap() {
+            DataType::EntrezgeneSet(mut genes) => genes.remove(0),
+            _ => panic!("No Entrezgene set found"),
+        }
+    }
+
+    #[test]
+    fn test_entrezgene_rna() {
+        let xml = r#"<Entrezgene>
+            <Entrezgene_type value="ncRNA">5</Entrezgene_type>
+            <Entrezgene_gene><Gene-ref><Gene-ref_locus>TP73-AS1</Gene-ref_locus></Gene-ref></Entrezgene_gene>
+            <Entrezgene_rna><RNA-ref>
+              <RNA-ref_type value="ncRNA"/>
+              <RNA-ref_ext><RNA-ref_ext_gen><RNA-gen>
+                <RNA-gen_class>lncRNA</RNA-gen_class>
+                <RNA-gen_product>TP73 antisense RNA 1</RNA-gen_product>
+              </RNA-gen></RNA-ref_ext_gen></RNA-ref_ext>
+            </RNA-ref></Entrezgene_rna>
+        </Entrezgene>"#;
+        let mut reader = Reader::from_str(xml);
+        reader.trim_text(true);
+
+        let gene: Entrezgene = read_node(&mut reader).unwrap();
+        let rna = gene.rna.unwrap();
+        assert_eq!(rna.r#type, RnaRefType::ncRNA);
+        match rna.ext {
+            Some(RnaRefExt::Gen(gen)) => {
+                assert_eq!(gen.class.as_deref(), Some("lncRNA"));
+                assert_eq!(gen.product.as_deref(), Some("TP73 antisense RNA 1"));
+            }
+            ext => panic!("Unexpected ext {:?}", ext),
+        }
+    }
+
+    #[test]
+    fn test_entrezgene_locus() {
+        let gene = get_tp73();
+
+        let locus = gene.genomic_locus().unwrap();
+        assert_eq!(locus.versioned_accession().as_deref(), Some("NC_000001.11"));
+        let interval = locus.locus_interval().unwrap();
+        assert_eq!((interval.from, interval.to), (3652515, 3736200));
+
+        let transcripts = gene.transcript_accessions();
+        assert_eq!(transcripts[0], "NM_005427.4");
+        assert!(transcripts.contains(&"XM_047429524.1".to_string()));
+        assert!(transcripts.iter().all(|acc| !acc.starts_with("NP_")));
+    }
+
+    #[test]
+    fn test_gene_pipeline_requests() {
+        let gene = get_tp73();
+        let pipeline = GenePipeline::new("TP73", "Homo sapiens").flank(100);
+
+        assert_eq!(pipeline.search_term(), "TP73[sym] AND Homo sapiens[orgn]");
+        let url = build_search_url(EntrezDb::Gene, &pipeline.search_term());
+        assert!(url.contains("&term=TP73%5Bsym%5D+AND+Homo+sapiens%5Borgn%5D&"));
+
+        let url = pipeline.genomic_request(&gene).unwrap().build_url();
+        assert!(url.contains("id=NC_000001.11"));
+        assert!(url.contains("&seq_start=3652416&seq_stop=3736301&strand=1"));
+    }
+
+    #[test]
+    fn test_entrezgene_go_terms() {
+        let gene = get_tp73();
+        let terms = gene.go_terms();
+        assert_eq!(terms.len(), 49);
+
+        let first = &terms[0];
+        assert_eq!(first.aspect, GoAspect::Function);
+        assert_eq!(first.id, "GO:0001228");
+        assert_eq!(
+            first.name.as_deref(),
+            Some("DNA-binding transcription activator activity, RNA polymerase II-specific")
+        );
+        assert_eq!(first.qualifier.as_deref(), Some("enables"));
+        assert_eq!(first.evidence.as_deref(), Some("IDA"));
+        assert_eq!(first.pmids, vec![16343436]);
+
+        for aspect in [GoAspect::Function, GoAspect::Process, GoAspect::Component] {
+            assert!(terms.iter().any(|term| term.aspect == aspect));
+        }
+    }
+
+    #[test]
+    fn test_entrezgene_orthologs() {
+        let gene = get_tp73();
+        let orthologs = gene.orthologs();
+        assert_eq!(orthologs.len(), 2);
+
+        let mouse = &orthologs[1];
+        assert_eq!(mouse.gene_id, 22062);
+        assert_eq!(mouse.taxid, Some(10090));
+        assert_eq!(mouse.symbol.as_deref(), Some("TP73"));
+        assert_eq!(mouse.organism.as_deref(), Some("mouse"));
+        assert_eq!(mouse.label.as_deref(), Some("Additional"));
+        assert_eq!((orthologs[0].gene_id, orthologs[0].taxid), (7161, Some(9606)));
+    }
+
+    #[test]
+    fn test_entrezgene_phenotypes() {
+        let gene = get_tp73();
+        let phenotypes = gene.phenotypes();
+        assert_eq!(phenotypes.len(), 3);
+
+        let ciliary = &phenotypes[1];
+        assert_eq!(ciliary.name, "Ciliary dyskinesia, primary, 47, and lissencephaly");
+        let dbs: Vec<&str> = ciliary.xrefs.iter().map(|tag| tag.db.as_str()).collect();
+        assert_eq!(dbs, ["GTRDisease", "MedGen", "MIM"]);
+        assert_eq!(ciliary.xrefs[2].tag, ObjectId::Id(619466));
+        assert_eq!(phenotypes[2].pmids, vec![22589738]);
+    }
+
+    #[test]
+    fn test_entrezgene_generifs() {
+        let gene = get_tp73();
+        let generifs = gene.generifs();
+        assert!(!generifs.is_empty());
+        assert_eq!(
+            generifs[0].text,
+            "DeltaNp73 and its effector targets promote colorectal peritoneal carcinosis and predict survival."
+        );
+        assert_eq!(generifs[0].pmids, vec![38629257]);
+    }
+
+    #[test]
+    fn test_entrezgene_homology() {
+        let xml = r#"
+<Entrezgene>
+  <Entrezgene_type value="protein-coding">6</Entrezgene_type>
+  <Entrezgene_gene>
+    <Gene-ref>
+      <Gene-ref_locus>TP73</Gene-ref_locus>
+    </Gene-ref>
+  </Entrezgene_gene>
+  <Entrezgene_homology>
+    <Gene-commentary>
+      <Gene-commentary_type value="comment">254</Gene-commentary_type>
+      <Gene-commentary_heading>Mouse, Rat</Gene-commentary_heading>
+      <Gene-commentary_source>
+        <Other-source>
+          <Other-source_src>
+            <Dbtag>
+              <Dbtag_db>GeneID</Dbtag_db>
+              <Dbtag_tag>
+                <Object-id>
+                  <Object-id_id>22062</Object-id_id>
+                </Object-id>
+              </Dbtag_tag>
+            </Dbtag>
+          </Other-source_src>
+          <Other-source_anchor>Trp73</Other-source_anchor>
+        </Other-source>
+      </Gene-commentary_source>
+    </Gene-commentary>
+  </Entrezgene_homology>
+</Entrezgene>
+"#;
+        let mut reader = Reader::from_str(xml);
+        reader.trim_text(true);
+        let gene: Entrezgene = read_node(&mut reader).unwrap();
+        assert_eq!(gene.homology.as_ref().map(Vec::len), Some(1));
+
+        let orthologs = gene.orthologs();
+        assert_eq!(orthologs.len(), 1);
+        assert_eq!(orthologs[0].gene_id, 22062);
+        assert_eq!(orthologs[0].taxid, None);
+    }
+
+    #[test]
+    fn test_entrezgene_summary() {
+        let gene = get_tp73();
+        assert_eq!(gene.summary(), "Entrezgene 7161 TP73 (ProteinCoding)");
+
+        let tree = gene.tree();
+        let lines: Vec<&str> = tree.lines().collect();
+        assert_eq!(lines[1], "  source: Homo sapiens (taxon 9606)");
+        assert_eq!(lines[2], "  desc: tumor protein p73");
+        assert!(lines.contains(&"  locus: 3 commentaries"));
+    }
+
+    #[test]
+    fn test_entrezgene_assembly_loci() {
+        let gene = get_tp73();
+        let loci = gene.assembly_loci();
+        let names: Vec<&str> = loci.iter().map(|locus| locus.assembly).collect();
+        assert_eq!(names, ["GRCh38.p14", "T2T-CHM13v2.0"]);
+
+        let grch38 = gene.assembly_locus("GRCh38").unwrap();
+        assert_eq!(grch38.category, "Reference");
+        assert_eq!(grch38.unit.as_deref(), Some("Primary Assembly"));
+        assert_eq!(grch38.chromosome, Some("1"));
+        assert_eq!(grch38.accession().as_deref(), Some("NC_000001.11"));
+        assert_eq!(grch38.range(), Some((3652515, 3736200)));
+        assert_eq!(grch38.products().len(), 30);
+
+        let t2t = gene.assembly_locus("T2T-CHM13v2.0").unwrap();
+        assert_eq!(t2t.category, "Alternate");
+        assert_eq!(t2t.accession().as_deref(), Some("NC_060925.1"));
+
+        // the same protein is placed on each assembly
+        let protein = t2t.product("NP_005418.1").unwrap();
+        assert_eq!(protein.label(), Some("isoform a"));
+        assert_eq!(protein.parent.and_then(|mrna| mrna.accession.as_deref()), Some("NM_005427"));
+        assert_eq!(protein.range(), Some((3192213, 3244677)));
+        assert_eq!(grch38.product("NP_005418").unwrap().range(), Some((3682365, 3733078)));
+
+        assert!(gene.assembly_locus("GRCh3").is_none());
+    }
 }
@@ -0,0 +1,36 @@
+//! Text formats retrieved through a custom transport, without network access
+
+use ncbi::transport::{reset_transport, set_transport};
+use ncbi::{fetch_fasta, fetch_genbank, parse_fasta, EntrezDb};
+
+#[test]
+fn fetch_text_formats() {
+    set_transport(|url: &str| {
+        if url.contains("id=missing") {
+            Ok("<!DOCTYPE html>\n<html><head><title>Error</title></head><body></body></html>".to_string())
+        } else if url.contains("rettype=fasta&retmode=text") {
+            Ok(">NP_000537.3 cellular tumor antigen p53 [Homo sapiens]\nMEEPQSDPSV\nEPPLSQETFS\n\n".to_string())
+        } else if url.contains("rettype=gp&retmode=text") || url.contains("rettype=gb&retmode=text") {
+            Ok("LOCUS       NP_000537    393 aa    linear   PRI 01-JAN-2024\n//\n".to_string())
+        } else {
+            Err(format!("404 Not Found: {}", url))
+        }
+    });
+
+    let fasta = fetch_fasta(EntrezDb::Protein, "NP_000537.3").unwrap();
+    let records = parse_fasta(&fasta).unwrap();
+    assert_eq!(records.len(), 1);
+    assert!(records[0].0.starts_with("NP_000537.3 "));
+    assert_eq!(records[0].1, "MEEPQSDPSVEPPLSQETFS");
+
+    assert!(fetch_genbank(EntrezDb::Protein, "NP_000537.3").unwrap().starts_with("LOCUS"));
+    assert!(fetch_genbank(EntrezDb::Nucleotide, "NM_000546.6").is_ok());
+
+    let err = fetch_fasta(EntrezDb::Nucleotide, "missing").unwrap_err();
+    assert_eq!(err, "Expected FASTA but EFetch returned HTML page (Error)");
+
+    // formats which the database does not provide are not requested
+    assert!(fetch_genbank(EntrezDb::Gene, "7157").unwrap_err().contains("not valid for db=gene"));
+
+    reset_transport();
+}
@@ -0,0 +1,98 @@
+use ncbi::entrezgene::EntrezgeneType;
+use ncbi::flat::{open_flat, FlatReader, GeneAccession, GeneGo, GeneInfo};
+use ncbi::general::{DbTag, ObjectId};
+use ncbi::seqloc::NaStrand;
+
+#[test]
+fn read_gene_info() {
+    let genes: Vec<GeneInfo> = open_flat("tests/data/gene_info.tsv")
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(genes.len(), 4);
+
+    let tp53 = &genes[0];
+    assert_eq!((tp53.tax_id, tp53.gene_id), (9606, 7157));
+    assert_eq!(tp53.symbol, "TP53");
+    assert!(tp53.locus_tag.is_none());
+    assert_eq!(tp53.synonyms, vec!["BCC7", "BMFS5", "LFS1", "P53", "TRP53"]);
+    assert_eq!(tp53.db_xrefs[0], DbTag { db: "MIM".into(), tag: ObjectId::Id(191170) });
+    assert_eq!(tp53.db_xrefs[1], DbTag { db: "HGNC".into(), tag: ObjectId::Str("HGNC:11998".into()) });
+    assert_eq!(tp53.map_location.as_deref(), Some("17p13.1"));
+    assert_eq!(tp53.gene_type, EntrezgeneType::ProteinCoding);
+    assert_eq!(tp53.other_designations.len(), 3);
+    assert!(tp53.feature_type.is_empty());
+
+    assert_eq!(genes[3].gene_type, EntrezgeneType::NcRna);
+    assert!(genes[3].chromosome.is_none());
+}
+
+#[test]
+fn filter_taxa() {
+    let mouse: Vec<GeneInfo> = open_flat("tests/data/gene_info.tsv")
+        .unwrap()
+        .with_taxa(&[10090])
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(mouse.len(), 1);
+    assert_eq!(mouse[0].symbol, "Trp53");
+
+    let go: Vec<GeneGo> = open_flat("tests/data/gene2go.tsv")
+        .unwrap()
+        .with_taxa(&[9606])
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(go.len(), 3);
+    assert_eq!(go[0].pubmed_ids, vec![20959462, 22127205]);
+    assert!(go[1].pubmed_ids.is_empty());
+    assert!(!go[1].is_negated());
+    assert!(go[2].is_negated());
+    assert_eq!(go[2].category, "Component");
+}
+
+#[test]
+fn read_gene2accession() {
+    let rows: Vec<GeneAccession> = open_flat("tests/data/gene2accession.tsv")
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(rows.len(), 3);
+
+    let refseq = &rows[0];
+    assert_eq!(refseq.status.as_deref(), Some("REVIEWED"));
+    assert_eq!(refseq.rna.as_ref().unwrap().accession, "NM_000546.6");
+    assert_eq!(refseq.protein.as_ref().unwrap().gi, Some(120407068));
+    assert_eq!((refseq.start, refseq.end), (Some(7661778), Some(7687537)));
+    assert_eq!(refseq.range0().unwrap().start1().get(), 7661779);
+    assert_eq!(refseq.orientation, Some(NaStrand::Minus));
+    assert!(refseq.mature_peptide.is_none());
+    assert_eq!(refseq.symbol.as_deref(), Some("TP53"));
+
+    let genbank = &rows[1];
+    assert!(genbank.status.is_none() && genbank.rna.is_none());
+    assert!(genbank.start.is_none() && genbank.orientation.is_none());
+    assert_eq!(rows[2].orientation, Some(NaStrand::Plus));
+}
+
+#[test]
+fn invalid_rows() {
+    let data = "#tax_id\tGeneID\n9606\t7157\tTP53\n\nabc\t1\tGO:1\t-\t-\tterm\t-\tProcess\n";
+    let rows: Vec<Result<GeneGo, String>> = FlatReader::new(data.as_bytes()).collect();
+    assert_eq!(rows.len(), 2);
+    assert!(rows[0].as_ref().unwrap_err().starts_with("Line 2"));
+    assert!(rows[1].as_ref().unwrap_err().starts_with("Line 4"));
+}
+
+#[test]
+fn read_compressed() {
+    let plain: Vec<GeneInfo> = open_flat("tests/data/gene_info.tsv")
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    let compressed: Vec<GeneInfo> = open_flat("tests/data/gene_info.tsv.gz")
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(plain, compressed);
+    assert!(open_flat::<GeneInfo>("tests/data/missing.tsv").is_err());
+}
@@ -0,0 +1,92 @@
+//! Malformed input must be rejected without panicking
+//!
+//! Inputs are derived deterministically from the fixtures in `tests/data` so that any
+//! failure is reproducible.
+//!
+//! Not run with the `strict` feature, which deliberately panics on unknown tags.
+#![cfg(not(feature = "strict"))]
+
+use ncbi::fuzz::{fuzz_parse_bioseq, fuzz_parse_entrezgene, fuzz_parse_xml};
+use std::fs;
+
+/// xorshift64* generator, so that no additional dependencies are needed
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545F4914F6CDD1D)
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+}
+
+fn parse_all(data: &[u8]) {
+    fuzz_parse_bioseq(data);
+    fuzz_parse_entrezgene(data);
+    fuzz_parse_xml(data);
+}
+
+/// Sample of `path`, truncated to `len` bytes
+fn fixture(path: &str, len: usize) -> Vec<u8> {
+    let mut data = fs::read(path).unwrap();
+    data.truncate(len);
+    data
+}
+
+/// Copy of `data` with a few bytes replaced by XML syntax or random values
+fn mutate(rng: &mut Rng, data: &[u8]) -> Vec<u8> {
+    const SYNTAX: &[u8] = b"<>/=\"' \n&;";
+    let mut data = data.to_vec();
+    for _ in 0..=rng.below(4) {
+        let i = rng.below(data.len());
+        match rng.below(3) {
+            0 => data[i] = SYNTAX[rng.below(SYNTAX.len())],
+            1 => data[i] = rng.next() as u8,
+            _ => {
+                data.remove(i);
+            }
+        }
+    }
+    data
+}
+
+#[test]
+fn arbitrary_bytes() {
+    let mut rng = Rng(0x9E3779B97F4A7C15);
+    for _ in 0..2000 {
+        let len = rng.below(512);
+        let data: Vec<u8> = (0..len).map(|_| rng.next() as u8).collect();
+        parse_all(&data);
+    }
+}
+
+#[test]
+fn truncated_fixtures() {
+    let mut rng = Rng(1);
+    let popset = fixture("tests/data/popset.xml", usize::MAX);
+    let gene = fixture("tests/data/tp73.genbank.xml", 64 * 1024);
+    let wgs = fixture("tests/data/2519734237.xml", 64 * 1024);
+    for data in [popset, gene, wgs] {
+        for _ in 0..300 {
+            parse_all(&data[..rng.below(data.len())]);
+        }
+    }
+}
+
+#[test]
+fn mutated_fixtures() {
+    let mut rng = Rng(2);
+    let popset = fixture("tests/data/popset.xml", usize::MAX);
+    let gene = fixture("tests/data/tp73.genbank.xml", 64 * 1024);
+    let wgs = fixture("tests/data/2519734237.xml", 64 * 1024);
+    for data in [popset, gene, wgs] {
+        for _ in 0..300 {
+            parse_all(&mutate(&mut rng, &data));
+        }
+    }
+}
@@ -0,0 +1,157 @@
+use ncbi::gbseq::{GBSeq, GBSet};
+use ncbi::general::{FuzzLimit, IntFuzz, ObjectId};
+use ncbi::seq::{BioSeq, Descriptors, Mol, SeqData, Strand};
+use ncbi::seqfeat::SeqFeatData;
+use ncbi::seqloc::{NaStrand, SeqId, SeqLoc, TextseqId};
+use ncbi::{get_local_xml, parse_xml, parse_xml_multi, DataType};
+
+fn gb_set() -> GBSet {
+    match parse_xml(&get_local_xml("tests/data/gbseq.xml")).unwrap() {
+        DataType::GBSet(set) => set,
+        _ => panic!("No GBSet found"),
+    }
+}
+
+fn ddbj(accession: &str, version: u64) -> SeqId {
+    SeqId::Ddbj(TextseqId {
+        accession: Some(accession.to_string()),
+        version: Some(version),
+        ..TextseqId::default()
+    })
+}
+
+#[test]
+fn parse_gb_set() {
+    let set = gb_set();
+    assert_eq!(set.len(), 1);
+
+    let gbseq = &set[0];
+    assert_eq!(gbseq.accession_version.as_deref(), Some("AB000001.2"));
+    assert_eq!(gbseq.length, 60);
+    assert_eq!(gbseq.moltype, "DNA");
+    assert_eq!(gbseq.other_seqids.as_ref().unwrap().len(), 2);
+    assert_eq!(gbseq.keywords, Some(vec!["synthetic".to_string()]));
+    assert_eq!(gbseq.references.as_ref().unwrap()[0].pubmed, Some(33333333));
+
+    let features = gbseq.feature_table.as_ref().unwrap();
+    assert_eq!(features.len(), 4);
+    assert_eq!(features[2].key, "CDS");
+    assert_eq!(features[2].intervals.as_ref().unwrap().len(), 2);
+    assert_eq!(features[2].partial3, Some(true));
+    assert_eq!(features[2].qualifier("protein_id"), Some("BAA00001.1"));
+    assert_eq!(gbseq.sequence.as_ref().map(String::len), Some(60));
+}
+
+#[test]
+fn gbseq_to_bioseq() {
+    let bioseq = BioSeq::from(&gb_set()[0]);
+    assert_eq!(bioseq.id, vec![ddbj("AB000001", 2), SeqId::Gi(1000003)]);
+    assert_eq!(bioseq.title(), Some("Escherichia coli thrL gene, partial cds"));
+    assert_eq!(bioseq.taxid(), Some(562));
+    assert_eq!(bioseq.organism().unwrap().lineage_vec().last(), Some(&"Escherichia"));
+    assert!(bioseq.update_date().is_some());
+
+    let inst = bioseq.inst.as_ref().unwrap();
+    assert_eq!(inst.mol, Mol::DNA);
+    assert_eq!(inst.strand, Strand::DS);
+    assert_eq!(inst.length, Some(60));
+    assert!(matches!(&inst.seq_data, Some(SeqData::Ina(residues)) if residues.starts_with("ATGAAACGC")));
+
+    // the source feature only gives the organism
+    let features = bioseq.annot.as_ref().unwrap()[0].features();
+    assert_eq!(features.len(), 3);
+
+    let cds = &features[1];
+    assert!(matches!(cds.data, SeqFeatData::CdRegion(_)));
+    assert_eq!(cds.partial, Some(true));
+    assert_eq!(cds.product, Some(SeqLoc::Whole(ddbj("BAA00001", 1))));
+    assert_eq!(cds.location.total_range(), Some((0, 59)));
+    assert!(cds.location.is_partial_3prime());
+    assert!(!cds.location.is_partial_5prime());
+
+    let misc = &features[2];
+    match &misc.location {
+        SeqLoc::Int(interval) => {
+            assert_eq!((interval.from, interval.to), (44, 54));
+            assert_eq!(interval.strand, Some(NaStrand::Minus));
+            assert_eq!(interval.fuzz_from, Some(IntFuzz::Lim(FuzzLimit::LT)));
+            assert_eq!(interval.id, ddbj("AB000001", 2));
+        }
+        loc => panic!("unexpected location {:?}", loc),
+    }
+    assert_eq!(misc.comment.as_deref(), Some("antisense element"));
+}
+
+#[test]
+fn bioseq_to_gbseq() {
+    let original = &gb_set()[0];
+    let gbseq = GBSeq::from(BioSeq::from(original));
+
+    assert_eq!(gbseq.accession_version, original.accession_version);
+    assert_eq!(gbseq.primary_accession, original.primary_accession);
+    assert_eq!(gbseq.other_seqids, original.other_seqids);
+    assert_eq!(gbseq.definition, original.definition);
+    assert_eq!(gbseq.length, original.length);
+    assert_eq!(gbseq.moltype, original.moltype);
+    assert_eq!(gbseq.strandedness, original.strandedness);
+    assert_eq!(gbseq.division, original.division);
+    assert_eq!(gbseq.update_date, original.update_date);
+    assert_eq!(gbseq.organism, original.organism);
+    assert_eq!(gbseq.taxonomy, original.taxonomy);
+    assert_eq!(gbseq.keywords, original.keywords);
+    assert_eq!(gbseq.sequence, original.sequence);
+
+    let reference = &gbseq.references.as_ref().unwrap()[0];
+    assert_eq!(reference.pubmed, Some(33333333));
+    assert_eq!(reference.title.as_deref(), Some("A synthetic GenBank record"));
+    assert_eq!(reference.authors.as_ref().unwrap().len(), 2);
+
+    let features = gbseq.feature_table.as_ref().unwrap();
+    let locations: Vec<&str> = features.iter().map(|feature| feature.location.as_str()).collect();
+    assert_eq!(locations, ["1..60", "1..>60", "join(1..20,31..>60)", "complement(<45..55)"]);
+    assert_eq!(features[0].qualifier("db_xref"), Some("taxon:562"));
+
+    let cds = &features[2];
+    assert_eq!(cds.key, "CDS");
+    assert_eq!(cds.intervals, original.feature_table.as_ref().unwrap()[2].intervals);
+    assert_eq!(cds.partial3, Some(true));
+    assert_eq!(cds.qualifier("product"), Some("thr operon leader peptide"));
+    assert_eq!(cds.qualifier("protein_id"), Some("BAA00001.1"));
+    assert_eq!(cds.qualifier("transl_table"), Some("11"));
+    assert_eq!(features[3].intervals.as_ref().unwrap()[0].iscomp, Some(true));
+}
+
+#[test]
+fn local_ids() {
+    let gbseq = GBSeq {
+        accession_version: Some("AB000001.2".to_string()),
+        other_seqids: Some(vec!["lcl|contig_1".to_string()]),
+        ..GBSeq::default()
+    };
+    let bioseq = BioSeq::from(gbseq);
+    assert_eq!(
+        bioseq.id,
+        vec![
+            SeqId::Genbank(TextseqId {
+                accession: Some("AB000001".to_string()),
+                version: Some(2),
+                ..TextseqId::default()
+            }),
+            SeqId::Local(ObjectId::Str("contig_1".into())),
+        ]
+    );
+}
+
+#[test]
+fn concatenated_sets() {
+    let xml = get_local_xml("tests/data/gbseq.xml");
+    let joined = format!("{}\n{}", xml, xml);
+    let sets: Vec<GBSet> = parse_xml_multi(&joined)
+        .map(|data| match data.unwrap() {
+            DataType::GBSet(set) => set,
+            _ => panic!("No GBSet found"),
+        })
+        .collect();
+    assert_eq!(sets.len(), 2);
+    assert_eq!(sets[0], sets[1]);
+}
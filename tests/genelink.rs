@@ -0,0 +1,96 @@
+//! Entrezgene records of annotated features, served by a custom transport
+
+use ncbi::builder::{interval, BioSeqBuilder, SeqFeatBuilder};
+use ncbi::general::{DbTag, ObjectId};
+use ncbi::genelink::{fetch_feature_genes, gene_links, FeatureKey};
+use ncbi::seqfeat::{FeatId, GeneRef, RnaRefType, SeqFeatData};
+use ncbi::seqloc::SeqId;
+use ncbi::seqset::{BioSeqSet, SeqEntry};
+use ncbi::transport::{set_async_transport, AsyncHttpTransport, ResponseFuture};
+use std::sync::{Arc, Mutex};
+
+fn gene_id(id: u64) -> DbTag {
+    DbTag {
+        db: "GeneID".into(),
+        tag: ObjectId::Id(id),
+    }
+}
+
+/// Serves `tp73.genbank.xml` to every request, keeping the requested URLs
+struct Genes(Mutex<Vec<String>>);
+
+impl AsyncHttpTransport for Genes {
+    fn get<'a>(&'a self, url: &'a str) -> ResponseFuture<'a> {
+        self.0.lock().unwrap().push(url.to_string());
+        Box::pin(async move { std::fs::read_to_string("tests/data/tp73.genbank.xml").map_err(|e| e.to_string()) })
+    }
+}
+
+#[test]
+fn feature_genes() {
+    let id = SeqId::from_defline("ref|NC_000001.11|").unwrap();
+    let tp73 = GeneRef {
+        locus: Some("TP73".to_string()),
+        locus_tag: Some("TP73_1".to_string()),
+        ..GeneRef::default()
+    };
+    let gene = SeqFeatBuilder::new(SeqFeatData::Gene(tp73), interval(&id, 0, 29))
+        .dbxref(gene_id(7161))
+        .build()
+        .unwrap();
+    let mut cds = SeqFeatBuilder::cds(interval(&id, 0, 29)).dbxref(gene_id(7161)).build().unwrap();
+    cds.id = Some(FeatId::Local(ObjectId::Id(2)));
+    // CDS identified by the locus_tag of its gene xref, whose gene is not returned
+    let orphan = SeqFeatBuilder::cds(interval(&id, 30, 59))
+        .gene_xref(GeneRef {
+            locus_tag: Some("ORPHAN_1".to_string()),
+            ..GeneRef::default()
+        })
+        .dbxref(gene_id(999))
+        .build()
+        .unwrap();
+    // neither gene nor CDS
+    let rrna = SeqFeatBuilder::rna(RnaRefType::rRNA, interval(&id, 0, 9)).dbxref(gene_id(1)).build().unwrap();
+    let bioseq = BioSeqBuilder::new(id)
+        .iupacna(&"ACGT".repeat(15))
+        .feature(gene)
+        .feature(cds)
+        .feature(orphan)
+        .feature(rrna)
+        .build()
+        .unwrap();
+    let set = BioSeqSet {
+        seq_set: vec![SeqEntry::Seq(bioseq)],
+        ..BioSeqSet::default()
+    };
+
+    let links = gene_links(&set);
+    assert_eq!(links.len(), 3);
+    assert_eq!(links[0].key, FeatureKey::LocusTag("TP73_1".to_string()));
+    assert_eq!(links[2].key, FeatureKey::LocusTag("ORPHAN_1".to_string()));
+
+    let transport = Arc::new(Genes(Mutex::new(Vec::new())));
+    set_async_transport(Shared(transport.clone()));
+    let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+    let genes = runtime.block_on(fetch_feature_genes(&set)).unwrap();
+
+    // both GeneIDs are requested at once
+    let urls = transport.0.lock().unwrap().clone();
+    assert_eq!(urls.len(), 1);
+    assert!(urls[0].contains("db=gene&id=999,7161&retmode=xml"));
+
+    assert_eq!(genes.len(), 2);
+    let tp73 = &genes[&FeatureKey::Id(FeatId::Local(ObjectId::Id(2)))];
+    assert_eq!(tp73.gene.locus.as_deref(), Some("TP73"));
+    assert_eq!(genes[&FeatureKey::LocusTag("TP73_1".to_string())], *tp73);
+    assert!(!genes.contains_key(&FeatureKey::LocusTag("ORPHAN_1".to_string())));
+}
+
+/// Installs a transport which remains inspectable by the test
+struct Shared(Arc<Genes>);
+
+impl AsyncHttpTransport for Shared {
+    fn get<'a>(&'a self, url: &'a str) -> ResponseFuture<'a> {
+        self.0.get(url)
+    }
+}
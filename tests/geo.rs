@@ -0,0 +1,60 @@
+use ncbi::geo::{GeoDataset, GeoProfile};
+use std::fs;
+
+#[test]
+fn parse_dataset_summaries() {
+    let xml = fs::read_to_string("tests/data/gds_summary.xml").unwrap();
+    let datasets = GeoDataset::from_esummary(&xml).unwrap();
+    assert_eq!(datasets.len(), 2);
+
+    let series = &datasets[0];
+    assert_eq!(series.uid, 200002034);
+    assert_eq!(series.accession, "GSE2034");
+    assert_eq!(series.entry_type.as_deref(), Some("GSE"));
+    assert_eq!(series.title.as_deref(), Some("Breast cancer relapse free survival"));
+    assert_eq!(series.taxon.as_deref(), Some("Homo sapiens"));
+    assert_eq!(series.platforms, vec!["GPL96"]);
+    assert_eq!(series.n_samples, Some(286));
+    assert_eq!(series.samples.len(), 2);
+    assert_eq!(series.samples[0].accession, "GSM36777");
+    assert_eq!(series.samples[1].title.as_deref(), Some("Breast tumor 287"));
+    assert_eq!(series.pubmed_ids, vec![15721472]);
+    assert_eq!(series.bioproject.as_deref(), Some("PRJNA91413"));
+
+    let platform = &datasets[1];
+    assert_eq!(platform.accession, "GPL96");
+    assert_eq!(platform.series, vec!["GSE2034", "GSE3494"]);
+    assert_eq!(platform.summary, None);
+    assert!(platform.samples.is_empty());
+    assert!(platform.pubmed_ids.is_empty());
+}
+
+#[test]
+fn parse_profile_summaries() {
+    let xml = r#"<eSummaryResult>
+<DocSum>
+	<Id>4395473</Id>
+	<Item Name="ID_REF" Type="String">201746_at</Item>
+	<Item Name="GDS" Type="String">807</Item>
+	<Item Name="geneName" Type="String">TP53</Item>
+	<Item Name="geneDesc" Type="String">tumor protein p53</Item>
+	<Item Name="nucDesc" Type="String"></Item>
+	<Item Name="title" Type="String">Breast cancer relapse free survival</Item>
+	<Item Name="taxon" Type="String">Homo sapiens</Item>
+	<Item Name="GBACC" Type="String">NM_000546</Item>
+	<Item Name="geneID" Type="Integer">7157</Item>
+</DocSum>
+</eSummaryResult>"#;
+    let profiles = GeoProfile::from_esummary(xml).unwrap();
+    assert_eq!(profiles.len(), 1);
+
+    let profile = &profiles[0];
+    assert_eq!(profile.uid, 4395473);
+    assert_eq!(profile.id_ref.as_deref(), Some("201746_at"));
+    assert_eq!(profile.dataset.as_deref(), Some("GDS807"));
+    assert_eq!(profile.gene_name.as_deref(), Some("TP53"));
+    assert_eq!(profile.gene_id, Some(7157));
+    assert_eq!(profile.genbank_accession.as_deref(), Some("NM_000546"));
+
+    assert!(GeoProfile::from_esummary("<eSearchResult></eSearchResult>").is_err());
+}
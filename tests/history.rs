@@ -0,0 +1,84 @@
+//! Paging through history server results which expire, without network access
+
+use ncbi::history::{HistorySession, RefreshPolicy, MAX_POST_IDS};
+use ncbi::transport::{reset_transport, set_transport};
+use ncbi::{EntrezDb, FetchRequest};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// History server storing 5 records, whose first session expires after one page
+fn expiring_server() -> Arc<AtomicUsize> {
+    let sessions = Arc::new(AtomicUsize::new(0));
+    let counter = sessions.clone();
+    set_transport(move |url: &str| {
+        if url.contains("esearch.fcgi") {
+            assert!(url.contains("&usehistory=y"));
+            let session = counter.fetch_add(1, Ordering::SeqCst) + 1;
+            return Ok(format!(
+                "<eSearchResult><Count>5</Count><RetMax>0</RetMax><QueryKey>1</QueryKey><WebEnv>MCID_{}</WebEnv><IdList/></eSearchResult>",
+                session
+            ));
+        }
+        if url.contains("epost.fcgi") {
+            assert!(url.contains("&id=1,2,3,4,5"));
+            let session = counter.fetch_add(1, Ordering::SeqCst) + 1;
+            return Ok(format!("<ePostResult><QueryKey>1</QueryKey><WebEnv>MCID_{}</WebEnv></ePostResult>", session));
+        }
+        assert!(!url.contains("&id="));
+        let start: u64 = url.split("&retstart=").nth(1).unwrap().split('&').next().unwrap().parse().unwrap();
+        if url.contains("WebEnv=MCID_1&") && start > 0 {
+            return Ok("<eFetchResult><ERROR>Unable to obtain query #1</ERROR></eFetchResult>".to_string());
+        }
+        let ids: Vec<String> = (start + 1..=(start + 2).min(5)).map(|id| format!(">{}\nACGT\n", id)).collect();
+        Ok(ids.concat())
+    });
+    sessions
+}
+
+#[test]
+fn refresh_expired_history() {
+    let request = FetchRequest::new(EntrezDb::Nucleotide, "").rettype("fasta").retmode("text");
+
+    let sessions = expiring_server();
+    let mut session = HistorySession::search(EntrezDb::Nucleotide, "Myotis[Organism]");
+    let pages: Vec<String> = session.pages(&request, 2).collect::<Result<_, _>>().unwrap();
+    assert_eq!(pages, [">1\nACGT\n>2\nACGT\n", ">3\nACGT\n>4\nACGT\n", ">5\nACGT\n"]);
+    assert_eq!(session.count, 5);
+    assert_eq!(session.refreshes, 1);
+    assert_eq!(session.web_env.as_deref(), Some("MCID_2"));
+    assert_eq!(sessions.load(Ordering::SeqCst), 2);
+
+    let message = session.message();
+    assert_eq!((message.web_env.as_deref(), message.query_key, message.count), (Some("MCID_2"), Some(1), Some(5)));
+
+    // posted UIDs are posted again
+    expiring_server();
+    let mut session = HistorySession::post(EntrezDb::Nucleotide, &["1", "2", "3", "4", "5"]);
+    assert_eq!(session.pages(&request, 2).count(), 3);
+    assert_eq!(session.refreshes, 1);
+
+    // expiration is returned as is, and the next request stores the results again
+    expiring_server();
+    let mut session = HistorySession::search(EntrezDb::Nucleotide, "Myotis[Organism]").policy(RefreshPolicy::Fail);
+    assert!(session.fetch_page(&request, 0, 2).is_ok());
+    let err = session.fetch_page(&request, 2, 2).unwrap_err();
+    assert!(err.starts_with("efetch error: Unable to obtain query #1 ("), "{}", err);
+    assert!(!session.is_stored());
+    assert_eq!(session.fetch_page(&request, 2, 2).unwrap(), ">3\nACGT\n>4\nACGT\n");
+    assert_eq!(session.refreshes, 0);
+
+    reset_transport();
+}
+
+#[test]
+fn store_urls() {
+    let session = HistorySession::search(EntrezDb::Nucleotide, "Myotis[Organism] AND cytb&retmax=1");
+    let url = session.build_store_url();
+    assert!(url.contains("&term=Myotis%5BOrganism%5D+AND+cytb%26retmax%3D1&"), "{}", url);
+    assert!(url.ends_with("&usehistory=y&retmax=0"));
+
+    // too many UIDs are rejected before being sent
+    let ids: Vec<String> = (0..=MAX_POST_IDS).map(|id| id.to_string()).collect();
+    let err = HistorySession::post(EntrezDb::Nucleotide, &ids).store().unwrap_err();
+    assert_eq!(err, format!("Cannot post {} UIDs at once, the limit is {}", MAX_POST_IDS + 1, MAX_POST_IDS));
+}
@@ -0,0 +1,56 @@
+use ncbi::index::FeatureIndex;
+use ncbi::seqfeat::{SeqFeat, SeqFeatData};
+use ncbi::seqset::{BioSeqSet, SeqEntry};
+use ncbi::{get_local_xml, parse_xml, DataType};
+
+fn wgs() -> BioSeqSet {
+    match parse_xml(&get_local_xml("tests/data/2519734237.xml")).unwrap() {
+        DataType::BioSeqSet(set) => set,
+        _ => panic!("No Bioseq set found"),
+    }
+}
+
+/// Features overlapping `from..=to`, found by scanning every feature
+fn scan(features: &[&SeqFeat], from: i64, to: i64) -> usize {
+    features
+        .iter()
+        .filter_map(|feat| feat.location.total_range())
+        .filter(|(start, end)| *start <= to && *end >= from)
+        .count()
+}
+
+#[test]
+fn wgs_feature_index() {
+    let set = wgs();
+    let bioseq = match set.seq_set.first() {
+        Some(SeqEntry::Seq(bioseq)) => bioseq,
+        _ => panic!("Entry is not Bioseq"),
+    };
+    let index = FeatureIndex::from_bioseq(bioseq);
+    let features: Vec<&SeqFeat> = index.iter().collect();
+    assert!(!index.is_empty());
+
+    for (from, to) in [(0, 0), (10_000, 12_500), (40_000, 41_000), (86_000, 90_000)] {
+        let found = index.features_overlapping(from..=to);
+        assert_eq!(found.len(), scan(&features, from, to));
+        assert!(found
+            .windows(2)
+            .all(|pair| pair[0].location.total_range() <= pair[1].location.total_range()));
+
+        for feat in index.features_within(from..=to) {
+            let (start, end) = feat.location.total_range().unwrap();
+            assert!(start >= from && end <= to);
+        }
+    }
+
+    // nearest gene of a position covered by a gene is that gene
+    let is_gene = |feat: &SeqFeat| matches!(feat.data, SeqFeatData::Gene(_));
+    let (start, end) = features
+        .iter()
+        .find(|feat| is_gene(feat))
+        .and_then(|feat| feat.location.total_range())
+        .unwrap();
+    let nearest = index.nearest_where((start + end) / 2, is_gene).unwrap();
+    assert!(is_gene(nearest));
+    assert!(nearest.location.total_range().is_some_and(|(s, e)| s <= end && e >= start));
+}
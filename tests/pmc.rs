@@ -0,0 +1,121 @@
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use ncbi::pmc::{parse_articles, parse_pubmed_articles, OaPackage};
+use std::fs;
+
+fn jats() -> String {
+    fs::read_to_string("tests/data/jats.xml").unwrap()
+}
+
+/// Gzip compressed package of three articles, and a file which is not an article
+fn package() -> Vec<u8> {
+    let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::fast()));
+    let mut append = |path: &str, data: &str| {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder.append_data(&mut header, path, data.as_bytes()).unwrap();
+    };
+    for pmcid in ["PMC1000001", "PMC1000002", "PMC1000003"] {
+        let xml = jats().replace("PMC1000001", pmcid);
+        append(&format!("PMC001xxxxxx/{}.xml", pmcid), &xml);
+    }
+    append("PMC001xxxxxx/filelist.txt", "PMC1000001\nPMC1000002\nPMC1000003\n");
+    builder.into_inner().unwrap().finish().unwrap()
+}
+
+#[test]
+fn parse_jats_article() {
+    let articles = parse_articles(&jats()).unwrap();
+    assert_eq!(articles.len(), 1);
+
+    let article = &articles[0];
+    assert_eq!(article.article_type.as_deref(), Some("research-article"));
+    assert_eq!(article.pmcid.as_deref(), Some("PMC1000001"));
+    assert_eq!(article.pmid, Some(10000001));
+    assert_eq!(article.doi.as_deref(), Some("10.0000/example.2023.001"));
+    assert_eq!(article.journal.as_deref(), Some("Example Genomics"));
+    assert_eq!(
+        article.title.as_deref(),
+        Some("Annotation of Escherichia coli contigs & plasmids")
+    );
+    assert_eq!(article.pub_year, Some(2023));
+    assert_eq!(article.keywords, vec!["genome annotation", "WGS"]);
+    assert_eq!(
+        article.r#abstract.as_deref(),
+        Some("Background Draft genomes are split into contigs. Results We annotated 88 genes.")
+    );
+
+    // references are not contributors
+    assert_eq!(article.contributors.len(), 3);
+    let authors: Vec<_> = article.authors().collect();
+    assert_eq!(authors.len(), 2);
+    assert_eq!(authors[1].surname.as_deref(), Some("Roe"));
+    assert_eq!(authors[1].given_names.as_deref(), Some("Richard A"));
+
+    let sections = &article.sections;
+    assert_eq!(sections.len(), 4);
+    assert_eq!(sections[0].title, None);
+    assert_eq!(sections[0].paragraphs, vec!["An unsectioned opening paragraph."]);
+    assert_eq!(sections[1].title.as_deref(), Some("Introduction"));
+    assert_eq!(
+        sections[1].paragraphs,
+        vec!["Whole genome shotgun assemblies1 are annotated by PGAP."]
+    );
+    assert_eq!((sections[2].level, sections[2].title.as_deref()), (2, Some("Contigs")));
+    // figure captions are skipped
+    assert_eq!(sections[2].paragraphs.len(), 1);
+    assert_eq!(sections[3].level, 1);
+    assert!(article.body_text().ends_with("separate record.\n\nGenes were counted."));
+}
+
+#[test]
+fn parse_pubmed_article() {
+    let articles = parse_pubmed_articles(&fs::read_to_string("tests/data/pubmed_article.xml").unwrap()).unwrap();
+    assert_eq!(articles.len(), 1);
+
+    let article = &articles[0];
+    assert_eq!(article.article_type.as_deref(), Some("Journal Article"));
+    // ids of references are skipped
+    assert_eq!(article.pmid, Some(10000002));
+    assert_eq!(article.pmcid.as_deref(), Some("PMC1000002"));
+    assert_eq!(article.doi.as_deref(), Some("10.0000/example.2023.002"));
+    assert_eq!(article.journal.as_deref(), Some("Example Journal of Microbial Genomics"));
+    assert_eq!(article.pub_year, Some(2023));
+    assert!(article.title.as_deref().unwrap().starts_with("Plasmid content of Klebsiella pneumoniae"));
+    assert!(article.r#abstract.as_deref().unwrap().ends_with("assembled their plasmids. Most resistance genes were found on IncF plasmids."));
+    assert_eq!(article.authors().count(), 3);
+    assert_eq!(article.contributors[0].surname.as_deref(), Some("Doe"));
+    assert_eq!(article.contributors[1].given_names.as_deref(), Some("Richard"));
+    assert!(article.sections.is_empty());
+
+    assert!(parse_pubmed_articles(&jats()).is_err());
+}
+
+#[test]
+fn parse_jats_errors() {
+    assert!(parse_articles("<pmc-articleset></pmc-articleset>").is_err());
+    assert!(parse_articles("<article><front></article>").is_err());
+}
+
+#[test]
+fn read_oa_package() {
+    let data = package();
+
+    let mut package = OaPackage::from_gz(data.as_slice());
+    let pmcids: Vec<_> = package
+        .articles()
+        .unwrap()
+        .map(|article| article.unwrap().pmcid.unwrap())
+        .collect();
+    assert_eq!(pmcids, vec!["PMC1000001", "PMC1000002", "PMC1000003"]);
+
+    let mut package = OaPackage::from_gz(data.as_slice()).with_pmcids(&["PMC1000003", "1000001"]);
+    let pmcids: Vec<_> = package
+        .articles()
+        .unwrap()
+        .map(|article| article.unwrap().pmcid.unwrap())
+        .collect();
+    assert_eq!(pmcids, vec!["PMC1000001", "PMC1000003"]);
+}
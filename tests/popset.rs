@@ -0,0 +1,133 @@
+use ncbi::seqalign::{SeqAlignSegs, SeqAlignType};
+use ncbi::seqfeat::{OrgModSubType, SubSourceSubType};
+use ncbi::mapper::SeqLocMapper;
+use ncbi::seqloc::{NaStrand, SeqId, SeqInterval, SeqLoc};
+use ncbi::parsing::parse_node;
+use ncbi::seqset::{BioSeqSet, BioSeqSetClass};
+use ncbi::{get_local_xml, parse_xml, DataType};
+
+const POPSET: &str = "tests/data/popset.xml";
+
+fn get_popset() -> BioSeqSet {
+    let data = get_local_xml(POPSET);
+    if let DataType::BioSeqSet(set) = parse_xml(data.as_str()).unwrap() {
+        set
+    } else {
+        panic!("No Bioseq set found")
+    }
+}
+
+#[test]
+fn parse_popset_class() {
+    let set = get_popset();
+    assert_eq!(set.class, BioSeqSetClass::PopSet);
+    assert_eq!(set.bioseqs().len(), 3);
+}
+
+#[test]
+fn parse_popset_alignment() {
+    let set = get_popset();
+
+    let alignments = set.alignments();
+    assert_eq!(alignments.len(), 1);
+
+    let align = alignments[0];
+    assert_eq!(align.r#type, SeqAlignType::Global);
+    assert_eq!(align.dim, Some(3));
+    if let SeqAlignSegs::DenSeg(denseg) = &align.segs {
+        assert_eq!(denseg.numseg, 3);
+        assert_eq!(denseg.ids.len(), 3);
+        assert_eq!(denseg.starts, vec![0, 0, 0, 15, -1, 15, 18, 15, 18]);
+        assert_eq!(denseg.lens, vec![15, 3, 12]);
+        assert_eq!(denseg.strand(0), Some(&NaStrand::Plus));
+        assert_eq!(denseg.start(1, 1), None);
+        assert_eq!(denseg.aligned_length(), 30);
+    } else {
+        panic!("Alignment is not a Dense-seg")
+    }
+}
+
+#[test]
+fn popset_aligned_seqs() {
+    let set = get_popset();
+
+    let rows = set.aligned_seqs();
+    assert_eq!(rows.len(), 3);
+
+    for row in rows.iter() {
+        assert!(row.bioseq.is_some());
+        assert_eq!(row.taxname(), Some("Myotis lucifugus"));
+        assert_eq!(row.org_mods().len(), 1);
+        assert_eq!(row.org_mods()[0].subtype, OrgModSubType::Isolate);
+        assert_eq!(row.subsources()[0].subtype, SubSourceSubType::Country);
+        assert_eq!(row.gapped().unwrap().len(), 30);
+    }
+
+    let second = &rows[1];
+    if let SeqId::Genbank(id) = second.id {
+        assert_eq!(id.accession.as_deref(), Some("MN908102"));
+    } else {
+        panic!("Unexpected Seq-id")
+    }
+    assert_eq!(second.org_mods()[0].subname, "ML-12");
+    assert_eq!(second.gapped().unwrap(), "ATGACCAACATCCGA---ACCCACCCACTA");
+    assert_eq!(rows[2].gapped().unwrap(), "ATGACTAACATCCGAAAGACCCACCCGCTA");
+}
+
+#[test]
+fn popset_map_between_rows() {
+    let set = get_popset();
+    let align = set.alignments()[0];
+    let rows = set.aligned_seqs();
+
+    let mapper = SeqLocMapper::from_seq_align(align, rows[1].id, rows[0].id).unwrap();
+    let loc = SeqLoc::Int(SeqInterval {
+        from: 10,
+        to: 20,
+        id: rows[1].id.clone(),
+        ..SeqInterval::default()
+    });
+
+    // second row has a gap over columns 15..18
+    if let SeqLoc::PackedInt(intervals) = mapper.map(&loc) {
+        let ranges: Vec<(i64, i64)> = intervals.iter().map(|i| (i.from, i.to)).collect();
+        assert_eq!(ranges, vec![(10, 14), (18, 23)]);
+        assert!(intervals.iter().all(|i| i.id == *rows[0].id));
+    } else {
+        panic!("Expected mapped location to be split")
+    }
+}
+
+#[test]
+fn parse_popset_parallel() {
+    let data = get_local_xml(POPSET);
+    let expected = get_popset();
+    for threads in [0, 1, 2, 3, 8] {
+        let set = BioSeqSet::from_str_parallel(&data, threads).unwrap();
+        assert_eq!(set, expected);
+    }
+
+    let data = get_local_xml("tests/data/2519734237.xml");
+    let expected = match parse_xml(data.as_str()).unwrap() {
+        DataType::BioSeqSet(set) => set,
+        _ => panic!("No Bioseq set found"),
+    };
+    assert_eq!(BioSeqSet::from_str_parallel(&data, 3).unwrap(), expected);
+
+    // a set without entries is parsed as such
+    let data = "<Bioseq-set><Bioseq-set_id><Object-id><Object-id_id>1</Object-id_id></Object-id></Bioseq-set_id><Bioseq-set_seq-set/></Bioseq-set>";
+    let set = BioSeqSet::from_str_parallel(data, 2).unwrap();
+    assert!(set.seq_set.is_empty());
+    assert_eq!(Some(set), parse_node::<BioSeqSet>(data));
+    assert!(BioSeqSet::entries_xml(data).unwrap().is_empty());
+}
+
+#[test]
+fn parse_compressed_popset() {
+    let data = get_local_xml("tests/data/popset.xml.gz");
+    assert_eq!(data, get_local_xml(POPSET));
+    match parse_xml(data.as_str()).unwrap() {
+        DataType::BioSeqSet(set) => assert_eq!(set.bioseqs().len(), 3),
+        _ => panic!("No Bioseq set found"),
+    }
+}
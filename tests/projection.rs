@@ -0,0 +1,51 @@
+use ncbi::parsing::ParseOptions;
+use ncbi::seq::SeqDesc;
+use ncbi::seqset::BioSeqSet;
+use ncbi::{get_local_xml, parse_xml, parse_xml_with, DataType};
+
+fn bioseq_set(data: Result<DataType, String>) -> BioSeqSet {
+    match data.unwrap() {
+        DataType::BioSeqSet(set) => set,
+        _ => panic!("No Bioseq set found"),
+    }
+}
+
+#[test]
+fn select_ids_and_sources() {
+    let data = get_local_xml("tests/data/2519734237.xml");
+    let full = bioseq_set(parse_xml(&data));
+
+    let options = ParseOptions::select(&["Bioseq_id", "Seqdesc_source"]);
+    let projected = bioseq_set(parse_xml_with(&data, &options));
+
+    let full_seqs = full.bioseqs();
+    let projected_seqs = projected.bioseqs();
+    assert_eq!(full_seqs.len(), projected_seqs.len());
+
+    for (full, projected) in full_seqs.iter().zip(projected_seqs.iter()) {
+        assert_eq!(full.id, projected.id);
+        assert!(projected.inst.is_none());
+        assert!(projected.annot.is_none());
+
+        let descr = projected.descr.as_ref().unwrap();
+        assert!(descr.iter().all(|desc| matches!(desc, SeqDesc::Source(_))));
+        let expected = full
+            .descr
+            .iter()
+            .flatten()
+            .find(|desc| matches!(desc, SeqDesc::Source(_)));
+        assert_eq!(descr.first(), expected);
+    }
+}
+
+#[test]
+fn options_are_scoped() {
+    let data = get_local_xml("tests/data/2519734237.xml");
+    let options = ParseOptions::select(&["Bioseq_id"]);
+    let projected = bioseq_set(parse_xml_with(&data, &options));
+    assert!(projected.bioseqs().iter().all(|seq| seq.annot.is_none()));
+
+    // parsing afterwards is unaffected
+    let full = bioseq_set(parse_xml(&data));
+    assert!(full.bioseqs().iter().any(|seq| seq.annot.is_some()));
+}
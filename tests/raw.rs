@@ -0,0 +1,69 @@
+#![cfg(feature = "raw-xml")]
+
+use ncbi::parsing::parse_node;
+use ncbi::seq::{BioSeq, SeqDesc};
+
+const BIOSEQ: &str = r#"<Bioseq>
+  <Bioseq_id>
+    <Seq-id>
+      <Seq-id_gi>1</Seq-id_gi>
+    </Seq-id>
+  </Bioseq_id>
+  <Bioseq_descr>
+    <Seq-descr>
+      <Seqdesc>
+        <Seqdesc_title>kept</Seqdesc_title>
+      </Seqdesc>
+      <Seqdesc>
+        <Seqdesc_genbank>
+          <GB-block>
+            <GB-block_keywords>
+              <GB-block_keywords_E>RefSeq</GB-block_keywords_E>
+            </GB-block_keywords>
+          </GB-block>
+        </Seqdesc_genbank>
+      </Seqdesc>
+      <Seqdesc>
+        <Seqdesc_comment>also kept</Seqdesc_comment>
+      </Seqdesc>
+    </Seq-descr>
+  </Bioseq_descr>
+  <Bioseq_inst>
+    <Seq-inst>
+      <Seq-inst_repr value="raw"/>
+      <Seq-inst_mol value="dna"/>
+      <Seq-inst_length>4</Seq-inst_length>
+    </Seq-inst>
+  </Bioseq_inst>
+</Bioseq>"#;
+
+#[test]
+fn unknown_descriptors_are_kept() {
+    let bioseq: BioSeq = parse_node(BIOSEQ).unwrap();
+    let descr = bioseq.descr.unwrap();
+    assert_eq!(descr.len(), 3);
+    assert!(matches!(&descr[0], SeqDesc::Title(title) if title == "kept"));
+    assert!(matches!(&descr[2], SeqDesc::Comment(comment) if comment == "also kept"));
+
+    let raw = match &descr[1] {
+        SeqDesc::Unknown(raw) => raw,
+        desc => panic!("unexpected descriptor {:?}", desc),
+    };
+    let start = BIOSEQ.find("<Seqdesc_genbank>").unwrap();
+    let end = BIOSEQ.find("</Seqdesc_genbank>").unwrap() + "</Seqdesc_genbank>".len();
+    assert_eq!(raw, &BIOSEQ[start..end]);
+}
+
+#[test]
+fn unknown_feature_and_annot_data_are_kept() {
+    use ncbi::seq::{SeqAnnot, SeqAnnotData};
+    use ncbi::seqfeat::SeqFeatData;
+
+    let imp = "<SeqFeatData_imp><Imp-feat><Imp-feat_key>misc_feature</Imp-feat_key></Imp-feat></SeqFeatData_imp>";
+    let data: SeqFeatData = parse_node(&format!("<SeqFeatData>{}</SeqFeatData>", imp)).unwrap();
+    assert_eq!(data, SeqFeatData::Unknown(imp.to_string()));
+
+    let graph = "<Seq-annot_data_graph><Seq-graph><Seq-graph_numval>0</Seq-graph_numval></Seq-graph></Seq-annot_data_graph>";
+    let annot: SeqAnnot = parse_node(&format!("<Seq-annot><Seq-annot_data>{}</Seq-annot_data></Seq-annot>", graph)).unwrap();
+    assert_eq!(annot.data, SeqAnnotData::Unknown(graph.to_string()));
+}
@@ -0,0 +1,72 @@
+use ncbi::record::{Record, RecordSource};
+use ncbi::seqloc::NaStrand;
+use ncbi::{get_local_xml, parse_xml, DataType};
+
+#[test]
+fn record_from_set() {
+    let set = match parse_xml(&get_local_xml("tests/data/2519734237.xml")).unwrap() {
+        DataType::BioSeqSet(set) => set,
+        _ => panic!("No Bioseq set found"),
+    };
+    let records = Record::from_set(&set);
+    assert_eq!(records.len(), 1);
+
+    let record = &records[0];
+    assert_eq!(record.id, "NZ_JARQWN010000024.1");
+    // delta sequences do not give their residues
+    assert_eq!(record.sequence, None);
+
+    let taxonomy = record.taxonomy.as_ref().unwrap();
+    assert_eq!(taxonomy.organism.as_deref(), Some("Klebsiella pneumoniae"));
+    assert_eq!(taxonomy.taxid, Some(573));
+    assert_eq!(taxonomy.lineage.first().map(String::as_str), Some("Bacteria"));
+
+    assert_eq!(record.references.len(), 2);
+    assert!(record.references[0].citation.contains("Direct Submission"));
+
+    assert_eq!(record.features.len(), 176);
+    let gene = &record.features[0];
+    assert_eq!(gene.key, "gene");
+    assert_eq!((gene.start, gene.end), (Some(307), Some(1152)));
+    let range = gene.range0().unwrap();
+    assert_eq!((range.start1().get(), range.end1().get(), range.len()), (308, 1153, 846));
+    assert_eq!(gene.strand, Some(NaStrand::Minus));
+    assert_eq!(gene.qualifier("gene"), Some("nhoA"));
+    assert_eq!(gene.qualifier("locus_tag"), Some("QRP16_RS18680"));
+
+    // the original object remains available
+    let bioseq = record.bioseq().unwrap();
+    assert_eq!(bioseq, set.bioseqs()[0]);
+}
+
+#[test]
+fn record_from_fasta() {
+    let fasta = ">NP_000537.3 cellular tumor antigen p53 isoform a [Homo sapiens]\n\
+        MEEPQSDPSV\n\
+        EPPLSQETFS\n\
+        \n\
+        >seq2\n\
+        ACGT\n";
+    let records = Record::from_fasta(fasta).unwrap();
+    assert_eq!(records.len(), 2);
+
+    let record = &records[0];
+    assert_eq!(record.id, "NP_000537.3");
+    assert_eq!(
+        record.description.as_deref(),
+        Some("cellular tumor antigen p53 isoform a [Homo sapiens]")
+    );
+    assert_eq!(record.sequence.as_deref(), Some("MEEPQSDPSVEPPLSQETFS"));
+    assert_eq!(record.length(), Some(20));
+    let organism = record.taxonomy.as_ref().and_then(|t| t.organism.as_deref());
+    assert_eq!(organism, Some("Homo sapiens"));
+    assert!(record.bioseq().is_none());
+    assert!(matches!(record.source(), RecordSource::Fasta(defline) if defline.starts_with("NP_000537.3 ")));
+
+    assert_eq!(records[1].id, "seq2");
+    assert_eq!(records[1].description, None);
+    assert!(records[1].taxonomy.is_none());
+
+    assert!(Record::from_fasta("").is_err());
+    assert!(Record::from_fasta("ACGT\n>seq").is_err());
+}
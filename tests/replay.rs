@@ -0,0 +1,99 @@
+#![cfg(feature = "fixtures")]
+//! Integration tests using recorded responses from `tests/data/responses`
+//!
+//! Run with `cargo test --features fixtures`
+
+use ncbi::delta::DeltaResolver;
+use ncbi::fixtures::{replay, Cassette};
+use ncbi::seqset::BioSeqSetClass;
+use ncbi::{fetch_popset, fetch_search, get_local_xml, parse_xml, DataType, EntrezDb, FetchRequest};
+
+const RESPONSES: &str = "tests/data/responses";
+
+#[test]
+fn replay_search() {
+    let _guard = replay(Cassette::from_dir(RESPONSES));
+
+    let result = fetch_search(EntrezDb::PopSet, "Myotis lucifugus cytb").unwrap();
+    assert_eq!(result.count, 1);
+    assert_eq!(result.ids, vec!["2104551001"]);
+}
+
+#[test]
+fn replay_popset() {
+    let _guard = replay(Cassette::from_dir(RESPONSES));
+
+    let set = fetch_popset("2104551001").unwrap();
+    assert_eq!(set.class, BioSeqSetClass::PopSet);
+    assert_eq!(set.aligned_seqs().len(), 3);
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn replay_fetch_stream() {
+    use ncbi::fetch_stream;
+    use ncbi::seqset::SeqEntry;
+
+    let _guard = replay(Cassette::from_dir(RESPONSES));
+    let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+    let entries = runtime.block_on(async {
+        let mut stream = fetch_stream::<SeqEntry>(EntrezDb::PopSet, "2104551001", "native", "xml").await?;
+        let mut entries = Vec::new();
+        while let Some(entry) = stream.next_node().await {
+            entries.push(entry?);
+        }
+        Ok::<_, String>(entries)
+    });
+    assert_eq!(entries.unwrap().len(), 3);
+
+    // not sent to the network when no response is recorded
+    let missing = runtime.block_on(fetch_stream::<SeqEntry>(EntrezDb::PopSet, "1", "native", "xml"));
+    assert!(missing.is_err());
+}
+
+#[test]
+fn replay_missing_response() {
+    let _guard = replay(Cassette::from_dir(RESPONSES));
+    assert!(fetch_popset("1").is_err());
+}
+
+#[test]
+fn replay_delta_components() {
+    // the WGS contig which NZ_JARQWN010000024.1 is built from
+    let contig: String = "ACGT".repeat(21622);
+    let contig = format!("{}A", contig);
+    let response = format!(
+        "<Bioseq-set><Bioseq-set_seq-set><Seq-entry><Seq-entry_seq><Bioseq>\
+        <Bioseq_id><Seq-id><Seq-id_genbank><Textseq-id>\
+        <Textseq-id_accession>JARQWN010000024</Textseq-id_accession>\
+        <Textseq-id_version>1</Textseq-id_version>\
+        </Textseq-id></Seq-id_genbank></Seq-id></Bioseq_id>\
+        <Bioseq_inst><Seq-inst><Seq-inst_repr value=\"raw\"/><Seq-inst_mol value=\"dna\"/>\
+        <Seq-inst_length>86489</Seq-inst_length>\
+        <Seq-inst_seq-data><Seq-data><Seq-data_iupacna><IUPACna>{}</IUPACna></Seq-data_iupacna></Seq-data></Seq-inst_seq-data>\
+        </Seq-inst></Bioseq_inst>\
+        </Bioseq></Seq-entry_seq></Seq-entry></Bioseq-set_seq-set></Bioseq-set>",
+        contig
+    );
+    let url = FetchRequest::new(EntrezDb::Nucleotide, "JARQWN010000024.1")
+        .rettype("native")
+        .retmode("xml")
+        .build_url();
+    let _guard = replay(Cassette::new().with_response(&url, &response));
+
+    let set = match parse_xml(&get_local_xml("tests/data/2519734237.xml")).unwrap() {
+        DataType::BioSeqSet(set) => set,
+        _ => panic!("expected a Bioseq-set"),
+    };
+    let scaffold = set.bioseqs()[0];
+
+    let mut resolver = DeltaResolver::new();
+    let residues = resolver.residues(scaffold).unwrap();
+    assert_eq!(residues.len(), contig.len());
+    assert!(residues == contig);
+    assert_eq!(resolver.cached(), 1);
+
+    // served from the cache
+    let _guard = replay(Cassette::new());
+    assert_eq!(resolver.residues(scaffold).unwrap().len(), 86489);
+}
@@ -0,0 +1,148 @@
+//! Property-based round-trip tests
+//!
+//! Arbitrary instances of core structures are checked to survive serialization
+//! unchanged: through JSON for all structures, and through XML for those which can be
+//! written as XML (eg: [`Pssm`]). Identifiers, locations and features are built by
+//! their `Arbitrary` implementations (with the `arbitrary` feature) from bytes generated
+//! by QuickCheck.
+
+use ncbi::parsing::parse_node;
+use ncbi::scoremat::{Pssm, PssmFinalData, PssmIntermediateData};
+use ncbi::{get_local_xml, parse_xml, DataType};
+use quickcheck::{Arbitrary, Gen, QuickCheck};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fmt::Debug;
+
+/// Number of instances generated per property
+const TESTS: u64 = 200;
+
+/// Maximum number of bytes from which an instance is built
+const SIZE: usize = 512;
+
+/// Arbitrary text, without control characters or surrounding whitespace, which
+/// parsers do not preserve
+fn text(g: &mut Gen) -> String {
+    let text: String = String::arbitrary(g).chars().filter(|c| !c.is_control()).collect();
+    match text.trim() {
+        "" => "x".to_string(),
+        text => text.to_string(),
+    }
+}
+
+fn option<T>(g: &mut Gen, f: impl FnOnce(&mut Gen) -> T) -> Option<T> {
+    bool::arbitrary(g).then(|| f(g))
+}
+
+/// Finite real, as NaN never compares equal and infinities are not valid XML reals
+fn real(g: &mut Gen) -> f64 {
+    match f64::arbitrary(g) {
+        value if value.is_finite() => value,
+        _ => 0.0,
+    }
+}
+
+fn pssm(g: &mut Gen) -> Pssm {
+    let num_rows = i64::from(u8::arbitrary(g) % 4 + 1);
+    let num_columns = i64::from(u8::arbitrary(g) % 4 + 1);
+    let cells = (num_rows * num_columns) as usize;
+    let final_data = |g: &mut Gen| PssmFinalData {
+        scores: (0..cells).map(|_| i64::from(i16::arbitrary(g))).collect(),
+        lambda: real(g),
+        kappa: real(g),
+        h: real(g),
+        scaling_factor: i64::from(u8::arbitrary(g)),
+        lambda_ungapped: option(g, real),
+        kappa_ungapped: option(g, real),
+        h_ungapped: option(g, real),
+        word_score_threshold: option(g, real),
+    };
+    let intermediate_data = |g: &mut Gen| PssmIntermediateData {
+        freq_ratios: (0..cells).map(|_| real(g)).collect(),
+        information_content: option(g, |g| (0..num_columns).map(|_| real(g)).collect()),
+        ..Default::default()
+    };
+    Pssm {
+        is_protein: bool::arbitrary(g),
+        identifier: option(g, text),
+        num_rows,
+        num_columns,
+        row_labels: option(g, |g| (0..num_rows).map(|_| text(g)).collect()),
+        by_row: bool::arbitrary(g),
+        query: None,
+        intermediate_data: option(g, intermediate_data),
+        final_data: Some(final_data(g)),
+    }
+}
+
+/// Wrapper which allows [`Arbitrary`] to be implemented for types of this crate
+#[cfg(feature = "arbitrary")]
+#[derive(Clone, Debug)]
+struct Arb<T>(T);
+
+#[cfg(feature = "arbitrary")]
+impl<T> Arbitrary for Arb<T>
+where
+    T: for<'a> arbitrary::Arbitrary<'a> + Clone + 'static,
+{
+    fn arbitrary(g: &mut Gen) -> Self {
+        loop {
+            let bytes = Vec::<u8>::arbitrary(g);
+            if let Ok(value) = arbitrary::Unstructured::new(&bytes).arbitrary() {
+                return Arb(value);
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct ArbPssm(Pssm);
+
+impl Arbitrary for ArbPssm {
+    fn arbitrary(g: &mut Gen) -> Self {
+        ArbPssm(pssm(g))
+    }
+}
+
+fn json_round_trip<T: Serialize + DeserializeOwned + PartialEq + Debug>(value: &T) -> bool {
+    let json = serde_json::to_string(value).unwrap();
+    let parsed: T = serde_json::from_str(&json).unwrap_or_else(|e| panic!("{}: {}", e, json));
+    &parsed == value
+}
+
+fn check<A: Arbitrary + Debug>(property: fn(A) -> bool) {
+    QuickCheck::new().rng(Gen::new(SIZE)).tests(TESTS).quickcheck(property);
+}
+
+#[cfg(feature = "arbitrary")]
+#[test]
+fn json_round_trips() {
+    use ncbi::general::{Date, DbTag, ObjectId};
+    use ncbi::seqfeat::SeqFeat;
+    use ncbi::seqloc::{SeqId, SeqLoc};
+
+    check::<Arb<ObjectId>>(|Arb(value)| json_round_trip(&value));
+    check::<Arb<DbTag>>(|Arb(value)| json_round_trip(&value));
+    check::<Arb<Date>>(|Arb(value)| json_round_trip(&value));
+    check::<Arb<SeqId>>(|Arb(value)| json_round_trip(&value));
+    check::<Arb<SeqLoc>>(|Arb(value)| json_round_trip(&value));
+    check::<Arb<SeqFeat>>(|Arb(value)| json_round_trip(&value));
+}
+
+#[test]
+fn pssm_round_trips() {
+    check::<ArbPssm>(|ArbPssm(value)| json_round_trip(&value));
+    check::<ArbPssm>(|ArbPssm(pssm)| parse_node::<Pssm>(&pssm.to_xml()) == Some(pssm));
+}
+
+/// Records parsed from fixtures serialize to JSON and back unchanged
+#[test]
+fn fixture_json_consistency() {
+    for path in ["tests/data/2519734237.xml", "tests/data/popset.xml", "tests/data/tp73.genbank.xml"] {
+        match parse_xml(&get_local_xml(path)).unwrap() {
+            DataType::BioSeqSet(set) => assert!(json_round_trip(&set), "{}", path),
+            DataType::EntrezgeneSet(genes) => assert!(json_round_trip(&genes), "{}", path),
+            _ => panic!("Unexpected record in {}", path),
+        }
+    }
+}
@@ -1,5 +1,7 @@
+use ncbi::parsing::parse_node;
 use ncbi::scoremat::{
     BlockProperty,
+    BlockPropertyType,
     CoreBlock,
     LoopConstraint,
     CoreDef,
@@ -11,3 +13,122 @@ use ncbi::scoremat::{
     PssmParameters,
     PssmWithParameters,
 };
+use std::fs;
+
+fn read_fixture() -> PssmWithParameters {
+    let xml = fs::read_to_string("tests/data/pssm.xml").unwrap();
+    parse_node::<PssmWithParameters>(&xml).unwrap()
+}
+
+#[test]
+fn parse_pssm_with_parameters() {
+    let parsed = read_fixture();
+    let pssm = &parsed.pssm;
+
+    assert!(pssm.is_protein);
+    assert!(!pssm.by_row);
+    assert_eq!(pssm.identifier.as_deref(), Some("cd00180"));
+    assert_eq!((pssm.num_rows, pssm.num_columns), (28, 3));
+
+    let final_data: &PssmFinalData = pssm.final_data.as_ref().unwrap();
+    assert_eq!(final_data.scores.len(), 84);
+    assert_eq!(final_data.kappa, 0.041);
+    assert_eq!(final_data.scaling_factor, 1);
+
+    let intermediate: &PssmIntermediateData = pssm.intermediate_data.as_ref().unwrap();
+    assert_eq!(intermediate.freq_ratios.len(), 84);
+    assert_eq!(intermediate.num_indept_obsr, Some(vec![4.5, 3.25, 10.0]));
+
+    let params: &PssmParameters = parsed.params.as_ref().unwrap();
+    let rpsdb: &FormatRpsDbParameters = params.rpsdbparams.as_ref().unwrap();
+    assert_eq!(rpsdb.matrix_name, "BLOSUM62");
+    assert_eq!(rpsdb.gap_open, Some(11));
+
+    let constraints: &CoreDef = params.constraints.as_ref().unwrap();
+    let block: &CoreBlock = &constraints.blocks[0];
+    let property: &BlockProperty = &block.property.as_ref().unwrap()[0];
+    assert_eq!(property.r#type, BlockPropertyType::Name);
+    let loops: &[LoopConstraint] = &constraints.loops;
+    assert_eq!(loops.len(), 2);
+    assert_eq!(loops[1].maxlength, 100000);
+    assert_eq!(constraints.is_discontinuous, Some(false));
+
+    let site: &SiteAnnot = &params.annotated_sites.as_ref().unwrap()[0];
+    assert_eq!(site.description.as_deref(), Some("ATP binding site"));
+    assert_eq!(site.aliases, Some(vec!["nucleotide binding".to_string()]));
+
+    assert_eq!(parsed.validate(), Ok(()));
+}
+
+#[test]
+fn pssm_score() {
+    let mut pssm: Pssm = read_fixture().pssm;
+
+    // scores are stored column by column in ncbistdaa order
+    assert_eq!(pssm.score('-', 0), Some(-14));
+    assert_eq!(pssm.score('A', 0), Some(-13));
+    assert_eq!(pssm.score('w', 2), Some(206));
+    assert_eq!(pssm.score('A', 3), None);
+    assert_eq!(pssm.score('a', 1), Some(87));
+    assert_eq!(pssm.freq_ratio('C', 1), Some(1.03));
+
+    pssm.row_labels = Some(vec!["A".to_string(), "C".to_string()]);
+    pssm.num_rows = 2;
+    pssm.num_columns = 42;
+    assert_eq!(pssm.score('C', 0), Some(-13));
+    assert_eq!(pssm.score('D', 0), None);
+    assert_eq!(pssm.validate(), Ok(()));
+
+    pssm.by_row = true;
+    assert_eq!(pssm.offset(1, 0), Some(42));
+    assert_eq!(pssm.score('C', 0), Some(100));
+}
+
+#[test]
+fn pssm_validate() {
+    let mut pssm = read_fixture().pssm;
+
+    pssm.num_columns = 4;
+    assert!(pssm.validate().unwrap_err().contains("scores"));
+
+    pssm.num_columns = 3;
+    pssm.intermediate_data.as_mut().unwrap().freq_ratios.pop();
+    assert!(pssm.validate().unwrap_err().contains("freq_ratios"));
+
+    pssm.intermediate_data = None;
+    pssm.row_labels = Some(vec!["A".to_string()]);
+    assert!(pssm.validate().unwrap_err().contains("row labels"));
+
+    pssm.final_data = None;
+    assert!(pssm.validate().is_err());
+}
+
+#[test]
+fn pssm_round_trip() {
+    let parsed = read_fixture();
+    let xml = parsed.to_xml();
+    assert_eq!(parse_node::<PssmWithParameters>(&xml), Some(parsed.clone()));
+
+    let pssm = parse_node::<Pssm>(&parsed.pssm.to_xml()).unwrap();
+    assert_eq!(pssm, parsed.pssm);
+
+    let mut incomplete = parsed.pssm.to_xml();
+    incomplete = incomplete.replace("<Pssm_numRows>28</Pssm_numRows>", "");
+    assert!(parse_node::<Pssm>(&incomplete).is_none());
+}
+
+#[test]
+fn pssm_text_round_trip() {
+    let mut parsed = read_fixture();
+    let text = "<α & 'β'> \"γ\"";
+    parsed.pssm.identifier = Some(text.to_string());
+    parsed.pssm.row_labels = Some(vec![text.to_string(); parsed.pssm.num_rows as usize]);
+    let params = parsed.params.as_mut().unwrap();
+    params.rpsdbparams.as_mut().unwrap().matrix_name = text.to_string();
+    let site = &mut params.annotated_sites.as_mut().unwrap()[0];
+    site.description = Some(text.to_string());
+    site.aliases = Some(vec![text.to_string()]);
+    site.motif = Some(text.to_string());
+
+    assert_eq!(parse_node::<PssmWithParameters>(&parsed.to_xml()), Some(parsed));
+}
@@ -0,0 +1,42 @@
+use ncbi::general::{Date, DateStd, ObjectId};
+use ncbi::seq::{AnnotDesc, SeqAnnot};
+use ncbi::seqfeat::SeqFeatData;
+use ncbi::seqloc::SeqId;
+use ncbi::{get_local_xml, parse_xml, DataType};
+
+#[test]
+fn standalone_annot() {
+    let annot = SeqAnnot::from_file("tests/data/seq_annot.xml").unwrap();
+    assert_eq!(annot.name.as_deref(), Some("SNP"));
+
+    let desc = annot.desc.as_ref().unwrap();
+    assert_eq!(desc.len(), 4);
+    assert_eq!(desc[0], AnnotDesc::Name("dbSNP".to_string()));
+    assert_eq!(desc[1], AnnotDesc::Title("Synthetic variation track".to_string()));
+    assert_eq!(
+        desc[2],
+        AnnotDesc::CreateDate(Date::Date(DateStd {
+            year: 2024,
+            month: Some(3),
+            day: Some(1),
+            ..DateStd::default()
+        }))
+    );
+    assert_eq!(desc[3], AnnotDesc::Src(SeqId::Gi(2519734237)));
+
+    let features = annot.features();
+    assert_eq!(features.len(), 3);
+    assert!(features.iter().all(|feat| feat.data == SeqFeatData::Region("variation".to_string())));
+    assert_eq!(features[0].qualifier("replace"), Some("A/G"));
+    assert_eq!(features[2].location.total_range(), Some((2871, 2871)));
+    assert_eq!(features[1].dbxref("dbSNP").map(|tag| &tag.tag), Some(&ObjectId::Id(1002)));
+
+    // also recognized as a top-level object by `parse_xml()`
+    match parse_xml(&get_local_xml("tests/data/seq_annot.xml")).unwrap() {
+        DataType::SeqAnnot(parsed) => assert_eq!(parsed, annot),
+        _ => panic!("No Seq-annot found"),
+    }
+
+    assert!(SeqAnnot::from_file("tests/data/nuc_prot.xml").unwrap_err().ends_with("is not a Seq-annot"));
+    assert!(SeqAnnot::from_file("tests/data/missing.xml").is_err());
+}
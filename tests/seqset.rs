@@ -0,0 +1,69 @@
+use ncbi::general::{Date, DateStd, DbTag, ObjectId};
+use ncbi::seq::Mol;
+use ncbi::seqloc::SeqId;
+use ncbi::seqset::{BioSeqSet, BioSeqSetClass, SeqEntry};
+use ncbi::{get_local_xml, parse_xml, DataType};
+
+fn nuc_prot() -> BioSeqSet {
+    match parse_xml(&get_local_xml("tests/data/nuc_prot.xml")).unwrap() {
+        DataType::BioSeqSet(set) => set,
+        _ => panic!("No Bioseq set found"),
+    }
+}
+
+#[test]
+fn set_fields() {
+    let set = nuc_prot();
+    assert_eq!(set.id, Some(ObjectId::Id(42)));
+    assert_eq!(
+        set.coll,
+        Some(DbTag {
+            db: "GenBank".into(),
+            tag: ObjectId::Str("release".into()),
+        })
+    );
+    assert_eq!(set.level, Some(1));
+    assert_eq!(set.class, BioSeqSetClass::Genbank);
+    assert_eq!(set.release.as_deref(), Some("256.0"));
+    assert_eq!(
+        set.date,
+        Some(Date::Date(DateStd {
+            year: 2023,
+            month: Some(6),
+            day: Some(15),
+            ..DateStd::default()
+        }))
+    );
+
+    match set.seq_set.first() {
+        Some(SeqEntry::Set(inner)) => {
+            assert_eq!(inner.class, BioSeqSetClass::NucProt);
+            assert_eq!(inner.level, Some(2));
+        }
+        _ => panic!("Entry is not Bioseq-set"),
+    }
+}
+
+#[test]
+fn nuc_prot_view() {
+    let set = nuc_prot();
+    // the outer set is not itself a nuc-prot set
+    assert!(set.as_nuc_prot().is_none());
+    assert!(set.as_seg_set().is_none());
+
+    let sets = set.nuc_prot_sets();
+    assert_eq!(sets.len(), 1);
+    let nuc_prot = &sets[0];
+    assert_eq!(nuc_prot.nucleotide.id, vec![SeqId::Gi(1000001)]);
+    assert_eq!(nuc_prot.nucleotide.inst.as_ref().unwrap().mol, Mol::RNA);
+    assert_eq!(nuc_prot.proteins.len(), 1);
+    assert_eq!(nuc_prot.proteins[0].id, vec![SeqId::Gi(1000002)]);
+    assert!(nuc_prot.proteins[0].is_protein());
+}
+
+#[test]
+fn bare_class() {
+    let xml = "<Bioseq-set><class value=\"pop-set\"/><seq-set></seq-set></Bioseq-set>";
+    let set = ncbi::parsing::parse_node::<BioSeqSet>(xml).unwrap();
+    assert_eq!(set.class, BioSeqSetClass::PopSet);
+}
@@ -0,0 +1,69 @@
+//! Streaming of downloaded entries to files, without network access
+
+use ncbi::history::HistorySession;
+use ncbi::seqset::{BioSeqSet, SeqEntry};
+use ncbi::sink::{FastaSink, GffSink, RecordSink, XmlDirSink};
+use ncbi::transport::{reset_transport, set_transport};
+use ncbi::{parse_xml, DataType, EntrezDb, FetchRequest};
+
+#[test]
+fn download_to_sinks() {
+    let popset = std::fs::read_to_string("tests/data/popset.xml").unwrap();
+    set_transport(move |url: &str| {
+        if url.contains("esearch.fcgi") {
+            return Ok("<eSearchResult><Count>3</Count><QueryKey>1</QueryKey><WebEnv>MCID_1</WebEnv></eSearchResult>".to_string());
+        }
+        assert!(url.contains("&rettype=native&retmode=xml"));
+        Ok(popset.clone())
+    });
+
+    let mut session = HistorySession::search(EntrezDb::PopSet, "Myotis lucifugus cytb");
+    let request = FetchRequest::new(EntrezDb::PopSet, "").rettype("fasta").retmode("text");
+    let mut sink = FastaSink::new(Vec::new());
+    assert_eq!(session.download(&request, 500, &mut sink).unwrap(), 3);
+    let fasta = String::from_utf8(sink.into_inner()).unwrap();
+    assert_eq!(fasta.matches('>').count(), 3);
+    assert!(fasta.lines().all(|line| line.starts_with('>') || line.len() <= 70));
+
+    let dir = std::env::temp_dir().join(format!("ncbi-sink-{}", std::process::id()));
+    let mut sink = XmlDirSink::create(&dir).unwrap();
+    assert_eq!(session.download(&request, 500, &mut sink).unwrap(), 3);
+    assert_eq!(sink.paths.len(), 3);
+    let xml = std::fs::read_to_string(&sink.paths[0]).unwrap();
+    assert!(xml.starts_with("<?xml version=\"1.0\"?>\n<Seq-entry>"));
+    let entry = ncbi::parsing::parse_node::<SeqEntry>(&xml).unwrap();
+    assert!(matches!(entry, SeqEntry::Seq(_)));
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    reset_transport();
+}
+
+#[test]
+fn gff_sink() {
+    let xml = std::fs::read_to_string("tests/data/nuc_prot.xml").unwrap();
+    let set = match parse_xml(&xml).unwrap() {
+        DataType::BioSeqSet(set) => set,
+        _ => panic!("No Bioseq-set found"),
+    };
+    let entries = BioSeqSet::entries_xml(&xml).unwrap();
+    assert_eq!(entries.len(), set.seq_set.len());
+
+    let mut sink = GffSink::new(Vec::new());
+    for (entry, xml) in set.seq_set.iter().zip(entries) {
+        sink.write_entry(entry, Some(xml)).unwrap();
+    }
+    sink.finish().unwrap();
+    let gff = String::from_utf8(sink.into_inner()).unwrap();
+    assert_eq!(gff, ncbi::gff::bioseq_set_to_gff3(&set));
+
+    // the header is written even without entries
+    let mut empty = GffSink::new(Vec::new());
+    empty.finish().unwrap();
+    assert_eq!(empty.into_inner(), b"##gff-version 3\n");
+
+    // entries without XML cannot be written as XML files
+    let dir = std::env::temp_dir().join(format!("ncbi-sink-empty-{}", std::process::id()));
+    let mut sink = XmlDirSink::create(&dir).unwrap();
+    assert!(sink.write_entry(&set.seq_set[0], None).is_err());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,112 @@
+//! Snapshots of the records bundled in `tests/data`
+//!
+//! Each fixture is parsed and reduced to the overview given by [`Summary::tree()`],
+//! which is compared to the golden file of the same name in `tests/snapshots`. A
+//! parser which gains or loses fields changes the overview, and fails the test until
+//! the golden file is reviewed and updated by running the tests with
+//! `UPDATE_SNAPSHOTS=1`.
+
+use ncbi::gbseq::GBSeq;
+use ncbi::pmc::{parse_articles, parse_pubmed_articles};
+use ncbi::seq::BioSeq;
+use ncbi::seqset::BioSeqSet;
+use ncbi::summary::Summary;
+use ncbi::{get_local_xml, parse_xml, DataType};
+use std::fs;
+use std::path::Path;
+
+/// Line endings and trailing whitespace are not significant
+fn normalize(text: &str) -> String {
+    text.lines().map(str::trim_end).collect::<Vec<_>>().join("\n").trim_end().to_string() + "\n"
+}
+
+fn assert_snapshot(name: &str, actual: &str) {
+    let path = Path::new("tests/snapshots").join(format!("{}.snap", name));
+    let actual = normalize(actual);
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        fs::write(&path, &actual).unwrap();
+        return;
+    }
+
+    let expected = fs::read_to_string(&path)
+        .unwrap_or_else(|_| panic!("No snapshot {}, run with UPDATE_SNAPSHOTS=1 to create it", path.display()));
+    let expected = normalize(&expected);
+    if expected != actual {
+        let (line, (expected_line, actual_line)) = expected
+            .lines()
+            .chain(std::iter::repeat(""))
+            .zip(actual.lines().chain(std::iter::repeat("")))
+            .enumerate()
+            .find(|(_, (expected, actual))| expected != actual)
+            .unwrap();
+        panic!(
+            "Snapshot {} differs at line {}\n  expected: {}\n    actual: {}\n\
+             run with UPDATE_SNAPSHOTS=1 to accept the changes",
+            path.display(),
+            line + 1,
+            expected_line,
+            actual_line
+        );
+    }
+}
+
+fn bioseq_set(path: &str) -> BioSeqSet {
+    match parse_xml(&get_local_xml(path)).unwrap() {
+        DataType::BioSeqSet(set) => set,
+        _ => panic!("No Bioseq set found"),
+    }
+}
+
+#[test]
+fn wgs_contig() {
+    assert_snapshot("wgs_contig", &bioseq_set("tests/data/2519734237.xml").tree());
+}
+
+#[test]
+fn nuc_prot() {
+    assert_snapshot("nuc_prot", &bioseq_set("tests/data/nuc_prot.xml").tree());
+}
+
+#[test]
+fn pdb_protein() {
+    assert_snapshot("pdb_protein", &bioseq_set("tests/data/pdb_protein.xml").tree());
+}
+
+#[test]
+fn popset() {
+    assert_snapshot("popset", &bioseq_set("tests/data/popset.xml").tree());
+}
+
+#[test]
+fn entrezgene() {
+    let genes = match parse_xml(&get_local_xml("tests/data/tp73.genbank.xml")).unwrap() {
+        DataType::EntrezgeneSet(genes) => genes,
+        _ => panic!("No Entrezgene set found"),
+    };
+    let trees: String = genes.iter().map(Summary::tree).collect();
+    assert_snapshot("entrezgene", &trees);
+}
+
+#[test]
+fn gbseq() {
+    let set = match parse_xml(&get_local_xml("tests/data/gbseq.xml")).unwrap() {
+        DataType::GBSet(set) => set,
+        _ => panic!("No GBSet found"),
+    };
+    let trees: String = set.iter().map(|gbseq: &GBSeq| BioSeq::from(gbseq).tree()).collect();
+    assert_snapshot("gbseq", &trees);
+}
+
+#[test]
+fn jats_article() {
+    let articles = parse_articles(&fs::read_to_string("tests/data/jats.xml").unwrap()).unwrap();
+    let trees: String = articles.iter().map(Summary::tree).collect();
+    assert_snapshot("jats_article", &trees);
+}
+
+#[test]
+fn pubmed_article() {
+    let articles = parse_pubmed_articles(&fs::read_to_string("tests/data/pubmed_article.xml").unwrap()).unwrap();
+    let trees: String = articles.iter().map(Summary::tree).collect();
+    assert_snapshot("pubmed_article", &trees);
+}
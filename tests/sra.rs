@@ -0,0 +1,84 @@
+use ncbi::sra::{parse_experiment_packages, parse_runinfo, LibraryLayout};
+use std::fs;
+
+#[test]
+fn parse_experiment_package() {
+    let xml = fs::read_to_string("tests/data/sra.xml").unwrap();
+    let packages = parse_experiment_packages(&xml).unwrap();
+    assert_eq!(packages.len(), 1);
+
+    let package = &packages[0];
+    let experiment = &package.experiment;
+    assert_eq!(experiment.accession, "SRX3357932");
+    assert_eq!(experiment.study_ref.as_deref(), Some("SRP123456"));
+    assert_eq!(experiment.sample_ref.as_deref(), Some("SRS2654321"));
+    assert_eq!(experiment.library.strategy.as_deref(), Some("WGS"));
+    assert_eq!(experiment.library.source.as_deref(), Some("GENOMIC"));
+    assert_eq!(
+        experiment.library.layout,
+        LibraryLayout::Paired {
+            nominal_length: Some(350),
+            nominal_sdev: Some(25.5),
+        }
+    );
+    assert_eq!(experiment.platform.name.as_deref(), Some("ILLUMINA"));
+    assert_eq!(
+        experiment.platform.instrument_model.as_deref(),
+        Some("Illumina MiSeq")
+    );
+
+    let study = package.study.as_ref().unwrap();
+    assert_eq!(
+        study.title.as_deref(),
+        Some("Escherichia coli K-12 resequencing")
+    );
+    let sample = package.sample.as_ref().unwrap();
+    assert_eq!(sample.title.as_deref(), Some("E. coli MG1655"));
+    assert_eq!(sample.taxon_id, Some(511145));
+
+    assert_eq!(package.runs.len(), 2);
+    let run = &package.runs[0];
+    assert_eq!(run.accession, "SRR6252121");
+    assert_eq!(run.total_spots, Some(1002898));
+    assert_eq!(run.size, Some(306172839));
+    assert_eq!(run.files.len(), 2);
+    assert!(run
+        .sra_file()
+        .unwrap()
+        .url
+        .as_ref()
+        .unwrap()
+        .ends_with("SRR6252121.1"));
+    assert!(package.runs[1].files.is_empty());
+}
+
+#[test]
+fn parse_experiment_package_errors() {
+    assert!(parse_experiment_packages("<ERROR>Invalid uid</ERROR>").is_err());
+    assert!(
+        parse_experiment_packages("<EXPERIMENT_PACKAGE_SET><EXPERIMENT_PACKAGE></RUN>").is_err()
+    );
+}
+
+#[test]
+fn parse_runinfo_csv() {
+    let csv = fs::read_to_string("tests/data/runinfo.csv").unwrap();
+    let runs = parse_runinfo(&csv).unwrap();
+    assert_eq!(runs.len(), 2);
+
+    let run = &runs[0];
+    assert_eq!(run.run, "SRR6252121");
+    assert_eq!(run.experiment.as_deref(), Some("SRX3357932"));
+    assert_eq!(run.bases, Some(601738800));
+    assert_eq!(run.library_layout.as_deref(), Some("PAIRED"));
+    assert_eq!(run.tax_id, Some(511145));
+    assert_eq!(
+        run.scientific_name.as_deref(),
+        Some("Escherichia coli str. K-12 substr. MG1655")
+    );
+    assert_eq!(run.get("CenterName"), Some("EXAMPLE UNIVERSITY"));
+    assert_eq!(run.get("AssemblyName"), Some(""));
+
+    assert!(parse_runinfo("").is_err());
+    assert!(parse_runinfo("not,a,runinfo\n1,2,3").is_err());
+}
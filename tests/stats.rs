@@ -0,0 +1,68 @@
+use ncbi::general::UserObject;
+use ncbi::seqset::BioSeqSet;
+use ncbi::stats::SetStats;
+use ncbi::user::StructuredComment;
+use ncbi::visit::{walk, Visitor};
+use ncbi::{get_local_xml, parse_xml, DataType};
+
+fn wgs() -> BioSeqSet {
+    match parse_xml(&get_local_xml("tests/data/2519734237.xml")).unwrap() {
+        DataType::BioSeqSet(set) => set,
+        _ => panic!("No Bioseq set found"),
+    }
+}
+
+#[derive(Default)]
+struct AnnotationData(Option<StructuredComment>);
+
+impl Visitor for AnnotationData {
+    fn visit_user_object(&mut self, object: &UserObject) {
+        if let Ok(comment) = StructuredComment::try_from(object) {
+            if comment.name() == Some("Genome-Annotation-Data") {
+                self.0 = Some(comment);
+            }
+        }
+    }
+}
+
+#[test]
+fn wgs_set_stats() {
+    let set = wgs();
+    let stats = SetStats::from_set(&set);
+
+    assert_eq!(stats.sequences, 1);
+    assert_eq!(stats.proteins, 0);
+    assert_eq!(stats.total_length, 86489);
+    assert_eq!((stats.n50, stats.l50), (Some(86489), Some(1)));
+    // the contig is a delta sequence, so its residues are not given
+    assert_eq!(stats.n_count, None);
+    assert_eq!(stats.gc_content, None);
+
+    let seq = &stats.per_seq[0];
+    assert_eq!(seq.id, "NZ_JARQWN010000024.1");
+    assert_eq!(seq.features, stats.features);
+
+    let features = &stats.features;
+    assert_eq!(features.by_key.get("gene"), Some(&88));
+    assert_eq!(features.by_key.get("CDS"), Some(&88));
+    assert_eq!(features.pseudo_genes, 1);
+    assert_eq!(features.cds_with_protein, 87);
+    assert_eq!(features.cds_without_protein(), 1);
+}
+
+#[test]
+fn annotation_totals_match_structured_comment() {
+    let set = wgs();
+    let mut annotation = AnnotationData::default();
+    walk(&set, &mut annotation);
+    let comment = annotation.0.expect("no Genome-Annotation-Data comment");
+
+    // the comment reports totals for the whole genome, and this record is a single
+    // contig, so only the names are comparable
+    let totals = SetStats::from_set(&set).features.annotation_totals();
+    for (name, _) in totals.iter().filter(|(_, count)| *count > 0) {
+        let value = comment.get(name).unwrap_or_else(|| panic!("{} is not reported", name));
+        value.replace(',', "").parse::<usize>().unwrap();
+    }
+    assert_eq!(comment.get("Genes (total)"), Some("5,288"));
+}
@@ -0,0 +1,76 @@
+use ncbi::seq::BioSeq;
+use ncbi::seqloc::{PDBSeqId, SeqId};
+use ncbi::structure::{pdb_ids, StructureSummary};
+
+const RESPONSE: &str = r#"<?xml version="1.0" encoding="UTF-8" ?>
+<!DOCTYPE eSummaryResult PUBLIC "-//NLM//DTD esummary v1 20041029//EN" "https://eutils.ncbi.nlm.nih.gov/eutils/dtd/20041029/esummary-v1.dtd">
+<eSummaryResult>
+<DocSum>
+	<Id>54241</Id>
+	<Item Name="PdbAcc" Type="String">1TUP</Item>
+	<Item Name="PdbDescr" Type="String">TUMOR SUPPRESSOR P53 COMPLEXED WITH DNA</Item>
+	<Item Name="EC" Type="String"></Item>
+	<Item Name="Resolution" Type="String">2.2</Item>
+	<Item Name="ExpMethod" Type="String">X-Ray Diffraction</Item>
+	<Item Name="PdbClass" Type="String">Antioncogene/DNA</Item>
+	<Item Name="PdbReleaseDate" Type="Date">1995/07/10 00:00</Item>
+	<Item Name="PdbDepositDate" Type="Date">1995/07/11 00:00</Item>
+	<Item Name="OrganismList" Type="List">
+		<Item Name="string" Type="String">Homo sapiens</Item>
+	</Item>
+	<Item Name="PdbAccSynList" Type="List"></Item>
+	<Item Name="LigCode" Type="String">ZN</Item>
+	<Item Name="LigCount" Type="Integer">1</Item>
+	<Item Name="ProteinMoleculeCount" Type="Integer">3</Item>
+	<Item Name="DNAMoleculeCount" Type="Integer">2</Item>
+	<Item Name="RNAMoleculeCount" Type="Integer">0</Item>
+</DocSum>
+</eSummaryResult>"#;
+
+#[test]
+fn parse_structure_summary() {
+    let summaries = StructureSummary::from_esummary(RESPONSE).unwrap();
+    assert_eq!(summaries.len(), 1);
+
+    let summary = &summaries[0];
+    assert_eq!(summary.mmdb_id, 54241);
+    assert_eq!(summary.pdb_acc, "1TUP");
+    assert_eq!(summary.method.as_deref(), Some("X-Ray Diffraction"));
+    assert_eq!(summary.resolution, Some(2.2));
+    assert_eq!(summary.organisms, vec!["Homo sapiens"]);
+    assert_eq!(summary.protein_count, Some(3));
+    assert_eq!(summary.dna_count, Some(2));
+    assert_eq!(summary.ligands, vec!["ZN"]);
+}
+
+#[test]
+fn link_pdb_ids() {
+    let summary = StructureSummary::from_esummary(RESPONSE).unwrap().remove(0);
+
+    let chain = summary.pdb_seq_id(Some("A"));
+    assert_eq!(chain.mol, "1TUP");
+    assert_eq!(chain.chain_id.as_deref(), Some("A"));
+    assert!(summary.contains(&chain));
+
+    let bioseq = BioSeq {
+        id: vec![
+            SeqId::Pdb(PDBSeqId {
+                mol: "1tup".to_string(),
+                rel: None,
+                chain_id: Some("B".to_string()),
+            }),
+            SeqId::Gi(1310770),
+        ],
+        ..BioSeq::default()
+    };
+    let ids = pdb_ids(&bioseq);
+    assert_eq!(ids.len(), 1);
+    assert!(summary.contains(ids[0]));
+
+    let other = PDBSeqId {
+        mol: "2OCJ".to_string(),
+        rel: None,
+        chain_id: None,
+    };
+    assert!(!summary.contains(&other));
+}
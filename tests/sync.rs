@@ -0,0 +1,50 @@
+//! Incremental searches served by a custom transport, without network access
+
+use ncbi::sync::SyncQuery;
+use ncbi::transport::{reset_transport, set_transport};
+use ncbi::EntrezDb;
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn incremental_runs() {
+    let urls = Arc::new(Mutex::new(Vec::new()));
+    let seen = urls.clone();
+    set_transport(move |url: &str| {
+        seen.lock().unwrap().push(url.to_string());
+        // results are split in two pages on the first run
+        let (count, ids) = if url.contains("mindate=") {
+            (1, "<Id>3</Id>")
+        } else if url.contains("retstart=0&") {
+            (3, "<Id>1</Id><Id>2</Id>")
+        } else {
+            (3, "<Id>3</Id>")
+        };
+        Ok(format!("<eSearchResult><Count>{}</Count><IdList>{}</IdList></eSearchResult>", count, ids))
+    });
+
+    let path = std::env::temp_dir().join(format!("ncbi-sync-{}.txt", std::process::id()));
+    let mut query = SyncQuery::new(EntrezDb::Nucleotide, "Myotis[Organism]");
+    query.load(&path).unwrap();
+    assert_eq!(query.last_run, None);
+
+    assert_eq!(query.run().unwrap(), ["1", "2", "3"]);
+    assert_eq!(urls.lock().unwrap().len(), 2);
+    let today = query.last_run.clone().unwrap();
+    assert_eq!(today.len(), "YYYY/MM/DD".len());
+    query.save(&path).unwrap();
+
+    let mut query = SyncQuery::new(EntrezDb::Nucleotide, "Myotis[Organism]");
+    query.load(&path).unwrap();
+    assert_eq!(query.last_run.as_ref(), Some(&today));
+    assert_eq!(query.run().unwrap(), ["3"]);
+    let last = urls.lock().unwrap().last().cloned().unwrap();
+    assert!(last.contains(&format!("&datetype=mdat&mindate={}&maxdate={}", today, today)));
+
+    // a failed run is retried from the same date
+    set_transport(|url: &str| Err(format!("offline: {}", url)));
+    assert!(query.run().is_err());
+    assert_eq!(query.last_run.as_ref(), Some(&today));
+
+    reset_transport();
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,133 @@
+use ncbi::general::{FuzzLimit, IntFuzz, ObjectId};
+use ncbi::seqfeat::{CdRegionFrame, GeneticCodeOpt, RnaRefExt, SeqFeatData};
+use ncbi::seqloc::{NaStrand, SeqId, SeqLoc};
+use ncbi::tbl::{bioseq_set_to_tbl, parse_tbl, seq_annot_to_tbl};
+use ncbi::{get_local_xml, parse_xml, DataType};
+use std::fs;
+
+#[test]
+fn parse_feature_table() {
+    let text = fs::read_to_string("tests/data/features.tbl").unwrap();
+    let tables = parse_tbl(&text).unwrap();
+    assert_eq!(tables.len(), 2);
+
+    let table = &tables[0];
+    assert!(matches!(&table.id, SeqId::Genbank(id) if id.accession.as_deref() == Some("AY123456")));
+    assert_eq!(table.name.as_deref(), Some("Table1"));
+    assert_eq!(table.features.len(), 4);
+
+    let gene = &table.features[0];
+    match &gene.data {
+        SeqFeatData::Gene(gene) => {
+            assert_eq!(gene.locus.as_deref(), Some("abcA"));
+            assert_eq!(gene.locus_tag.as_deref(), Some("ABC_0001"));
+        }
+        _ => panic!("Expected a gene"),
+    }
+    assert_eq!(gene.partial, Some(true));
+
+    let cds = &table.features[1];
+    match &cds.data {
+        SeqFeatData::CdRegion(cds) => {
+            assert_eq!(cds.frame, CdRegionFrame::Two);
+            assert_eq!(cds.code, Some(vec![GeneticCodeOpt::Id(11)]));
+        }
+        _ => panic!("Expected a coding region"),
+    }
+    match &cds.location {
+        SeqLoc::Mix(mix) => {
+            assert_eq!(mix.0.len(), 2);
+            match &mix.0[0] {
+                SeqLoc::Int(first) => {
+                    assert_eq!((first.from, first.to), (0, 99));
+                    assert_eq!(first.fuzz_from, Some(IntFuzz::Lim(FuzzLimit::LT)));
+                }
+                _ => panic!("Expected an interval"),
+            }
+        }
+        _ => panic!("Expected a mixed location"),
+    }
+    assert!(matches!(&cds.product, Some(SeqLoc::Whole(SeqId::Genbank(_)))));
+    assert_eq!(cds.comment.as_deref(), Some("putative"));
+    let dbxref = &cds.dbxref.as_ref().unwrap()[0];
+    assert_eq!(dbxref.db, "GeneID");
+    assert_eq!(dbxref.tag, ObjectId::Id(12345));
+
+    let trna = &table.features[2];
+    match &trna.location {
+        SeqLoc::Int(interval) => {
+            assert_eq!((interval.from, interval.to), (1499, 1999));
+            assert_eq!(interval.strand, Some(NaStrand::Minus));
+        }
+        _ => panic!("Expected an interval"),
+    }
+
+    let repeat = &table.features[3];
+    assert!(matches!(&repeat.data, SeqFeatData::Imp(imp) if imp.key == "repeat_region"));
+    assert_eq!(repeat.pseudo, Some(true));
+    assert_eq!(repeat.qual.as_ref().unwrap()[0].val, "dispersed");
+
+    let ncrna = &tables[1].features[0];
+    assert!(matches!(&tables[1].id, SeqId::Local(ObjectId::Str(id)) if id == "contig2"));
+    match &ncrna.data {
+        SeqFeatData::RNA(rna) => match &rna.ext {
+            Some(RnaRefExt::Gen(gen)) => {
+                assert_eq!(gen.class.as_deref(), Some("antisense_RNA"));
+                assert_eq!(gen.product.as_deref(), Some("regulator"));
+            }
+            _ => panic!("Expected generic RNA fields"),
+        },
+        _ => panic!("Expected an RNA"),
+    }
+    match &ncrna.location {
+        SeqLoc::Int(interval) => {
+            assert_eq!(interval.strand, Some(NaStrand::Minus));
+            assert_eq!(interval.fuzz_to, Some(IntFuzz::Lim(FuzzLimit::GT)));
+        }
+        _ => panic!("Expected an interval"),
+    }
+}
+
+#[test]
+fn write_feature_table() {
+    let text = fs::read_to_string("tests/data/features.tbl").unwrap();
+    let table = parse_tbl(&text).unwrap().remove(0);
+    let id = table.id.clone();
+    let tbl = seq_annot_to_tbl(&id, &table.into_annot(), None);
+
+    let mut lines = tbl.lines();
+    assert_eq!(lines.next(), Some(">Feature AY123456.1"));
+    assert_eq!(lines.next(), Some("<1\t1050\tgene"));
+    assert!(tbl.contains("<1\t100\tCDS\n200\t1050\n"));
+    assert!(tbl.contains("\t\t\tproduct\tABC transporter\n"));
+    assert!(tbl.contains("\t\t\tcodon_start\t2\n"));
+    assert!(tbl.contains("2000\t1500\ttRNA\n\t\t\tproduct\ttRNA-Ala\n"));
+    assert!(tbl.contains("\t\t\tpseudo\n"));
+}
+
+#[test]
+fn bioseq_set_round_trip() {
+    let data = get_local_xml("tests/data/2519734237.xml");
+    let set = match parse_xml(data.as_str()).unwrap() {
+        DataType::BioSeqSet(set) => set,
+        _ => panic!("No Bioseq set found"),
+    };
+
+    let tbl = bioseq_set_to_tbl(&set);
+    assert!(tbl.starts_with(">Feature NZ_JARQWN010000024.1\n"));
+    let tables = parse_tbl(&tbl).unwrap();
+    assert_eq!(tables.len(), 1);
+
+    let cds = tables[0]
+        .features
+        .iter()
+        .filter(|feat| matches!(feat.data, SeqFeatData::CdRegion(_)))
+        .count();
+    assert_eq!(cds, 88);
+
+    // writing the parsed table again gives the same output
+    let table = tables.into_iter().next().unwrap();
+    let id = table.id.clone();
+    let again = seq_annot_to_tbl(&id, &table.into_annot(), None);
+    assert_eq!(again, tbl);
+}
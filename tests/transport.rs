@@ -0,0 +1,78 @@
+//! Requests served by a custom transport, without network access
+
+use ncbi::transport::{http_get_async, reset_transport, set_async_transport, set_transport, AsyncHttpTransport, ResponseFuture};
+use ncbi::{fetch_popset, fetch_search, EntrezDb};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+#[test]
+fn custom_transport() {
+    let popset = std::fs::read_to_string("tests/data/popset.xml").unwrap();
+    let requests = Arc::new(AtomicUsize::new(0));
+    let counter = requests.clone();
+    set_transport(move |url: &str| {
+        counter.fetch_add(1, Ordering::SeqCst);
+        if url.contains("esearch.fcgi") {
+            Ok("<eSearchResult><Count>1</Count><IdList><Id>2104551001</Id></IdList></eSearchResult>".to_string())
+        } else if url.contains("db=popset") {
+            Ok(popset.clone())
+        } else {
+            Err(format!("404 Not Found: {}", url))
+        }
+    });
+
+    let result = fetch_search(EntrezDb::PopSet, "Myotis lucifugus cytb").unwrap();
+    assert_eq!(result.ids, vec!["2104551001"]);
+    let set = fetch_popset(&result.ids[0]).unwrap();
+    assert_eq!(set.aligned_seqs().len(), 3);
+    assert_eq!(requests.load(Ordering::SeqCst), 2);
+
+    // errors of the transport are returned as is
+    set_transport(|url: &str| Err(format!("offline: {}", url)));
+    assert!(fetch_search(EntrezDb::Gene, "TP53").unwrap_err().starts_with("offline"));
+
+    reset_transport();
+}
+
+/// Responds to requests for popsets with `tests/data/popset.xml`, and to any other
+/// request with the requested URL
+struct Echo;
+
+impl AsyncHttpTransport for Echo {
+    fn get<'a>(&'a self, url: &'a str) -> ResponseFuture<'a> {
+        Box::pin(async move {
+            match url.contains("db=popset") {
+                true => std::fs::read_to_string("tests/data/popset.xml").map_err(|e| e.to_string()),
+                false => Ok(url.to_string()),
+            }
+        })
+    }
+}
+
+#[test]
+fn custom_async_transport() {
+    set_async_transport(Echo);
+    let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+    let response = runtime.block_on(http_get_async("https://example.com/a")).unwrap();
+    assert_eq!(response, "https://example.com/a");
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn fetch_stream_transport() {
+    use ncbi::fetch_stream;
+    use ncbi::seqset::SeqEntry;
+
+    set_async_transport(Echo);
+    let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+    let count = runtime.block_on(async {
+        let mut entries = fetch_stream::<SeqEntry>(EntrezDb::PopSet, "2104551001", "native", "xml").await?;
+        let mut count = 0;
+        while let Some(entry) = entries.next_node().await {
+            entry?;
+            count += 1;
+        }
+        Ok::<_, String>(count)
+    });
+    assert_eq!(count, Ok(3));
+}